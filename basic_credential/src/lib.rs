@@ -51,6 +51,25 @@ impl Signer for SignatureKeyPair {
                 let signature: Signature = k.sign(payload);
                 Ok(signature.to_der().to_bytes().into())
             }
+            SignatureScheme::ECDSA_SECP384R1_SHA384 => {
+                let k = p384::ecdsa::SigningKey::from_slice(&self.private)
+                    .map_err(|_| Error::SigningError)?;
+                let signature: p384::ecdsa::Signature = k.sign(payload);
+                Ok(signature.to_der().to_bytes().into())
+            }
+            SignatureScheme::ECDSA_SECP521R1_SHA512 => {
+                let k = p521::ecdsa::SigningKey::from_slice(&self.private)
+                    .map_err(|_| Error::SigningError)?;
+                let signature: p521::ecdsa::Signature = k.sign(payload);
+                Ok(signature.to_der().to_bytes().into())
+            }
+            #[cfg(feature = "secp256k1")]
+            SignatureScheme::ECDSA_SECP256K1_SHA256 => {
+                let k = k256::ecdsa::SigningKey::from_slice(&self.private)
+                    .map_err(|_| Error::SigningError)?;
+                let signature: k256::ecdsa::Signature = k.sign(payload);
+                Ok(signature.to_der().to_bytes().into())
+            }
             SignatureScheme::ED25519 => {
                 let k = ed25519_dalek::Keypair::from_bytes(&self.private)
                     .map_err(|_| Error::SigningError)?;
@@ -89,6 +108,22 @@ impl SignatureKeyPair {
                 let pk = k.verifying_key().to_encoded_point(false).as_bytes().into();
                 (k.to_bytes().as_slice().into(), pk)
             }
+            SignatureScheme::ECDSA_SECP384R1_SHA384 => {
+                let k = p384::ecdsa::SigningKey::random(&mut OsRng);
+                let pk = k.verifying_key().to_encoded_point(false).as_bytes().into();
+                (k.to_bytes().as_slice().into(), pk)
+            }
+            SignatureScheme::ECDSA_SECP521R1_SHA512 => {
+                let k = p521::ecdsa::SigningKey::random(&mut OsRng);
+                let pk = k.verifying_key().to_encoded_point(false).as_bytes().into();
+                (k.to_bytes().as_slice().into(), pk)
+            }
+            #[cfg(feature = "secp256k1")]
+            SignatureScheme::ECDSA_SECP256K1_SHA256 => {
+                let k = k256::ecdsa::SigningKey::random(&mut OsRng);
+                let pk = k.verifying_key().to_encoded_point(false).as_bytes().into();
+                (k.to_bytes().as_slice().into(), pk)
+            }
             SignatureScheme::ED25519 => {
                 let k = ed25519_dalek::Keypair::generate(&mut rand_07::rngs::OsRng).to_bytes();
                 let pk = k[ed25519_dalek::SECRET_KEY_LENGTH..].to_vec();
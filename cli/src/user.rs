@@ -254,7 +254,9 @@ impl User {
             self.identity.borrow().credential_with_key.clone(),
         )
         .expect("Failed to create MlsGroup");
-        mls_group.set_aad(group_aad.as_slice());
+        mls_group
+            .set_aad(&self.crypto, group_aad.as_slice())
+            .expect("Failed to store MlsGroup");
 
         let group = Group {
             group_name: name.clone(),
@@ -346,7 +348,9 @@ impl User {
         let group_name = String::from_utf8(group_id.clone()).unwrap();
         let group_aad = group_name.clone() + " AAD";
 
-        mls_group.set_aad(group_aad.as_bytes());
+        mls_group
+            .set_aad(&self.crypto, group_aad.as_bytes())
+            .map_err(|e| format!("Failed to store MlsGroup: {e}"))?;
 
         let group = Group {
             group_name: group_name.clone(),
@@ -15,12 +15,14 @@ use mls_interop_proto::mls_client;
 use openmls::{
     ciphersuite::HpkePrivateKey,
     credentials::{Credential, CredentialType, CredentialWithKey},
+    extensions::{Extensions, ExternalSender, SenderExtensionIndex},
     framing::{MlsMessageIn, MlsMessageInBody, MlsMessageOut, ProcessedMessageContent},
     group::{
-        GroupEpoch, GroupId, MlsGroup, MlsGroupConfig, WireFormatPolicy,
-        PURE_CIPHERTEXT_WIRE_FORMAT_POLICY, PURE_PLAINTEXT_WIRE_FORMAT_POLICY,
+        GroupEpoch, GroupId, MlsGroup, MlsGroupConfig, ProposalStore, PublicGroup, StagedCommit,
+        WireFormatPolicy, PURE_CIPHERTEXT_WIRE_FORMAT_POLICY, PURE_PLAINTEXT_WIRE_FORMAT_POLICY,
     },
     key_packages::KeyPackage,
+    messages::{external_proposals::JoinProposal, proposals::ReInitProposal},
     prelude::{config::CryptoConfig, Capabilities, ExtensionType, SenderRatchetConfiguration},
     schedule::{psk::ResumptionPskUsage, ExternalPsk, PreSharedKeyId, Psk},
     treesync::{
@@ -37,7 +39,7 @@ use openmls_traits::{
     types::{Ciphersuite, HpkeKeyPair},
     OpenMlsCryptoProvider,
 };
-use tls_codec::{Deserialize, Serialize};
+use tls_codec::{Deserialize, Serialize, VLBytes};
 use tonic::{async_trait, transport::Server, Code, Request, Response, Status};
 use tracing::{debug, error, info, instrument, trace, Span};
 use tracing_subscriber::EnvFilter;
@@ -61,6 +63,21 @@ pub struct InteropGroup {
     signature_keys: SignatureKeyPair,
     messages_out: Vec<MlsMessageIn>,
     crypto_provider: OpenMlsRustCrypto,
+    /// The ReInit proposal of the last merged commit, if it contained one.
+    reinit: Option<ReInitProposal>,
+}
+
+/// The state of a client for a new group that is created from an existing
+/// group, i.e. a reinitialized group or a subgroup branch: The resumption PSK
+/// of the existing group for the first commit of the new group, which is also
+/// stored in `crypto_provider`, and the client's credential and signature keys
+/// in the new group.
+struct SubgroupState {
+    psk_id: PreSharedKeyId,
+    wire_format_policy: WireFormatPolicy,
+    credential: Credential,
+    signature_keys: SignatureKeyPair,
+    crypto_provider: OpenMlsRustCrypto,
 }
 
 type PendingState = (
@@ -80,6 +97,8 @@ pub struct MlsClientImpl {
     groups: Mutex<Vec<InteropGroup>>,
     pending_state: Mutex<HashMap<Vec<u8>, PendingState>>,
     transaction_id_map: Mutex<HashMap<u32, Vec<u8>>>, // Indirection, linking to pending key packages
+    pending_reinits: Mutex<HashMap<Vec<u8>, (ReInitProposal, SubgroupState)>>,
+    external_signers: Mutex<Vec<SignatureKeyPair>>,
 }
 
 impl MlsClientImpl {
@@ -89,10 +108,358 @@ impl MlsClientImpl {
             groups: Mutex::new(Vec::new()),
             pending_state: Mutex::new(HashMap::new()),
             transaction_id_map: Mutex::new(HashMap::new()),
+            pending_reinits: Mutex::new(HashMap::new()),
+            external_signers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Creates a key package for `identity` and keeps it, together with its
+    /// private keys, as pending state until the client joins a group with it.
+    fn create_pending_key_package(
+        &self,
+        ciphersuite: Ciphersuite,
+        identity: &[u8],
+    ) -> Result<CreateKeyPackageResponse, Status> {
+        let crypto_provider = OpenMlsRustCrypto::default();
+
+        debug!(
+            r#for = String::from_utf8_lossy(identity).to_string(),
+            "Creating key package."
+        );
+
+        let credential = Credential::new(identity.to_vec(), CredentialType::Basic).unwrap();
+        let signature_keys = SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
+
+        let key_package = new_key_package(
+            &crypto_provider,
+            ciphersuite,
+            credential.clone(),
+            &signature_keys,
+        )?;
+        let private_key = crypto_provider
+            .key_store()
+            .read::<HpkePrivateKey>(key_package.hpke_init_key().as_slice())
+            .unwrap();
+
+        let encryption_key_pair =
+            read_keys_from_key_store(&crypto_provider, key_package.leaf_node().encryption_key());
+
+        let transaction_id: [u8; 4] = crypto_provider.rand().random_array().unwrap();
+        let transaction_id = u32::from_be_bytes(transaction_id);
+
+        let key_package_msg: MlsMessageOut = key_package.clone().into();
+        let response = CreateKeyPackageResponse {
+            transaction_id,
+            key_package: key_package_msg
+                .tls_serialize_detached()
+                .expect("error serializing key package"),
+            encryption_priv: encryption_key_pair
+                .private
+                .tls_serialize_detached()
+                .unwrap(),
+            init_priv: private_key.tls_serialize_detached().unwrap(),
+            signature_priv: signature_keys.private().to_vec(),
+        };
+
+        self.transaction_id_map
+            .lock()
+            .unwrap()
+            .insert(transaction_id, identity.to_vec());
+        self.pending_state.lock().unwrap().insert(
+            identity.to_vec(),
+            (
+                key_package,
+                private_key,
+                encryption_key_pair,
+                credential,
+                signature_keys,
+                crypto_provider,
+            ),
+        );
+
+        Ok(response)
+    }
+
+    /// Prepares the reinitialization of the group with the given `state_id`
+    /// after a commit with a ReInit proposal was merged. The key package in
+    /// the response is used to add the client to the new group.
+    fn prepare_reinit(&self, state_id: u32) -> Result<HandleReInitCommitResponse, Status> {
+        let mut groups = self.groups.lock().unwrap();
+        let interop_group = groups
+            .get_mut(state_id as usize)
+            .ok_or_else(|| Status::new(Code::InvalidArgument, "unknown state_id"))?;
+        let reinit = interop_group
+            .reinit
+            .take()
+            .ok_or(Status::failed_precondition(
+                "the commit didn't contain a ReInit proposal",
+            ))?;
+
+        let subgroup = subgroup_state(
+            interop_group,
+            ResumptionPskUsage::Reinit,
+            reinit.ciphersuite(),
+            interop_group.wire_format_policy,
+        )?;
+        let key_package = new_key_package(
+            &subgroup.crypto_provider,
+            reinit.ciphersuite(),
+            subgroup.credential.clone(),
+            &subgroup.signature_keys,
+        )?;
+        let key_package_msg: MlsMessageOut = key_package.into();
+
+        let reinit_id = subgroup
+            .crypto_provider
+            .rand()
+            .random_vec(16)
+            .map_err(|_| Status::internal("unable to create a reinit ID"))?;
+        let epoch_authenticator = interop_group
+            .group
+            .epoch_authenticator()
+            .as_slice()
+            .to_vec();
+        self.pending_reinits
+            .lock()
+            .unwrap()
+            .insert(reinit_id.clone(), (reinit, subgroup));
+
+        Ok(HandleReInitCommitResponse {
+            reinit_id,
+            key_package: key_package_msg
+                .tls_serialize_detached()
+                .map_err(|_| Status::internal("failed to serialize key package"))?,
+            epoch_authenticator,
+        })
+    }
+
+    /// Creates a new group from an existing one, i.e. a reinitialized group
+    /// or a subgroup branch, and adds the clients of the `key_packages` to it.
+    /// The first commit of the new group contains the resumption PSK of the
+    /// existing group.
+    fn create_subgroup(
+        &self,
+        subgroup: SubgroupState,
+        group_id: GroupId,
+        ciphersuite: Ciphersuite,
+        extensions: Extensions,
+        key_packages: &[Vec<u8>],
+        external_tree: bool,
+    ) -> Result<CreateSubgroupResponse, Status> {
+        let SubgroupState {
+            psk_id,
+            wire_format_policy,
+            credential,
+            signature_keys,
+            crypto_provider,
+        } = subgroup;
+
+        let mls_group_config = MlsGroupConfig::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .max_past_epochs(32)
+            .number_of_resumption_psks(32)
+            .sender_ratchet_configuration(SenderRatchetConfiguration::default())
+            .use_ratchet_tree_extension(true)
+            .wire_format_policy(wire_format_policy)
+            .build();
+        let mut group = MlsGroup::new_with_group_id(
+            &crypto_provider,
+            &signature_keys,
+            &mls_group_config,
+            group_id,
+            CredentialWithKey {
+                credential,
+                signature_key: signature_keys.public().into(),
+            },
+        )
+        .map_err(into_status)?;
+
+        group
+            .propose_external_psk(&crypto_provider, &signature_keys, psk_id)
+            .map_err(into_status)?;
+        for key_package in key_packages {
+            let key_package = MlsMessageIn::tls_deserialize_exact(key_package)
+                .map_err(|_| Status::invalid_argument("Invalid key package"))?
+                .into_keypackage()
+                .ok_or(Status::invalid_argument("Message was not a key package"))?;
+            group
+                .propose_add_member(&crypto_provider, &signature_keys, &key_package)
+                .map_err(into_status)?;
         }
+        if extensions.iter().next().is_some() {
+            group
+                .propose_group_context_extensions(&crypto_provider, &signature_keys, extensions)
+                .map_err(into_status)?;
+        }
+
+        let (_commit, welcome_option, _group_info) = group
+            .commit_to_pending_proposals(&crypto_provider, &signature_keys)
+            .map_err(into_status)?;
+        group
+            .merge_pending_commit(&crypto_provider)
+            .map_err(into_status)?;
+        trace!(epoch=?group.epoch(), "Subgroup created.");
+
+        let welcome = if let Some(welcome) = welcome_option {
+            welcome
+                .tls_serialize_detached()
+                .map_err(|_| Status::aborted("failed to serialize welcome"))?
+        } else {
+            vec![]
+        };
+        let ratchet_tree = if external_tree {
+            group
+                .export_ratchet_tree()
+                .tls_serialize_detached()
+                .map_err(|_| Status::aborted("failed to serialize ratchet tree"))?
+        } else {
+            vec![]
+        };
+        let epoch_authenticator = group.epoch_authenticator().as_slice().to_vec();
+
+        let mut groups = self.groups.lock().unwrap();
+        let state_id = groups.len() as u32;
+        groups.push(InteropGroup {
+            group,
+            wire_format_policy,
+            signature_keys,
+            messages_out: Vec::new(),
+            crypto_provider,
+            reinit: None,
+        });
+
+        Ok(CreateSubgroupResponse {
+            state_id,
+            welcome,
+            ratchet_tree,
+            epoch_authenticator,
+        })
+    }
+
+    /// Joins a reinitialized group or a subgroup branch with the `welcome`.
+    /// The resumption PSK of the existing group must be stored in the
+    /// `crypto_provider`. Returns the state ID and the epoch authenticator of
+    /// the new group.
+    fn join_subgroup(
+        &self,
+        wire_format_policy: WireFormatPolicy,
+        signature_keys: SignatureKeyPair,
+        crypto_provider: OpenMlsRustCrypto,
+        welcome: &[u8],
+        ratchet_tree: Vec<u8>,
+    ) -> Result<(u32, Vec<u8>), Status> {
+        let mls_group_config = MlsGroupConfig::builder()
+            .max_past_epochs(32)
+            .number_of_resumption_psks(32)
+            .sender_ratchet_configuration(SenderRatchetConfiguration::default())
+            .use_ratchet_tree_extension(true)
+            .wire_format_policy(wire_format_policy)
+            .build();
+
+        let welcome = MlsMessageIn::tls_deserialize_exact(welcome)
+            .map_err(|_| Status::aborted("failed to deserialize MlsMessage with a Welcome"))?
+            .into_welcome()
+            .ok_or(Status::invalid_argument(
+                "unable to get Welcome from MlsMessage",
+            ))?;
+        let ratchet_tree = ratchet_tree_from_config(ratchet_tree);
+
+        let group =
+            MlsGroup::new_from_welcome(&crypto_provider, &mls_group_config, welcome, ratchet_tree)
+                .map_err(into_status)?;
+        trace!(epoch=?group.epoch(), "Subgroup joined.");
+        let epoch_authenticator = group.epoch_authenticator().as_slice().to_vec();
+
+        let mut groups = self.groups.lock().unwrap();
+        let state_id = groups.len() as u32;
+        groups.push(InteropGroup {
+            group,
+            wire_format_policy,
+            signature_keys,
+            messages_out: Vec::new(),
+            crypto_provider,
+            reinit: None,
+        });
+
+        Ok((state_id, epoch_authenticator))
     }
 }
 
+/// Builds a key package with the capabilities of the interop client.
+fn new_key_package(
+    crypto_provider: &OpenMlsRustCrypto,
+    ciphersuite: Ciphersuite,
+    credential: Credential,
+    signature_keys: &SignatureKeyPair,
+) -> Result<KeyPackage, Status> {
+    KeyPackage::builder()
+        .leaf_node_capabilities(Capabilities::new(
+            Some(&[ProtocolVersion::Mls10, ProtocolVersion::Mls10Draft11]),
+            Some(&[
+                Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519,
+                Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
+                Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519,
+                Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521,
+                Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384,
+            ]),
+            Some(&EXTENSION_TYPES),
+            None,
+            Some(&CREDENTIAL_TYPES),
+        ))
+        .build(
+            CryptoConfig {
+                ciphersuite,
+                version: ProtocolVersion::default(),
+            },
+            crypto_provider,
+            signature_keys,
+            CredentialWithKey {
+                credential,
+                signature_key: signature_keys.public().into(),
+            },
+        )
+        .map_err(into_status)
+}
+
+/// Creates the state of the client for a new group with the given
+/// `ciphersuite` that is created from `interop_group` in its current epoch:
+/// new signature keys and a new crypto provider that stores the resumption PSK
+/// of the current epoch with the given `usage`.
+fn subgroup_state(
+    interop_group: &InteropGroup,
+    usage: ResumptionPskUsage,
+    ciphersuite: Ciphersuite,
+    wire_format_policy: WireFormatPolicy,
+) -> Result<SubgroupState, Status> {
+    let group = &interop_group.group;
+    let crypto_provider = OpenMlsRustCrypto::default();
+
+    let psk_nonce = crypto_provider
+        .rand()
+        .random_vec(ciphersuite.hash_length())
+        .map_err(|_| Status::internal("unable to create a PSK nonce"))?;
+    let psk_id =
+        PreSharedKeyId::resumption(usage, group.group_id().clone(), group.epoch(), psk_nonce);
+    let resumption_psk = group
+        .get_past_resumption_psk(group.epoch())
+        .ok_or(Status::internal("missing resumption PSK"))?;
+    psk_id
+        .write_to_key_store(&crypto_provider, ciphersuite, resumption_psk.as_slice())
+        .map_err(into_status)?;
+
+    let credential = group.credential().map_err(into_status)?.clone();
+    let signature_keys = SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
+    signature_keys.store(crypto_provider.key_store()).unwrap();
+
+    Ok(SubgroupState {
+        psk_id,
+        wire_format_policy,
+        credential,
+        signature_keys,
+        crypto_provider,
+    })
+}
+
 fn into_status<E: Display>(e: E) -> Status {
     let message = "mls group error ".to_string() + &e.to_string();
     error!("{message}");
@@ -113,6 +480,8 @@ fn to_ciphersuite(cs: u32) -> Result<&'static Ciphersuite, Status> {
         Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519,
         Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
         Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519,
+        Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521,
+        Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384,
     ];
     match ciphersuites.iter().find(|&&cs| cs == cs_name) {
         Some(ciphersuite) => Ok(ciphersuite),
@@ -123,6 +492,24 @@ fn to_ciphersuite(cs: u32) -> Result<&'static Ciphersuite, Status> {
     }
 }
 
+/// Converts the extensions of a request into group context [`Extensions`].
+fn to_extensions(extensions: &[mls_client::Extension]) -> Result<Extensions, Status> {
+    let extensions = extensions
+        .iter()
+        .map(|extension| {
+            let extension_type = u16::try_from(extension.extension_type)
+                .map_err(|_| Status::invalid_argument("Invalid extension type"))?;
+            let mut bytes = extension_type.to_be_bytes().to_vec();
+            VLBytes::new(extension.extension_data.clone())
+                .tls_serialize(&mut bytes)
+                .map_err(|_| Status::invalid_argument("Invalid extension data"))?;
+            openmls::extensions::Extension::tls_deserialize_exact(bytes)
+                .map_err(|_| Status::invalid_argument("Invalid extension"))
+        })
+        .collect::<Result<Vec<_>, Status>>()?;
+    Extensions::from_vec(extensions).map_err(into_status)
+}
+
 fn _into_bytes(obj: impl serde::Serialize) -> Vec<u8> {
     serde_json::to_string_pretty(&obj)
         .expect("Error serializing test vectors")
@@ -200,6 +587,8 @@ impl MlsClient for MlsClientImpl {
             Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519,
             Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
             Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519,
+            Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521,
+            Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384,
         ];
         let response = SupportedCiphersuitesResponse {
             ciphersuites: ciphersuites
@@ -259,6 +648,7 @@ impl MlsClient for MlsClientImpl {
             signature_keys,
             messages_out: Vec::new(),
             crypto_provider: backend,
+            reinit: None,
         };
 
         let mut groups = self.groups.lock().unwrap();
@@ -279,83 +669,8 @@ impl MlsClient for MlsClientImpl {
         let request = request.get_ref();
         info!(?request, "Request");
 
-        let crypto_provider = OpenMlsRustCrypto::default();
         let ciphersuite = *to_ciphersuite(request.cipher_suite)?;
-        let identity = request.identity.clone();
-
-        debug!(
-            r#for = String::from_utf8_lossy(&identity).to_string(),
-            "Creating key package."
-        );
-
-        let credential = Credential::new(identity, CredentialType::Basic).unwrap();
-        let signature_keys = SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
-
-        let key_package = KeyPackage::builder()
-            .leaf_node_capabilities(Capabilities::new(
-                Some(&[ProtocolVersion::Mls10, ProtocolVersion::Mls10Draft11]),
-                Some(&[
-                    Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519,
-                    Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
-                    Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519,
-                ]),
-                Some(&EXTENSION_TYPES),
-                None,
-                Some(&CREDENTIAL_TYPES),
-            ))
-            .build(
-                CryptoConfig {
-                    ciphersuite,
-                    version: ProtocolVersion::default(),
-                },
-                &crypto_provider,
-                &signature_keys,
-                CredentialWithKey {
-                    credential: credential.clone(),
-                    signature_key: signature_keys.public().into(),
-                },
-            )
-            .unwrap();
-        let private_key = crypto_provider
-            .key_store()
-            .read::<HpkePrivateKey>(key_package.hpke_init_key().as_slice())
-            .unwrap();
-
-        let encryption_key_pair =
-            read_keys_from_key_store(&crypto_provider, key_package.leaf_node().encryption_key());
-
-        let transaction_id: [u8; 4] = crypto_provider.rand().random_array().unwrap();
-        let transaction_id = u32::from_be_bytes(transaction_id);
-
-        let key_package_msg: MlsMessageOut = key_package.clone().into();
-        let response = CreateKeyPackageResponse {
-            transaction_id,
-            key_package: key_package_msg
-                .tls_serialize_detached()
-                .expect("error serializing key package"),
-            encryption_priv: encryption_key_pair
-                .private
-                .tls_serialize_detached()
-                .unwrap(),
-            init_priv: private_key.tls_serialize_detached().unwrap(),
-            signature_priv: signature_keys.private().to_vec(),
-        };
-
-        self.transaction_id_map
-            .lock()
-            .unwrap()
-            .insert(transaction_id, request.identity.clone());
-        self.pending_state.lock().unwrap().insert(
-            request.identity.clone(),
-            (
-                key_package,
-                private_key,
-                encryption_key_pair,
-                credential,
-                signature_keys,
-                crypto_provider,
-            ),
-        );
+        let response = self.create_pending_key_package(ciphersuite, &request.identity)?;
 
         info!(?response, "Response");
         Ok(Response::new(response))
@@ -446,6 +761,7 @@ impl MlsClient for MlsClientImpl {
             signature_keys: my_signature_keys,
             messages_out: Vec::new(),
             crypto_provider,
+            reinit: None,
         };
         trace!("   in epoch {:?}", interop_group.group.epoch());
         trace!(
@@ -552,6 +868,7 @@ impl MlsClient for MlsClientImpl {
                     signature_keys: signer,
                     messages_out: Vec::new(),
                     crypto_provider: backend,
+                    reinit: None,
                 },
                 commit,
             )
@@ -653,7 +970,10 @@ impl MlsClient for MlsClientImpl {
             .ok_or_else(|| Status::new(Code::InvalidArgument, "unknown state_id"))?;
         trace!(actor=String::from_utf8_lossy(interop_group.group.own_identity().unwrap()).to_string(), epoch=?interop_group.group.epoch(), "Protecting.");
 
-        interop_group.group.set_aad(&request.authenticated_data);
+        interop_group
+            .group
+            .set_aad(&interop_group.crypto_provider, &request.authenticated_data)
+            .map_err(into_status)?;
 
         let ciphertext = interop_group
             .group
@@ -729,18 +1049,14 @@ impl MlsClient for MlsClientImpl {
 
         let raw_psk_id = request.psk_id.clone();
         trace!("   psk_id {:x?}", raw_psk_id);
-        let external_psk = Psk::External(ExternalPsk::new(raw_psk_id));
 
         fn store(
             ciphersuite: Ciphersuite,
             crypto_provider: &OpenMlsRustCrypto,
-            external_psk: Psk,
+            raw_psk_id: Vec<u8>,
             secret: &[u8],
         ) -> Result<(), Status> {
-            let psk_id = PreSharedKeyId::new(ciphersuite, crypto_provider.rand(), external_psk)
-                .map_err(|_| Status::internal("unable to create PreSharedKeyId from raw psk_id"))?;
-            psk_id
-                .write_to_key_store(crypto_provider, ciphersuite, secret)
+            PreSharedKeyId::store_external(crypto_provider, ciphersuite, raw_psk_id, secret)
                 .map_err(|_| Status::new(Code::Internal, "unable to store PSK"))?;
             Ok(())
         }
@@ -758,7 +1074,7 @@ impl MlsClient for MlsClientImpl {
             store(
                 pending_state.0.ciphersuite(),
                 &pending_state.5,
-                external_psk,
+                raw_psk_id,
                 &request.psk_secret,
             )?;
         } else {
@@ -776,7 +1092,7 @@ impl MlsClient for MlsClientImpl {
             store(
                 interop_group.group.ciphersuite(),
                 &interop_group.crypto_provider,
-                external_psk,
+                raw_psk_id,
                 &request.psk_secret,
             )?;
         }
@@ -824,7 +1140,10 @@ impl MlsClient for MlsClientImpl {
             .number_of_resumption_psks(32)
             .wire_format_policy(interop_group.wire_format_policy)
             .build();
-        interop_group.group.set_configuration(&mls_group_config);
+        interop_group
+            .group
+            .set_configuration(&interop_group.crypto_provider, &mls_group_config)
+            .map_err(into_status)?;
         let (proposal, _) = interop_group
             .group
             .propose_add_member(
@@ -872,7 +1191,10 @@ impl MlsClient for MlsClientImpl {
             .use_ratchet_tree_extension(true)
             .wire_format_policy(interop_group.wire_format_policy)
             .build();
-        interop_group.group.set_configuration(&mls_group_config);
+        interop_group
+            .group
+            .set_configuration(&interop_group.crypto_provider, &mls_group_config)
+            .map_err(into_status)?;
         let (proposal, _) = interop_group
             .group
             .propose_self_update(
@@ -926,7 +1248,10 @@ impl MlsClient for MlsClientImpl {
             .use_ratchet_tree_extension(true)
             .wire_format_policy(interop_group.wire_format_policy)
             .build();
-        interop_group.group.set_configuration(&mls_group_config);
+        interop_group
+            .group
+            .set_configuration(&interop_group.crypto_provider, &mls_group_config)
+            .map_err(into_status)?;
         trace!("   prepared remove");
 
         let (proposal, _) = interop_group
@@ -958,10 +1283,38 @@ impl MlsClient for MlsClientImpl {
         let request = request.get_ref();
         info!(?request, "Request");
 
-        let response = Status::unimplemented("Re-init is not implemented");
+        let mut groups = self.groups.lock().unwrap();
+        let interop_group = groups
+            .get_mut(request.state_id as usize)
+            .ok_or_else(|| Status::new(Code::InvalidArgument, "unknown state_id"))?;
+        let group = &mut interop_group.group;
+
+        Span::current().record("actor", bytes_to_string(group.own_identity().unwrap()));
+
+        let reinit = ReInitProposal::new(
+            GroupId::from_slice(&request.group_id),
+            ProtocolVersion::default(),
+            *to_ciphersuite(request.cipher_suite)?,
+            to_extensions(&request.extensions)?,
+        );
+        let (proposal, _) = group
+            .propose_reinit(
+                &interop_group.crypto_provider,
+                &interop_group.signature_keys,
+                reinit,
+            )
+            .map_err(into_status)?;
+        trace!(?proposal, "Re-init proposal created.");
+
+        // Store the proposal for potential future use.
+        interop_group.messages_out.push(proposal.clone().into());
+
+        let response = ProposalResponse {
+            proposal: proposal.to_bytes().unwrap(),
+        };
 
         info!(?response, "Response");
-        Err(response)
+        Ok(Response::new(response))
     }
 
     #[instrument(skip_all, fields(actor))]
@@ -1001,7 +1354,9 @@ impl MlsClient for MlsClientImpl {
             match processed_message.into_content() {
                 ProcessedMessageContent::ApplicationMessage(_) => unreachable!(),
                 ProcessedMessageContent::ProposalMessage(proposal) => {
-                    group.store_pending_proposal(*proposal);
+                    group
+                        .store_pending_proposal(&interop_group.crypto_provider, *proposal)
+                        .map_err(into_status)?;
                 }
                 ProcessedMessageContent::ExternalJoinProposalMessage(_) => unreachable!(),
                 ProcessedMessageContent::StagedCommitMessage(_) => unreachable!(),
@@ -1083,11 +1438,13 @@ impl MlsClient for MlsClientImpl {
 
                     (msg_out, proposal_ref)
                 }
-                "groupContextExtensions" => {
-                    return Err(Status::internal(
-                        "Unsupported proposal type (group context extension)",
-                    ))
-                }
+                "groupContextExtensions" => group
+                    .propose_group_context_extensions_by_value(
+                        &interop_group.crypto_provider,
+                        &interop_group.signature_keys,
+                        to_extensions(&proposal.extensions)?,
+                    )
+                    .map_err(|_| Status::internal("Unable to generate proposal by value"))?,
                 _ => return Err(Status::invalid_argument("Invalid proposal type")),
             };
 
@@ -1119,6 +1476,10 @@ impl MlsClient for MlsClientImpl {
 
         debug!(commit=?group.pending_commit(), "Pending commit created. (Note: Not merged yet.)");
 
+        interop_group.reinit = group
+            .pending_commit()
+            .and_then(StagedCommit::reinit_proposal)
+            .cloned();
         group
             .merge_pending_commit(&interop_group.crypto_provider)
             .map_err(into_status)?;
@@ -1179,7 +1540,9 @@ impl MlsClient for MlsClientImpl {
             match processed_message.into_content() {
                 ProcessedMessageContent::ApplicationMessage(_) => unreachable!(),
                 ProcessedMessageContent::ProposalMessage(proposal) => {
-                    group.store_pending_proposal(*proposal);
+                    group
+                        .store_pending_proposal(&interop_group.crypto_provider, *proposal)
+                        .map_err(into_status)?;
                 }
                 ProcessedMessageContent::ExternalJoinProposalMessage(_) => unreachable!(),
                 ProcessedMessageContent::StagedCommitMessage(_) => unreachable!(),
@@ -1208,6 +1571,7 @@ impl MlsClient for MlsClientImpl {
             ProcessedMessageContent::ExternalJoinProposalMessage(_) => unreachable!(),
             ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
                 debug!(commit=?staged_commit, "Merging staged commit.");
+                interop_group.reinit = staged_commit.reinit_proposal().cloned();
                 group
                     .merge_staged_commit(&interop_group.crypto_provider, *staged_commit)
                     .map_err(into_status)?;
@@ -1402,88 +1766,435 @@ impl MlsClient for MlsClientImpl {
     #[instrument(skip_all, fields(actor))]
     async fn group_context_extensions_proposal(
         &self,
-        _request: Request<GroupContextExtensionsProposalRequest>,
+        request: Request<GroupContextExtensionsProposalRequest>,
     ) -> Result<Response<ProposalResponse>, Status> {
-        Err(Status::unimplemented(
-            "Group context extension is not implemented yet",
-        ))
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        let mut groups = self.groups.lock().unwrap();
+        let interop_group = groups
+            .get_mut(request.state_id as usize)
+            .ok_or_else(|| Status::new(Code::InvalidArgument, "unknown state_id"))?;
+        let group = &mut interop_group.group;
+
+        Span::current().record("actor", bytes_to_string(group.own_identity().unwrap()));
+
+        let (proposal, _) = group
+            .propose_group_context_extensions(
+                &interop_group.crypto_provider,
+                &interop_group.signature_keys,
+                to_extensions(&request.extensions)?,
+            )
+            .map_err(into_status)?;
+        trace!(?proposal, "Group context extensions proposal created.");
+
+        // Store the proposal for potential future use.
+        interop_group.messages_out.push(proposal.clone().into());
+
+        let response = ProposalResponse {
+            proposal: proposal.to_bytes().unwrap(),
+        };
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all)]
     async fn re_init_commit(
         &self,
-        _: Request<CommitRequest>,
+        request: Request<CommitRequest>,
     ) -> Result<Response<CommitResponse>, Status> {
-        todo!()
+        // The ReInit proposal of the commit is kept until the pending commit
+        // is handled.
+        self.commit(request).await
     }
 
+    #[instrument(skip_all)]
     async fn handle_pending_re_init_commit(
         &self,
-        _: Request<HandlePendingCommitRequest>,
+        request: Request<HandlePendingCommitRequest>,
     ) -> Result<Response<HandleReInitCommitResponse>, Status> {
-        todo!()
+        let state_id = request.get_ref().state_id;
+        self.handle_pending_commit(request).await?;
+
+        let response = self.prepare_reinit(state_id)?;
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all)]
     async fn handle_re_init_commit(
         &self,
-        _: Request<HandleCommitRequest>,
+        request: Request<HandleCommitRequest>,
     ) -> Result<Response<HandleReInitCommitResponse>, Status> {
-        todo!()
+        let state_id = request.get_ref().state_id;
+        self.handle_commit(request).await?;
+
+        let response = self.prepare_reinit(state_id)?;
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all)]
     async fn re_init_welcome(
         &self,
-        _: Request<ReInitWelcomeRequest>,
+        request: Request<ReInitWelcomeRequest>,
     ) -> Result<Response<CreateSubgroupResponse>, Status> {
-        todo!()
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        let (reinit, mut subgroup) = self
+            .pending_reinits
+            .lock()
+            .unwrap()
+            .remove(&request.reinit_id)
+            .ok_or(Status::invalid_argument("unknown reinit_id"))?;
+        subgroup.wire_format_policy = wire_format_policy(request.encrypt_handshake);
+
+        let response = self.create_subgroup(
+            subgroup,
+            reinit.group_id().clone(),
+            reinit.ciphersuite(),
+            reinit.extensions().clone(),
+            &request.key_package,
+            request.external_tree,
+        )?;
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all)]
     async fn handle_re_init_welcome(
         &self,
-        _: Request<HandleReInitWelcomeRequest>,
+        request: Request<HandleReInitWelcomeRequest>,
     ) -> Result<Response<JoinGroupResponse>, Status> {
-        todo!()
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        let (_reinit, subgroup) = self
+            .pending_reinits
+            .lock()
+            .unwrap()
+            .remove(&request.reinit_id)
+            .ok_or(Status::invalid_argument("unknown reinit_id"))?;
+
+        let (state_id, epoch_authenticator) = self.join_subgroup(
+            subgroup.wire_format_policy,
+            subgroup.signature_keys,
+            subgroup.crypto_provider,
+            &request.welcome,
+            request.ratchet_tree.clone(),
+        )?;
+
+        let response = JoinGroupResponse {
+            state_id,
+            epoch_authenticator,
+        };
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all)]
     async fn create_branch(
         &self,
-        _: Request<CreateBranchRequest>,
+        request: Request<CreateBranchRequest>,
     ) -> Result<Response<CreateSubgroupResponse>, Status> {
-        todo!()
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        let (subgroup, ciphersuite) = {
+            let groups = self.groups.lock().unwrap();
+            let interop_group = groups
+                .get(request.state_id as usize)
+                .ok_or_else(|| Status::new(Code::InvalidArgument, "unknown state_id"))?;
+            let ciphersuite = interop_group.group.ciphersuite();
+            let subgroup = subgroup_state(
+                interop_group,
+                ResumptionPskUsage::Branch,
+                ciphersuite,
+                interop_group.wire_format_policy,
+            )?;
+            (subgroup, ciphersuite)
+        };
+
+        let response = self.create_subgroup(
+            subgroup,
+            GroupId::from_slice(&request.group_id),
+            ciphersuite,
+            to_extensions(&request.extensions)?,
+            &request.key_packages,
+            request.external_tree,
+        )?;
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all)]
     async fn handle_branch(
         &self,
-        _: Request<HandleBranchRequest>,
+        request: Request<HandleBranchRequest>,
     ) -> Result<Response<HandleBranchResponse>, Status> {
-        todo!()
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        let identity = self
+            .transaction_id_map
+            .lock()
+            .unwrap()
+            .get(&request.transaction_id)
+            .cloned()
+            .ok_or(Status::invalid_argument("unknown transaction_id"))?;
+        let (key_package, _, _, _, signature_keys, crypto_provider) = self
+            .pending_state
+            .lock()
+            .unwrap()
+            .remove(&identity)
+            .ok_or(Status::aborted(format!(
+                "failed to find key package for identity {identity:x?}"
+            )))?;
+
+        // Store the resumption PSK of the existing group for the new group.
+        // Its usage and nonce are not part of the key.
+        let wire_format_policy = {
+            let groups = self.groups.lock().unwrap();
+            let interop_group = groups
+                .get(request.state_id as usize)
+                .ok_or_else(|| Status::new(Code::InvalidArgument, "unknown state_id"))?;
+            let group = &interop_group.group;
+            let resumption_psk = group
+                .get_past_resumption_psk(group.epoch())
+                .ok_or(Status::internal("missing resumption PSK"))?;
+            PreSharedKeyId::resumption(
+                ResumptionPskUsage::Branch,
+                group.group_id().clone(),
+                group.epoch(),
+                vec![],
+            )
+            .write_to_key_store(
+                &crypto_provider,
+                key_package.ciphersuite(),
+                resumption_psk.as_slice(),
+            )
+            .map_err(into_status)?;
+            interop_group.wire_format_policy
+        };
+
+        let (state_id, epoch_authenticator) = self.join_subgroup(
+            wire_format_policy,
+            signature_keys,
+            crypto_provider,
+            &request.welcome,
+            request.ratchet_tree.clone(),
+        )?;
+
+        let response = HandleBranchResponse {
+            state_id,
+            epoch_authenticator,
+        };
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all)]
     async fn new_member_add_proposal(
         &self,
-        _: Request<NewMemberAddProposalRequest>,
+        request: Request<NewMemberAddProposalRequest>,
     ) -> Result<Response<NewMemberAddProposalResponse>, Status> {
-        todo!()
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        let group_info = MlsMessageIn::tls_deserialize_exact(&request.group_info)
+            .map_err(|_| Status::invalid_argument("failed to deserialize group info"))?
+            .into_verifiable_group_info()
+            .ok_or(Status::invalid_argument("Message was not a group info"))?;
+
+        // The key package is kept as pending state so that the client can join
+        // the group with the Welcome once the proposal was committed.
+        let key_package_response =
+            self.create_pending_key_package(group_info.ciphersuite(), &request.identity)?;
+
+        let proposal = {
+            let pending_state = self.pending_state.lock().unwrap();
+            let (key_package, _, _, _, signature_keys, _) = pending_state
+                .get(&request.identity)
+                .ok_or(Status::internal("Unable to retrieve pending state"))?;
+            JoinProposal::new(
+                key_package.clone(),
+                group_info.group_id().clone(),
+                group_info.epoch(),
+                signature_keys,
+            )
+            .map_err(into_status)?
+        };
+
+        let response = NewMemberAddProposalResponse {
+            transaction_id: key_package_response.transaction_id,
+            proposal: proposal
+                .tls_serialize_detached()
+                .map_err(|_| Status::internal("failed to serialize proposal"))?,
+            init_priv: key_package_response.init_priv,
+            encryption_priv: key_package_response.encryption_priv,
+            signature_priv: key_package_response.signature_priv,
+        };
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all)]
     async fn create_external_signer(
         &self,
-        _: Request<CreateExternalSignerRequest>,
+        request: Request<CreateExternalSignerRequest>,
     ) -> Result<Response<CreateExternalSignerResponse>, Status> {
-        todo!()
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        let ciphersuite = *to_ciphersuite(request.cipher_suite)?;
+        let credential = Credential::new(request.identity.clone(), CredentialType::Basic).unwrap();
+        let signature_keys = SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
+
+        let external_sender = ExternalSender::new(signature_keys.public().into(), credential)
+            .tls_serialize_detached()
+            .map_err(|_| Status::internal("failed to serialize external sender"))?;
+
+        let mut external_signers = self.external_signers.lock().unwrap();
+        let signer_id = external_signers.len() as u32;
+        external_signers.push(signature_keys);
+
+        let response = CreateExternalSignerResponse {
+            signer_id,
+            external_sender,
+        };
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all, fields(actor))]
     async fn add_external_signer(
         &self,
-        _: Request<AddExternalSignerRequest>,
+        request: Request<AddExternalSignerRequest>,
     ) -> Result<Response<ProposalResponse>, Status> {
-        todo!()
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        let mut groups = self.groups.lock().unwrap();
+        let interop_group = groups
+            .get_mut(request.state_id as usize)
+            .ok_or_else(|| Status::new(Code::InvalidArgument, "unknown state_id"))?;
+        let group = &mut interop_group.group;
+
+        Span::current().record("actor", bytes_to_string(group.own_identity().unwrap()));
+
+        let external_sender = ExternalSender::tls_deserialize_exact(&request.external_sender)
+            .map_err(|_| Status::invalid_argument("Invalid external sender"))?;
+        let (proposal, _) = group
+            .propose_add_external_sender(
+                &interop_group.crypto_provider,
+                &interop_group.signature_keys,
+                external_sender,
+            )
+            .map_err(into_status)?;
+        trace!(?proposal, "External sender proposal created.");
+
+        // Store the proposal for potential future use.
+        interop_group.messages_out.push(proposal.clone().into());
+
+        let response = ProposalResponse {
+            proposal: proposal.to_bytes().unwrap(),
+        };
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
+    #[instrument(skip_all)]
     async fn external_signer_proposal(
         &self,
-        _: Request<ExternalSignerProposalRequest>,
+        request: Request<ExternalSignerProposalRequest>,
     ) -> Result<Response<ProposalResponse>, Status> {
-        todo!()
+        let request = request.get_ref();
+        info!(?request, "Request");
+
+        let external_signers = self.external_signers.lock().unwrap();
+        let signature_keys = external_signers
+            .get(request.signer_id as usize)
+            .ok_or_else(|| Status::new(Code::InvalidArgument, "unknown signer_id"))?;
+
+        let group_info = MlsMessageIn::tls_deserialize_exact(&request.group_info)
+            .map_err(|_| Status::invalid_argument("failed to deserialize group info"))?
+            .into_verifiable_group_info()
+            .ok_or(Status::invalid_argument("Message was not a group info"))?;
+        let ratchet_tree = ratchet_tree_from_config(request.ratchet_tree.clone())
+            .ok_or(Status::invalid_argument("missing ratchet tree"))?;
+        let (public_group, _group_info) = PublicGroup::from_external(
+            &OpenMlsRustCrypto::default(),
+            ratchet_tree,
+            group_info,
+            ProposalStore::new(),
+        )
+        .map_err(into_status)?;
+
+        let sender_index = public_group
+            .external_senders()
+            .and_then(|external_senders| {
+                external_senders.iter().position(|external_sender| {
+                    external_sender.signature_key().as_slice() == signature_keys.public()
+                })
+            })
+            .map(|index| SenderExtensionIndex::new(index as u32))
+            .ok_or(Status::failed_precondition(
+                "the signer is not an external sender of the group",
+            ))?;
+
+        let description = request
+            .description
+            .as_ref()
+            .ok_or(Status::invalid_argument("missing proposal description"))?;
+        let proposal_type = String::from_utf8_lossy(&description.proposal_type).to_string();
+        trace!(r#type = proposal_type, "Creating external proposal.");
+
+        let proposal = match proposal_type.as_ref() {
+            "add" => {
+                let key_package = MlsMessageIn::tls_deserialize_exact(&description.key_package)
+                    .map_err(|_| Status::invalid_argument("Invalid key package"))?
+                    .into_keypackage()
+                    .ok_or(Status::invalid_argument("Message was not a key package"))?;
+                public_group
+                    .create_external_add_proposal(key_package, signature_keys, sender_index)
+                    .map_err(into_status)?
+            }
+            "remove" => {
+                let removed_credential =
+                    Credential::new(description.removed_id.clone(), CredentialType::Basic).unwrap();
+                let removed = public_group
+                    .members()
+                    .find(|member| member.credential == removed_credential)
+                    .ok_or(Status::invalid_argument("unknown member"))?
+                    .index;
+                public_group
+                    .create_external_remove_proposal(removed, signature_keys, sender_index)
+                    .map_err(into_status)?
+            }
+            _ => {
+                return Err(Status::invalid_argument(
+                    "External senders can only propose adds and removes",
+                ))
+            }
+        };
+
+        let response = ProposalResponse {
+            proposal: proposal.to_bytes().unwrap(),
+        };
+
+        info!(?response, "Response");
+        Ok(Response::new(response))
     }
 
     async fn free(&self, _request: Request<FreeRequest>) -> Result<Response<FreeResponse>, Status> {
@@ -1,4 +1,6 @@
-use openmls_traits::key_store::{MlsEntity, OpenMlsKeyStore};
+use openmls_traits::key_store::{
+    BatchOperation, MlsEntity, MlsEntityId, OpenMlsKeyStore, WriteBatch,
+};
 use std::{collections::HashMap, sync::RwLock};
 
 #[derive(Debug, Default)]
@@ -15,13 +17,8 @@ impl OpenMlsKeyStore for MemoryKeyStore {
     ///
     /// Returns an error if storing fails.
     fn store<V: MlsEntity>(&self, k: &[u8], v: &V) -> Result<(), Self::Error> {
-        let value = serde_json::to_vec(v).map_err(|_| MemoryKeyStoreError::SerializationError)?;
-        // We unwrap here, because this is the only function claiming a write
-        // lock on `credential_bundles`. It only holds the lock very briefly and
-        // should not panic during that period.
-        let mut values = self.values.write().unwrap();
-        values.insert(k.to_vec(), value);
-        Ok(())
+        let value = self.encode(v)?;
+        self.store_encoded(V::ID, k, &value)
     }
 
     /// Read and return a value stored for ID `k` that implements the
@@ -33,11 +30,7 @@ impl OpenMlsKeyStore for MemoryKeyStore {
         // `init_key_package_bundles` (this one and `generate_key_package_bundle`) only
         // hold the lock very briefly and should not panic during that period.
         let values = self.values.read().unwrap();
-        if let Some(value) = values.get(k) {
-            serde_json::from_slice(value).ok()
-        } else {
-            None
-        }
+        values.get(k).and_then(|value| self.decode(value))
     }
 
     /// Delete a value stored for ID `k`.
@@ -49,6 +42,47 @@ impl OpenMlsKeyStore for MemoryKeyStore {
         values.remove(k);
         Ok(())
     }
+
+    /// Encode the value `v` as JSON.
+    ///
+    /// Returns an error if serializing fails.
+    fn encode<V: MlsEntity>(&self, v: &V) -> Result<Vec<u8>, Self::Error> {
+        serde_json::to_vec(v).map_err(|_| MemoryKeyStoreError::SerializationError)
+    }
+
+    /// Decode a value that was encoded with [`Self::encode()`].
+    ///
+    /// Returns [`None`] if deserializing fails.
+    fn decode<V: MlsEntity>(&self, value: &[u8]) -> Option<V> {
+        serde_json::from_slice(value).ok()
+    }
+
+    /// Store a `value` that was encoded with [`Self::encode()`] for ID `k`.
+    fn store_encoded(
+        &self,
+        _entity_id: MlsEntityId,
+        k: &[u8],
+        value: &[u8],
+    ) -> Result<(), Self::Error> {
+        // We unwrap here, because this is the only function claiming a write
+        // lock on `credential_bundles`. It only holds the lock very briefly and
+        // should not panic during that period.
+        let mut values = self.values.write().unwrap();
+        values.insert(k.to_vec(), value.to_vec());
+        Ok(())
+    }
+
+    /// Apply all writes and deletions of the `batch` at once.
+    fn apply_batch(&self, batch: WriteBatch) -> Result<(), Self::Error> {
+        let mut values = self.values.write().unwrap();
+        for operation in batch.into_operations() {
+            match operation {
+                BatchOperation::Store { key, value, .. } => values.insert(key, value),
+                BatchOperation::Delete { key, .. } => values.remove(&key),
+            };
+        }
+        Ok(())
+    }
 }
 
 /// Errors thrown by the key store.
@@ -0,0 +1,72 @@
+//! Runs an example client against a running `examples-ds`.
+//!
+//! Usage: `examples-client <identity> [address]`, where the address defaults
+//! to `127.0.0.1:7878`. Commands are read from stdin, one per line:
+//!
+//! ```text
+//! publish                  publish a fresh key package
+//! create <group>           create a group
+//! add <group> <identity>   add a client to a group
+//! send <group> <text>      send a message to a group
+//! group-info <group>       publish a group info for external joins
+//! join <group>             join a group with an external commit
+//! members <group>          list the members of a group
+//! receive                  process all queued messages
+//! ```
+
+use std::io::{self, BufRead};
+
+use openmls_examples::client::{Client, ClientError, Event, TcpConnection};
+
+fn main() -> Result<(), ClientError> {
+    let mut args = std::env::args().skip(1);
+    let Some(identity) = args.next() else {
+        eprintln!("Usage: examples-client <identity> [address]");
+        std::process::exit(2);
+    };
+    let addr = args.next().unwrap_or_else(|| "127.0.0.1:7878".to_owned());
+    let mut client = Client::new(identity, TcpConnection::connect(addr)?)?;
+
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        let mut words = line.splitn(3, ' ');
+        let result = match (words.next(), words.next(), words.next()) {
+            (Some("publish"), None, None) => client.publish_key_package(),
+            (Some("create"), Some(group), None) => client.create_group(group),
+            (Some("add"), Some(group), Some(identity)) => client.add_member(group, identity),
+            (Some("send"), Some(group), Some(text)) => client.send_message(group, text),
+            (Some("group-info"), Some(group), None) => client.publish_group_info(group),
+            (Some("join"), Some(group), None) => client.join_by_external_commit(group),
+            (Some("members"), Some(group), None) => client.members(group).map(|members| {
+                println!("{}", members.join(", "));
+            }),
+            (Some("receive"), None, None) => client.receive().map(|events| {
+                for event in events {
+                    print_event(event);
+                }
+            }),
+            (None | Some(""), ..) => continue,
+            _ => {
+                eprintln!("Unknown command: {line}");
+                continue;
+            }
+        };
+        match result {
+            Ok(()) => println!("ok"),
+            Err(e) => eprintln!("error: {e}"),
+        }
+    }
+    Ok(())
+}
+
+fn print_event(event: Event) {
+    match event {
+        Event::Joined { group } => println!("[{group}] joined"),
+        Event::NewEpoch { group, epoch } => println!("[{group}] epoch {epoch}"),
+        Event::Message {
+            group,
+            sender,
+            text,
+        } => println!("[{group}] {sender}: {text}"),
+    }
+}
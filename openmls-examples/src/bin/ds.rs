@@ -0,0 +1,17 @@
+//! Runs the example delivery service.
+//!
+//! Usage: `examples-ds [address]`, where the address defaults to
+//! `127.0.0.1:7878`.
+
+use std::net::TcpListener;
+
+use openmls_examples::ds::{serve, DeliveryService};
+
+fn main() -> std::io::Result<()> {
+    let addr = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:7878".to_owned());
+    let listener = TcpListener::bind(&addr)?;
+    println!("Delivery service listening on {}", listener.local_addr()?);
+    serve(DeliveryService::new(), listener)
+}
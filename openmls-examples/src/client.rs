@@ -0,0 +1,448 @@
+//! # An example MLS client
+//!
+//! The [`Client`] composes the [`MlsGroup`] API into the operations of a
+//! simple messenger: publishing key packages, creating groups, adding members,
+//! sending and receiving messages, and joining groups with external commits.
+//!
+//! The client talks to the [`DeliveryService`] through a [`Connection`],
+//! either in-process or over TCP with a [`TcpConnection`].
+
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpStream, ToSocketAddrs},
+};
+
+use openmls::prelude::*;
+use openmls_basic_credential::SignatureKeyPair;
+use openmls_memory_keystore::MemoryKeyStoreError;
+use openmls_rust_crypto::OpenMlsRustCrypto;
+use openmls_traits::{types::CryptoError, OpenMlsCryptoProvider};
+use thiserror::Error;
+
+use crate::ds::{DeliveryService, Request, Response};
+
+/// The ciphersuite used by all example clients.
+pub const CIPHERSUITE: Ciphersuite = Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
+
+/// Client error
+#[derive(Error, Debug)]
+pub enum ClientError {
+    /// The delivery service returned an error.
+    #[error("Delivery service error: {0}")]
+    DeliveryService(String),
+    /// The delivery service returned an unexpected response.
+    #[error("Unexpected response from the delivery service.")]
+    UnexpectedResponse,
+    /// The group is not known to this client.
+    #[error("Unknown group {0}.")]
+    UnknownGroup(String),
+    /// The message received from the delivery service can't be handled.
+    #[error("Unexpected message from the delivery service.")]
+    UnexpectedMessage,
+    /// See [`io::Error`] for more details.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// See [`serde_json::Error`] for more details.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// See [`tls_codec::Error`] for more details.
+    #[error(transparent)]
+    Codec(#[from] tls_codec::Error),
+    /// See [`CryptoError`] for more details.
+    #[error(transparent)]
+    Crypto(#[from] CryptoError),
+    /// See [`CredentialError`] for more details.
+    #[error(transparent)]
+    Credential(#[from] CredentialError),
+    /// See [`MemoryKeyStoreError`] for more details.
+    #[error(transparent)]
+    KeyStore(#[from] MemoryKeyStoreError),
+    /// See [`KeyPackageNewError`] for more details.
+    #[error(transparent)]
+    KeyPackageNew(#[from] KeyPackageNewError<MemoryKeyStoreError>),
+    /// See [`KeyPackageVerifyError`] for more details.
+    #[error(transparent)]
+    KeyPackageVerify(#[from] KeyPackageVerifyError),
+    /// See [`NewGroupError`] for more details.
+    #[error(transparent)]
+    NewGroup(#[from] NewGroupError<MemoryKeyStoreError>),
+    /// See [`AddMembersError`] for more details.
+    #[error(transparent)]
+    AddMembers(#[from] AddMembersError<MemoryKeyStoreError>),
+    /// See [`MergePendingCommitError`] for more details.
+    #[error(transparent)]
+    MergePendingCommit(#[from] MergePendingCommitError<MemoryKeyStoreError>),
+    /// See [`MergeCommitError`] for more details.
+    #[error(transparent)]
+    MergeCommit(#[from] MergeCommitError<MemoryKeyStoreError>),
+    /// See [`CreateMessageError`] for more details.
+    #[error(transparent)]
+    CreateMessage(#[from] CreateMessageError<MemoryKeyStoreError>),
+    /// See [`ProcessMessageError`] for more details.
+    #[error(transparent)]
+    ProcessMessage(#[from] ProcessMessageError<MemoryKeyStoreError>),
+    /// See [`WelcomeError`] for more details.
+    #[error(transparent)]
+    Welcome(#[from] WelcomeError<MemoryKeyStoreError>),
+    /// See [`ExportGroupInfoError`] for more details.
+    #[error(transparent)]
+    ExportGroupInfo(#[from] ExportGroupInfoError),
+    /// See [`ExternalCommitError`] for more details.
+    #[error(transparent)]
+    ExternalCommit(#[from] ExternalCommitError<MemoryKeyStoreError>),
+}
+
+/// A connection to the [`DeliveryService`].
+pub trait Connection {
+    /// Sends the `request` to the delivery service and returns its response.
+    fn request(&mut self, request: Request) -> Result<Response, ClientError>;
+}
+
+/// An in-process connection.
+impl Connection for DeliveryService {
+    fn request(&mut self, request: Request) -> Result<Response, ClientError> {
+        Ok(self.handle(request))
+    }
+}
+
+/// A connection to a delivery service served with [`crate::ds::serve()`].
+#[derive(Debug)]
+pub struct TcpConnection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl TcpConnection {
+    /// Connects to the delivery service at `addr`.
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let writer = TcpStream::connect(addr)?;
+        let reader = BufReader::new(writer.try_clone()?);
+        Ok(Self { reader, writer })
+    }
+}
+
+impl Connection for TcpConnection {
+    fn request(&mut self, request: Request) -> Result<Response, ClientError> {
+        serde_json::to_writer(&mut self.writer, &request)?;
+        self.writer.write_all(b"\n")?;
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        Ok(serde_json::from_str(&line)?)
+    }
+}
+
+/// Something that happened while receiving messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// The client was added to the group with the given name.
+    Joined { group: String },
+    /// A new epoch of the group was reached by merging a commit.
+    NewEpoch { group: String, epoch: u64 },
+    /// An application message was received in a group.
+    Message {
+        group: String,
+        sender: String,
+        text: String,
+    },
+}
+
+/// An example MLS client.
+///
+/// Groups are identified by a name, which is used as the group ID.
+#[derive(Debug)]
+pub struct Client<C: Connection> {
+    identity: String,
+    backend: OpenMlsRustCrypto,
+    signer: SignatureKeyPair,
+    credential_with_key: CredentialWithKey,
+    group_config: MlsGroupConfig,
+    groups: HashMap<String, MlsGroup>,
+    connection: C,
+}
+
+impl<C: Connection> Client<C> {
+    /// Creates a new client with a fresh basic credential for the `identity`.
+    pub fn new(identity: impl Into<String>, connection: C) -> Result<Self, ClientError> {
+        let identity = identity.into();
+        let backend = OpenMlsRustCrypto::default();
+        let credential = Credential::new(identity.clone().into_bytes(), CredentialType::Basic)?;
+        let signer = SignatureKeyPair::new(CIPHERSUITE.signature_algorithm())?;
+        signer.store(backend.key_store())?;
+        let credential_with_key = CredentialWithKey {
+            credential,
+            signature_key: signer.to_public_vec().into(),
+        };
+        // Members always ship the ratchet tree in Welcome and group info
+        // messages, so that nobody has to fetch it separately.
+        let group_config = MlsGroupConfig::builder()
+            .use_ratchet_tree_extension(true)
+            .crypto_config(CryptoConfig::with_default_version(CIPHERSUITE))
+            .build();
+
+        Ok(Self {
+            identity,
+            backend,
+            signer,
+            credential_with_key,
+            group_config,
+            groups: HashMap::new(),
+            connection,
+        })
+    }
+
+    /// Returns the identity of the client.
+    pub fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    /// Returns the group with the given name, if the client is a member.
+    pub fn group(&self, group: &str) -> Option<&MlsGroup> {
+        self.groups.get(group)
+    }
+
+    /// Returns the identities of the members of the group.
+    pub fn members(&self, group: &str) -> Result<Vec<String>, ClientError> {
+        Ok(member_identities(self.group_ref(group)?))
+    }
+
+    /// Creates a fresh key package and publishes it on the delivery service,
+    /// so that other clients can add this client to groups.
+    pub fn publish_key_package(&mut self) -> Result<(), ClientError> {
+        let key_package = KeyPackage::builder().build(
+            CryptoConfig::with_default_version(CIPHERSUITE),
+            &self.backend,
+            &self.signer,
+            self.credential_with_key.clone(),
+        )?;
+        self.expect_ok(Request::PublishKeyPackage {
+            identity: self.identity.clone(),
+            key_package: key_package.tls_serialize_detached()?,
+        })
+    }
+
+    /// Creates a new group with the given name and this client as its only
+    /// member.
+    pub fn create_group(&mut self, group: &str) -> Result<(), ClientError> {
+        let mls_group = MlsGroup::new_with_group_id(
+            &self.backend,
+            &self.signer,
+            &self.group_config,
+            GroupId::from_slice(group.as_bytes()),
+            self.credential_with_key.clone(),
+        )?;
+        self.groups.insert(group.to_owned(), mls_group);
+        Ok(())
+    }
+
+    /// Adds the client `identity` to the group with one of the key packages
+    /// it published.
+    ///
+    /// The commit is sent to the existing members and the Welcome to the new
+    /// member.
+    pub fn add_member(&mut self, group: &str, identity: &str) -> Result<(), ClientError> {
+        let key_package = match self.connection.request(Request::FetchKeyPackage {
+            identity: identity.to_owned(),
+        })? {
+            Response::KeyPackage(bytes) => {
+                KeyPackageIn::tls_deserialize_exact(bytes.as_slice())?
+                    .validate(self.backend.crypto(), ProtocolVersion::Mls10)?
+            }
+            response => return Err(unexpected(response)),
+        };
+
+        let recipients = self.other_members(group)?;
+        let mls_group = group_mut(&mut self.groups, group)?;
+        let (commit, welcome, _group_info) =
+            mls_group.add_members(&self.backend, &self.signer, &[key_package])?;
+        // Merge only after the DS accepted the commit. Otherwise the group
+        // would advance to an epoch the other members never see.
+        self.send(recipients, &commit)?;
+        group_mut(&mut self.groups, group)?.merge_pending_commit(&self.backend)?;
+        self.send(vec![identity.to_owned()], &welcome)
+    }
+
+    /// Sends an application message to all other members of the group.
+    pub fn send_message(&mut self, group: &str, text: &str) -> Result<(), ClientError> {
+        let recipients = self.other_members(group)?;
+        let mls_group = group_mut(&mut self.groups, group)?;
+        let message = mls_group.create_message(&self.backend, &self.signer, text.as_bytes())?;
+        self.send(recipients, &message)
+    }
+
+    /// Publishes a group info of the current epoch of the group, including
+    /// the ratchet tree, so that other clients can join with an external
+    /// commit.
+    pub fn publish_group_info(&mut self, group: &str) -> Result<(), ClientError> {
+        let group_info =
+            self.group_ref(group)?
+                .export_group_info(&self.backend, &self.signer, true)?;
+        self.expect_ok(Request::PublishGroupInfo {
+            group_id: group.as_bytes().to_vec(),
+            group_info: group_info.tls_serialize_detached()?,
+        })
+    }
+
+    /// Joins the group with an external commit, using the group info
+    /// published on the delivery service. The commit is sent to all existing
+    /// members.
+    pub fn join_by_external_commit(&mut self, group: &str) -> Result<(), ClientError> {
+        let verifiable_group_info = match self.connection.request(Request::FetchGroupInfo {
+            group_id: group.as_bytes().to_vec(),
+        })? {
+            Response::GroupInfo(bytes) => {
+                match MlsMessageIn::tls_deserialize_exact(bytes.as_slice())?.extract() {
+                    MlsMessageInBody::GroupInfo(group_info) => group_info,
+                    _ => return Err(ClientError::UnexpectedMessage),
+                }
+            }
+            response => return Err(unexpected(response)),
+        };
+
+        let (mut mls_group, commit, _group_info) = MlsGroup::join_by_external_commit(
+            &self.backend,
+            &self.signer,
+            None,
+            verifiable_group_info,
+            &self.group_config,
+            &[],
+            self.credential_with_key.clone(),
+        )?;
+        let recipients = member_identities(&mls_group)
+            .into_iter()
+            .filter(|member| member != &self.identity)
+            .collect();
+        self.send(recipients, &commit)?;
+        mls_group.merge_pending_commit(&self.backend)?;
+        self.groups.insert(group.to_owned(), mls_group);
+        Ok(())
+    }
+
+    /// Fetches and processes all messages queued for this client on the
+    /// delivery service.
+    ///
+    /// Commits are merged right away and proposals are stored as pending.
+    pub fn receive(&mut self) -> Result<Vec<Event>, ClientError> {
+        let messages = match self.connection.request(Request::Receive {
+            identity: self.identity.clone(),
+        })? {
+            Response::Messages(messages) => messages,
+            response => return Err(unexpected(response)),
+        };
+
+        let mut events = Vec::new();
+        for bytes in messages {
+            let message = MlsMessageIn::tls_deserialize_exact(bytes.as_slice())?;
+            let protocol_message: ProtocolMessage = match message.extract() {
+                MlsMessageInBody::Welcome(welcome) => {
+                    let mls_group = MlsGroup::new_from_welcome(
+                        &self.backend,
+                        &self.group_config,
+                        welcome,
+                        None,
+                    )?;
+                    let group = group_name(mls_group.group_id());
+                    self.groups.insert(group.clone(), mls_group);
+                    events.push(Event::Joined { group });
+                    continue;
+                }
+                MlsMessageInBody::PrivateMessage(message) => message.into(),
+                MlsMessageInBody::PublicMessage(message) => message.into(),
+                _ => return Err(ClientError::UnexpectedMessage),
+            };
+
+            let group = group_name(protocol_message.group_id());
+            let mls_group = group_mut(&mut self.groups, &group)?;
+            let processed_message = mls_group.process_message(&self.backend, protocol_message)?;
+            let sender =
+                String::from_utf8_lossy(processed_message.credential().identity()).into_owned();
+            match processed_message.into_content() {
+                ProcessedMessageContent::ApplicationMessage(application_message) => {
+                    events.push(Event::Message {
+                        group,
+                        sender,
+                        text: String::from_utf8_lossy(&application_message.into_bytes())
+                            .into_owned(),
+                    })
+                }
+                ProcessedMessageContent::ProposalMessage(proposal) => {
+                    mls_group.store_pending_proposal(&self.backend, *proposal)?;
+                }
+                ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+                    mls_group.merge_staged_commit(&self.backend, *staged_commit)?;
+                    let epoch = mls_group.epoch().as_u64();
+                    events.push(Event::NewEpoch { group, epoch });
+                }
+                ProcessedMessageContent::ExternalJoinProposalMessage(_) => {}
+            }
+        }
+        Ok(events)
+    }
+
+    fn group_ref(&self, group: &str) -> Result<&MlsGroup, ClientError> {
+        self.groups
+            .get(group)
+            .ok_or_else(|| ClientError::UnknownGroup(group.to_owned()))
+    }
+
+    /// Returns the identities of all members of the group except this client.
+    fn other_members(&self, group: &str) -> Result<Vec<String>, ClientError> {
+        Ok(self
+            .members(group)?
+            .into_iter()
+            .filter(|member| member != &self.identity)
+            .collect())
+    }
+
+    fn send(
+        &mut self,
+        recipients: Vec<String>,
+        message: &MlsMessageOut,
+    ) -> Result<(), ClientError> {
+        if recipients.is_empty() {
+            return Ok(());
+        }
+        self.expect_ok(Request::Send {
+            recipients,
+            message: message.tls_serialize_detached()?,
+        })
+    }
+
+    fn expect_ok(&mut self, request: Request) -> Result<(), ClientError> {
+        match self.connection.request(request)? {
+            Response::Ok => Ok(()),
+            response => Err(unexpected(response)),
+        }
+    }
+}
+
+// Takes the map rather than the client, so that the backend and signer can be
+// borrowed alongside the group.
+fn group_mut<'a>(
+    groups: &'a mut HashMap<String, MlsGroup>,
+    group: &str,
+) -> Result<&'a mut MlsGroup, ClientError> {
+    groups
+        .get_mut(group)
+        .ok_or_else(|| ClientError::UnknownGroup(group.to_owned()))
+}
+
+fn member_identities(mls_group: &MlsGroup) -> Vec<String> {
+    mls_group
+        .members()
+        .map(|member| String::from_utf8_lossy(member.credential.identity()).into_owned())
+        .collect()
+}
+
+fn group_name(group_id: &GroupId) -> String {
+    String::from_utf8_lossy(group_id.as_slice()).into_owned()
+}
+
+fn unexpected(response: Response) -> ClientError {
+    match response {
+        Response::Error(e) => ClientError::DeliveryService(e),
+        _ => ClientError::UnexpectedResponse,
+    }
+}
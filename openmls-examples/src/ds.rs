@@ -0,0 +1,172 @@
+//! # A minimal in-memory delivery service
+//!
+//! The delivery service (DS) stores key packages and group infos and queues
+//! messages for clients. Like the DS in `delivery-service/ds`, it does not
+//! know about groups: clients send the list of recipients along with each
+//! message.
+//!
+//! All MLS objects are passed around TLS-serialized, so that the DS never
+//! needs to parse them. Over TCP, each [`Request`] and [`Response`] is a
+//! single line of JSON.
+//!
+//! **⚠️ There is no authentication or persistence. Don't use this outside of
+//! examples and tests.**
+
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// A request of a client to the delivery service.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Request {
+    /// Publish a TLS-serialized key package of the client `identity`.
+    PublishKeyPackage {
+        identity: String,
+        key_package: Vec<u8>,
+    },
+    /// Fetch (and consume) a key package of the client `identity`.
+    FetchKeyPackage { identity: String },
+    /// Publish the TLS-serialized group info message of the group `group_id`,
+    /// replacing any group info published before.
+    PublishGroupInfo {
+        group_id: Vec<u8>,
+        group_info: Vec<u8>,
+    },
+    /// Fetch the latest group info message of the group `group_id`.
+    FetchGroupInfo { group_id: Vec<u8> },
+    /// Queue the TLS-serialized `message` for all `recipients`.
+    Send {
+        recipients: Vec<String>,
+        message: Vec<u8>,
+    },
+    /// Fetch (and consume) all messages queued for the client `identity`.
+    Receive { identity: String },
+}
+
+/// A response of the delivery service to a [`Request`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Response {
+    /// The request was handled and there is nothing to return.
+    Ok,
+    /// A TLS-serialized key package.
+    KeyPackage(Vec<u8>),
+    /// A TLS-serialized group info message.
+    GroupInfo(Vec<u8>),
+    /// The TLS-serialized messages queued for a client, oldest first.
+    Messages(Vec<Vec<u8>>),
+    /// The request could not be handled.
+    Error(String),
+}
+
+#[derive(Debug, Default)]
+struct State {
+    key_packages: HashMap<String, VecDeque<Vec<u8>>>,
+    group_infos: HashMap<Vec<u8>, Vec<u8>>,
+    queues: HashMap<String, VecDeque<Vec<u8>>>,
+}
+
+/// The in-memory delivery service.
+///
+/// The delivery service can be shared between threads. Use [`serve()`] to
+/// make it reachable over TCP.
+#[derive(Debug, Default, Clone)]
+pub struct DeliveryService {
+    state: Arc<Mutex<State>>,
+}
+
+impl DeliveryService {
+    /// Creates a new, empty delivery service.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handles a single [`Request`].
+    pub fn handle(&self, request: Request) -> Response {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        match request {
+            Request::PublishKeyPackage {
+                identity,
+                key_package,
+            } => {
+                state
+                    .key_packages
+                    .entry(identity)
+                    .or_default()
+                    .push_back(key_package);
+                Response::Ok
+            }
+            Request::FetchKeyPackage { identity } => match state
+                .key_packages
+                .get_mut(&identity)
+                .and_then(VecDeque::pop_front)
+            {
+                Some(key_package) => Response::KeyPackage(key_package),
+                None => Response::Error(format!("No key package for {identity}")),
+            },
+            Request::PublishGroupInfo {
+                group_id,
+                group_info,
+            } => {
+                state.group_infos.insert(group_id, group_info);
+                Response::Ok
+            }
+            Request::FetchGroupInfo { group_id } => match state.group_infos.get(&group_id) {
+                Some(group_info) => Response::GroupInfo(group_info.clone()),
+                None => Response::Error("No group info for this group".to_owned()),
+            },
+            Request::Send {
+                recipients,
+                message,
+            } => {
+                for recipient in recipients {
+                    state
+                        .queues
+                        .entry(recipient)
+                        .or_default()
+                        .push_back(message.clone());
+                }
+                Response::Ok
+            }
+            Request::Receive { identity } => Response::Messages(
+                state
+                    .queues
+                    .get_mut(&identity)
+                    .map(|queue| queue.drain(..).collect())
+                    .unwrap_or_default(),
+            ),
+        }
+    }
+}
+
+/// Serves the delivery service on the `listener`, handling every connection
+/// in its own thread. This only returns if accepting a connection fails.
+pub fn serve(ds: DeliveryService, listener: TcpListener) -> io::Result<()> {
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let ds = ds.clone();
+        thread::spawn(move || {
+            // A client that misbehaves only loses its own connection.
+            let _ = handle_connection(&ds, stream);
+        });
+    }
+    Ok(())
+}
+
+fn handle_connection(ds: &DeliveryService, stream: TcpStream) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let response = match serde_json::from_str(&line?) {
+            Ok(request) => ds.handle(request),
+            Err(e) => Response::Error(format!("Malformed request: {e}")),
+        };
+        serde_json::to_writer(&mut writer, &response)?;
+        writer.write_all(b"\n")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,17 @@
+//! # OpenMLS examples
+//!
+//! This crate shows how the OpenMLS API is meant to be composed into an
+//! application. It consists of
+//! * a minimal in-memory delivery service ([`ds`]), runnable as the
+//!   `examples-ds` binary, and
+//! * an example client ([`client`]), runnable as the `examples-client`
+//!   binary, that publishes key packages, creates groups, adds members,
+//!   exchanges messages and joins groups with external commits.
+//!
+//! The integration tests of this crate run these flows end to end, so that
+//! changes to the public API that break them are caught.
+//!
+//! **⚠️ None of this is meant for production use.**
+
+pub mod client;
+pub mod ds;
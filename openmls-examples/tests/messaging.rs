@@ -0,0 +1,138 @@
+use std::{
+    io::{BufRead, BufReader},
+    net::TcpListener,
+    process::{Command, Stdio},
+    thread,
+};
+
+use openmls_examples::{
+    client::{Client, ClientError, Connection, Event, TcpConnection},
+    ds::{serve, DeliveryService},
+};
+
+/// Runs the full flow of the example clients: Alice creates a group and adds
+/// Bob with his published key package, both exchange messages, and Charlie
+/// joins with an external commit.
+fn run_scenario<C: Connection>(
+    mut alice: Client<C>,
+    mut bob: Client<C>,
+    mut charlie: Client<C>,
+) -> Result<(), ClientError> {
+    bob.publish_key_package()?;
+    alice.create_group("lunch")?;
+    alice.add_member("lunch", "Bob")?;
+
+    assert_eq!(
+        bob.receive()?,
+        vec![Event::Joined {
+            group: "lunch".to_owned()
+        }]
+    );
+    assert_eq!(bob.members("lunch")?, vec!["Alice", "Bob"]);
+
+    alice.send_message("lunch", "Pizza?")?;
+    bob.send_message("lunch", "Sure!")?;
+    assert_eq!(
+        bob.receive()?,
+        vec![Event::Message {
+            group: "lunch".to_owned(),
+            sender: "Alice".to_owned(),
+            text: "Pizza?".to_owned(),
+        }]
+    );
+    assert_eq!(
+        alice.receive()?,
+        vec![Event::Message {
+            group: "lunch".to_owned(),
+            sender: "Bob".to_owned(),
+            text: "Sure!".to_owned(),
+        }]
+    );
+
+    // Charlie was not added by anyone and joins on his own
+    bob.publish_group_info("lunch")?;
+    charlie.join_by_external_commit("lunch")?;
+    for client in [&mut alice, &mut bob] {
+        assert_eq!(
+            client.receive()?,
+            vec![Event::NewEpoch {
+                group: "lunch".to_owned(),
+                epoch: 2
+            }]
+        );
+    }
+    assert_eq!(charlie.members("lunch")?, vec!["Alice", "Bob", "Charlie"]);
+
+    charlie.send_message("lunch", "Count me in")?;
+    for client in [&mut alice, &mut bob] {
+        assert_eq!(
+            client.receive()?,
+            vec![Event::Message {
+                group: "lunch".to_owned(),
+                sender: "Charlie".to_owned(),
+                text: "Count me in".to_owned(),
+            }]
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn in_process() {
+    let ds = DeliveryService::new();
+    run_scenario(
+        Client::new("Alice", ds.clone()).unwrap(),
+        Client::new("Bob", ds.clone()).unwrap(),
+        Client::new("Charlie", ds).unwrap(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn over_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || serve(DeliveryService::new(), listener));
+
+    let connect = || TcpConnection::connect(addr).unwrap();
+    run_scenario(
+        Client::new("Alice", connect()).unwrap(),
+        Client::new("Bob", connect()).unwrap(),
+        Client::new("Charlie", connect()).unwrap(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn ds_binary() {
+    let mut ds = Command::new(env!("CARGO_BIN_EXE_examples-ds"))
+        .arg("127.0.0.1:0")
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut line = String::new();
+    BufReader::new(ds.stdout.take().unwrap())
+        .read_line(&mut line)
+        .unwrap();
+    let addr = line.trim().rsplit(' ').next().unwrap().to_owned();
+
+    let connect = || TcpConnection::connect(&addr).unwrap();
+    let result = run_scenario(
+        Client::new("Alice", connect()).unwrap(),
+        Client::new("Bob", connect()).unwrap(),
+        Client::new("Charlie", connect()).unwrap(),
+    );
+    ds.kill().unwrap();
+    result.unwrap();
+}
+
+#[test]
+fn missing_key_package() {
+    let ds = DeliveryService::new();
+    let mut alice = Client::new("Alice", ds).unwrap();
+    alice.create_group("lunch").unwrap();
+
+    let err = alice.add_member("lunch", "Bob").unwrap_err();
+    assert!(matches!(err, ClientError::DeliveryService(_)));
+    assert_eq!(alice.members("lunch").unwrap(), vec!["Alice"]);
+}
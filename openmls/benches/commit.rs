@@ -0,0 +1,109 @@
+//! Benchmarks of the creation of commits with an update path in large groups,
+//! on a single thread and on rayon's global thread pool.
+//!
+//! The first commit after adding many members in a single commit has to
+//! encrypt the path secrets to all other members, because the parent nodes
+//! on the path of the committer are blank. Without the `parallel` feature,
+//! both variants run on a single thread.
+//!
+//! The "single thread" variant is the baseline for the "thread pool" variant,
+//! e.g. `cargo bench --bench commit` reports both for every group size.
+
+#[macro_use]
+extern crate criterion;
+extern crate openmls;
+
+use criterion::{BenchmarkId, Criterion};
+use openmls::prelude::{config::CryptoConfig, *};
+use openmls_basic_credential::SignatureKeyPair;
+use openmls_rust_crypto::OpenMlsRustCrypto;
+use openmls_traits::OpenMlsCryptoProvider;
+
+/// The numbers of members of the benchmarked groups.
+const MEMBER_COUNTS: [usize; 3] = [128, 1024, 4096];
+
+fn key_package(
+    ciphersuite: Ciphersuite,
+    backend: &impl OpenMlsCryptoProvider,
+    identity: &[u8],
+) -> (KeyPackage, CredentialWithKey, SignatureKeyPair) {
+    let credential = Credential::new(identity.to_vec(), CredentialType::Basic).unwrap();
+    let signer = SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
+    let credential_with_key = CredentialWithKey {
+        credential,
+        signature_key: signer.to_public_vec().into(),
+    };
+    let key_package = KeyPackage::builder()
+        .build(
+            CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &signer,
+            credential_with_key.clone(),
+        )
+        .expect("An unexpected error occurred.");
+
+    (key_package, credential_with_key, signer)
+}
+
+/// Creates a group of the given number of `members` that were all added by
+/// the group's creator in a single commit.
+fn setup(
+    ciphersuite: Ciphersuite,
+    backend: &impl OpenMlsCryptoProvider,
+    members: usize,
+) -> (MlsGroup, SignatureKeyPair) {
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+    let (_key_package, credential_with_key, signer) = key_package(ciphersuite, backend, b"Alice");
+    let mut group = MlsGroup::new(backend, &signer, &mls_group_config, credential_with_key)
+        .expect("An unexpected error occurred.");
+
+    let key_packages = (1..members)
+        .map(|i| key_package(ciphersuite, backend, format!("Member {i}").as_bytes()).0)
+        .collect::<Vec<_>>();
+    group
+        .add_members(backend, &signer, &key_packages)
+        .expect("An unexpected error occurred.");
+    group
+        .merge_pending_commit(backend)
+        .expect("An unexpected error occurred.");
+
+    (group, signer)
+}
+
+fn criterion_commit(c: &mut Criterion, backend: &impl OpenMlsCryptoProvider) {
+    let ciphersuite = Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
+    let single_thread = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .expect("An unexpected error occurred.");
+    let mut benchmark_group = c.benchmark_group("MlsGroup self update");
+    benchmark_group.sample_size(10);
+    for members in MEMBER_COUNTS {
+        let (mut group, signer) = setup(ciphersuite, backend, members);
+        let mut self_update = || {
+            group
+                .self_update(backend, &signer)
+                .expect("An unexpected error occurred.");
+            group
+                .clear_pending_commit(backend)
+                .expect("An unexpected error occurred.");
+        };
+        benchmark_group.bench_function(BenchmarkId::new("single thread", members), |b| {
+            b.iter(|| single_thread.install(&mut self_update))
+        });
+        benchmark_group.bench_function(BenchmarkId::new("thread pool", members), |b| {
+            b.iter(&mut self_update)
+        });
+    }
+    benchmark_group.finish();
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let backend = &OpenMlsRustCrypto::default();
+    criterion_commit(c, backend);
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,113 @@
+//! Micro-benchmarks of the validation of commits by a [`PublicGroup`], e.g.
+//! on a Delivery Service, depending on the number of proposals they cover.
+
+#[macro_use]
+extern crate criterion;
+extern crate openmls;
+
+use criterion::{BatchSize, BenchmarkId, Criterion};
+use openmls::prelude::{config::CryptoConfig, *};
+use openmls_basic_credential::SignatureKeyPair;
+use openmls_rust_crypto::OpenMlsRustCrypto;
+use openmls_traits::OpenMlsCryptoProvider;
+
+/// The numbers of add proposals covered by the benchmarked commits.
+const PROPOSAL_COUNTS: [usize; 3] = [1, 10, 50];
+
+fn key_package(
+    ciphersuite: Ciphersuite,
+    backend: &impl OpenMlsCryptoProvider,
+    identity: &[u8],
+) -> (KeyPackage, CredentialWithKey, SignatureKeyPair) {
+    let credential = Credential::new(identity.to_vec(), CredentialType::Basic).unwrap();
+    let signer = SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
+    let credential_with_key = CredentialWithKey {
+        credential,
+        signature_key: signer.to_public_vec().into(),
+    };
+    let key_package = KeyPackage::builder()
+        .build(
+            CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &signer,
+            credential_with_key.clone(),
+        )
+        .expect("An unexpected error occurred.");
+
+    (key_package, credential_with_key, signer)
+}
+
+/// Creates a public group of a fresh group and a commit of the group's
+/// creator that adds `proposals` members.
+fn setup(
+    ciphersuite: Ciphersuite,
+    backend: &impl OpenMlsCryptoProvider,
+    proposals: usize,
+) -> (PublicGroup, ProtocolMessage) {
+    let mls_group_config = MlsGroupConfig::builder()
+        .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+    let (_key_package, credential_with_key, signer) = key_package(ciphersuite, backend, b"Alice");
+    let mut group = MlsGroup::new(backend, &signer, &mls_group_config, credential_with_key)
+        .expect("An unexpected error occurred.");
+
+    let verifiable_group_info = group
+        .export_group_info(backend, &signer, false)
+        .expect("An unexpected error occurred.")
+        .into_verifiable_group_info()
+        .expect("Unexpected message type.");
+    let (public_group, _group_info) = PublicGroup::from_external(
+        backend,
+        group.export_ratchet_tree().into(),
+        verifiable_group_info,
+        ProposalStore::new(),
+    )
+    .expect("An unexpected error occurred.");
+
+    let key_packages = (0..proposals)
+        .map(|i| key_package(ciphersuite, backend, format!("Member {i}").as_bytes()).0)
+        .collect::<Vec<_>>();
+    let (commit, _welcome, _group_info) = group
+        .add_members(backend, &signer, &key_packages)
+        .expect("An unexpected error occurred.");
+
+    (
+        public_group,
+        commit
+            .into_protocol_message()
+            .expect("Unexpected message type."),
+    )
+}
+
+fn criterion_validation(c: &mut Criterion, backend: &impl OpenMlsCryptoProvider) {
+    let ciphersuite = Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
+    let mut group = c.benchmark_group("PublicGroup process commit");
+    for proposals in PROPOSAL_COUNTS {
+        let (public_group, commit) = setup(ciphersuite, backend, proposals);
+        group.bench_with_input(
+            BenchmarkId::new("add proposals", proposals),
+            &commit,
+            |b, commit| {
+                b.iter_batched(
+                    || commit.clone(),
+                    |commit| {
+                        public_group
+                            .process_message(backend, commit)
+                            .expect("An unexpected error occurred.")
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let backend = &OpenMlsRustCrypto::default();
+    criterion_validation(c, backend);
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
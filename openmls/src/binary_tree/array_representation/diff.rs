@@ -7,6 +7,11 @@
 //! the them. Instead, the changes can be applied to the diff and the results
 //! examined before merging the given diff back into the tree (or not).
 //!
+//! Both only hold the nodes that were changed. Nodes are copied from the tree
+//! when they are first changed through the diff, so that creating, staging and
+//! merging a diff is proportional to the number of changed nodes and not to the
+//! size of the tree.
+//!
 //! # Don't Panic!
 //!
 //! Functions in this module should never panic. However, if there is a bug in
@@ -17,7 +22,7 @@
 //! [`LibraryError`](ABinaryTreeDiffError::LibraryError).
 
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, fmt::Debug};
+use std::{collections::BTreeMap, fmt::Debug, sync::Arc};
 use thiserror::Error;
 
 use crate::error::LibraryError;
@@ -26,8 +31,8 @@ use super::{
     sorted_iter::sorted_iter,
     tree::{ABinaryTree, ABinaryTreeError},
     treemath::{
-        copath, direct_path, left, lowest_common_ancestor, right, root, LeafNodeIndex,
-        ParentNodeIndex, TreeNodeIndex, TreeSize, MAX_TREE_SIZE, MIN_TREE_SIZE,
+        copath, direct_path, is_node_in_tree, left, lowest_common_ancestor, right, root, subtree,
+        LeafNodeIndex, ParentNodeIndex, TreeNodeIndex, TreeSize, MAX_TREE_SIZE, MIN_TREE_SIZE,
     },
 };
 
@@ -41,15 +46,19 @@ use super::{
 /// original content, it can't provide the same information as the [`AbDiff`] it
 /// was created from. However, the lack of the internal reference means that its
 /// lifetime is not tied to that of the original tree.
-#[derive(Debug, Serialize, Deserialize)]
-pub(crate) struct StagedAbDiff<L: Clone + Debug + Default, P: Clone + Debug + Default> {
-    leaf_diff: BTreeMap<LeafNodeIndex, L>,
-    parent_diff: BTreeMap<ParentNodeIndex, P>,
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
+pub(crate) struct StagedAbDiff<
+    L: Clone + Debug + Default + PartialEq,
+    P: Clone + Debug + Default + PartialEq,
+> {
+    leaf_diff: BTreeMap<LeafNodeIndex, Arc<L>>,
+    parent_diff: BTreeMap<ParentNodeIndex, Arc<P>>,
     size: TreeSize,
 }
 
-impl<'a, L: Clone + Debug + Default, P: Clone + Debug + Default> From<AbDiff<'a, L, P>>
-    for StagedAbDiff<L, P>
+impl<'a, L: Clone + Debug + Default + PartialEq, P: Clone + Debug + Default + PartialEq>
+    From<AbDiff<'a, L, P>> for StagedAbDiff<L, P>
 {
     fn from(diff: AbDiff<'a, L, P>) -> Self {
         StagedAbDiff {
@@ -60,9 +69,17 @@ impl<'a, L: Clone + Debug + Default, P: Clone + Debug + Default> From<AbDiff<'a,
     }
 }
 
-impl<L: Clone + Debug + Default, P: Clone + Debug + Default> StagedAbDiff<L, P> {
+impl<L: Clone + Debug + Default + PartialEq, P: Clone + Debug + Default + PartialEq>
+    StagedAbDiff<L, P>
+{
     /// Return the leaf and parent diffs as a tuple.
-    pub(super) fn into_diffs(self) -> (BTreeMap<LeafNodeIndex, L>, BTreeMap<ParentNodeIndex, P>) {
+    #[allow(clippy::type_complexity)]
+    pub(super) fn into_diffs(
+        self,
+    ) -> (
+        BTreeMap<LeafNodeIndex, Arc<L>>,
+        BTreeMap<ParentNodeIndex, Arc<P>>,
+    ) {
         (self.leaf_diff, self.parent_diff)
     }
 
@@ -70,6 +87,13 @@ impl<L: Clone + Debug + Default, P: Clone + Debug + Default> StagedAbDiff<L, P>
     pub(super) fn tree_size(&self) -> TreeSize {
         self.size
     }
+
+    /// Returns an iterator over the leaves that are changed by the diff.
+    pub(crate) fn changed_leaves(&self) -> impl Iterator<Item = (LeafNodeIndex, &L)> {
+        self.leaf_diff
+            .iter()
+            .map(|(index, leaf)| (*index, leaf.as_ref()))
+    }
 }
 
 /// The [`AbDiff`] represents a set of differences (i.e. a "Diff") for an
@@ -77,17 +101,20 @@ impl<L: Clone + Debug + Default, P: Clone + Debug + Default> StagedAbDiff<L, P>
 /// accessed mutably or immutably. Any changes are saved by the [`AbDiff`] applied
 /// to the original [`ABinaryTree`] instance by converting it to a [`StagedAbDiff`]
 /// and subsequently merging it.
-pub(crate) struct AbDiff<'a, L: Clone + Debug + Default, P: Clone + Debug + Default> {
+pub(crate) struct AbDiff<
+    'a,
+    L: Clone + Debug + Default + PartialEq,
+    P: Clone + Debug + Default + PartialEq,
+> {
     original_tree: &'a ABinaryTree<L, P>,
-    leaf_diff: BTreeMap<LeafNodeIndex, L>,
-    parent_diff: BTreeMap<ParentNodeIndex, P>,
+    leaf_diff: BTreeMap<LeafNodeIndex, Arc<L>>,
+    parent_diff: BTreeMap<ParentNodeIndex, Arc<P>>,
     size: TreeSize,
     default_leaf: L,
-    default_parent: P,
 }
 
-impl<'a, L: Clone + Debug + Default, P: Clone + Debug + Default> From<&'a ABinaryTree<L, P>>
-    for AbDiff<'a, L, P>
+impl<'a, L: Clone + Debug + Default + PartialEq, P: Clone + Debug + Default + PartialEq>
+    From<&'a ABinaryTree<L, P>> for AbDiff<'a, L, P>
 {
     fn from(tree: &'a ABinaryTree<L, P>) -> AbDiff<'a, L, P> {
         AbDiff {
@@ -96,12 +123,13 @@ impl<'a, L: Clone + Debug + Default, P: Clone + Debug + Default> From<&'a ABinar
             parent_diff: BTreeMap::new(),
             size: tree.tree_size(),
             default_leaf: L::default(),
-            default_parent: P::default(),
         }
     }
 }
 
-impl<'a, L: Clone + Debug + Default, P: Clone + Debug + Default> AbDiff<'a, L, P> {
+impl<'a, L: Clone + Debug + Default + PartialEq, P: Clone + Debug + Default + PartialEq>
+    AbDiff<'a, L, P>
+{
     // Functions handling interactions with leaves.
     ///////////////////////////////////////////////
 
@@ -141,12 +169,18 @@ impl<'a, L: Clone + Debug + Default, P: Clone + Debug + Default> AbDiff<'a, L, P
     /// content.
     pub(crate) fn replace_leaf(&mut self, leaf_index: LeafNodeIndex, new_leaf: L) {
         debug_assert!(leaf_index.u32() < self.leaf_count());
-        self.leaf_diff.insert(leaf_index, new_leaf);
+        self.leaf_diff.insert(leaf_index, Arc::new(new_leaf));
     }
 
     /// Replace the content of the parent node at the given leaf index with new
     /// content.
     pub(crate) fn replace_parent(&mut self, parent_index: ParentNodeIndex, node: P) {
+        self.replace_parent_shared(parent_index, Arc::new(node));
+    }
+
+    /// Replace the content of the parent node at the given leaf index with a
+    /// node that can be shared with other nodes in the diff.
+    fn replace_parent_shared(&mut self, parent_index: ParentNodeIndex, node: Arc<P>) {
         debug_assert!(parent_index.u32() < self.parent_count());
         self.parent_diff.insert(parent_index, node);
     }
@@ -158,7 +192,7 @@ impl<'a, L: Clone + Debug + Default, P: Clone + Debug + Default> AbDiff<'a, L, P
         let diff_leaves = self
             .leaf_diff
             .iter()
-            .map(|(index, leaf)| (*index, leaf))
+            .map(|(index, leaf)| (*index, leaf.as_ref()))
             .peekable();
 
         // Combine the original leaves with the leaves from the diff. Since both
@@ -183,7 +217,7 @@ impl<'a, L: Clone + Debug + Default, P: Clone + Debug + Default> AbDiff<'a, L, P
         let diff_parents = self
             .parent_diff
             .iter()
-            .map(|(index, parent)| (*index, parent))
+            .map(|(index, parent)| (*index, parent.as_ref()))
             .peekable();
 
         // Combine the original parents with the parents from the diff. Since
@@ -213,11 +247,13 @@ impl<'a, L: Clone + Debug + Default, P: Clone + Debug + Default> AbDiff<'a, L, P
         direct_path(leaf_index, self.size())
     }
 
-    /// Sets all nodes in the direct path to a copy of the given node.
+    /// Sets all nodes in the direct path to the given node. The nodes share a
+    /// single copy of the node until one of them is changed.
     pub(crate) fn set_direct_path_to_node(&mut self, leaf_index: LeafNodeIndex, node: &P) {
+        let node = Arc::new(node.clone());
         let direct_path = self.direct_path(leaf_index);
         for node_index in &direct_path {
-            self.replace_parent(*node_index, node.clone());
+            self.replace_parent_shared(*node_index, node.clone());
         }
     }
 
@@ -264,6 +300,18 @@ impl<'a, L: Clone + Debug + Default, P: Clone + Debug + Default> AbDiff<'a, L, P
         }
     }
 
+    /// Returns `true` if the node at `node_index` is part of the original tree
+    /// and neither the node nor any node below it was changed through the
+    /// diff.
+    pub(crate) fn is_subtree_unchanged(&self, node_index: TreeNodeIndex) -> bool {
+        if !is_node_in_tree(node_index, self.original_tree.tree_size()) {
+            return false;
+        }
+        let (leaves, parents) = subtree(node_index);
+        self.leaf_diff.range(leaves).next().is_none()
+            && self.parent_diff.range(parents).next().is_none()
+    }
+
     // Functions pertaining to the whole diff
     /////////////////////////////////////////
 
@@ -312,7 +360,7 @@ impl<'a, L: Clone + Debug + Default, P: Clone + Debug + Default> AbDiff<'a, L, P
     pub(crate) fn leaf(&self, leaf_index: LeafNodeIndex) -> &L {
         // Check if it's in the diff.
         if let Some(node) = self.leaf_diff.get(&leaf_index) {
-            node
+            node.as_ref()
         // If it's not in the diff, it could be that it's outside of the
         // diff, in which case we want to return a blank here, because the
         // diff might have been trimmed in the mean time.
@@ -328,58 +376,42 @@ impl<'a, L: Clone + Debug + Default, P: Clone + Debug + Default> AbDiff<'a, L, P
     pub(crate) fn parent(&self, parent_index: ParentNodeIndex) -> &P {
         // Check if it's in the diff.
         if let Some(node) = self.parent_diff.get(&parent_index) {
-            return node;
+            return node.as_ref();
         }
         // If it isn't in the diff, it must be in the tree.
         self.original_tree.parent_by_index(parent_index)
     }
 
     /// Returns a mutable reference to the leaf node in the diff at index
-    /// `leaf_index`. If the diff doesn't have a node at that index, it clones
-    /// the node to the diff and returns a mutable reference to that node.
+    /// `leaf_index`. If the diff doesn't have its own copy of the node yet, it
+    /// clones the node to the diff and returns a mutable reference to that
+    /// node.
     pub(crate) fn leaf_mut(&mut self, leaf_index: LeafNodeIndex) -> &mut L {
         debug_assert!(leaf_index.u32() < self.leaf_count());
-        // We then check if the node is already in the diff. (Not using `if let
-        // ...` here, because the borrow checker doesn't like that).
-        if self.leaf_diff.contains_key(&leaf_index) {
-            return self
-                .leaf_diff
-                .get_mut(&leaf_index)
-                // We just checked that this index exists, so this must be Some.
-                .unwrap_or(&mut self.default_leaf);
-            // If not, we take a copy from the original tree and put it in the
-            // diff before returning a mutable reference to it.
-        }
-        let tree_node = self.original_tree.leaf_by_index(leaf_index);
-        self.replace_leaf(leaf_index, tree_node.clone());
-        self.leaf_diff
-            .get_mut(&leaf_index)
-            // We just inserted this into the diff, so this should be Some.
-            .unwrap_or(&mut self.default_leaf)
+        // If the node is not in the diff yet, we take it from the original
+        // tree. It is only copied by `make_mut` if it is still shared with the
+        // tree or with other nodes in the diff.
+        let node = self
+            .leaf_diff
+            .entry(leaf_index)
+            .or_insert_with(|| self.original_tree.shared_leaf(leaf_index));
+        Arc::make_mut(node)
     }
 
     /// Returns a mutable reference to the parent node in the diff at index
-    /// `parent_index`. If the diff doesn't have a node at that index, it clones
-    /// the node to the diff and returns a mutable reference to that node.
+    /// `parent_index`. If the diff doesn't have its own copy of the node yet,
+    /// it clones the node to the diff and returns a mutable reference to that
+    /// node.
     pub(crate) fn parent_mut(&mut self, parent_index: ParentNodeIndex) -> &mut P {
         debug_assert!(parent_index.u32() < self.parent_count());
-        // We then check if the node is already in the diff. (Not using `if let
-        // ...` here, because the borrow checker doesn't like that).
-        if self.parent_diff.contains_key(&parent_index) {
-            return self
-                .parent_diff
-                .get_mut(&parent_index)
-                // We just checked that this index exists, so this must be Some.
-                .unwrap_or(&mut self.default_parent);
-            // If not, we take a copy from the original tree and put it in the
-            // diff before returning a mutable reference to it.
-        }
-        let tree_node = self.original_tree.parent_by_index(parent_index);
-        self.replace_parent(parent_index, tree_node.clone());
-        self.parent_diff
-            .get_mut(&parent_index)
-            // We just inserted this into the diff, so this should be Some.
-            .unwrap_or(&mut self.default_parent)
+        // If the node is not in the diff yet, we take it from the original
+        // tree. It is only copied by `make_mut` if it is still shared with the
+        // tree or with other nodes in the diff.
+        let node = self
+            .parent_diff
+            .entry(parent_index)
+            .or_insert_with(|| self.original_tree.shared_parent(parent_index));
+        Arc::make_mut(node)
     }
 
     // Index checking
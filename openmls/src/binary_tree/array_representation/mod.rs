@@ -15,12 +15,13 @@ pub use treemath::LeafNodeIndex;
 
 // Crate
 pub(crate) mod diff;
+pub(crate) mod nodes;
 pub(crate) mod sorted_iter;
 pub(crate) mod tree;
 
 pub(crate) use treemath::{
-    direct_path, is_node_in_tree, left, right, root, ParentNodeIndex, TreeNodeIndex, TreeSize,
-    MIN_TREE_SIZE,
+    direct_path, is_node_in_tree, left, right, root, subtree, ParentNodeIndex, TreeNodeIndex,
+    TreeSize, MIN_TREE_SIZE,
 };
 
 #[cfg(any(feature = "test-utils", test))]
@@ -29,5 +30,5 @@ pub(crate) use treemath::level;
 mod treemath;
 
 // Tests
-#[cfg(any(feature = "test-utils", test))]
+#[cfg(any(feature = "test-vectors", test))]
 pub mod kat_treemath;
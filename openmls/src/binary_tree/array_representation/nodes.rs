@@ -0,0 +1,185 @@
+//! Storage of the leaf or the parent nodes of an
+//! [`ABinaryTree`](super::tree::ABinaryTree).
+//!
+//! # About
+//!
+//! By default, the nodes are stored in a vector with one entry per node. In
+//! large groups, most of the nodes of the tree are blank, so that the vector
+//! is mostly made up of blank nodes. With the `sparse-tree` feature, the nodes
+//! are stored in a [`SparseNodes`] instead, which only holds the nodes that
+//! aren't blank.
+//!
+//! A node is blank if it is equal to the default value of its type. Both
+//! representations are serialized as the full list of nodes, so that
+//! serialized trees can be read independently of the feature.
+
+use std::{fmt::Debug, sync::Arc};
+
+#[cfg(any(feature = "sparse-tree", test))]
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use serde::{Deserialize, Serialize};
+
+/// The representation of the nodes used by the tree.
+#[cfg(not(feature = "sparse-tree"))]
+pub(in crate::binary_tree) type Nodes<T> = DenseNodes<T>;
+
+/// The representation of the nodes used by the tree.
+#[cfg(feature = "sparse-tree")]
+pub(in crate::binary_tree) type Nodes<T> = SparseNodes<T>;
+
+/// Nodes stored in a vector with one entry per node.
+#[cfg(any(not(feature = "sparse-tree"), test))]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub(in crate::binary_tree) struct DenseNodes<T> {
+    nodes: Vec<Arc<T>>,
+}
+
+#[cfg(any(not(feature = "sparse-tree"), test))]
+impl<T: Clone + Debug + Default + PartialEq> DenseNodes<T> {
+    /// Append the given node.
+    pub(in crate::binary_tree) fn push(&mut self, node: T) {
+        self.nodes.push(Arc::new(node))
+    }
+
+    /// Return the number of nodes.
+    pub(in crate::binary_tree) fn len(&self) -> u32 {
+        // The number of nodes is bounded by the maximum size of the tree.
+        self.nodes.len() as u32
+    }
+
+    /// Return the node at the given index or `None` if the index is out of
+    /// range.
+    pub(in crate::binary_tree) fn get(&self, index: u32) -> Option<&Arc<T>> {
+        self.nodes.get(index as usize)
+    }
+
+    /// Replace the node at the given index. The index has to be in range.
+    pub(in crate::binary_tree) fn set(&mut self, index: u32, node: Arc<T>) {
+        debug_assert!(index < self.len());
+        if let Some(n) = self.nodes.get_mut(index as usize) {
+            *n = node;
+        }
+    }
+
+    /// Change the number of nodes to `len`. New nodes are blank and share a
+    /// single blank node.
+    pub(in crate::binary_tree) fn resize(&mut self, len: u32) {
+        self.nodes.resize(len as usize, Arc::default())
+    }
+
+    /// Returns an iterator over all nodes, ordered by their index.
+    pub(in crate::binary_tree) fn iter(&self) -> impl Iterator<Item = &T> {
+        self.nodes.iter().map(Arc::as_ref)
+    }
+}
+
+/// Nodes stored in a map that only contains the nodes that aren't blank.
+#[cfg(any(feature = "sparse-tree", test))]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(in crate::binary_tree) struct SparseNodes<T> {
+    nodes: BTreeMap<u32, Arc<T>>,
+    len: u32,
+    blank: Arc<T>,
+}
+
+#[cfg(any(feature = "sparse-tree", test))]
+impl<T: Clone + Debug + Default + PartialEq> SparseNodes<T> {
+    /// Append the given node.
+    pub(in crate::binary_tree) fn push(&mut self, node: T) {
+        self.len += 1;
+        if node != T::default() {
+            self.nodes.insert(self.len - 1, Arc::new(node));
+        }
+    }
+
+    /// Return the number of nodes, including the blank ones.
+    pub(in crate::binary_tree) fn len(&self) -> u32 {
+        self.len
+    }
+
+    /// Return the node at the given index or `None` if the index is out of
+    /// range. Blank nodes share a single blank node.
+    pub(in crate::binary_tree) fn get(&self, index: u32) -> Option<&Arc<T>> {
+        if index < self.len {
+            Some(self.nodes.get(&index).unwrap_or(&self.blank))
+        } else {
+            None
+        }
+    }
+
+    /// Replace the node at the given index. The index has to be in range.
+    pub(in crate::binary_tree) fn set(&mut self, index: u32, node: Arc<T>) {
+        debug_assert!(index < self.len);
+        if index >= self.len {
+            return;
+        }
+        if *node == T::default() {
+            self.nodes.remove(&index);
+        } else {
+            self.nodes.insert(index, node);
+        }
+    }
+
+    /// Change the number of nodes to `len`. New nodes are blank.
+    pub(in crate::binary_tree) fn resize(&mut self, len: u32) {
+        self.nodes.retain(|&index, _| index < len);
+        self.len = len;
+    }
+
+    /// Returns an iterator over all nodes, ordered by their index. Blank
+    /// nodes are yielded as well.
+    pub(in crate::binary_tree) fn iter(&self) -> impl Iterator<Item = &T> {
+        (0..self.len).map(|index| self.nodes.get(&index).unwrap_or(&self.blank).as_ref())
+    }
+}
+
+#[cfg(any(feature = "sparse-tree", test))]
+impl<T: Clone + Debug + Default + PartialEq + Serialize> Serialize for SparseNodes<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+#[cfg(any(feature = "sparse-tree", test))]
+impl<'de, T> Deserialize<'de> for SparseNodes<T>
+where
+    T: Clone + Debug + Default + PartialEq + Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct SparseNodesVisitor<T>(PhantomData<T>);
+
+        impl<'de, T> serde::de::Visitor<'de> for SparseNodesVisitor<T>
+        where
+            T: Clone + Debug + Default + PartialEq + Deserialize<'de>,
+        {
+            type Value = SparseNodes<T>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a sequence of nodes")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::SeqAccess<'de>,
+            {
+                // Blank nodes are dropped one by one, so that the full list
+                // of nodes is never held in memory.
+                let mut nodes = SparseNodes::default();
+                while let Some(node) = seq.next_element()? {
+                    nodes.push(node);
+                }
+                Ok(nodes)
+            }
+        }
+
+        deserializer.deserialize_seq(SparseNodesVisitor(PhantomData))
+    }
+}
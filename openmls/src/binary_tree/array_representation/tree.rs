@@ -6,22 +6,28 @@
 //! representation. The main [`ABinaryTree`] struct is generally immutable, but
 //! allows the creation of an [`AbDiff`] struct, where changes can be made before
 //! merging it back into an existing tree.
+//!
+//! The nodes are reference counted, so that clones of the tree and diffs share
+//! all nodes they don't change. A node is only copied when it is changed
+//! through a diff. With the `sparse-tree` feature, blank nodes are not stored
+//! at all (see [`nodes`](super::nodes)).
 
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::Arc};
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use super::{
     diff::{AbDiff, StagedAbDiff},
+    nodes::Nodes,
     treemath::{common_direct_path, LeafNodeIndex, ParentNodeIndex, TreeSize, MAX_TREE_SIZE},
 };
 
 #[derive(Clone, Debug)]
 pub(crate) enum TreeNode<L, P>
 where
-    L: Clone + Debug + Default,
-    P: Clone + Debug + Default,
+    L: Clone + Debug + Default + PartialEq,
+    P: Clone + Debug + Default + PartialEq,
 {
     Leaf(L),
     Parent(P),
@@ -29,16 +35,22 @@ where
 
 #[cfg_attr(test, derive(PartialEq))]
 #[derive(Clone, Debug, Serialize, Deserialize)]
-/// A representation of a full, left-balanced binary tree that uses a simple
-/// vector to store nodes. Each tree has to consist of at least one node.
-pub(crate) struct ABinaryTree<L: Clone + Debug + Default, P: Clone + Debug + Default> {
-    leaf_nodes: Vec<L>,
-    parent_nodes: Vec<P>,
+/// A representation of a full, left-balanced binary tree that stores its leaf
+/// and parent nodes in the array-representation (see [`Nodes`]). Each tree
+/// has to consist of at least one node.
+pub(crate) struct ABinaryTree<
+    L: Clone + Debug + Default + PartialEq,
+    P: Clone + Debug + Default + PartialEq,
+> {
+    leaf_nodes: Nodes<L>,
+    parent_nodes: Nodes<P>,
     default_leaf: L,
     default_parent: P,
 }
 
-impl<L: Clone + Debug + Default, P: Clone + Debug + Default> ABinaryTree<L, P> {
+impl<L: Clone + Debug + Default + PartialEq, P: Clone + Debug + Default + PartialEq>
+    ABinaryTree<L, P>
+{
     /// Create a tree from the given vector of nodes. The vector of nodes can't
     /// be empty and has to yield a full, left-balanced binary tree. The nodes
     /// in the tree are ordered in the array-representation. This function
@@ -46,7 +58,15 @@ impl<L: Clone + Debug + Default, P: Clone + Debug + Default> ABinaryTree<L, P> {
     /// of nodes does not allow the creation of a full, left-balanced binary
     /// tree and an [`ABinaryTreeError::OutOfRange`] error if the number of
     /// given nodes exceeds the range of [`TreeNodeIndex`].
-    pub(crate) fn new(nodes: Vec<TreeNode<L, P>>) -> Result<Self, ABinaryTreeError> {
+    ///
+    /// The nodes are consumed one by one, so that blank nodes never have to be
+    /// held in memory all at once if the tree is sparse.
+    pub(crate) fn new<I>(nodes: I) -> Result<Self, ABinaryTreeError>
+    where
+        I: IntoIterator<Item = TreeNode<L, P>>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let nodes = nodes.into_iter();
         // No more than 2^30 nodes
         if nodes.len() > MAX_TREE_SIZE as usize {
             return Err(ABinaryTreeError::OutOfRange);
@@ -54,12 +74,12 @@ impl<L: Clone + Debug + Default, P: Clone + Debug + Default> ABinaryTree<L, P> {
         if nodes.len() % 2 != 1 {
             return Err(ABinaryTreeError::InvalidNumberOfNodes);
         }
-        let mut leaf_nodes = Vec::new();
-        let mut parent_nodes = Vec::new();
+        let mut leaf_nodes = Nodes::default();
+        let mut parent_nodes = Nodes::default();
 
         // Split the nodes intow two vectors, one for the leaf nodes and one for
         // the parent nodes.
-        for (i, node) in nodes.into_iter().enumerate() {
+        for (i, node) in nodes.enumerate() {
             match node {
                 TreeNode::Leaf(l) => {
                     if i % 2 == 0 {
@@ -92,37 +112,55 @@ impl<L: Clone + Debug + Default, P: Clone + Debug + Default> ABinaryTree<L, P> {
     /// cannot be found.
     pub(in crate::binary_tree) fn leaf_by_index(&self, leaf_index: LeafNodeIndex) -> &L {
         self.leaf_nodes
-            .get(leaf_index.usize())
+            .get(leaf_index.u32())
+            .map(Arc::as_ref)
             .unwrap_or(&self.default_leaf)
     }
 
+    /// Returns a new reference to the leaf node at index `leaf_index`, so
+    /// that it can be shared with a diff. Returns a blank node if the node
+    /// cannot be found.
+    pub(in crate::binary_tree) fn shared_leaf(&self, leaf_index: LeafNodeIndex) -> Arc<L> {
+        self.leaf_nodes
+            .get(leaf_index.u32())
+            .cloned()
+            .unwrap_or_default()
+    }
+
     /// Obtain a reference to the data contained in the parent node at index
     /// `parent_index`, where the indexing corresponds to the array
     /// representation of the underlying binary tree. Returns the default value
     /// if the node cannot be found.
     pub(crate) fn parent_by_index(&self, parent_index: ParentNodeIndex) -> &P {
         self.parent_nodes
-            .get(parent_index.usize())
+            .get(parent_index.u32())
+            .map(Arc::as_ref)
             .unwrap_or(&self.default_parent)
     }
 
+    /// Returns a new reference to the parent node at index `parent_index`, so
+    /// that it can be shared with a diff. Returns a blank node if the node
+    /// cannot be found.
+    pub(in crate::binary_tree) fn shared_parent(&self, parent_index: ParentNodeIndex) -> Arc<P> {
+        self.parent_nodes
+            .get(parent_index.u32())
+            .cloned()
+            .unwrap_or_default()
+    }
+
     /// Return the number of nodes in the tree.
     pub(crate) fn tree_size(&self) -> TreeSize {
-        // We can cast the size to a u32, because the maximum size of a
-        // tree is 2^30.
-        TreeSize::new((self.leaf_nodes.len() + self.parent_nodes.len()) as u32)
+        TreeSize::new(self.leaf_nodes.len() + self.parent_nodes.len())
     }
 
     /// Return the number of leaf nodes in the tree.
     pub(crate) fn leaf_count(&self) -> u32 {
-        // This works, because the tree always has at least one leaf.
-        self.leaf_nodes.len() as u32
+        self.leaf_nodes.len()
     }
 
     /// Return the number of parent nodes in the tree.
     pub(crate) fn parent_count(&self) -> u32 {
-        // This works, because the tree always has at least one leaf.
-        self.parent_nodes.len() as u32
+        self.parent_nodes.len()
     }
 
     /// Returns an iterator over a tuple of the leaf index and a reference to a
@@ -153,16 +191,18 @@ impl<L: Clone + Debug + Default, P: Clone + Debug + Default> ABinaryTree<L, P> {
     /// Depending on the changes made to the diff, this can either increase or
     /// decrease the size of the tree, although not beyond the minimum size of
     /// leaf or the maximum size of `u32::MAX`.
+    ///
+    /// Only the changed nodes are replaced. All other nodes remain shared with
+    /// clones of the tree.
     pub(crate) fn merge_diff(&mut self, diff: StagedAbDiff<L, P>) {
         let tree_size = diff.tree_size();
 
         let (leaf_diff, parent_diff) = diff.into_diffs();
 
-        // Resize the tree to the new size.
-        self.leaf_nodes
-            .resize_with(tree_size.leaf_count() as usize, Default::default);
-        self.parent_nodes
-            .resize_with(tree_size.parent_count() as usize, Default::default);
+        // Resize the tree to the new size. New nodes are blank until the diff
+        // says otherwise.
+        self.leaf_nodes.resize(tree_size.leaf_count());
+        self.parent_nodes.resize(tree_size.parent_count());
 
         // Merge leaves
         // Iterate over the BTreeMap in order of indices.
@@ -170,13 +210,7 @@ impl<L: Clone + Debug + Default, P: Clone + Debug + Default> ABinaryTree<L, P> {
             // Assert that the node index is within the range of the tree.
             debug_assert!(leaf_index.u32() < self.leaf_count());
 
-            match self.leaf_nodes.get_mut(leaf_index.usize()) {
-                Some(n) => *n = diff_leaf,
-                None => {
-                    // Panic in debug mode
-                    debug_assert!(false);
-                }
-            }
+            self.leaf_nodes.set(leaf_index.u32(), diff_leaf);
         }
 
         // Merge parents
@@ -185,13 +219,7 @@ impl<L: Clone + Debug + Default, P: Clone + Debug + Default> ABinaryTree<L, P> {
             // Assert that the node index is within the range of the tree.
             debug_assert!(parent_index.u32() < self.parent_count());
 
-            match self.parent_nodes.get_mut(parent_index.usize()) {
-                Some(n) => *n = diff_parent,
-                None => {
-                    // Panic in debug mode
-                    debug_assert!(false);
-                }
-            }
+            self.parent_nodes.set(parent_index.u32(), diff_parent);
         }
     }
 
@@ -199,7 +227,8 @@ impl<L: Clone + Debug + Default, P: Clone + Debug + Default> ABinaryTree<L, P> {
     /// value if the leaf is not found.
     pub(crate) fn leaf(&self, leaf_index: LeafNodeIndex) -> &L {
         self.leaf_nodes
-            .get(leaf_index.usize())
+            .get(leaf_index.u32())
+            .map(Arc::as_ref)
             .unwrap_or(&self.default_leaf)
     }
 
@@ -216,10 +245,13 @@ impl<L: Clone + Debug + Default, P: Clone + Debug + Default> ABinaryTree<L, P> {
 }
 
 #[cfg(test)]
-impl<L: Clone + Debug + Default, P: Clone + Debug + Default> ABinaryTree<L, P> {
+impl<L: Clone + Debug + Default + PartialEq, P: Clone + Debug + Default + PartialEq>
+    ABinaryTree<L, P>
+{
     pub(crate) fn parent(&self, parent_index: ParentNodeIndex) -> &P {
         self.parent_nodes
-            .get(parent_index.usize())
+            .get(parent_index.u32())
+            .map(Arc::as_ref)
             .unwrap_or(&self.default_parent)
     }
 }
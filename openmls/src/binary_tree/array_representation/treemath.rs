@@ -1,4 +1,7 @@
-use std::cmp::Ordering;
+use std::{
+    cmp::Ordering,
+    ops::{Range, RangeInclusive},
+};
 
 use serde::{Deserialize, Serialize};
 use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
@@ -431,6 +434,37 @@ pub(crate) fn common_direct_path(
     common_path
 }
 
+/// The leaves and the parents in the subtree below a node, including the node
+/// itself.
+pub(crate) fn subtree(
+    node_index: TreeNodeIndex,
+) -> (RangeInclusive<LeafNodeIndex>, Range<ParentNodeIndex>) {
+    let x = node_index.u32();
+    let k = level(x);
+    // The leftmost and the rightmost node of the subtree are leaves.
+    let first = x - ((1 << k) - 1);
+    let last = x + ((1 << k) - 1);
+    (
+        LeafNodeIndex::from_tree_index(first)..=LeafNodeIndex::from_tree_index(last),
+        ParentNodeIndex::new(first / 2)..ParentNodeIndex::new(last / 2),
+    )
+}
+
+#[test]
+fn test_subtree() {
+    let (leaves, parents) = subtree(TreeNodeIndex::new(4));
+    assert_eq!(leaves, LeafNodeIndex::new(2)..=LeafNodeIndex::new(2));
+    assert!(parents.is_empty());
+
+    let (leaves, parents) = subtree(TreeNodeIndex::new(5));
+    assert_eq!(leaves, LeafNodeIndex::new(2)..=LeafNodeIndex::new(3));
+    assert_eq!(parents, ParentNodeIndex::new(2)..ParentNodeIndex::new(3));
+
+    let (leaves, parents) = subtree(TreeNodeIndex::new(7));
+    assert_eq!(leaves, LeafNodeIndex::new(0)..=LeafNodeIndex::new(7));
+    assert_eq!(parents, ParentNodeIndex::new(0)..ParentNodeIndex::new(7));
+}
+
 #[cfg(any(feature = "test-utils", test))]
 pub(crate) fn node_width(n: usize) -> usize {
     if n == 0 {
@@ -1,7 +1,10 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, sync::Arc};
 
 use crate::binary_tree::{
-    array_representation::tree::{ABinaryTree, TreeNode},
+    array_representation::{
+        nodes::{DenseNodes, SparseNodes},
+        tree::{ABinaryTree, TreeNode},
+    },
     MlsBinaryTree, MlsBinaryTreeError,
 };
 
@@ -172,7 +175,7 @@ fn test_new_tree_error() {
 
 #[test]
 fn test_diff_iter() {
-    let nodes = (0..101)
+    let nodes: Vec<_> = (0..101)
         .map(|i| {
             if i % 2 == 0 {
                 TreeNode::Leaf(i)
@@ -204,7 +207,7 @@ fn test_diff_iter() {
 
 #[test]
 fn test_diff_mutable_access_after_manipulation() {
-    let nodes = (0..101)
+    let nodes: Vec<_> = (0..101)
         .map(|i| {
             if i % 2 == 0 {
                 TreeNode::Leaf(i)
@@ -239,7 +242,7 @@ fn diff_leaf_access() {
     // We want to test if leaf access works correctly in a diff. In particular,
     // we want to ensure that if we access outside of the diff (but inside of
     // the original tree, e.g. because the tree was shrunk) we get a blank (i.e. the default leaf) back.
-    let nodes = (0..7)
+    let nodes: Vec<_> = (0..7)
         .map(|i| {
             if i % 2 == 0 {
                 // Let's add 10 so we recognize the default leaf which should be 0.
@@ -260,3 +263,97 @@ fn diff_leaf_access() {
     let leaf_outside_of_diff = diff.leaf(LeafNodeIndex::new(3));
     assert_eq!(leaf_outside_of_diff, &0)
 }
+
+#[test]
+fn test_diff_copy_on_write() {
+    let nodes: Vec<_> = (0..15)
+        .map(|i| {
+            if i % 2 == 0 {
+                TreeNode::Leaf(i)
+            } else {
+                TreeNode::Parent(i)
+            }
+        })
+        .collect();
+    let mut tree: ABinaryTree<u32, u32> = MlsBinaryTree::new(nodes).expect("error creating tree");
+    let original_tree = tree.clone();
+
+    let mut diff = tree.empty_diff();
+    *diff.leaf_mut(LeafNodeIndex::new(1)) += 100;
+    diff.set_direct_path_to_node(LeafNodeIndex::new(0), &100);
+    *diff.parent_mut(ParentNodeIndex::new(3)) += 1;
+    let staged_diff = diff.into();
+    tree.merge_diff(staged_diff);
+
+    // The changes are only visible in the tree the diff was merged into.
+    assert_eq!(tree.leaf(LeafNodeIndex::new(1)), &102);
+    assert_eq!(original_tree.leaf(LeafNodeIndex::new(1)), &2);
+    assert_eq!(tree.parent(ParentNodeIndex::new(0)), &100);
+    assert_eq!(tree.parent(ParentNodeIndex::new(1)), &100);
+    assert_eq!(tree.parent(ParentNodeIndex::new(3)), &101);
+    assert_eq!(original_tree.parent(ParentNodeIndex::new(3)), &7);
+
+    // Unchanged nodes are still shared with the clone of the tree.
+    for leaf_index in [0, 2, 3, 4, 5, 6, 7] {
+        let leaf_index = LeafNodeIndex::new(leaf_index);
+        assert!(Arc::ptr_eq(
+            &tree.shared_leaf(leaf_index),
+            &original_tree.shared_leaf(leaf_index)
+        ));
+    }
+    assert!(!Arc::ptr_eq(
+        &tree.shared_leaf(LeafNodeIndex::new(1)),
+        &original_tree.shared_leaf(LeafNodeIndex::new(1))
+    ));
+    for parent_index in [2, 4, 5, 6] {
+        let parent_index = ParentNodeIndex::new(parent_index);
+        assert!(Arc::ptr_eq(
+            &tree.shared_parent(parent_index),
+            &original_tree.shared_parent(parent_index)
+        ));
+    }
+}
+
+#[test]
+fn test_sparse_nodes() {
+    // Only every tenth node isn't blank.
+    let values = (0..100).map(|i| if i % 10 == 0 { i + 1 } else { 0 });
+    let mut dense: DenseNodes<u32> = DenseNodes::default();
+    let mut sparse = SparseNodes::default();
+    for value in values {
+        dense.push(value);
+        sparse.push(value);
+    }
+    assert_eq!(sparse.len(), 100);
+    assert!(sparse.iter().eq(dense.iter()));
+    assert_eq!(sparse.get(10).map(|n| **n), Some(11));
+    assert_eq!(sparse.get(11).map(|n| **n), Some(0));
+    assert_eq!(sparse.get(100), None);
+
+    // Changing nodes, including blanking them, works like on the dense
+    // representation.
+    for (index, node) in [(0, 0), (5, 6), (99, 7)] {
+        dense.set(index, Arc::new(node));
+        sparse.set(index, Arc::new(node));
+    }
+    assert!(sparse.iter().eq(dense.iter()));
+
+    // Shrinking drops the nodes beyond the new size and growing adds blanks.
+    dense.resize(50);
+    sparse.resize(50);
+    dense.resize(120);
+    sparse.resize(120);
+    assert_eq!(sparse.len(), 120);
+    assert!(sparse.iter().eq(dense.iter()));
+    assert_eq!(sparse.get(99).map(|n| **n), Some(0));
+
+    // Both representations have the same serialization.
+    let serialized = serde_json::to_vec(&sparse).expect("error serializing nodes");
+    assert_eq!(
+        serialized,
+        serde_json::to_vec(&dense).expect("error serializing nodes")
+    );
+    let deserialized: SparseNodes<u32> =
+        serde_json::from_slice(&serialized).expect("error deserializing nodes");
+    assert_eq!(deserialized, sparse);
+}
@@ -6,8 +6,8 @@ use super::*;
 pub(crate) const NONCE_BYTES: usize = 12;
 
 /// AEAD keys holding the plain key value and the AEAD algorithm type.
-#[derive(Serialize, Deserialize)]
-#[cfg_attr(any(feature = "test-utils", test), derive(Clone, PartialEq, Eq))]
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(any(feature = "test-utils", test), derive(PartialEq, Eq))]
 #[cfg_attr(feature = "crypto-debug", derive(Debug))]
 pub struct AeadKey {
     aead_mode: AeadType,
@@ -62,6 +62,16 @@ impl AeadKey {
         self.value.as_slice()
     }
 
+    /// Returns the key value as a [`Secret`], e.g. to derive further keys
+    /// from it.
+    pub(crate) fn to_secret(
+        &self,
+        ciphersuite: Ciphersuite,
+        mls_version: ProtocolVersion,
+    ) -> Secret {
+        Secret::from_slice(self.value.as_slice(), mls_version, ciphersuite)
+    }
+
     /// Encrypt a payload under the AeadKey given a nonce.
     pub(crate) fn aead_seal(
         &self,
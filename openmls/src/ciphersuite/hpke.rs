@@ -51,6 +51,9 @@ pub(crate) enum Error {
         "Error while serializing content. This should only happen if a bounds check was missing."
     )]
     MissingBoundCheck,
+    /// Encryption failed.
+    #[error("Encryption failed.")]
+    EncryptionFailed,
     /// Decryption failed.
     #[error("Decryption failed.")]
     DecryptionFailed,
@@ -109,13 +112,15 @@ pub(crate) fn encrypt_with_label(
     log_crypto!(debug, "* public key:  {public_key:x?}");
     log_crypto!(debug, "* plaintext:   {plaintext:x?}");
 
-    let cipher = crypto.hpke_seal(
-        ciphersuite.hpke_config(),
-        public_key,
-        &context,
-        &[],
-        plaintext,
-    );
+    let cipher = crypto
+        .hpke_seal(
+            ciphersuite.hpke_config(),
+            public_key,
+            &context,
+            &[],
+            plaintext,
+        )
+        .map_err(|_| Error::EncryptionFailed)?;
 
     log_crypto!(debug, "* ciphertext:  {:x?}", cipher);
 
@@ -25,8 +25,10 @@ mod secret;
 
 // Public
 pub mod hash_ref;
+pub mod policy;
 pub mod signable;
 pub mod signature;
+pub mod signature_cache;
 
 // Crate
 pub(crate) use aead::*;
@@ -34,6 +36,7 @@ pub(crate) use mac::*;
 pub(crate) use reuse_guard::*;
 pub(crate) use secret::*;
 pub(crate) use signature::*;
+pub(crate) use signature_cache::CachingCrypto;
 
 pub(crate) use serde::{Deserialize, Serialize};
 
@@ -49,7 +52,7 @@ pub use openmls_traits::types::HpkePrivateKey;
 /// Compare two byte slices in a way that's hopefully not optimised out by the
 /// compiler.
 #[inline(never)]
-fn equal_ct(a: &[u8], b: &[u8]) -> bool {
+pub(crate) fn equal_ct(a: &[u8], b: &[u8]) -> bool {
     let mut diff = 0u8;
     for (l, r) in a.iter().zip(b.iter()) {
         diff |= l ^ r;
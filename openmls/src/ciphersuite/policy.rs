@@ -0,0 +1,216 @@
+//! # Ciphersuite policy
+//!
+//! A [`CiphersuitePolicy`] restricts the ciphersuites a client uses and
+//! accepts, e.g. to centrally forbid ciphersuites below a certain security
+//! level. It is checked when creating groups and key packages, when joining
+//! groups from a [`Welcome`](crate::messages::Welcome) and when tracking
+//! groups with a [`PublicGroup`](crate::group::PublicGroup).
+//!
+//! ```
+//! use openmls::prelude::*;
+//!
+//! // Only allow ciphersuites with at least 192 bits of security for signatures.
+//! let policy = CiphersuitePolicy::default().with_minimum_signature_strength(192);
+//! assert!(policy
+//!     .check(Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519)
+//!     .is_err());
+//! assert!(policy
+//!     .check(Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384)
+//!     .is_ok());
+//! ```
+
+use openmls_traits::types::{Ciphersuite, HashType, SignatureScheme};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Ciphersuite policy error.
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum CiphersuitePolicyError {
+    /// The ciphersuite is not in the list of allowed ciphersuites.
+    #[error("The ciphersuite {0:?} is not allowed.")]
+    NotAllowed(Ciphersuite),
+    /// The hash function of the ciphersuite is too weak.
+    #[error("The hash function of {ciphersuite:?} has a strength of {strength} bits, the minimum is {minimum} bits.")]
+    HashTooWeak {
+        /// The rejected ciphersuite.
+        ciphersuite: Ciphersuite,
+        /// The strength of its hash function in bits.
+        strength: u16,
+        /// The minimum strength required by the policy in bits.
+        minimum: u16,
+    },
+    /// The signature scheme of the ciphersuite is too weak.
+    #[error("The signature scheme of {ciphersuite:?} has a strength of {strength} bits, the minimum is {minimum} bits.")]
+    SignatureTooWeak {
+        /// The rejected ciphersuite.
+        ciphersuite: Ciphersuite,
+        /// The strength of its signature scheme in bits.
+        strength: u16,
+        /// The minimum strength required by the policy in bits.
+        minimum: u16,
+    },
+}
+
+/// Policy for the ciphersuites of groups and key packages.
+///
+///  - allowed_ciphersuites:
+/// The ciphersuites that may be used. The default value is `None`, i.e. all
+/// ciphersuites are allowed.
+///  - minimum_hash_strength:
+/// The minimum collision resistance of the hash function of a ciphersuite in
+/// bits, i.e. half of the output length. The default value is 0.
+///  - minimum_signature_strength:
+/// The minimum security level of the signature scheme of a ciphersuite in
+/// bits. The default value is 0.
+///
+/// A ciphersuite has to satisfy all three restrictions.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CiphersuitePolicy {
+    allowed_ciphersuites: Option<Vec<Ciphersuite>>,
+    minimum_hash_strength: u16,
+    minimum_signature_strength: u16,
+}
+
+impl CiphersuitePolicy {
+    /// Set the ciphersuites that may be used.
+    pub fn with_allowed_ciphersuites(mut self, allowed_ciphersuites: Vec<Ciphersuite>) -> Self {
+        self.allowed_ciphersuites = Some(allowed_ciphersuites);
+        self
+    }
+
+    /// Set the minimum strength of the hash function in bits.
+    pub fn with_minimum_hash_strength(mut self, minimum_hash_strength: u16) -> Self {
+        self.minimum_hash_strength = minimum_hash_strength;
+        self
+    }
+
+    /// Set the minimum strength of the signature scheme in bits.
+    pub fn with_minimum_signature_strength(mut self, minimum_signature_strength: u16) -> Self {
+        self.minimum_signature_strength = minimum_signature_strength;
+        self
+    }
+
+    /// Get the ciphersuites that may be used, or `None` if all are allowed.
+    pub fn allowed_ciphersuites(&self) -> Option<&[Ciphersuite]> {
+        self.allowed_ciphersuites.as_deref()
+    }
+
+    /// Get the minimum strength of the hash function in bits.
+    pub fn minimum_hash_strength(&self) -> u16 {
+        self.minimum_hash_strength
+    }
+
+    /// Get the minimum strength of the signature scheme in bits.
+    pub fn minimum_signature_strength(&self) -> u16 {
+        self.minimum_signature_strength
+    }
+
+    /// Checks that the `ciphersuite` is permitted by this policy.
+    ///
+    /// Returns a [`CiphersuitePolicyError`] for the first restriction that
+    /// the ciphersuite violates.
+    pub fn check(&self, ciphersuite: Ciphersuite) -> Result<(), CiphersuitePolicyError> {
+        if let Some(allowed_ciphersuites) = &self.allowed_ciphersuites {
+            if !allowed_ciphersuites.contains(&ciphersuite) {
+                return Err(CiphersuitePolicyError::NotAllowed(ciphersuite));
+            }
+        }
+
+        let strength = hash_strength(ciphersuite.hash_algorithm());
+        if strength < self.minimum_hash_strength {
+            return Err(CiphersuitePolicyError::HashTooWeak {
+                ciphersuite,
+                strength,
+                minimum: self.minimum_hash_strength,
+            });
+        }
+
+        let strength = signature_strength(ciphersuite.signature_algorithm());
+        if strength < self.minimum_signature_strength {
+            return Err(CiphersuitePolicyError::SignatureTooWeak {
+                ciphersuite,
+                strength,
+                minimum: self.minimum_signature_strength,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the collision resistance of the `hash_type` in bits.
+fn hash_strength(hash_type: HashType) -> u16 {
+    (hash_type.size() * 4) as u16
+}
+
+/// Returns the security level of the `signature_scheme` in bits.
+fn signature_strength(signature_scheme: SignatureScheme) -> u16 {
+    match signature_scheme {
+        SignatureScheme::ECDSA_SECP256R1_SHA256 | SignatureScheme::ED25519 => 128,
+        SignatureScheme::ECDSA_SECP384R1_SHA384 => 192,
+        SignatureScheme::ED448 => 224,
+        SignatureScheme::ECDSA_SECP521R1_SHA512 => 256,
+        #[cfg(feature = "secp256k1")]
+        SignatureScheme::ECDSA_SECP256K1_SHA256 => 128,
+        // The NIST security categories 2, 3 and 5.
+        #[cfg(feature = "pq-signatures")]
+        SignatureScheme::ML_DSA_44 => 128,
+        #[cfg(feature = "pq-signatures")]
+        SignatureScheme::ML_DSA_65 => 192,
+        #[cfg(feature = "pq-signatures")]
+        SignatureScheme::ML_DSA_87 => 256,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use openmls_traits::types::Ciphersuite;
+
+    use super::{CiphersuitePolicy, CiphersuitePolicyError};
+
+    #[test]
+    fn ciphersuite_policy() {
+        let x25519 = Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
+        let p256 = Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256;
+        let p384 = Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384;
+        let x448 = Ciphersuite::MLS_256_DHKEMX448_AES256GCM_SHA512_Ed448;
+
+        // The default policy allows everything.
+        let policy = CiphersuitePolicy::default();
+        for ciphersuite in [x25519, p256, p384, x448] {
+            assert_eq!(policy.check(ciphersuite), Ok(()));
+        }
+
+        let policy = CiphersuitePolicy::default().with_allowed_ciphersuites(vec![p256, p384]);
+        assert_eq!(
+            policy.check(x25519),
+            Err(CiphersuitePolicyError::NotAllowed(x25519))
+        );
+        assert_eq!(policy.check(p256), Ok(()));
+
+        let policy = CiphersuitePolicy::default().with_minimum_hash_strength(192);
+        assert_eq!(
+            policy.check(p256),
+            Err(CiphersuitePolicyError::HashTooWeak {
+                ciphersuite: p256,
+                strength: 128,
+                minimum: 192,
+            })
+        );
+        assert_eq!(policy.check(p384), Ok(()));
+
+        let policy = CiphersuitePolicy::default().with_minimum_signature_strength(256);
+        assert_eq!(
+            policy.check(x448),
+            Err(CiphersuitePolicyError::SignatureTooWeak {
+                ciphersuite: x448,
+                strength: 224,
+                minimum: 256,
+            })
+        );
+        assert_eq!(
+            policy.check(Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521),
+            Ok(())
+        );
+    }
+}
@@ -192,6 +192,19 @@ impl Secret {
         length: usize,
     ) -> Result<Secret, CryptoError> {
         let full_label = format!("{} {}", self.mls_version, label);
+        self.kdf_expand_full_label(backend, full_label, context, length)
+    }
+
+    /// Expand a `Secret` like [`Self::kdf_expand_label()`], but with a
+    /// `full_label` that already contains the label prefix, e.g. to
+    /// reproduce the prefix of other protocol versions.
+    pub(crate) fn kdf_expand_full_label(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        full_label: String,
+        context: &[u8],
+        length: usize,
+    ) -> Result<Secret, CryptoError> {
         log::trace!(
             "KDF expand with label \"{}\" and {:?} with context {:x?}",
             &full_label,
@@ -28,7 +28,13 @@
 //! Similarly, only the [`Verifiable`] struct should implement the
 //! [`tls_codec::Deserialize`] trait.
 
-use openmls_traits::{crypto::OpenMlsCrypto, signatures::Signer};
+use std::future::Future;
+
+use openmls_traits::{
+    crypto::OpenMlsCrypto,
+    signatures::{AsyncSigner, Signer},
+    types::SignatureScheme,
+};
 use thiserror::Error;
 use tls_codec::Serialize;
 
@@ -91,6 +97,20 @@ pub trait Signable: Sized {
     /// Return the string label used for labeled signing.
     fn label(&self) -> &str;
 
+    /// Return the labeled payload, i.e. the serialized `SignContent` that is
+    /// passed to the signer.
+    fn sign_content(&self) -> Result<Vec<u8>, SignatureError> {
+        let payload = self
+            .unsigned_payload()
+            .map_err(|_| SignatureError::SigningError)?;
+        SignContent::new(self.label(), payload.into())
+            .tls_serialize_detached()
+            .map_err(|e| {
+                log::error!("Serializing SignContent failed, {:?}", e);
+                SignatureError::SigningError
+            })
+    }
+
     /// Sign the payload with the given `private_key`.
     ///
     /// Returns a `Signature`.
@@ -98,23 +118,31 @@ pub trait Signable: Sized {
     where
         Self::SignedOutput: SignedStruct<Self>,
     {
-        let payload = self
-            .unsigned_payload()
-            .map_err(|_| SignatureError::SigningError)?;
-        let payload = match SignContent::new(self.label(), payload.into()).tls_serialize_detached()
-        {
-            Ok(p) => p,
-            Err(e) => {
-                log::error!("Serializing SignContent failed, {:?}", e);
-                return Err(SignatureError::SigningError);
-            }
-        };
-        let signature = signer
-            .sign(&payload)
-            .map_err(|_| SignatureError::SigningError)?;
+        let payload = self.sign_content()?;
+        let signature = Signer::sign(signer, &payload).map_err(|_| SignatureError::SigningError)?;
 
         Ok(Self::SignedOutput::from_payload(self, signature.into()))
     }
+
+    /// Sign the payload with the given [`AsyncSigner`].
+    ///
+    /// Returns a `Signature`.
+    fn sign_async(
+        self,
+        signer: &impl AsyncSigner,
+    ) -> impl Future<Output = Result<Self::SignedOutput, SignatureError>>
+    where
+        Self::SignedOutput: SignedStruct<Self>,
+    {
+        async move {
+            let payload = self.sign_content()?;
+            let signature = AsyncSigner::sign(signer, &payload)
+                .await
+                .map_err(|_| SignatureError::SigningError)?;
+
+            Ok(Self::SignedOutput::from_payload(self, signature.into()))
+        }
+    }
 }
 
 /// The verifiable trait must be implemented by any struct that is signed with
@@ -174,17 +202,7 @@ fn verify(
     verifiable: &impl Verifiable,
     pk: &OpenMlsSignaturePublicKey,
 ) -> Result<(), SignatureError> {
-    let payload = verifiable
-        .unsigned_payload()
-        .map_err(|_| SignatureError::VerificationError)?;
-    let sign_content = SignContent::new(verifiable.label(), payload.into());
-    let payload = match sign_content.tls_serialize_detached() {
-        Ok(p) => p,
-        Err(e) => {
-            log::error!("Serializing SignContent failed, {:?}", e);
-            return Err(SignatureError::VerificationError);
-        }
-    };
+    let payload = sign_content(verifiable)?;
     crypto
         .verify_signature(
             pk.signature_scheme(),
@@ -194,3 +212,117 @@ fn verify(
         )
         .map_err(|_| SignatureError::VerificationError)
 }
+
+/// Returns the serialized `SignContent` of the `verifiable`, i.e. the data the
+/// signature is computed over.
+fn sign_content(verifiable: &impl Verifiable) -> Result<Vec<u8>, SignatureError> {
+    let payload = verifiable
+        .unsigned_payload()
+        .map_err(|_| SignatureError::VerificationError)?;
+    let sign_content = SignContent::new(verifiable.label(), payload.into());
+    sign_content.tls_serialize_detached().map_err(|e| {
+        log::error!("Serializing SignContent failed, {:?}", e);
+        SignatureError::VerificationError
+    })
+}
+
+/// A set of signature checks that are verified together through
+/// [`OpenMlsCrypto::verify_batch()`].
+///
+/// Each check is added with an error of type `E` that is returned by
+/// [`SignatureBatch::verify()`] if that particular signature turns out to be
+/// invalid.
+///
+/// **NOTE:** [`SignatureBatch::add()`] returns the verified struct right away.
+/// It MUST be discarded unless [`SignatureBatch::verify()`] succeeds.
+pub(crate) struct SignatureBatch<E> {
+    checks: Vec<SignatureCheck<E>>,
+}
+
+struct SignatureCheck<E> {
+    signature_scheme: SignatureScheme,
+    payload: Vec<u8>,
+    public_key: Vec<u8>,
+    signature: Vec<u8>,
+    error: E,
+}
+
+impl<E> SignatureBatch<E> {
+    /// Create an empty batch.
+    pub(crate) fn new() -> Self {
+        Self { checks: vec![] }
+    }
+
+    /// Add the signature check of `verifiable` against `pk` to the batch and
+    /// return the verified struct.
+    ///
+    /// Returns `error` if the signature is larger than any valid signature of
+    /// the signature scheme of `pk` or the payload can't be serialized.
+    pub(crate) fn add<V, T>(
+        &mut self,
+        verifiable: V,
+        pk: &OpenMlsSignaturePublicKey,
+        error: E,
+    ) -> Result<T, E>
+    where
+        V: Verifiable,
+        T: VerifiedStruct<V>,
+    {
+        if pk.check_signature_size(verifiable.signature()).is_err() {
+            return Err(error);
+        }
+        let payload = match sign_content(&verifiable) {
+            Ok(payload) => payload,
+            Err(_) => return Err(error),
+        };
+        self.checks.push(SignatureCheck {
+            signature_scheme: pk.signature_scheme(),
+            payload,
+            public_key: pk.as_slice().to_vec(),
+            signature: verifiable.signature().value().to_vec(),
+            error,
+        });
+        Ok(T::from_verifiable(verifiable, T::SealingType::default()))
+    }
+
+    /// Verify all signatures in the batch, one batch per signature scheme.
+    ///
+    /// If a batch fails, the signatures of that batch are verified one by
+    /// one and the error of the first invalid signature is returned.
+    pub(crate) fn verify(self, crypto: &impl OpenMlsCrypto) -> Result<(), E> {
+        let mut checks = self.checks;
+        while let Some(signature_scheme) = checks.first().map(|check| check.signature_scheme) {
+            let (batch, rest): (Vec<_>, Vec<_>) = checks
+                .into_iter()
+                .partition(|check| check.signature_scheme == signature_scheme);
+            checks = rest;
+
+            let messages: Vec<&[u8]> = batch.iter().map(|c| c.payload.as_slice()).collect();
+            let public_keys: Vec<&[u8]> = batch.iter().map(|c| c.public_key.as_slice()).collect();
+            let signatures: Vec<&[u8]> = batch.iter().map(|c| c.signature.as_slice()).collect();
+            if crypto
+                .verify_batch(signature_scheme, &messages, &public_keys, &signatures)
+                .is_ok()
+            {
+                continue;
+            }
+
+            // Find the culprit to return a meaningful error. If every
+            // signature is valid on its own, the batch is accepted.
+            for check in batch {
+                if crypto
+                    .verify_signature(
+                        signature_scheme,
+                        &check.payload,
+                        &check.public_key,
+                        &check.signature,
+                    )
+                    .is_err()
+                {
+                    return Err(check.error);
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -134,7 +134,15 @@ impl<T> SignedStruct<T> for Signature {
 
 impl OpenMlsSignaturePublicKey {
     /// Create a new signature public key from raw key bytes.
+    ///
+    /// Returns a [`CryptoError::InvalidLength`] if the signature scheme has a
+    /// fixed public key size and the key doesn't match it.
     pub fn new(value: VLBytes, signature_scheme: SignatureScheme) -> Result<Self, CryptoError> {
+        if let Some(public_key_size) = signature_scheme.public_key_size() {
+            if value.as_slice().len() != public_key_size {
+                return Err(CryptoError::InvalidLength);
+            }
+        }
         Ok(Self {
             value,
             signature_scheme,
@@ -157,6 +165,7 @@ impl OpenMlsSignaturePublicKey {
         signature: &Signature,
         sign_content: &SignContent,
     ) -> Result<(), CryptoError> {
+        self.check_signature_size(signature)?;
         let payload = match sign_content.tls_serialize_detached() {
             Ok(p) => p,
             Err(e) => {
@@ -183,6 +192,7 @@ impl OpenMlsSignaturePublicKey {
         signature: &Signature,
         payload: &[u8],
     ) -> Result<(), CryptoError> {
+        self.check_signature_size(signature)?;
         backend
             .crypto()
             .verify_signature(
@@ -199,6 +209,16 @@ impl OpenMlsSignaturePublicKey {
         self.signature_scheme
     }
 
+    /// Reject signatures that are larger than any valid signature of the
+    /// signature scheme before handing them to the crypto backend. This
+    /// matters in particular for the large post-quantum signatures.
+    pub(crate) fn check_signature_size(&self, signature: &Signature) -> Result<(), CryptoError> {
+        if signature.value.as_slice().len() > self.signature_scheme.max_signature_size() {
+            return Err(CryptoError::InvalidSignature);
+        }
+        Ok(())
+    }
+
     /// Returns the bytes of the signature public key.
     pub fn as_slice(&self) -> &[u8] {
         self.value.as_ref()
@@ -0,0 +1,373 @@
+//! # Signature cache
+//!
+//! A [`SignatureCache`] remembers signatures that were verified successfully,
+//! so that they don't have to be verified again, e.g. when the same commit is
+//! processed by several groups of a client or processed again after it was
+//! buffered, or when the leaf nodes of a large ratchet tree are verified
+//! again on a re-join.
+//!
+//! A signature is identified by the hash of the signature scheme, the public
+//! key, the signed content and the signature itself. Only valid signatures
+//! are cached, so a cache can be shared between groups and clients of the
+//! same application.
+//!
+//! ```
+//! use std::sync::Arc;
+//!
+//! use openmls::prelude::*;
+//!
+//! // Remember the last 10 000 valid signatures.
+//! let signature_cache = Arc::new(SignatureCache::new(10_000));
+//! assert!(signature_cache.is_empty());
+//! ```
+
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use openmls_traits::{
+    crypto::OpenMlsCrypto,
+    types::{
+        AeadType, Ciphersuite, CryptoError, ExporterSecret, HashType, HpkeCiphertext, HpkeConfig,
+        HpkeKemType, HpkeKeyPair, KemOutput, SignatureScheme,
+    },
+};
+use tls_codec::{
+    SecretVLBytes, Serialize as TlsSerializeTrait, TlsSerialize, TlsSize, VLByteSlice,
+};
+
+/// A bounded cache of successfully verified signatures.
+///
+/// Once the cache holds `capacity` signatures, the oldest one is dropped for
+/// every new one. The cache can be used from several threads at once.
+#[derive(Debug)]
+pub struct SignatureCache {
+    capacity: usize,
+    entries: Mutex<SignatureCacheEntries>,
+}
+
+#[derive(Debug, Default)]
+struct SignatureCacheEntries {
+    keys: HashSet<Vec<u8>>,
+    // The keys in the order they were inserted, oldest first.
+    order: VecDeque<Vec<u8>>,
+}
+
+impl SignatureCache {
+    /// Create an empty cache for at most `capacity` signatures. A capacity of
+    /// 0 disables the cache.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(SignatureCacheEntries::default()),
+        }
+    }
+
+    /// Returns the maximum number of signatures in the cache.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of signatures in the cache.
+    pub fn len(&self) -> usize {
+        self.lock().keys.len()
+    }
+
+    /// Returns `true` if the cache doesn't contain any signatures.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all signatures from the cache.
+    pub fn clear(&self) {
+        *self.lock() = SignatureCacheEntries::default();
+    }
+
+    /// Locks the entries of the cache. The entries are never left
+    /// half-written, so a lock that was poisoned by a panic of another thread
+    /// is recovered.
+    fn lock(&self) -> MutexGuard<'_, SignatureCacheEntries> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn contains(&self, key: &[u8]) -> bool {
+        self.lock().keys.contains(key)
+    }
+
+    fn insert(&self, key: Vec<u8>) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.lock();
+        if !entries.keys.insert(key.clone()) {
+            return;
+        }
+        entries.order.push_back(key);
+        while entries.order.len() > self.capacity {
+            if let Some(oldest) = entries.order.pop_front() {
+                entries.keys.remove(&oldest);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for SignatureCache {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+#[derive(TlsSerialize, TlsSize)]
+struct SignatureCacheKeyInput<'a> {
+    signature_scheme: SignatureScheme,
+    public_key: VLByteSlice<'a>,
+    data: VLByteSlice<'a>,
+    signature: VLByteSlice<'a>,
+}
+
+/// An [`OpenMlsCrypto`] provider that looks up signatures in a
+/// [`SignatureCache`] before verifying them with the wrapped provider, and
+/// adds the signatures that were verified successfully to the cache.
+///
+/// All other functions are forwarded to the wrapped provider.
+pub(crate) struct CachingCrypto<'a, C: OpenMlsCrypto> {
+    crypto: &'a C,
+    cache: Option<&'a SignatureCache>,
+}
+
+impl<'a, C: OpenMlsCrypto> CachingCrypto<'a, C> {
+    /// Wrap `crypto`. Without a `cache`, all signatures are verified by
+    /// `crypto`.
+    pub(crate) fn new(crypto: &'a C, cache: Option<&'a SignatureCache>) -> Self {
+        Self { crypto, cache }
+    }
+
+    /// Returns the cache key of the signature, or `None` if there is no
+    /// cache or the key can't be computed.
+    fn cache_key(
+        &self,
+        alg: SignatureScheme,
+        data: &[u8],
+        pk: &[u8],
+        signature: &[u8],
+    ) -> Option<Vec<u8>> {
+        self.cache?;
+        let input = SignatureCacheKeyInput {
+            signature_scheme: alg,
+            public_key: VLByteSlice(pk),
+            data: VLByteSlice(data),
+            signature: VLByteSlice(signature),
+        }
+        .tls_serialize_detached()
+        .ok()?;
+        self.crypto.hash(HashType::Sha2_256, &input).ok()
+    }
+
+    fn is_cached(&self, key: &Option<Vec<u8>>) -> bool {
+        match (self.cache, key) {
+            (Some(cache), Some(key)) => cache.contains(key),
+            _ => false,
+        }
+    }
+
+    fn add_to_cache(&self, key: Option<Vec<u8>>) {
+        if let (Some(cache), Some(key)) = (self.cache, key) {
+            cache.insert(key)
+        }
+    }
+}
+
+impl<'a, C: OpenMlsCrypto> OpenMlsCrypto for CachingCrypto<'a, C> {
+    fn supports(&self, ciphersuite: Ciphersuite) -> Result<(), CryptoError> {
+        self.crypto.supports(ciphersuite)
+    }
+
+    fn supported_ciphersuites(&self) -> Vec<Ciphersuite> {
+        self.crypto.supported_ciphersuites()
+    }
+
+    fn supports_signature_scheme(&self, scheme: SignatureScheme) -> Result<(), CryptoError> {
+        self.crypto.supports_signature_scheme(scheme)
+    }
+
+    fn supports_aead(&self, aead: AeadType) -> Result<(), CryptoError> {
+        self.crypto.supports_aead(aead)
+    }
+
+    fn supports_hash(&self, hash_type: HashType) -> Result<(), CryptoError> {
+        self.crypto.supports_hash(hash_type)
+    }
+
+    fn supports_hpke_kem(&self, kem: HpkeKemType) -> Result<(), CryptoError> {
+        self.crypto.supports_hpke_kem(kem)
+    }
+
+    fn hkdf_extract(
+        &self,
+        hash_type: HashType,
+        salt: &[u8],
+        ikm: &[u8],
+    ) -> Result<SecretVLBytes, CryptoError> {
+        self.crypto.hkdf_extract(hash_type, salt, ikm)
+    }
+
+    fn hkdf_expand(
+        &self,
+        hash_type: HashType,
+        prk: &[u8],
+        info: &[u8],
+        okm_len: usize,
+    ) -> Result<SecretVLBytes, CryptoError> {
+        self.crypto.hkdf_expand(hash_type, prk, info, okm_len)
+    }
+
+    fn hash(&self, hash_type: HashType, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        self.crypto.hash(hash_type, data)
+    }
+
+    fn aead_encrypt(
+        &self,
+        alg: AeadType,
+        key: &[u8],
+        data: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        self.crypto.aead_encrypt(alg, key, data, nonce, aad)
+    }
+
+    fn aead_decrypt(
+        &self,
+        alg: AeadType,
+        key: &[u8],
+        ct_tag: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        self.crypto.aead_decrypt(alg, key, ct_tag, nonce, aad)
+    }
+
+    fn signature_key_gen(&self, alg: SignatureScheme) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        self.crypto.signature_key_gen(alg)
+    }
+
+    fn verify_signature(
+        &self,
+        alg: SignatureScheme,
+        data: &[u8],
+        pk: &[u8],
+        signature: &[u8],
+    ) -> Result<(), CryptoError> {
+        let key = self.cache_key(alg, data, pk, signature);
+        if self.is_cached(&key) {
+            return Ok(());
+        }
+        self.crypto.verify_signature(alg, data, pk, signature)?;
+        self.add_to_cache(key);
+        Ok(())
+    }
+
+    fn verify_batch(
+        &self,
+        alg: SignatureScheme,
+        messages: &[&[u8]],
+        public_keys: &[&[u8]],
+        signatures: &[&[u8]],
+    ) -> Result<(), CryptoError> {
+        if messages.len() != public_keys.len() || messages.len() != signatures.len() {
+            return Err(CryptoError::InvalidLength);
+        }
+        // Only the signatures that aren't cached are verified.
+        let mut keys = vec![];
+        let mut batch = (vec![], vec![], vec![]);
+        for ((data, pk), signature) in messages.iter().zip(public_keys).zip(signatures) {
+            let key = self.cache_key(alg, data, pk, signature);
+            if !self.is_cached(&key) {
+                keys.push(key);
+                batch.0.push(*data);
+                batch.1.push(*pk);
+                batch.2.push(*signature);
+            }
+        }
+        if !keys.is_empty() {
+            self.crypto
+                .verify_batch(alg, &batch.0, &batch.1, &batch.2)?;
+        }
+        for key in keys {
+            self.add_to_cache(key);
+        }
+        Ok(())
+    }
+
+    fn sign(&self, alg: SignatureScheme, data: &[u8], key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        self.crypto.sign(alg, data, key)
+    }
+
+    fn hpke_seal(
+        &self,
+        config: HpkeConfig,
+        pk_r: &[u8],
+        info: &[u8],
+        aad: &[u8],
+        ptxt: &[u8],
+    ) -> Result<HpkeCiphertext, CryptoError> {
+        self.crypto.hpke_seal(config, pk_r, info, aad, ptxt)
+    }
+
+    fn hpke_open(
+        &self,
+        config: HpkeConfig,
+        input: &HpkeCiphertext,
+        sk_r: &[u8],
+        info: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        self.crypto.hpke_open(config, input, sk_r, info, aad)
+    }
+
+    fn hpke_setup_sender_and_export(
+        &self,
+        config: HpkeConfig,
+        pk_r: &[u8],
+        info: &[u8],
+        exporter_context: &[u8],
+        exporter_length: usize,
+    ) -> Result<(KemOutput, ExporterSecret), CryptoError> {
+        self.crypto.hpke_setup_sender_and_export(
+            config,
+            pk_r,
+            info,
+            exporter_context,
+            exporter_length,
+        )
+    }
+
+    fn hpke_setup_receiver_and_export(
+        &self,
+        config: HpkeConfig,
+        enc: &[u8],
+        sk_r: &[u8],
+        info: &[u8],
+        exporter_context: &[u8],
+        exporter_length: usize,
+    ) -> Result<ExporterSecret, CryptoError> {
+        self.crypto.hpke_setup_receiver_and_export(
+            config,
+            enc,
+            sk_r,
+            info,
+            exporter_context,
+            exporter_length,
+        )
+    }
+
+    fn derive_hpke_keypair(
+        &self,
+        config: HpkeConfig,
+        ikm: &[u8],
+    ) -> Result<HpkeKeyPair, CryptoError> {
+        self.crypto.derive_hpke_keypair(config, ikm)
+    }
+}
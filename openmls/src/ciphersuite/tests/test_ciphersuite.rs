@@ -8,12 +8,15 @@ use crate::{ciphersuite::*, test_utils::*};
 #[apply(ciphersuites_and_backends)]
 fn test_hpke_seal_open(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
     let plaintext = &[1, 2, 3];
-    let kp = backend.crypto().derive_hpke_keypair(
-        ciphersuite.hpke_config(),
-        Secret::random(ciphersuite, backend, None)
-            .expect("Not enough randomness.")
-            .as_slice(),
-    );
+    let kp = backend
+        .crypto()
+        .derive_hpke_keypair(
+            ciphersuite.hpke_config(),
+            Secret::random(ciphersuite, backend, None)
+                .expect("Not enough randomness.")
+                .as_slice(),
+        )
+        .expect("Error deriving an HPKE key pair.");
     let ciphertext = hpke::encrypt_with_label(
         &kp.public,
         "label",
@@ -73,3 +76,32 @@ fn test_hpke_seal_open(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoPro
         CryptoError::HpkeDecryptionError
     );
 }
+
+// Public keys and signatures of the wrong size are rejected before they reach
+// the crypto backend.
+#[apply(ciphersuites_and_backends)]
+fn test_signature_sizes(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let signature_scheme = ciphersuite.signature_algorithm();
+    let (_private, public) = backend
+        .crypto()
+        .signature_key_gen(signature_scheme)
+        .expect("Error generating signature key.");
+
+    if let Some(public_key_size) = signature_scheme.public_key_size() {
+        assert_eq!(public.len(), public_key_size);
+        let mut too_long = public.clone();
+        too_long.push(0);
+        assert_eq!(
+            OpenMlsSignaturePublicKey::new(too_long.into(), signature_scheme).unwrap_err(),
+            CryptoError::InvalidLength
+        );
+    }
+
+    let pk = OpenMlsSignaturePublicKey::new(public.into(), signature_scheme)
+        .expect("Error creating public key.");
+    let oversized = Signature::from(vec![0u8; signature_scheme.max_signature_size() + 1]);
+    assert_eq!(
+        pk.verify(backend, &oversized, b"payload").unwrap_err(),
+        CryptoError::InvalidSignature
+    );
+}
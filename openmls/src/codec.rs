@@ -0,0 +1,117 @@
+//! # TLS codec
+//!
+//! OpenMLS encodes all messages with the [`tls_codec`] crate. This module
+//! re-exports the traits and derive macros of the codec, so that crates
+//! defining their own extensions or proposals can encode them without
+//! depending on `tls_codec` themselves.
+//!
+//! The items of this module are part of the stable API of OpenMLS, i.e. they
+//! only change with a breaking release of OpenMLS and not whenever OpenMLS
+//! bumps its `tls_codec` dependency. Depending on `tls_codec` directly instead
+//! requires matching the exact version that OpenMLS uses.
+//!
+//! The derive macros expand to paths starting with `tls_codec::`. Import the
+//! re-exported crate into the module that uses them:
+//!
+//! ```
+//! use openmls::codec::{tls_codec, TlsDeserialize, TlsSerialize, TlsSize, VLBytes};
+//!
+//! #[derive(TlsSerialize, TlsDeserialize, TlsSize)]
+//! struct MyExtension {
+//!     payload: VLBytes,
+//!     counter: u32,
+//! }
+//! ```
+//!
+//! Variable-length vectors are encoded with a variable-length integer prefix
+//! that holds their length in bytes. [`serialize_vl_vec()`] and
+//! [`deserialize_vl_vec()`] encode and decode such vectors, e.g. to build the
+//! content of an [`UnknownExtension`](crate::extensions::UnknownExtension).
+
+pub use tls_codec::{
+    self, Deserialize, Error, SecretVLBytes, Serialize, Size, TlsDeserialize, TlsSerialize,
+    TlsSize, VLBytes,
+};
+
+/// Serialize the `items` as a variable-length vector, i.e. prefixed with
+/// their encoded length in bytes.
+///
+/// Returns an error if an item can't be serialized or the vector is too long.
+pub fn serialize_vl_vec<T: Serialize>(items: &[T]) -> Result<Vec<u8>, Error> {
+    let mut content = Vec::new();
+    for item in items {
+        item.tls_serialize(&mut content)?;
+    }
+    VLBytes::new(content).tls_serialize_detached()
+}
+
+/// Deserialize a variable-length vector from the beginning of `bytes` and
+/// advance `bytes` past it.
+///
+/// Returns an error if the length prefix is invalid or an item can't be
+/// deserialized.
+pub fn deserialize_vl_vec<T: Deserialize>(bytes: &mut &[u8]) -> Result<Vec<T>, Error> {
+    let content = VLBytes::tls_deserialize(bytes)?;
+    let mut content = content.as_slice();
+    let mut items = Vec::new();
+    while !content.is_empty() {
+        items.push(T::tls_deserialize(&mut content)?);
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::extensions::UnknownExtension;
+
+    #[derive(Debug, PartialEq, TlsSerialize, TlsDeserialize, TlsSize)]
+    struct Entry {
+        payload: VLBytes,
+        counter: u32,
+    }
+
+    #[test]
+    fn vl_vec() {
+        let entries = vec![
+            Entry {
+                payload: vec![1, 2, 3].into(),
+                counter: 1,
+            },
+            Entry {
+                payload: vec![].into(),
+                counter: 2,
+            },
+        ];
+
+        // The encoding matches the encoding of a `Vec`.
+        let serialized = serialize_vl_vec(&entries).unwrap();
+        assert_eq!(serialized, entries.tls_serialize_detached().unwrap());
+
+        // Trailing bytes are left in place.
+        let bytes = [serialized.as_slice(), &[0xff]].concat();
+        let mut reader = bytes.as_slice();
+        let deserialized: Vec<Entry> = deserialize_vl_vec(&mut reader).unwrap();
+        assert_eq!(deserialized, entries);
+        assert_eq!(reader, &[0xff]);
+
+        // Truncated vectors are rejected.
+        let mut reader = &serialized[..serialized.len() - 1];
+        assert!(deserialize_vl_vec::<Entry>(&mut reader).is_err());
+    }
+
+    #[test]
+    fn unknown_extension() {
+        let entry = Entry {
+            payload: vec![4, 5].into(),
+            counter: 3,
+        };
+        let extension = UnknownExtension::encode(&entry).unwrap();
+        assert_eq!(extension.decode::<Entry>().unwrap(), entry);
+
+        // Trailing bytes are rejected.
+        let mut extension = extension;
+        extension.0.push(0);
+        assert!(extension.decode::<Entry>().is_err());
+    }
+}
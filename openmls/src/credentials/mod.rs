@@ -37,6 +37,8 @@ use crate::ciphersuite::SignaturePublicKey;
 
 // Public
 pub mod errors;
+mod validator;
+pub use validator::*;
 
 /// CredentialType.
 ///
@@ -0,0 +1,67 @@
+//! # Credential validation
+//!
+//! OpenMLS only checks that the leaf nodes in a group are signed with the
+//! signature keys they contain. Whether a [`Credential`] is acceptable, e.g.
+//! whether its identity belongs to an allowed domain or whether its
+//! certificate chains to a pinned root, is up to the application.
+//!
+//! A [`CredentialValidator`] lets the application plug such a policy into an
+//! [`MlsGroup`](crate::group::MlsGroup) with
+//! [`MlsGroup::set_credential_validator()`](crate::group::MlsGroup::set_credential_validator()).
+//! The group then calls it for every leaf node that enters the tree through a
+//! commit it processes, i.e. for added members, for updated leaf nodes of
+//! existing members and for members that join through an external commit.
+
+use std::fmt::{Debug, Display};
+
+use super::Credential;
+use crate::ciphersuite::SignaturePublicKey;
+
+/// A policy that decides whether a [`Credential`] may enter a group.
+///
+/// See the [module documentation](self) for when it is called.
+pub trait CredentialValidator: Send + Sync {
+    /// Validates the `credential` of a new leaf node together with the
+    /// `signature_key` of the leaf node.
+    ///
+    /// Returns a [`CredentialRejection`] if the policy doesn't accept the
+    /// credential. The message that brought the leaf node into the group is
+    /// then rejected.
+    fn validate(
+        &self,
+        credential: &Credential,
+        signature_key: &SignaturePublicKey,
+    ) -> Result<(), CredentialRejection>;
+}
+
+impl Debug for dyn CredentialValidator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CredentialValidator")
+    }
+}
+
+/// The reason why a [`CredentialValidator`] rejected a [`Credential`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CredentialRejection {
+    reason: String,
+}
+
+impl CredentialRejection {
+    /// Create a new rejection with the given `reason`.
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+
+    /// Returns the reason of the rejection.
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+impl Display for CredentialRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.reason)
+    }
+}
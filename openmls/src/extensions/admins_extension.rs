@@ -0,0 +1,58 @@
+use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
+
+use super::{Deserialize, Serialize};
+use crate::credentials::Credential;
+
+/// # Admins Extension
+///
+/// The admins extension is a GroupContext extension that lists the
+/// credentials of the administrators of the group. It uses an extension type
+/// from the private use range.
+///
+/// If the group context contains the extension, all members only accept Add,
+/// Remove and `GroupContextExtensions` proposals from members whose
+/// credential is in the list, see
+/// [`PublicGroup::is_admin()`](crate::group::PublicGroup::is_admin()).
+/// Members can still remove themselves from the group, and proposals of the
+/// external senders of the group are accepted as well. Since changing the
+/// list requires a `GroupContextExtensions` proposal, only admins can add or
+/// remove admins. The list is managed with
+/// [`MlsGroup::set_admins()`](crate::group::MlsGroup::set_admins()).
+///
+/// ```c
+/// struct {
+///     Credential admins<V>;
+/// } Admins;
+/// ```
+#[derive(
+    PartialEq,
+    Eq,
+    Clone,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    TlsSerialize,
+    TlsDeserialize,
+    TlsSize,
+)]
+pub struct AdminsExtension {
+    admins: Vec<Credential>,
+}
+
+impl AdminsExtension {
+    /// Create a new admins extension with the given credentials.
+    pub fn new(admins: Vec<Credential>) -> Self {
+        Self { admins }
+    }
+
+    /// Get the credentials of the admins.
+    pub fn admins(&self) -> &[Credential] {
+        &self.admins
+    }
+
+    /// Returns `true` if the `credential` is the credential of an admin.
+    pub fn contains(&self, credential: &Credential) -> bool {
+        self.admins.contains(credential)
+    }
+}
@@ -0,0 +1,44 @@
+use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize, VLBytes};
+
+use super::{Deserialize, Serialize};
+
+/// # Application State Hash Extension
+///
+/// The application state hash extension is a GroupContext extension that
+/// contains a hash of state that the application replicates between the
+/// members of a group, e.g. the heads of a CRDT. It uses an extension type
+/// from the private use range.
+///
+/// Since all members agree on the GroupContext, members that process a commit
+/// setting this extension also agree on the application state hash. The
+/// extension is set with
+/// [`CommitBuilder::app_state_hash()`](crate::group::CommitBuilder::app_state_hash())
+/// and compared to the local state with
+/// [`StagedCommit::compare_app_state()`](crate::group::StagedCommit::compare_app_state()).
+/// OpenMLS doesn't interpret the hash.
+///
+/// ```c
+/// struct {
+///     opaque app_state_hash<V>;
+/// } AppStateHash;
+/// ```
+#[derive(
+    PartialEq, Eq, Clone, Debug, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+pub struct AppStateHashExtension {
+    app_state_hash: VLBytes,
+}
+
+impl AppStateHashExtension {
+    /// Create a new application state hash extension from a byte slice.
+    pub fn new(app_state_hash: &[u8]) -> Self {
+        Self {
+            app_state_hash: app_state_hash.into(),
+        }
+    }
+
+    /// Get the application state hash as byte slice.
+    pub fn as_slice(&self) -> &[u8] {
+        self.app_state_hash.as_slice()
+    }
+}
@@ -3,9 +3,10 @@ use std::io::{Read, Write};
 use tls_codec::{Deserialize, Serialize, Size, VLBytes};
 
 use crate::extensions::{
-    ApplicationIdExtension, Extension, ExtensionType, ExternalPubExtension,
-    ExternalSendersExtension, RatchetTreeExtension, RequiredCapabilitiesExtension,
-    UnknownExtension,
+    AdminsExtension, AppStateHashExtension, ApplicationIdExtension, CompressedRatchetTreeExtension,
+    CompromiseRecoveryExtension, CredentialBindingsExtension, Extension, ExtensionType,
+    ExternalPubExtension, ExternalSendersExtension, LastResortExtension, RatchetTreeExtension,
+    RequiredCapabilitiesExtension, ServiceMemberExtension, UnknownExtension,
 };
 
 fn vlbytes_len_len(length: usize) -> usize {
@@ -34,6 +35,13 @@ impl Size for Extension {
             Extension::RequiredCapabilities(e) => e.tls_serialized_len(),
             Extension::ExternalPub(e) => e.tls_serialized_len(),
             Extension::ExternalSenders(e) => e.tls_serialized_len(),
+            Extension::LastResort(e) => e.tls_serialized_len(),
+            Extension::CompressedRatchetTree(e) => e.tls_serialized_len(),
+            Extension::AppStateHash(e) => e.tls_serialized_len(),
+            Extension::CredentialBindings(e) => e.tls_serialized_len(),
+            Extension::CompromiseRecovery(e) => e.tls_serialized_len(),
+            Extension::ServiceMember(e) => e.tls_serialized_len(),
+            Extension::Admins(e) => e.tls_serialized_len(),
             Extension::Unknown(_, e) => e.0.len(),
         };
 
@@ -65,6 +73,13 @@ impl Serialize for Extension {
             Extension::RequiredCapabilities(e) => e.tls_serialize(&mut extension_data),
             Extension::ExternalPub(e) => e.tls_serialize(&mut extension_data),
             Extension::ExternalSenders(e) => e.tls_serialize(&mut extension_data),
+            Extension::LastResort(e) => e.tls_serialize(&mut extension_data),
+            Extension::CompressedRatchetTree(e) => e.tls_serialize(&mut extension_data),
+            Extension::AppStateHash(e) => e.tls_serialize(&mut extension_data),
+            Extension::CredentialBindings(e) => e.tls_serialize(&mut extension_data),
+            Extension::CompromiseRecovery(e) => e.tls_serialize(&mut extension_data),
+            Extension::ServiceMember(e) => e.tls_serialize(&mut extension_data),
+            Extension::Admins(e) => e.tls_serialize(&mut extension_data),
             Extension::Unknown(_, e) => extension_data
                 .write_all(e.0.as_slice())
                 .map(|_| e.0.len())
@@ -111,6 +126,27 @@ impl Deserialize for Extension {
             ExtensionType::ExternalSenders => Extension::ExternalSenders(
                 ExternalSendersExtension::tls_deserialize(&mut extension_data)?,
             ),
+            ExtensionType::LastResort => {
+                Extension::LastResort(LastResortExtension::tls_deserialize(&mut extension_data)?)
+            }
+            ExtensionType::CompressedRatchetTree => Extension::CompressedRatchetTree(
+                CompressedRatchetTreeExtension::tls_deserialize(&mut extension_data)?,
+            ),
+            ExtensionType::AppStateHash => Extension::AppStateHash(
+                AppStateHashExtension::tls_deserialize(&mut extension_data)?,
+            ),
+            ExtensionType::CredentialBindings => Extension::CredentialBindings(
+                CredentialBindingsExtension::tls_deserialize(&mut extension_data)?,
+            ),
+            ExtensionType::CompromiseRecovery => Extension::CompromiseRecovery(
+                CompromiseRecoveryExtension::tls_deserialize(&mut extension_data)?,
+            ),
+            ExtensionType::ServiceMember => Extension::ServiceMember(
+                ServiceMemberExtension::tls_deserialize(&mut extension_data)?,
+            ),
+            ExtensionType::Admins => {
+                Extension::Admins(AdminsExtension::tls_deserialize(&mut extension_data)?)
+            }
             ExtensionType::Unknown(unknown) => {
                 Extension::Unknown(unknown, UnknownExtension(extension_data.to_vec()))
             }
@@ -0,0 +1,75 @@
+#[cfg(feature = "tree-compression")]
+use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait};
+use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize, VLBytes};
+
+use super::{Deserialize, Serialize};
+#[cfg(feature = "tree-compression")]
+use crate::{
+    error::LibraryError,
+    extensions::errors::RatchetTreeError,
+    treesync::{RatchetTree, RatchetTreeIn},
+};
+
+/// The maximum size of a decompressed ratchet tree. Larger trees are rejected
+/// to bound the memory that a malicious extension can allocate.
+#[cfg(feature = "tree-compression")]
+const MAX_DECOMPRESSED_RATCHET_TREE_SIZE: usize = 1 << 28;
+
+/// # Compressed Ratchet Tree Extension.
+///
+/// The compressed ratchet tree extension contains the DEFLATE-compressed
+/// serialization of a ratchet tree, i.e. of the content of a
+/// [`RatchetTreeExtension`](super::RatchetTreeExtension). It uses an
+/// extension type from the private use range.
+///
+/// A group only uses this extension instead of the
+/// [`RatchetTreeExtension`](super::RatchetTreeExtension) if the
+/// [`RequiredCapabilitiesExtension`](super::RequiredCapabilitiesExtension) of
+/// the group contains [`ExtensionType::CompressedRatchetTree`](super::ExtensionType::CompressedRatchetTree),
+/// i.e. if all members and all new members support it. Compressing and
+/// decompressing requires the `tree-compression` feature.
+///
+/// ```c
+/// struct {
+///     opaque compressed_ratchet_tree<V>;
+/// } CompressedRatchetTree;
+/// ```
+#[derive(
+    PartialEq, Eq, Clone, Debug, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+pub struct CompressedRatchetTreeExtension {
+    compressed_ratchet_tree: VLBytes,
+}
+
+impl CompressedRatchetTreeExtension {
+    /// Build a new extension by compressing the `ratchet_tree`.
+    #[cfg(feature = "tree-compression")]
+    pub fn new(ratchet_tree: RatchetTree) -> Result<Self, LibraryError> {
+        let serialized_tree = ratchet_tree
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+        Ok(Self {
+            compressed_ratchet_tree: miniz_oxide::deflate::compress_to_vec(&serialized_tree, 6)
+                .into(),
+        })
+    }
+
+    /// Decompress and return the [`RatchetTreeIn`] from this extension.
+    ///
+    /// Returns an error if the tree can't be decompressed or deserialized.
+    #[cfg(feature = "tree-compression")]
+    pub fn ratchet_tree(&self) -> Result<RatchetTreeIn, RatchetTreeError> {
+        let serialized_tree = miniz_oxide::inflate::decompress_to_vec_with_limit(
+            self.compressed_ratchet_tree.as_slice(),
+            MAX_DECOMPRESSED_RATCHET_TREE_SIZE,
+        )
+        .map_err(|_| RatchetTreeError::Invalid)?;
+        RatchetTreeIn::tls_deserialize_exact(serialized_tree.as_slice())
+            .map_err(|_| RatchetTreeError::Invalid)
+    }
+
+    /// Returns the length of the compressed ratchet tree in bytes.
+    pub fn compressed_len(&self) -> usize {
+        self.compressed_ratchet_tree.as_slice().len()
+    }
+}
@@ -0,0 +1,42 @@
+use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
+
+use super::{Deserialize, Serialize};
+use crate::group::GroupEpoch;
+
+/// # Compromise Recovery Extension
+///
+/// The compromise recovery extension is a GroupContext extension that marks
+/// the commit setting it as an emergency re-keying after a suspected
+/// compromise of a device. It uses an extension type from the private use
+/// range.
+///
+/// The extension is set with
+/// [`MlsGroup::emergency_rekey()`](crate::group::MlsGroup::emergency_rekey())
+/// and contains the epoch that the commit starts. Since the extension is kept
+/// in the group context by later `GroupContextExtensions` proposals, only a
+/// commit that sets it for its own new epoch is a compromise recovery, see
+/// [`StagedCommit::is_compromise_recovery()`](crate::group::StagedCommit::is_compromise_recovery()).
+///
+/// ```c
+/// struct {
+///     uint64 epoch;
+/// } CompromiseRecovery;
+/// ```
+#[derive(
+    PartialEq, Eq, Clone, Debug, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+pub struct CompromiseRecoveryExtension {
+    epoch: GroupEpoch,
+}
+
+impl CompromiseRecoveryExtension {
+    /// Create a new compromise recovery extension for the given `epoch`.
+    pub fn new(epoch: GroupEpoch) -> Self {
+        Self { epoch }
+    }
+
+    /// Get the epoch started by the compromise recovery commit.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+}
@@ -0,0 +1,177 @@
+use openmls_traits::{crypto::OpenMlsCrypto, signatures::Signer, types::SignatureScheme};
+use tls_codec::{Serialize as TlsSerializeTrait, TlsDeserialize, TlsSerialize, TlsSize};
+
+use super::{Deserialize, Serialize};
+use crate::{
+    ciphersuite::{
+        signable::{Signable, SignatureError, SignedStruct, Verifiable},
+        OpenMlsSignaturePublicKey, Signature, SignaturePublicKey,
+    },
+    credentials::{Credential, CredentialWithKey},
+};
+
+const SIGNATURE_CREDENTIAL_BINDING_LABEL: &str = "CredentialBindingTBS";
+
+/// The to-be-signed content of a [`CredentialBinding`].
+///
+/// ```c
+/// struct {
+///     SignaturePublicKey pseudonym_signature_key;
+///     Credential credential;
+///     SignaturePublicKey signature_key;
+/// } CredentialBindingTBS;
+/// ```
+#[derive(
+    PartialEq, Eq, Clone, Debug, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+struct CredentialBindingTbs {
+    pseudonym_signature_key: SignaturePublicKey,
+    credential: Credential,
+    signature_key: SignaturePublicKey,
+}
+
+/// A proof that the leaf with a pseudonymous signature key belongs to the
+/// holder of a real credential.
+///
+/// The binding is signed with the signature key of the real credential over
+/// the signature key of the pseudonymous leaf. It is created alongside a
+/// pseudonymous key package with
+/// [`KeyPackageBuilder::build_pseudonymous()`](crate::key_packages::KeyPackageBuilder::build_pseudonymous())
+/// and handed to the adder out of band, so that key package directories only
+/// ever see the pseudonym. The adder includes it in the [`Welcome`] through
+/// [`CommitBuilder::credential_bindings()`](crate::group::CommitBuilder::credential_bindings()).
+///
+/// Note that OpenMLS only checks the signature. Whether the real credential
+/// and its signature key are acceptable has to be validated by the
+/// application, e.g. before passing the binding to the commit builder or
+/// after retrieving it from a [`Welcome`] with
+/// [`MlsGroup::credential_bindings_from_welcome()`](crate::group::MlsGroup::credential_bindings_from_welcome()).
+///
+/// ```c
+/// struct {
+///     CredentialBindingTBS payload;
+///     opaque signature<V>;
+/// } CredentialBinding;
+/// ```
+///
+/// [`Welcome`]: crate::messages::Welcome
+#[derive(
+    PartialEq, Eq, Clone, Debug, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+pub struct CredentialBinding {
+    payload: CredentialBindingTbs,
+    signature: Signature,
+}
+
+impl CredentialBinding {
+    /// Create a new binding of the pseudonymous leaf with the given
+    /// `pseudonym_signature_key` to the `credential_with_key`, signed with the
+    /// `signer` of the real credential.
+    pub fn new(
+        pseudonym_signature_key: SignaturePublicKey,
+        credential_with_key: CredentialWithKey,
+        signer: &impl Signer,
+    ) -> Result<Self, SignatureError> {
+        CredentialBindingTbs {
+            pseudonym_signature_key,
+            credential: credential_with_key.credential,
+            signature_key: credential_with_key.signature_key,
+        }
+        .sign(signer)
+    }
+
+    /// Returns the signature key of the pseudonymous leaf.
+    pub fn pseudonym_signature_key(&self) -> &SignaturePublicKey {
+        &self.payload.pseudonym_signature_key
+    }
+
+    /// Returns the real credential.
+    pub fn credential(&self) -> &Credential {
+        &self.payload.credential
+    }
+
+    /// Returns the signature key of the real credential.
+    pub fn signature_key(&self) -> &SignaturePublicKey {
+        &self.payload.signature_key
+    }
+
+    /// Verify the signature of the binding with the signature key of the real
+    /// credential.
+    pub fn verify(
+        &self,
+        crypto: &impl OpenMlsCrypto,
+        signature_scheme: SignatureScheme,
+    ) -> Result<(), SignatureError> {
+        let public_key = OpenMlsSignaturePublicKey::from_signature_key(
+            self.payload.signature_key.clone(),
+            signature_scheme,
+        );
+        self.verify_no_out(crypto, &public_key)
+    }
+}
+
+impl Signable for CredentialBindingTbs {
+    type SignedOutput = CredentialBinding;
+
+    fn unsigned_payload(&self) -> Result<Vec<u8>, tls_codec::Error> {
+        self.tls_serialize_detached()
+    }
+
+    fn label(&self) -> &str {
+        SIGNATURE_CREDENTIAL_BINDING_LABEL
+    }
+}
+
+impl SignedStruct<CredentialBindingTbs> for CredentialBinding {
+    fn from_payload(payload: CredentialBindingTbs, signature: Signature) -> Self {
+        Self { payload, signature }
+    }
+}
+
+impl Verifiable for CredentialBinding {
+    fn unsigned_payload(&self) -> Result<Vec<u8>, tls_codec::Error> {
+        self.payload.tls_serialize_detached()
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn label(&self) -> &str {
+        SIGNATURE_CREDENTIAL_BINDING_LABEL
+    }
+}
+
+/// # Credential Bindings Extension
+///
+/// The credential bindings extension is a GroupInfo extension that carries
+/// the [`CredentialBinding`]s of members that were added with pseudonymous
+/// key packages. It uses an extension type from the private use range.
+///
+/// OpenMLS only includes the extension in the GroupInfo that is encrypted in
+/// the [`Welcome`](crate::messages::Welcome), never in a GroupInfo that is
+/// returned for publication.
+///
+/// ```c
+/// struct {
+///     CredentialBinding bindings<V>;
+/// } CredentialBindings;
+/// ```
+#[derive(
+    PartialEq, Eq, Clone, Debug, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+pub struct CredentialBindingsExtension {
+    bindings: Vec<CredentialBinding>,
+}
+
+impl CredentialBindingsExtension {
+    /// Create a new credential bindings extension.
+    pub fn new(bindings: Vec<CredentialBinding>) -> Self {
+        Self { bindings }
+    }
+
+    /// Get the credential bindings.
+    pub fn bindings(&self) -> &[CredentialBinding] {
+        &self.bindings
+    }
+}
@@ -45,21 +45,23 @@ mod test {
             let mut external_pub_extensions = Vec::new();
 
             for _ in 0..8 {
-                let hpke_public_key = {
-                    let ikm = Secret::random(
-                        Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519,
-                        &backend,
-                        ProtocolVersion::default(),
-                    )
-                    .unwrap();
-                    let init_key = backend.crypto().derive_hpke_keypair(
+                let hpke_public_key =
+                    {
+                        let ikm = Secret::random(
+                            Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519,
+                            &backend,
+                            ProtocolVersion::default(),
+                        )
+                        .unwrap();
+                        let init_key = backend.crypto().derive_hpke_keypair(
                         Ciphersuite::hpke_config(
                             &Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519,
                         ),
                         ikm.as_slice(),
-                    );
-                    init_key.public
-                };
+                    )
+                    .unwrap();
+                        init_key.public
+                    };
 
                 external_pub_extensions.push(ExternalPubExtension::new(hpke_public_key.into()));
             }
@@ -29,11 +29,13 @@ impl ExternalSender {
         }
     }
 
-    pub(crate) fn credential(&self) -> &Credential {
+    /// Returns the credential of this external sender.
+    pub fn credential(&self) -> &Credential {
         &self.credential
     }
 
-    pub(crate) fn signature_key(&self) -> &SignaturePublicKey {
+    /// Returns the signature key of this external sender.
+    pub fn signature_key(&self) -> &SignaturePublicKey {
         &self.signature_key
     }
 }
@@ -0,0 +1,56 @@
+use std::io::{Read, Write};
+
+use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait, Size};
+
+use super::{Deserialize, Serialize};
+
+/// # Last Resort Extension
+///
+/// The last resort extension is a KeyPackage extension that marks a key
+/// package that may be used more than once. A client publishes such a key
+/// package next to its one-time key packages, so that it can still be added
+/// to groups when all of its one-time key packages are used up.
+///
+/// When a client joins a group with a last resort key package, the key
+/// package and its private keys stay in the key store, so that the same key
+/// package can be used for further [`Welcome`](crate::messages::Welcome)
+/// messages. The client should replace it with a fresh one soon after it was
+/// used, since all groups joined with it start with the same leaf
+/// encryption key.
+///
+/// The extension type is the one from draft-ietf-mls-extensions. The
+/// extension has no content.
+///
+/// ```c
+/// struct {} LastResort;
+/// ```
+#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LastResortExtension {}
+
+impl LastResortExtension {
+    /// Create a new last resort extension.
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Size for LastResortExtension {
+    fn tls_serialized_len(&self) -> usize {
+        0
+    }
+}
+
+impl TlsSerializeTrait for LastResortExtension {
+    fn tls_serialize<W: Write>(&self, _writer: &mut W) -> Result<usize, tls_codec::Error> {
+        Ok(0)
+    }
+}
+
+impl TlsDeserializeTrait for LastResortExtension {
+    fn tls_deserialize<R: Read>(_bytes: &mut R) -> Result<Self, tls_codec::Error>
+    where
+        Self: Sized,
+    {
+        Ok(Self {})
+    }
+}
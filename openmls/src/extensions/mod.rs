@@ -18,6 +18,13 @@
 //! - [`RatchetTreeExtension`] (GroupInfo extension)
 //! - [`RequiredCapabilitiesExtension`] (GroupContext extension)
 //! - [`ExternalPubExtension`] (GroupInfo extension)
+//! - [`LastResortExtension`] (KeyPackage extension)
+//! - [`CompressedRatchetTreeExtension`] (GroupInfo extension)
+//! - [`AppStateHashExtension`] (GroupContext extension)
+//! - [`CredentialBindingsExtension`] (GroupInfo extension)
+//! - [`CompromiseRecoveryExtension`] (GroupContext extension)
+//! - [`ServiceMemberExtension`] (LeafNode extension)
+//! - [`AdminsExtension`] (GroupContext extension)
 
 use std::{
     fmt::Debug,
@@ -26,26 +33,42 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 
+use crate::treesync::RatchetTreeIn;
+
 // Private
+mod admins_extension;
+mod app_state_hash_extension;
 mod application_id_extension;
 mod codec;
+mod compressed_ratchet_tree_extension;
+mod compromise_recovery_extension;
+mod credential_bindings_extension;
 mod external_pub_extension;
 mod external_sender_extension;
+mod last_resort_extension;
 mod ratchet_tree_extension;
 mod required_capabilities;
+mod service_member_extension;
 use errors::*;
 
 // Public
 pub mod errors;
 
 // Public re-exports
+pub use admins_extension::AdminsExtension;
+pub use app_state_hash_extension::AppStateHashExtension;
 pub use application_id_extension::ApplicationIdExtension;
+pub use compressed_ratchet_tree_extension::CompressedRatchetTreeExtension;
+pub use compromise_recovery_extension::CompromiseRecoveryExtension;
+pub use credential_bindings_extension::{CredentialBinding, CredentialBindingsExtension};
 pub use external_pub_extension::ExternalPubExtension;
 pub use external_sender_extension::{
     ExternalSender, ExternalSendersExtension, SenderExtensionIndex,
 };
+pub use last_resort_extension::LastResortExtension;
 pub use ratchet_tree_extension::RatchetTreeExtension;
 pub use required_capabilities::RequiredCapabilitiesExtension;
+pub use service_member_extension::ServiceMemberExtension;
 
 #[cfg(test)]
 mod test_extensions;
@@ -62,6 +85,13 @@ mod test_extensions;
 /// | 0x0003           | required_capabilities    | GC         | Y           | RFC XXXX  |
 /// | 0x0004           | external_pub             | GI         | Y           | RFC XXXX  |
 /// | 0x0005           | external_senders         | GC         | Y           | RFC XXXX  |
+/// | 0x000a           | last_resort              | KP         | Y           | draft-ietf-mls-extensions |
+/// | 0xff01           | compressed_ratchet_tree  | GI         | N           | OpenMLS   |
+/// | 0xff02           | app_state_hash           | GC         | N           | OpenMLS   |
+/// | 0xff03           | credential_bindings      | GI         | N           | OpenMLS   |
+/// | 0xff04           | compromise_recovery      | GC         | N           | OpenMLS   |
+/// | 0xff05           | service_member           | LN         | N           | OpenMLS   |
+/// | 0xff06           | admins                   | GC         | N           | OpenMLS   |
 /// | 0xff00  - 0xffff | Reserved for Private Use | N/A        | N/A         | RFC XXXX  |
 ///
 /// Note: OpenMLS does not provide a `Reserved` variant in [ExtensionType].
@@ -87,6 +117,38 @@ pub enum ExtensionType {
     /// of senders that are permitted to send external proposals to the group.
     ExternalSenders,
 
+    /// KeyPackage extension that marks a key package that may be used more
+    /// than once, as a last resort.
+    LastResort,
+
+    /// The compressed ratchet tree extension provides the whole public state
+    /// of the ratchet tree in compressed form. It uses an extension type from
+    /// the private use range.
+    CompressedRatchetTree,
+
+    /// Group context extension that contains a hash of the state the
+    /// application replicates between the members. It uses an extension type
+    /// from the private use range.
+    AppStateHash,
+
+    /// Group info extension that binds pseudonymous leaves to the real
+    /// credentials of their owners. It uses an extension type from the private
+    /// use range.
+    CredentialBindings,
+
+    /// Group context extension that marks a commit as an emergency re-keying
+    /// after a suspected compromise. It uses an extension type from the
+    /// private use range.
+    CompromiseRecovery,
+
+    /// Leaf node extension that marks the leaf of a service member, e.g. of a
+    /// bot. It uses an extension type from the private use range.
+    ServiceMember,
+
+    /// Group context extension that lists the credentials of the admins of
+    /// the group. It uses an extension type from the private use range.
+    Admins,
+
     /// A currently unknown extension type.
     Unknown(u16),
 }
@@ -125,6 +187,13 @@ impl From<u16> for ExtensionType {
             3 => ExtensionType::RequiredCapabilities,
             4 => ExtensionType::ExternalPub,
             5 => ExtensionType::ExternalSenders,
+            0x000a => ExtensionType::LastResort,
+            0xff01 => ExtensionType::CompressedRatchetTree,
+            0xff02 => ExtensionType::AppStateHash,
+            0xff03 => ExtensionType::CredentialBindings,
+            0xff04 => ExtensionType::CompromiseRecovery,
+            0xff05 => ExtensionType::ServiceMember,
+            0xff06 => ExtensionType::Admins,
             unknown => ExtensionType::Unknown(unknown),
         }
     }
@@ -138,6 +207,13 @@ impl From<ExtensionType> for u16 {
             ExtensionType::RequiredCapabilities => 3,
             ExtensionType::ExternalPub => 4,
             ExtensionType::ExternalSenders => 5,
+            ExtensionType::LastResort => 0x000a,
+            ExtensionType::CompressedRatchetTree => 0xff01,
+            ExtensionType::AppStateHash => 0xff02,
+            ExtensionType::CredentialBindings => 0xff03,
+            ExtensionType::CompromiseRecovery => 0xff04,
+            ExtensionType::ServiceMember => 0xff05,
+            ExtensionType::Admins => 0xff06,
             ExtensionType::Unknown(unknown) => unknown,
         }
     }
@@ -145,15 +221,25 @@ impl From<ExtensionType> for u16 {
 
 impl ExtensionType {
     /// Check whether an [`ExtensionType`] is supported or not.
+    ///
+    /// The [`ExtensionType::CompressedRatchetTree`] is only supported with the
+    /// `tree-compression` feature.
     pub fn is_supported(&self) -> bool {
-        matches!(
-            self,
+        match self {
             ExtensionType::ApplicationId
-                | ExtensionType::RatchetTree
-                | ExtensionType::RequiredCapabilities
-                | ExtensionType::ExternalPub
-                | ExtensionType::ExternalSenders
-        )
+            | ExtensionType::RatchetTree
+            | ExtensionType::RequiredCapabilities
+            | ExtensionType::ExternalPub
+            | ExtensionType::ExternalSenders
+            | ExtensionType::LastResort
+            | ExtensionType::AppStateHash
+            | ExtensionType::CredentialBindings
+            | ExtensionType::CompromiseRecovery
+            | ExtensionType::ServiceMember
+            | ExtensionType::Admins => true,
+            ExtensionType::CompressedRatchetTree => cfg!(feature = "tree-compression"),
+            ExtensionType::Unknown(_) => false,
+        }
     }
 }
 
@@ -188,6 +274,27 @@ pub enum Extension {
     /// A [`ExternalPubExtension`]
     ExternalSenders(ExternalSendersExtension),
 
+    /// A [`LastResortExtension`]
+    LastResort(LastResortExtension),
+
+    /// A [`CompressedRatchetTreeExtension`]
+    CompressedRatchetTree(CompressedRatchetTreeExtension),
+
+    /// An [`AppStateHashExtension`]
+    AppStateHash(AppStateHashExtension),
+
+    /// A [`CredentialBindingsExtension`]
+    CredentialBindings(CredentialBindingsExtension),
+
+    /// A [`CompromiseRecoveryExtension`]
+    CompromiseRecovery(CompromiseRecoveryExtension),
+
+    /// A [`ServiceMemberExtension`]
+    ServiceMember(ServiceMemberExtension),
+
+    /// An [`AdminsExtension`]
+    Admins(AdminsExtension),
+
     /// A currently unknown extension.
     Unknown(u16, UnknownExtension),
 }
@@ -196,6 +303,25 @@ pub enum Extension {
 #[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
 pub struct UnknownExtension(pub Vec<u8>);
 
+impl UnknownExtension {
+    /// Build the extension from the TLS encoding of a `value`, e.g. of a
+    /// custom extension type that derives the traits of
+    /// [`codec`](crate::codec).
+    ///
+    /// Returns an error if the value can't be serialized.
+    pub fn encode<T: tls_codec::Serialize>(value: &T) -> Result<Self, tls_codec::Error> {
+        Ok(Self(value.tls_serialize_detached()?))
+    }
+
+    /// Decode the content of the extension as a `T`.
+    ///
+    /// Returns an error if the content isn't exactly the TLS encoding of a
+    /// `T`.
+    pub fn decode<T: tls_codec::Deserialize>(&self) -> Result<T, tls_codec::Error> {
+        T::tls_deserialize_exact(self.0.as_slice())
+    }
+}
+
 /// A list of extensions with unique extension types.
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize, tls_codec::TlsSize)]
 pub struct Extensions {
@@ -335,6 +461,85 @@ impl Extensions {
             })
     }
 
+    /// Returns the ratchet tree from the [`RatchetTreeExtension`] or, with the
+    /// `tree-compression` feature, from the [`CompressedRatchetTreeExtension`]
+    /// if there is any.
+    ///
+    /// Returns an error if the compressed ratchet tree is invalid.
+    pub(crate) fn any_ratchet_tree(&self) -> Result<Option<RatchetTreeIn>, RatchetTreeError> {
+        if let Some(extension) = self.ratchet_tree() {
+            return Ok(Some(extension.ratchet_tree().clone()));
+        }
+        #[cfg(feature = "tree-compression")]
+        if let Some(extension) = self.compressed_ratchet_tree() {
+            return extension.ratchet_tree().map(Some);
+        }
+        Ok(None)
+    }
+
+    /// Get a reference to the [`CompressedRatchetTreeExtension`] if there is any.
+    pub fn compressed_ratchet_tree(&self) -> Option<&CompressedRatchetTreeExtension> {
+        self.find_by_type(ExtensionType::CompressedRatchetTree)
+            .and_then(|e| match e {
+                Extension::CompressedRatchetTree(e) => Some(e),
+                _ => None,
+            })
+    }
+
+    /// Get a reference to the [`AppStateHashExtension`] if there is any.
+    pub fn app_state_hash(&self) -> Option<&AppStateHashExtension> {
+        self.find_by_type(ExtensionType::AppStateHash)
+            .and_then(|e| match e {
+                Extension::AppStateHash(e) => Some(e),
+                _ => None,
+            })
+    }
+
+    /// Get a reference to the [`CredentialBindingsExtension`] if there is any.
+    pub fn credential_bindings(&self) -> Option<&CredentialBindingsExtension> {
+        self.find_by_type(ExtensionType::CredentialBindings)
+            .and_then(|e| match e {
+                Extension::CredentialBindings(e) => Some(e),
+                _ => None,
+            })
+    }
+
+    /// Get a reference to the [`CompromiseRecoveryExtension`] if there is any.
+    pub fn compromise_recovery(&self) -> Option<&CompromiseRecoveryExtension> {
+        self.find_by_type(ExtensionType::CompromiseRecovery)
+            .and_then(|e| match e {
+                Extension::CompromiseRecovery(e) => Some(e),
+                _ => None,
+            })
+    }
+
+    /// Get a reference to the [`ServiceMemberExtension`] if there is any.
+    pub fn service_member(&self) -> Option<&ServiceMemberExtension> {
+        self.find_by_type(ExtensionType::ServiceMember)
+            .and_then(|e| match e {
+                Extension::ServiceMember(e) => Some(e),
+                _ => None,
+            })
+    }
+
+    /// Get a reference to the [`AdminsExtension`] if there is any.
+    pub fn admins(&self) -> Option<&AdminsExtension> {
+        self.find_by_type(ExtensionType::Admins)
+            .and_then(|e| match e {
+                Extension::Admins(e) => Some(e),
+                _ => None,
+            })
+    }
+
+    /// Get a reference to the [`LastResortExtension`] if there is any.
+    pub fn last_resort(&self) -> Option<&LastResortExtension> {
+        self.find_by_type(ExtensionType::LastResort)
+            .and_then(|e| match e {
+                Extension::LastResort(e) => Some(e),
+                _ => None,
+            })
+    }
+
     /// Get a reference to the [`RequiredCapabilitiesExtension`] if there is any.
     pub fn required_capabilities(&self) -> Option<&RequiredCapabilitiesExtension> {
         self.find_by_type(ExtensionType::RequiredCapabilities)
@@ -437,6 +642,13 @@ impl Extension {
             Extension::RequiredCapabilities(_) => ExtensionType::RequiredCapabilities,
             Extension::ExternalPub(_) => ExtensionType::ExternalPub,
             Extension::ExternalSenders(_) => ExtensionType::ExternalSenders,
+            Extension::LastResort(_) => ExtensionType::LastResort,
+            Extension::CompressedRatchetTree(_) => ExtensionType::CompressedRatchetTree,
+            Extension::AppStateHash(_) => ExtensionType::AppStateHash,
+            Extension::CredentialBindings(_) => ExtensionType::CredentialBindings,
+            Extension::CompromiseRecovery(_) => ExtensionType::CompromiseRecovery,
+            Extension::ServiceMember(_) => ExtensionType::ServiceMember,
+            Extension::Admins(_) => ExtensionType::Admins,
             Extension::Unknown(kind, _) => ExtensionType::Unknown(*kind),
         }
     }
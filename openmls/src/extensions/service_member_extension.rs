@@ -0,0 +1,54 @@
+use std::io::{Read, Write};
+
+use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait, Size};
+
+use super::{Deserialize, Serialize};
+
+/// # Service Member Extension
+///
+/// The service member extension is a LeafNode extension that marks the leaf
+/// of a service member, e.g. of a bot, as opposed to the leaf of a human
+/// member. It uses an extension type from the private use range and has no
+/// content.
+///
+/// If the group requires support for the extension through its
+/// [`RequiredCapabilitiesExtension`](super::RequiredCapabilitiesExtension),
+/// all members place added service members in the right-most blank leaf
+/// instead of the left-most one. Service members then gather at the right
+/// edge of the tree and don't grow the copaths of the other members, which
+/// keeps commits small in groups with many short-lived service members. The
+/// placement of members that join through an external commit is not changed.
+///
+/// ```c
+/// struct {} ServiceMember;
+/// ```
+#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ServiceMemberExtension {}
+
+impl ServiceMemberExtension {
+    /// Create a new service member extension.
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Size for ServiceMemberExtension {
+    fn tls_serialized_len(&self) -> usize {
+        0
+    }
+}
+
+impl TlsSerializeTrait for ServiceMemberExtension {
+    fn tls_serialize<W: Write>(&self, _writer: &mut W) -> Result<usize, tls_codec::Error> {
+        Ok(0)
+    }
+}
+
+impl TlsDeserializeTrait for ServiceMemberExtension {
+    fn tls_deserialize<R: Read>(_bytes: &mut R) -> Result<Self, tls_codec::Error>
+    where
+        Self: Sized,
+    {
+        Ok(Self {})
+    }
+}
@@ -112,6 +112,7 @@ fn ratchet_tree_extension(ciphersuite: Ciphersuite, backend: &impl OpenMlsCrypto
         bob_key_package_bundle,
         backend,
         ResumptionPskStore::new(1024),
+        None,
     ) {
         Ok(g) => g,
         Err(e) => panic!("Could not join group with ratchet tree extension {e}"),
@@ -186,6 +187,7 @@ fn ratchet_tree_extension(ciphersuite: Ciphersuite, backend: &impl OpenMlsCrypto
         bob_key_package_bundle,
         backend,
         ResumptionPskStore::new(1024),
+        None,
     )
     .err();
 
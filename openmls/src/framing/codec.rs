@@ -5,7 +5,7 @@ use crate::versions::ProtocolVersion;
 
 use super::{
     mls_auth_content::FramedContentAuthData, mls_content_in::FramedContentBodyIn,
-    private_message_in::PrivateMessageContentIn, *,
+    private_message_in::PrivateMessageContentIn, shared_bytes::read_vl_length, *,
 };
 
 impl Size for PrivateMessageContent {
@@ -60,6 +60,38 @@ pub(super) fn deserialize_ciphertext_content<R: Read>(
     Ok(PrivateMessageContentIn { content, auth })
 }
 
+/// Like [`deserialize_ciphertext_content()`], but takes ownership of the
+/// decrypted `bytes`, so that application data is moved out of them instead of
+/// being copied.
+pub(super) fn deserialize_owned_ciphertext_content(
+    mut bytes: Vec<u8>,
+    content_type: ContentType,
+) -> Result<PrivateMessageContentIn, tls_codec::Error> {
+    if content_type != ContentType::Application {
+        return deserialize_ciphertext_content(&mut bytes.as_slice(), content_type);
+    }
+
+    let (header_len, data_len) = read_vl_length(&mut bytes.as_slice())?;
+    let data_end = header_len
+        .checked_add(data_len)
+        .filter(|data_end| *data_end <= bytes.len())
+        .ok_or(Error::EndOfStream)?;
+    let mut remainder = &bytes[data_end..];
+    let auth = FramedContentAuthData::deserialize(&mut remainder, content_type)?;
+
+    // ValSem011: PrivateMessageContentIn padding must be all-zero.
+    if !remainder.iter().all(|byte| *byte == 0x00) {
+        return Err(Error::InvalidInput);
+    }
+
+    bytes.truncate(data_end);
+    bytes.drain(..header_len);
+    Ok(PrivateMessageContentIn {
+        content: FramedContentBodyIn::Application(bytes.into()),
+        auth,
+    })
+}
+
 impl Deserialize for MlsMessageIn {
     fn tls_deserialize<R: Read>(bytes: &mut R) -> Result<Self, tls_codec::Error> {
         let version = ProtocolVersion::tls_deserialize(bytes)?;
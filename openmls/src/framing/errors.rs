@@ -77,3 +77,92 @@ pub enum MlsMessageError {
     #[error("The message (or one of its parts) is too large to be encoded.")]
     UnableToEncode,
 }
+
+/// Decoding error with the location of the malformed field.
+///
+/// Returned by the traced deserialization functions, e.g.
+/// [`MlsMessageIn::tls_deserialize_traced()`](crate::framing::MlsMessageIn::tls_deserialize_traced).
+#[cfg(feature = "diagnostics")]
+#[derive(Error, Debug, PartialEq, Clone)]
+#[error("Error decoding {path} at byte offset {offset}: {error:?}")]
+pub struct DecodeError {
+    offset: usize,
+    path: String,
+    error: tls_codec::Error,
+}
+
+#[cfg(feature = "diagnostics")]
+impl DecodeError {
+    pub(crate) fn new(offset: usize, path: String, error: tls_codec::Error) -> Self {
+        Self {
+            offset,
+            path,
+            error,
+        }
+    }
+
+    /// Returns the byte offset at which decoding failed.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the path of the field that could not be decoded, e.g.
+    /// `MlsMessage.PublicMessage.content.body.Commit.proposals[3].key_package.leaf_node.capabilities`.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the underlying [`tls_codec::Error`].
+    pub fn tls_codec_error(&self) -> &tls_codec::Error {
+        &self.error
+    }
+}
+
+/// A [`DecodeLimits`](crate::framing::DecodeLimits) limit was exceeded.
+#[derive(Error, Debug, PartialEq, Eq, Clone)]
+pub enum DecodeLimitError {
+    /// The message is larger than the maximum message size.
+    #[error("The message has {size} bytes, the maximum is {max}.")]
+    MessageTooLarge {
+        /// The size of the message in bytes.
+        size: usize,
+        /// The maximum message size.
+        max: usize,
+    },
+    /// The commit has more proposals than allowed.
+    #[error("The commit has {proposals} proposals, the maximum is {max}.")]
+    TooManyProposals {
+        /// The number of proposals of the commit.
+        proposals: usize,
+        /// The maximum number of proposals.
+        max: usize,
+    },
+    /// An extension list has more extensions than allowed.
+    #[error("An extension list has {extensions} extensions, the maximum is {max}.")]
+    TooManyExtensions {
+        /// The number of extensions of the list.
+        extensions: usize,
+        /// The maximum number of extensions.
+        max: usize,
+    },
+    /// The update path has more nodes than allowed.
+    #[error("The update path has {length} nodes, the maximum is {max}.")]
+    PathTooLong {
+        /// The number of nodes of the update path.
+        length: usize,
+        /// The maximum number of nodes.
+        max: usize,
+    },
+}
+
+/// Error decoding a message within
+/// [`DecodeLimits`](crate::framing::DecodeLimits).
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum MessageDecodeError {
+    /// The message could not be decoded.
+    #[error("The message could not be decoded: {0:?}")]
+    DecodingError(#[from] tls_codec::Error),
+    /// See [`DecodeLimitError`] for more details.
+    #[error(transparent)]
+    LimitExceeded(#[from] DecodeLimitError),
+}
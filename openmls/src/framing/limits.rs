@@ -0,0 +1,238 @@
+//! # Decode limits
+//!
+//! Incoming messages are decoded before they can be authenticated, so a
+//! malicious peer or Delivery Service can send messages that are expensive
+//! to decode and process, e.g. commits with millions of proposals. The
+//! [`DecodeLimits`] bound the size and the structure of incoming messages.
+//!
+//! The limits are enforced by [`MlsMessageIn::tls_deserialize_with_limits()`]
+//! and, for the content of [`PrivateMessage`](super::PrivateMessage)s that is
+//! only available after decryption, when a message is processed by a group
+//! with the limits of its [`MlsGroupConfig`](crate::group::MlsGroupConfig).
+//! The size is checked before anything is decoded, so the allocations made
+//! while decoding are bounded by the maximum message size.
+
+use serde::{Deserialize, Serialize};
+use tls_codec::Deserialize as TlsDeserializeTrait;
+
+use super::{
+    errors::{DecodeLimitError, MessageDecodeError},
+    mls_content_in::FramedContentBodyIn,
+    MlsMessageIn, MlsMessageInBody,
+};
+use crate::{
+    extensions::Extensions,
+    messages::proposals_in::{ProposalIn, ProposalOrRefIn},
+    treesync::node::leaf_node::LeafNodeIn,
+};
+
+/// The default maximum size of an incoming message in bytes (16 MiB).
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+/// The default maximum number of proposals of a commit.
+const DEFAULT_MAX_PROPOSALS: usize = 10_000;
+/// The default maximum number of extensions of an extension list.
+const DEFAULT_MAX_EXTENSIONS: usize = 256;
+/// The default maximum number of nodes of an update path. Trees with 2^32
+/// leaves have 32 nodes in a direct path.
+const DEFAULT_MAX_PATH_LENGTH: usize = 32;
+
+/// Upper bounds for incoming messages. See the
+/// [module documentation](self) for where they are enforced.
+///
+///  - max_message_size:
+/// The maximum size of an encoded message in bytes. The default is 16 MiB.
+///  - max_proposals:
+/// The maximum number of proposals (by value or by reference) of a commit.
+/// The default is 10000.
+///  - max_extensions:
+/// The maximum number of extensions of each extension list, e.g. of a key
+/// package, a leaf node or a group context. The default is 256.
+///  - max_path_length:
+/// The maximum number of nodes of the update path of a commit. The default
+/// is 32, which doesn't restrict any valid path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodeLimits {
+    max_message_size: usize,
+    max_proposals: usize,
+    max_extensions: usize,
+    max_path_length: usize,
+}
+
+impl Default for DecodeLimits {
+    fn default() -> Self {
+        Self {
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            max_proposals: DEFAULT_MAX_PROPOSALS,
+            max_extensions: DEFAULT_MAX_EXTENSIONS,
+            max_path_length: DEFAULT_MAX_PATH_LENGTH,
+        }
+    }
+}
+
+impl DecodeLimits {
+    /// Create new limits with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum size of an encoded message in bytes.
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size;
+        self
+    }
+
+    /// Set the maximum number of proposals of a commit.
+    pub fn with_max_proposals(mut self, max_proposals: usize) -> Self {
+        self.max_proposals = max_proposals;
+        self
+    }
+
+    /// Set the maximum number of extensions of an extension list.
+    pub fn with_max_extensions(mut self, max_extensions: usize) -> Self {
+        self.max_extensions = max_extensions;
+        self
+    }
+
+    /// Set the maximum number of nodes of an update path.
+    pub fn with_max_path_length(mut self, max_path_length: usize) -> Self {
+        self.max_path_length = max_path_length;
+        self
+    }
+
+    /// Get the maximum size of an encoded message in bytes.
+    pub fn max_message_size(&self) -> usize {
+        self.max_message_size
+    }
+
+    /// Get the maximum number of proposals of a commit.
+    pub fn max_proposals(&self) -> usize {
+        self.max_proposals
+    }
+
+    /// Get the maximum number of extensions of an extension list.
+    pub fn max_extensions(&self) -> usize {
+        self.max_extensions
+    }
+
+    /// Get the maximum number of nodes of an update path.
+    pub fn max_path_length(&self) -> usize {
+        self.max_path_length
+    }
+
+    /// Checks the size of an encoded message.
+    pub(crate) fn check_message_size(&self, size: usize) -> Result<(), DecodeLimitError> {
+        if size > self.max_message_size {
+            return Err(DecodeLimitError::MessageTooLarge {
+                size,
+                max: self.max_message_size,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks the structure of a decoded message. The content of private
+    /// messages and the group info of Welcome messages are encrypted and are
+    /// not checked.
+    pub(crate) fn check_message(&self, message: &MlsMessageIn) -> Result<(), DecodeLimitError> {
+        match &message.body {
+            MlsMessageInBody::PublicMessage(public_message) => {
+                self.check_content_body(public_message.content())
+            }
+            MlsMessageInBody::PrivateMessage(_) | MlsMessageInBody::Welcome(_) => Ok(()),
+            MlsMessageInBody::GroupInfo(group_info) => {
+                self.check_extensions(group_info.group_context_extensions())?;
+                self.check_extensions(group_info.extensions())
+            }
+            MlsMessageInBody::KeyPackage(key_package) => {
+                self.check_extensions(key_package.extensions())?;
+                self.check_leaf_node(key_package.leaf_node())
+            }
+        }
+    }
+
+    /// Checks the structure of the body of a decoded (and decrypted) message.
+    pub(crate) fn check_content_body(
+        &self,
+        body: &FramedContentBodyIn,
+    ) -> Result<(), DecodeLimitError> {
+        match body {
+            FramedContentBodyIn::Application(_) => Ok(()),
+            FramedContentBodyIn::Proposal(proposal) => self.check_proposal(proposal),
+            FramedContentBodyIn::Commit(commit) => {
+                let proposals = commit.proposals();
+                if proposals.len() > self.max_proposals {
+                    return Err(DecodeLimitError::TooManyProposals {
+                        proposals: proposals.len(),
+                        max: self.max_proposals,
+                    });
+                }
+                for proposal_or_ref in proposals {
+                    if let ProposalOrRefIn::Proposal(proposal) = proposal_or_ref {
+                        self.check_proposal(proposal)?;
+                    }
+                }
+                if let Some(path) = commit.path() {
+                    let length = path.nodes().len();
+                    if length > self.max_path_length {
+                        return Err(DecodeLimitError::PathTooLong {
+                            length,
+                            max: self.max_path_length,
+                        });
+                    }
+                    self.check_leaf_node(path.leaf_node())?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn check_proposal(&self, proposal: &ProposalIn) -> Result<(), DecodeLimitError> {
+        match proposal {
+            ProposalIn::Add(add_proposal) => {
+                let key_package = add_proposal.key_package();
+                self.check_extensions(key_package.extensions())?;
+                self.check_leaf_node(key_package.leaf_node())
+            }
+            ProposalIn::Update(update_proposal) => {
+                self.check_leaf_node(update_proposal.leaf_node())
+            }
+            ProposalIn::GroupContextExtensions(group_context_extensions_proposal) => {
+                self.check_extensions(group_context_extensions_proposal.extensions())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_leaf_node(&self, leaf_node: &LeafNodeIn) -> Result<(), DecodeLimitError> {
+        self.check_extensions(leaf_node.extensions())
+    }
+
+    fn check_extensions(&self, extensions: &Extensions) -> Result<(), DecodeLimitError> {
+        let count = extensions.iter().count();
+        if count > self.max_extensions {
+            return Err(DecodeLimitError::TooManyExtensions {
+                extensions: count,
+                max: self.max_extensions,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl MlsMessageIn {
+    /// Deserialize an [`MlsMessageIn`] from `bytes` within the given
+    /// [`DecodeLimits`].
+    ///
+    /// Returns [`MessageDecodeError::LimitExceeded`] if the message is larger
+    /// than the maximum message size, in which case it isn't decoded at all,
+    /// or if its structure exceeds one of the other limits.
+    pub fn tls_deserialize_with_limits(
+        bytes: &[u8],
+        limits: &DecodeLimits,
+    ) -> Result<Self, MessageDecodeError> {
+        limits.check_message_size(bytes.len())?;
+        let message = Self::tls_deserialize_exact(bytes)?;
+        limits.check_message(&message)?;
+        Ok(message)
+    }
+}
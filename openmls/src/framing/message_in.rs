@@ -14,10 +14,14 @@
 //! If an [`MlsMessageIn`] contains a [`PublicMessage`] or [`PrivateMessage`],
 //! can be used to determine which group can be used to process the message.
 
+use bytes::{Buf, Bytes};
+use openmls_traits::{crypto::OpenMlsCrypto, types::Ciphersuite};
+use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait};
+
 use super::*;
 use crate::{
-    key_packages::KeyPackageIn, messages::group_info::VerifiableGroupInfo,
-    versions::ProtocolVersion,
+    ciphersuite::hash_ref::make_proposal_ref, error::LibraryError, key_packages::KeyPackageIn,
+    messages::group_info::VerifiableGroupInfo, versions::ProtocolVersion,
 };
 
 /// Before use with the [`MlsGroup`] API, the message has to be unpacked via
@@ -105,6 +109,97 @@ impl MlsMessageIn {
         }
     }
 
+    /// Returns the protocol version.
+    pub fn version(&self) -> ProtocolVersion {
+        self.version
+    }
+
+    /// Returns the (unverified) group ID of a [`PublicMessageIn`],
+    /// [`PrivateMessageIn`] or [`VerifiableGroupInfo`], e.g. to route the
+    /// message to its group without processing it. Returns `None` for other
+    /// messages.
+    pub fn group_id(&self) -> Option<&GroupId> {
+        match &self.body {
+            MlsMessageInBody::PublicMessage(m) => Some(m.group_id()),
+            MlsMessageInBody::PrivateMessage(m) => Some(m.group_id()),
+            MlsMessageInBody::GroupInfo(group_info) => Some(group_info.group_id()),
+            MlsMessageInBody::Welcome(_) | MlsMessageInBody::KeyPackage(_) => None,
+        }
+    }
+
+    /// Returns the (unverified) epoch of a [`PublicMessageIn`],
+    /// [`PrivateMessageIn`] or [`VerifiableGroupInfo`]. Returns `None` for
+    /// other messages.
+    pub fn epoch(&self) -> Option<GroupEpoch> {
+        match &self.body {
+            MlsMessageInBody::PublicMessage(m) => Some(m.epoch()),
+            MlsMessageInBody::PrivateMessage(m) => Some(m.epoch()),
+            MlsMessageInBody::GroupInfo(group_info) => Some(group_info.epoch()),
+            MlsMessageInBody::Welcome(_) | MlsMessageInBody::KeyPackage(_) => None,
+        }
+    }
+
+    /// Returns the (unverified) content type of a [`PublicMessageIn`] or
+    /// [`PrivateMessageIn`]. Returns `None` for other messages.
+    pub fn content_type(&self) -> Option<ContentType> {
+        match &self.body {
+            MlsMessageInBody::PublicMessage(m) => Some(m.content_type()),
+            MlsMessageInBody::PrivateMessage(m) => Some(m.content_type()),
+            _ => None,
+        }
+    }
+
+    /// Returns the (unverified) sender of a [`PublicMessageIn`]. Returns
+    /// `None` for other messages, including [`PrivateMessageIn`]s, whose
+    /// sender is encrypted.
+    pub fn sender(&self) -> Option<&Sender> {
+        match &self.body {
+            MlsMessageInBody::PublicMessage(m) => Some(m.sender()),
+            _ => None,
+        }
+    }
+
+    /// Deserialize an [`MlsMessageIn`] from a buffer that holds exactly one
+    /// message, e.g. as received from the DS.
+    ///
+    /// In contrast to the regular deserialization, the ciphertext of a
+    /// [`PrivateMessageIn`] isn't copied out of the buffer, but keeps a
+    /// reference to it. Servers and relays that handle many large messages
+    /// can pass the [`Bytes`] of their network stack to avoid copying the
+    /// message bodies.
+    pub fn from_bytes(bytes: impl Into<Bytes>) -> Result<Self, tls_codec::Error> {
+        let mut buffer = bytes.into();
+        let mut reader = buffer.as_ref();
+        let version = ProtocolVersion::tls_deserialize(&mut reader)?;
+        let wire_format = WireFormat::tls_deserialize(&mut reader)?;
+        if wire_format != WireFormat::PrivateMessage {
+            return Self::tls_deserialize_exact(buffer.as_ref());
+        }
+
+        let consumed = buffer.len() - reader.len();
+        buffer.advance(consumed);
+        let private_message = PrivateMessageIn::split_from(&mut buffer)?;
+        if !buffer.is_empty() {
+            return Err(tls_codec::Error::TrailingData);
+        }
+        Ok(Self {
+            version,
+            body: MlsMessageInBody::PrivateMessage(private_message),
+        })
+    }
+
+    /// Deserialize an [`MlsMessageIn`] from `bytes`.
+    ///
+    /// In contrast to the regular deserialization, the returned
+    /// [`DecodeError`] contains the byte offset and the path of the field that
+    /// could not be decoded, e.g.
+    /// `MlsMessage.PublicMessage.content.body.Commit.proposals[3].key_package.leaf_node.capabilities`.
+    /// The additional work is only done if the deserialization fails.
+    #[cfg(feature = "diagnostics")]
+    pub fn tls_deserialize_traced(bytes: &[u8]) -> Result<Self, DecodeError> {
+        tracing_decoder::deserialize_traced(bytes, "MlsMessage", tracing_decoder::trace_mls_message)
+    }
+
     /// Extract the content of an [`MlsMessageIn`] after deserialization for use
     /// with the [`MlsGroup`] API.
     pub fn extract(self) -> MlsMessageInBody {
@@ -170,7 +265,7 @@ impl MlsMessageIn {
 /// Enum containing a message for use with `process_message` and an
 /// [`MlsGroup`]. Both [`PublicMessage`] and [`PrivateMessage`] implement
 /// [`Into<ProtocolMessage>`].
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ProtocolMessage {
     /// A [`ProtocolMessage`] containing a [`PrivateMessage`].
     PrivateMessage(PrivateMessageIn),
@@ -187,7 +282,8 @@ impl ProtocolMessage {
         }
     }
 
-    /// Returns the group ID.
+    /// Returns the (unverified) group ID, e.g. to route the message to its
+    /// group.
     pub fn group_id(&self) -> &GroupId {
         match self {
             ProtocolMessage::PrivateMessage(ref m) => m.group_id(),
@@ -195,7 +291,7 @@ impl ProtocolMessage {
         }
     }
 
-    /// Returns the epoch.
+    /// Returns the (unverified) epoch.
     pub fn epoch(&self) -> GroupEpoch {
         match self {
             ProtocolMessage::PrivateMessage(ref m) => m.epoch(),
@@ -203,7 +299,7 @@ impl ProtocolMessage {
         }
     }
 
-    /// Returns the content type.
+    /// Returns the (unverified) content type.
     pub fn content_type(&self) -> ContentType {
         match self {
             ProtocolMessage::PrivateMessage(ref m) => m.content_type(),
@@ -211,6 +307,15 @@ impl ProtocolMessage {
         }
     }
 
+    /// Returns the (unverified) sender of a [`PublicMessageIn`], or `None`
+    /// for a [`PrivateMessageIn`], whose sender is encrypted.
+    pub fn sender(&self) -> Option<&Sender> {
+        match self {
+            ProtocolMessage::PrivateMessage(_) => None,
+            ProtocolMessage::PublicMessage(ref m) => Some(m.sender()),
+        }
+    }
+
     /// Returns `true` if this is either an external proposal or external commit
     pub fn is_external(&self) -> bool {
         match &self {
@@ -229,6 +334,75 @@ impl ProtocolMessage {
     pub fn is_handshake_message(&self) -> bool {
         self.content_type().is_handshake_message()
     }
+
+    /// Returns the hash of the TLS serialized `MLSMessage` with the given
+    /// `version` that contains this message, computed with the hash function
+    /// of the `ciphersuite`.
+    pub(crate) fn digest(
+        &self,
+        crypto: &impl OpenMlsCrypto,
+        ciphersuite: Ciphersuite,
+        version: ProtocolVersion,
+    ) -> Result<Vec<u8>, LibraryError> {
+        let mut serialized = version
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+        self.wire_format()
+            .tls_serialize(&mut serialized)
+            .map_err(LibraryError::missing_bound_check)?;
+        match self {
+            ProtocolMessage::PrivateMessage(m) => m.tls_serialize(&mut serialized),
+            ProtocolMessage::PublicMessage(m) => m.tls_serialize(&mut serialized),
+        }
+        .map_err(LibraryError::missing_bound_check)?;
+        crypto
+            .hash(ciphersuite.hash_algorithm(), &serialized)
+            .map_err(LibraryError::unexpected_crypto_error)
+    }
+
+    /// Computes the [`ProposalRef`] of the proposal in this message, i.e. the
+    /// reference that commits use to cover the proposal, with the hash
+    /// function of the `ciphersuite` of the group.
+    ///
+    /// The reference is computed from the unverified message, e.g. by a DS
+    /// that orders the proposals of a group. Returns
+    /// [`ProposalRefError::AuthenticatedContentHasWrongType`] if the message
+    /// doesn't contain a proposal and [`ProposalRefError::EncryptedMessage`]
+    /// for a [`PrivateMessageIn`]. The members of the group get the
+    /// reference of an encrypted proposal from the [`QueuedProposal`] that is
+    /// returned when processing it.
+    ///
+    /// [`QueuedProposal`]: crate::group::QueuedProposal
+    pub fn proposal_ref(
+        &self,
+        crypto: &impl OpenMlsCrypto,
+        ciphersuite: Ciphersuite,
+    ) -> Result<ProposalRef, ProposalRefError> {
+        let public_message = match self {
+            ProtocolMessage::PublicMessage(public_message) => public_message,
+            ProtocolMessage::PrivateMessage(_) => return Err(ProposalRefError::EncryptedMessage),
+        };
+        if public_message.content_type() != ContentType::Proposal {
+            return Err(ProposalRefError::AuthenticatedContentHasWrongType {
+                wrong: public_message.content_type(),
+            });
+        }
+        // The reference is computed over the `AuthenticatedContent`, which
+        // is the message without its membership tag
+        let mut encoded = WireFormat::PublicMessage
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+        public_message
+            .content
+            .tls_serialize(&mut encoded)
+            .map_err(LibraryError::missing_bound_check)?;
+        public_message
+            .auth
+            .tls_serialize(&mut encoded)
+            .map_err(LibraryError::missing_bound_check)?;
+        make_proposal_ref(&encoded, ciphersuite, crypto)
+            .map_err(|error| LibraryError::unexpected_crypto_error(error).into())
+    }
 }
 
 impl From<PrivateMessageIn> for ProtocolMessage {
@@ -138,6 +138,25 @@ impl MlsMessageOut {
         }
     }
 
+    /// Splits a Welcome message into Welcome messages for at most
+    /// `max_recipients` new members each. See [`Welcome::split()`] for
+    /// details.
+    ///
+    /// Returns `None` if this is not a Welcome message.
+    pub fn split_welcome(&self, max_recipients: usize) -> Option<Vec<MlsMessageOut>> {
+        match &self.body {
+            MlsMessageOutBody::Welcome(welcome) => Some(
+                welcome
+                    .clone()
+                    .split(max_recipients)
+                    .into_iter()
+                    .map(|welcome| MlsMessageOut::from_welcome(welcome, self.version))
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
     /// Serializes the message to a byte vector. Returns [`MlsMessageError::UnableToEncode`] on failure.
     pub fn to_bytes(&self) -> Result<Vec<u8>, MlsMessageError> {
         self.tls_serialize_detached()
@@ -5,7 +5,7 @@
 
 use std::io::{Read, Write};
 
-use openmls_traits::signatures::Signer;
+use openmls_traits::signatures::{AsyncSigner, Signer};
 use serde::{Deserialize, Serialize};
 use tls_codec::{
     Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait, Size, TlsSerialize, TlsSize,
@@ -88,13 +88,16 @@ pub(crate) struct AuthenticatedContent {
 
 impl AuthenticatedContent {
     /// Convenience function for creating a [`VerifiableAuthenticatedContent`].
+    /// The `serialized_context` has to be the serialization of the `context`,
+    /// which is passed in so that it isn't serialized again for every message.
     #[inline]
-    fn new_and_sign(
-        framing_parameters: FramingParameters,
+    async fn new_and_sign(
+        framing_parameters: FramingParameters<'_>,
         sender: Sender,
         body: FramedContentBody,
         context: &GroupContext,
-        signer: &impl Signer,
+        serialized_context: &[u8],
+        signer: &impl AsyncSigner,
     ) -> Result<Self, LibraryError> {
         let mut content_tbs = FramedContentTbs::new(
             framing_parameters.wire_format(),
@@ -106,25 +109,46 @@ impl AuthenticatedContent {
         );
 
         if matches!(sender, Sender::NewMemberCommit | Sender::Member(_)) {
-            let serialized_context = context
-                .tls_serialize_detached()
-                .map_err(LibraryError::missing_bound_check)?;
-            content_tbs = content_tbs.with_context(serialized_context);
+            content_tbs = content_tbs.with_context(serialized_context.to_vec());
         }
 
         content_tbs
-            .sign(signer)
+            .sign_async(signer)
+            .await
             .map_err(|_| LibraryError::custom("Signing failed"))
     }
 
     /// This constructor builds an `AuthenticatedContent` containing an application
     /// message. The sender type is always `SenderType::Member`.
+    #[cfg(any(feature = "test-utils", test))]
     pub(crate) fn new_application(
         sender_leaf_index: LeafNodeIndex,
         authenticated_data: &[u8],
         application_message: &[u8],
         context: &GroupContext,
         signer: &impl Signer,
+    ) -> Result<Self, LibraryError> {
+        crate::utils::poll_ready(Self::new_application_async(
+            sender_leaf_index,
+            authenticated_data,
+            application_message,
+            context,
+            &context
+                .tls_serialize_detached()
+                .map_err(LibraryError::missing_bound_check)?,
+            signer,
+        ))
+    }
+
+    /// This constructor builds an `AuthenticatedContent` containing an application
+    /// message. The sender type is always `SenderType::Member`.
+    pub(crate) async fn new_application_async(
+        sender_leaf_index: LeafNodeIndex,
+        authenticated_data: &[u8],
+        application_message: &[u8],
+        context: &GroupContext,
+        serialized_context: &[u8],
+        signer: &impl AsyncSigner,
     ) -> Result<Self, LibraryError> {
         let framing_parameters =
             FramingParameters::new(authenticated_data, WireFormat::PrivateMessage);
@@ -133,26 +157,53 @@ impl AuthenticatedContent {
             Sender::Member(sender_leaf_index),
             FramedContentBody::Application(application_message.into()),
             context,
+            serialized_context,
             signer,
         )
+        .await
     }
 
     /// This constructor builds an `PublicMessage` containing a Proposal.
     /// The sender type is always `SenderType::Member`.
+    #[cfg(any(feature = "test-utils", test))]
     pub(crate) fn member_proposal(
         framing_parameters: FramingParameters,
         sender_leaf_index: LeafNodeIndex,
         proposal: Proposal,
         context: &GroupContext,
         signer: &impl Signer,
+    ) -> Result<Self, LibraryError> {
+        crate::utils::poll_ready(Self::member_proposal_async(
+            framing_parameters,
+            sender_leaf_index,
+            proposal,
+            context,
+            &context
+                .tls_serialize_detached()
+                .map_err(LibraryError::missing_bound_check)?,
+            signer,
+        ))
+    }
+
+    /// Like [`AuthenticatedContent::member_proposal()`], but signs with an
+    /// [`AsyncSigner`].
+    pub(crate) async fn member_proposal_async(
+        framing_parameters: FramingParameters<'_>,
+        sender_leaf_index: LeafNodeIndex,
+        proposal: Proposal,
+        context: &GroupContext,
+        serialized_context: &[u8],
+        signer: &impl AsyncSigner,
     ) -> Result<Self, LibraryError> {
         Self::new_and_sign(
             framing_parameters,
             Sender::Member(sender_leaf_index),
             FramedContentBody::Proposal(proposal),
             context,
+            serialized_context,
             signer,
         )
+        .await
     }
 
     /// This constructor builds an `PublicMessage` containing an External Proposal.
@@ -202,25 +253,50 @@ impl AuthenticatedContent {
             .map_err(|_| LibraryError::custom("Signing failed"))
     }
 
+    /// This constructor builds an `PublicMessage` containing a Commit. See
+    /// [`AuthenticatedContent::commit_async()`].
+    #[cfg(test)]
+    pub(crate) fn commit(
+        framing_parameters: FramingParameters,
+        sender: Sender,
+        commit: Commit,
+        context: &GroupContext,
+        signer: &impl Signer,
+    ) -> Result<Self, LibraryError> {
+        crate::utils::poll_ready(Self::commit_async(
+            framing_parameters,
+            sender,
+            commit,
+            context,
+            &context
+                .tls_serialize_detached()
+                .map_err(LibraryError::missing_bound_check)?,
+            signer,
+        ))
+    }
+
     /// This constructor builds an `PublicMessage` containing a Commit. If the
     /// given `CommitType` is `Member`, the `SenderType` is `Member` as well. If
     /// it's an `External` commit, the `SenderType` is `NewMemberCommit`. If it is an
     /// `External` commit, the context is not signed along with the rest of the
     /// commit.
-    pub(crate) fn commit(
-        framing_parameters: FramingParameters,
+    pub(crate) async fn commit_async(
+        framing_parameters: FramingParameters<'_>,
         sender: Sender,
         commit: Commit,
         context: &GroupContext,
-        signer: &impl Signer,
+        serialized_context: &[u8],
+        signer: &impl AsyncSigner,
     ) -> Result<Self, LibraryError> {
         Self::new_and_sign(
             framing_parameters,
             sender,
             FramedContentBody::Commit(commit),
             context,
+            serialized_context,
             signer,
         )
+        .await
     }
 
     /// Get the signature.
@@ -243,6 +319,15 @@ impl AuthenticatedContent {
         &self.content.body
     }
 
+    /// Returns the application data of an application message without copying
+    /// it, or `None` if the content isn't an application message.
+    pub(crate) fn into_application_data(self) -> Option<Vec<u8>> {
+        match self.content.body {
+            FramedContentBody::Application(application_data) => Some(application_data.into()),
+            FramedContentBody::Proposal(_) | FramedContentBody::Commit(_) => None,
+        }
+    }
+
     /// Get the wire format.
     pub(crate) fn wire_format(&self) -> WireFormat {
         self.wire_format
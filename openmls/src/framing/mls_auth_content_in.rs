@@ -191,6 +191,12 @@ impl VerifiableAuthenticatedContentIn {
     pub(crate) fn content_type(&self) -> ContentType {
         self.tbs.content.body.content_type()
     }
+
+    /// Get the (unverified) content body, e.g. to check it against the
+    /// [`DecodeLimits`](crate::framing::DecodeLimits) before it is verified.
+    pub(crate) fn body(&self) -> &FramedContentBodyIn {
+        &self.tbs.content.body
+    }
 }
 
 impl Verifiable for VerifiableAuthenticatedContentIn {
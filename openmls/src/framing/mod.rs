@@ -39,6 +39,35 @@
 //!
 //!  - [`MlsMessageIn`]/[`MlsMessageOut`]: Unified message type for incoming & outgoing MLS messages
 //!  - [`ApplicationMessage`]: Application message received through a [`ProcessedMessage`]
+//!
+//! ## Verified and unverified inbound types
+//!
+//! Inbound messages are deserialized into types that have not been verified
+//! yet and that don't expose their contents. The contents are only available
+//! on the types that verification returns:
+//!
+//! - [`KeyPackageIn`](crate::key_packages::KeyPackageIn) becomes a
+//!   [`KeyPackage`](crate::key_packages::KeyPackage) with
+//!   `KeyPackageIn::validate()`.
+//! - [`VerifiableGroupInfo`](crate::messages::group_info::VerifiableGroupInfo)
+//!   is verified when joining a group with it.
+//! - [`RatchetTreeIn`](crate::treesync::RatchetTreeIn) becomes a
+//!   [`RatchetTree`](crate::treesync::RatchetTree) with
+//!   `RatchetTreeIn::into_verified()`.
+//! - [`PublicMessageIn`] and [`PrivateMessageIn`] become a
+//!   [`ProcessedMessage`] with `MlsGroup::process_message()`.
+//!
+//! The only exception is the metadata that is needed to route a message to
+//! its group or to a handler before it can be verified:
+//!
+//! - the wire format, the protocol version, and the group ID, the epoch, the
+//!   content type and, for [`PublicMessageIn`]s, the sender of
+//!   [`MlsMessageIn`]s and [`ProtocolMessage`]s, and
+//! - the ciphersuite, the group ID and the epoch of a
+//!   [`VerifiableGroupInfo`](crate::messages::group_info::VerifiableGroupInfo),
+//!   e.g. to create the signature keys for an external commit.
+//!
+//! None of these values are authenticated.
 
 use serde::{Deserialize, Serialize};
 use tls_codec::*;
@@ -53,17 +82,22 @@ use crate::{
 
 pub(crate) mod codec;
 
+pub(crate) mod limits;
 pub(crate) mod message_in;
 pub(crate) mod message_out;
 pub(crate) mod mls_auth_content;
 pub(crate) mod mls_auth_content_in;
 pub(crate) mod mls_content;
 pub(crate) mod mls_content_in;
+pub(crate) mod padding;
 pub(crate) mod private_message;
 pub(crate) mod private_message_in;
 pub(crate) mod public_message;
 pub(crate) mod public_message_in;
 pub(crate) mod sender;
+pub(crate) mod shared_bytes;
+#[cfg(feature = "diagnostics")]
+pub(crate) mod tracing_decoder;
 pub(crate) mod validation;
 pub(crate) use errors::*;
 
@@ -83,8 +117,10 @@ pub(crate) use sender::*;
 // Public
 pub mod errors;
 
+pub use limits::DecodeLimits;
 pub use message_in::*;
 pub use message_out::*;
+pub use padding::PaddingPolicy;
 pub use private_message::*;
 pub use private_message_in::*;
 pub use public_message::*;
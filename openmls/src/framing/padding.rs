@@ -0,0 +1,107 @@
+//! # Padding of PrivateMessages
+//!
+//! The content of a [`PrivateMessage`](super::PrivateMessage) is padded with
+//! zero bytes before it is encrypted, so that the length of the ciphertext
+//! reveals less about the length of the content. The [`PaddingPolicy`]
+//! defines how much padding is added.
+
+use serde::{Deserialize, Serialize};
+
+/// Policy for the padding of the content of
+/// [`PrivateMessage`](super::PrivateMessage)s.
+///
+/// The padding is chosen such that the length of the ciphertext, i.e. the
+/// padded content and the AEAD tag, follows the policy.
+///
+///  - `None`:
+/// No padding is added.
+///  - `BlockSize`:
+/// The ciphertext length is padded to a multiple of the block size. A block
+/// size of 0 is the same as `None`.
+///  - `Padme`:
+/// The ciphertext length is padded with the PADMÉ scheme (Nikitin et al.,
+/// "Reducing Metadata Leakage from Encrypted Files and Communication with
+/// PURBs"). It leaks at most O(log log L) bits of a length L and adds at most
+/// 12% of overhead, so it scales to large messages better than a fixed block
+/// size.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaddingPolicy {
+    /// No padding.
+    #[default]
+    None,
+    /// Pad to a multiple of the given block size in bytes.
+    BlockSize(usize),
+    /// Pad with the PADMÉ scheme.
+    Padme,
+}
+
+impl PaddingPolicy {
+    /// Returns the number of padding bytes that are added to a ciphertext of
+    /// the given `length`.
+    pub fn padding_length(&self, length: usize) -> usize {
+        match *self {
+            PaddingPolicy::None | PaddingPolicy::BlockSize(0) => 0,
+            PaddingPolicy::BlockSize(block_size) => {
+                (block_size - (length % block_size)) % block_size
+            }
+            PaddingPolicy::Padme => padme(length) - length,
+        }
+    }
+}
+
+/// Returns the length that `length` is padded to with the PADMÉ scheme, i.e.
+/// `length` rounded up such that its `E - S` lowest bits are zero, where `E`
+/// is the position of its highest set bit and `S` the number of bits needed
+/// to represent `E`.
+fn padme(length: usize) -> usize {
+    if length < 2 {
+        return length;
+    }
+    let exponent = usize::BITS - 1 - length.leading_zeros();
+    let exponent_bits = u32::BITS - exponent.leading_zeros();
+    let mask = (1usize << (exponent - exponent_bits)) - 1;
+    // Lengths close to `usize::MAX` can't be encoded anyway and are left
+    // unpadded.
+    length
+        .checked_add(mask)
+        .map_or(length, |length| length & !mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn padding_length() {
+        for length in 0..1000 {
+            assert_eq!(PaddingPolicy::None.padding_length(length), 0);
+            assert_eq!(PaddingPolicy::BlockSize(0).padding_length(length), 0);
+            assert_eq!(
+                (length + PaddingPolicy::BlockSize(16).padding_length(length)) % 16,
+                0
+            );
+        }
+
+        // Lengths padded with PADMÉ
+        for (length, padded_length) in [
+            (0, 0),
+            (1, 1),
+            (8, 8),
+            (9, 10),
+            (100, 104),
+            (1000, 1024),
+            (1_000_000, 1_015_808),
+        ] {
+            assert_eq!(
+                length + PaddingPolicy::Padme.padding_length(length),
+                padded_length
+            );
+        }
+
+        // The overhead is at most 12%
+        for length in 1..100_000 {
+            let padding_length = PaddingPolicy::Padme.padding_length(length);
+            assert!(padding_length * 100 <= length * 12);
+        }
+    }
+}
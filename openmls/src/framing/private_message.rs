@@ -74,7 +74,7 @@ impl PrivateMessage {
         ciphersuite: Ciphersuite,
         backend: &impl OpenMlsCryptoProvider,
         message_secrets: &mut MessageSecrets,
-        padding_size: usize,
+        padding_policy: PaddingPolicy,
     ) -> Result<PrivateMessage, MessageEncryptionError> {
         log::debug!("PrivateMessage::try_from_authenticated_content");
         log::trace!("  ciphersuite: {}", ciphersuite);
@@ -88,7 +88,7 @@ impl PrivateMessage {
             ciphersuite,
             backend,
             message_secrets,
-            padding_size,
+            padding_policy,
         )
     }
 
@@ -98,7 +98,7 @@ impl PrivateMessage {
         ciphersuite: Ciphersuite,
         backend: &impl OpenMlsCryptoProvider,
         message_secrets: &mut MessageSecrets,
-        padding_size: usize,
+        padding_policy: PaddingPolicy,
     ) -> Result<PrivateMessage, MessageEncryptionError> {
         Self::encrypt_content(
             None,
@@ -106,7 +106,7 @@ impl PrivateMessage {
             ciphersuite,
             backend,
             message_secrets,
-            padding_size,
+            padding_policy,
         )
     }
 
@@ -117,7 +117,7 @@ impl PrivateMessage {
         backend: &impl OpenMlsCryptoProvider,
         header: MlsMessageHeader,
         message_secrets: &mut MessageSecrets,
-        padding_size: usize,
+        padding_policy: PaddingPolicy,
     ) -> Result<PrivateMessage, MessageEncryptionError> {
         Self::encrypt_content(
             Some(header),
@@ -125,7 +125,7 @@ impl PrivateMessage {
             ciphersuite,
             backend,
             message_secrets,
-            padding_size,
+            padding_policy,
         )
     }
 
@@ -137,7 +137,7 @@ impl PrivateMessage {
         ciphersuite: Ciphersuite,
         backend: &impl OpenMlsCryptoProvider,
         message_secrets: &mut MessageSecrets,
-        padding_size: usize,
+        padding_policy: PaddingPolicy,
     ) -> Result<PrivateMessage, MessageEncryptionError> {
         let sender_index = if let Some(index) = public_message.sender().as_member() {
             index
@@ -185,7 +185,7 @@ impl PrivateMessage {
                 backend,
                 &Self::encode_padded_ciphertext_content_detached(
                     public_message,
-                    padding_size,
+                    padding_policy,
                     ciphersuite.mac_length(),
                 )
                 .map_err(LibraryError::missing_bound_check)?,
@@ -256,7 +256,7 @@ impl PrivateMessage {
     /// Encodes the `PrivateMessageContent` struct with padding.
     fn encode_padded_ciphertext_content_detached(
         authenticated_content: &AuthenticatedContent,
-        padding_size: usize,
+        padding_policy: PaddingPolicy,
         mac_len: usize,
     ) -> Result<Vec<u8>, tls_codec::Error> {
         let plaintext_length = authenticated_content
@@ -264,15 +264,8 @@ impl PrivateMessage {
             .serialized_len_without_type()
             + authenticated_content.auth.tls_serialized_len();
 
-        let padding_length = if padding_size > 0 {
-            // Calculate padding block size.
-            // Only the AEAD tag is added.
-            let padding_offset = plaintext_length + mac_len;
-            // Return padding block size
-            (padding_size - (padding_offset % padding_size)) % padding_size
-        } else {
-            0
-        };
+        // Only the AEAD tag is added to the padded content.
+        let padding_length = padding_policy.padding_length(plaintext_length + mac_len);
 
         // Persist all initial fields manually (avoids cloning them)
         let buffer = &mut Vec::with_capacity(plaintext_length + padding_length);
@@ -1,9 +1,11 @@
+use bytes::{Buf, Bytes};
 use openmls_traits::{types::Ciphersuite, OpenMlsCryptoProvider};
 use tls_codec::{Deserialize, Serialize, TlsDeserialize, TlsSerialize, TlsSize};
 
 use super::{
-    codec::deserialize_ciphertext_content, mls_auth_content::FramedContentAuthData,
+    codec::deserialize_owned_ciphertext_content, mls_auth_content::FramedContentAuthData,
     mls_auth_content_in::VerifiableAuthenticatedContentIn, mls_content_in::FramedContentBodyIn,
+    shared_bytes::SharedVLBytes,
 };
 
 use crate::{
@@ -30,17 +32,50 @@ use super::*;
 ///     opaque ciphertext<V>;
 /// } PrivateMessage;
 /// ```
-#[derive(Debug, PartialEq, Eq, Clone, TlsSerialize, TlsSize, TlsDeserialize)]
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    serde::Serialize,
+    serde::Deserialize,
+    TlsSerialize,
+    TlsSize,
+    TlsDeserialize,
+)]
 pub struct PrivateMessageIn {
     group_id: GroupId,
     epoch: GroupEpoch,
     content_type: ContentType,
     authenticated_data: VLBytes,
-    encrypted_sender_data: VLBytes,
-    ciphertext: VLBytes,
+    encrypted_sender_data: SharedVLBytes,
+    ciphertext: SharedVLBytes,
 }
 
 impl PrivateMessageIn {
+    /// Reads a [`PrivateMessageIn`] from the front of `buffer` and advances
+    /// the buffer past it. The encrypted sender data and the ciphertext share
+    /// the buffer and are not copied.
+    pub(crate) fn split_from(buffer: &mut Bytes) -> Result<Self, tls_codec::Error> {
+        let mut reader = buffer.as_ref();
+        let group_id = GroupId::tls_deserialize(&mut reader)?;
+        let epoch = GroupEpoch::tls_deserialize(&mut reader)?;
+        let content_type = ContentType::tls_deserialize(&mut reader)?;
+        let authenticated_data = VLBytes::tls_deserialize(&mut reader)?;
+        let consumed = buffer.len() - reader.len();
+        buffer.advance(consumed);
+        let encrypted_sender_data = SharedVLBytes::split_from(buffer)?;
+        let ciphertext = SharedVLBytes::split_from(buffer)?;
+        Ok(Self {
+            group_id,
+            epoch,
+            content_type,
+            authenticated_data,
+            encrypted_sender_data,
+            ciphertext,
+        })
+    }
+
     /// Decrypt the sender data from this [`PrivateMessageIn`].
     pub(crate) fn sender_data(
         &self,
@@ -110,7 +145,7 @@ impl PrivateMessageIn {
             "Decryption key for private message: {ratchet_key:x?}"
         );
         log_crypto!(trace, "Decryption of private message private_message_content_aad_bytes: {private_message_content_aad_bytes:x?} - ratchet_nonce: {ratchet_nonce:x?}");
-        log::trace!("Decrypting ciphertext {:x?}", self.ciphertext);
+        log::trace!("Decrypting ciphertext {:x?}", self.ciphertext.as_slice());
         let private_message_content_bytes = ratchet_key
             .aead_open(
                 backend,
@@ -128,11 +163,8 @@ impl PrivateMessageIn {
             "  Successfully decrypted PublicMessage bytes: {:x?}",
             private_message_content_bytes
         );
-        deserialize_ciphertext_content(
-            &mut private_message_content_bytes.as_slice(),
-            self.content_type(),
-        )
-        .map_err(|_| MessageDecryptionError::MalformedContent)
+        deserialize_owned_ciphertext_content(private_message_content_bytes, self.content_type())
+            .map_err(|_| MessageDecryptionError::MalformedContent)
     }
 
     /// This function decrypts a [`PrivateMessage`] into a [`VerifiableAuthenticatedContent`].
@@ -141,7 +173,7 @@ impl PrivateMessageIn {
         &self,
         ciphersuite: Ciphersuite,
         backend: &impl OpenMlsCryptoProvider,
-        message_secrets: &mut MessageSecrets,
+        message_secrets: &MessageSecrets,
         sender_index: LeafNodeIndex,
         sender_ratchet_configuration: &SenderRatchetConfiguration,
         sender_data: MlsSenderData,
@@ -149,7 +181,7 @@ impl PrivateMessageIn {
         let secret_type = SecretType::from(&self.content_type);
         // Extract generation and key material for encryption
         let (ratchet_key, ratchet_nonce) = message_secrets
-            .secret_tree_mut()
+            .secret_tree()
             .secret_for_decryption(
                 ciphersuite,
                 backend,
@@ -264,8 +296,8 @@ impl From<PrivateMessageIn> for PrivateMessage {
             epoch: value.epoch,
             content_type: value.content_type,
             authenticated_data: value.authenticated_data,
-            encrypted_sender_data: value.encrypted_sender_data,
-            ciphertext: value.ciphertext,
+            encrypted_sender_data: value.encrypted_sender_data.into(),
+            ciphertext: value.ciphertext.into(),
         }
     }
 }
@@ -278,8 +310,8 @@ impl From<PrivateMessage> for PrivateMessageIn {
             epoch: value.epoch,
             content_type: value.content_type,
             authenticated_data: value.authenticated_data,
-            encrypted_sender_data: value.encrypted_sender_data,
-            ciphertext: value.ciphertext,
+            encrypted_sender_data: value.encrypted_sender_data.into(),
+            ciphertext: value.ciphertext.into(),
         }
     }
 }
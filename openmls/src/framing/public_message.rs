@@ -121,6 +121,16 @@ impl PublicMessage {
         &self.content.sender
     }
 
+    /// Get the group ID of this message.
+    pub(crate) fn group_id(&self) -> &GroupId {
+        &self.content.group_id
+    }
+
+    /// Get the epoch of this message.
+    pub(crate) fn epoch(&self) -> GroupEpoch {
+        self.content.epoch
+    }
+
     /// Adds a membership tag to this `PublicMessage`. The membership_tag is
     /// produced using the the membership secret.
     ///
@@ -40,13 +40,6 @@ pub struct PublicMessageIn {
     pub(crate) membership_tag: Option<MembershipTag>,
 }
 
-#[cfg(any(test, feature = "test-utils"))]
-impl PublicMessageIn {
-    pub(crate) fn content(&self) -> &crate::framing::mls_content_in::FramedContentBodyIn {
-        &self.content.body
-    }
-}
-
 #[cfg(test)]
 impl PublicMessageIn {
     pub fn set_confirmation_tag(&mut self, confirmation_tag: Option<ConfirmationTag>) {
@@ -95,13 +88,22 @@ impl PublicMessageIn {
         }
     }
 
+    /// Returns the (unverified) content of the message.
+    pub(crate) fn content(&self) -> &crate::framing::mls_content_in::FramedContentBodyIn {
+        &self.content.body
+    }
+
     /// Returns the [`ContentType`] of the message.
-    pub fn content_type(&self) -> ContentType {
+    pub(crate) fn content_type(&self) -> ContentType {
         self.content.body.content_type()
     }
 
-    /// Get the sender of this message.
-    pub fn sender(&self) -> &Sender {
+    /// Get the (unverified) sender of this message.
+    ///
+    /// The sender is only exposed through the
+    /// [`ProcessedMessage`](crate::framing::ProcessedMessage) after the
+    /// message was verified.
+    pub(crate) fn sender(&self) -> &Sender {
         &self.content.sender
     }
 
@@ -164,12 +166,12 @@ impl PublicMessageIn {
     }
 
     /// Get the group epoch.
-    pub fn epoch(&self) -> GroupEpoch {
+    pub(crate) fn epoch(&self) -> GroupEpoch {
         self.content.epoch
     }
 
     /// Get the [`GroupId`].
-    pub fn group_id(&self) -> &GroupId {
+    pub(crate) fn group_id(&self) -> &GroupId {
         &self.content.group_id
     }
 
@@ -190,11 +192,6 @@ impl PublicMessageIn {
     pub(crate) fn membership_tag(&self) -> Option<&MembershipTag> {
         self.membership_tag.as_ref()
     }
-
-    /// Get the [`ConfirmationTag`].
-    pub fn confirmation_tag(&self) -> Option<&ConfirmationTag> {
-        self.auth.confirmation_tag.as_ref()
-    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,180 @@
+//! Variable-length byte vectors that share the buffer they were read from.
+//!
+//! The ciphertexts of [`PrivateMessageIn`](super::PrivateMessageIn)s are
+//! stored as [`SharedVLBytes`]. When a message is read with
+//! [`MlsMessageIn::from_bytes()`](super::MlsMessageIn::from_bytes), they point
+//! into the buffer of the received message instead of being copied out of it.
+//! Serialized, they look like [`VLBytes`].
+
+use std::io::{Read, Write};
+
+use bytes::{Buf, Bytes};
+use tls_codec::{Deserialize, Error, Serialize, Size, VLByteSlice, VLBytes};
+
+/// A variable-length byte vector backed by a reference-counted buffer.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct SharedVLBytes(Bytes);
+
+impl SharedVLBytes {
+    /// Returns the bytes as a slice.
+    pub(crate) fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Reads a byte vector from the front of `buffer` and advances the buffer
+    /// past it. The returned bytes share the buffer and are not copied.
+    pub(crate) fn split_from(buffer: &mut Bytes) -> Result<Self, Error> {
+        let (header_len, len) = read_vl_length(&mut buffer.as_ref())?;
+        if buffer.len() - header_len < len {
+            return Err(Error::EndOfStream);
+        }
+        buffer.advance(header_len);
+        Ok(Self(buffer.split_to(len)))
+    }
+}
+
+impl From<Vec<u8>> for SharedVLBytes {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes.into())
+    }
+}
+
+impl From<VLBytes> for SharedVLBytes {
+    fn from(bytes: VLBytes) -> Self {
+        Vec::<u8>::from(bytes).into()
+    }
+}
+
+impl From<SharedVLBytes> for VLBytes {
+    fn from(bytes: SharedVLBytes) -> Self {
+        bytes.as_slice().into()
+    }
+}
+
+/// Reads the length prefix of a variable-length vector as defined in Section
+/// 2.1.2 of RFC 9420 and returns the length of the prefix and the length of the
+/// vector.
+pub(crate) fn read_vl_length<R: Read>(bytes: &mut R) -> Result<(usize, usize), Error> {
+    let mut first = [0u8; 1];
+    bytes
+        .read_exact(&mut first)
+        .map_err(|_| Error::EndOfStream)?;
+    let header_len = match first[0] >> 6 {
+        0 => 1,
+        1 => 2,
+        2 => 4,
+        _ => return Err(Error::InvalidVectorLength),
+    };
+    let mut len = (first[0] & 0x3f) as usize;
+    let mut rest = [0u8; 3];
+    bytes
+        .read_exact(&mut rest[..header_len - 1])
+        .map_err(|_| Error::EndOfStream)?;
+    for byte in &rest[..header_len - 1] {
+        len = (len << 8) | *byte as usize;
+    }
+    // The length has to be encoded with the minimum number of bytes.
+    let min_header_len = match len {
+        0..=0x3f => 1,
+        0x40..=0x3fff => 2,
+        _ => 4,
+    };
+    if header_len != min_header_len {
+        return Err(Error::InvalidVectorLength);
+    }
+    Ok((header_len, len))
+}
+
+impl Size for SharedVLBytes {
+    fn tls_serialized_len(&self) -> usize {
+        VLByteSlice(self.as_slice()).tls_serialized_len()
+    }
+}
+
+impl Serialize for SharedVLBytes {
+    fn tls_serialize<W: Write>(&self, writer: &mut W) -> Result<usize, Error> {
+        VLByteSlice(self.as_slice()).tls_serialize(writer)
+    }
+}
+
+impl Deserialize for SharedVLBytes {
+    fn tls_deserialize<R: Read>(bytes: &mut R) -> Result<Self, Error> {
+        VLBytes::tls_deserialize(bytes).map(Self::from)
+    }
+}
+
+impl serde::Serialize for SharedVLBytes {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serde::Serialize::serialize(&VLBytes::from(self.clone()), serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for SharedVLBytes {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        <VLBytes as serde::Deserialize>::deserialize(deserializer).map(Self::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_from() {
+        for len in [0, 1, 0x3f, 0x40, 0x3fff, 0x4000] {
+            let data = vec![0xa5u8; len];
+            let mut buffer = VLBytes::from(data.clone())
+                .tls_serialize_detached()
+                .unwrap();
+            buffer.extend_from_slice(b"rest");
+            let mut buffer = Bytes::from(buffer);
+            let start = buffer.as_ptr() as usize;
+
+            let bytes = SharedVLBytes::split_from(&mut buffer).unwrap();
+            assert_eq!(bytes.as_slice(), data.as_slice());
+            assert_eq!(buffer.as_ref(), b"rest");
+            // The bytes point into the buffer.
+            if len > 0 {
+                let offset = bytes.as_slice().as_ptr() as usize - start;
+                assert!(offset <= 4);
+            }
+
+            // The bytes are serialized like `VLBytes`.
+            let serialized = bytes.tls_serialize_detached().unwrap();
+            assert_eq!(
+                VLBytes::tls_deserialize(&mut serialized.as_slice())
+                    .unwrap()
+                    .as_slice(),
+                data.as_slice()
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_length() {
+        // Truncated vector
+        let mut buffer = Bytes::from_static(&[0x05, 0x01, 0x02]);
+        assert_eq!(
+            SharedVLBytes::split_from(&mut buffer),
+            Err(Error::EndOfStream)
+        );
+        // Non-minimal length encoding
+        let mut buffer = Bytes::from_static(&[0x40, 0x01, 0x00]);
+        assert_eq!(
+            SharedVLBytes::split_from(&mut buffer),
+            Err(Error::InvalidVectorLength)
+        );
+        // Reserved length prefix
+        let mut buffer = Bytes::from_static(&[0xc0, 0x00, 0x00, 0x00, 0x00]);
+        assert_eq!(
+            SharedVLBytes::split_from(&mut buffer),
+            Err(Error::InvalidVectorLength)
+        );
+    }
+}
@@ -139,7 +139,7 @@ fn codec_ciphertext(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvid
             sender: LeafNodeIndex::new(987543210),
         },
         &mut message_secrets,
-        0,
+        PaddingPolicy::None,
     )
     .expect("Could not encrypt PublicMessage.");
 
@@ -199,7 +199,7 @@ fn wire_format_checks(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProv
             sender: sender_index,
         },
         &mut message_secrets,
-        0,
+        PaddingPolicy::None,
     )
     .expect("Could not encrypt PublicMessage.")
     .into();
@@ -215,7 +215,7 @@ fn wire_format_checks(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProv
         .to_verifiable_content(
             ciphersuite,
             backend,
-            &mut message_secrets,
+            &message_secrets,
             sender_index,
             configuration,
             sender_data,
@@ -243,7 +243,7 @@ fn wire_format_checks(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProv
         ciphersuite,
         backend,
         &mut message_secrets,
-        0,
+        PaddingPolicy::None,
     )
     .expect("Could not encrypt PublicMessage.")
     .into();
@@ -258,7 +258,7 @@ fn wire_format_checks(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProv
         .to_verifiable_content(
             ciphersuite,
             backend,
-            &mut message_secrets,
+            &message_secrets,
             sender_index,
             configuration,
             sender_data,
@@ -283,7 +283,7 @@ fn wire_format_checks(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProv
             ciphersuite,
             backend,
             &mut message_secrets,
-            0,
+            PaddingPolicy::None,
         )
         .expect_err("Could encrypt despite wrong wire format."),
         MessageEncryptionError::WrongWireFormat
@@ -468,6 +468,7 @@ fn unknown_sender(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
         bob_key_package_bundle,
         backend,
         ResumptionPskStore::new(1024),
+        None,
     )
     .expect("Bob: Error creating group from Welcome");
 
@@ -512,6 +513,7 @@ fn unknown_sender(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
         charlie_key_package_bundle,
         backend,
         ResumptionPskStore::new(1024),
+        None,
     )
     .expect("Charlie: Error creating group from Welcome");
 
@@ -578,7 +580,7 @@ fn unknown_sender(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
             sender: LeafNodeIndex::new(987543210u32),
         },
         group_alice.message_secrets_test_mut(),
-        0,
+        PaddingPolicy::None,
     )
     .expect("Encryption error");
 
@@ -707,6 +709,7 @@ pub(crate) fn setup_alice_bob_group(
         bob_key_package_bundle,
         backend,
         ResumptionPskStore::new(1024),
+        None,
     )
     .expect("error creating group from welcome");
 
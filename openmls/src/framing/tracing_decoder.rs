@@ -0,0 +1,324 @@
+//! # Tracing decoder
+//!
+//! This module contains a decoder that walks the TLS encoding of incoming
+//! messages field by field and keeps track of the byte offset and the path
+//! of the field that is currently being decoded. It is only used to locate
+//! the field that caused a deserialization error after the regular
+//! `tls_codec` deserialization failed, so that the error can be reported as
+//! e.g. `Commit.proposals[3].key_package.leaf_node.capabilities` instead of
+//! just an `EndOfStream`.
+//!
+//! The decoder mirrors the structure of the incoming message types. Fields
+//! that are not walked explicitly are decoded as a whole and reported with
+//! the path of the enclosing field.
+
+use tls_codec::{Deserialize, VLBytes};
+
+use super::{
+    errors::DecodeError, mls_auth_content::FramedContentAuthData,
+    mls_content_in::FramedContentBodyIn, private_message_in::PrivateMessageIn, sender::Sender,
+    ContentType,
+};
+use crate::{
+    ciphersuite::{
+        hash_ref::ProposalRef,
+        signature::{Signature, SignaturePublicKey},
+        HpkePublicKey,
+    },
+    credentials::{Credential, CredentialType},
+    extensions::{ExtensionType, Extensions},
+    group::{GroupEpoch, GroupId},
+    messages::{
+        group_info::VerifiableGroupInfo, proposals::ProposalType, proposals_in::ProposalIn, Welcome,
+    },
+    treesync::{node::leaf_node::LeafNodeSource, treekem::UpdatePathIn, EncryptionKey},
+    versions::ProtocolVersion,
+};
+use openmls_traits::types::{Ciphersuite, VerifiableCiphersuite};
+
+/// A decoder that keeps track of the current offset and field path.
+pub(crate) struct TracingDecoder<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    end: usize,
+    path: Vec<String>,
+}
+
+impl<'a> TracingDecoder<'a> {
+    /// Create a new decoder for `bytes`. The `root` is the first element of
+    /// all field paths, usually the name of the decoded type.
+    pub(crate) fn new(bytes: &'a [u8], root: &str) -> Self {
+        Self {
+            bytes,
+            position: 0,
+            end: bytes.len(),
+            path: vec![root.to_string()],
+        }
+    }
+
+    fn remaining(&self) -> &'a [u8] {
+        &self.bytes[self.position..self.end]
+    }
+
+    fn path(&self) -> String {
+        let mut path = String::new();
+        for segment in &self.path {
+            if !path.is_empty() && !segment.starts_with('[') {
+                path.push('.');
+            }
+            path.push_str(segment);
+        }
+        path
+    }
+
+    fn error(&self, consumed: usize, error: tls_codec::Error) -> DecodeError {
+        DecodeError::new(self.position + consumed, self.path(), error)
+    }
+
+    /// Decode the field `name` with the given function.
+    fn field_with<T>(
+        &mut self,
+        name: &str,
+        decode: impl FnOnce(&mut &'a [u8]) -> Result<T, tls_codec::Error>,
+    ) -> Result<T, DecodeError> {
+        self.path.push(name.to_string());
+        let mut remaining = self.remaining();
+        let length = remaining.len();
+        let result = decode(&mut remaining);
+        let consumed = length - remaining.len();
+        match result {
+            Ok(value) => {
+                self.position += consumed;
+                self.path.pop();
+                Ok(value)
+            }
+            Err(e) => Err(self.error(consumed, e)),
+        }
+    }
+
+    /// Decode the field `name` of type `T`.
+    fn field<T: Deserialize>(&mut self, name: &str) -> Result<T, DecodeError> {
+        self.field_with(name, |bytes| T::tls_deserialize(bytes))
+    }
+
+    /// Decode a value of type `T` without advancing the decoder, e.g. to
+    /// look at the discriminant of an enum.
+    fn peek<T: Deserialize>(&self) -> Result<T, DecodeError> {
+        let mut remaining = self.remaining();
+        T::tls_deserialize(&mut remaining).map_err(|e| self.error(0, e))
+    }
+
+    /// Walk the field `name` with `walk`.
+    fn nested<T>(
+        &mut self,
+        name: &str,
+        walk: impl FnOnce(&mut Self) -> Result<T, DecodeError>,
+    ) -> Result<T, DecodeError> {
+        self.path.push(name.to_string());
+        let value = walk(self)?;
+        self.path.pop();
+        Ok(value)
+    }
+
+    /// Walk the variable-length vector `name`, calling `walk` for every
+    /// element.
+    fn vector(
+        &mut self,
+        name: &str,
+        mut walk: impl FnMut(&mut Self) -> Result<(), DecodeError>,
+    ) -> Result<(), DecodeError> {
+        self.path.push(name.to_string());
+        let (header_length, length) = self.vector_header()?;
+        let start = self.position + header_length;
+        if length > self.end - start {
+            return Err(self.error(self.end - self.position, tls_codec::Error::EndOfStream));
+        }
+        let end = std::mem::replace(&mut self.end, start + length);
+        self.position = start;
+        let mut index = 0;
+        while self.position < self.end {
+            self.path.push(format!("[{index}]"));
+            walk(self)?;
+            self.path.pop();
+            index += 1;
+        }
+        self.end = end;
+        self.path.pop();
+        Ok(())
+    }
+
+    /// Read the length header of a variable-length vector. The two most
+    /// significant bits of the first byte encode the size of the header.
+    fn vector_header(&self) -> Result<(usize, usize), DecodeError> {
+        let remaining = self.remaining();
+        let first = *remaining
+            .first()
+            .ok_or_else(|| self.error(0, tls_codec::Error::EndOfStream))?;
+        let header_length = match first >> 6 {
+            0 => 1,
+            1 => 2,
+            2 => 4,
+            _ => return Err(self.error(0, tls_codec::Error::InvalidVectorLength)),
+        };
+        if remaining.len() < header_length {
+            return Err(self.error(remaining.len(), tls_codec::Error::EndOfStream));
+        }
+        let mut length = (first & 0x3f) as usize;
+        for byte in &remaining[1..header_length] {
+            length = (length << 8) | *byte as usize;
+        }
+        Ok((header_length, length))
+    }
+}
+
+/// Deserialize a `T` from `bytes`. If deserialization fails, `bytes` are
+/// walked with `trace` to locate the malformed field. If the walk doesn't
+/// find a malformed field, e.g. because `T` performs additional checks during
+/// deserialization, the error is reported with the offset at which `T`
+/// failed and the `root` path.
+pub(crate) fn deserialize_traced<T: Deserialize>(
+    bytes: &[u8],
+    root: &str,
+    trace: impl FnOnce(&mut TracingDecoder) -> Result<(), DecodeError>,
+) -> Result<T, DecodeError> {
+    let mut remaining = bytes;
+    T::tls_deserialize(&mut remaining).map_err(|error| {
+        let mut decoder = TracingDecoder::new(bytes, root);
+        match trace(&mut decoder) {
+            Err(traced_error) => traced_error,
+            Ok(()) => DecodeError::new(bytes.len() - remaining.len(), root.to_string(), error),
+        }
+    })
+}
+
+/// Walk an `MLSMessage`.
+pub(crate) fn trace_mls_message(decoder: &mut TracingDecoder) -> Result<(), DecodeError> {
+    decoder.field::<ProtocolVersion>("version")?;
+    let wire_format = decoder.field::<u16>("wire_format")?;
+    match wire_format {
+        1 => decoder.nested("PublicMessage", trace_public_message),
+        2 => decoder
+            .field::<PrivateMessageIn>("PrivateMessage")
+            .map(|_| ()),
+        3 => decoder.field::<Welcome>("Welcome").map(|_| ()),
+        4 => decoder
+            .field::<VerifiableGroupInfo>("GroupInfo")
+            .map(|_| ()),
+        5 => decoder.nested("KeyPackage", trace_key_package),
+        _ => Err(decoder.error(0, tls_codec::Error::UnknownValue(wire_format.into()))),
+    }
+}
+
+/// Walk a `PublicMessage`.
+fn trace_public_message(decoder: &mut TracingDecoder) -> Result<(), DecodeError> {
+    let (sender, content_type) = decoder.nested("content", |decoder| {
+        decoder.field::<GroupId>("group_id")?;
+        decoder.field::<GroupEpoch>("epoch")?;
+        let sender = decoder.field::<Sender>("sender")?;
+        decoder.field::<VLBytes>("authenticated_data")?;
+        let content_type = decoder.nested("body", trace_content_body)?;
+        Ok((sender, content_type))
+    })?;
+    decoder.field_with("auth", |bytes| {
+        FramedContentAuthData::deserialize(bytes, content_type)
+    })?;
+    if sender.is_member() {
+        decoder.field::<VLBytes>("membership_tag")?;
+    }
+    Ok(())
+}
+
+/// Walk a `FramedContent` body and return its content type.
+fn trace_content_body(decoder: &mut TracingDecoder) -> Result<ContentType, DecodeError> {
+    let content_type = decoder.peek::<ContentType>()?;
+    match content_type {
+        ContentType::Commit => {
+            decoder.field::<u8>("content_type")?;
+            decoder.nested("Commit", |decoder| {
+                decoder.vector("proposals", trace_proposal_or_ref)?;
+                decoder.field::<Option<UpdatePathIn>>("path")?;
+                Ok(())
+            })?;
+        }
+        ContentType::Proposal => {
+            decoder.field::<u8>("content_type")?;
+            decoder.nested("Proposal", trace_proposal)?;
+        }
+        ContentType::Application => {
+            decoder.field::<FramedContentBodyIn>("Application")?;
+        }
+    }
+    Ok(content_type)
+}
+
+/// Walk a `ProposalOrRef`.
+fn trace_proposal_or_ref(decoder: &mut TracingDecoder) -> Result<(), DecodeError> {
+    match decoder.field::<u8>("type")? {
+        1 => trace_proposal(decoder),
+        2 => decoder.field::<ProposalRef>("reference").map(|_| ()),
+        other => Err(decoder.error(0, tls_codec::Error::UnknownValue(other.into()))),
+    }
+}
+
+/// Walk a `Proposal`. Only `Add` proposals are walked into, all other
+/// proposals are decoded as a whole.
+fn trace_proposal(decoder: &mut TracingDecoder) -> Result<(), DecodeError> {
+    let proposal_type = decoder.peek::<u16>()?;
+    if ProposalType::from(proposal_type) == ProposalType::Add {
+        decoder.field::<u16>("proposal_type")?;
+        decoder.nested("key_package", trace_key_package)
+    } else {
+        decoder.field::<ProposalIn>("proposal").map(|_| ())
+    }
+}
+
+/// Walk a `KeyPackage`.
+pub(crate) fn trace_key_package(decoder: &mut TracingDecoder) -> Result<(), DecodeError> {
+    decoder.field::<ProtocolVersion>("version")?;
+    decoder.field::<Ciphersuite>("cipher_suite")?;
+    decoder.field::<HpkePublicKey>("init_key")?;
+    decoder.nested("leaf_node", trace_leaf_node)?;
+    decoder.field::<Extensions>("extensions")?;
+    decoder.field::<Signature>("signature")?;
+    Ok(())
+}
+
+/// Walk a `LeafNode`.
+fn trace_leaf_node(decoder: &mut TracingDecoder) -> Result<(), DecodeError> {
+    decoder.field::<EncryptionKey>("encryption_key")?;
+    decoder.field::<SignaturePublicKey>("signature_key")?;
+    decoder.field::<Credential>("credential")?;
+    decoder.nested("capabilities", |decoder| {
+        decoder.field::<Vec<ProtocolVersion>>("versions")?;
+        decoder.field::<Vec<VerifiableCiphersuite>>("cipher_suites")?;
+        decoder.field::<Vec<ExtensionType>>("extensions")?;
+        decoder.field::<Vec<ProposalType>>("proposals")?;
+        decoder.field::<Vec<CredentialType>>("credentials")?;
+        Ok(())
+    })?;
+    decoder.field::<LeafNodeSource>("leaf_node_source")?;
+    decoder.field::<Extensions>("extensions")?;
+    decoder.field::<Signature>("signature")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vector_paths() {
+        // A vector of two vectors, the length of the second one exceeds the
+        // length of the outer vector.
+        let bytes = [0x04, 0x01, 0xaa, 0x03, 0xbb];
+        let mut decoder = TracingDecoder::new(&bytes, "Test");
+        let error = decoder
+            .vector("items", |decoder| {
+                decoder.vector("value", |decoder| decoder.field::<u8>("byte").map(|_| ()))
+            })
+            .expect_err("Decoding a truncated vector succeeded.");
+
+        assert_eq!(error.path(), "Test.items[1].value");
+        assert_eq!(error.offset(), 5);
+    }
+}
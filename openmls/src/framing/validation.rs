@@ -95,20 +95,15 @@ impl DecryptedMessage {
     pub(crate) fn from_inbound_ciphertext(
         ciphertext: PrivateMessageIn,
         backend: &impl OpenMlsCryptoProvider,
-        group: &mut CoreGroup,
+        group: &CoreGroup,
         sender_ratchet_configuration: &SenderRatchetConfiguration,
     ) -> Result<Self, ValidationError> {
         // This will be refactored with #265.
         let ciphersuite = group.ciphersuite();
-        // TODO: #819 The old leaves should not be needed any more.
-        //       Revisit when the transition is further along.
-        let (message_secrets, _old_leaves) = group
-            .message_secrets_and_leaves_mut(ciphertext.epoch())
-            .map_err(|_| MessageDecryptionError::AeadError)?;
-        let sender_data = ciphertext.sender_data(message_secrets, backend, ciphersuite)?;
         let message_secrets = group
-            .message_secrets_mut(ciphertext.epoch())
+            .message_secrets_for_epoch(ciphertext.epoch())
             .map_err(|_| MessageDecryptionError::AeadError)?;
+        let sender_data = ciphertext.sender_data(message_secrets, backend, ciphersuite)?;
         let verifiable_content = ciphertext.to_verifiable_content(
             ciphersuite,
             backend,
@@ -297,6 +292,7 @@ pub struct ProcessedMessage {
     authenticated_data: Vec<u8>,
     content: ProcessedMessageContent,
     credential: Credential,
+    message_digest: Vec<u8>,
 }
 
 impl ProcessedMessage {
@@ -316,9 +312,17 @@ impl ProcessedMessage {
             authenticated_data,
             content,
             credential,
+            message_digest: Vec::new(),
         }
     }
 
+    /// Sets the digest of the serialized message this message was processed
+    /// from.
+    pub(crate) fn with_message_digest(mut self, message_digest: Vec<u8>) -> Self {
+        self.message_digest = message_digest;
+        self
+    }
+
     /// Returns the group ID of the message.
     pub fn group_id(&self) -> &GroupId {
         &self.group_id
@@ -353,6 +357,17 @@ impl ProcessedMessage {
     pub fn credential(&self) -> &Credential {
         &self.credential
     }
+
+    /// Returns the hash of the serialized `MLSMessage` this message was
+    /// processed from, computed with the hash function of the group's
+    /// ciphersuite.
+    ///
+    /// The digest is stable for the exact bytes the Delivery Service
+    /// delivered, so it can be used to acknowledge processed messages to the
+    /// DS without hashing the message again on the application side.
+    pub fn message_digest(&self) -> &[u8] {
+        &self.message_digest
+    }
 }
 
 /// Content of a processed message.
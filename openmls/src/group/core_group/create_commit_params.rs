@@ -3,8 +3,8 @@
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    credentials::CredentialWithKey, framing::FramingParameters, group::ProposalStore,
-    messages::proposals::Proposal,
+    credentials::CredentialWithKey, extensions::CredentialBinding, framing::FramingParameters,
+    group::ProposalStore, messages::proposals::Proposal, schedule::psk::PskResolver,
 };
 
 #[cfg(doc)]
@@ -24,6 +24,9 @@ pub(crate) struct CreateCommitParams<'a> {
     force_self_update: bool,                        // Optional
     commit_type: CommitType,                        // Optional (default is `Member`)
     credential_with_key: Option<CredentialWithKey>, // Mandatory for external commits
+    create_group_info: Option<bool>,                // Optional (default depends on group config)
+    credential_bindings: Vec<CredentialBinding>,    // Optional
+    psk_resolver: Option<&'a dyn PskResolver>,      // Optional
 }
 
 pub(crate) struct TempBuilderCCPM0 {}
@@ -58,6 +61,9 @@ impl<'a> TempBuilderCCPM1<'a> {
                 force_self_update: true,
                 commit_type: CommitType::Member,
                 credential_with_key: None,
+                create_group_info: None,
+                credential_bindings: vec![],
+                psk_resolver: None,
             },
         }
     }
@@ -68,7 +74,6 @@ impl<'a> CreateCommitParamsBuilder<'a> {
         self.ccp.inline_proposals = inline_proposals;
         self
     }
-    #[cfg(test)]
     pub(crate) fn force_self_update(mut self, force_self_update: bool) -> Self {
         self.ccp.force_self_update = force_self_update;
         self
@@ -81,6 +86,21 @@ impl<'a> CreateCommitParamsBuilder<'a> {
         self.ccp.credential_with_key = Some(credential_with_key);
         self
     }
+    pub(crate) fn create_group_info(mut self, create_group_info: Option<bool>) -> Self {
+        self.ccp.create_group_info = create_group_info;
+        self
+    }
+    pub(crate) fn credential_bindings(
+        mut self,
+        credential_bindings: Vec<CredentialBinding>,
+    ) -> Self {
+        self.ccp.credential_bindings = credential_bindings;
+        self
+    }
+    pub(crate) fn psk_resolver(mut self, psk_resolver: Option<&'a dyn PskResolver>) -> Self {
+        self.ccp.psk_resolver = psk_resolver;
+        self
+    }
     pub(crate) fn build(self) -> CreateCommitParams<'a> {
         self.ccp
     }
@@ -108,4 +128,13 @@ impl<'a> CreateCommitParams<'a> {
     pub(crate) fn take_credential_with_key(&mut self) -> Option<CredentialWithKey> {
         self.credential_with_key.take()
     }
+    pub(crate) fn create_group_info(&self) -> Option<bool> {
+        self.create_group_info
+    }
+    pub(crate) fn credential_bindings(&self) -> &[CredentialBinding] {
+        &self.credential_bindings
+    }
+    pub(crate) fn psk_resolver(&self) -> Option<&'a dyn PskResolver> {
+        self.psk_resolver
+    }
 }
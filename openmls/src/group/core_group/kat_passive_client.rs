@@ -307,7 +307,8 @@ impl PassiveClient {
                 self.group
                     .as_mut()
                     .unwrap()
-                    .store_pending_proposal(*queued_proposal);
+                    .store_pending_proposal(&self.backend, *queued_proposal)
+                    .expect("Could not store proposal.");
             }
             ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
                 self.group
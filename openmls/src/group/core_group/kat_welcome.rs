@@ -210,7 +210,14 @@ pub fn run_test_vector(test_vector: WelcomeTestVector) -> Result<(), &'static st
     let psk_secret = {
         let resumption_psk_store = ResumptionPskStore::new(1024);
 
-        let psks = load_psks(backend.key_store(), &resumption_psk_store, &[]).unwrap();
+        let psks = load_psks(
+            backend.key_store(),
+            &resumption_psk_store,
+            None,
+            cipher_suite,
+            &[],
+        )
+        .unwrap();
 
         PskSecret::new(&backend, cipher_suite, psks).unwrap()
     };
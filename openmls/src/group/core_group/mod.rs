@@ -5,12 +5,11 @@
 //! This means that some functions that are not expected to fail and throw an
 //! error, will still return a `Result` since they may throw a `LibraryError`.
 
-// Private
-mod new_from_welcome;
-
 // Crate
 pub(crate) mod create_commit_params;
+#[cfg(feature = "external-commit")]
 pub(crate) mod new_from_external_init;
+pub(crate) mod new_from_welcome;
 pub(crate) mod past_secrets;
 pub(crate) mod process;
 pub(crate) mod proposals;
@@ -25,15 +24,22 @@ pub(crate) mod kat_welcome;
 pub(crate) mod test_core_group;
 #[cfg(test)]
 mod test_create_commit_params;
-#[cfg(test)]
+#[cfg(all(test, feature = "external-commit"))]
 mod test_external_init;
 #[cfg(test)]
 mod test_past_secrets;
 #[cfg(test)]
 mod test_proposals;
 
+use std::sync::Arc;
+
 use log::{debug, trace};
-use openmls_traits::{key_store::OpenMlsKeyStore, signatures::Signer, types::Ciphersuite};
+use openmls_traits::{
+    key_store::OpenMlsKeyStore,
+    signatures::{AsyncSigner, Signer},
+    storage::{StorageKey, StorageProvider},
+    types::Ciphersuite,
+};
 use serde::{Deserialize, Serialize};
 use tls_codec::Serialize as TlsSerializeTrait;
 
@@ -46,8 +52,8 @@ use self::{
 use super::{
     builder::TempBuilderPG1,
     errors::{
-        CoreGroupBuildError, CreateAddProposalError, CreateCommitError, ExporterError,
-        ValidationError,
+        CoreGroupBuildError, CreateAddProposalError, CreateCommitError,
+        CreateGroupContextExtProposalError, ExporterError, ValidationError,
     },
     group_context::*,
     public_group::{diff::compute_path::PathComputationResult, PublicGroup},
@@ -55,9 +61,10 @@ use super::{
 
 use crate::{
     binary_tree::array_representation::{LeafNodeIndex, TreeSize},
-    ciphersuite::{signable::Signable, HpkePublicKey},
+    ciphersuite::{signable::Signable, signature_cache::SignatureCache, HpkePublicKey},
     credentials::*,
     error::LibraryError,
+    extensions::CredentialBindingsExtension,
     framing::{mls_auth_content::AuthenticatedContent, *},
     group::{config::CryptoConfig, *},
     key_packages::*,
@@ -76,8 +83,6 @@ use crate::{
     versions::ProtocolVersion,
 };
 
-#[cfg(test)]
-use super::errors::CreateGroupContextExtProposalError;
 #[cfg(test)]
 use crate::treesync::node::leaf_node::TreePosition;
 #[cfg(test)]
@@ -121,8 +126,8 @@ impl Member {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[cfg_attr(test, derive(PartialEq, Clone))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
 pub(crate) struct CoreGroup {
     public_group: PublicGroup,
     group_epoch_secrets: GroupEpochSecrets,
@@ -136,7 +141,7 @@ pub(crate) struct CoreGroup {
     /// of the current epoch is kept.
     /// If more secrets from past epochs should be kept in order to be
     /// able to decrypt application messages from previous epochs, the size of
-    /// the store must be increased through [`max_past_epochs()`].
+    /// the store must be increased through a [`PastEpochRetention`] policy.
     message_secrets_store: MessageSecretsStore,
     // Resumption psk store. This is where the resumption psks are kept in a rollover list.
     pub(crate) resumption_psk_store: ResumptionPskStore,
@@ -147,7 +152,7 @@ pub(crate) struct CoreGroupBuilder {
     public_group_builder: TempBuilderPG1,
     config: Option<CoreGroupConfig>,
     psk_ids: Vec<PreSharedKeyId>,
-    max_past_epochs: usize,
+    past_epoch_retention: PastEpochRetention,
 }
 
 impl CoreGroupBuilder {
@@ -162,7 +167,7 @@ impl CoreGroupBuilder {
         Self {
             config: None,
             psk_ids: vec![],
-            max_past_epochs: 0,
+            past_epoch_retention: PastEpochRetention::default(),
             public_group_builder,
         }
     }
@@ -201,7 +206,12 @@ impl CoreGroupBuilder {
     }
     /// Set the number of past epochs the group should keep secrets.
     pub fn with_max_past_epoch_secrets(mut self, max_past_epochs: usize) -> Self {
-        self.max_past_epochs = max_past_epochs;
+        self.past_epoch_retention = PastEpochRetention::new(max_past_epochs);
+        self
+    }
+    /// Set the [`PastEpochRetention`] policy for the secrets of past epochs.
+    pub fn with_past_epoch_retention(mut self, past_epoch_retention: PastEpochRetention) -> Self {
+        self.past_epoch_retention = past_epoch_retention;
         self
     }
     /// Set the [`Lifetime`] for the own leaf in the group.
@@ -252,7 +262,13 @@ impl CoreGroupBuilder {
 
         // Prepare the PskSecret
         let psk_secret = {
-            let psks = load_psks(backend.key_store(), &resumption_psk_store, &self.psk_ids)?;
+            let psks = load_psks(
+                backend.key_store(),
+                &resumption_psk_store,
+                None,
+                ciphersuite,
+                &self.psk_ids,
+            )?;
 
             PskSecret::new(backend, ciphersuite, psks)?
         };
@@ -277,8 +293,8 @@ impl CoreGroupBuilder {
             .tag(backend, &[])
             .map_err(LibraryError::unexpected_crypto_error)?;
 
-        let message_secrets_store =
-            MessageSecretsStore::new_with_secret(self.max_past_epochs, message_secrets);
+        let mut message_secrets_store = MessageSecretsStore::new_with_secret(0, message_secrets);
+        message_secrets_store.set_retention(&self.past_epoch_retention);
 
         let public_group = public_group_builder
             .with_confirmation_tag(initial_confirmation_tag)
@@ -325,6 +341,20 @@ impl CoreGroup {
         framing_parameters: FramingParameters,
         joiner_key_package: KeyPackage,
         signer: &impl Signer,
+    ) -> Result<AuthenticatedContent, CreateAddProposalError> {
+        crate::utils::poll_ready(self.create_add_proposal_async(
+            framing_parameters,
+            joiner_key_package,
+            signer,
+        ))
+    }
+
+    /// Like [`CoreGroup::create_add_proposal()`], but signs with an [`AsyncSigner`].
+    pub(crate) async fn create_add_proposal_async(
+        &self,
+        framing_parameters: FramingParameters<'_>,
+        joiner_key_package: KeyPackage,
+        signer: &impl AsyncSigner,
     ) -> Result<AuthenticatedContent, CreateAddProposalError> {
         if let Some(required_capabilities) = self.required_capabilities() {
             joiner_key_package
@@ -336,13 +366,15 @@ impl CoreGroup {
             key_package: joiner_key_package,
         };
         let proposal = Proposal::Add(add_proposal);
-        AuthenticatedContent::member_proposal(
+        AuthenticatedContent::member_proposal_async(
             framing_parameters,
             self.own_leaf_index(),
             proposal,
             self.context(),
+            self.public_group.serialized_group_context()?,
             signer,
         )
+        .await
         .map_err(|e| e.into())
     }
 
@@ -350,6 +382,7 @@ impl CoreGroup {
     // struct {
     //     KeyPackage key_package;
     // } Update;
+    #[cfg(test)]
     pub(crate) fn create_update_proposal(
         &self,
         framing_parameters: FramingParameters,
@@ -357,16 +390,34 @@ impl CoreGroup {
         //      operate on a reference to make this more efficient.
         leaf_node: LeafNode,
         signer: &impl Signer,
+    ) -> Result<AuthenticatedContent, LibraryError> {
+        crate::utils::poll_ready(self.create_update_proposal_async(
+            framing_parameters,
+            leaf_node,
+            signer,
+        ))
+    }
+
+    /// Create an `Update` proposal, signed with an [`AsyncSigner`].
+    pub(crate) async fn create_update_proposal_async(
+        &self,
+        framing_parameters: FramingParameters<'_>,
+        // XXX: There's no need to own this. The [`UpdateProposal`] should
+        //      operate on a reference to make this more efficient.
+        leaf_node: LeafNode,
+        signer: &impl AsyncSigner,
     ) -> Result<AuthenticatedContent, LibraryError> {
         let update_proposal = UpdateProposal { leaf_node };
         let proposal = Proposal::Update(update_proposal);
-        AuthenticatedContent::member_proposal(
+        AuthenticatedContent::member_proposal_async(
             framing_parameters,
             self.own_leaf_index(),
             proposal,
             self.context(),
+            self.public_group.serialized_group_context()?,
             signer,
         )
+        .await
     }
 
     // 11.1.3. Remove
@@ -378,19 +429,35 @@ impl CoreGroup {
         framing_parameters: FramingParameters,
         removed: LeafNodeIndex,
         signer: &impl Signer,
+    ) -> Result<AuthenticatedContent, ValidationError> {
+        crate::utils::poll_ready(self.create_remove_proposal_async(
+            framing_parameters,
+            removed,
+            signer,
+        ))
+    }
+
+    /// Like [`CoreGroup::create_remove_proposal()`], but signs with an [`AsyncSigner`].
+    pub(crate) async fn create_remove_proposal_async(
+        &self,
+        framing_parameters: FramingParameters<'_>,
+        removed: LeafNodeIndex,
+        signer: &impl AsyncSigner,
     ) -> Result<AuthenticatedContent, ValidationError> {
         if self.public_group().leaf(removed).is_none() {
             return Err(ValidationError::UnknownMember);
         }
         let remove_proposal = RemoveProposal { removed };
         let proposal = Proposal::Remove(remove_proposal);
-        AuthenticatedContent::member_proposal(
+        AuthenticatedContent::member_proposal_async(
             framing_parameters,
             self.own_leaf_index(),
             proposal,
             self.context(),
+            self.public_group.serialized_group_context()?,
             signer,
         )
+        .await
         .map_err(ValidationError::LibraryError)
     }
 
@@ -399,31 +466,123 @@ impl CoreGroup {
     //     PreSharedKeyID psk;
     // } PreSharedKey;
     // TODO: #751
+    #[cfg(feature = "psk")]
     pub(crate) fn create_presharedkey_proposal(
         &self,
         framing_parameters: FramingParameters,
         psk: PreSharedKeyId,
         signer: &impl Signer,
+    ) -> Result<AuthenticatedContent, LibraryError> {
+        crate::utils::poll_ready(self.create_presharedkey_proposal_async(
+            framing_parameters,
+            psk,
+            signer,
+        ))
+    }
+
+    /// Like [`CoreGroup::create_presharedkey_proposal()`], but signs with an [`AsyncSigner`].
+    #[cfg(feature = "psk")]
+    pub(crate) async fn create_presharedkey_proposal_async(
+        &self,
+        framing_parameters: FramingParameters<'_>,
+        psk: PreSharedKeyId,
+        signer: &impl AsyncSigner,
     ) -> Result<AuthenticatedContent, LibraryError> {
         let presharedkey_proposal = PreSharedKeyProposal::new(psk);
         let proposal = Proposal::PreSharedKey(presharedkey_proposal);
-        AuthenticatedContent::member_proposal(
+        AuthenticatedContent::member_proposal_async(
+            framing_parameters,
+            self.own_leaf_index(),
+            proposal,
+            self.context(),
+            self.public_group.serialized_group_context()?,
+            signer,
+        )
+        .await
+    }
+
+    /// Create a `ReInit` proposal.
+    #[cfg(feature = "reinit")]
+    pub(crate) fn create_reinit_proposal(
+        &self,
+        framing_parameters: FramingParameters,
+        reinit: ReInitProposal,
+        signer: &impl Signer,
+    ) -> Result<AuthenticatedContent, LibraryError> {
+        crate::utils::poll_ready(self.create_reinit_proposal_async(
+            framing_parameters,
+            reinit,
+            signer,
+        ))
+    }
+
+    /// Like [`CoreGroup::create_reinit_proposal()`], but signs with an [`AsyncSigner`].
+    #[cfg(feature = "reinit")]
+    pub(crate) async fn create_reinit_proposal_async(
+        &self,
+        framing_parameters: FramingParameters<'_>,
+        reinit: ReInitProposal,
+        signer: &impl AsyncSigner,
+    ) -> Result<AuthenticatedContent, LibraryError> {
+        let proposal = Proposal::ReInit(reinit);
+        AuthenticatedContent::member_proposal_async(
             framing_parameters,
             self.own_leaf_index(),
             proposal,
             self.context(),
+            self.public_group.serialized_group_context()?,
             signer,
         )
+        .await
     }
 
     /// Create a `GroupContextExtensions` proposal.
-    #[cfg(test)]
+    ///
+    /// Returns an error if the extensions can't be applied to the group (see
+    /// [`CoreGroup::validate_group_context_extensions()`]).
     pub(crate) fn create_group_context_ext_proposal(
         &self,
         framing_parameters: FramingParameters,
         extensions: Extensions,
         signer: &impl Signer,
     ) -> Result<AuthenticatedContent, CreateGroupContextExtProposalError> {
+        crate::utils::poll_ready(self.create_group_context_ext_proposal_async(
+            framing_parameters,
+            extensions,
+            signer,
+        ))
+    }
+
+    /// Like [`CoreGroup::create_group_context_ext_proposal()`], but signs with an [`AsyncSigner`].
+    pub(crate) async fn create_group_context_ext_proposal_async(
+        &self,
+        framing_parameters: FramingParameters<'_>,
+        extensions: Extensions,
+        signer: &impl AsyncSigner,
+    ) -> Result<AuthenticatedContent, CreateGroupContextExtProposalError> {
+        self.validate_group_context_extensions(&extensions)?;
+
+        let proposal = GroupContextExtensionProposal::new(extensions);
+        let proposal = Proposal::GroupContextExtensions(proposal);
+        AuthenticatedContent::member_proposal_async(
+            framing_parameters,
+            self.own_leaf_index(),
+            proposal,
+            self.context(),
+            self.public_group.serialized_group_context()?,
+            signer,
+        )
+        .await
+        .map_err(|e| e.into())
+    }
+
+    /// Check that the given group context extensions can be applied to the
+    /// group, i.e. that all members support the required capabilities and
+    /// all extensions that are not defined in the MLS spec.
+    pub(crate) fn validate_group_context_extensions(
+        &self,
+        extensions: &Extensions,
+    ) -> Result<(), CreateGroupContextExtProposalError> {
         // Ensure that the group supports all the extensions that are wanted.
 
         let required_extension = extensions
@@ -443,42 +602,76 @@ impl CoreGroup {
             self.public_group()
                 .check_extension_support(required_capabilities.extension_types())?;
         }
-        let proposal = GroupContextExtensionProposal::new(extensions);
-        let proposal = Proposal::GroupContextExtensions(proposal);
-        AuthenticatedContent::member_proposal(
-            framing_parameters,
-            self.own_leaf_index(),
-            proposal,
-            self.context(),
-            signer,
-        )
-        .map_err(|e| e.into())
+
+        // Ensure that all members support the proposed extensions that are
+        // not defined in the MLS spec.
+        let unknown_extension_types: Vec<ExtensionType> = extensions
+            .iter()
+            .map(|extension| extension.extension_type())
+            .filter(|extension_type| matches!(extension_type, ExtensionType::Unknown(_)))
+            .collect();
+        self.public_group()
+            .check_extension_support(&unknown_extension_types)?;
+
+        // Ensure that all members support the credential types of the
+        // external senders.
+        if let Some(external_senders) = extensions.external_senders() {
+            let credential_types: Vec<CredentialType> = external_senders
+                .iter()
+                .map(|external_sender| external_sender.credential().credential_type())
+                .collect();
+            self.public_group()
+                .check_credential_support(&credential_types)?;
+        }
+
+        Ok(())
     }
 
     // Create application message
+    #[cfg(test)]
     pub(crate) fn create_application_message(
         &mut self,
         aad: &[u8],
         msg: &[u8],
-        padding_size: usize,
+        padding_policy: PaddingPolicy,
         backend: &impl OpenMlsCryptoProvider,
         signer: &impl Signer,
     ) -> Result<PrivateMessage, MessageEncryptionError> {
-        let public_message = AuthenticatedContent::new_application(
+        crate::utils::poll_ready(self.create_application_message_async(
+            aad,
+            msg,
+            padding_policy,
+            backend,
+            signer,
+        ))
+    }
+
+    // Create application message, signing it with an `AsyncSigner`
+    pub(crate) async fn create_application_message_async(
+        &mut self,
+        aad: &[u8],
+        msg: &[u8],
+        padding_policy: PaddingPolicy,
+        backend: &impl OpenMlsCryptoProvider,
+        signer: &impl AsyncSigner,
+    ) -> Result<PrivateMessage, MessageEncryptionError> {
+        let public_message = AuthenticatedContent::new_application_async(
             self.own_leaf_index(),
             aad,
             msg,
             self.context(),
+            self.public_group.serialized_group_context()?,
             signer,
-        )?;
-        self.encrypt(public_message, padding_size, backend)
+        )
+        .await?;
+        self.encrypt(public_message, padding_policy, backend)
     }
 
     // Encrypt an PublicMessage into an PrivateMessage
     pub(crate) fn encrypt(
         &mut self,
         public_message: AuthenticatedContent,
-        padding_size: usize,
+        padding_policy: PaddingPolicy,
         backend: &impl OpenMlsCryptoProvider,
     ) -> Result<PrivateMessage, MessageEncryptionError> {
         PrivateMessage::try_from_authenticated_content(
@@ -486,7 +679,7 @@ impl CoreGroup {
             self.ciphersuite(),
             backend,
             self.message_secrets_store.message_secrets_mut(),
-            padding_size,
+            padding_policy,
         )
     }
 
@@ -528,6 +721,19 @@ impl CoreGroup {
         label: &str,
         context: &[u8],
         key_length: usize,
+    ) -> Result<Vec<u8>, ExporterError> {
+        self.export_secret_with_quirks(backend, label, context, key_length, &[])
+    }
+
+    /// Exporter that reproduces the given [`ExporterQuirk`]s of another
+    /// implementation.
+    pub(crate) fn export_secret_with_quirks(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        label: &str,
+        context: &[u8],
+        key_length: usize,
+        quirks: &[ExporterQuirk],
     ) -> Result<Vec<u8>, ExporterError> {
         if key_length > u16::MAX.into() {
             log::error!("Got a key that is larger than u16::MAX");
@@ -536,7 +742,14 @@ impl CoreGroup {
         Ok(self
             .group_epoch_secrets
             .exporter_secret()
-            .derive_exported_secret(self.ciphersuite(), backend, label, context, key_length)
+            .derive_exported_secret_with_quirks(
+                self.ciphersuite(),
+                backend,
+                label,
+                context,
+                key_length,
+                quirks,
+            )
             .map_err(LibraryError::unexpected_crypto_error)?)
     }
 
@@ -548,29 +761,30 @@ impl CoreGroup {
     ) -> Result<GroupInfo, LibraryError> {
         let extensions = {
             let ratchet_tree_extension = || {
-                Extension::RatchetTree(RatchetTreeExtension::new(
-                    self.public_group().export_ratchet_tree(),
-                ))
+                self.context()
+                    .ratchet_tree_extension(self.public_group().export_ratchet_tree())
             };
 
             let external_pub_extension = || {
                 let external_pub = self
                     .group_epoch_secrets()
                     .external_secret()
-                    .derive_external_keypair(backend.crypto(), self.ciphersuite())
+                    .derive_external_keypair(backend.crypto(), self.ciphersuite())?
                     .public;
-                Extension::ExternalPub(ExternalPubExtension::new(HpkePublicKey::from(external_pub)))
+                Ok::<_, LibraryError>(Extension::ExternalPub(ExternalPubExtension::new(
+                    HpkePublicKey::from(external_pub),
+                )))
             };
 
             if with_ratchet_tree {
-                Extensions::from_vec(vec![ratchet_tree_extension(), external_pub_extension()])
+                Extensions::from_vec(vec![ratchet_tree_extension()?, external_pub_extension()?])
                     .map_err(|_| {
                         LibraryError::custom(
                             "There should not have been duplicate extensions here.",
                         )
                     })?
             } else {
-                Extensions::single(external_pub_extension())
+                Extensions::single(external_pub_extension()?)
             }
         };
 
@@ -687,11 +901,71 @@ impl CoreGroup {
         self.message_secrets_store.message_secrets()
     }
 
-    /// Sets the size of the [`MessageSecretsStore`], i.e. the number of past
-    /// epochs to keep.
+    /// Sets the [`PastEpochRetention`] policy of the [`MessageSecretsStore`],
+    /// i.e. the number of past epochs to keep and for how long.
     /// This allows application messages from previous epochs to be decrypted.
-    pub(crate) fn set_max_past_epochs(&mut self, max_past_epochs: usize) {
-        self.message_secrets_store.resize(max_past_epochs);
+    pub(crate) fn set_past_epoch_retention(&mut self, past_epoch_retention: &PastEpochRetention) {
+        self.message_secrets_store
+            .set_retention(past_epoch_retention);
+    }
+
+    /// Sets the number of resumption PSKs that are kept in the
+    /// [`ResumptionPskStore`]. If it holds more, the oldest ones are dropped.
+    pub(crate) fn set_number_of_resumption_psks(&mut self, number_of_resumption_psks: usize) {
+        self.resumption_psk_store
+            .set_max_number_of_secrets(number_of_resumption_psks);
+    }
+
+    /// Sets the [`LifetimePolicy`] that is used to check the lifetimes of the
+    /// key packages in Add proposals.
+    pub(crate) fn set_lifetime_policy(&mut self, lifetime_policy: LifetimePolicy) {
+        self.public_group.set_lifetime_policy(lifetime_policy);
+    }
+
+    /// Sets the [`DecodeLimits`] for the structure of incoming messages.
+    pub(crate) fn set_decode_limits(&mut self, decode_limits: DecodeLimits) {
+        self.public_group.set_decode_limits(decode_limits);
+    }
+
+    /// Sets the [`LeafPlacementStrategy`] that decides where the leaf nodes
+    /// of added members are put in the tree.
+    pub(crate) fn set_leaf_placement_strategy(
+        &mut self,
+        leaf_placement_strategy: LeafPlacementStrategy,
+    ) {
+        self.public_group
+            .set_leaf_placement_strategy(leaf_placement_strategy);
+    }
+
+    /// Sets a custom [`LeafPlacement`] that is used instead of the
+    /// [`LeafPlacementStrategy`].
+    pub(crate) fn set_leaf_placement(&mut self, leaf_placement: impl LeafPlacement + 'static) {
+        self.public_group.set_leaf_placement(leaf_placement);
+    }
+
+    /// Removes the custom [`LeafPlacement`], if any.
+    pub(crate) fn clear_leaf_placement(&mut self) {
+        self.public_group.clear_leaf_placement();
+    }
+
+    /// Sets the [`SignatureCache`] that is used when processing messages.
+    pub(crate) fn set_signature_cache(&mut self, signature_cache: Arc<SignatureCache>) {
+        self.public_group.set_signature_cache(signature_cache);
+    }
+
+    /// Removes the [`SignatureCache`], if any.
+    pub(crate) fn clear_signature_cache(&mut self) {
+        self.public_group.clear_signature_cache();
+    }
+
+    /// Returns a reference to the [`MessageSecretsStore`].
+    pub(crate) fn message_secrets_store(&self) -> &MessageSecretsStore {
+        &self.message_secrets_store
+    }
+
+    /// Returns a mutable reference to the [`MessageSecretsStore`].
+    pub(crate) fn message_secrets_store_mut(&mut self) -> &mut MessageSecretsStore {
+        &mut self.message_secrets_store
     }
 
     /// Get the message secrets. Either from the secrets store or from the group.
@@ -722,93 +996,100 @@ impl CoreGroup {
         }
     }
 
-    /// Get the message secrets and leaves for the given epoch. Either from the
-    /// secrets store or from the group.
-    ///
-    /// Note that the leaves vector is empty for message secrets of the current
-    /// epoch. The caller can use treesync in this case.
-    pub(crate) fn message_secrets_and_leaves_mut(
-        &mut self,
-        epoch: GroupEpoch,
-    ) -> Result<(&mut MessageSecrets, &[Member]), MessageDecryptionError> {
-        if epoch < self.context().epoch() {
-            self.message_secrets_store
-                .secrets_and_leaves_for_epoch_mut(epoch)
-                .ok_or({
-                    MessageDecryptionError::SecretTreeError(SecretTreeError::TooDistantInThePast)
-                })
-        } else {
-            // No need for leaves here. The tree of the current epoch is
-            // available to the caller.
-            Ok((self.message_secrets_store.message_secrets_mut(), &[]))
-        }
-    }
-
     pub(crate) fn own_leaf_node(&self) -> Result<&LeafNode, LibraryError> {
         self.public_group()
             .leaf(self.own_leaf_index())
             .ok_or_else(|| LibraryError::custom("Tree has no own leaf."))
     }
 
-    /// Store the given [`EncryptionKeyPair`]s in the `backend`'s key store
+    /// Returns the [`StorageKey`] of the encryption key pairs of this group's
+    /// current [`GroupEpoch`].
+    pub(crate) fn epoch_keypairs_key(&self) -> StorageKey<'_> {
+        StorageKey::EpochKeyPairs {
+            group_id: self.group_id().as_slice(),
+            epoch: self.context().epoch().as_u64(),
+            leaf_index: self.own_leaf_index().u32(),
+        }
+    }
+
+    /// Store the given [`EncryptionKeyPair`]s in the `backend`'s storage
     /// indexed by this group's [`GroupId`] and [`GroupEpoch`].
     ///
-    /// Returns an error if access to the key store fails.
+    /// Returns an error if access to the storage fails.
     pub(super) fn store_epoch_keypairs<KeyStore: OpenMlsKeyStore>(
         &self,
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         keypair_references: &[EncryptionKeyPair],
     ) -> Result<(), KeyStore::Error> {
-        let k = EpochKeypairId::new(
-            self.group_id(),
-            self.context().epoch().as_u64(),
-            self.own_leaf_index(),
-        );
         backend
             .key_store()
-            .store(&k.0, &keypair_references.to_vec())
+            .write(self.epoch_keypairs_key(), &keypair_references.to_vec())
     }
 
     /// Read the [`EncryptionKeyPair`]s of this group and its current
-    /// [`GroupEpoch`] from the `backend`'s key store.
+    /// [`GroupEpoch`] from the `backend`'s storage.
     ///
-    /// Returns `None` if access to the key store fails.
+    /// Returns an empty vector if access to the storage fails.
     pub(super) fn read_epoch_keypairs<KeyStore: OpenMlsKeyStore>(
         &self,
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
     ) -> Vec<EncryptionKeyPair> {
-        let k = EpochKeypairId::new(
-            self.group_id(),
-            self.context().epoch().as_u64(),
-            self.own_leaf_index(),
-        );
         backend
             .key_store()
-            .read::<Vec<EncryptionKeyPair>>(&k.0)
+            .load::<Vec<EncryptionKeyPair>>(self.epoch_keypairs_key())
             .unwrap_or_default()
     }
 
     /// Delete the [`EncryptionKeyPair`]s from the previous [`GroupEpoch`] from
-    /// the `backend`'s key store.
+    /// the `backend`'s storage.
     ///
-    /// Returns an error if access to the key store fails.
+    /// Returns an error if access to the storage fails.
     pub(super) fn delete_previous_epoch_keypairs<KeyStore: OpenMlsKeyStore>(
         &self,
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
     ) -> Result<(), KeyStore::Error> {
-        let k = EpochKeypairId::new(
-            self.group_id(),
-            self.context().epoch().as_u64() - 1,
-            self.own_leaf_index(),
-        );
-        backend.key_store().delete::<Vec<EncryptionKeyPair>>(&k.0)
+        let k = StorageKey::EpochKeyPairs {
+            group_id: self.group_id().as_slice(),
+            epoch: self.context().epoch().as_u64() - 1,
+            leaf_index: self.own_leaf_index().u32(),
+        };
+        backend.key_store().remove::<Vec<EncryptionKeyPair>>(k)
     }
 
     pub(crate) fn create_commit<KeyStore: OpenMlsKeyStore>(
         &self,
-        mut params: CreateCommitParams,
+        params: CreateCommitParams,
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         signer: &impl Signer,
+    ) -> Result<CreateCommitResult, CreateCommitError<KeyStore::Error>> {
+        crate::utils::poll_ready(self.create_commit_async(params, backend, signer))
+    }
+
+    /// Like [`CoreGroup::create_commit()`], but signs with an
+    /// [`AsyncSigner`].
+    pub(crate) async fn create_commit_async<KeyStore: OpenMlsKeyStore>(
+        &self,
+        params: CreateCommitParams<'_>,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl AsyncSigner,
+    ) -> Result<CreateCommitResult, CreateCommitError<KeyStore::Error>> {
+        self.create_commit_with_leaf_signer_async(params, backend, signer, signer)
+            .await
+    }
+
+    /// Like [`CoreGroup::create_commit_async()`], but signs the own leaf node
+    /// in the update path with the `leaf_signer`, while the commit itself is
+    /// signed with the `signer` of the current own leaf node.
+    ///
+    /// This is used to rotate the own credential and signature key with a
+    /// commit, in which case the new [`CredentialWithKey`] is passed in the
+    /// `params`.
+    pub(crate) async fn create_commit_with_leaf_signer_async<KeyStore: OpenMlsKeyStore>(
+        &self,
+        mut params: CreateCommitParams<'_>,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl AsyncSigner,
+        leaf_signer: &impl AsyncSigner,
     ) -> Result<CreateCommitResult, CreateCommitError<KeyStore::Error>> {
         let ciphersuite = self.ciphersuite();
 
@@ -864,6 +1145,9 @@ impl CoreGroup {
             .validate_remove_proposals(&proposal_queue)?;
         self.public_group
             .validate_pre_shared_key_proposals(&proposal_queue)?;
+        // Only admins may add and remove members if the group has admins
+        self.public_group
+            .validate_admin_proposals(&proposal_queue, &sender)?;
         // Validate update proposals for member commits
         if let Sender::Member(sender_index) = &sender {
             // ValSem110
@@ -883,6 +1167,25 @@ impl CoreGroup {
             return Err(CreateCommitError::CannotRemoveSelf);
         }
 
+        // Check that every credential binding belongs to one of the added
+        // key packages and is signed by the real credential.
+        for binding in params.credential_bindings() {
+            let is_invited =
+                apply_proposals_values
+                    .invitation_list
+                    .iter()
+                    .any(|(_, add_proposal)| {
+                        add_proposal.key_package().leaf_node().signature_key()
+                            == binding.pseudonym_signature_key()
+                    });
+            if !is_invited {
+                return Err(CreateCommitError::InvalidCredentialBinding);
+            }
+            binding
+                .verify(backend.crypto(), ciphersuite.signature_algorithm())
+                .map_err(|_| CreateCommitError::InvalidCredentialBinding)?;
+        }
+
         let path_computation_result =
             // If path is needed, compute path values
             if apply_proposals_values.path_required
@@ -897,9 +1200,9 @@ impl CoreGroup {
                     self.own_leaf_index(),
                     apply_proposals_values.exclusion_list(),
                     params.commit_type(),
-                    signer,
+                    leaf_signer,
                     params.take_credential_with_key()
-                )?
+                ).await?
             } else {
                 // If path is not needed, update the group context and return
                 // empty path processing results
@@ -914,13 +1217,15 @@ impl CoreGroup {
         };
 
         // Build AuthenticatedContent
-        let mut authenticated_content = AuthenticatedContent::commit(
+        let mut authenticated_content = AuthenticatedContent::commit_async(
             *params.framing_parameters(),
             sender,
             commit,
             self.public_group.group_context(),
+            self.public_group.serialized_group_context()?,
             signer,
-        )?;
+        )
+        .await?;
 
         // Update the confirmed transcript hash using the commit we just created.
         diff.update_confirmed_transcript_hash(backend, &authenticated_content)?;
@@ -943,6 +1248,8 @@ impl CoreGroup {
             let psks = load_psks(
                 backend.key_store(),
                 &self.resumption_psk_store,
+                params.psk_resolver(),
+                ciphersuite,
                 &apply_proposals_values.presharedkeys,
             )?;
 
@@ -978,20 +1285,26 @@ impl CoreGroup {
 
         diff.update_interim_transcript_hash(ciphersuite, backend, confirmation_tag.clone())?;
 
+        // Unless explicitly requested otherwise, a group info is only
+        // returned if the group uses the ratchet tree extension.
+        let create_group_info = params
+            .create_group_info()
+            .unwrap_or(self.use_ratchet_tree_extension);
+
         // only computes the group info if necessary
-        let group_info = if !apply_proposals_values.invitation_list.is_empty()
-            || self.use_ratchet_tree_extension
+        let group_info = if !apply_proposals_values.invitation_list.is_empty() || create_group_info
         {
             // Create the ratchet tree extension if necessary
             let external_pub = provisional_epoch_secrets
                 .external_secret()
-                .derive_external_keypair(backend.crypto(), ciphersuite)
+                .derive_external_keypair(backend.crypto(), ciphersuite)?
                 .public;
             let external_pub_extension =
                 Extension::ExternalPub(ExternalPubExtension::new(external_pub.into()));
             let other_extensions: Extensions = if self.use_ratchet_tree_extension {
                 Extensions::from_vec(vec![
-                    Extension::RatchetTree(RatchetTreeExtension::new(diff.export_ratchet_tree())),
+                    diff.group_context()
+                        .ratchet_tree_extension(diff.export_ratchet_tree())?,
                     external_pub_extension,
                 ])?
             } else {
@@ -1008,11 +1321,33 @@ impl CoreGroup {
                 )
             };
             // Sign to-be-signed group info.
-            Some(group_info_tbs.sign(signer)?)
+            Some(group_info_tbs.sign_async(signer).await?)
         } else {
             None
         };
 
+        // The credential bindings are only revealed to the joiners, so the
+        // group info in the welcome is signed separately if there are any.
+        let welcome_group_info = match &group_info {
+            Some(group_info)
+                if !apply_proposals_values.invitation_list.is_empty()
+                    && !params.credential_bindings().is_empty() =>
+            {
+                let mut extensions = group_info.extensions().clone();
+                extensions.add(Extension::CredentialBindings(
+                    CredentialBindingsExtension::new(params.credential_bindings().to_vec()),
+                ))?;
+                let group_info_tbs = GroupInfoTBS::new(
+                    diff.group_context().clone(),
+                    extensions,
+                    group_info.confirmation_tag().clone(),
+                    self.own_leaf_index(),
+                );
+                Some(group_info_tbs.sign_async(signer).await?)
+            }
+            _ => None,
+        };
+
         // Check if new members were added and, if so, create welcome messages
         let welcome_option = if !apply_proposals_values.invitation_list.is_empty() {
             // Encrypt GroupInfo object
@@ -1022,8 +1357,9 @@ impl CoreGroup {
             let encrypted_group_info = welcome_key
                 .aead_seal(
                     backend,
-                    group_info
+                    welcome_group_info
                         .as_ref()
+                        .or(group_info.as_ref())
                         .ok_or_else(|| LibraryError::custom("GroupInfo was not computed"))?
                         .tls_serialize_detached()
                         .map_err(LibraryError::missing_bound_check)?
@@ -1071,13 +1407,16 @@ impl CoreGroup {
         let staged_commit = StagedCommit::new(
             proposal_queue,
             StagedCommitState::GroupMember(Box::new(staged_commit_state)),
+            self.own_leaf_index(),
+            &self.public_group,
+            Some(self.own_leaf_index()),
         );
 
         Ok(CreateCommitResult {
             commit: authenticated_content,
             welcome_option,
             staged_commit,
-            group_info: group_info.filter(|_| self.use_ratchet_tree_extension),
+            group_info: group_info.filter(|_| create_group_info),
         })
     }
 
@@ -1087,22 +1426,6 @@ impl CoreGroup {
     }
 }
 
-/// Composite key for key material of a client within an epoch
-pub struct EpochKeypairId(Vec<u8>);
-
-impl EpochKeypairId {
-    fn new(group_id: &GroupId, epoch: u64, leaf_index: LeafNodeIndex) -> Self {
-        Self(
-            [
-                group_id.as_slice(),
-                &leaf_index.u32().to_be_bytes(),
-                &epoch.to_be_bytes(),
-            ]
-            .concat(),
-        )
-    }
-}
-
 #[cfg(any(feature = "test-utils", test))]
 impl CoreGroup {
     pub(crate) fn context_mut(&mut self) -> &mut GroupContext {
@@ -1117,11 +1440,6 @@ impl CoreGroup {
         println!("{}: {}", message, self.public_group().export_ratchet_tree());
     }
 
-    #[cfg(test)]
-    pub(crate) fn message_secrets_store(&self) -> &MessageSecretsStore {
-        &self.message_secrets_store
-    }
-
     #[cfg(test)]
     pub(crate) fn set_group_context(&mut self, group_context: GroupContext) {
         self.public_group.set_group_context(group_context)
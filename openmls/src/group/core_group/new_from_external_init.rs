@@ -5,6 +5,7 @@ use crate::{
         errors::ExternalCommitError,
     },
     messages::proposals::{ExternalInitProposal, Proposal},
+    treesync::RatchetTreeSource,
 };
 
 use super::CoreGroup;
@@ -22,29 +23,40 @@ impl CoreGroup {
     /// Returns the new `CoreGroup` object, as well as the `PublicMessage`
     /// containing the commit.
     ///
-    /// Note: If there is a group member in the group with the same identity as us,
-    /// this will create a remove proposal.
+    /// Note: If there is a member in the group with the given
+    /// `previous_signature_key`, or otherwise with the same signature key as
+    /// us, this will create a remove proposal for that member. At most one
+    /// member is removed.
     pub(crate) fn join_by_external_commit(
         backend: &impl OpenMlsCryptoProvider,
         signer: &impl Signer,
         mut params: CreateCommitParams,
-        ratchet_tree: Option<RatchetTreeIn>,
+        ratchet_tree: RatchetTreeSource<'_>,
         verifiable_group_info: VerifiableGroupInfo,
+        previous_signature_key: Option<&[u8]>,
     ) -> Result<ExternalCommitResult, ExternalCommitError> {
         // Build the ratchet tree
 
-        // Set nodes either from the extension or from the `nodes_option`.
-        // If we got a ratchet tree extension in the welcome, we enable it for
-        // this group. Note that this is not strictly necessary. But there's
-        // currently no other mechanism to enable the extension.
-        let (ratchet_tree, enable_ratchet_tree_extension) =
-            match verifiable_group_info.extensions().ratchet_tree() {
-                Some(extension) => (extension.ratchet_tree().clone(), true),
-                None => match ratchet_tree {
-                    Some(ratchet_tree) => (ratchet_tree, false),
-                    None => return Err(ExternalCommitError::MissingRatchetTree),
-                },
-            };
+        // Set nodes either from the extension or from the `ratchet_tree`
+        // source. If we got a ratchet tree extension in the welcome, we enable
+        // it for this group. Note that this is not strictly necessary. But
+        // there's currently no other mechanism to enable the extension.
+        let (ratchet_tree, enable_ratchet_tree_extension) = match verifiable_group_info
+            .extensions()
+            .any_ratchet_tree()
+            .map_err(|_| ExternalCommitError::MalformedRatchetTree)?
+        {
+            Some(ratchet_tree) => (ratchet_tree, true),
+            None => {
+                let ratchet_tree = ratchet_tree
+                    .ratchet_tree(
+                        verifiable_group_info.group_id(),
+                        verifiable_group_info.epoch(),
+                    )
+                    .ok_or(ExternalCommitError::MissingRatchetTree)?;
+                (ratchet_tree, false)
+            }
+        };
 
         let (public_group, group_info) = PublicGroup::from_external(
             backend,
@@ -87,17 +99,27 @@ impl CoreGroup {
 
         let mut inline_proposals = vec![external_init_proposal];
 
-        // If there is a group member in the group with the same identity as us,
-        // commit a remove proposal.
+        // If our previous leaf is still in the group when re-joining, or
+        // otherwise a member with our signature key, commit a remove proposal
+        // for it. Other members with the same identity are left untouched.
         let params_credential_with_key = params
             .take_credential_with_key()
             .ok_or(ExternalCommitError::MissingCredential)?;
-        if let Some(us) = public_group.members().find(|member| {
-            member.signature_key == params_credential_with_key.signature_key.as_slice()
-        }) {
+        let previous_leaf = previous_signature_key
+            .and_then(|previous_signature_key| {
+                public_group
+                    .members()
+                    .find(|member| member.signature_key == previous_signature_key)
+            })
+            .or_else(|| {
+                public_group.members().find(|member| {
+                    member.signature_key == params_credential_with_key.signature_key.as_slice()
+                })
+            });
+        if let Some(us) = previous_leaf {
             let remove_proposal = Proposal::Remove(RemoveProposal { removed: us.index });
             inline_proposals.push(remove_proposal);
-        };
+        }
 
         let own_leaf_index = public_group.leftmost_free_index(inline_proposals.iter().map(Some))?;
 
@@ -2,27 +2,72 @@ use log::debug;
 use openmls_traits::key_store::OpenMlsKeyStore;
 
 use crate::{
-    ciphersuite::hash_ref::HashReference,
+    ciphersuite::{hash_ref::HashReference, policy::CiphersuitePolicy},
     group::{core_group::*, errors::WelcomeError},
-    schedule::psk::store::ResumptionPskStore,
+    schedule::psk::{store::ResumptionPskStore, PskResolver},
     treesync::{
         errors::{DerivePathError, PublicTreeError},
         node::encryption_keys::EncryptionKeyPair,
+        RatchetTreeSource,
     },
 };
 
+/// Options for joining a group from a [`Welcome`]. See
+/// [`WelcomeProcessingBuilder`](crate::group::WelcomeProcessingBuilder).
+pub(crate) struct WelcomeOptions<'a> {
+    pub(crate) ratchet_tree: RatchetTreeSource<'a>,
+    /// If set, the lifetimes of the leaf nodes in the ratchet tree are
+    /// checked against this policy.
+    pub(crate) lifetime_policy: Option<LifetimePolicy>,
+    pub(crate) psk_resolver: Option<&'a dyn PskResolver>,
+    /// Whether the encryption key of the key package is kept in the key
+    /// store.
+    pub(crate) keep_key_package: bool,
+    /// The cache for the signatures of the ratchet tree and the group info,
+    /// which is also used by the new group.
+    pub(crate) signature_cache: Option<Arc<SignatureCache>>,
+}
+
 impl CoreGroup {
     // Join a group from a welcome message
+    #[cfg(test)]
     pub fn new_from_welcome<KeyStore: OpenMlsKeyStore>(
         welcome: Welcome,
         ratchet_tree: Option<RatchetTreeIn>,
         key_package_bundle: KeyPackageBundle,
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        resumption_psk_store: ResumptionPskStore,
+        psk_resolver: Option<&dyn PskResolver>,
+    ) -> Result<Self, WelcomeError<KeyStore::Error>> {
+        let keep_key_package = key_package_bundle.key_package.last_resort();
+        Self::new_from_welcome_with_options(
+            welcome,
+            key_package_bundle,
+            backend,
+            resumption_psk_store,
+            WelcomeOptions {
+                ratchet_tree: ratchet_tree.into(),
+                lifetime_policy: None,
+                psk_resolver,
+                keep_key_package,
+                signature_cache: None,
+            },
+        )
+    }
+
+    /// Join a group from a welcome message with the given [`WelcomeOptions`].
+    pub(crate) fn new_from_welcome_with_options<KeyStore: OpenMlsKeyStore>(
+        welcome: Welcome,
+        key_package_bundle: KeyPackageBundle,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         mut resumption_psk_store: ResumptionPskStore,
+        options: WelcomeOptions<'_>,
     ) -> Result<Self, WelcomeError<KeyStore::Error>> {
         log::debug!("CoreGroup::new_from_welcome_internal");
 
-        // Read the encryption key pair from the key store and delete it there.
+        // Read the encryption key pair from the key store and delete it there,
+        // unless the key package should be kept, e.g. because it is a last
+        // resort key package that can be used again.
         // TODO #1207: Key store access happens as early as possible so it can
         // be pulled up later more easily.
         let leaf_keypair = EncryptionKeyPair::read_from_key_store(
@@ -30,9 +75,11 @@ impl CoreGroup {
             key_package_bundle.key_package.leaf_node().encryption_key(),
         )
         .ok_or(WelcomeError::NoMatchingEncryptionKey)?;
-        leaf_keypair
-            .delete_from_key_store(backend)
-            .map_err(|_| WelcomeError::NoMatchingEncryptionKey)?;
+        if !options.keep_key_package {
+            leaf_keypair
+                .delete_from_key_store(backend)
+                .map_err(|_| WelcomeError::NoMatchingEncryptionKey)?;
+        }
 
         let ciphersuite = welcome.ciphersuite();
 
@@ -66,6 +113,8 @@ impl CoreGroup {
             let psks = load_psks(
                 backend.key_store(),
                 &resumption_psk_store,
+                options.psk_resolver,
+                ciphersuite,
                 &group_secrets.psks,
             )?;
 
@@ -115,30 +164,71 @@ impl CoreGroup {
 
         // Build the ratchet tree
 
-        // Set nodes either from the extension or from the `nodes_option`.
-        // If we got a ratchet tree extension in the welcome, we enable it for
-        // this group. Note that this is not strictly necessary. But there's
-        // currently no other mechanism to enable the extension.
-        let (ratchet_tree, enable_ratchet_tree_extension) =
-            match verifiable_group_info.extensions().ratchet_tree() {
-                Some(extension) => (extension.ratchet_tree().clone(), true),
-                None => match ratchet_tree {
-                    Some(ratchet_tree) => (ratchet_tree, false),
-                    None => return Err(WelcomeError::MissingRatchetTree),
-                },
-            };
+        // Set nodes either from the extension or from the source given in the
+        // options. If we got a ratchet tree extension in the welcome, we
+        // enable it for this group. Note that this is not strictly necessary.
+        // But there's currently no other mechanism to enable the extension.
+        let (ratchet_tree, enable_ratchet_tree_extension) = match verifiable_group_info
+            .extensions()
+            .any_ratchet_tree()
+            .map_err(|_| WelcomeError::MalformedWelcomeMessage)?
+        {
+            Some(ratchet_tree) => (ratchet_tree, true),
+            None => {
+                let ratchet_tree = options
+                    .ratchet_tree
+                    .ratchet_tree(
+                        verifiable_group_info.group_id(),
+                        verifiable_group_info.epoch(),
+                    )
+                    .ok_or(WelcomeError::MissingRatchetTree)?;
+                (ratchet_tree, false)
+            }
+        };
 
         let welcome_sender_index = verifiable_group_info.signer();
 
-        // Since there is currently only the external pub extension, there is no
-        // group info extension of interest here.
-        let (public_group, _group_info_extensions) = PublicGroup::from_external(
+        // The ciphersuite was already checked against the policy of the group
+        // configuration.
+        let (public_group, group_info) = PublicGroup::from_external_internal(
             backend,
             ratchet_tree,
             verifiable_group_info,
             ProposalStore::new(),
+            &CiphersuitePolicy::default(),
+            options.signature_cache,
         )?;
 
+        // Check the lifetimes of the leaf nodes that were created from key
+        // packages, if requested.
+        if let Some(lifetime_policy) = options.lifetime_policy {
+            for member in public_group.members() {
+                let acceptable = public_group
+                    .leaf(member.index)
+                    .and_then(|leaf_node| leaf_node.life_time())
+                    .map_or(true, |lifetime| lifetime_policy.accepts(lifetime));
+                if !acceptable {
+                    return Err(WelcomeError::InvalidLifetime(member.index));
+                }
+            }
+        }
+
+        // Credential bindings must belong to a leaf of the tree and be signed
+        // by the real credential.
+        if let Some(credential_bindings) = group_info.extensions().credential_bindings() {
+            for binding in credential_bindings.bindings() {
+                let is_member = public_group
+                    .members()
+                    .any(|m| m.signature_key == binding.pseudonym_signature_key().as_slice());
+                if !is_member {
+                    return Err(WelcomeError::InvalidCredentialBinding);
+                }
+                binding
+                    .verify(backend.crypto(), ciphersuite.signature_algorithm())
+                    .map_err(|_| WelcomeError::InvalidCredentialBinding)?;
+            }
+        }
+
         // Find our own leaf in the tree.
         let own_leaf_index = public_group
             .members()
@@ -244,6 +334,62 @@ impl CoreGroup {
         Ok(group)
     }
 
+    /// Decrypts the [`VerifiableGroupInfo`] of the `welcome` with the given
+    /// [`KeyPackageBundle`] without joining the group.
+    pub(crate) fn group_info_from_welcome<KeyStore: OpenMlsKeyStore>(
+        welcome: &Welcome,
+        key_package_bundle: &KeyPackageBundle,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        resumption_psk_store: &ResumptionPskStore,
+    ) -> Result<VerifiableGroupInfo, WelcomeError<KeyStore::Error>> {
+        let ciphersuite = welcome.ciphersuite();
+        let egs = Self::find_key_package_from_welcome_secrets(
+            key_package_bundle
+                .key_package()
+                .hash_ref(backend.crypto())?,
+            welcome.secrets(),
+        )
+        .ok_or(WelcomeError::JoinerSecretNotFound)?;
+        if ciphersuite != key_package_bundle.key_package().ciphersuite() {
+            return Err(WelcomeError::CiphersuiteMismatch);
+        }
+
+        let group_secrets = GroupSecrets::try_from_ciphertext(
+            key_package_bundle.private_key(),
+            egs.encrypted_group_secrets(),
+            welcome.encrypted_group_info(),
+            ciphersuite,
+            backend.crypto(),
+        )?;
+        let psks = load_psks(
+            backend.key_store(),
+            resumption_psk_store,
+            None,
+            ciphersuite,
+            &group_secrets.psks,
+        )?;
+        let psk_secret = PskSecret::new(backend, ciphersuite, psks)?;
+        let mut key_schedule = KeySchedule::init(
+            ciphersuite,
+            backend,
+            &group_secrets.joiner_secret,
+            psk_secret,
+        )?;
+        let (welcome_key, welcome_nonce) = key_schedule
+            .welcome(backend)
+            .map_err(|_| LibraryError::custom("Using the key schedule in the wrong state"))?
+            .derive_welcome_key_nonce(backend)
+            .map_err(LibraryError::unexpected_crypto_error)?;
+
+        Ok(VerifiableGroupInfo::try_from_ciphertext(
+            &welcome_key,
+            &welcome_nonce,
+            welcome.encrypted_group_info(),
+            &[],
+            backend,
+        )?)
+    }
+
     // Helper functions
 
     pub(crate) fn find_key_package_from_welcome_secrets(
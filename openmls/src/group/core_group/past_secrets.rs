@@ -1,31 +1,67 @@
-use std::collections::VecDeque;
+use std::{
+    collections::VecDeque,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
-use crate::schedule::message_secrets::MessageSecrets;
+use crate::{group::PastEpochRetention, schedule::message_secrets::MessageSecrets};
 
 use super::*;
 
 // Internal helper struct
-#[derive(Serialize, Deserialize)]
-#[cfg_attr(test, derive(PartialEq, Clone))]
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "crypto-debug", derive(Debug))]
 struct EpochTree {
     epoch: u64,
     message_secrets: MessageSecrets,
     leaves: Vec<Member>,
+    // The time at which the epoch ended, in seconds since the UNIX epoch.
+    #[serde(default)]
+    retired_at: u64,
+}
+
+// The epoch in which the own client was removed from the group, together with
+// its members. See `MessageSecretsStore::retain_final_epoch()`.
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
+#[cfg_attr(feature = "crypto-debug", derive(Debug))]
+struct FinalEpoch {
+    epoch: u64,
+    leaves: Vec<Member>,
+}
+
+/// Returns the current time in seconds since the UNIX epoch.
+pub(crate) fn now() -> u64 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(elapsed) => elapsed.as_secs(),
+        Err(_) => {
+            log::error!("SystemTime before UNIX EPOCH.");
+            0
+        }
+    }
 }
 
 /// Can store message secrets for up to `max_epochs`. The trees are added with [`self::add()`] and can be queried
 /// with [`Self::get_epoch()`].
-#[derive(Serialize, Deserialize)]
-#[cfg_attr(test, derive(PartialEq, Clone))]
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "crypto-debug", derive(Debug))]
 pub(crate) struct MessageSecretsStore {
     // Maximum size of the `past_epoch_trees` list.
     max_epochs: usize,
+    // Maximum time for which past message secrets are kept after their epoch
+    // ended.
+    #[serde(default)]
+    max_age: Option<Duration>,
     // Past message secrets.
     past_epoch_trees: VecDeque<EpochTree>,
     // The message secrets of the current epoch.
     message_secrets: MessageSecrets,
+    // The final epoch of the own client, if application messages of that
+    // epoch can still be decrypted with the message secrets of the current
+    // epoch.
+    #[serde(default)]
+    final_epoch: Option<FinalEpoch>,
 }
 
 #[cfg(not(feature = "crypto-debug"))]
@@ -33,8 +69,10 @@ impl core::fmt::Debug for MessageSecretsStore {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("MessageSecretsStore")
             .field("max_epochs", &"***")
+            .field("max_age", &"***")
             .field("past_epoch_trees", &"***")
             .field("message_secrets", &"***")
+            .field("final_epoch", &"***")
             .finish()
     }
 }
@@ -45,19 +83,37 @@ impl MessageSecretsStore {
     pub(crate) fn new_with_secret(max_epochs: usize, message_secrets: MessageSecrets) -> Self {
         Self {
             max_epochs,
+            max_age: None,
             past_epoch_trees: VecDeque::new(),
             message_secrets,
+            final_epoch: None,
         }
     }
 
-    /// Resize the store.
-    pub(crate) fn resize(&mut self, max_past_epochs: usize) {
-        let old_size = self.max_epochs;
-        self.max_epochs = max_past_epochs;
-        if old_size > max_past_epochs {
-            let num_epochs_out = old_size - max_past_epochs;
-            self.past_epoch_trees.rotate_left(num_epochs_out);
-            self.past_epoch_trees.truncate(max_past_epochs);
+    /// Apply the `retention` policy to the store and drop the message secrets
+    /// that it no longer permits to keep.
+    pub(crate) fn set_retention(&mut self, retention: &PastEpochRetention) {
+        self.max_epochs = retention.max_epochs();
+        self.max_age = retention.max_age();
+        if self.past_epoch_trees.len() > self.max_epochs {
+            let num_epochs_out = self.past_epoch_trees.len() - self.max_epochs;
+            self.past_epoch_trees.drain(..num_epochs_out);
+        }
+        self.drop_expired();
+    }
+
+    /// Drop the keys of skipped messages that the sender ratchet
+    /// `configuration` no longer permits to keep, in the current epoch and
+    /// all past epochs.
+    pub(crate) fn prune_skipped_keys(&mut self, configuration: &SenderRatchetConfiguration) {
+        self.message_secrets
+            .secret_tree_mut()
+            .prune_skipped_keys(configuration);
+        for epoch_tree in self.past_epoch_trees.iter_mut() {
+            epoch_tree
+                .message_secrets
+                .secret_tree_mut()
+                .prune_skipped_keys(configuration);
         }
     }
 
@@ -70,8 +126,10 @@ impl MessageSecretsStore {
         message_secrets: MessageSecrets,
         leaves: Vec<Member>,
     ) {
+        let now = now();
+        self.drop_expired_at(now);
         // Don't store the tree if it's not intended
-        if self.max_epochs == 0 {
+        if self.max_epochs == 0 || self.max_age == Some(Duration::ZERO) {
             return;
         }
         if self.past_epoch_trees.len() >= self.max_epochs {
@@ -82,6 +140,7 @@ impl MessageSecretsStore {
             epoch: group_epoch.into().as_u64(),
             message_secrets,
             leaves,
+            retired_at: now,
         });
         debug_assert!(
             self.max_epochs >= self.past_epoch_trees.len(),
@@ -91,6 +150,64 @@ impl MessageSecretsStore {
         );
     }
 
+    /// Drop the message secrets of all past epochs that ended longer than the
+    /// maximum age ago.
+    ///
+    /// Returns the number of epochs that were dropped.
+    pub(crate) fn drop_expired(&mut self) -> usize {
+        self.drop_expired_at(now())
+    }
+
+    /// Drop the message secrets of all past epochs that ended longer than the
+    /// maximum age ago at time `now` (in seconds since the UNIX epoch).
+    ///
+    /// Returns the number of epochs that were dropped.
+    pub(crate) fn drop_expired_at(&mut self, now: u64) -> usize {
+        let max_age = match self.max_age {
+            Some(max_age) => max_age,
+            None => return 0,
+        };
+        let old_len = self.past_epoch_trees.len();
+        self.past_epoch_trees.retain(|epoch_tree| {
+            Duration::from_secs(now.saturating_sub(epoch_tree.retired_at)) < max_age
+        });
+        old_len - self.past_epoch_trees.len()
+    }
+
+    /// Returns whether the message secrets of the past epoch `group_epoch`
+    /// expired, i.e. whether [`Self::drop_expired()`] would drop them.
+    pub(crate) fn is_expired(&self, group_epoch: impl Into<GroupEpoch>) -> bool {
+        let Some(max_age) = self.max_age else {
+            return false;
+        };
+        let epoch = group_epoch.into().as_u64();
+        let now = now();
+        self.past_epoch_trees.iter().any(|epoch_tree| {
+            epoch_tree.epoch == epoch
+                && Duration::from_secs(now.saturating_sub(epoch_tree.retired_at)) >= max_age
+        })
+    }
+
+    /// Drop the message secrets of all past epochs before `group_epoch`.
+    ///
+    /// Returns the number of epochs that were dropped.
+    pub(crate) fn drop_epochs_before(&mut self, group_epoch: impl Into<GroupEpoch>) -> usize {
+        let epoch = group_epoch.into().as_u64();
+        let old_len = self.past_epoch_trees.len();
+        self.past_epoch_trees
+            .retain(|epoch_tree| epoch_tree.epoch >= epoch);
+        old_len - self.past_epoch_trees.len()
+    }
+
+    /// Returns the past epochs for which message secrets are stored, from the
+    /// oldest to the newest.
+    pub(crate) fn past_epochs(&self) -> Vec<GroupEpoch> {
+        self.past_epoch_trees
+            .iter()
+            .map(|epoch_tree| epoch_tree.epoch.into())
+            .collect()
+    }
+
     /// Get a mutable reference to a secret tree for a given epoch `group_epoch`.
     /// If no message secrets are found for that epoch, `None` is returned.
     pub(crate) fn secrets_for_epoch_mut(
@@ -98,6 +215,9 @@ impl MessageSecretsStore {
         group_epoch: impl Into<GroupEpoch>,
     ) -> Option<&mut MessageSecrets> {
         let epoch = group_epoch.into().as_u64();
+        if self.is_final_epoch(epoch) {
+            return Some(&mut self.message_secrets);
+        }
         for epoch_tree in self.past_epoch_trees.iter_mut() {
             if epoch_tree.epoch == epoch {
                 return Some(&mut epoch_tree.message_secrets);
@@ -113,6 +233,9 @@ impl MessageSecretsStore {
         group_epoch: impl Into<GroupEpoch>,
     ) -> Option<&MessageSecrets> {
         let epoch = group_epoch.into().as_u64();
+        if self.is_final_epoch(epoch) {
+            return Some(&self.message_secrets);
+        }
         for epoch_tree in self.past_epoch_trees.iter() {
             if epoch_tree.epoch == epoch {
                 return Some(&epoch_tree.message_secrets);
@@ -121,25 +244,12 @@ impl MessageSecretsStore {
         None
     }
 
-    /// Get a mutable reference to a secret tree for a given epoch `group_epoch`.
-    /// Return a mutable reference to the [`MessageSecrets`] and a slice to the
-    /// [`Member`]s of the epoch.
-    pub(crate) fn secrets_and_leaves_for_epoch_mut(
-        &mut self,
-        group_epoch: impl Into<GroupEpoch>,
-    ) -> Option<(&mut MessageSecrets, &[Member])> {
-        let epoch = group_epoch.into().as_u64();
-        for epoch_tree in self.past_epoch_trees.iter_mut() {
-            if epoch_tree.epoch == epoch {
-                return Some((&mut epoch_tree.message_secrets, &epoch_tree.leaves));
-            }
-        }
-        None
-    }
-
     /// Return a slice with the [`Member`]s of the `group_epoch`.
     pub(crate) fn leaves_for_epoch(&self, group_epoch: impl Into<GroupEpoch>) -> &[Member] {
         let epoch = group_epoch.into().as_u64();
+        if let Some(final_epoch) = self.final_epoch.as_ref().filter(|f| f.epoch == epoch) {
+            return &final_epoch.leaves;
+        }
         for epoch_tree in self.past_epoch_trees.iter() {
             if epoch_tree.epoch == epoch {
                 return &epoch_tree.leaves;
@@ -154,12 +264,52 @@ impl MessageSecretsStore {
         group_epoch: GroupEpoch,
         leaf_index: LeafNodeIndex,
     ) -> bool {
-        self.past_epoch_trees.iter().any(|t| {
-            t.epoch == group_epoch.0
-                && t.leaves
-                    .iter()
-                    .any(|Member { index, .. }| *index == leaf_index)
-        })
+        let has_leaf = |leaves: &[Member]| {
+            leaves
+                .iter()
+                .any(|Member { index, .. }| *index == leaf_index)
+        };
+        self.final_epoch
+            .iter()
+            .any(|f| f.epoch == group_epoch.0 && has_leaf(&f.leaves))
+            || self
+                .past_epoch_trees
+                .iter()
+                .any(|t| t.epoch == group_epoch.0 && has_leaf(&t.leaves))
+    }
+
+    /// Keep the message secrets of the current epoch as the secrets of
+    /// `group_epoch`, the final epoch of the own client before it was removed
+    /// from the group, with the `leaves` of that epoch.
+    ///
+    /// The secret tree is sealed, so that only the sender ratchets that were
+    /// already derived can be used. The secrets of all other past epochs are
+    /// dropped.
+    pub(crate) fn retain_final_epoch(
+        &mut self,
+        group_epoch: impl Into<GroupEpoch>,
+        leaves: Vec<Member>,
+    ) {
+        self.past_epoch_trees.clear();
+        self.message_secrets.secret_tree_mut().seal();
+        self.final_epoch = Some(FinalEpoch {
+            epoch: group_epoch.into().as_u64(),
+            leaves,
+        });
+    }
+
+    /// Delete all message secrets, so that no message can be decrypted
+    /// anymore.
+    pub(crate) fn shred(&mut self) {
+        self.past_epoch_trees.clear();
+        self.message_secrets.secret_tree_mut().shred();
+        self.final_epoch = None;
+    }
+
+    /// Returns whether `epoch` is the final epoch of the own client. See
+    /// [`Self::retain_final_epoch()`].
+    fn is_final_epoch(&self, epoch: u64) -> bool {
+        matches!(&self.final_epoch, Some(final_epoch) if final_epoch.epoch == epoch)
     }
 
     /// Get a mutable reference to the message secrets of the current epoch.
@@ -1,11 +1,13 @@
 use core_group::proposals::QueuedProposal;
 
 use crate::{
+    ciphersuite::{signable::Verifiable, CachingCrypto, OpenMlsSignaturePublicKey},
     framing::mls_content::FramedContentBody,
     group::{
         errors::{MergeCommitError, StageCommitError, ValidationError},
         mls_group::errors::ProcessMessageError,
     },
+    schedule::psk::PskResolver,
 };
 
 use super::{proposals::ProposalStore, *};
@@ -45,12 +47,14 @@ impl CoreGroup {
         proposal_store: &ProposalStore,
         old_epoch_keypairs: Vec<EncryptionKeyPair>,
         leaf_node_keypairs: Vec<EncryptionKeyPair>,
+        psk_resolver: Option<&dyn PskResolver>,
     ) -> Result<ProcessedMessage, ProcessMessageError> {
         // Checks the following semantic validation:
         //  - ValSem010
         //  - ValSem246 (as part of ValSem010)
+        let crypto = CachingCrypto::new(backend.crypto(), self.public_group().signature_cache());
         let (content, credential) =
-            unverified_message.verify(self.ciphersuite(), backend.crypto(), self.version())?;
+            unverified_message.verify(self.ciphersuite(), &crypto, self.version())?;
 
         match content.sender() {
             Sender::Member(_) | Sender::NewMemberCommit | Sender::NewMemberProposal => {
@@ -58,12 +62,22 @@ impl CoreGroup {
                 let authenticated_data = content.authenticated_data().to_owned();
 
                 let content = match content.content() {
-                    FramedContentBody::Application(application_message) => {
+                    FramedContentBody::Application(_) => {
+                        let application_data =
+                            content.into_application_data().ok_or_else(|| {
+                                LibraryError::custom("The content is an application message")
+                            })?;
                         ProcessedMessageContent::ApplicationMessage(ApplicationMessage::new(
-                            application_message.as_slice().to_owned(),
+                            application_data,
                         ))
                     }
-                    FramedContentBody::Proposal(_) => {
+                    FramedContentBody::Proposal(proposal) => {
+                        if !self
+                            .public_group()
+                            .is_authorized_proposal(&sender, proposal, None)
+                        {
+                            return Err(ValidationError::SenderNotAdmin.into());
+                        }
                         let proposal = Box::new(QueuedProposal::from_authenticated_content_by_ref(
                             self.ciphersuite(),
                             backend,
@@ -82,6 +96,7 @@ impl CoreGroup {
                             old_epoch_keypairs,
                             leaf_node_keypairs,
                             backend,
+                            psk_resolver,
                         )?;
                         ProcessedMessageContent::StagedCommitMessage(Box::new(staged_commit))
                     }
@@ -103,7 +118,7 @@ impl CoreGroup {
                     FramedContentBody::Application(_) => {
                         Err(ProcessMessageError::UnauthorizedExternalApplicationMessage)
                     }
-                    FramedContentBody::Proposal(Proposal::Remove(_)) => {
+                    FramedContentBody::Proposal(Proposal::Add(_) | Proposal::Remove(_)) => {
                         let content = ProcessedMessageContent::ProposalMessage(Box::new(
                             QueuedProposal::from_authenticated_content_by_ref(
                                 self.ciphersuite(),
@@ -167,14 +182,17 @@ impl CoreGroup {
     ///  - ValSem245
     ///  - ValSem246 (as part of ValSem010)
     pub(crate) fn process_message(
-        &mut self,
+        &self,
         backend: &impl OpenMlsCryptoProvider,
         message: impl Into<ProtocolMessage>,
         sender_ratchet_configuration: &SenderRatchetConfiguration,
         proposal_store: &ProposalStore,
         own_leaf_nodes: &[LeafNode],
+        psk_resolver: Option<&dyn PskResolver>,
     ) -> Result<ProcessedMessage, ProcessMessageError> {
         let message: ProtocolMessage = message.into();
+        let message_digest =
+            message.digest(backend.crypto(), self.ciphersuite(), self.version())?;
 
         // Checks the following semantic validation:
         //  - ValSem002
@@ -203,7 +221,9 @@ impl CoreGroup {
             proposal_store,
             old_epoch_keypairs,
             leaf_node_keypairs,
+            psk_resolver,
         )
+        .map(|processed_message| processed_message.with_message_digest(message_digest))
     }
 
     /// Performs framing validation and, if necessary, decrypts the given message.
@@ -217,7 +237,7 @@ impl CoreGroup {
     ///  - ValSem006
     ///  - ValSem007 MembershipTag presence
     pub(crate) fn decrypt_message(
-        &mut self,
+        &self,
         backend: &impl OpenMlsCryptoProvider,
         message: ProtocolMessage,
         sender_ratchet_configuration: &SenderRatchetConfiguration,
@@ -262,6 +282,60 @@ impl CoreGroup {
         }
     }
 
+    /// Returns the confirmation tag of a commit that a member sent in the
+    /// previous epoch, or `None` if the commit can't be authenticated with the
+    /// retained secrets of that epoch.
+    ///
+    /// The signature is verified with the signature key that the sender had
+    /// in the tree of the previous epoch. Apart from that, the commit is not
+    /// validated, since it is not processed further.
+    pub(crate) fn verify_previous_epoch_commit(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        message: ProtocolMessage,
+        sender_ratchet_configuration: &SenderRatchetConfiguration,
+    ) -> Option<ConfirmationTag> {
+        let epoch = message.epoch();
+        let decrypted_message = match message {
+            ProtocolMessage::PublicMessage(public_message) => {
+                let message_secrets = self.message_secrets_for_epoch(epoch).ok()?;
+                DecryptedMessage::from_inbound_public_message(
+                    public_message,
+                    message_secrets,
+                    message_secrets.serialized_context().to_vec(),
+                    backend,
+                )
+                .ok()?
+            }
+            ProtocolMessage::PrivateMessage(ciphertext) => {
+                DecryptedMessage::from_inbound_ciphertext(
+                    ciphertext,
+                    backend,
+                    self,
+                    sender_ratchet_configuration,
+                )
+                .ok()?
+            }
+        };
+        let verifiable_content = decrypted_message.verifiable_content();
+        let Sender::Member(leaf_index) = verifiable_content.sender() else {
+            return None;
+        };
+        let sender = self
+            .message_secrets_store
+            .leaves_for_epoch(epoch)
+            .iter()
+            .find(|member| member.index == *leaf_index)?;
+        let signature_key = OpenMlsSignaturePublicKey::from_signature_key(
+            sender.signature_key.clone().into(),
+            self.ciphersuite().signature_algorithm(),
+        );
+        verifiable_content
+            .verify_no_out(backend.crypto(), &signature_key)
+            .ok()?;
+        verifiable_content.confirmation_tag().cloned()
+    }
+
     /// Helper function to read decryption keypairs.
     pub(super) fn read_decryption_keypairs(
         &self,
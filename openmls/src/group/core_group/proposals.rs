@@ -20,8 +20,7 @@ use crate::{
 
 /// A [ProposalStore] can store the standalone proposals that are received from the DS
 /// in between two commit messages.
-#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
-#[cfg_attr(test, derive(Clone))]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ProposalStore {
     queued_proposals: Vec<QueuedProposal>,
 }
@@ -42,12 +41,82 @@ impl ProposalStore {
     pub(crate) fn add(&mut self, queued_proposal: QueuedProposal) {
         self.queued_proposals.push(queued_proposal);
     }
-    pub(crate) fn proposals(&self) -> impl Iterator<Item = &QueuedProposal> {
+
+    /// Returns an iterator over the stored proposals in the order in which
+    /// they were stored. Each [`QueuedProposal`] contains its
+    /// [`ProposalRef`] and [`Sender`].
+    pub fn proposals(&self) -> impl Iterator<Item = &QueuedProposal> {
         self.queued_proposals.iter()
     }
-    pub(crate) fn is_empty(&self) -> bool {
+
+    /// Returns the stored proposal with the given reference, if any.
+    pub fn get(&self, proposal_ref: &ProposalRef) -> Option<&QueuedProposal> {
+        self.queued_proposals
+            .iter()
+            .find(|p| &p.proposal_reference == proposal_ref)
+    }
+
+    /// Returns the number of stored proposals.
+    pub fn len(&self) -> usize {
+        self.queued_proposals.len()
+    }
+
+    /// Returns `true` if no proposals are stored.
+    pub fn is_empty(&self) -> bool {
         self.queued_proposals.is_empty()
     }
+
+    /// Returns the pairs of stored proposals that conflict with each other,
+    /// see [`ProposalConflict`].
+    ///
+    /// A commit that covers conflicting proposals is either invalid or
+    /// ignores one of them, so the application might want to remove one of
+    /// each pair before committing.
+    pub fn conflicts(&self) -> Vec<ProposalConflict> {
+        let mut conflicts = vec![];
+        for (index, first) in self.queued_proposals.iter().enumerate() {
+            for second in &self.queued_proposals[index + 1..] {
+                let conflict = match (&first.proposal, &second.proposal) {
+                    (Proposal::Add(first_add), Proposal::Add(second_add))
+                        if first_add.key_package().hpke_init_key()
+                            == second_add.key_package().hpke_init_key() =>
+                    {
+                        ProposalConflict::DuplicateAdd(
+                            first.proposal_reference(),
+                            second.proposal_reference(),
+                        )
+                    }
+                    (Proposal::Remove(first_remove), Proposal::Remove(second_remove))
+                        if first_remove.removed() == second_remove.removed() =>
+                    {
+                        ProposalConflict::DuplicateRemove(
+                            first.proposal_reference(),
+                            second.proposal_reference(),
+                        )
+                    }
+                    (Proposal::Remove(remove), Proposal::Update(_))
+                        if second.sender == Sender::Member(remove.removed()) =>
+                    {
+                        ProposalConflict::RemoveAndUpdate {
+                            remove: first.proposal_reference(),
+                            update: second.proposal_reference(),
+                        }
+                    }
+                    (Proposal::Update(_), Proposal::Remove(remove))
+                        if first.sender == Sender::Member(remove.removed()) =>
+                    {
+                        ProposalConflict::RemoveAndUpdate {
+                            remove: second.proposal_reference(),
+                            update: first.proposal_reference(),
+                        }
+                    }
+                    _ => continue,
+                };
+                conflicts.push(conflict);
+            }
+        }
+        conflicts
+    }
     pub(crate) fn empty(&mut self) {
         self.queued_proposals.clear();
     }
@@ -64,6 +133,23 @@ impl ProposalStore {
     }
 }
 
+/// A pair of proposals in a [`ProposalStore`] that conflict with each other,
+/// see [`ProposalStore::conflicts()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProposalConflict {
+    /// Two Add proposals add the same key package.
+    DuplicateAdd(ProposalRef, ProposalRef),
+    /// Two Remove proposals remove the same member.
+    DuplicateRemove(ProposalRef, ProposalRef),
+    /// A Remove proposal removes the member that sent an Update proposal.
+    RemoveAndUpdate {
+        /// The reference of the Remove proposal.
+        remove: ProposalRef,
+        /// The reference of the Update proposal.
+        update: ProposalRef,
+    },
+}
+
 /// Alternative representation of a Proposal, where the sender is extracted from
 /// the encapsulating PublicMessage and the ProposalRef is attached.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -153,7 +239,7 @@ impl QueuedProposal {
         &self.proposal
     }
     /// Returns the `ProposalRef`.
-    pub(crate) fn proposal_reference(&self) -> ProposalRef {
+    pub fn proposal_reference(&self) -> ProposalRef {
         self.proposal_reference.clone()
     }
     /// Returns the `ProposalOrRefType`.
@@ -171,7 +257,7 @@ impl QueuedProposal {
 /// references to Proposals, such that, given a reference, a proposal can be
 /// accessed efficiently. To enable iteration over the queue in order, the
 /// `ProposalQueue` also contains a vector of `ProposalRef`s.
-#[derive(Default, Debug, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct ProposalQueue {
     /// `proposal_references` holds references to the proposals in the queue and
     /// determines the order of the queue.
@@ -405,6 +491,7 @@ impl ProposalQueue {
         let mut proposal_pool: HashMap<ProposalRef, QueuedProposal> = HashMap::new();
         let mut contains_own_updates = false;
         let mut contains_external_init = false;
+        let mut group_context_extensions: Option<ProposalRef> = None;
 
         // Aggregate both proposal types to a common iterator
         // We checked earlier that only proposals can end up here
@@ -479,7 +566,12 @@ impl ProposalQueue {
                     }
                 }
                 Proposal::GroupContextExtensions(_) => {
-                    // TODO: Validate proposal?
+                    // Only keep the last group context extensions proposal.
+                    if let Some(previous) = group_context_extensions.take() {
+                        valid_proposals.remove(&previous);
+                    }
+                    group_context_extensions = Some(queued_proposal.proposal_reference());
+                    valid_proposals.insert(queued_proposal.proposal_reference());
                     proposal_pool.insert(queued_proposal.proposal_reference(), queued_proposal);
                 }
                 Proposal::AppAck(_) => unimplemented!("See #291"),
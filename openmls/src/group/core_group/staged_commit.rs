@@ -6,7 +6,11 @@ use public_group::diff::{apply_proposals::ApplyProposalsValues, StagedPublicGrou
 
 use super::{super::errors::*, proposals::ProposalStore, *};
 use crate::{
+    ciphersuite::SignaturePublicKey,
+    extensions::{Extension, ExtensionType, Extensions},
     framing::mls_auth_content::AuthenticatedContent,
+    group::public_group::PublicGroup,
+    schedule::psk::PskResolver,
     treesync::node::encryption_keys::EncryptionKeyPair,
 };
 
@@ -18,6 +22,7 @@ impl CoreGroup {
         epoch_secrets: &GroupEpochSecrets,
         commit_secret: CommitSecret,
         serialized_provisional_group_context: &[u8],
+        psk_resolver: Option<&dyn PskResolver>,
     ) -> Result<EpochSecrets, StageCommitError> {
         // Check if we need to include the init secret from an external commit
         // we applied earlier or if we use the one from the previous epoch.
@@ -27,7 +32,7 @@ impl CoreGroup {
             // Decrypt the content and derive the external init secret.
             let external_priv = epoch_secrets
                 .external_secret()
-                .derive_external_keypair(backend.crypto(), self.ciphersuite())
+                .derive_external_keypair(backend.crypto(), self.ciphersuite())?
                 .private;
             let init_secret = InitSecret::from_kem_output(
                 backend,
@@ -58,6 +63,8 @@ impl CoreGroup {
             let psks = load_psks(
                 backend.key_store(),
                 &self.resumption_psk_store,
+                psk_resolver,
+                self.ciphersuite(),
                 &apply_proposals_values.presharedkeys,
             )?;
 
@@ -118,6 +125,7 @@ impl CoreGroup {
         old_epoch_keypairs: Vec<EncryptionKeyPair>,
         leaf_node_keypairs: Vec<EncryptionKeyPair>,
         backend: &impl OpenMlsCryptoProvider,
+        psk_resolver: Option<&dyn PskResolver>,
     ) -> Result<StagedCommit, StageCommitError> {
         // Check that the sender is another member of the group
         if let Sender::Member(member) = mls_content.sender() {
@@ -145,6 +153,9 @@ impl CoreGroup {
             return Ok(StagedCommit::new(
                 proposal_queue,
                 StagedCommitState::PublicState(Box::new(staged_diff)),
+                sender_index,
+                &self.public_group,
+                Some(self.own_leaf_index()),
             ));
         }
 
@@ -228,6 +239,7 @@ impl CoreGroup {
                 self.group_epoch_secrets(),
                 commit_secret,
                 &serialized_provisional_group_context,
+                psk_resolver,
             )?
             .split_secrets(
                 serialized_provisional_group_context,
@@ -263,7 +275,13 @@ impl CoreGroup {
                 new_leaf_keypair_option,
             )));
 
-        Ok(StagedCommit::new(proposal_queue, staged_commit_state))
+        Ok(StagedCommit::new(
+            proposal_queue,
+            staged_commit_state,
+            sender_index,
+            &self.public_group,
+            Some(self.own_leaf_index()),
+        ))
     }
 
     /// Merges a [StagedCommit] into the group state and optionally return a [`SecretTree`]
@@ -362,31 +380,108 @@ impl CoreGroup {
             old_epoch_keypairs,
             leaf_node_keypairs,
             backend,
+            None,
         )
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) enum StagedCommitState {
     PublicState(Box<StagedPublicGroupDiff>),
     GroupMember(Box<MemberStagedCommitState>),
 }
 
 /// Contains the changes from a commit to the group state.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StagedCommit {
     staged_proposal_queue: ProposalQueue,
     state: StagedCommitState,
+    // The leaf index of the committer in the new epoch. It is `None` for
+    // staged commits stored by earlier versions.
+    #[serde(default)]
+    committer: Option<LeafNodeIndex>,
+    #[serde(default)]
+    credential_updates: Vec<CredentialUpdate>,
+    #[serde(default)]
+    group_context_extension_changes: GroupContextExtensionChanges,
+    #[serde(default)]
+    removed_members: Vec<RemovedMember>,
+    // The leaf index of the own client in the current epoch. It is `None` for
+    // staged commits of a `PublicGroup` and for staged commits stored by
+    // earlier versions.
+    #[serde(default)]
+    own_leaf_index: Option<LeafNodeIndex>,
 }
 
 impl StagedCommit {
     /// Create a new [`StagedCommit`] from the provisional group state created
-    /// during the commit process.
-    pub(crate) fn new(staged_proposal_queue: ProposalQueue, state: StagedCommitState) -> Self {
-        StagedCommit {
+    /// during the commit process. The `committer` is the leaf index of the
+    /// committer in the new epoch and the `public_group` is the group state
+    /// the commit is applied to. The `own_leaf_index` is the leaf index of
+    /// the own client, if the commit is staged by a member.
+    pub(crate) fn new(
+        staged_proposal_queue: ProposalQueue,
+        state: StagedCommitState,
+        committer: LeafNodeIndex,
+        public_group: &PublicGroup,
+        own_leaf_index: Option<LeafNodeIndex>,
+    ) -> Self {
+        let mut staged_commit = StagedCommit {
             staged_proposal_queue,
             state,
-        }
+            committer: Some(committer),
+            credential_updates: Vec::new(),
+            group_context_extension_changes: GroupContextExtensionChanges::default(),
+            removed_members: Vec::new(),
+            own_leaf_index,
+        };
+        staged_commit.credential_updates = staged_commit.compute_credential_updates(public_group);
+        staged_commit.group_context_extension_changes = GroupContextExtensionChanges::new(
+            public_group.group_context().extensions(),
+            staged_commit.group_context().extensions(),
+        );
+        staged_commit.removed_members = staged_commit
+            .remove_proposals()
+            .filter_map(|remove| {
+                let leaf_index = remove.remove_proposal().removed();
+                let leaf_node = public_group.leaf(leaf_index)?;
+                Some(RemovedMember {
+                    leaf_index,
+                    credential: leaf_node.credential().clone(),
+                    signature_key: leaf_node.signature_key().clone(),
+                })
+            })
+            .collect();
+        staged_commit
+    }
+
+    /// Collects the leaves of existing members whose credential or signature
+    /// key is changed by this commit.
+    fn compute_credential_updates(&self, public_group: &PublicGroup) -> Vec<CredentialUpdate> {
+        // A removed leaf can be filled by an added member in the same commit,
+        // which is not a credential update.
+        let removed: Vec<LeafNodeIndex> = self
+            .remove_proposals()
+            .map(|remove| remove.remove_proposal().removed())
+            .collect();
+        self.new_leaf_nodes()
+            .filter(|(leaf_index, _)| !removed.contains(leaf_index))
+            .filter_map(|(leaf_index, leaf_node)| {
+                let previous = public_group.leaf(leaf_index)?;
+                if previous.credential() == leaf_node.credential()
+                    && previous.signature_key() == leaf_node.signature_key()
+                {
+                    return None;
+                }
+                Some(CredentialUpdate {
+                    leaf_index,
+                    previous_credential: previous.credential().clone(),
+                    previous_signature_key: previous.signature_key().clone(),
+                    credential: leaf_node.credential().clone(),
+                    signature_key: leaf_node.signature_key().clone(),
+                })
+            })
+            .collect()
     }
 
     /// Returns the Add proposals that are covered by the Commit message as in iterator over [QueuedAddProposal].
@@ -409,11 +504,101 @@ impl StagedCommit {
         self.staged_proposal_queue.psk_proposals()
     }
 
+    /// Returns the ReInit proposal that is covered by the Commit message, if
+    /// any. After merging such a commit, the group has to be reinitialized
+    /// with the parameters of the proposal.
+    pub fn reinit_proposal(&self) -> Option<&ReInitProposal> {
+        self.queued_proposals()
+            .find_map(|queued_proposal| match queued_proposal.proposal() {
+                Proposal::ReInit(reinit) => Some(reinit),
+                _ => None,
+            })
+    }
+
+    /// Returns the members added by this commit, together with their leaf
+    /// index in the new epoch.
+    pub fn added_members(&self) -> impl Iterator<Item = AddedMember<'_>> {
+        self.queued_proposals().filter_map(|queued_proposal| {
+            let Proposal::Add(add_proposal) = queued_proposal.proposal() else {
+                return None;
+            };
+            let key_package = add_proposal.key_package();
+            let (leaf_index, _) = self
+                .new_leaf_nodes()
+                .find(|(_, leaf_node)| *leaf_node == key_package.leaf_node())?;
+            Some(AddedMember {
+                leaf_index,
+                key_package,
+                sender: queued_proposal.sender(),
+            })
+        })
+    }
+
+    /// Returns the members removed by this commit, with their credential in
+    /// the current epoch.
+    pub fn removed_members(&self) -> &[RemovedMember] {
+        &self.removed_members
+    }
+
+    /// Returns the members whose leaf node is replaced by this commit, i.e.
+    /// the senders of the covered Update proposals and the committer, if the
+    /// commit has an update path. Members added by the commit, including the
+    /// committer of an external commit, are not included.
+    pub fn updated_members(&self) -> impl Iterator<Item = UpdatedMember<'_>> {
+        let added: Vec<LeafNodeIndex> = self
+            .added_members()
+            .map(|added_member| added_member.leaf_index())
+            .collect();
+        let external_commit = self.queued_proposals().any(|queued_proposal| {
+            queued_proposal
+                .proposal()
+                .is_type(ProposalType::ExternalInit)
+        });
+        self.new_leaf_nodes()
+            .filter(move |(leaf_index, _)| {
+                !added.contains(leaf_index)
+                    && !(external_commit && Some(*leaf_index) == self.committer)
+            })
+            .map(|(leaf_index, leaf_node)| UpdatedMember {
+                leaf_index,
+                leaf_node,
+                committer: Some(leaf_index) == self.committer,
+            })
+    }
+
+    /// Returns the IDs of the pre-shared keys that are injected into the key
+    /// schedule by this commit.
+    pub fn psk_ids(&self) -> impl Iterator<Item = &PreSharedKeyId> {
+        self.queued_proposals()
+            .filter_map(|queued_proposal| match queued_proposal.proposal() {
+                Proposal::PreSharedKey(psk_proposal) => Some(psk_proposal.psk_id()),
+                _ => None,
+            })
+    }
+
+    /// Returns how this commit changes the own leaf, or `None` if it doesn't
+    /// change it. Always returns `None` for commits staged by a
+    /// [`PublicGroup`].
+    pub fn own_leaf_change(&self) -> Option<OwnLeafChange> {
+        let own_leaf_index = self.own_leaf_index?;
+        if self.self_removed() {
+            return Some(OwnLeafChange::Removed);
+        }
+        self.updated_members()
+            .any(|updated_member| updated_member.leaf_index() == own_leaf_index)
+            .then_some(OwnLeafChange::Updated)
+    }
+
     /// Returns an iterator over all [`QueuedProposal`]s.
     pub(crate) fn queued_proposals(&self) -> impl Iterator<Item = &QueuedProposal> {
         self.staged_proposal_queue.queued_proposals()
     }
 
+    /// Returns the leaf index of the committer in the new epoch, if known.
+    pub(crate) fn committer(&self) -> Option<LeafNodeIndex> {
+        self.committer
+    }
+
     /// Returns `true` if the member was removed through a proposal covered by this Commit message
     /// and `false` otherwise.
     pub fn self_removed(&self) -> bool {
@@ -428,6 +613,34 @@ impl StagedCommit {
         }
     }
 
+    /// Returns an iterator over the leaf nodes that enter the tree with this
+    /// commit, i.e. the leaf nodes of added members, updated leaf nodes and
+    /// the new leaf node of the committer.
+    pub(crate) fn new_leaf_nodes(&self) -> impl Iterator<Item = (LeafNodeIndex, &LeafNode)> {
+        let staged_diff = match self.state {
+            StagedCommitState::PublicState(ref ps) => ps.as_ref(),
+            StagedCommitState::GroupMember(ref gm) => &gm.staged_diff,
+        };
+        staged_diff.new_leaf_nodes()
+    }
+
+    /// Returns the credential updates of this commit, i.e. the members whose
+    /// credential or signature key changes through an Update proposal or
+    /// the update path of the committer. See
+    /// [`MlsGroup::update_credential()`](crate::group::MlsGroup::update_credential())
+    /// and [`MlsGroup::propose_credential_update()`](crate::group::MlsGroup::propose_credential_update()).
+    pub fn credential_updates(&self) -> &[CredentialUpdate] {
+        &self.credential_updates
+    }
+
+    /// Returns the changes of the group context extensions in this commit
+    /// compared to the current epoch. The changes are empty if the commit
+    /// doesn't cover a `GroupContextExtensions` proposal, or if the proposal
+    /// sets the extensions the group already has.
+    pub fn group_context_extension_changes(&self) -> &GroupContextExtensionChanges {
+        &self.group_context_extension_changes
+    }
+
     /// Consume this [`StagedCommit`] and return the internal [`StagedCommitState`].
     pub(crate) fn into_state(self) -> StagedCommitState {
         self.state
@@ -443,10 +656,297 @@ impl StagedCommit {
             None
         }
     }
+
+    /// Returns the application state hash that this commit registers in the
+    /// group context, i.e. the [`AppStateHashExtension`](crate::extensions::AppStateHashExtension)
+    /// in the `GroupContextExtensions` proposal covered by the commit. Returns
+    /// `None` if the commit doesn't cover such a proposal.
+    pub fn app_state_hash(&self) -> Option<&[u8]> {
+        self.queued_proposals()
+            .find_map(|queued_proposal| match queued_proposal.proposal() {
+                Proposal::GroupContextExtensions(gce_proposal) => {
+                    gce_proposal.extensions().app_state_hash()
+                }
+                _ => None,
+            })
+            .map(|extension| extension.as_slice())
+    }
+
+    /// Compares the application state hash registered by this commit (see
+    /// [`StagedCommit::app_state_hash()`]) with the hash of the local
+    /// `app_state_hash`.
+    pub fn compare_app_state(&self, app_state_hash: &[u8]) -> AppStateComparison {
+        match self.app_state_hash() {
+            Some(committed_hash) if committed_hash == app_state_hash => AppStateComparison::Match,
+            Some(_) => AppStateComparison::Mismatch,
+            None => AppStateComparison::NotIncluded,
+        }
+    }
+
+    /// Returns `true` if this commit is an emergency re-keying after a
+    /// suspected compromise, i.e. if it covers a `GroupContextExtensions`
+    /// proposal that sets a
+    /// [`CompromiseRecoveryExtension`](crate::extensions::CompromiseRecoveryExtension)
+    /// for the epoch started by this commit. See
+    /// [`MlsGroup::emergency_rekey()`](crate::group::MlsGroup::emergency_rekey()).
+    ///
+    /// Merging such a commit drops the message secrets of all past epochs,
+    /// regardless of the [`PastEpochRetention`](crate::group::PastEpochRetention)
+    /// of the group.
+    pub fn is_compromise_recovery(&self) -> bool {
+        let epoch = self.group_context().epoch();
+        self.queued_proposals().any(|queued_proposal| {
+            matches!(
+                queued_proposal.proposal(),
+                Proposal::GroupContextExtensions(gce_proposal)
+                    if gce_proposal
+                        .extensions()
+                        .compromise_recovery()
+                        .map_or(false, |extension| extension.epoch() == epoch)
+            )
+        })
+    }
+}
+
+/// The result of comparing the application state hash registered by a commit
+/// with the local application state. See [`StagedCommit::compare_app_state()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppStateComparison {
+    /// The commit registers the same application state hash.
+    Match,
+    /// The commit registers a different application state hash, i.e. the
+    /// application state of the committer diverged from the local state.
+    Mismatch,
+    /// The commit doesn't register an application state hash.
+    NotIncluded,
+}
+
+/// A member added by a [`StagedCommit`]. See [`StagedCommit::added_members()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddedMember<'a> {
+    leaf_index: LeafNodeIndex,
+    key_package: &'a KeyPackage,
+    sender: &'a Sender,
+}
+
+impl<'a> AddedMember<'a> {
+    /// Returns the leaf index of the member in the new epoch.
+    pub fn leaf_index(&self) -> LeafNodeIndex {
+        self.leaf_index
+    }
+
+    /// Returns the key package the member was added with.
+    pub fn key_package(&self) -> &'a KeyPackage {
+        self.key_package
+    }
+
+    /// Returns the credential of the member.
+    pub fn credential(&self) -> &'a Credential {
+        self.key_package.leaf_node().credential()
+    }
+
+    /// Returns the sender of the Add proposal.
+    pub fn sender(&self) -> &'a Sender {
+        self.sender
+    }
+}
+
+/// A member removed by a [`StagedCommit`]. See
+/// [`StagedCommit::removed_members()`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemovedMember {
+    leaf_index: LeafNodeIndex,
+    credential: Credential,
+    signature_key: SignaturePublicKey,
+}
+
+impl RemovedMember {
+    /// Returns the leaf index of the member in the current epoch.
+    pub fn leaf_index(&self) -> LeafNodeIndex {
+        self.leaf_index
+    }
+
+    /// Returns the credential of the member.
+    pub fn credential(&self) -> &Credential {
+        &self.credential
+    }
+
+    /// Returns the signature key of the member.
+    pub fn signature_key(&self) -> &SignaturePublicKey {
+        &self.signature_key
+    }
+}
+
+/// A member whose leaf node is replaced by a [`StagedCommit`]. See
+/// [`StagedCommit::updated_members()`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdatedMember<'a> {
+    leaf_index: LeafNodeIndex,
+    leaf_node: &'a LeafNode,
+    committer: bool,
+}
+
+impl<'a> UpdatedMember<'a> {
+    /// Returns the leaf index of the member.
+    pub fn leaf_index(&self) -> LeafNodeIndex {
+        self.leaf_index
+    }
+
+    /// Returns the new leaf node of the member.
+    pub fn leaf_node(&self) -> &'a LeafNode {
+        self.leaf_node
+    }
+
+    /// Returns `true` if the member is the committer, whose leaf node is
+    /// replaced through the update path, and `false` if it is replaced through
+    /// an Update proposal.
+    pub fn is_committer(&self) -> bool {
+        self.committer
+    }
+}
+
+/// How a [`StagedCommit`] changes the own leaf. See
+/// [`StagedCommit::own_leaf_change()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OwnLeafChange {
+    /// The own client is removed from the group.
+    Removed,
+    /// The own leaf node is replaced, either through the update path of an
+    /// own commit or through an own Update proposal.
+    Updated,
+}
+
+/// A change of the credential or the signature key of a member in a
+/// [`StagedCommit`]. See [`StagedCommit::credential_updates()`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialUpdate {
+    leaf_index: LeafNodeIndex,
+    previous_credential: Credential,
+    previous_signature_key: SignaturePublicKey,
+    credential: Credential,
+    signature_key: SignaturePublicKey,
+}
+
+impl CredentialUpdate {
+    /// Returns the leaf index of the member.
+    pub fn leaf_index(&self) -> LeafNodeIndex {
+        self.leaf_index
+    }
+
+    /// Returns the credential of the member before the commit.
+    pub fn previous_credential(&self) -> &Credential {
+        &self.previous_credential
+    }
+
+    /// Returns the signature key of the member before the commit.
+    pub fn previous_signature_key(&self) -> &SignaturePublicKey {
+        &self.previous_signature_key
+    }
+
+    /// Returns the credential of the member after the commit.
+    pub fn credential(&self) -> &Credential {
+        &self.credential
+    }
+
+    /// Returns the signature key of the member after the commit.
+    pub fn signature_key(&self) -> &SignaturePublicKey {
+        &self.signature_key
+    }
+}
+
+/// The changes of the group context extensions in a [`StagedCommit`]. See
+/// [`StagedCommit::group_context_extension_changes()`].
+///
+/// Extensions are matched by their [`ExtensionType`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupContextExtensionChanges {
+    added: Vec<Extension>,
+    removed: Vec<Extension>,
+    modified: Vec<ModifiedExtension>,
+}
+
+impl GroupContextExtensionChanges {
+    /// Compares the `previous` extensions of the group context with the
+    /// `extensions` after the commit.
+    fn new(previous: &Extensions, extensions: &Extensions) -> Self {
+        let added = extensions
+            .iter()
+            .filter(|extension| !previous.contains(extension.extension_type()))
+            .cloned()
+            .collect();
+        let removed = previous
+            .iter()
+            .filter(|extension| !extensions.contains(extension.extension_type()))
+            .cloned()
+            .collect();
+        let modified = extensions
+            .iter()
+            .filter_map(|extension| {
+                let previous = previous
+                    .iter()
+                    .find(|previous| previous.extension_type() == extension.extension_type())?;
+                (previous != extension).then(|| ModifiedExtension {
+                    previous: previous.clone(),
+                    extension: extension.clone(),
+                })
+            })
+            .collect();
+        Self {
+            added,
+            removed,
+            modified,
+        }
+    }
+
+    /// Returns the extensions that the commit adds to the group context.
+    pub fn added(&self) -> &[Extension] {
+        &self.added
+    }
+
+    /// Returns the extensions that the commit removes from the group context.
+    pub fn removed(&self) -> &[Extension] {
+        &self.removed
+    }
+
+    /// Returns the extensions whose content the commit changes.
+    pub fn modified(&self) -> &[ModifiedExtension] {
+        &self.modified
+    }
+
+    /// Returns `true` if the commit doesn't change the group context
+    /// extensions.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// A group context extension whose content is changed by a [`StagedCommit`].
+/// See [`GroupContextExtensionChanges::modified()`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModifiedExtension {
+    previous: Extension,
+    extension: Extension,
+}
+
+impl ModifiedExtension {
+    /// Returns the type of the extension.
+    pub fn extension_type(&self) -> ExtensionType {
+        self.extension.extension_type()
+    }
+
+    /// Returns the extension before the commit.
+    pub fn previous(&self) -> &Extension {
+        &self.previous
+    }
+
+    /// Returns the extension after the commit.
+    pub fn extension(&self) -> &Extension {
+        &self.extension
+    }
 }
 
 /// This struct is used internally by [StagedCommit] to encapsulate all the modified group state.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct MemberStagedCommitState {
     group_epoch_secrets: GroupEpochSecrets,
     message_secrets: MessageSecrets,
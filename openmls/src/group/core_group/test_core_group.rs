@@ -129,12 +129,15 @@ fn test_failed_groupinfo_decryption(
     let welcome_nonce = AeadNonce::random(backend);
 
     // Generate receiver key pair.
-    let receiver_key_pair = backend.crypto().derive_hpke_keypair(
-        ciphersuite.hpke_config(),
-        Secret::random(ciphersuite, backend, None)
-            .expect("Not enough randomness.")
-            .as_slice(),
-    );
+    let receiver_key_pair = backend
+        .crypto()
+        .derive_hpke_keypair(
+            ciphersuite.hpke_config(),
+            Secret::random(ciphersuite, backend, None)
+                .expect("Not enough randomness.")
+                .as_slice(),
+        )
+        .expect("Error deriving an HPKE key pair.");
     let hpke_context = b"group info welcome test info";
     let group_secrets = b"these should be the group secrets";
     let mut encrypted_group_secrets = hpke::encrypt_with_label(
@@ -183,6 +186,7 @@ fn test_failed_groupinfo_decryption(
         key_package_bundle,
         backend,
         ResumptionPskStore::new(1024),
+        None,
     )
     .expect_err("Creation of core group from a broken Welcome was successful.");
 
@@ -411,6 +415,7 @@ fn test_psks(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
         bob_key_package_bundle,
         backend,
         ResumptionPskStore::new(1024),
+        None,
     )
     .expect("Could not create new group from Welcome");
 
@@ -504,6 +509,7 @@ fn test_staged_commit_creation(ciphersuite: Ciphersuite, backend: &impl OpenMlsC
         bob_key_package_bundle,
         backend,
         ResumptionPskStore::new(1024),
+        None,
     )
     .expect("An unexpected error occurred.");
 
@@ -657,6 +663,7 @@ fn test_proposal_application_after_self_was_removed(
         bob_kpb,
         backend,
         ResumptionPskStore::new(1024),
+        None,
     )
     .expect("Error joining group.");
 
@@ -737,6 +744,7 @@ fn test_proposal_application_after_self_was_removed(
         charlie_kpb,
         backend,
         ResumptionPskStore::new(1024),
+        None,
     )
     .expect("Error joining group.");
 
@@ -10,6 +10,7 @@ use crate::{
     },
     messages::proposals::{ProposalOrRef, ProposalType},
     test_utils::*,
+    treesync::RatchetTreeSource,
 };
 
 use openmls_rust_crypto::OpenMlsRustCrypto;
@@ -48,8 +49,9 @@ fn test_external_init(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProv
         backend,
         &charlie_signer,
         params,
-        None,
+        RatchetTreeSource::Extension,
         verifiable_group_info,
+        None,
     )
     .expect("Error initializing group externally.");
 
@@ -126,8 +128,9 @@ fn test_external_init(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProv
         backend,
         &bob_signer,
         params,
-        Some(ratchet_tree.into()),
+        RatchetTreeSource::Provided(ratchet_tree.into()),
         verifiable_group_info,
+        None,
     )
     .expect("Error initializing group externally.");
 
@@ -213,8 +216,9 @@ fn test_external_init_single_member_group(
         backend,
         &charly_signer,
         params,
-        Some(ratchet_tree.into()),
+        RatchetTreeSource::Provided(ratchet_tree.into()),
         verifiable_group_info,
+        None,
     )
     .expect("Error initializing group externally.");
 
@@ -280,8 +284,9 @@ fn test_external_init_broken_signature(
             backend,
             &charlie_signer,
             params,
+            RatchetTreeSource::Extension,
+            verifiable_group_info,
             None,
-            verifiable_group_info
         )
         .expect_err("Signature was corrupted. This should have failed.")
     );
@@ -1,8 +1,12 @@
 //! This module contains tests regarding the use of [`MessageSecretsStore`]
 
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use crate::{
-    binary_tree::LeafNodeIndex, group::past_secrets::MessageSecretsStore,
-    schedule::message_secrets::MessageSecrets, test_utils::*,
+    binary_tree::LeafNodeIndex,
+    group::{past_secrets::MessageSecretsStore, GroupEpoch, PastEpochRetention},
+    schedule::message_secrets::MessageSecrets,
+    test_utils::*,
 };
 
 #[apply(ciphersuites_and_backends)]
@@ -62,3 +66,54 @@ fn test_empty_secret_tree_store(ciphersuite: Ciphersuite, backend: &impl OpenMls
     // Make sure we cannot access the message secrets we just stored
     assert!(message_secrets_store.secrets_for_epoch_mut(0).is_none());
 }
+
+#[apply(ciphersuites_and_backends)]
+fn test_secret_tree_store_retention(
+    ciphersuite: Ciphersuite,
+    backend: &impl OpenMlsCryptoProvider,
+) {
+    // Create a store that keeps up to 5 epochs
+    let mut message_secrets_store = MessageSecretsStore::new_with_secret(
+        5,
+        MessageSecrets::random(ciphersuite, backend, LeafNodeIndex::new(0)),
+    );
+    for i in 0..5u64 {
+        message_secrets_store.add(
+            i,
+            MessageSecrets::random(ciphersuite, backend, LeafNodeIndex::new(0)),
+            Vec::new(),
+        );
+    }
+    assert_eq!(
+        message_secrets_store.past_epochs(),
+        (0..5u64).map(GroupEpoch::from).collect::<Vec<_>>()
+    );
+
+    // Shrinking the store drops the oldest epochs
+    message_secrets_store.set_retention(&PastEpochRetention::new(3));
+    assert_eq!(
+        message_secrets_store.past_epochs(),
+        (2..5u64).map(GroupEpoch::from).collect::<Vec<_>>()
+    );
+
+    // Force-dropping epochs only drops older epochs
+    assert_eq!(message_secrets_store.drop_epochs_before(3), 1);
+    assert!(message_secrets_store.secrets_for_epoch(2).is_none());
+    assert!(message_secrets_store.secrets_for_epoch(3).is_some());
+    assert_eq!(message_secrets_store.drop_epochs_before(3), 0);
+
+    // Without a maximum age, nothing expires
+    assert_eq!(message_secrets_store.drop_expired_at(u64::MAX), 0);
+
+    // With a maximum age, epochs expire once it has passed
+    message_secrets_store
+        .set_retention(&PastEpochRetention::new(3).with_max_age(Duration::from_secs(60)));
+    assert_eq!(message_secrets_store.past_epochs().len(), 2);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("SystemTime before UNIX EPOCH!")
+        .as_secs();
+    assert_eq!(message_secrets_store.drop_expired_at(now + 30), 0);
+    assert_eq!(message_secrets_store.drop_expired_at(now + 120), 2);
+    assert!(message_secrets_store.past_epochs().is_empty());
+}
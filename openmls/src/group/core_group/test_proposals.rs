@@ -434,6 +434,7 @@ fn test_group_context_extensions(ciphersuite: Ciphersuite, backend: &impl OpenMl
         bob_key_package_bundle,
         backend,
         ResumptionPskStore::new(1024),
+        None,
     )
     .expect("Error joining group.");
 }
@@ -529,6 +530,7 @@ fn test_group_context_extension_proposal_fails(
         bob_key_package_bundle,
         backend,
         ResumptionPskStore::new(1024),
+        None,
     )
     .expect("Error joining group.");
 
@@ -610,6 +612,7 @@ fn test_group_context_extension_proposal(
         bob_key_package_bundle,
         backend,
         ResumptionPskStore::new(1024),
+        None,
     )
     .expect("Error joining group.");
 
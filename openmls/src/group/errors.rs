@@ -2,15 +2,19 @@
 //!
 //! This module contains errors that originate at lower levels and are partially re-exported in errors thrown by functions of the `MlsGroup` API.
 
+use std::convert::Infallible;
+
 use thiserror::Error;
 
 pub use super::mls_group::errors::*;
 use super::public_group::errors::{CreationFromExternalError, PublicGroupBuildError};
+use super::{GroupEpoch, GroupId};
 use crate::{
-    ciphersuite::signable::SignatureError,
+    binary_tree::LeafNodeIndex,
+    ciphersuite::{policy::CiphersuitePolicyError, signable::SignatureError},
     error::LibraryError,
     extensions::errors::{ExtensionError, InvalidExtensionError},
-    framing::errors::{MessageDecryptionError, SenderError},
+    framing::errors::{DecodeLimitError, MessageDecryptionError, SenderError},
     key_packages::errors::KeyPackageVerifyError,
     key_packages::errors::{KeyPackageExtensionSupportError, KeyPackageNewError},
     messages::{group_info::GroupInfoError, GroupSecretsError},
@@ -88,17 +92,37 @@ pub enum WelcomeError<KeyStoreError> {
     /// This error indicates the leaf node is invalid. See [`LeafNodeValidationError`] for more details.
     #[error(transparent)]
     LeafNodeValidation(#[from] LeafNodeValidationError),
+    /// A credential binding doesn't belong to a member or its signature is
+    /// invalid.
+    #[error("A credential binding doesn't belong to a member or its signature is invalid.")]
+    InvalidCredentialBinding,
+    /// The ciphersuite of the Welcome is not permitted by the
+    /// [`CiphersuitePolicy`](crate::ciphersuite::policy::CiphersuitePolicy).
+    #[error(transparent)]
+    CiphersuitePolicy(#[from] CiphersuitePolicyError),
+    /// The lifetime of the leaf node at the given index is not acceptable
+    /// under the [`LifetimePolicy`](crate::key_packages::LifetimePolicy)
+    /// used to join the group.
+    #[error("The lifetime of the leaf node at index {0:?} is not acceptable.")]
+    InvalidLifetime(LeafNodeIndex),
+    /// A group with the ID of the group of the Welcome is already in the
+    /// storage and must not be replaced.
+    #[error("The group {0:?} already exists.")]
+    GroupAlreadyExists(GroupId),
 }
 
 /// External Commit error
 #[derive(Error, Debug, PartialEq, Clone)]
-pub enum ExternalCommitError {
+pub enum ExternalCommitError<KeyStoreError = Infallible> {
     /// See [`LibraryError`] for more details.
     #[error(transparent)]
     LibraryError(#[from] LibraryError),
     /// No ratchet tree available to build initial tree.
     #[error("No ratchet tree available to build initial tree.")]
     MissingRatchetTree,
+    /// The compressed ratchet tree in the group info is invalid.
+    #[error("The compressed ratchet tree in the group info is invalid.")]
+    MalformedRatchetTree,
     /// No external_pub extension available to join group by external commit.
     #[error("No external_pub extension available to join group by external commit.")]
     MissingExternalPub,
@@ -121,6 +145,38 @@ pub enum ExternalCommitError {
     /// Credential is missing from external commit.
     #[error("Credential is missing from external commit.")]
     MissingCredential,
+    /// Error writing the group state to the storage.
+    #[error("Error writing the group state to the storage.")]
+    StorageError(KeyStoreError),
+    /// The ciphersuite of the group is not permitted by the
+    /// [`CiphersuitePolicy`](crate::ciphersuite::policy::CiphersuitePolicy).
+    #[error(transparent)]
+    CiphersuitePolicy(#[from] CiphersuitePolicyError),
+}
+
+impl ExternalCommitError {
+    /// Converts an error of an operation that doesn't write to the storage
+    /// into the error of an [`MlsGroup`](super::MlsGroup) operation that does.
+    pub(crate) fn with_storage_error<KeyStoreError>(self) -> ExternalCommitError<KeyStoreError> {
+        match self {
+            ExternalCommitError::LibraryError(e) => ExternalCommitError::LibraryError(e),
+            ExternalCommitError::MissingRatchetTree => ExternalCommitError::MissingRatchetTree,
+            ExternalCommitError::MalformedRatchetTree => ExternalCommitError::MalformedRatchetTree,
+            ExternalCommitError::MissingExternalPub => ExternalCommitError::MissingExternalPub,
+            ExternalCommitError::UnsupportedCiphersuite => {
+                ExternalCommitError::UnsupportedCiphersuite
+            }
+            ExternalCommitError::UnknownSender => ExternalCommitError::UnknownSender,
+            ExternalCommitError::InvalidGroupInfoSignature => {
+                ExternalCommitError::InvalidGroupInfoSignature
+            }
+            ExternalCommitError::CommitError => ExternalCommitError::CommitError,
+            ExternalCommitError::PublicGroupError(e) => ExternalCommitError::PublicGroupError(e),
+            ExternalCommitError::MissingCredential => ExternalCommitError::MissingCredential,
+            ExternalCommitError::StorageError(e) => match e {},
+            ExternalCommitError::CiphersuitePolicy(e) => ExternalCommitError::CiphersuitePolicy(e),
+        }
+    }
 }
 
 /// Stage Commit error
@@ -233,6 +289,12 @@ pub enum CreateCommitError<KeyStoreError> {
     /// See [`InvalidExtensionError`] for more details.
     #[error(transparent)]
     InvalidExtensionError(#[from] InvalidExtensionError),
+    /// A credential binding doesn't belong to an added key package or its
+    /// signature is invalid.
+    #[error(
+        "A credential binding doesn't belong to an added key package or its signature is invalid."
+    )]
+    InvalidCredentialBinding,
 }
 
 /// Validation error
@@ -245,8 +307,13 @@ pub enum ValidationError {
     #[error("Message group ID differs from the group's group ID.")]
     WrongGroupId,
     /// Message epoch differs from the group's epoch.
-    #[error("Message epoch differs from the group's epoch.")]
-    WrongEpoch,
+    #[error("Message epoch {received} differs from the group's epoch {expected}.")]
+    WrongEpoch {
+        /// The epoch of the message.
+        received: GroupEpoch,
+        /// The epoch of the group.
+        expected: GroupEpoch,
+    },
     /// The PublicMessage is not a Commit despite the sender begin of type [NewMemberCommit](crate::prelude::Sender::NewMemberCommit).
     #[error("The PublicMessage is not a Commit despite the sender begin of type NewMemberCommit.")]
     NotACommit,
@@ -315,6 +382,12 @@ pub enum ValidationError {
         "The ciphersuite in the KeyPackage of the Add proposal does not match the group context."
     )]
     InvalidAddProposalCiphersuite,
+    /// The message exceeds the decode limits of the group.
+    #[error(transparent)]
+    DecodeLimitExceeded(#[from] DecodeLimitError),
+    /// The proposal requires the sender to be an admin of the group.
+    #[error("The proposal requires the sender to be an admin of the group.")]
+    SenderNotAdmin,
 }
 
 /// Proposal validation error
@@ -358,9 +431,24 @@ pub enum ProposalValidationError {
         "The add proposal's ciphersuite or protocol version do not match the ones in the group context."
     )]
     InvalidAddProposalCiphersuiteOrVersion,
+    /// The lifetime of the key package in an add proposal is not acceptable.
+    #[error("The lifetime of the key package in an add proposal is not acceptable.")]
+    InvalidAddProposalLifetime,
     /// See [`PskError`] for more details.
     #[error(transparent)]
     Psk(#[from] PskError),
+    /// The commit contains more than one group context extensions proposal.
+    #[error("The commit contains more than one group context extensions proposal.")]
+    MultipleGroupContextExtensionsProposals,
+    /// A group member doesn't support the proposed group context extensions.
+    #[error("A group member doesn't support the proposed group context extensions.")]
+    UnsupportedGroupContextExtensions,
+    /// Not all members support the credential types of the external senders.
+    #[error("Not all members support the credential types of the external senders.")]
+    UnsupportedExternalSenderCredential,
+    /// The proposal requires the sender to be an admin of the group.
+    #[error("The proposal requires the sender to be an admin of the group.")]
+    SenderNotAdmin,
 }
 
 /// External Commit validaton error
@@ -395,6 +483,10 @@ pub enum ExternalCommitValidationError {
     /// External commit contains referenced proposal
     #[error("Found a referenced proposal in an External Commit.")]
     ReferencedProposal,
+    /// The group has admins, and the joiner is neither an admin nor re-joining
+    /// the group.
+    #[error("The group has admins, and the joiner is neither an admin nor re-joining the group.")]
+    JoinerNotAdmin,
 }
 
 /// Create add proposal error
@@ -500,16 +592,13 @@ pub(crate) enum CoreGroupParseMessageError {
 
 /// Create group context ext proposal error
 #[derive(Error, Debug, PartialEq, Clone)]
-pub(crate) enum CreateGroupContextExtProposalError {
+pub enum CreateGroupContextExtProposalError {
     /// See [`LibraryError`] for more details.
     #[error(transparent)]
     LibraryError(#[from] LibraryError),
     /// See [`KeyPackageExtensionSupportError`] for more details.
     #[error(transparent)]
     KeyPackageExtensionSupport(#[from] KeyPackageExtensionSupportError),
-    /// See [`TreeSyncError`] for more details.
-    #[error(transparent)]
-    TreeSyncError(#[from] TreeSyncError),
     /// See [`ExtensionError`] for more details.
     #[error(transparent)]
     Extension(#[from] ExtensionError),
@@ -527,4 +616,7 @@ pub enum MergeCommitError<KeyStoreError> {
     /// Error accessing the key store.
     #[error("Error accessing the key store.")]
     KeyStoreError(KeyStoreError),
+    /// Error writing the group state to the storage.
+    #[error("Error writing the group state to the storage.")]
+    StorageError(KeyStoreError),
 }
@@ -8,6 +8,7 @@ use super::*;
 use crate::{
     error::LibraryError,
     framing::{mls_auth_content::AuthenticatedContent, ConfirmedTranscriptHashInput},
+    treesync::RatchetTree,
     versions::ProtocolVersion,
 };
 
@@ -90,6 +91,11 @@ impl GroupContext {
         self.epoch.increment()
     }
 
+    /// Replace the current extensions with the given [`Extensions`].
+    pub(crate) fn set_extensions(&mut self, extensions: Extensions) {
+        self.extensions = extensions;
+    }
+
     /// Update the current tree hash to the new value
     pub(crate) fn update_tree_hash(&mut self, new_tree_hash: Vec<u8>) {
         self.tree_hash = new_tree_hash.into()
@@ -158,4 +164,32 @@ impl GroupContext {
     pub fn required_capabilities(&self) -> Option<&RequiredCapabilitiesExtension> {
         self.extensions.required_capabilities()
     }
+
+    /// Returns the extension that carries the `ratchet_tree` in a GroupInfo
+    /// of this group.
+    ///
+    /// The ratchet tree is compressed if the required capabilities of the
+    /// group contain [`ExtensionType::CompressedRatchetTree`], i.e. if all
+    /// members and new members can decompress it. Groups can only require it
+    /// with the `tree-compression` feature.
+    pub(crate) fn ratchet_tree_extension(
+        &self,
+        ratchet_tree: RatchetTree,
+    ) -> Result<Extension, LibraryError> {
+        #[cfg(feature = "tree-compression")]
+        if self
+            .required_capabilities()
+            .map_or(false, |required_capabilities| {
+                required_capabilities
+                    .extension_types()
+                    .contains(&ExtensionType::CompressedRatchetTree)
+            })
+        {
+            return CompressedRatchetTreeExtension::new(ratchet_tree)
+                .map(Extension::CompressedRatchetTree);
+        }
+        Ok(Extension::RatchetTree(RatchetTreeExtension::new(
+            ratchet_tree,
+        )))
+    }
 }
@@ -0,0 +1,67 @@
+//! Management of the admins of an [`MlsGroup`].
+//!
+//! The admins are listed in the [`AdminsExtension`] of the group context. See
+//! the extension for the proposals that are restricted to admins.
+
+use openmls_traits::signatures::Signer;
+
+use crate::{
+    credentials::Credential,
+    extensions::{AdminsExtension, Extension, ExtensionType},
+    messages::group_info::GroupInfo,
+};
+
+use super::*;
+
+impl MlsGroup {
+    /// Returns the [`AdminsExtension`] of the group context, if any.
+    pub fn admins(&self) -> Option<&AdminsExtension> {
+        self.group.public_group().admins()
+    }
+
+    /// Returns `true` if the member at `leaf_index` is an admin of the group.
+    ///
+    /// Returns `false` if the group has no admins.
+    pub fn is_admin(&self, leaf_index: LeafNodeIndex) -> bool {
+        self.group.public_group().is_admin(leaf_index)
+    }
+
+    /// Replaces the admins of the group with the given credentials by
+    /// committing a `GroupContextExtensions` proposal, see
+    /// [`MlsGroup::update_group_context_extensions()`]. The other group
+    /// context extensions are kept.
+    ///
+    /// If the group already has admins, only an admin can change them.
+    /// Otherwise, any member can set the initial admins.
+    #[allow(clippy::type_complexity)]
+    pub fn set_admins<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        admins: Vec<Credential>,
+    ) -> Result<
+        (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
+        UpdateGroupContextExtensionsError<KeyStore::Error>,
+    > {
+        let mut extensions = self.group.context().extensions().clone();
+        extensions.add_or_replace(Extension::Admins(AdminsExtension::new(admins)));
+        self.update_group_context_extensions(backend, signer, extensions)
+    }
+
+    /// Removes the [`AdminsExtension`] from the group context by committing a
+    /// `GroupContextExtensions` proposal, so that all members can add and
+    /// remove members again. Only an admin can remove the extension.
+    #[allow(clippy::type_complexity)]
+    pub fn clear_admins<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+    ) -> Result<
+        (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
+        UpdateGroupContextExtensionsError<KeyStore::Error>,
+    > {
+        let mut extensions = self.group.context().extensions().clone();
+        extensions.remove(ExtensionType::Admins);
+        self.update_group_context_extensions(backend, signer, extensions)
+    }
+}
@@ -1,4 +1,4 @@
-use openmls_traits::signatures::Signer;
+use openmls_traits::signatures::{AsyncSigner, Signer};
 
 use super::{errors::CreateMessageError, *};
 
@@ -11,12 +11,22 @@ impl MlsGroup {
     /// Returns `CreateMessageError::MlsGroupStateError::PendingProposal` if pending proposals
     /// exist. In that case `.process_pending_proposals()` must be called first
     /// and incoming messages from the DS must be processed afterwards.
-    pub fn create_message(
+    pub fn create_message<KeyStore: OpenMlsKeyStore>(
         &mut self,
-        backend: &impl OpenMlsCryptoProvider,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         signer: &impl Signer,
         message: &[u8],
-    ) -> Result<MlsMessageOut, CreateMessageError> {
+    ) -> Result<MlsMessageOut, CreateMessageError<KeyStore::Error>> {
+        crate::utils::poll_ready(self.create_message_internal(backend, signer, message))
+    }
+
+    /// Like [`MlsGroup::create_message()`], but signs with an [`AsyncSigner`].
+    pub(super) async fn create_message_internal<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl AsyncSigner,
+        message: &[u8],
+    ) -> Result<MlsMessageOut, CreateMessageError<KeyStore::Error>> {
         if !self.is_active() {
             return Err(CreateMessageError::GroupStateError(
                 MlsGroupStateError::UseAfterEviction,
@@ -30,18 +40,18 @@ impl MlsGroup {
 
         let ciphertext = self
             .group
-            .create_application_message(
+            .create_application_message_async(
                 &self.aad,
                 message,
-                self.configuration().padding_size(),
+                self.configuration().padding_policy(),
                 backend,
                 signer,
             )
+            .await
             // We know the application message is wellformed and we have the key material of the current epoch
             .map_err(|_| LibraryError::custom("Malformed plaintext"))?;
-
-        // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.store(backend)
+            .map_err(CreateMessageError::StorageError)?;
 
         Ok(MlsMessageOut::from_private_message(
             ciphertext,
@@ -0,0 +1,837 @@
+//! Async operations
+//!
+//! This module contains async variants of the main [`MlsGroup`] operations
+//! for use with an [`OpenMlsAsyncCryptoProvider`], i.e. with a key store that
+//! is accessed asynchronously, e.g. because it is backed by a remote service.
+//!
+//! Each async variant first prefetches the key material that the operation
+//! needs from the async key store, then runs the operation on top of an
+//! in-memory key store and finally writes all changes of the operation to
+//! the async key store.
+//!
+//! The operations that create commits, proposals or application messages
+//! take an [`AsyncSigner`], such that signing can be delegated to a remote
+//! signing service or a secure enclave. Every [`Signer`] is an
+//! [`AsyncSigner`] as well. Creating a new group still requires a
+//! synchronous signer.
+//!
+//! The changes of an operation are made on a copy of the group, which only
+//! replaces the group once the changes were written to the async key store.
+//! If writing fails, the group is left untouched.
+//!
+//! Key material that is not prefetched appears to be missing from the key
+//! store during the operation. This includes the pre-shared keys the
+//! operation needs, which are prefetched like the other key material. The
+//! variants that take an [`AsyncPskResolver`] additionally resolve the PSKs
+//! that are not in the async key store through the resolver.
+
+use std::{collections::HashMap, sync::RwLock};
+
+use openmls_traits::{
+    key_store::{MlsEntity, MlsEntityId, OpenMlsAsyncKeyStore, WriteBatch},
+    signatures::{AsyncSigner, Signer},
+    OpenMlsAsyncCryptoProvider,
+};
+use thiserror::Error;
+
+use super::{proposal::Propose, *};
+use crate::{
+    ciphersuite::HpkePrivateKey,
+    credentials::CredentialWithKey,
+    framing::mls_content_in::FramedContentBodyIn,
+    group::errors::{MergeCommitError, StageCommitError, WelcomeError},
+    messages::{
+        group_info::GroupInfo,
+        proposals_in::{ProposalIn, ProposalOrRefIn},
+    },
+    schedule::{
+        errors::PskError,
+        psk::{AsyncPskResolver, PskBundle, PskResolver},
+        PreSharedKeyId,
+    },
+    treesync::{node::encryption_keys::EncryptionKeyPair, RatchetTreeIn},
+};
+
+/// The error type of the key store of an [`OpenMlsAsyncCryptoProvider`].
+type AsyncKeyStoreError<Provider> =
+    <<Provider as OpenMlsAsyncCryptoProvider>::KeyStoreProvider as OpenMlsAsyncKeyStore>::Error;
+
+impl MlsGroup {
+    // === Group creation ===
+
+    /// Async variant of [`MlsGroup::new()`].
+    pub async fn new_async<Provider: OpenMlsAsyncCryptoProvider>(
+        backend: &Provider,
+        signer: &impl Signer,
+        mls_group_config: &MlsGroupConfig,
+        credential_with_key: CredentialWithKey,
+    ) -> Result<
+        Self,
+        AsyncOperationError<NewGroupError<BufferedKeyStoreError>, AsyncKeyStoreError<Provider>>,
+    > {
+        let provider = BufferedProvider::new(backend);
+        let group = Self::new(&provider, signer, mls_group_config, credential_with_key)
+            .map_err(AsyncOperationError::OperationError)?;
+        provider.flush().await?;
+        Ok(group)
+    }
+
+    /// Async variant of [`MlsGroup::new_from_welcome()`].
+    pub async fn new_from_welcome_async<Provider: OpenMlsAsyncCryptoProvider>(
+        backend: &Provider,
+        mls_group_config: &MlsGroupConfig,
+        welcome: Welcome,
+        ratchet_tree: Option<RatchetTreeIn>,
+    ) -> Result<
+        Self,
+        AsyncOperationError<WelcomeError<BufferedKeyStoreError>, AsyncKeyStoreError<Provider>>,
+    > {
+        Self::new_from_welcome_with_psk_resolver_async(
+            backend,
+            mls_group_config,
+            welcome,
+            ratchet_tree,
+            &NoPskResolver,
+        )
+        .await
+    }
+
+    /// Async variant of [`MlsGroup::new_from_welcome_with_psk_resolver()`].
+    ///
+    /// The PSKs of the group are only known once the [`Welcome`] is
+    /// decrypted. If some of them are missing, joining is repeated once with
+    /// the PSKs read from the async key store or resolved through the
+    /// `psk_resolver`. Unlike in the sync variant, the resolver is not set for
+    /// the new group.
+    pub async fn new_from_welcome_with_psk_resolver_async<Provider: OpenMlsAsyncCryptoProvider>(
+        backend: &Provider,
+        mls_group_config: &MlsGroupConfig,
+        welcome: Welcome,
+        ratchet_tree: Option<RatchetTreeIn>,
+        psk_resolver: &impl AsyncPskResolver,
+    ) -> Result<
+        Self,
+        AsyncOperationError<WelcomeError<BufferedKeyStoreError>, AsyncKeyStoreError<Provider>>,
+    > {
+        let provider = Self::prefetch_welcome_keys(backend, &welcome).await?;
+        let missing_psks = match Self::new_from_welcome(
+            &provider,
+            mls_group_config,
+            welcome.clone(),
+            ratchet_tree.clone(),
+        ) {
+            Err(WelcomeError::Psk(PskError::MissingPsks(missing_psks))) => missing_psks,
+            result => {
+                let group = result.map_err(AsyncOperationError::OperationError)?;
+                provider.flush().await?;
+                return Ok(group);
+            }
+        };
+
+        // The failed attempt is discarded together with its provider.
+        let provider = Self::prefetch_welcome_keys(backend, &welcome).await?;
+        provider
+            .resolve_psks(psk_resolver, welcome.ciphersuite(), missing_psks)
+            .await?;
+        let group = Self::new_from_welcome(&provider, mls_group_config, welcome, ratchet_tree)
+            .map_err(AsyncOperationError::OperationError)?;
+        provider.flush().await?;
+        Ok(group)
+    }
+
+    // === Membership management ===
+
+    /// Async variant of [`MlsGroup::add_members()`].
+    pub async fn add_members_async<Provider: OpenMlsAsyncCryptoProvider>(
+        &mut self,
+        backend: &Provider,
+        signer: &impl AsyncSigner,
+        key_packages: &[KeyPackage],
+    ) -> Result<
+        (MlsMessageOut, MlsMessageOut, Option<GroupInfo>),
+        AsyncOperationError<AddMembersError<BufferedKeyStoreError>, AsyncKeyStoreError<Provider>>,
+    > {
+        let provider = self.prefetch_epoch_keys(backend).await?;
+        let mut group = self.copy();
+        let result = group
+            .add_members_internal(&provider, signer, key_packages)
+            .await
+            .map_err(AsyncOperationError::OperationError)?;
+        provider.flush().await?;
+        self.replace_with_copy(group);
+        Ok(result)
+    }
+
+    /// Async variant of [`MlsGroup::remove_members()`].
+    pub async fn remove_members_async<Provider: OpenMlsAsyncCryptoProvider>(
+        &mut self,
+        backend: &Provider,
+        signer: &impl AsyncSigner,
+        members: &[LeafNodeIndex],
+    ) -> Result<
+        (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
+        AsyncOperationError<
+            RemoveMembersError<BufferedKeyStoreError>,
+            AsyncKeyStoreError<Provider>,
+        >,
+    > {
+        let provider = self.prefetch_epoch_keys(backend).await?;
+        let mut group = self.copy();
+        let result = group
+            .remove_members_internal(&provider, signer, members)
+            .await
+            .map_err(AsyncOperationError::OperationError)?;
+        provider.flush().await?;
+        self.replace_with_copy(group);
+        Ok(result)
+    }
+
+    /// Async variant of [`MlsGroup::self_update()`].
+    pub async fn self_update_async<Provider: OpenMlsAsyncCryptoProvider>(
+        &mut self,
+        backend: &Provider,
+        signer: &impl AsyncSigner,
+    ) -> Result<
+        (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
+        AsyncOperationError<SelfUpdateError<BufferedKeyStoreError>, AsyncKeyStoreError<Provider>>,
+    > {
+        let provider = self.prefetch_epoch_keys(backend).await?;
+        let mut group = self.copy();
+        let result = group
+            .self_update_internal(&provider, signer)
+            .await
+            .map_err(AsyncOperationError::OperationError)?;
+        provider.flush().await?;
+        self.replace_with_copy(group);
+        Ok(result)
+    }
+
+    /// Async variant of [`MlsGroup::commit_to_pending_proposals()`].
+    pub async fn commit_to_pending_proposals_async<Provider: OpenMlsAsyncCryptoProvider>(
+        &mut self,
+        backend: &Provider,
+        signer: &impl AsyncSigner,
+    ) -> Result<
+        (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
+        AsyncOperationError<
+            CommitToPendingProposalsError<BufferedKeyStoreError>,
+            AsyncKeyStoreError<Provider>,
+        >,
+    > {
+        let provider = self.prefetch_epoch_keys(backend).await?;
+        let mut group = self.copy();
+        let result = group
+            .commit_to_pending_proposals_internal(&provider, signer)
+            .await
+            .map_err(AsyncOperationError::OperationError)?;
+        provider.flush().await?;
+        self.replace_with_copy(group);
+        Ok(result)
+    }
+
+    /// Async variant of [`MlsGroup::commit_to_pending_proposals()`] that
+    /// resolves the PSKs of the pending PSK proposals through the
+    /// `psk_resolver` if they are not in the async key store.
+    pub async fn commit_to_pending_proposals_with_psk_resolver_async<
+        Provider: OpenMlsAsyncCryptoProvider,
+    >(
+        &mut self,
+        backend: &Provider,
+        signer: &impl AsyncSigner,
+        psk_resolver: &impl AsyncPskResolver,
+    ) -> Result<
+        (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
+        AsyncOperationError<
+            CommitToPendingProposalsError<BufferedKeyStoreError>,
+            AsyncKeyStoreError<Provider>,
+        >,
+    > {
+        let provider = self.prefetch_epoch_keys(backend).await?;
+        provider
+            .resolve_psks(psk_resolver, self.ciphersuite(), self.pending_psk_ids())
+            .await?;
+        let mut group = self.copy();
+        let result = group
+            .commit_to_pending_proposals_internal(&provider, signer)
+            .await
+            .map_err(AsyncOperationError::OperationError)?;
+        provider.flush().await?;
+        self.replace_with_copy(group);
+        Ok(result)
+    }
+
+    // === Proposals ===
+
+    /// Async variant of [`MlsGroup::propose()`].
+    pub async fn propose_async<Provider: OpenMlsAsyncCryptoProvider>(
+        &mut self,
+        backend: &Provider,
+        signer: &impl AsyncSigner,
+        propose: Propose,
+        ref_or_value: ProposalOrRefType,
+    ) -> Result<
+        (MlsMessageOut, ProposalRef),
+        AsyncOperationError<ProposalError<BufferedKeyStoreError>, AsyncKeyStoreError<Provider>>,
+    > {
+        let provider = self.prefetch_epoch_keys(backend).await?;
+        let mut group = self.copy();
+        let result = group
+            .propose_internal(&provider, signer, propose, ref_or_value)
+            .await
+            .map_err(AsyncOperationError::OperationError)?;
+        provider.flush().await?;
+        self.replace_with_copy(group);
+        Ok(result)
+    }
+
+    // === Application messages ===
+
+    /// Async variant of [`MlsGroup::create_message()`].
+    pub async fn create_message_async<Provider: OpenMlsAsyncCryptoProvider>(
+        &mut self,
+        backend: &Provider,
+        signer: &impl AsyncSigner,
+        message: &[u8],
+    ) -> Result<
+        MlsMessageOut,
+        AsyncOperationError<
+            CreateMessageError<BufferedKeyStoreError>,
+            AsyncKeyStoreError<Provider>,
+        >,
+    > {
+        let provider = BufferedProvider::new(backend);
+        let mut group = self.copy();
+        let message = group
+            .create_message_internal(&provider, signer, message)
+            .await
+            .map_err(AsyncOperationError::OperationError)?;
+        provider.flush().await?;
+        self.replace_with_copy(group);
+        Ok(message)
+    }
+
+    // === Processing ===
+
+    /// Async variant of [`MlsGroup::process_message()`].
+    pub async fn process_message_async<Provider: OpenMlsAsyncCryptoProvider>(
+        &mut self,
+        backend: &Provider,
+        message: impl Into<ProtocolMessage>,
+    ) -> Result<
+        ProcessedMessage,
+        AsyncOperationError<
+            ProcessMessageError<BufferedKeyStoreError>,
+            AsyncKeyStoreError<Provider>,
+        >,
+    > {
+        self.process_message_with_psk_resolver_async(backend, message, &NoPskResolver)
+            .await
+    }
+
+    /// Async variant of [`MlsGroup::process_message()`] that resolves the
+    /// PSKs of a commit through the `psk_resolver` if they are not in the
+    /// async key store.
+    ///
+    /// The PSKs of the PSK proposals in the proposal store and of the PSK
+    /// proposals that a commit sent as [`PublicMessage`] contains by value are
+    /// resolved before processing. The PSK proposals by value in an encrypted
+    /// commit are only known once the commit is decrypted, so processing is
+    /// repeated once with their PSKs resolved.
+    pub async fn process_message_with_psk_resolver_async<Provider: OpenMlsAsyncCryptoProvider>(
+        &mut self,
+        backend: &Provider,
+        message: impl Into<ProtocolMessage>,
+        psk_resolver: &impl AsyncPskResolver,
+    ) -> Result<
+        ProcessedMessage,
+        AsyncOperationError<
+            ProcessMessageError<BufferedKeyStoreError>,
+            AsyncKeyStoreError<Provider>,
+        >,
+    > {
+        let message = message.into();
+        let mut psk_ids = self.pending_psk_ids();
+        if let ProtocolMessage::PublicMessage(public_message) = &message {
+            if let FramedContentBodyIn::Commit(commit) = public_message.content() {
+                psk_ids.extend(
+                    commit
+                        .proposals()
+                        .iter()
+                        .filter_map(|proposal| match proposal {
+                            ProposalOrRefIn::Proposal(ProposalIn::PreSharedKey(psk_proposal)) => {
+                                Some(psk_proposal.clone().into_psk_id())
+                            }
+                            _ => None,
+                        }),
+                );
+            }
+        }
+
+        let mut provider = self.prefetch_epoch_keys(backend).await?;
+        provider
+            .resolve_psks(psk_resolver, self.ciphersuite(), psk_ids.clone())
+            .await?;
+        let mut group = self.copy();
+        let mut result = group.process_message(&provider, message.clone());
+        let missing_psks = match &result {
+            Err(ProcessMessageError::InvalidCommit(StageCommitError::PskError(
+                PskError::MissingPsks(missing_psks),
+            ))) => missing_psks.clone(),
+            _ => vec![],
+        };
+        if !missing_psks.is_empty() {
+            // The failed attempt is discarded together with its copy and
+            // its provider.
+            psk_ids.extend(missing_psks);
+            provider = self.prefetch_epoch_keys(backend).await?;
+            provider
+                .resolve_psks(psk_resolver, self.ciphersuite(), psk_ids)
+                .await?;
+            group = self.copy();
+            result = group.process_message(&provider, message);
+        }
+
+        provider.flush().await?;
+        // Like in the sync variant, the changes are kept even if processing
+        // fails, e.g. when the message was buffered.
+        self.replace_with_copy(group);
+        result.map_err(AsyncOperationError::OperationError)
+    }
+
+    /// Async variant of [`MlsGroup::merge_staged_commit()`].
+    pub async fn merge_staged_commit_async<Provider: OpenMlsAsyncCryptoProvider>(
+        &mut self,
+        backend: &Provider,
+        staged_commit: StagedCommit,
+    ) -> Result<
+        (),
+        AsyncOperationError<MergeCommitError<BufferedKeyStoreError>, AsyncKeyStoreError<Provider>>,
+    > {
+        let provider = self.prefetch_epoch_keys(backend).await?;
+        let mut group = self.copy();
+        group
+            .merge_staged_commit(&provider, staged_commit)
+            .map_err(AsyncOperationError::OperationError)?;
+        provider.flush().await?;
+        self.replace_with_copy(group);
+        Ok(())
+    }
+
+    /// Async variant of [`MlsGroup::merge_pending_commit()`].
+    pub async fn merge_pending_commit_async<Provider: OpenMlsAsyncCryptoProvider>(
+        &mut self,
+        backend: &Provider,
+    ) -> Result<
+        (),
+        AsyncOperationError<
+            MergePendingCommitError<BufferedKeyStoreError>,
+            AsyncKeyStoreError<Provider>,
+        >,
+    > {
+        let provider = self.prefetch_epoch_keys(backend).await?;
+        let mut group = self.copy();
+        group
+            .merge_pending_commit(&provider)
+            .map_err(AsyncOperationError::OperationError)?;
+        provider.flush().await?;
+        self.replace_with_copy(group);
+        Ok(())
+    }
+
+    // === Load ===
+
+    /// Async variant of [`MlsGroup::load()`].
+    pub async fn load_async(
+        group_id: &GroupId,
+        backend: &impl OpenMlsAsyncCryptoProvider,
+    ) -> Option<MlsGroup> {
+        let key = StorageKey::GroupState {
+            group_id: group_id.as_slice(),
+        };
+        let mut mls_group: MlsGroup = backend.key_store().read(&key.to_bytes()).await?;
+        let proposal_store = backend
+            .key_store()
+            .read(&mls_group.proposal_store_key().to_bytes())
+            .await;
+        if let Some(proposal_store) = proposal_store {
+            mls_group.proposal_store = proposal_store;
+        }
+        let key = StorageKey::ReceiverState {
+            group_id: group_id.as_slice(),
+        };
+        let receiver_state: Option<processing::ReceiverState> =
+            backend.key_store().read(&key.to_bytes()).await;
+        if let Some(receiver_state) = receiver_state {
+            let mut sender_ratchets = Vec::new();
+            for &(epoch, leaf_index) in receiver_state.sender_ratchets() {
+                if let Some(ratchets) = backend
+                    .key_store()
+                    .read(&mls_group.sender_ratchets_key(epoch, leaf_index).to_bytes())
+                    .await
+                {
+                    sender_ratchets.push((epoch, leaf_index, ratchets));
+                }
+            }
+            mls_group.restore_receiver_state(receiver_state, sender_ratchets);
+        }
+        Some(mls_group)
+    }
+
+    /// Returns a [`BufferedProvider`] with the [`KeyPackage`] of the
+    /// `welcome` and its private keys prefetched.
+    async fn prefetch_welcome_keys<'a, Provider: OpenMlsAsyncCryptoProvider>(
+        backend: &'a Provider,
+        welcome: &Welcome,
+    ) -> Result<BufferedProvider<'a, Provider>, LibraryError> {
+        let provider = BufferedProvider::new(backend);
+        for egs in welcome.secrets() {
+            let hash_ref = egs.new_member();
+            let key_package = provider.prefetch::<KeyPackage>(hash_ref.as_slice()).await?;
+            if let Some(key_package) = key_package {
+                provider
+                    .prefetch::<HpkePrivateKey>(key_package.hpke_init_key().as_slice())
+                    .await?;
+                provider
+                    .prefetch::<EncryptionKeyPair>(
+                        &key_package
+                            .leaf_node()
+                            .encryption_key()
+                            .storage_key()
+                            .to_bytes(),
+                    )
+                    .await?;
+                break;
+            }
+        }
+        Ok(provider)
+    }
+
+    /// Returns the IDs of the PSKs of the PSK proposals in the proposal
+    /// store.
+    fn pending_psk_ids(&self) -> Vec<PreSharedKeyId> {
+        self.proposal_store
+            .proposals()
+            .filter_map(|queued_proposal| match queued_proposal.proposal() {
+                Proposal::PreSharedKey(psk_proposal) => Some(psk_proposal.clone().into_psk_id()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns a [`BufferedProvider`] with the key material of the current
+    /// epoch prefetched, i.e. the encryption keys of the epoch, the
+    /// encryption keys of the own leaf nodes in pending proposals and the
+    /// PSKs of pending PSK proposals.
+    async fn prefetch_epoch_keys<'a, Provider: OpenMlsAsyncCryptoProvider>(
+        &self,
+        backend: &'a Provider,
+    ) -> Result<BufferedProvider<'a, Provider>, LibraryError> {
+        let provider = BufferedProvider::new(backend);
+        provider
+            .prefetch::<Vec<EncryptionKeyPair>>(&self.group.epoch_keypairs_key().to_bytes())
+            .await?;
+        for leaf_node in &self.own_leaf_nodes {
+            provider
+                .prefetch::<EncryptionKeyPair>(&leaf_node.encryption_key().storage_key().to_bytes())
+                .await?;
+        }
+        for psk_id in self.pending_psk_ids() {
+            provider
+                .prefetch::<PskBundle>(&psk_id.keystore_id()?)
+                .await?;
+        }
+        Ok(provider)
+    }
+
+    /// Creates the proposal described by `propose`, signs it with the given
+    /// [`AsyncSigner`] and adds it to the proposal store.
+    async fn propose_internal<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl AsyncSigner,
+        propose: Propose,
+        ref_or_value: ProposalOrRefType,
+    ) -> Result<(MlsMessageOut, ProposalRef), ProposalError<KeyStore::Error>> {
+        self.is_operational()?;
+
+        let framing_parameters = self.framing_parameters();
+        let proposal = match propose {
+            Propose::Add(key_package) => {
+                self.group
+                    .create_add_proposal_async(framing_parameters, key_package, signer)
+                    .await?
+            }
+            Propose::Update(leaf_node) => {
+                self._propose_self_udpate(backend, signer, leaf_node)
+                    .await?
+            }
+            Propose::Remove(leaf_index) => {
+                self.group
+                    .create_remove_proposal_async(
+                        framing_parameters,
+                        LeafNodeIndex::new(leaf_index),
+                        signer,
+                    )
+                    .await?
+            }
+            Propose::RemoveCredential(credential) => {
+                let member_index = self
+                    .group
+                    .public_group()
+                    .members()
+                    .find(|m| m.credential == credential)
+                    .map(|m| m.index)
+                    .ok_or(ProposeRemoveMemberError::UnknownMember)?;
+                self.group
+                    .create_remove_proposal_async(framing_parameters, member_index, signer)
+                    .await?
+            }
+            #[cfg(feature = "psk")]
+            Propose::PreSharedKey(psk_id) => {
+                self.group
+                    .create_presharedkey_proposal_async(framing_parameters, psk_id, signer)
+                    .await?
+            }
+            #[cfg(not(feature = "psk"))]
+            Propose::PreSharedKey(_) => {
+                return Err(LibraryError::custom("Unsupported proposal type PreSharedKey").into())
+            }
+            #[cfg(feature = "reinit")]
+            Propose::ReInit {
+                group_id,
+                version,
+                ciphersuite,
+                extensions,
+            } => {
+                let reinit = ReInitProposal::new(group_id, version, ciphersuite, extensions);
+                self.group
+                    .create_reinit_proposal_async(framing_parameters, reinit, signer)
+                    .await?
+            }
+            Propose::ExternalInit(_) => {
+                return Err(LibraryError::custom("Unsupported proposal type ExternalInit").into())
+            }
+            Propose::GroupContextExtensions(extensions) => {
+                self.group
+                    .create_group_context_ext_proposal_async(framing_parameters, extensions, signer)
+                    .await?
+            }
+        };
+
+        let queued_proposal = QueuedProposal::from_authenticated_content(
+            self.ciphersuite(),
+            backend,
+            proposal.clone(),
+            ref_or_value,
+        )?;
+        let proposal_ref = queued_proposal.proposal_reference();
+        self.proposal_store.add(queued_proposal);
+
+        let mls_message = self.content_to_mls_message(proposal, backend)?;
+        self.store(backend).map_err(ProposalError::StorageError)?;
+
+        Ok((mls_message, proposal_ref))
+    }
+}
+
+/// Resolves no PSKs, such that only the PSKs in the async key store are
+/// used.
+struct NoPskResolver;
+
+impl PskResolver for NoPskResolver {
+    fn resolve(&self, _psk_id: &PreSharedKeyId) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+/// A crypto provider that uses the crypto and randomness providers of the
+/// wrapped [`OpenMlsAsyncCryptoProvider`] and a [`BufferedKeyStore`] in front
+/// of its key store.
+struct BufferedProvider<'a, Provider: OpenMlsAsyncCryptoProvider> {
+    backend: &'a Provider,
+    key_store: BufferedKeyStore<'a, Provider::KeyStoreProvider>,
+}
+
+impl<'a, Provider: OpenMlsAsyncCryptoProvider> BufferedProvider<'a, Provider> {
+    fn new(backend: &'a Provider) -> Self {
+        Self {
+            backend,
+            key_store: BufferedKeyStore::new(backend.key_store()),
+        }
+    }
+
+    /// Reads the value for `k` from the async key store and makes it
+    /// available to the operation. Returns the value if there is one.
+    async fn prefetch<V: MlsEntity + Send>(&self, k: &[u8]) -> Result<Option<V>, LibraryError> {
+        let value = self.backend.key_store().read::<V>(k).await;
+        if let Some(value) = &value {
+            self.provide(k, value)?;
+        }
+        Ok(value)
+    }
+
+    /// Makes the value `v` available to the operation under `k` without
+    /// writing it to the async key store.
+    fn provide<V: MlsEntity>(&self, k: &[u8], v: &V) -> Result<(), LibraryError> {
+        let serialized_value = self
+            .key_store
+            .encode(v)
+            .map_err(|_| LibraryError::custom("Could not serialize a prefetched value."))?;
+        // We unwrap here, because the lock is only held very briefly and
+        // nothing should panic during that period.
+        self.key_store
+            .values
+            .write()
+            .unwrap()
+            .insert(k.to_vec(), Some(serialized_value));
+        Ok(())
+    }
+
+    /// Makes the PSKs with the given IDs available to the operation. PSKs
+    /// that are not in the async key store are resolved through the
+    /// `psk_resolver`. Resolved PSKs are not written to the async key store.
+    async fn resolve_psks(
+        &self,
+        psk_resolver: &impl AsyncPskResolver,
+        ciphersuite: Ciphersuite,
+        psk_ids: Vec<PreSharedKeyId>,
+    ) -> Result<(), LibraryError> {
+        for psk_id in psk_ids {
+            let keystore_id = psk_id.keystore_id()?;
+            if self.prefetch::<PskBundle>(&keystore_id).await?.is_some() {
+                continue;
+            }
+            if let Some(psk) = psk_resolver.resolve(&psk_id).await {
+                self.provide(&keystore_id, &PskBundle::new(&psk, ciphersuite))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes all changes to the async key store in one batch (see
+    /// [`OpenMlsAsyncKeyStore::apply_batch()`]).
+    ///
+    /// Returns an error if writing to the key store fails.
+    async fn flush<OperationError>(
+        self,
+    ) -> Result<(), AsyncOperationError<OperationError, AsyncKeyStoreError<Provider>>> {
+        let batch = self
+            .key_store
+            .batch
+            .into_inner()
+            .map_err(|_| LibraryError::custom("Buffered key store lock is poisoned."))?;
+        self.backend
+            .key_store()
+            .apply_batch(batch)
+            .await
+            .map_err(AsyncOperationError::KeyStoreError)
+    }
+}
+
+impl<'a, Provider: OpenMlsAsyncCryptoProvider> OpenMlsCryptoProvider
+    for BufferedProvider<'a, Provider>
+{
+    type CryptoProvider = Provider::CryptoProvider;
+    type RandProvider = Provider::RandProvider;
+    type KeyStoreProvider = BufferedKeyStore<'a, Provider::KeyStoreProvider>;
+
+    fn crypto(&self) -> &Self::CryptoProvider {
+        self.backend.crypto()
+    }
+
+    fn rand(&self) -> &Self::RandProvider {
+        self.backend.rand()
+    }
+
+    fn key_store(&self) -> &Self::KeyStoreProvider {
+        &self.key_store
+    }
+}
+
+/// Error of the in-memory key store that the async operations run on.
+#[derive(Error, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BufferedKeyStoreError {
+    /// Error serializing a value.
+    #[error("Error serializing a value.")]
+    SerializationError,
+}
+
+/// An in-memory key store in front of an [`OpenMlsAsyncKeyStore`].
+///
+/// Reads only return values that were prefetched from the async key store or
+/// written during the operation. Writes and deletions are recorded in a
+/// [`WriteBatch`] and applied to the async key store after the operation.
+struct BufferedKeyStore<'a, KeyStore: OpenMlsAsyncKeyStore> {
+    // The async key store, which encodes the values.
+    key_store: &'a KeyStore,
+    // Encoded values prefetched or written (`Some`) or deleted (`None`).
+    values: RwLock<HashMap<Vec<u8>, Option<Vec<u8>>>>,
+    batch: RwLock<WriteBatch>,
+}
+
+impl<'a, KeyStore: OpenMlsAsyncKeyStore> BufferedKeyStore<'a, KeyStore> {
+    fn new(key_store: &'a KeyStore) -> Self {
+        Self {
+            key_store,
+            values: RwLock::new(HashMap::new()),
+            batch: RwLock::new(WriteBatch::new()),
+        }
+    }
+}
+
+impl<'a, KeyStore: OpenMlsAsyncKeyStore> std::fmt::Debug for BufferedKeyStore<'a, KeyStore> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BufferedKeyStore").finish_non_exhaustive()
+    }
+}
+
+impl<'a, KeyStore: OpenMlsAsyncKeyStore> OpenMlsKeyStore for BufferedKeyStore<'a, KeyStore> {
+    type Error = BufferedKeyStoreError;
+
+    fn store<V: MlsEntity>(&self, k: &[u8], v: &V) -> Result<(), Self::Error> {
+        let value = self.encode(v)?;
+        self.store_encoded(V::ID, k, &value)
+    }
+
+    fn read<V: MlsEntity>(&self, k: &[u8]) -> Option<V> {
+        match self.values.read().unwrap().get(k) {
+            Some(Some(value)) => self.decode(value),
+            _ => None,
+        }
+    }
+
+    fn delete<V: MlsEntity>(&self, k: &[u8]) -> Result<(), Self::Error> {
+        self.values.write().unwrap().insert(k.to_vec(), None);
+        self.batch.write().unwrap().delete::<V>(k);
+        Ok(())
+    }
+
+    fn encode<V: MlsEntity>(&self, v: &V) -> Result<Vec<u8>, Self::Error> {
+        self.key_store
+            .encode(v)
+            .map_err(|_| BufferedKeyStoreError::SerializationError)
+    }
+
+    fn decode<V: MlsEntity>(&self, value: &[u8]) -> Option<V> {
+        self.key_store.decode(value)
+    }
+
+    fn store_encoded(
+        &self,
+        entity_id: MlsEntityId,
+        k: &[u8],
+        value: &[u8],
+    ) -> Result<(), Self::Error> {
+        // We unwrap here, because the locks are only held very briefly and
+        // nothing should panic during that period.
+        self.batch
+            .write()
+            .unwrap()
+            .store(entity_id, k, value.to_vec());
+        self.values
+            .write()
+            .unwrap()
+            .insert(k.to_vec(), Some(value.to_vec()));
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+//! MLS group auditor tags
+//!
+//! This module contains an opt-in mode that allows a designated non-member,
+//! e.g. a compliance relay, to authenticate the handshake traffic of a group
+//! without joining it.
+//!
+//! If [`MlsGroupConfig::use_auditor_tags()`] is set, members derive a
+//! per-epoch [`AuditorKey`] from the exporter secret and hand it to the
+//! auditor through a secure channel, e.g. with [`MlsGroup::auditor_key()`].
+//! Outgoing [`PublicMessage`]s are then wrapped into an
+//! [`AuditorTaggedMessage`] with [`MlsGroup::tag_for_auditor()`], which
+//! attaches a second tag next to the membership tag. The auditor checks it
+//! with [`AuditorKey::verify()`].
+//!
+//! The tag proves that the message was created by a member of the group in
+//! the epoch of the key and that it wasn't modified. It doesn't identify the
+//! member, since all members share the key, and the auditor can't verify the
+//! signature of the message without the group context. The sender that the
+//! message indicates is therefore only claimed by one of the members. The
+//! auditor doesn't learn any other secret of the group.
+
+use serde::{Deserialize, Serialize};
+use tls_codec::{
+    Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait, TlsDeserialize,
+    TlsSerialize, TlsSize, VLBytes,
+};
+
+use crate::{
+    ciphersuite::{Mac, Secret},
+    group::errors::ExporterError,
+};
+
+use super::*;
+
+/// The exporter label used to derive the auditor key.
+const AUDITOR_KEY_LABEL: &str = "MLS 1.0 auditor MAC key";
+
+/// The key that an auditor uses to verify the [`AuditorTag`]s of a group in
+/// one epoch. See [`MlsGroup::auditor_key()`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditorKey {
+    group_id: GroupId,
+    epoch: GroupEpoch,
+    key: Secret,
+}
+
+/// The tag that authenticates a message for the holder of an [`AuditorKey`].
+#[derive(Debug, Clone, PartialEq, TlsSerialize, TlsDeserialize, TlsSize)]
+pub struct AuditorTag(Mac);
+
+/// A serialized [`MlsMessageOut`] that contains a [`PublicMessage`] together
+/// with its [`AuditorTag`].
+///
+/// ```c
+/// struct {
+///     opaque message<V>;
+///     MAC auditor_tag;
+/// } AuditorTaggedMessage;
+/// ```
+#[derive(Debug, Clone, PartialEq, TlsSerialize, TlsDeserialize, TlsSize)]
+pub struct AuditorTaggedMessage {
+    message: VLBytes,
+    auditor_tag: AuditorTag,
+}
+
+impl AuditorTaggedMessage {
+    /// Returns the serialized message.
+    pub fn message_bytes(&self) -> &[u8] {
+        self.message.as_slice()
+    }
+
+    /// Returns the [`AuditorTag`] of the message.
+    pub fn auditor_tag(&self) -> &AuditorTag {
+        &self.auditor_tag
+    }
+
+    /// Deserializes the message, e.g. for processing by the members of the
+    /// group.
+    pub fn into_message(self) -> Result<MlsMessageIn, tls_codec::Error> {
+        MlsMessageIn::tls_deserialize_exact(self.message.as_slice())
+    }
+}
+
+impl AuditorKey {
+    /// Returns the [`GroupId`] of the group the key belongs to.
+    pub fn group_id(&self) -> &GroupId {
+        &self.group_id
+    }
+
+    /// Returns the epoch the key belongs to.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Verifies the [`AuditorTag`] of the `message`, i.e. that a member of
+    /// the group created it in the epoch of this key. Since all members share
+    /// the key, this doesn't authenticate the sender that is indicated in the
+    /// message.
+    ///
+    /// Returns an error if the message is not a [`PublicMessage`] of the
+    /// group and epoch of this key or if the tag is invalid.
+    pub fn verify(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        message: &AuditorTaggedMessage,
+    ) -> Result<(), AuditorTagError> {
+        let mls_message = MlsMessageIn::tls_deserialize_exact(message.message.as_slice())
+            .map_err(|_| AuditorTagError::MalformedMessage)?;
+        let public_message = match mls_message.body {
+            MlsMessageInBody::PublicMessage(public_message) => public_message,
+            _ => return Err(AuditorTagError::NotAPublicMessage),
+        };
+        if public_message.group_id() != &self.group_id {
+            return Err(AuditorTagError::WrongGroupId);
+        }
+        if public_message.epoch() != self.epoch {
+            return Err(AuditorTagError::WrongEpoch);
+        }
+
+        if self.tag(backend, message.message.as_slice())? != message.auditor_tag {
+            return Err(AuditorTagError::InvalidTag);
+        }
+        Ok(())
+    }
+
+    /// Computes the [`AuditorTag`] of the serialized message.
+    fn tag(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        message: &[u8],
+    ) -> Result<AuditorTag, LibraryError> {
+        Mac::new(backend, &self.key, message)
+            .map(AuditorTag)
+            .map_err(LibraryError::unexpected_crypto_error)
+    }
+}
+
+impl MlsGroup {
+    /// Returns the [`AuditorKey`] of the current epoch.
+    ///
+    /// The key has to be sent to the auditor through a secure channel. It
+    /// has to be sent again after each epoch change.
+    ///
+    /// Returns [`AuditorTagError::Disabled`] if the group doesn't use auditor
+    /// tags (see [`MlsGroupConfig::use_auditor_tags()`]).
+    pub fn auditor_key(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<AuditorKey, AuditorTagError> {
+        if !self.configuration().use_auditor_tags() {
+            return Err(AuditorTagError::Disabled);
+        }
+        if !self.is_active() {
+            return Err(MlsGroupStateError::UseAfterEviction.into());
+        }
+        let ciphersuite = self.ciphersuite();
+        let key = self
+            .group
+            .export_secret(backend, AUDITOR_KEY_LABEL, &[], ciphersuite.hash_length())
+            .map_err(|e| match e {
+                ExporterError::LibraryError(e) => e,
+                ExporterError::KeyLengthTooLong => {
+                    LibraryError::custom("The hash length is always a valid key length.")
+                }
+            })?;
+        Ok(AuditorKey {
+            group_id: self.group_id().clone(),
+            epoch: self.epoch(),
+            key: Secret::from_slice(&key, self.group.version(), ciphersuite),
+        })
+    }
+
+    /// Attaches an [`AuditorTag`] to the `message`, which has to be a
+    /// [`PublicMessage`] of the current epoch that was created by this group,
+    /// e.g. a commit or a proposal.
+    ///
+    /// The returned [`AuditorTaggedMessage`] is sent to the DS instead of the
+    /// message itself. Members get the message back through
+    /// [`AuditorTaggedMessage::into_message()`].
+    ///
+    /// Returns [`AuditorTagError::Disabled`] if the group doesn't use auditor
+    /// tags (see [`MlsGroupConfig::use_auditor_tags()`]).
+    pub fn tag_for_auditor(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        message: &MlsMessageOut,
+    ) -> Result<AuditorTaggedMessage, AuditorTagError> {
+        let public_message = match &message.body {
+            MlsMessageOutBody::PublicMessage(public_message) => public_message,
+            _ => return Err(AuditorTagError::NotAPublicMessage),
+        };
+        if public_message.group_id() != self.group_id() {
+            return Err(AuditorTagError::WrongGroupId);
+        }
+        if public_message.epoch() != self.epoch() {
+            return Err(AuditorTagError::WrongEpoch);
+        }
+
+        let auditor_key = self.auditor_key(backend)?;
+        let message = message
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+        let auditor_tag = auditor_key.tag(backend, &message)?;
+        Ok(AuditorTaggedMessage {
+            message: message.into(),
+            auditor_tag,
+        })
+    }
+}
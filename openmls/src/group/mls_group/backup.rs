@@ -0,0 +1,228 @@
+//! MLS group backup keys
+//!
+//! Applications that back up the message history of a group can encrypt the
+//! backups with keys that are derived from the group, so that only members of
+//! the group in the epoch of a backup can decrypt it. The keys are derived
+//! from a dedicated exporter secret and never reuse the keys of the messages
+//! of the group.
+//!
+//! [`MlsGroup::backup_key_ratchet()`] returns the [`BackupKeyRatchet`] of the
+//! current epoch. Each call to [`BackupKeyRatchet::next_key()`] returns the
+//! [`BackupKey`] for the next backup and ratchets the secret forward, so that
+//! the keys of earlier backups can't be derived from the ratchet anymore:
+//!
+//! ```text
+//! backup_secret[0] = MLS-Exporter("MLS 1.0 backup secret", "", KDF.Nh)
+//!
+//! backup_key[n]      = ExpandWithLabel(backup_secret[n], "backup key", n, AEAD.Nk)
+//! backup_nonce[n]    = ExpandWithLabel(backup_secret[n], "backup nonce", n, AEAD.Nn)
+//! backup_secret[n+1] = ExpandWithLabel(backup_secret[n], "backup secret", n, KDF.Nh)
+//! ```
+//!
+//! where `n` is encoded as a big-endian `uint32`. Members restore a backup
+//! with [`MlsGroup::backup_key()`] while they are in the epoch of the backup.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ciphersuite::Secret, group::errors::ExporterError};
+
+use super::*;
+
+/// The exporter label used to derive the first backup secret of an epoch.
+const BACKUP_SECRET_LABEL: &str = "MLS 1.0 backup secret";
+
+/// The maximum number of generations a [`BackupKeyRatchet`] is ratcheted
+/// forward at once.
+const MAX_FORWARD_DISTANCE: u32 = 1000;
+
+/// The key and nonce that encrypt one backup of a group. See
+/// [`BackupKeyRatchet::next_key()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupKey {
+    group_id: GroupId,
+    epoch: GroupEpoch,
+    generation: u32,
+    key: Secret,
+    nonce: Secret,
+}
+
+impl BackupKey {
+    /// Returns the ID of the group of the key.
+    pub fn group_id(&self) -> &GroupId {
+        &self.group_id
+    }
+
+    /// Returns the epoch of the key.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the generation of the key, which has to be stored with the
+    /// backup to derive the key again.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Returns the AEAD key for the ciphersuite of the group.
+    pub fn key(&self) -> &[u8] {
+        self.key.as_slice()
+    }
+
+    /// Returns the AEAD nonce for the ciphersuite of the group.
+    pub fn nonce(&self) -> &[u8] {
+        self.nonce.as_slice()
+    }
+}
+
+/// The forward ratchet of the [`BackupKey`]s of a group in one epoch.
+///
+/// The ratchet only holds the secret of its current generation. It can be
+/// stored by the application to continue with the next generation later.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupKeyRatchet {
+    group_id: GroupId,
+    epoch: GroupEpoch,
+    generation: u32,
+    secret: Secret,
+}
+
+impl BackupKeyRatchet {
+    /// Returns the ID of the group of the ratchet.
+    pub fn group_id(&self) -> &GroupId {
+        &self.group_id
+    }
+
+    /// Returns the epoch of the ratchet.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the generation of the next key.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Returns the [`BackupKey`] of the current generation and ratchets the
+    /// secret forward.
+    ///
+    /// Returns [`BackupKeyError::GenerationExhausted`] if the ratchet reached
+    /// the last generation.
+    pub fn next_key(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<BackupKey, BackupKeyError> {
+        let next_generation = self
+            .generation
+            .checked_add(1)
+            .ok_or(BackupKeyError::GenerationExhausted)?;
+        let context = self.generation.to_be_bytes();
+        let ciphersuite = self.secret.ciphersuite();
+        let key = self
+            .secret
+            .kdf_expand_label(
+                backend,
+                "backup key",
+                &context,
+                ciphersuite.aead_key_length(),
+            )
+            .map_err(LibraryError::unexpected_crypto_error)?;
+        let nonce = self
+            .secret
+            .kdf_expand_label(
+                backend,
+                "backup nonce",
+                &context,
+                ciphersuite.aead_nonce_length(),
+            )
+            .map_err(LibraryError::unexpected_crypto_error)?;
+        let backup_key = BackupKey {
+            group_id: self.group_id.clone(),
+            epoch: self.epoch,
+            generation: self.generation,
+            key,
+            nonce,
+        };
+        self.secret = self
+            .secret
+            .kdf_expand_label(
+                backend,
+                "backup secret",
+                &context,
+                ciphersuite.hash_length(),
+            )
+            .map_err(LibraryError::unexpected_crypto_error)?;
+        self.generation = next_generation;
+        Ok(backup_key)
+    }
+
+    /// Ratchets the secret forward to the `generation` and returns its
+    /// [`BackupKey`], e.g. to decrypt a backup.
+    ///
+    /// Returns [`BackupKeyError::GenerationInThePast`] if the ratchet moved
+    /// past the `generation` already and
+    /// [`BackupKeyError::TooDistantInTheFuture`] if the `generation` is too
+    /// far ahead of the ratchet.
+    pub fn key_for_generation(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        generation: u32,
+    ) -> Result<BackupKey, BackupKeyError> {
+        if generation < self.generation {
+            return Err(BackupKeyError::GenerationInThePast);
+        }
+        if generation - self.generation > MAX_FORWARD_DISTANCE {
+            return Err(BackupKeyError::TooDistantInTheFuture);
+        }
+        loop {
+            let backup_key = self.next_key(backend)?;
+            if backup_key.generation == generation {
+                return Ok(backup_key);
+            }
+        }
+    }
+}
+
+impl MlsGroup {
+    /// Returns the [`BackupKeyRatchet`] of the current epoch, starting with
+    /// the first generation.
+    ///
+    /// All members of the group in the current epoch derive the same ratchet.
+    /// The member that creates backups should keep its ratchet, e.g. with the
+    /// application's other state, so that no key is used for two backups.
+    pub fn backup_key_ratchet(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<BackupKeyRatchet, BackupKeyError> {
+        if !self.is_active() {
+            return Err(MlsGroupStateError::UseAfterEviction.into());
+        }
+        let ciphersuite = self.ciphersuite();
+        let secret = self
+            .group
+            .export_secret(backend, BACKUP_SECRET_LABEL, &[], ciphersuite.hash_length())
+            .map_err(|e| match e {
+                ExporterError::LibraryError(e) => e,
+                ExporterError::KeyLengthTooLong => {
+                    LibraryError::custom("The hash length is always a valid key length.")
+                }
+            })?;
+        Ok(BackupKeyRatchet {
+            group_id: self.group_id().clone(),
+            epoch: self.epoch(),
+            generation: 0,
+            secret: Secret::from_slice(&secret, self.group.version(), ciphersuite),
+        })
+    }
+
+    /// Returns the [`BackupKey`] of the `generation` in the current epoch,
+    /// e.g. to restore a backup. See
+    /// [`BackupKeyRatchet::key_for_generation()`].
+    pub fn backup_key(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        generation: u32,
+    ) -> Result<BackupKey, BackupKeyError> {
+        self.backup_key_ratchet(backend)?
+            .key_for_generation(backend, generation)
+    }
+}
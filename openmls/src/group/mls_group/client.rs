@@ -0,0 +1,380 @@
+//! A client that manages the groups of one member.
+//!
+//! [`MlsClient`] owns the crypto provider, the signer and the credential of a
+//! client together with all of its [`MlsGroup`]s. It routes incoming messages
+//! to their group and keeps a set of published key packages replenished. The
+//! IDs of the groups and the key packages are persisted in the storage, so
+//! that the client can be restored with [`MlsClient::load()`].
+
+use std::{
+    collections::{hash_map, HashMap},
+    time::SystemTime,
+};
+
+use openmls_traits::{
+    key_store::{MlsEntity, MlsEntityId},
+    signatures::Signer,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{errors::ClientProcessMessageError, *};
+use crate::{
+    credentials::CredentialWithKey, key_packages::errors::KeyPackageNewError,
+    treesync::RatchetTreeIn,
+};
+
+/// The key store error of the crypto provider `Provider`.
+type KeyStoreError<Provider> =
+    <<Provider as OpenMlsCryptoProvider>::KeyStoreProvider as OpenMlsKeyStore>::Error;
+
+/// A client with a single credential and any number of groups, keyed by their
+/// [`GroupId`].
+///
+/// New groups are created and joined with the [`MlsGroupConfig`] of the
+/// client, and can be changed through [`MlsClient::group_mut()`] afterwards.
+/// Like all [`MlsGroup`]s, the groups write their state to the storage of the
+/// crypto provider after every operation, so there is no separate function to
+/// save them. The client itself stores the IDs of its groups and its unused
+/// key packages whenever they change, keyed by its signature key, and
+/// [`MlsClient::load()`] restores all of them after a restart.
+pub struct MlsClient<Provider: OpenMlsCryptoProvider, S: Signer> {
+    backend: Provider,
+    signer: S,
+    credential_with_key: CredentialWithKey,
+    mls_group_config: MlsGroupConfig,
+    groups: HashMap<GroupId, MlsGroup>,
+    // The key packages that were published and not used to join a group yet.
+    key_packages: Vec<KeyPackage>,
+}
+
+/// The state of an [`MlsClient`] that is persisted in the storage.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ClientState {
+    group_ids: Vec<GroupId>,
+    key_packages: Vec<KeyPackage>,
+}
+
+impl MlsEntity for ClientState {
+    const ID: MlsEntityId = MlsEntityId::ClientState;
+}
+
+/// The result of [`MlsClient::process_message()`].
+#[derive(Debug)]
+pub enum ClientMessage {
+    /// A message of one of the groups was processed. Staged commits and
+    /// proposals are not merged or stored yet, see
+    /// [`MlsGroup::process_message()`].
+    Processed(Box<ProcessedMessage>),
+    /// A [`Welcome`] was processed and the client joined the group with the
+    /// given ID.
+    Joined(GroupId),
+}
+
+impl<Provider: OpenMlsCryptoProvider, S: Signer> MlsClient<Provider, S> {
+    /// Creates a new client without any groups.
+    ///
+    /// The `mls_group_config` is used for all groups that are created or
+    /// joined, and its crypto config and lifetime policy for the key packages
+    /// of the client. Nothing is written to the storage until the groups or
+    /// key packages of the client change.
+    pub fn new(
+        backend: Provider,
+        signer: S,
+        credential_with_key: CredentialWithKey,
+        mls_group_config: MlsGroupConfig,
+    ) -> Self {
+        Self {
+            backend,
+            signer,
+            credential_with_key,
+            mls_group_config,
+            groups: HashMap::new(),
+            key_packages: vec![],
+        }
+    }
+
+    /// Restores the client with the signature key of the
+    /// `credential_with_key` from the storage of the `backend`, including all
+    /// of its groups and unused key packages. A client that was never stored
+    /// is restored without groups or key packages.
+    ///
+    /// Returns the client and the IDs of the groups whose state was not found
+    /// in the storage.
+    pub fn load(
+        backend: Provider,
+        signer: S,
+        credential_with_key: CredentialWithKey,
+        mls_group_config: MlsGroupConfig,
+    ) -> (Self, Vec<GroupId>) {
+        let state: ClientState = backend
+            .key_store()
+            .load(StorageKey::ClientState {
+                signature_key: credential_with_key.signature_key.as_slice(),
+            })
+            .unwrap_or_default();
+        let mut client = Self::new(backend, signer, credential_with_key, mls_group_config);
+        client.key_packages = state.key_packages;
+        let mut missing = vec![];
+        for group_id in state.group_ids {
+            match MlsGroup::load(&group_id, &client.backend) {
+                Some(group) => {
+                    client.groups.insert(group_id, group);
+                }
+                None => missing.push(group_id),
+            }
+        }
+        (client, missing)
+    }
+
+    /// Writes the IDs of the groups and the unused key packages of the client
+    /// to the storage.
+    fn store_state(&self) -> Result<(), KeyStoreError<Provider>> {
+        let mut group_ids: Vec<GroupId> = self.groups.keys().cloned().collect();
+        group_ids.sort_by(|a, b| a.as_slice().cmp(b.as_slice()));
+        let state = ClientState {
+            group_ids,
+            key_packages: self.key_packages.clone(),
+        };
+        self.backend.key_store().write(
+            StorageKey::ClientState {
+                signature_key: self.credential_with_key.signature_key.as_slice(),
+            },
+            &state,
+        )
+    }
+
+    /// Returns the crypto provider, the signer and the credential of the
+    /// client, e.g. to restore it with [`MlsClient::load()`] later.
+    pub fn into_parts(self) -> (Provider, S, CredentialWithKey) {
+        (self.backend, self.signer, self.credential_with_key)
+    }
+
+    /// Returns the crypto provider of the client.
+    pub fn backend(&self) -> &Provider {
+        &self.backend
+    }
+
+    /// Returns the signer of the client.
+    pub fn signer(&self) -> &S {
+        &self.signer
+    }
+
+    /// Returns the credential and signature key of the client.
+    pub fn credential_with_key(&self) -> &CredentialWithKey {
+        &self.credential_with_key
+    }
+
+    /// Returns the [`MlsGroupConfig`] for new groups.
+    pub fn mls_group_config(&self) -> &MlsGroupConfig {
+        &self.mls_group_config
+    }
+
+    // === Groups ===
+
+    /// Returns the group with the given ID, if the client is in it.
+    pub fn group(&self, group_id: &GroupId) -> Option<&MlsGroup> {
+        self.groups.get(group_id)
+    }
+
+    /// Returns the group with the given ID for an operation, if the client is
+    /// in it. The crypto provider and signer of the client are returned
+    /// alongside the group, since most operations of [`MlsGroup`] need them.
+    pub fn group_mut(&mut self, group_id: &GroupId) -> Option<(&mut MlsGroup, &Provider, &S)> {
+        let group = self.groups.get_mut(group_id)?;
+        Some((group, &self.backend, &self.signer))
+    }
+
+    /// Returns the IDs of all groups of the client.
+    pub fn group_ids(&self) -> impl Iterator<Item = &GroupId> {
+        self.groups.keys()
+    }
+
+    /// Returns all groups of the client.
+    pub fn groups(&self) -> hash_map::Values<'_, GroupId, MlsGroup> {
+        self.groups.values()
+    }
+
+    /// Creates a new group with the [`MlsGroupConfig`] of the client and
+    /// returns its ID.
+    pub fn create_group(&mut self) -> Result<GroupId, NewGroupError<KeyStoreError<Provider>>> {
+        let group = MlsGroup::new(
+            &self.backend,
+            &self.signer,
+            &self.mls_group_config,
+            self.credential_with_key.clone(),
+        )?;
+        let group_id = group.group_id().clone();
+        self.groups.insert(group_id.clone(), group);
+        self.store_state().map_err(NewGroupError::StorageError)?;
+        Ok(group_id)
+    }
+
+    /// Joins the group of the `welcome` with the [`MlsGroupConfig`] of the
+    /// client and returns its ID. The key package that was used to join is
+    /// no longer returned by [`MlsClient::key_packages()`].
+    ///
+    /// Returns [`WelcomeError::GroupAlreadyExists`] if the storage already
+    /// contains a group with the same ID, since whoever has a key package of
+    /// the client could otherwise replace a group the client is in. A group that should be re-joined has to be removed with
+    /// [`MlsClient::remove_group()`] first.
+    pub fn join_group(
+        &mut self,
+        welcome: Welcome,
+        ratchet_tree: Option<RatchetTreeIn>,
+    ) -> Result<GroupId, WelcomeError<KeyStoreError<Provider>>> {
+        let used_key_packages: Vec<_> = welcome
+            .secrets()
+            .iter()
+            .map(|secrets| secrets.new_member())
+            .collect();
+        let mut builder = MlsGroup::welcome_processing_builder(&self.mls_group_config, welcome)
+            .replace_existing_group(false);
+        if let Some(ratchet_tree) = ratchet_tree {
+            builder = builder.ratchet_tree(ratchet_tree);
+        }
+        let group = builder.build(&self.backend)?;
+        let crypto = self.backend.crypto();
+        self.key_packages.retain(|key_package| {
+            key_package
+                .hash_ref(crypto)
+                .map_or(true, |hash_ref| !used_key_packages.contains(&hash_ref))
+        });
+        let group_id = group.group_id().clone();
+        self.groups.insert(group_id.clone(), group);
+        self.store_state().map_err(WelcomeError::KeyStoreError)?;
+        Ok(group_id)
+    }
+
+    /// Removes the group with the given ID from the client and returns it,
+    /// e.g. after the client left it. The state of the group is deleted from
+    /// the storage, see [`MlsGroup::delete()`].
+    pub fn remove_group(
+        &mut self,
+        group_id: &GroupId,
+    ) -> Result<Option<MlsGroup>, KeyStoreError<Provider>> {
+        let Some(group) = self.groups.remove(group_id) else {
+            return Ok(None);
+        };
+        self.store_state()?;
+        group.delete(&self.backend)?;
+        Ok(Some(group))
+    }
+
+    /// Loads the groups with the given IDs from the storage of the crypto
+    /// provider, see [`MlsGroup::load()`], replacing groups of the client
+    /// with the same IDs.
+    ///
+    /// Returns the IDs of the groups that were not found in the storage.
+    pub fn load_groups<'a>(
+        &mut self,
+        group_ids: impl IntoIterator<Item = &'a GroupId>,
+    ) -> Result<Vec<GroupId>, KeyStoreError<Provider>> {
+        let mut missing = vec![];
+        for group_id in group_ids {
+            match MlsGroup::load(group_id, &self.backend) {
+                Some(group) => {
+                    self.groups.insert(group_id.clone(), group);
+                }
+                None => missing.push(group_id.clone()),
+            }
+        }
+        self.store_state()?;
+        Ok(missing)
+    }
+
+    // === Messages ===
+
+    /// Processes an incoming message.
+    ///
+    /// A `PublicMessage` or `PrivateMessage` is processed by the group with
+    /// its group ID, like with [`MlsGroup::process_message()`]. The returned
+    /// [`ProcessedMessage`] has to be handled through [`MlsClient::group_mut()`]
+    /// like the one of the group, e.g. by merging a staged commit. A
+    /// [`Welcome`] is used to join its group with [`MlsClient::join_group()`],
+    /// assuming that the ratchet tree is included in the `Welcome`. A
+    /// `Welcome` for a group the client is already in is rejected.
+    ///
+    /// Returns [`ClientProcessMessageError::UnknownGroup`] if the client is
+    /// not in the group of the message, and
+    /// [`ClientProcessMessageError::UnsupportedWireFormat`] for a `GroupInfo`
+    /// or a `KeyPackage`.
+    pub fn process_message(
+        &mut self,
+        message: MlsMessageIn,
+    ) -> Result<ClientMessage, ClientProcessMessageError<KeyStoreError<Provider>>> {
+        let wire_format = message.wire_format();
+        let protocol_message = match message.extract() {
+            MlsMessageInBody::PublicMessage(public_message) => {
+                ProtocolMessage::from(public_message)
+            }
+            MlsMessageInBody::PrivateMessage(private_message) => {
+                ProtocolMessage::from(private_message)
+            }
+            MlsMessageInBody::Welcome(welcome) => {
+                let group_id = self.join_group(welcome, None)?;
+                return Ok(ClientMessage::Joined(group_id));
+            }
+            MlsMessageInBody::GroupInfo(_) | MlsMessageInBody::KeyPackage(_) => {
+                return Err(ClientProcessMessageError::UnsupportedWireFormat(
+                    wire_format,
+                ))
+            }
+        };
+
+        let group_id = protocol_message.group_id();
+        let group = self
+            .groups
+            .get_mut(group_id)
+            .ok_or_else(|| ClientProcessMessageError::UnknownGroup(group_id.clone()))?;
+        let processed_message = group.process_message(&self.backend, protocol_message)?;
+        Ok(ClientMessage::Processed(Box::new(processed_message)))
+    }
+
+    // === Key packages ===
+
+    /// Returns the key packages of the client that were not used to join a
+    /// group yet.
+    pub fn key_packages(&self) -> &[KeyPackage] {
+        &self.key_packages
+    }
+
+    /// Replaces the key packages that need to be refreshed at `now`
+    /// according to the lifetime policy of the client, and creates new key
+    /// packages until the client has `target` unused key packages.
+    ///
+    /// Returns the new key packages, which have to be published, e.g. by
+    /// uploading them to the DS. The replaced key packages stay in the key
+    /// store until they are deleted with [`KeyPackage::delete()`], since a
+    /// [`Welcome`] for them may still be on its way.
+    pub fn replenish_key_packages(
+        &mut self,
+        target: usize,
+        now: SystemTime,
+    ) -> Result<Vec<KeyPackage>, KeyPackageNewError<KeyStoreError<Provider>>> {
+        let policy = self.mls_group_config.lifetime_policy();
+        let mut new_key_packages = vec![];
+        for key_package in self.key_packages.iter_mut() {
+            if key_package.needs_refresh(now, policy) {
+                *key_package = key_package.refresh(policy, &self.backend, &self.signer)?;
+                new_key_packages.push(key_package.clone());
+            }
+        }
+        while self.key_packages.len() < target {
+            let key_package = KeyPackage::builder()
+                .key_package_lifetime(policy.new_lifetime())
+                .build(
+                    *self.mls_group_config.crypto_config(),
+                    &self.backend,
+                    &self.signer,
+                    self.credential_with_key.clone(),
+                )?;
+            self.key_packages.push(key_package.clone());
+            new_key_packages.push(key_package);
+        }
+        if !new_key_packages.is_empty() {
+            self.store_state()
+                .map_err(KeyPackageNewError::KeyStoreError)?;
+        }
+        Ok(new_key_packages)
+    }
+}
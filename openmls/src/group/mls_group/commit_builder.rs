@@ -0,0 +1,278 @@
+//! MLS group commit builder
+//!
+//! This module contains the [`CommitBuilder`] that allows fine-grained control
+//! over the content of a commit created by an [`MlsGroup`].
+
+use openmls_traits::signatures::Signer;
+
+use super::{errors::CommitBuilderError, *};
+use crate::{
+    extensions::{AppStateHashExtension, CredentialBinding, Extension},
+    group::core_group::create_commit_params::CreateCommitParams,
+    messages::group_info::GroupInfo,
+};
+
+/// Builder for a commit in an [`MlsGroup`].
+///
+/// The builder is created through [`MlsGroup::commit_builder()`] and allows
+/// choosing exactly which of the stored proposals are committed, adding inline
+/// proposals, forcing or suppressing an update path, setting the AAD,
+/// registering an application state hash, revealing credential bindings to
+/// the joiners, overriding the wire format and deciding whether a
+/// [`GroupInfo`] is returned alongside the commit.
+///
+/// By default the commit covers all pending proposals, contains an update
+/// path, uses the group's AAD and wire format policy and returns a
+/// [`GroupInfo`] only if the group uses the ratchet tree extension.
+#[derive(Debug)]
+pub struct CommitBuilder<'a> {
+    group: &'a mut MlsGroup,
+    proposal_refs: Option<Vec<ProposalRef>>,
+    inline_proposals: Vec<Proposal>,
+    force_self_update: bool,
+    aad: Option<Vec<u8>>,
+    create_group_info: Option<bool>,
+    app_state_hash: Option<Vec<u8>>,
+    credential_bindings: Vec<CredentialBinding>,
+    wire_format: Option<OutgoingWireFormatPolicy>,
+}
+
+impl MlsGroup {
+    /// Returns a [`CommitBuilder`] for creating a commit in this group.
+    pub fn commit_builder(&mut self) -> CommitBuilder<'_> {
+        CommitBuilder {
+            group: self,
+            proposal_refs: None,
+            inline_proposals: vec![],
+            force_self_update: true,
+            aad: None,
+            create_group_info: None,
+            app_state_hash: None,
+            credential_bindings: vec![],
+            wire_format: None,
+        }
+    }
+}
+
+impl<'a> CommitBuilder<'a> {
+    /// Only commit the pending proposals with the given [`ProposalRef`]s
+    /// instead of all pending proposals.
+    ///
+    /// Passing an empty list excludes all pending proposals. The commit fails
+    /// if the selected proposals conflict with each other, see
+    /// [`ProposalStore::conflicts()`].
+    pub fn include_proposals(mut self, proposal_refs: Vec<ProposalRef>) -> Self {
+        self.proposal_refs = Some(proposal_refs);
+        self
+    }
+
+    /// Add an inline proposal to the commit.
+    pub fn add_proposal(mut self, proposal: Proposal) -> Self {
+        self.inline_proposals.push(proposal);
+        self
+    }
+
+    /// Add a list of inline proposals to the commit.
+    pub fn add_proposals(mut self, proposals: impl IntoIterator<Item = Proposal>) -> Self {
+        self.inline_proposals.extend(proposals);
+        self
+    }
+
+    /// Set whether the commit contains an update path.
+    ///
+    /// If set to `false`, the update path is only omitted if none of the
+    /// committed proposals require one. Defaults to `true`.
+    pub fn force_self_update(mut self, force_self_update: bool) -> Self {
+        self.force_self_update = force_self_update;
+        self
+    }
+
+    /// Set the AAD used for this commit only. The group's AAD is left
+    /// untouched.
+    pub fn aad(mut self, aad: &[u8]) -> Self {
+        self.aad = Some(aad.to_vec());
+        self
+    }
+
+    /// Set whether a [`GroupInfo`] is returned alongside the commit.
+    ///
+    /// Defaults to the `use_ratchet_tree_extension` flag of the group's
+    /// configuration.
+    pub fn create_group_info(mut self, create_group_info: bool) -> Self {
+        self.create_group_info = Some(create_group_info);
+        self
+    }
+
+    /// Register a hash of the state the application replicates between the
+    /// members, e.g. the heads of a CRDT, in the group context of the new
+    /// epoch.
+    ///
+    /// The hash is set in an [`AppStateHashExtension`] through a
+    /// `GroupContextExtensions` proposal that keeps all other group context
+    /// extensions. If an inline `GroupContextExtensions` proposal was added to
+    /// the builder, the hash is added to that proposal instead. Members
+    /// processing the commit can compare the hash to their own state with
+    /// [`StagedCommit::compare_app_state()`].
+    ///
+    /// Note that a commit can only cover a single `GroupContextExtensions`
+    /// proposal, so the commit fails if one of the committed pending proposals
+    /// is a `GroupContextExtensions` proposal as well.
+    pub fn app_state_hash(mut self, app_state_hash: &[u8]) -> Self {
+        self.app_state_hash = Some(app_state_hash.to_vec());
+        self
+    }
+
+    /// Reveal the real credentials of members that are added with
+    /// pseudonymous key packages to the joiners.
+    ///
+    /// The [`CredentialBinding`]s are obtained from the joiners out of band,
+    /// see
+    /// [`KeyPackageBuilder::build_pseudonymous()`](crate::key_packages::KeyPackageBuilder::build_pseudonymous()).
+    /// They are only included in the GroupInfo that is encrypted in the
+    /// [`Welcome`], so neither the existing members nor a returned
+    /// [`GroupInfo`] learn them. The real credentials are not validated by
+    /// OpenMLS and should be checked by the application beforehand.
+    ///
+    /// The commit fails if a binding doesn't belong to one of the added key
+    /// packages or if its signature is invalid.
+    pub fn credential_bindings(
+        mut self,
+        credential_bindings: impl IntoIterator<Item = CredentialBinding>,
+    ) -> Self {
+        self.credential_bindings.extend(credential_bindings);
+        self
+    }
+
+    /// Set the wire format of this commit only, e.g. to send a single commit
+    /// as [`PublicMessage`] so that the DS can validate it in a group that
+    /// otherwise encrypts all handshake messages.
+    ///
+    /// The wire format has to be accepted by the group's
+    /// [`WireFormatPolicy`], see [`WireFormatPolicy::with_plaintext_commits()`].
+    /// Defaults to the outgoing wire format policy of the group.
+    pub fn wire_format(mut self, wire_format: OutgoingWireFormatPolicy) -> Self {
+        self.wire_format = Some(wire_format);
+        self
+    }
+
+    /// Create the commit.
+    ///
+    /// If successful, it returns a triple of [`MlsMessageOut`] (containing the
+    /// commit), an optional [`MlsMessageOut`] (containing the [`Welcome`]) and
+    /// an optional [`GroupInfo`]. The group is in the
+    /// [`MlsGroupState::PendingCommit`] state afterwards.
+    ///
+    /// Returns an error if there is a pending commit, if one of the
+    /// requested proposals can't be found in the proposal store, if the
+    /// requested proposals conflict with each other or if the
+    /// requested wire format isn't accepted by the group's wire format
+    /// policy.
+    #[allow(clippy::type_complexity)]
+    pub fn finalize<KeyStore: OpenMlsKeyStore>(
+        self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+    ) -> Result<
+        (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
+        CommitBuilderError<KeyStore::Error>,
+    > {
+        let group = self.group;
+        group.is_operational()?;
+
+        let wire_format_policy = group.mls_group_config.wire_format_policy();
+        let wire_format = match self.wire_format {
+            Some(wire_format) => {
+                if !wire_format_policy.accepts(wire_format.into(), ContentType::Commit) {
+                    return Err(CommitBuilderError::IncompatibleWireFormat);
+                }
+                wire_format
+            }
+            None => wire_format_policy.outgoing(),
+        };
+
+        // Collect the selected proposals into a separate store if only a
+        // subset of the pending proposals should be committed.
+        let proposal_store = match self.proposal_refs {
+            Some(proposal_refs) => {
+                let mut proposal_store = ProposalStore::new();
+                for proposal_ref in proposal_refs {
+                    let queued_proposal = group
+                        .proposal_store
+                        .proposals()
+                        .find(|p| p.proposal_reference() == proposal_ref)
+                        .ok_or(CommitBuilderError::ProposalNotFound(proposal_ref))?;
+                    proposal_store.add(queued_proposal.clone());
+                }
+                if let Some(conflict) = proposal_store.conflicts().into_iter().next() {
+                    return Err(CommitBuilderError::ConflictingProposals(conflict));
+                }
+                Some(proposal_store)
+            }
+            None => None,
+        };
+
+        let mut inline_proposals = self.inline_proposals;
+        if let Some(app_state_hash) = self.app_state_hash {
+            let extension = Extension::AppStateHash(AppStateHashExtension::new(&app_state_hash));
+            let gce_proposal = inline_proposals
+                .iter_mut()
+                .find_map(|proposal| match proposal {
+                    Proposal::GroupContextExtensions(gce_proposal) => Some(gce_proposal),
+                    _ => None,
+                });
+            match gce_proposal {
+                Some(gce_proposal) => {
+                    let mut extensions = gce_proposal.extensions().clone();
+                    extensions.add_or_replace(extension);
+                    *gce_proposal = GroupContextExtensionProposal::new(extensions);
+                }
+                None => {
+                    let mut extensions = group.group.context().extensions().clone();
+                    extensions.add_or_replace(extension);
+                    inline_proposals.push(Proposal::GroupContextExtensions(
+                        GroupContextExtensionProposal::new(extensions),
+                    ));
+                }
+            }
+        }
+
+        let aad = self.aad.unwrap_or_else(|| group.aad.clone());
+        let framing_parameters = FramingParameters::new(&aad, wire_format);
+
+        let params = CreateCommitParams::builder()
+            .framing_parameters(framing_parameters)
+            .proposal_store(proposal_store.as_ref().unwrap_or(&group.proposal_store))
+            .inline_proposals(inline_proposals)
+            .force_self_update(self.force_self_update)
+            .create_group_info(self.create_group_info)
+            .credential_bindings(self.credential_bindings)
+            .psk_resolver(group.psk_resolver.as_deref())
+            .build();
+        let create_commit_result = group.group.create_commit(params, backend, signer)?;
+
+        // Convert PublicMessage messages to MLSMessage and encrypt them if required by
+        // the configuration
+        let mls_message = group.content_to_mls_message_with_wire_format(
+            create_commit_result.commit,
+            wire_format,
+            backend,
+        )?;
+
+        // Set the current group state to [`MlsGroupState::PendingCommit`],
+        // storing the current [`StagedCommit`] from the commit results
+        group.group_state = MlsGroupState::PendingCommit(Box::new(PendingCommitState::Member(
+            create_commit_result.staged_commit,
+        )));
+        group
+            .store(backend)
+            .map_err(CommitBuilderError::StorageError)?;
+
+        Ok((
+            mls_message,
+            create_commit_result
+                .welcome_option
+                .map(|w| MlsMessageOut::from_welcome(w, group.group.version())),
+            create_commit_result.group_info,
+        ))
+    }
+}
@@ -0,0 +1,101 @@
+//! # Commit validation
+//!
+//! OpenMLS checks that commits are valid according to the MLS protocol, but
+//! not whether they are acceptable for the application, e.g. whether the
+//! committer is allowed to remove an admin or to change the group context
+//! extensions.
+//!
+//! A [`CommitValidator`] lets the application plug such a policy into an
+//! [`MlsGroup`](crate::group::MlsGroup) with
+//! [`MlsGroup::set_commit_validator()`](crate::group::MlsGroup::set_commit_validator()).
+//! The group then calls it for every commit it processes with
+//! [`MlsGroup::process_message()`](crate::group::MlsGroup::process_message()),
+//! before the [`StagedCommit`] is returned to the application. A rejected
+//! commit can't be merged.
+//!
+//! Functions and closures with the signature of
+//! [`CommitValidator::validate()`] are validators:
+//!
+//! ```
+//! use openmls::prelude::*;
+//!
+//! fn no_removals(
+//!     staged_commit: &StagedCommit,
+//!     _group: &PublicGroup,
+//! ) -> Result<(), CommitRejection> {
+//!     if staged_commit.removed_members().is_empty() {
+//!         Ok(())
+//!     } else {
+//!         Err(CommitRejection::new("Members can't be removed."))
+//!     }
+//! }
+//!
+//! fn set_policy(group: &mut MlsGroup) {
+//!     group.set_commit_validator(no_removals);
+//! }
+//! ```
+
+use std::fmt::{Debug, Display};
+
+use crate::group::{PublicGroup, StagedCommit};
+
+/// A policy that decides whether a commit may be merged.
+///
+/// See the [module documentation](self) for when it is called.
+pub trait CommitValidator: Send + Sync {
+    /// Validates the `staged_commit` against the `group` it is applied to,
+    /// i.e. the public state of the group in the current epoch.
+    ///
+    /// Returns a [`CommitRejection`] if the policy doesn't accept the commit.
+    /// The commit is then rejected.
+    fn validate(
+        &self,
+        staged_commit: &StagedCommit,
+        group: &PublicGroup,
+    ) -> Result<(), CommitRejection>;
+}
+
+impl<F> CommitValidator for F
+where
+    F: Fn(&StagedCommit, &PublicGroup) -> Result<(), CommitRejection> + Send + Sync,
+{
+    fn validate(
+        &self,
+        staged_commit: &StagedCommit,
+        group: &PublicGroup,
+    ) -> Result<(), CommitRejection> {
+        self(staged_commit, group)
+    }
+}
+
+impl Debug for dyn CommitValidator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CommitValidator")
+    }
+}
+
+/// The reason why a [`CommitValidator`] rejected a commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitRejection {
+    reason: String,
+}
+
+impl CommitRejection {
+    /// Create a new rejection with the given `reason`.
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+        }
+    }
+
+    /// Returns the reason of the rejection.
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+impl Display for CommitRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.reason)
+    }
+}
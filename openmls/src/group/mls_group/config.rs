@@ -29,10 +29,15 @@
 
 use super::*;
 use crate::{
-    group::config::CryptoConfig, key_packages::Lifetime,
+    ciphersuite::policy::CiphersuitePolicy,
+    group::config::CryptoConfig,
+    key_packages::{Lifetime, LifetimePolicy},
+    schedule::ExporterCompatibility,
     tree::sender_ratchet::SenderRatchetConfiguration,
+    treesync::LeafPlacementStrategy,
 };
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// Specifies the configuration parameters for a [`MlsGroup`]. Refer to
 /// the [User Manual](https://openmls.tech/book/user_manual/group_config.html) for more information about the different configuration values.
@@ -43,10 +48,18 @@ pub struct MlsGroupConfig {
     pub(crate) wire_format_policy: WireFormatPolicy,
     /// Size of padding in bytes
     pub(crate) padding_size: usize,
+    /// Flag to indicate that the PADMÉ padding scheme is used instead of
+    /// padding to a multiple of `padding_size`
+    #[serde(default)]
+    pub(crate) use_padme: bool,
     /// Maximum number of past epochs for which application messages
     /// can be decrypted. The default is 0.
     pub(crate) max_past_epochs: usize,
-    /// Number of resumtion secrets to keep
+    /// Maximum time for which the secrets of past epochs are kept after the
+    /// epoch ended. The default is `None`, i.e. no limit.
+    #[serde(default)]
+    pub(crate) max_past_epoch_age: Option<Duration>,
+    /// Number of resumption secrets to keep. The default is 0.
     pub(crate) number_of_resumption_psks: usize,
     /// Flag to indicate the Ratchet Tree Extension should be used
     pub(crate) use_ratchet_tree_extension: bool,
@@ -60,6 +73,34 @@ pub struct MlsGroupConfig {
     pub(crate) lifetime: Lifetime,
     /// Ciphersuite and protocol version
     pub(crate) crypto_config: CryptoConfig,
+    /// Configuration of the buffer for messages from future epochs
+    #[serde(default)]
+    pub(crate) message_buffer_configuration: MessageBufferConfiguration,
+    /// Flag to indicate that members attach auditor tags to handshake messages
+    #[serde(default)]
+    pub(crate) use_auditor_tags: bool,
+    /// Policy for the lifetimes of key packages in Add proposals
+    #[serde(default)]
+    pub(crate) lifetime_policy: LifetimePolicy,
+    /// Policy for the ciphersuites of the group and of Welcomes
+    #[serde(default)]
+    pub(crate) ciphersuite_policy: CiphersuitePolicy,
+    /// Grace period for decrypting application messages after being removed
+    #[serde(default)]
+    pub(crate) removal_grace: RemovalGrace,
+    /// Strategy to place the leaf nodes of added members in the tree
+    #[serde(default)]
+    pub(crate) leaf_placement_strategy: LeafPlacementStrategy,
+    /// Exporter labels for which secrets are exported like in other
+    /// implementations
+    #[serde(default)]
+    pub(crate) exporter_compatibility: ExporterCompatibility,
+    /// Limits for the size and structure of incoming messages
+    #[serde(default)]
+    pub(crate) decode_limits: DecodeLimits,
+    /// Maximum number of events in the event log, or 0 to disable the log
+    #[serde(default)]
+    pub(crate) event_log_capacity: usize,
 }
 
 impl MlsGroupConfig {
@@ -73,16 +114,45 @@ impl MlsGroupConfig {
         self.wire_format_policy
     }
 
-    /// Returns the [`MlsGroupConfig`] padding size.
+    /// Returns the [`MlsGroupConfig`] padding size, i.e. the block size if
+    /// the padding policy is [`PaddingPolicy::BlockSize`] and 0 otherwise.
     pub fn padding_size(&self) -> usize {
         self.padding_size
     }
 
+    /// Returns the [`MlsGroupConfig`] padding policy.
+    pub fn padding_policy(&self) -> PaddingPolicy {
+        if self.use_padme {
+            PaddingPolicy::Padme
+        } else if self.padding_size == 0 {
+            PaddingPolicy::None
+        } else {
+            PaddingPolicy::BlockSize(self.padding_size)
+        }
+    }
+
     /// Returns the [`MlsGroupConfig`] max past epochs.
     pub fn max_past_epochs(&self) -> usize {
         self.max_past_epochs
     }
 
+    /// Returns the [`MlsGroupConfig`] past epoch retention policy.
+    pub fn past_epoch_retention(&self) -> PastEpochRetention {
+        PastEpochRetention {
+            max_epochs: self.max_past_epochs,
+            max_age: self.max_past_epoch_age,
+        }
+    }
+
+    /// Returns the [`MlsGroupConfig`] message secrets policy, i.e. its past
+    /// epoch retention policy and sender ratchet configuration.
+    pub fn message_secrets_policy(&self) -> MessageSecretsPolicy {
+        MessageSecretsPolicy {
+            past_epoch_retention: self.past_epoch_retention(),
+            sender_ratchet_configuration: self.sender_ratchet_configuration.clone(),
+        }
+    }
+
     /// Returns the [`MlsGroupConfig`] number of resumption psks.
     pub fn number_of_resumption_psks(&self) -> usize {
         self.number_of_resumption_psks
@@ -103,6 +173,11 @@ impl MlsGroupConfig {
         &self.external_senders
     }
 
+    /// Returns the [`MlsGroupConfig`] required capabilities.
+    pub fn required_capabilities(&self) -> &RequiredCapabilitiesExtension {
+        &self.required_capabilities
+    }
+
     /// Returns the [`MlsGroupConfig`] lifetime configuration.
     pub fn lifetime(&self) -> &Lifetime {
         &self.lifetime
@@ -113,6 +188,52 @@ impl MlsGroupConfig {
         &self.crypto_config
     }
 
+    /// Returns the [`MlsGroupConfig`] message buffer configuration.
+    pub fn message_buffer_configuration(&self) -> &MessageBufferConfiguration {
+        &self.message_buffer_configuration
+    }
+
+    /// Returns the [`MlsGroupConfig`] boolean flag that indicates whether
+    /// auditor tags are used. See [`MlsGroup::tag_for_auditor()`].
+    pub fn use_auditor_tags(&self) -> bool {
+        self.use_auditor_tags
+    }
+
+    /// Returns the [`MlsGroupConfig`] key package lifetime policy.
+    pub fn lifetime_policy(&self) -> &LifetimePolicy {
+        &self.lifetime_policy
+    }
+
+    /// Returns the [`MlsGroupConfig`] ciphersuite policy.
+    pub fn ciphersuite_policy(&self) -> &CiphersuitePolicy {
+        &self.ciphersuite_policy
+    }
+
+    /// Returns the [`MlsGroupConfig`] removal grace configuration.
+    pub fn removal_grace(&self) -> &RemovalGrace {
+        &self.removal_grace
+    }
+
+    /// Returns the [`MlsGroupConfig`] leaf placement strategy.
+    pub fn leaf_placement_strategy(&self) -> LeafPlacementStrategy {
+        self.leaf_placement_strategy
+    }
+
+    /// Returns the [`MlsGroupConfig`] exporter compatibility.
+    pub fn exporter_compatibility(&self) -> &ExporterCompatibility {
+        &self.exporter_compatibility
+    }
+
+    /// Returns the [`MlsGroupConfig`] decode limits.
+    pub fn decode_limits(&self) -> &DecodeLimits {
+        &self.decode_limits
+    }
+
+    /// Returns the [`MlsGroupConfig`] event log capacity.
+    pub fn event_log_capacity(&self) -> usize {
+        self.event_log_capacity
+    }
+
     #[cfg(any(feature = "test-utils", test))]
     pub fn test_default(ciphersuite: Ciphersuite) -> Self {
         Self::builder()
@@ -145,9 +266,24 @@ impl MlsGroupConfigBuilder {
         self
     }
 
-    /// Sets the `padding_size` property of the MlsGroupConfig.
+    /// Sets the `padding_size` property of the MlsGroupConfig, i.e. sets the
+    /// padding policy to [`PaddingPolicy::BlockSize`].
     pub fn padding_size(mut self, padding_size: usize) -> Self {
         self.config.padding_size = padding_size;
+        self.config.use_padme = false;
+        self
+    }
+
+    /// Sets the [`PaddingPolicy`] that is applied to outgoing PrivateMessages
+    /// of the group.
+    pub fn padding_policy(mut self, padding_policy: PaddingPolicy) -> Self {
+        let (padding_size, use_padme) = match padding_policy {
+            PaddingPolicy::None => (0, false),
+            PaddingPolicy::BlockSize(block_size) => (block_size, false),
+            PaddingPolicy::Padme => (0, true),
+        };
+        self.config.padding_size = padding_size;
+        self.config.use_padme = use_padme;
         self
     }
 
@@ -166,6 +302,17 @@ impl MlsGroupConfigBuilder {
         self
     }
 
+    /// Sets the `max_past_epochs` and the `max_past_epoch_age` properties of
+    /// the MlsGroupConfig from a [`PastEpochRetention`] policy.
+    ///
+    /// The same **WARNING** as for
+    /// [`max_past_epochs()`](Self::max_past_epochs()) applies.
+    pub fn past_epoch_retention(mut self, past_epoch_retention: PastEpochRetention) -> Self {
+        self.config.max_past_epochs = past_epoch_retention.max_epochs;
+        self.config.max_past_epoch_age = past_epoch_retention.max_age;
+        self
+    }
+
     /// Sets the `number_of_resumption_psks` property of the MlsGroupConfig.
     pub fn number_of_resumption_psks(mut self, number_of_resumption_psks: usize) -> Self {
         self.config.number_of_resumption_psks = number_of_resumption_psks;
@@ -178,6 +325,16 @@ impl MlsGroupConfigBuilder {
         self
     }
 
+    /// Sets the `max_past_epochs`, `max_past_epoch_age` and
+    /// `sender_ratchet_configuration` properties of the MlsGroupConfig from a
+    /// [`MessageSecretsPolicy`].
+    pub fn message_secrets_policy(mut self, message_secrets_policy: MessageSecretsPolicy) -> Self {
+        self = self.past_epoch_retention(message_secrets_policy.past_epoch_retention);
+        self.config.sender_ratchet_configuration =
+            message_secrets_policy.sender_ratchet_configuration;
+        self
+    }
+
     /// Sets the `sender_ratchet_configuration` property of the MlsGroupConfig.
     /// See [`SenderRatchetConfiguration`] for more information.
     pub fn sender_ratchet_configuration(
@@ -200,18 +357,349 @@ impl MlsGroupConfigBuilder {
         self
     }
 
+    /// Sets the `required_capabilities` property of the MlsGroupConfig.
+    /// The creator of the group has to support all of them.
+    pub fn required_capabilities(
+        mut self,
+        required_capabilities: RequiredCapabilitiesExtension,
+    ) -> Self {
+        self.config.required_capabilities = required_capabilities;
+        self
+    }
+
     /// Sets the `external_senders` property of the MlsGroupConfig.
     pub fn external_senders(mut self, external_senders: ExternalSendersExtension) -> Self {
         self.config.external_senders = external_senders;
         self
     }
 
+    /// Sets the `message_buffer_configuration` property of the MlsGroupConfig.
+    /// See [`MessageBufferConfiguration`] for more information.
+    pub fn message_buffer_configuration(
+        mut self,
+        message_buffer_configuration: MessageBufferConfiguration,
+    ) -> Self {
+        self.config.message_buffer_configuration = message_buffer_configuration;
+        self
+    }
+
+    /// Sets the `use_auditor_tags` property of the MlsGroupConfig.
+    /// See [`MlsGroup::tag_for_auditor()`] for more information.
+    pub fn use_auditor_tags(mut self, use_auditor_tags: bool) -> Self {
+        self.config.use_auditor_tags = use_auditor_tags;
+        self
+    }
+
+    /// Sets the `lifetime_policy` property of the MlsGroupConfig, which
+    /// determines whether the lifetimes of the key packages in Add proposals
+    /// are acceptable.
+    pub fn lifetime_policy(mut self, lifetime_policy: LifetimePolicy) -> Self {
+        self.config.lifetime_policy = lifetime_policy;
+        self
+    }
+
+    /// Sets the `ciphersuite_policy` property of the MlsGroupConfig, which
+    /// determines whether the ciphersuite of a new group or of a group that
+    /// is joined is acceptable.
+    pub fn ciphersuite_policy(mut self, ciphersuite_policy: CiphersuitePolicy) -> Self {
+        self.config.ciphersuite_policy = ciphersuite_policy;
+        self
+    }
+
+    /// Sets the `removal_grace` property of the MlsGroupConfig, which
+    /// determines whether application messages of the final epoch can still
+    /// be decrypted after the own client was removed from the group.
+    /// See [`RemovalGrace`] for more information.
+    pub fn removal_grace(mut self, removal_grace: RemovalGrace) -> Self {
+        self.config.removal_grace = removal_grace;
+        self
+    }
+
+    /// Sets the `leaf_placement_strategy` property of the MlsGroupConfig,
+    /// which determines where the leaf nodes of added members are put in the
+    /// tree. All members of the group must use the same strategy.
+    /// See [`LeafPlacementStrategy`] for more information.
+    pub fn leaf_placement_strategy(
+        mut self,
+        leaf_placement_strategy: LeafPlacementStrategy,
+    ) -> Self {
+        self.config.leaf_placement_strategy = leaf_placement_strategy;
+        self
+    }
+
+    /// Sets the `exporter_compatibility` property of the MlsGroupConfig,
+    /// which lists the exporter labels for which
+    /// [`MlsGroup::export_secret()`] reproduces the quirks of other
+    /// implementations. See [`ExporterCompatibility`] for more information.
+    pub fn exporter_compatibility(mut self, exporter_compatibility: ExporterCompatibility) -> Self {
+        self.config.exporter_compatibility = exporter_compatibility;
+        self
+    }
+
+    /// Sets the `decode_limits` property of the MlsGroupConfig, which bounds
+    /// the size and the structure of incoming messages. See [`DecodeLimits`]
+    /// for more information.
+    pub fn decode_limits(mut self, decode_limits: DecodeLimits) -> Self {
+        self.config.decode_limits = decode_limits;
+        self
+    }
+
+    /// Sets the `event_log_capacity` property of the MlsGroupConfig, which
+    /// is the maximum number of events that [`MlsGroup::event_log()`]
+    /// keeps. The oldest events are dropped when the log is full. The default
+    /// is 0, which disables the log.
+    pub fn event_log_capacity(mut self, event_log_capacity: usize) -> Self {
+        self.config.event_log_capacity = event_log_capacity;
+        self
+    }
+
     /// Finalizes the builder and retursn an `[MlsGroupConfig`].
     pub fn build(self) -> MlsGroupConfig {
         self.config
     }
 }
 
+/// Policy for keeping the message secrets of past epochs, so that application
+/// messages from these epochs can still be decrypted.
+///
+///  - max_epochs:
+/// The maximum number of past epochs for which secrets are kept. The default
+/// value is 0, i.e. no secrets of past epochs are kept.
+///  - max_age:
+/// The maximum time for which the secrets of a past epoch are kept after the
+/// epoch ended. The default value is `None`, i.e. secrets are only dropped
+/// when more than `max_epochs` newer past epochs are kept.
+///
+/// Expired secrets are dropped when the group processes a message or merges a
+/// commit, or when [`MlsGroup::drop_expired_past_epochs()`] is called.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PastEpochRetention {
+    max_epochs: usize,
+    max_age: Option<Duration>,
+}
+
+impl PastEpochRetention {
+    /// Create a new policy that keeps the secrets of up to `max_epochs` past
+    /// epochs.
+    pub fn new(max_epochs: usize) -> Self {
+        Self {
+            max_epochs,
+            max_age: None,
+        }
+    }
+
+    /// Set the maximum time for which the secrets of a past epoch are kept
+    /// after the epoch ended.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Get the maximum number of past epochs for which secrets are kept.
+    pub fn max_epochs(&self) -> usize {
+        self.max_epochs
+    }
+
+    /// Get the maximum time for which the secrets of a past epoch are kept.
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age
+    }
+}
+
+/// Policy for the message secrets that are kept to decrypt application
+/// messages that arrive late or out of order.
+///
+///  - past_epoch_retention:
+/// How long the secrets of past epochs are kept. See [`PastEpochRetention`].
+///  - sender_ratchet_configuration:
+/// How many keys of skipped messages are kept per sender within an epoch. See
+/// [`SenderRatchetConfiguration`].
+///
+/// Keeping more secrets makes the group more tolerant to delayed messages at
+/// the expense of memory and forward secrecy. The policy of a group can be
+/// changed at runtime with [`MlsGroup::set_message_secrets_policy()`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageSecretsPolicy {
+    past_epoch_retention: PastEpochRetention,
+    sender_ratchet_configuration: SenderRatchetConfiguration,
+}
+
+impl MessageSecretsPolicy {
+    /// Create a new policy.
+    pub fn new(
+        past_epoch_retention: PastEpochRetention,
+        sender_ratchet_configuration: SenderRatchetConfiguration,
+    ) -> Self {
+        Self {
+            past_epoch_retention,
+            sender_ratchet_configuration,
+        }
+    }
+
+    /// Set the policy for the secrets of past epochs.
+    pub fn with_past_epoch_retention(mut self, past_epoch_retention: PastEpochRetention) -> Self {
+        self.past_epoch_retention = past_epoch_retention;
+        self
+    }
+
+    /// Set the configuration of the sender ratchets.
+    pub fn with_sender_ratchet_configuration(
+        mut self,
+        sender_ratchet_configuration: SenderRatchetConfiguration,
+    ) -> Self {
+        self.sender_ratchet_configuration = sender_ratchet_configuration;
+        self
+    }
+
+    /// Get the policy for the secrets of past epochs.
+    pub fn past_epoch_retention(&self) -> PastEpochRetention {
+        self.past_epoch_retention
+    }
+
+    /// Get the configuration of the sender ratchets.
+    pub fn sender_ratchet_configuration(&self) -> &SenderRatchetConfiguration {
+        &self.sender_ratchet_configuration
+    }
+}
+
+/// The default maximum number of buffered messages per sender and epoch.
+const DEFAULT_MAX_MESSAGES_PER_SENDER: usize = 16;
+
+fn default_max_messages_per_sender() -> usize {
+    DEFAULT_MAX_MESSAGES_PER_SENDER
+}
+
+/// Configuration of the buffer for messages from future epochs.
+///
+/// If the buffer is enabled, [`MlsGroup::process_message()`] keeps messages
+/// from future epochs instead of rejecting them. They are processed
+/// automatically once a merged commit brings the group to their epoch (see
+/// [`MlsGroup::take_processed_buffered_messages()`]), or with
+/// [`MlsGroup::process_buffered_messages()`].
+///  - max_messages:
+/// The maximum number of messages in the buffer. The default value is 0,
+/// i.e. the buffer is disabled.
+///  - max_epochs:
+/// How many epochs ahead of the current epoch a message can be to be
+/// buffered. Messages from epochs further ahead are rejected. The default
+/// value is 0, i.e. the buffer is disabled.
+///  - max_messages_per_sender:
+/// The maximum number of buffered messages per epoch from the same sender.
+/// Messages from future epochs can't be authenticated yet, so the sender of
+/// a [`PublicMessage`] is taken as it is, and all [`PrivateMessage`]s of an
+/// epoch count as messages of the same unknown sender. The default value is
+/// 16.
+///
+/// Note that messages of the current epoch that arrive out of order don't
+/// need to be buffered, see [`SenderRatchetConfiguration`] instead.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageBufferConfiguration {
+    max_messages: usize,
+    max_epochs: u64,
+    #[serde(default = "default_max_messages_per_sender")]
+    max_messages_per_sender: usize,
+}
+
+impl Default for MessageBufferConfiguration {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+impl MessageBufferConfiguration {
+    /// Create a new configuration
+    pub fn new(max_messages: usize, max_epochs: u64) -> Self {
+        Self {
+            max_messages,
+            max_epochs,
+            max_messages_per_sender: DEFAULT_MAX_MESSAGES_PER_SENDER,
+        }
+    }
+
+    /// Set the maximum number of buffered messages per sender and epoch.
+    pub fn with_max_messages_per_sender(mut self, max_messages_per_sender: usize) -> Self {
+        self.max_messages_per_sender = max_messages_per_sender;
+        self
+    }
+
+    /// Get the maximum number of buffered messages.
+    pub fn max_messages(&self) -> usize {
+        self.max_messages
+    }
+
+    /// Get the maximum number of epochs a buffered message can be ahead of
+    /// the current epoch.
+    pub fn max_epochs(&self) -> u64 {
+        self.max_epochs
+    }
+
+    /// Get the maximum number of buffered messages per sender and epoch.
+    pub fn max_messages_per_sender(&self) -> usize {
+        self.max_messages_per_sender
+    }
+}
+
+/// Configuration of the grace period in which application messages can still
+/// be decrypted after the own client was removed from the group.
+///
+/// If the grace period is enabled, messages that were already in flight when
+/// the client was removed can still be processed with
+/// [`MlsGroup::process_message()`]. Only application messages of the final
+/// epoch of the client are accepted, and only from senders whose sender
+/// ratchets were already derived before the removal. All other secrets of the
+/// group are deleted when the client is removed.
+///  - max_messages:
+/// The maximum number of messages that are processed after the removal. The
+/// default value is 0, i.e. the grace period is disabled and all secrets are
+/// deleted when the client is removed.
+///  - max_age:
+/// The maximum time after the removal for which messages are processed. The
+/// default value is `None`, i.e. the grace period is only bounded by
+/// `max_messages`.
+///
+/// The remaining secrets are deleted as soon as one of the bounds is reached,
+/// or when [`MlsGroup::end_removal_grace()`] is called.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemovalGrace {
+    max_messages: usize,
+    max_age: Option<Duration>,
+}
+
+impl RemovalGrace {
+    /// Create a new configuration that allows processing up to
+    /// `max_messages` messages after the removal.
+    pub fn new(max_messages: usize) -> Self {
+        Self {
+            max_messages,
+            max_age: None,
+        }
+    }
+
+    /// Set the maximum time after the removal for which messages are
+    /// processed.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Get the maximum number of messages that are processed after the
+    /// removal.
+    pub fn max_messages(&self) -> usize {
+        self.max_messages
+    }
+
+    /// Get the maximum time after the removal for which messages are
+    /// processed.
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age
+    }
+
+    /// Returns whether the grace period is enabled.
+    pub fn is_enabled(&self) -> bool {
+        self.max_messages > 0 && self.max_age != Some(Duration::ZERO)
+    }
+}
+
 /// Defines what wire format is acceptable for incoming handshake messages.
 /// Note that application messages must always be encrypted.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -253,6 +741,8 @@ pub enum OutgoingWireFormatPolicy {
 pub struct WireFormatPolicy {
     outgoing: OutgoingWireFormatPolicy,
     incoming: IncomingWireFormatPolicy,
+    #[serde(default)]
+    plaintext_commits: bool,
 }
 
 impl WireFormatPolicy {
@@ -263,7 +753,41 @@ impl WireFormatPolicy {
         outgoing: OutgoingWireFormatPolicy,
         incoming: IncomingWireFormatPolicy,
     ) -> Self {
-        Self { outgoing, incoming }
+        Self {
+            outgoing,
+            incoming,
+            plaintext_commits: false,
+        }
+    }
+
+    /// Sets whether commits may be sent and received as [`PublicMessage`]
+    /// even if the incoming wire format policy requires a
+    /// [`PrivateMessage`](crate::framing::PrivateMessage).
+    ///
+    /// This allows a group that normally encrypts all handshake messages to
+    /// send individual commits, e.g. membership changes the DS has to
+    /// validate, in the clear through [`CommitBuilder::wire_format()`]. The
+    /// exception only applies to commits, all other handshake messages still
+    /// have to follow the incoming wire format policy. All members of the
+    /// group should use the same setting.
+    pub fn with_plaintext_commits(mut self, plaintext_commits: bool) -> Self {
+        self.plaintext_commits = plaintext_commits;
+        self
+    }
+
+    /// Returns `true` if commits may be sent and received as
+    /// [`PublicMessage`] regardless of the incoming wire format policy.
+    pub fn plaintext_commits(&self) -> bool {
+        self.plaintext_commits
+    }
+
+    /// Returns `true` if a handshake message of the given `content_type` is
+    /// accepted with the given `wire_format`.
+    pub(crate) fn accepts(&self, wire_format: WireFormat, content_type: ContentType) -> bool {
+        self.incoming.is_compatible_with(wire_format)
+            || (self.plaintext_commits
+                && wire_format == WireFormat::PublicMessage
+                && content_type == ContentType::Commit)
     }
 
     /// Returns a reference to the wire format policy's outgoing wire format policy.
@@ -308,12 +832,14 @@ pub const WIRE_FORMAT_POLICIES: [WireFormatPolicy; 4] = [
 pub const PURE_PLAINTEXT_WIRE_FORMAT_POLICY: WireFormatPolicy = WireFormatPolicy {
     outgoing: OutgoingWireFormatPolicy::AlwaysPlaintext,
     incoming: IncomingWireFormatPolicy::AlwaysPlaintext,
+    plaintext_commits: false,
 };
 
 /// Incoming and outgoing wire formats are always ciphertext.
 pub const PURE_CIPHERTEXT_WIRE_FORMAT_POLICY: WireFormatPolicy = WireFormatPolicy {
     outgoing: OutgoingWireFormatPolicy::AlwaysCiphertext,
     incoming: IncomingWireFormatPolicy::AlwaysCiphertext,
+    plaintext_commits: false,
 };
 
 /// Incoming wire formats can be mixed while outgoing wire formats are always
@@ -321,6 +847,7 @@ pub const PURE_CIPHERTEXT_WIRE_FORMAT_POLICY: WireFormatPolicy = WireFormatPolic
 pub const MIXED_PLAINTEXT_WIRE_FORMAT_POLICY: WireFormatPolicy = WireFormatPolicy {
     outgoing: OutgoingWireFormatPolicy::AlwaysPlaintext,
     incoming: IncomingWireFormatPolicy::Mixed,
+    plaintext_commits: false,
 };
 
 /// Incoming wire formats can be mixed while outgoing wire formats are always
@@ -328,4 +855,5 @@ pub const MIXED_PLAINTEXT_WIRE_FORMAT_POLICY: WireFormatPolicy = WireFormatPolic
 pub const MIXED_CIPHERTEXT_WIRE_FORMAT_POLICY: WireFormatPolicy = WireFormatPolicy {
     outgoing: OutgoingWireFormatPolicy::AlwaysCiphertext,
     incoming: IncomingWireFormatPolicy::Mixed,
+    plaintext_commits: false,
 };
@@ -1,17 +1,18 @@
-use openmls_traits::signatures::Signer;
+use openmls_traits::{crypto::OpenMlsCrypto, signatures::Signer, storage::StorageProvider};
 
 use super::*;
 use crate::{
+    ciphersuite::signable::Signable,
     ciphersuite::HpkePrivateKey,
     credentials::CredentialWithKey,
+    extensions::CredentialBinding,
     group::{
-        core_group::create_commit_params::CreateCommitParams,
-        errors::{CoreGroupBuildError, ExternalCommitError, WelcomeError},
+        errors::{CoreGroupBuildError, DeclineWelcomeError, WelcomeError},
         public_group::errors::PublicGroupBuildError,
     },
-    messages::group_info::{GroupInfo, VerifiableGroupInfo},
+    messages::welcome_decline::{WelcomeDecline, WelcomeDeclineTbs},
     schedule::psk::store::ResumptionPskStore,
-    treesync::RatchetTreeIn,
+    treesync::{node::encryption_keys::EncryptionKeyPair, RatchetTreeIn},
 };
 
 impl MlsGroup {
@@ -37,6 +38,10 @@ impl MlsGroup {
     }
 
     /// Creates a new group with a given group ID with the creator as the only member.
+    ///
+    /// Returns [`NewGroupError::CiphersuitePolicy`] if the ciphersuite of the
+    /// `mls_group_config` is not permitted by its
+    /// [`CiphersuitePolicy`](crate::ciphersuite::policy::CiphersuitePolicy).
     pub fn new_with_group_id<KeyStore: OpenMlsKeyStore>(
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         signer: &impl Signer,
@@ -44,6 +49,14 @@ impl MlsGroup {
         group_id: GroupId,
         credential_with_key: CredentialWithKey,
     ) -> Result<Self, NewGroupError<KeyStore::Error>> {
+        backend
+            .crypto()
+            .supports(mls_group_config.crypto_config.ciphersuite)
+            .map_err(|_| NewGroupError::UnsupportedCiphersuite)?;
+        mls_group_config
+            .ciphersuite_policy()
+            .check(mls_group_config.crypto_config.ciphersuite)?;
+
         // TODO #751
         let group_config = CoreGroupConfig {
             add_ratchet_tree_extension: mls_group_config.use_ratchet_tree_extension,
@@ -57,7 +70,7 @@ impl MlsGroup {
         .with_config(group_config)
         .with_required_capabilities(mls_group_config.required_capabilities.clone())
         .with_external_senders(mls_group_config.external_senders.clone())
-        .with_max_past_epoch_secrets(mls_group_config.max_past_epochs)
+        .with_past_epoch_retention(mls_group_config.past_epoch_retention())
         .with_lifetime(*mls_group_config.lifetime())
         .build(backend, signer)
         .map_err(|e| match e {
@@ -80,6 +93,11 @@ impl MlsGroup {
             },
         })?;
 
+        group.set_lifetime_policy(*mls_group_config.lifetime_policy());
+        group.set_leaf_placement_strategy(mls_group_config.leaf_placement_strategy());
+        group.set_decode_limits(*mls_group_config.decode_limits());
+        group.set_number_of_resumption_psks(mls_group_config.number_of_resumption_psks());
+
         // We already add a resumption PSK for epoch 0 to make things more unified.
         let resumption_psk = group.group_epoch_secrets().resumption_psk();
         group
@@ -93,15 +111,69 @@ impl MlsGroup {
             own_leaf_nodes: vec![],
             aad: vec![],
             group_state: MlsGroupState::Operational,
-            state_changed: InnerState::Changed,
+            departure: None,
+            receiver_state: Mutex::default(),
+            message_buffer: Mutex::default(),
+            processed_buffered_messages: vec![],
+            own_messages: None,
+            lost_commit: None,
+            credential_validator: None,
+            commit_validator: None,
+            observer: None,
+            psk_resolver: None,
+            event_log: VecDeque::new(),
+            event_timestamp: None,
+            exporter_cache: Mutex::default(),
+            store_lock: Mutex::new(()),
         };
+        mls_group
+            .store(backend)
+            .map_err(NewGroupError::StorageError)?;
 
         Ok(mls_group)
     }
 
+    /// Creates a new group (with a random group ID) with the creator and the
+    /// owner of the `peer_key_package` as its only members, e.g. for a 1:1
+    /// conversation.
+    ///
+    /// This is a convenience wrapper around [`MlsGroup::new()`],
+    /// [`MlsGroup::add_members()`] and [`MlsGroup::merge_pending_commit()`]
+    /// and does the same work as calling them one after the other. The commit
+    /// that adds the peer is merged right away, since there are no other
+    /// members that have to process it. Returns the group and the
+    /// [`Welcome`] for the peer. If the `mls_group_config` doesn't use the
+    /// ratchet tree extension, the peer additionally needs the tree from
+    /// [`MlsGroup::export_ratchet_tree()`].
+    pub fn new_direct<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        mls_group_config: &MlsGroupConfig,
+        credential_with_key: CredentialWithKey,
+        peer_key_package: KeyPackage,
+    ) -> Result<(Self, MlsMessageOut), NewDirectGroupError<KeyStore::Error>> {
+        let mut mls_group = Self::new(backend, signer, mls_group_config, credential_with_key)?;
+        let (_commit, welcome, _group_info) =
+            mls_group.add_members(backend, signer, &[peer_key_package])?;
+        mls_group.merge_pending_commit(backend)?;
+        Ok((mls_group, welcome))
+    }
+
     /// Creates a new group from a [`Welcome`] message. Returns an error
     /// ([`WelcomeError::NoMatchingKeyPackage`]) if no [`KeyPackage`]
-    /// can be found.
+    /// can be found and [`WelcomeError::CiphersuitePolicy`] if the
+    /// ciphersuite of the group is not permitted by the
+    /// [`CiphersuitePolicy`](crate::ciphersuite::policy::CiphersuitePolicy)
+    /// of the `mls_group_config`.
+    ///
+    /// The [`KeyPackage`] and its private keys are deleted from the key
+    /// store, unless it is a last resort key package (see
+    /// [`KeyPackageBuilder::mark_as_last_resort()`](crate::key_packages::KeyPackageBuilder::mark_as_last_resort())).
+    /// This is applied in one batch together with writing the new group
+    /// state, so that the key package can be used again if joining fails.
+    ///
+    /// See [`MlsGroup::welcome_processing_builder()`] for more control over
+    /// joining the group.
     // TODO: #1326 This should take an MlsMessage rather than a Welcome message.
     pub fn new_from_welcome<KeyStore: OpenMlsKeyStore>(
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
@@ -109,8 +181,145 @@ impl MlsGroup {
         welcome: Welcome,
         ratchet_tree: Option<RatchetTreeIn>,
     ) -> Result<Self, WelcomeError<KeyStore::Error>> {
-        let resumption_psk_store =
-            ResumptionPskStore::new(mls_group_config.number_of_resumption_psks);
+        let mut builder = Self::welcome_processing_builder(mls_group_config, welcome);
+        if let Some(ratchet_tree) = ratchet_tree {
+            builder = builder.ratchet_tree(ratchet_tree);
+        }
+        builder.build(backend)
+    }
+
+    /// Creates a new group from a [`Welcome`] message like
+    /// [`MlsGroup::new_from_welcome()`], but queries the `psk_resolver` for
+    /// the PSKs of the group that can't be found in the key store.
+    ///
+    /// Returns [`PskError::MissingPsks`](crate::schedule::errors::PskError::MissingPsks)
+    /// with the IDs of the PSKs that can't be resolved either. The
+    /// [`KeyPackage`] is not deleted in that case, so the application can
+    /// fetch the PSKs and try again.
+    ///
+    /// The `psk_resolver` is set as the [`PskResolver`] of the new group (see
+    /// [`MlsGroup::set_psk_resolver()`]).
+    pub fn new_from_welcome_with_psk_resolver<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        mls_group_config: &MlsGroupConfig,
+        welcome: Welcome,
+        ratchet_tree: Option<RatchetTreeIn>,
+        psk_resolver: impl PskResolver + 'static,
+    ) -> Result<Self, WelcomeError<KeyStore::Error>> {
+        let mut builder =
+            Self::welcome_processing_builder(mls_group_config, welcome).psk_resolver(psk_resolver);
+        if let Some(ratchet_tree) = ratchet_tree {
+            builder = builder.ratchet_tree(ratchet_tree);
+        }
+        builder.build(backend)
+    }
+
+    /// Declines a [`Welcome`] message and returns a signed [`WelcomeDecline`]
+    /// notice for the existing members of the group, who can verify it
+    /// through [`MlsGroup::process_welcome_decline()`] and remove the unused
+    /// leaf.
+    ///
+    /// The notice is signed with the `signer`, which must hold the signature
+    /// key of the [`KeyPackage`] the invitation was made for. The key package
+    /// and its private keys are deleted from the key store, unless it is a
+    /// last resort key package.
+    ///
+    /// Returns an error ([`WelcomeError::NoMatchingKeyPackage`]) if no
+    /// [`KeyPackage`] can be found.
+    pub fn decline_welcome<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        welcome: &Welcome,
+    ) -> Result<WelcomeDecline, DeclineWelcomeError<KeyStore::Error>> {
+        let key_package_bundle = Self::key_package_bundle_from_welcome(backend, welcome)?;
+
+        // Decrypt the group info to learn the group ID. Resumption PSKs are
+        // only used when re-initializing or branching a group, which requires
+        // joining it.
+        let verifiable_group_info = CoreGroup::group_info_from_welcome(
+            welcome,
+            &key_package_bundle,
+            backend,
+            &ResumptionPskStore::new(0),
+        )?;
+
+        let key_package = key_package_bundle.key_package();
+        let encryption_key = key_package.leaf_node().encryption_key();
+        let welcome_decline = WelcomeDeclineTbs::new(
+            verifiable_group_info.group_id().clone(),
+            key_package.hash_ref(backend.crypto())?,
+            encryption_key.clone(),
+        )
+        .sign(signer)
+        .map_err(|_| DeclineWelcomeError::SigningError)?;
+
+        // Delete the [`KeyPackage`] and the corresponding private keys from
+        // the key store, unless it's a last resort key package
+        if !key_package.last_resort() {
+            key_package
+                .delete(backend)
+                .map_err(DeclineWelcomeError::KeyStoreError)?;
+            backend
+                .key_store()
+                .remove::<EncryptionKeyPair>(encryption_key.storage_key())
+                .map_err(DeclineWelcomeError::KeyStoreError)?;
+        }
+
+        Ok(welcome_decline)
+    }
+
+    /// Returns the [`CredentialBinding`]s of the members that were added
+    /// with pseudonymous key packages in the `welcome`, without joining the
+    /// group.
+    ///
+    /// This allows the application to check the real credentials of the
+    /// joiners before calling [`MlsGroup::new_from_welcome()`]. The signatures
+    /// of the bindings are verified here. That every binding belongs to a leaf
+    /// of the group is checked when joining the group.
+    ///
+    /// Returns an error ([`WelcomeError::NoMatchingKeyPackage`]) if no
+    /// [`KeyPackage`] can be found and
+    /// [`WelcomeError::InvalidCredentialBinding`] if the signature of a
+    /// binding is invalid.
+    pub fn credential_bindings_from_welcome<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        welcome: &Welcome,
+    ) -> Result<Vec<CredentialBinding>, WelcomeError<KeyStore::Error>> {
+        let key_package_bundle = Self::key_package_bundle_from_welcome(backend, welcome)?;
+
+        // Resumption PSKs are only used when re-initializing or branching a
+        // group, which requires joining it.
+        let verifiable_group_info = CoreGroup::group_info_from_welcome(
+            welcome,
+            &key_package_bundle,
+            backend,
+            &ResumptionPskStore::new(0),
+        )?;
+
+        let bindings = verifiable_group_info
+            .extensions()
+            .credential_bindings()
+            .map(|e| e.bindings().to_vec())
+            .unwrap_or_default();
+        let signature_scheme = welcome.ciphersuite().signature_algorithm();
+        for binding in &bindings {
+            binding
+                .verify(backend.crypto(), signature_scheme)
+                .map_err(|_| WelcomeError::InvalidCredentialBinding)?;
+        }
+
+        Ok(bindings)
+    }
+}
+
+// Private methods of MlsGroup
+impl MlsGroup {
+    /// Reads the [`KeyPackageBundle`] the `welcome` was created for from the
+    /// key store.
+    pub(crate) fn key_package_bundle_from_welcome<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        welcome: &Welcome,
+    ) -> Result<KeyPackageBundle, WelcomeError<KeyStore::Error>> {
         let (key_package, _) = welcome
             .secrets()
             .iter()
@@ -128,99 +337,9 @@ impl MlsGroup {
             .key_store()
             .read::<HpkePrivateKey>(key_package.hpke_init_key().as_slice())
             .ok_or(WelcomeError::NoMatchingKeyPackage)?;
-        let key_package_bundle = KeyPackageBundle {
+        Ok(KeyPackageBundle {
             key_package,
             private_key,
-        };
-
-        // Delete the [`KeyPackage`] and the corresponding private key from the
-        // key store
-        key_package_bundle
-            .key_package
-            .delete(backend)
-            .map_err(WelcomeError::KeyStoreError)?;
-
-        let mut group = CoreGroup::new_from_welcome(
-            welcome,
-            ratchet_tree,
-            key_package_bundle,
-            backend,
-            resumption_psk_store,
-        )?;
-        group.set_max_past_epochs(mls_group_config.max_past_epochs);
-
-        let mls_group = MlsGroup {
-            mls_group_config: mls_group_config.clone(),
-            group,
-            proposal_store: ProposalStore::new(),
-            own_leaf_nodes: vec![],
-            aad: vec![],
-            group_state: MlsGroupState::Operational,
-            state_changed: InnerState::Changed,
-        };
-
-        Ok(mls_group)
-    }
-
-    /// Join an existing group through an External Commit.
-    /// The resulting [`MlsGroup`] instance starts off with a pending
-    /// commit (the external commit, which adds this client to the group).
-    /// Merging this commit is necessary for this [`MlsGroup`] instance to
-    /// function properly, as, for example, this client is not yet part of the
-    /// tree. As a result, it is not possible to clear the pending commit. If
-    /// the external commit was rejected due to an epoch change, the
-    /// [`MlsGroup`] instance has to be discarded and a new one has to be
-    /// created using this function based on the latest `ratchet_tree` and
-    /// group info. For more information on the external init process,
-    /// please see Section 11.2.1 in the MLS specification.
-    ///
-    /// Note: If there is a group member in the group with the same identity as us,
-    /// this will create a remove proposal.
-    pub fn join_by_external_commit(
-        backend: &impl OpenMlsCryptoProvider,
-        signer: &impl Signer,
-        ratchet_tree: Option<RatchetTreeIn>,
-        verifiable_group_info: VerifiableGroupInfo,
-        mls_group_config: &MlsGroupConfig,
-        aad: &[u8],
-        credential_with_key: CredentialWithKey,
-    ) -> Result<(Self, MlsMessageOut, Option<GroupInfo>), ExternalCommitError> {
-        // Prepare the commit parameters
-        let framing_parameters = FramingParameters::new(aad, WireFormat::PublicMessage);
-
-        let proposal_store = ProposalStore::new();
-        let params = CreateCommitParams::builder()
-            .framing_parameters(framing_parameters)
-            .proposal_store(&proposal_store)
-            .credential_with_key(credential_with_key)
-            .build();
-        let (mut group, create_commit_result) = CoreGroup::join_by_external_commit(
-            backend,
-            signer,
-            params,
-            ratchet_tree,
-            verifiable_group_info,
-        )?;
-        group.set_max_past_epochs(mls_group_config.max_past_epochs);
-
-        let mls_group = MlsGroup {
-            mls_group_config: mls_group_config.clone(),
-            group,
-            proposal_store: ProposalStore::new(),
-            own_leaf_nodes: vec![],
-            aad: vec![],
-            group_state: MlsGroupState::PendingCommit(Box::new(PendingCommitState::External(
-                create_commit_result.staged_commit,
-            ))),
-            state_changed: InnerState::Changed,
-        };
-
-        let public_message: PublicMessage = create_commit_result.commit.into();
-
-        Ok((
-            mls_group,
-            public_message.into(),
-            create_commit_result.group_info,
-        ))
+        })
     }
 }
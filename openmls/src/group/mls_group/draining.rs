@@ -0,0 +1,132 @@
+//! MLS group draining
+//!
+//! This module contains the processing of application messages of the final
+//! epoch after the own client was removed from the group. See
+//! [`RemovalGrace`].
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::{errors::ProcessMessageError, *};
+use crate::group::core_group::past_secrets::now;
+
+/// The state of the removal grace period of an inactive group.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Drain {
+    // The final epoch of the own client.
+    epoch: GroupEpoch,
+    // The time of the removal, in seconds since the UNIX epoch.
+    started_at: u64,
+    // The number of messages processed since the removal.
+    processed_messages: usize,
+}
+
+impl MlsGroup {
+    /// Returns whether the own client was removed from the group and can
+    /// still decrypt application messages of its final epoch. See
+    /// [`RemovalGrace`].
+    pub fn is_draining(&self) -> bool {
+        self.receiver_state().drain.is_some()
+    }
+
+    /// Ends the removal grace period and deletes the remaining secrets of the
+    /// final epoch. Afterwards, no messages can be processed anymore.
+    ///
+    /// This does nothing if the group is not in the removal grace period.
+    ///
+    /// The group state is written to the storage of the `backend`.
+    pub fn end_removal_grace<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<(), KeyStore::Error> {
+        if self.receiver_state_mut().drain.take().is_none() {
+            return Ok(());
+        }
+        self.group.message_secrets_store_mut().shred();
+        self.store(backend)
+    }
+
+    /// Keeps the message secrets of the `final_epoch` with its `members` if
+    /// the [`RemovalGrace`] of the configuration is enabled, and deletes all
+    /// message secrets otherwise. Must be called after merging the commit
+    /// that removed the own client.
+    pub(super) fn mark_inactive(&mut self, final_epoch: GroupEpoch, members: Vec<Member>) {
+        let message_secrets_store = self.group.message_secrets_store_mut();
+        if self.mls_group_config.removal_grace().is_enabled() {
+            message_secrets_store.retain_final_epoch(final_epoch, members);
+            self.receiver_state_mut().drain = Some(Drain {
+                epoch: final_epoch,
+                started_at: now(),
+                processed_messages: 0,
+            });
+        } else {
+            message_secrets_store.shred();
+        }
+    }
+
+    /// Processes an application message of the final epoch after the own
+    /// client was removed from the group. All other messages are rejected.
+    /// The remaining secrets are deleted once the removal grace period ends.
+    pub(super) fn process_message_after_removal<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        message: ProtocolMessage,
+    ) -> Result<ProcessedMessage, ProcessMessageError<KeyStore::Error>> {
+        let result = self.process_application_message(backend, message);
+
+        // Delete the secrets right away once the last message was processed
+        let expired = self.removal_grace_expired(self.receiver_state().drain.as_ref());
+        if expired {
+            self.end_removal_grace(backend)
+                .map_err(ProcessMessageError::StorageError)?;
+        }
+
+        result
+    }
+
+    /// Counts the `message` towards the [`RemovalGrace`] if it is an
+    /// application message of the final epoch and the removal grace period
+    /// didn't end yet.
+    ///
+    /// Returns [`MlsGroupStateError::UseAfterEviction`] otherwise.
+    pub(super) fn count_message_after_removal<KeyStoreError>(
+        &self,
+        message: &ProtocolMessage,
+    ) -> Result<(), ProcessMessageError<KeyStoreError>> {
+        let mut receiver_state = self.receiver_state();
+        if self.removal_grace_expired(receiver_state.drain.as_ref()) {
+            return Err(MlsGroupStateError::UseAfterEviction.into());
+        }
+        let Some(drain) = receiver_state.drain.as_mut() else {
+            return Err(MlsGroupStateError::UseAfterEviction.into());
+        };
+        if message.content_type() != ContentType::Application || message.epoch() != drain.epoch {
+            return Err(MlsGroupStateError::UseAfterEviction.into());
+        }
+        drain.processed_messages += 1;
+        Ok(())
+    }
+
+    /// Returns whether one of the bounds of the [`RemovalGrace`] was reached
+    /// for the `drain` of the group.
+    fn removal_grace_expired(&self, drain: Option<&Drain>) -> bool {
+        let Some(drain) = drain else {
+            return true;
+        };
+        let removal_grace = self.mls_group_config.removal_grace();
+        drain.processed_messages >= removal_grace.max_messages()
+            || matches!(removal_grace.max_age(), Some(max_age)
+                if Duration::from_secs(now().saturating_sub(drain.started_at)) >= max_age)
+    }
+}
+
+impl Drain {
+    /// Keeps the larger number of processed messages of this removal grace
+    /// period and the `stored` one, if both are the same period.
+    pub(super) fn merge(&mut self, stored: &Drain) {
+        if self.epoch == stored.epoch && self.started_at == stored.started_at {
+            self.processed_messages = self.processed_messages.max(stored.processed_messages);
+        }
+    }
+}
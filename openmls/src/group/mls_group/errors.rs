@@ -5,16 +5,27 @@
 
 // These errors are exposed through `crate::group::errors`.
 
+use std::convert::Infallible;
+
 use thiserror::Error;
 
 use crate::{
+    binary_tree::LeafNodeIndex,
+    ciphersuite::{hash_ref::ProposalRef, policy::CiphersuitePolicyError},
+    credentials::CredentialRejection,
     error::LibraryError,
     extensions::errors::InvalidExtensionError,
-    group::errors::{
-        CreateAddProposalError, CreateCommitError, MergeCommitError, StageCommitError,
-        ValidationError,
+    framing::WireFormat,
+    group::{
+        commit_validator::CommitRejection,
+        errors::{
+            CreateAddProposalError, CreateCommitError, CreateGroupContextExtProposalError,
+            ExternalCommitError, MergeCommitError, StageCommitError, ValidationError, WelcomeError,
+        },
+        GroupEpoch, GroupId, ProposalConflict,
     },
     schedule::errors::PskError,
+    tree::secret_tree::SecretTreeError,
     treesync::errors::{LeafNodeValidationError, PublicTreeError},
 };
 
@@ -30,6 +41,9 @@ pub enum NewGroupError<KeyStoreError> {
     /// Error accessing the key store.
     #[error("Error accessing the key store.")]
     KeyStoreError(KeyStoreError),
+    /// The ciphersuite in the configuration is not supported by the crypto provider.
+    #[error("The ciphersuite in the configuration is not supported by the crypto provider.")]
+    UnsupportedCiphersuite,
     /// Unsupported proposal type in required capabilities.
     #[error("Unsupported proposal type in required capabilities.")]
     UnsupportedProposalType,
@@ -39,6 +53,27 @@ pub enum NewGroupError<KeyStoreError> {
     /// Invalid extensions set in configuration
     #[error("Invalid extensions set in configuration")]
     InvalidExtensions(InvalidExtensionError),
+    /// Error writing the group state to the storage.
+    #[error("Error writing the group state to the storage.")]
+    StorageError(KeyStoreError),
+    /// The ciphersuite in the configuration is not permitted by its
+    /// [`CiphersuitePolicy`](crate::ciphersuite::policy::CiphersuitePolicy).
+    #[error(transparent)]
+    CiphersuitePolicy(#[from] CiphersuitePolicyError),
+}
+
+/// New direct group error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum NewDirectGroupError<KeyStoreError> {
+    /// See [`NewGroupError`] for more details.
+    #[error(transparent)]
+    NewGroupError(#[from] NewGroupError<KeyStoreError>),
+    /// See [`AddMembersError`] for more details.
+    #[error(transparent)]
+    AddMembersError(#[from] AddMembersError<KeyStoreError>),
+    /// See [`MergePendingCommitError`] for more details.
+    #[error(transparent)]
+    MergePendingCommitError(#[from] MergePendingCommitError<KeyStoreError>),
 }
 
 /// EmptyInput error
@@ -67,6 +102,9 @@ pub enum MlsGroupStateError {
     /// Can't execute operation because a pending commit exists.
     #[error("Can't execute operation because a pending commit exists.")]
     PendingCommit,
+    /// Can't execute operation because the client is leaving the group.
+    #[error("Can't execute operation because the client is leaving the group.")]
+    PendingLeave,
     /// Can't execute operation because there is no pending commit.
     #[error("Can't execute operation because there is no pending commit")]
     NoPendingCommit,
@@ -86,9 +124,80 @@ pub enum MergePendingCommitError<KeyStoreError> {
     MergeCommitError(#[from] MergeCommitError<KeyStoreError>),
 }
 
+/// Remove proposal error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum RemoveProposalError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// Error writing the group state to the storage.
+    #[error("Error writing the group state to the storage.")]
+    StorageError(KeyStoreError),
+}
+
+/// Rejoin error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum RejoinError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// The group info or the pending re-join belong to a different group.
+    #[error("The group info or the pending re-join belong to a different group.")]
+    GroupIdMismatch,
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// See [`ExternalCommitError`] for more details.
+    #[error(transparent)]
+    ExternalCommitError(#[from] ExternalCommitError),
+    /// See [`MergePendingCommitError`] for more details.
+    #[error(transparent)]
+    MergePendingCommitError(#[from] MergePendingCommitError<KeyStoreError>),
+    /// Error writing the group state to the storage.
+    #[error("Error writing the group state to the storage.")]
+    StorageError(KeyStoreError),
+}
+
+/// Decline welcome error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum DeclineWelcomeError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`WelcomeError`] for more details.
+    #[error(transparent)]
+    WelcomeError(#[from] WelcomeError<KeyStoreError>),
+    /// Error signing the decline notice.
+    #[error("Error signing the decline notice.")]
+    SigningError,
+    /// Error deleting the declined key package from the key store.
+    #[error("Error deleting the declined key package from the key store.")]
+    KeyStoreError(KeyStoreError),
+}
+
+/// Process welcome decline error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum ProcessWelcomeDeclineError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// The decline notice is for a different group.
+    #[error("The decline notice is for a different group.")]
+    WrongGroupId,
+    /// No member of the group has the encryption key of the decline notice.
+    #[error("No member of the group has the encryption key of the decline notice.")]
+    UnknownLeaf,
+    /// The signature of the decline notice is invalid.
+    #[error("The signature of the decline notice is invalid.")]
+    InvalidSignature,
+}
+
 /// Process message error
 #[derive(Error, Debug, PartialEq, Clone)]
-pub enum ProcessMessageError {
+pub enum ProcessMessageError<KeyStoreError = Infallible> {
     /// See [`LibraryError`] for more details.
     #[error(transparent)]
     LibraryError(#[from] LibraryError),
@@ -113,17 +222,193 @@ pub enum ProcessMessageError {
     /// The proposal is invalid for the Sender of type [External](crate::prelude::Sender::External)
     #[error("The proposal is invalid for the Sender of type External")]
     UnsupportedProposalType,
+    /// The message's sequence number is not the expected one.
+    #[error("The message's sequence number is {got}, but {expected} was expected.")]
+    OutOfOrder {
+        /// The expected sequence number.
+        expected: u64,
+        /// The sequence number of the message.
+        got: u64,
+    },
+    /// The message is from a future epoch and was buffered.
+    #[error("The message is from a future epoch and was buffered.")]
+    MessageBuffered,
+    /// The message is from a future epoch, but the message buffer is full.
+    #[error("The message is from a future epoch, but the message buffer is full.")]
+    MessageBufferFull,
+    /// The validation of the message exceeded its
+    /// [`ValidationBudget`](crate::group::ValidationBudget).
+    #[error("The validation of the message exceeded its budget.")]
+    BudgetExceeded,
+    /// The [`CredentialValidator`](crate::credentials::CredentialValidator) of
+    /// the group rejected the credential of a leaf node that the message adds
+    /// to the tree.
+    #[error("The credential of the leaf node at index {leaf_index} was rejected: {rejection}")]
+    CredentialRejected {
+        /// The index of the leaf node in the tree.
+        leaf_index: LeafNodeIndex,
+        /// The reason of the rejection.
+        rejection: CredentialRejection,
+    },
+    /// The [`CommitValidator`](crate::group::CommitValidator) of the group
+    /// rejected the commit.
+    #[error("The commit was rejected: {0}")]
+    CommitRejected(CommitRejection),
+    /// The message is a commit for the previous epoch that differs from the
+    /// commit merged by this group. This is only a fork if the sender merged
+    /// it, which comparing epoch authenticators reveals. See
+    /// [`MlsGroup::check_epoch_authenticator()`](super::MlsGroup::check_epoch_authenticator()).
+    #[error("A different commit was sent in epoch {epoch}.")]
+    CompetingCommit {
+        /// The epoch of the competing commits.
+        epoch: GroupEpoch,
+    },
+    /// The message is the pending commit of the own client, which the DS
+    /// echoed back. It can be merged with
+    /// [`MlsGroup::merge_pending_commit()`](super::MlsGroup::merge_pending_commit()).
+    #[error("The message is the pending commit of the own client.")]
+    OwnCommit,
+    /// The message is a commit of the own client for the current epoch that
+    /// differs from the pending commit, e.g. because the pending commit was
+    /// cleared and replaced after the DS accepted the previous one. The own
+    /// client has to re-join the group.
+    #[error("The message is a commit of the own client that is not the pending commit.")]
+    UnknownOwnCommit,
+    /// The message is a proposal of the own client, which is already in the
+    /// proposal store.
+    #[error("The message is a proposal of the own client.")]
+    OwnProposal,
+    /// Error writing the group state to the storage.
+    #[error("Error writing the group state to the storage.")]
+    StorageError(KeyStoreError),
+    /// Only application messages can be processed through a shared reference.
+    #[error("Only application messages can be processed through a shared reference.")]
+    NotAnApplicationMessage,
+}
+
+impl<KeyStoreError> ProcessMessageError<KeyStoreError> {
+    /// Classifies the error, so that the application can decide how to handle
+    /// the message, see [`ProcessMessageErrorKind`].
+    pub fn kind(&self) -> ProcessMessageErrorKind {
+        match self {
+            ProcessMessageError::ValidationError(ValidationError::WrongEpoch {
+                received,
+                expected,
+            }) => ProcessMessageErrorKind::WrongEpoch {
+                received: *received,
+                expected: *expected,
+            },
+            ProcessMessageError::InvalidCommit(StageCommitError::MissingProposal) => {
+                ProcessMessageErrorKind::StaleProposal
+            }
+            ProcessMessageError::GroupStateError(MlsGroupStateError::UseAfterEviction) => {
+                ProcessMessageErrorKind::UseAfterEviction
+            }
+            _ => ProcessMessageErrorKind::Other,
+        }
+    }
+}
+
+/// The category of a [`ProcessMessageError`], see
+/// [`ProcessMessageError::kind()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessMessageErrorKind {
+    /// The message is not for the current epoch of the group. Messages of a
+    /// future epoch can be buffered until the group reaches it. Handshake
+    /// messages of a past epoch are stale, e.g. a commit that lost the race
+    /// for its epoch against another commit, and can be dropped. If it was
+    /// the own commit, its changes can be proposed or committed again in the
+    /// current epoch.
+    WrongEpoch {
+        /// The epoch of the message.
+        received: GroupEpoch,
+        /// The epoch of the group.
+        expected: GroupEpoch,
+    },
+    /// The commit covers a proposal that is not in the proposal store, e.g.
+    /// because the proposal was sent in an earlier epoch or was not processed
+    /// yet. The commit can be processed again once the proposal was.
+    StaleProposal,
+    /// The own client is no longer a member of the group, so the message can
+    /// be dropped.
+    UseAfterEviction,
+    /// The message can't be processed for another reason, e.g. because it is
+    /// invalid.
+    Other,
+}
+
+impl ProcessMessageError {
+    /// Converts an error of an operation that doesn't write to the storage
+    /// into the error of an [`MlsGroup`](super::MlsGroup) operation that does.
+    pub(crate) fn with_storage_error<KeyStoreError>(self) -> ProcessMessageError<KeyStoreError> {
+        match self {
+            ProcessMessageError::LibraryError(e) => ProcessMessageError::LibraryError(e),
+            ProcessMessageError::IncompatibleWireFormat => {
+                ProcessMessageError::IncompatibleWireFormat
+            }
+            ProcessMessageError::ValidationError(e) => ProcessMessageError::ValidationError(e),
+            ProcessMessageError::GroupStateError(e) => ProcessMessageError::GroupStateError(e),
+            ProcessMessageError::InvalidSignature => ProcessMessageError::InvalidSignature,
+            ProcessMessageError::InvalidCommit(e) => ProcessMessageError::InvalidCommit(e),
+            ProcessMessageError::UnauthorizedExternalApplicationMessage => {
+                ProcessMessageError::UnauthorizedExternalApplicationMessage
+            }
+            ProcessMessageError::UnsupportedProposalType => {
+                ProcessMessageError::UnsupportedProposalType
+            }
+            ProcessMessageError::OutOfOrder { expected, got } => {
+                ProcessMessageError::OutOfOrder { expected, got }
+            }
+            ProcessMessageError::MessageBuffered => ProcessMessageError::MessageBuffered,
+            ProcessMessageError::MessageBufferFull => ProcessMessageError::MessageBufferFull,
+            ProcessMessageError::BudgetExceeded => ProcessMessageError::BudgetExceeded,
+            ProcessMessageError::CredentialRejected {
+                leaf_index,
+                rejection,
+            } => ProcessMessageError::CredentialRejected {
+                leaf_index,
+                rejection,
+            },
+            ProcessMessageError::CommitRejected(rejection) => {
+                ProcessMessageError::CommitRejected(rejection)
+            }
+            ProcessMessageError::CompetingCommit { epoch } => {
+                ProcessMessageError::CompetingCommit { epoch }
+            }
+            ProcessMessageError::OwnCommit => ProcessMessageError::OwnCommit,
+            ProcessMessageError::UnknownOwnCommit => ProcessMessageError::UnknownOwnCommit,
+            ProcessMessageError::OwnProposal => ProcessMessageError::OwnProposal,
+            ProcessMessageError::StorageError(e) => match e {},
+            ProcessMessageError::NotAnApplicationMessage => {
+                ProcessMessageError::NotAnApplicationMessage
+            }
+        }
+    }
+}
+
+/// Check epoch authenticator error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum CheckEpochAuthenticatorError {
+    /// The epoch authenticator is not from the current epoch of the group.
+    #[error("The epoch authenticator is not from the current epoch of the group.")]
+    WrongEpoch,
+    /// The epoch authenticators differ, i.e. the group forked.
+    #[error("The epoch authenticators differ, i.e. the group forked.")]
+    ForkDetected,
 }
 
 /// Create message error
 #[derive(Error, Debug, PartialEq, Clone)]
-pub enum CreateMessageError {
+pub enum CreateMessageError<KeyStoreError> {
     /// See [`LibraryError`] for more details.
     #[error(transparent)]
     LibraryError(#[from] LibraryError),
     /// See [`MlsGroupStateError`] for more details.
     #[error(transparent)]
     GroupStateError(#[from] MlsGroupStateError),
+    /// Error writing the group state to the storage.
+    #[error("Error writing the group state to the storage.")]
+    StorageError(KeyStoreError),
 }
 
 /// Add members error
@@ -141,11 +426,14 @@ pub enum AddMembersError<KeyStoreError> {
     /// See [`MlsGroupStateError`] for more details.
     #[error(transparent)]
     GroupStateError(#[from] MlsGroupStateError),
+    /// Error writing the group state to the storage.
+    #[error("Error writing the group state to the storage.")]
+    StorageError(KeyStoreError),
 }
 
 /// Propose add members error
 #[derive(Error, Debug, PartialEq, Clone)]
-pub enum ProposeAddMemberError {
+pub enum ProposeAddMemberError<KeyStoreError = Infallible> {
     /// See [`LibraryError`] for more details.
     #[error(transparent)]
     LibraryError(#[from] LibraryError),
@@ -158,11 +446,14 @@ pub enum ProposeAddMemberError {
     /// See [`LeafNodeValidationError`] for more details.
     #[error(transparent)]
     LeafNodeValidation(#[from] LeafNodeValidationError),
+    /// Error writing the group state to the storage.
+    #[error("Error writing the group state to the storage.")]
+    StorageError(KeyStoreError),
 }
 
 /// Propose remove members error
 #[derive(Error, Debug, PartialEq, Clone)]
-pub enum ProposeRemoveMemberError {
+pub enum ProposeRemoveMemberError<KeyStoreError = Infallible> {
     /// See [`LibraryError`] for more details.
     #[error(transparent)]
     LibraryError(#[from] LibraryError),
@@ -172,6 +463,9 @@ pub enum ProposeRemoveMemberError {
     /// The member that should be removed can not be found.
     #[error("The member that should be removed can not be found.")]
     UnknownMember,
+    /// Error writing the group state to the storage.
+    #[error("Error writing the group state to the storage.")]
+    StorageError(KeyStoreError),
 }
 
 /// Remove members error
@@ -192,17 +486,23 @@ pub enum RemoveMembersError<KeyStoreError> {
     /// The member that should be removed can not be found.
     #[error("The member that should be removed can not be found.")]
     UnknownMember,
+    /// Error writing the group state to the storage.
+    #[error("Error writing the group state to the storage.")]
+    StorageError(KeyStoreError),
 }
 
 /// Leave group error
 #[derive(Error, Debug, PartialEq, Clone)]
-pub enum LeaveGroupError {
+pub enum LeaveGroupError<KeyStoreError> {
     /// See [`LibraryError`] for more details.
     #[error(transparent)]
     LibraryError(#[from] LibraryError),
     /// See [`MlsGroupStateError`] for more details.
     #[error(transparent)]
     GroupStateError(#[from] MlsGroupStateError),
+    /// Error writing the group state to the storage.
+    #[error("Error writing the group state to the storage.")]
+    StorageError(KeyStoreError),
 }
 
 /// Self update error
@@ -220,6 +520,29 @@ pub enum SelfUpdateError<KeyStoreError> {
     /// Error accessing the key store.
     #[error("Error accessing the key store.")]
     KeyStoreError,
+    /// Error writing the group state to the storage.
+    #[error("Error writing the group state to the storage.")]
+    StorageError(KeyStoreError),
+}
+
+/// Update group context extensions error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum UpdateGroupContextExtensionsError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`CreateCommitError`] for more details.
+    #[error(transparent)]
+    CreateCommitError(#[from] CreateCommitError<KeyStoreError>),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// See [`CreateGroupContextExtProposalError`] for more details.
+    #[error(transparent)]
+    CreateGroupContextExtProposalError(#[from] CreateGroupContextExtProposalError),
+    /// Error writing the group state to the storage.
+    #[error("Error writing the group state to the storage.")]
+    StorageError(KeyStoreError),
 }
 
 /// Propose self update error
@@ -238,6 +561,12 @@ pub enum ProposeSelfUpdateError<KeyStoreError> {
     /// See [`PublicTreeError`] for more details.
     #[error(transparent)]
     PublicTreeError(#[from] PublicTreeError),
+    /// The signature scheme of the new signer doesn't match the ciphersuite.
+    #[error("The signature scheme of the new signer doesn't match the ciphersuite.")]
+    CiphersuiteSignatureSchemeMismatch,
+    /// Error writing the group state to the storage.
+    #[error("Error writing the group state to the storage.")]
+    StorageError(KeyStoreError),
 }
 
 /// Commit to pending proposals error
@@ -252,6 +581,69 @@ pub enum CommitToPendingProposalsError<KeyStoreError> {
     /// See [`MlsGroupStateError`] for more details.
     #[error(transparent)]
     GroupStateError(#[from] MlsGroupStateError),
+    /// Error writing the group state to the storage.
+    #[error("Error writing the group state to the storage.")]
+    StorageError(KeyStoreError),
+}
+
+/// Commit builder error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum CommitBuilderError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`CreateCommitError`] for more details.
+    #[error(transparent)]
+    CreateCommitError(#[from] CreateCommitError<KeyStoreError>),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// A requested proposal was not found in the proposal store.
+    #[error("A requested proposal was not found in the proposal store.")]
+    ProposalNotFound(ProposalRef),
+    /// Two of the requested proposals conflict with each other.
+    #[error("Two of the requested proposals conflict with each other.")]
+    ConflictingProposals(ProposalConflict),
+    /// The requested wire format is not accepted by the wire format policy.
+    #[error("The requested wire format is not accepted by the wire format policy.")]
+    IncompatibleWireFormat,
+    /// Error writing the group state to the storage.
+    #[error("Error writing the group state to the storage.")]
+    StorageError(KeyStoreError),
+}
+
+/// Rebase pending commit error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum RebasePendingCommitError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// No pending commit was discarded that could be rebased.
+    #[error("No pending commit was discarded that could be rebased.")]
+    NoLostCommit,
+    /// See [`CommitBuilderError`] for more details.
+    #[error(transparent)]
+    CommitBuilderError(#[from] CommitBuilderError<KeyStoreError>),
+}
+
+/// Client process message error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum ClientProcessMessageError<KeyStoreError> {
+    /// The client is not in the group of the message.
+    #[error("The client is not in the group {0:?}.")]
+    UnknownGroup(GroupId),
+    /// Messages with this wire format are not processed by a client.
+    #[error("Messages with the wire format {0:?} are not processed by a client.")]
+    UnsupportedWireFormat(WireFormat),
+    /// See [`WelcomeError`] for more details.
+    #[error(transparent)]
+    WelcomeError(#[from] WelcomeError<KeyStoreError>),
+    /// See [`ProcessMessageError`] for more details.
+    #[error(transparent)]
+    ProcessMessageError(#[from] ProcessMessageError<KeyStoreError>),
 }
 
 /// Errors that can happen when exporting a group info object.
@@ -274,9 +666,167 @@ pub enum ExportSecretError {
     /// The requested key length is too long.
     #[error("The requested key length is too long.")]
     KeyLengthTooLong,
+    /// The length of the exporter label doesn't match the requested length.
+    #[error("The length of the exporter label doesn't match the requested length.")]
+    KeyLengthMismatch,
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+}
+
+/// Exporter label error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum ExporterLabelError {
+    /// The namespace is empty.
+    #[error("The namespace is empty.")]
+    EmptyNamespace,
+    /// The namespace contains characters other than ASCII letters, digits, `-`, `_` and `.`.
+    #[error(
+        "The namespace contains characters other than ASCII letters, digits, `-`, `_` and `.`."
+    )]
+    InvalidNamespace,
+    /// The namespace is reserved for OpenMLS.
+    #[error("The namespace is reserved for OpenMLS.")]
+    ReservedNamespace,
+    /// The namespace was already registered.
+    #[error("The namespace was already registered.")]
+    DuplicateNamespace,
+}
+
+/// Join confirmation error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum JoinConfirmationError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// There is no member at the given leaf index.
+    #[error("There is no member at the given leaf index.")]
+    UnknownMember,
+    /// The join confirmation is not valid.
+    #[error("The join confirmation is not valid.")]
+    InvalidConfirmation,
+}
+
+/// Pairwise secret error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum PairwiseSecretError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// There is no other member at the given leaf index.
+    #[error("There is no other member at the given leaf index.")]
+    UnknownMember,
+    /// The requested key length is too long.
+    #[error("The requested key length is too long.")]
+    KeyLengthTooLong,
+}
+
+/// Backup key error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum BackupKeyError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
     /// See [`MlsGroupStateError`] for more details.
     #[error(transparent)]
     GroupStateError(#[from] MlsGroupStateError),
+    /// The ratchet reached the last generation.
+    #[error("The ratchet reached the last generation.")]
+    GenerationExhausted,
+    /// The ratchet moved past the requested generation.
+    #[error("The ratchet moved past the requested generation.")]
+    GenerationInThePast,
+    /// The requested generation is too far ahead of the ratchet.
+    #[error("The requested generation is too far ahead of the ratchet.")]
+    TooDistantInTheFuture,
+}
+
+/// Auditor tag error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum AuditorTagError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// The group doesn't use auditor tags.
+    #[error("The group doesn't use auditor tags.")]
+    Disabled,
+    /// The message could not be deserialized.
+    #[error("The message could not be deserialized.")]
+    MalformedMessage,
+    /// The message is not a PublicMessage.
+    #[error("The message is not a PublicMessage.")]
+    NotAPublicMessage,
+    /// The message is for a different group.
+    #[error("The message is for a different group.")]
+    WrongGroupId,
+    /// The message is from a different epoch.
+    #[error("The message is from a different epoch.")]
+    WrongEpoch,
+    /// The auditor tag is not valid.
+    #[error("The auditor tag is not valid.")]
+    InvalidTag,
+}
+
+/// Stream error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum StreamError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`MlsGroupStateError`] for more details.
+    #[error(transparent)]
+    GroupStateError(#[from] MlsGroupStateError),
+    /// See [`SecretTreeError`] for more details.
+    #[error(transparent)]
+    SecretTreeError(#[from] SecretTreeError),
+    /// See [`StreamChunkError`] for more details.
+    #[error(transparent)]
+    StreamChunkError(#[from] StreamChunkError),
+    /// The stream is for a different group.
+    #[error("The stream is for a different group.")]
+    WrongGroupId,
+    /// The stream is from a future epoch.
+    #[error("The stream is from a future epoch.")]
+    WrongEpoch,
+    /// The stream was created by the own client.
+    #[error("The stream was created by the own client.")]
+    InvalidSender,
+    /// Error writing the group state to the storage.
+    #[error("Error writing the group state to the storage.")]
+    StorageError(KeyStoreError),
+}
+
+/// Stream chunk error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum StreamChunkError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// The last chunk of the stream was already processed.
+    #[error("The last chunk of the stream was already processed.")]
+    Finished,
+    /// The stream has reached the maximum number of chunks.
+    #[error("The stream has reached the maximum number of chunks.")]
+    TooManyChunks,
+    /// The chunk could not be deserialized.
+    #[error("The chunk could not be deserialized.")]
+    MalformedChunk,
+    /// The chunk could not be decrypted, because it was modified, is out of
+    /// order or belongs to a different stream.
+    #[error("The chunk could not be decrypted.")]
+    InvalidChunk,
+    /// The stream ended before its last chunk.
+    #[error("The stream ended before its last chunk.")]
+    Truncated,
 }
 
 /// Propose PSK error
@@ -301,7 +851,7 @@ pub enum ProposalError<KeyStoreError> {
     LibraryError(#[from] LibraryError),
     /// See [`ProposeAddMemberError`] for more details.
     #[error(transparent)]
-    ProposeAddMemberError(#[from] ProposeAddMemberError),
+    ProposeAddMemberError(#[from] ProposeAddMemberError<KeyStoreError>),
     /// See [`CreateAddProposalError`] for more details.
     #[error(transparent)]
     CreateAddProposalError(#[from] CreateAddProposalError),
@@ -310,11 +860,74 @@ pub enum ProposalError<KeyStoreError> {
     ProposeSelfUpdateError(#[from] ProposeSelfUpdateError<KeyStoreError>),
     /// See [`ProposeRemoveMemberError`] for more details.
     #[error(transparent)]
-    ProposeRemoveMemberError(#[from] ProposeRemoveMemberError),
+    ProposeRemoveMemberError(#[from] ProposeRemoveMemberError<KeyStoreError>),
     /// See [`MlsGroupStateError`] for more details.
     #[error(transparent)]
     GroupStateError(#[from] MlsGroupStateError),
     /// See [`ValidationError`] for more details.
     #[error(transparent)]
     ValidationError(#[from] ValidationError),
+    /// See [`CreateGroupContextExtProposalError`] for more details.
+    #[error(transparent)]
+    CreateGroupContextExtProposalError(#[from] CreateGroupContextExtProposalError),
+    /// The sender index is not part of the external senders extension.
+    #[error("The sender index is not part of the external senders extension.")]
+    UnknownExternalSender,
+    /// Error writing the group state to the storage.
+    #[error("Error writing the group state to the storage.")]
+    StorageError(KeyStoreError),
+}
+
+/// Speculation error
+#[cfg(feature = "speculation")]
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum SpeculationError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// Error writing the speculative changes to the key store.
+    #[error("Error writing the speculative changes to the key store.")]
+    KeyStoreError(KeyStoreError),
+}
+
+/// SFrame key error
+#[cfg(feature = "sframe")]
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum SFrameError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// The number of KID bits or retained epochs is invalid.
+    #[error("The number of KID bits or retained epochs is invalid.")]
+    InvalidConfiguration,
+    /// See [`ExportSecretError`] for more details.
+    #[error(transparent)]
+    ExportSecretError(#[from] ExportSecretError),
+    /// The leaf index doesn't fit into the KID.
+    #[error("The leaf index doesn't fit into the KID.")]
+    IndexTooLarge,
+    /// The context ID doesn't fit into the KID.
+    #[error("The context ID doesn't fit into the KID.")]
+    ContextIdTooLarge,
+    /// The keys of the epoch are not available.
+    #[error("The keys of the epoch are not available.")]
+    UnknownEpoch,
+    /// The sender is not a member of the group in the epoch.
+    #[error("The sender is not a member of the group in the epoch.")]
+    UnknownSender,
+}
+
+/// Async operation error
+#[cfg(feature = "async")]
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum AsyncOperationError<OperationError, KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// The operation failed.
+    #[error(transparent)]
+    OperationError(OperationError),
+    /// Error writing the changes of the operation to the key store.
+    #[error("Error writing the changes of the operation to the key store.")]
+    KeyStoreError(KeyStoreError),
 }
@@ -0,0 +1,255 @@
+//! # Event log
+//!
+//! An [`MlsGroup`] can keep a log of the operations of the group, so that
+//! applications can render the history of a group, e.g. "Alice added Bob",
+//! without interpreting commits themselves. The log is disabled by default
+//! and enabled by setting the `event_log_capacity` of the
+//! [`MlsGroupConfig`]. It is part of the stored group state.
+//!
+//! A [`GroupEvent`] is logged
+//!  - for every proposal that is processed with
+//!    [`MlsGroup::process_message()`], and
+//!  - for every merged commit, both for the commit itself and for each of the
+//!    proposals it covers, in the order of the commit.
+//!
+//! The actor and the targets of an event are resolved when the event is
+//! logged, i.e. the credentials of removed members are still known.
+//! Timestamps are not known to the group and have to be supplied by the
+//! application with [`MlsGroup::set_event_timestamp()`], e.g. the time at which
+//! the Delivery Service received the message that is processed next.
+
+use std::ops::RangeBounds;
+
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// The kind of a [`GroupEvent`].
+///
+///  - `Proposed`:
+/// A proposal of the given type was processed. It only takes effect once it
+/// is committed.
+///  - `Committed`:
+/// A proposal of the given type was covered by a merged commit.
+///  - `Commit`:
+/// A commit was merged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GroupEventKind {
+    /// A proposal was processed.
+    Proposed(ProposalType),
+    /// A proposal was committed.
+    Committed(ProposalType),
+    /// A commit was merged.
+    Commit,
+}
+
+/// A record of an operation of the group. See the
+/// [module documentation](self).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupEvent {
+    epoch: GroupEpoch,
+    kind: GroupEventKind,
+    actor: Sender,
+    actor_credential: Option<Credential>,
+    targets: Vec<Credential>,
+    timestamp: Option<u64>,
+}
+
+impl GroupEvent {
+    /// Returns the epoch in which the proposal or the commit was sent. The
+    /// changes of a commit take effect in the following epoch.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the kind of the event.
+    pub fn kind(&self) -> GroupEventKind {
+        self.kind
+    }
+
+    /// Returns the sender of the proposal or the commit.
+    pub fn actor(&self) -> &Sender {
+        &self.actor
+    }
+
+    /// Returns the credential of the sender of the proposal or the commit,
+    /// if it is known.
+    pub fn actor_credential(&self) -> Option<&Credential> {
+        self.actor_credential.as_ref()
+    }
+
+    /// Returns the credentials of the members that the operation applies to,
+    /// i.e. the added member of an Add proposal and the removed member of a
+    /// Remove proposal. The targets of all other events are empty.
+    pub fn targets(&self) -> &[Credential] {
+        &self.targets
+    }
+
+    /// Returns the timestamp that was set with
+    /// [`MlsGroup::set_event_timestamp()`] when the event was logged.
+    pub fn timestamp(&self) -> Option<u64> {
+        self.timestamp
+    }
+}
+
+impl MlsGroup {
+    /// Returns the logged events of the epochs in the given range, oldest
+    /// first. See the [module documentation](self) for which events are
+    /// logged.
+    pub fn event_log(
+        &self,
+        epochs: impl RangeBounds<GroupEpoch>,
+    ) -> impl Iterator<Item = &GroupEvent> {
+        self.event_log
+            .iter()
+            .filter(move |event| epochs.contains(&event.epoch))
+    }
+
+    /// Sets the timestamp of the events that are logged from now on, e.g. the
+    /// time at which the Delivery Service received the message that is
+    /// processed next. The unit is up to the application.
+    ///
+    /// The timestamp is not part of the stored group state.
+    pub fn set_event_timestamp(&mut self, timestamp: Option<u64>) {
+        self.event_timestamp = timestamp;
+    }
+
+    /// Deletes all logged events and writes the group state to the storage of
+    /// the `backend`.
+    pub fn clear_event_log<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<(), KeyStore::Error> {
+        self.event_log.clear();
+        self.store(backend)
+    }
+
+    /// Logs the proposal of a processed message, if any.
+    pub(super) fn log_processed_message(&mut self, processed_message: &ProcessedMessage) {
+        if self.mls_group_config.event_log_capacity() == 0 {
+            return;
+        }
+        let (ProcessedMessageContent::ProposalMessage(queued_proposal)
+        | ProcessedMessageContent::ExternalJoinProposalMessage(queued_proposal)) =
+            processed_message.content()
+        else {
+            return;
+        };
+        let event = self.proposal_event(
+            queued_proposal,
+            GroupEventKind::Proposed(queued_proposal.proposal().proposal_type()),
+        );
+        self.push_events(vec![event]);
+    }
+
+    /// Returns the events of a staged commit. Must be called before the
+    /// commit is merged, so that the members it removes can be resolved.
+    pub(super) fn staged_commit_events(&self, staged_commit: &StagedCommit) -> Vec<GroupEvent> {
+        if self.mls_group_config.event_log_capacity() == 0 {
+            return vec![];
+        }
+
+        let is_external_commit = staged_commit.queued_proposals().any(|queued_proposal| {
+            queued_proposal
+                .proposal()
+                .is_type(ProposalType::ExternalInit)
+        });
+        let committer = staged_commit.committer();
+        let committer_credential = committer.and_then(|committer| {
+            staged_commit
+                .new_leaf_nodes()
+                .find(|(leaf_index, _)| *leaf_index == committer)
+                .map(|(_, leaf_node)| leaf_node)
+                .or_else(|| {
+                    self.group
+                        .public_group()
+                        .leaf(committer)
+                        .filter(|_| !is_external_commit)
+                })
+                .map(|leaf_node| leaf_node.credential().clone())
+        });
+        let actor = match committer {
+            _ if is_external_commit => Sender::NewMemberCommit,
+            Some(committer) => Sender::Member(committer),
+            // Staged commits stored by earlier versions are always own
+            // commits.
+            None => Sender::Member(self.own_leaf_index()),
+        };
+
+        let mut events = vec![GroupEvent {
+            epoch: self.epoch(),
+            kind: GroupEventKind::Commit,
+            actor,
+            actor_credential: committer_credential.clone(),
+            targets: vec![],
+            timestamp: self.event_timestamp,
+        }];
+        for queued_proposal in staged_commit.queued_proposals() {
+            let mut event = self.proposal_event(
+                queued_proposal,
+                GroupEventKind::Committed(queued_proposal.proposal().proposal_type()),
+            );
+            // Inline proposals of external commits are sent by the joiner.
+            if matches!(queued_proposal.sender(), Sender::NewMemberCommit) {
+                event.actor_credential = committer_credential.clone();
+            }
+            events.push(event);
+        }
+        events
+    }
+
+    /// Appends events to the log and drops the oldest events beyond the
+    /// capacity of the configuration.
+    pub(super) fn push_events(&mut self, events: Vec<GroupEvent>) {
+        let capacity = self.mls_group_config.event_log_capacity();
+        if capacity == 0 {
+            return;
+        }
+        self.event_log.extend(events);
+        self.truncate_event_log();
+    }
+
+    /// Drops the oldest events beyond the capacity of the configuration.
+    pub(super) fn truncate_event_log(&mut self) {
+        let capacity = self.mls_group_config.event_log_capacity();
+        while self.event_log.len() > capacity {
+            self.event_log.pop_front();
+        }
+    }
+
+    fn proposal_event(&self, queued_proposal: &QueuedProposal, kind: GroupEventKind) -> GroupEvent {
+        let public_group = self.group.public_group();
+        let member_credential = |leaf_index| {
+            public_group
+                .leaf(leaf_index)
+                .map(|leaf| leaf.credential().clone())
+        };
+        let targets = match queued_proposal.proposal() {
+            Proposal::Add(add_proposal) => {
+                vec![add_proposal.key_package().leaf_node().credential().clone()]
+            }
+            Proposal::Remove(remove_proposal) => member_credential(remove_proposal.removed())
+                .into_iter()
+                .collect(),
+            _ => vec![],
+        };
+        let actor_credential = match queued_proposal.sender() {
+            Sender::Member(leaf_index) => member_credential(*leaf_index),
+            Sender::External(index) => self
+                .external_senders()
+                .and_then(|external_senders| external_senders.get(index.index()))
+                .map(|external_sender| external_sender.credential().clone()),
+            // Joiners send Add proposals for themselves.
+            Sender::NewMemberProposal => targets.first().cloned(),
+            Sender::NewMemberCommit => None,
+        };
+        GroupEvent {
+            epoch: self.epoch(),
+            kind,
+            actor: queued_proposal.sender().clone(),
+            actor_credential,
+            targets,
+            timestamp: self.event_timestamp,
+        }
+    }
+}
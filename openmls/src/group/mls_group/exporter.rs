@@ -0,0 +1,280 @@
+//! # Typed exporter labels
+//!
+//! [`MlsGroup::export_secret()`](crate::group::MlsGroup::export_secret())
+//! accepts any label, so two subsystems of an application that pick the same
+//! label silently derive the same secret. An [`ExporterLabelRegistry`] hands
+//! out an [`ExporterNamespace`] for each subsystem, which in turn creates the
+//! [`ExporterLabel`]s of the subsystem together with the length of the
+//! exported secret, see [`ExporterLength`].
+//!
+//! The secrets of typed labels are exported with
+//! [`MlsGroup::export()`](crate::group::MlsGroup::export()) or, into
+//! fixed-size arrays, with
+//! [`MlsGroup::export_array()`](crate::group::MlsGroup::export_array()).
+//! Both cache the secrets until the group moves to a new epoch.
+//!
+//! ## Collision rules
+//!
+//! The exporter label of the label `name` in the namespace `namespace` is
+//! `namespace:name`.
+//!  - Namespaces are non-empty and consist of ASCII letters, digits, `-`,
+//!    `_` and `.`. Since they can't contain `:`, labels of different
+//!    namespaces never collide, and labels of the same namespace only
+//!    collide if their names are equal.
+//!  - The labels that OpenMLS uses internally, e.g. for join confirmations
+//!    or pairwise secrets, contain spaces before the first `:`, if any, so
+//!    they never collide with typed labels. The namespaces `mls` and
+//!    `openmls` are reserved in any capitalization.
+//!  - The registry rejects a namespace that was already registered. Labels
+//!    that are passed to `export_secret()` directly are not checked and
+//!    shouldn't contain `:`.
+//!
+//! Members of a group only derive the same secret if they use the same
+//! namespace and name, so the namespaces have to be agreed on by all clients
+//! of the application.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Debug,
+};
+
+use tls_codec::SecretVLBytes;
+
+use super::{errors::ExporterLabelError, *};
+
+/// The separator between the namespace and the name of an [`ExporterLabel`].
+const NAMESPACE_SEPARATOR: char = ':';
+
+/// The namespaces that are reserved for OpenMLS, compared ignoring case.
+const RESERVED_NAMESPACES: [&str; 2] = ["mls", "openmls"];
+
+/// The maximum number of secrets in the [`ExporterCache`]. Further secrets
+/// are exported without caching them.
+const MAX_CACHED_SECRETS: usize = 64;
+
+/// The length of the secret that is exported for an [`ExporterLabel`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExporterLength {
+    /// 16 bytes, e.g. for an AES-128 key.
+    Key128,
+    /// 32 bytes, e.g. for an AES-256 or ChaCha20 key.
+    Key256,
+    /// The output length of the hash function of the group's ciphersuite.
+    HashLength,
+    /// The given number of bytes.
+    Custom(usize),
+}
+
+impl ExporterLength {
+    /// Returns the length in bytes for the given `ciphersuite`.
+    pub fn length(&self, ciphersuite: Ciphersuite) -> usize {
+        match self {
+            ExporterLength::Key128 => 16,
+            ExporterLength::Key256 => 32,
+            ExporterLength::HashLength => ciphersuite.hash_length(),
+            ExporterLength::Custom(length) => *length,
+        }
+    }
+}
+
+/// The registry of the exporter namespaces of an application.
+///
+/// Each subsystem of the application registers its own namespace, and the
+/// registry makes sure that no namespace is handed out twice. See the
+/// [module documentation](self) for the collision rules.
+#[derive(Debug, Clone, Default)]
+pub struct ExporterLabelRegistry {
+    namespaces: BTreeSet<String>,
+}
+
+impl ExporterLabelRegistry {
+    /// Creates a registry without any namespaces.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the `namespace` and returns it for the creation of labels.
+    ///
+    /// Returns an [`ExporterLabelError`] if the namespace is empty, contains
+    /// other characters than ASCII letters, digits, `-`, `_` and `.`, is
+    /// reserved for OpenMLS or was registered before.
+    pub fn register(&mut self, namespace: &str) -> Result<ExporterNamespace, ExporterLabelError> {
+        if namespace.is_empty() {
+            return Err(ExporterLabelError::EmptyNamespace);
+        }
+        if !namespace
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            return Err(ExporterLabelError::InvalidNamespace);
+        }
+        if RESERVED_NAMESPACES
+            .iter()
+            .any(|reserved| reserved.eq_ignore_ascii_case(namespace))
+        {
+            return Err(ExporterLabelError::ReservedNamespace);
+        }
+        if !self.namespaces.insert(namespace.to_string()) {
+            return Err(ExporterLabelError::DuplicateNamespace);
+        }
+        Ok(ExporterNamespace {
+            name: namespace.to_string(),
+        })
+    }
+
+    /// Returns the registered namespaces in lexicographic order.
+    pub fn namespaces(&self) -> impl Iterator<Item = &str> {
+        self.namespaces.iter().map(String::as_str)
+    }
+}
+
+/// A namespace of exporter labels, registered with an
+/// [`ExporterLabelRegistry`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExporterNamespace {
+    name: String,
+}
+
+impl ExporterNamespace {
+    /// Returns the name of the namespace.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the label `name` of this namespace, for which secrets of the
+    /// given `length` are exported.
+    pub fn label(&self, name: &str, length: ExporterLength) -> ExporterLabel {
+        ExporterLabel {
+            label: format!("{}{NAMESPACE_SEPARATOR}{name}", self.name),
+            length,
+        }
+    }
+}
+
+/// An exporter label of an [`ExporterNamespace`] together with the length of
+/// the exported secrets.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ExporterLabel {
+    label: String,
+    length: ExporterLength,
+}
+
+impl ExporterLabel {
+    /// Returns the label as it is used in the exporter, i.e. `namespace:name`.
+    pub fn as_str(&self) -> &str {
+        &self.label
+    }
+
+    /// Returns the length of the exported secrets.
+    pub fn length(&self) -> ExporterLength {
+        self.length
+    }
+}
+
+/// The secrets exported for [`ExporterLabel`]s in the current epoch. It is not
+/// part of the stored group state. The secrets are zeroized when they are
+/// removed from the cache.
+#[derive(Default)]
+pub(crate) struct ExporterCache {
+    // The epoch authenticator of the epoch of the secrets.
+    epoch_authenticator: Vec<u8>,
+    // The secrets by label, context and length.
+    secrets: HashMap<(String, Vec<u8>, usize), SecretVLBytes>,
+}
+
+impl Debug for ExporterCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExporterCache")
+            .field("secrets", &self.secrets.len())
+            .finish()
+    }
+}
+
+impl MlsGroup {
+    /// Exports a secret for a typed `label` from the current epoch, see
+    /// [`MlsGroup::export_secret()`].
+    ///
+    /// The secret is cached until the group moves to a new epoch or
+    /// [`MlsGroup::clear_exporter_cache()`] is called. At most 64 secrets are
+    /// cached, further ones are derived again on every call.
+    pub fn export(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        label: &ExporterLabel,
+        context: &[u8],
+    ) -> Result<Vec<u8>, ExportSecretError> {
+        let key_length = label.length().length(self.ciphersuite());
+        self.export_cached(backend, label, context, key_length)
+    }
+
+    /// Exports a secret for a typed `label` from the current epoch into an
+    /// array, see [`MlsGroup::export()`].
+    ///
+    /// Returns [`ExportSecretError::KeyLengthMismatch`] if the length of the
+    /// `label` is not `N` for the ciphersuite of the group.
+    pub fn export_array<const N: usize>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        label: &ExporterLabel,
+        context: &[u8],
+    ) -> Result<[u8; N], ExportSecretError> {
+        if label.length().length(self.ciphersuite()) != N {
+            return Err(ExportSecretError::KeyLengthMismatch);
+        }
+        self.export_cached(backend, label, context, N)?
+            .try_into()
+            .map_err(|_| LibraryError::custom("Exported secret has the wrong length").into())
+    }
+
+    /// Removes the secrets exported with [`MlsGroup::export()`] from the
+    /// cache, e.g. once the application has derived its keys.
+    pub fn clear_exporter_cache(&self) {
+        let mut cache = self
+            .exporter_cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        *cache = ExporterCache::default();
+    }
+
+    /// Returns the number of secrets in the exporter cache.
+    #[cfg(test)]
+    pub(crate) fn cached_exported_secrets(&self) -> usize {
+        self.exporter_cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .secrets
+            .len()
+    }
+
+    fn export_cached(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        label: &ExporterLabel,
+        context: &[u8],
+        key_length: usize,
+    ) -> Result<Vec<u8>, ExportSecretError> {
+        if !self.is_active() {
+            return Err(MlsGroupStateError::UseAfterEviction.into());
+        }
+        let mut cache = self
+            .exporter_cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let epoch_authenticator = self.epoch_authenticator().as_slice();
+        if cache.epoch_authenticator != epoch_authenticator {
+            *cache = ExporterCache {
+                epoch_authenticator: epoch_authenticator.to_vec(),
+                secrets: HashMap::new(),
+            };
+        }
+        let key = (label.as_str().to_string(), context.to_vec(), key_length);
+        if let Some(secret) = cache.secrets.get(&key) {
+            return Ok(secret.as_slice().to_vec());
+        }
+        let secret = self.export_secret(backend, label.as_str(), context, key_length)?;
+        if cache.secrets.len() < MAX_CACHED_SECRETS {
+            cache.secrets.insert(key, secret.clone().into());
+        }
+        Ok(secret)
+    }
+}
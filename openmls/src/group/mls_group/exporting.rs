@@ -1,6 +1,11 @@
 use openmls_traits::signatures::Signer;
 
-use crate::{group::errors::ExporterError, schedule::EpochAuthenticator};
+use crate::{
+    extensions::ExtensionType,
+    group::errors::ExporterError,
+    messages::group_info_summary::{GroupInfoSummary, GroupInfoSummarySigner},
+    schedule::{EpochAuthenticator, ExporterCompatibility, VerificationCode},
+};
 
 use super::*;
 
@@ -8,6 +13,11 @@ impl MlsGroup {
     // === Export secrets ===
 
     /// Exports a secret from the current epoch.
+    ///
+    /// If the [`ExporterCompatibility`] of the [`MlsGroupConfig`] lists
+    /// quirks for the `label`, the secret is derived with these quirks
+    /// instead of as specified in the RFC.
+    ///
     /// Returns [`ExportSecretError::KeyLengthTooLong`] if the requested
     /// key length is too long.
     /// Returns [`ExportSecretError::GroupStateError(MlsGroupStateError::UseAfterEviction)`](MlsGroupStateError::UseAfterEviction)
@@ -20,9 +30,10 @@ impl MlsGroup {
         key_length: usize,
     ) -> Result<Vec<u8>, ExportSecretError> {
         if self.is_active() {
+            let quirks = self.configuration().exporter_compatibility().quirks(label);
             Ok(self
                 .group
-                .export_secret(backend, label, context, key_length)
+                .export_secret_with_quirks(backend, label, context, key_length, quirks)
                 .map_err(|e| match e {
                     ExporterError::LibraryError(e) => e.into(),
                     ExporterError::KeyLengthTooLong => ExportSecretError::KeyLengthTooLong,
@@ -35,10 +46,25 @@ impl MlsGroup {
     }
 
     /// Returns the epoch authenticator of the current epoch.
+    ///
+    /// All members in the same epoch of the group derive the same epoch
+    /// authenticator, so it can be used to verify out of band that the
+    /// members agree on the group state, e.g. through
+    /// [`MlsGroup::verification_code()`].
     pub fn epoch_authenticator(&self) -> &EpochAuthenticator {
         self.group.epoch_authenticator()
     }
 
+    /// Returns the [`VerificationCode`] of the current epoch, which is derived
+    /// from the [`EpochAuthenticator`] and meant to be compared by the users
+    /// out of band.
+    ///
+    /// The code changes with every epoch, so users have to compare the codes
+    /// while their clients are in the same epoch.
+    pub fn verification_code(&self) -> VerificationCode {
+        self.epoch_authenticator().verification_code()
+    }
+
     /// Returns the resumption PSK secret of the current epoch.
     pub fn resumption_psk_secret(&self) -> &ResumptionPskSecret {
         self.group.resumption_psk_secret()
@@ -46,20 +72,60 @@ impl MlsGroup {
 
     /// Returns a resumption psk for a given epoch. If no resumption psk
     /// is available for that epoch,  `None` is returned.
+    ///
+    /// The resumption psk of the current epoch is always available. The
+    /// group keeps the ones of as many epochs as configured with
+    /// [`MlsGroupConfigBuilder::number_of_resumption_psks()`].
     pub fn get_past_resumption_psk(&self, epoch: GroupEpoch) -> Option<&ResumptionPskSecret> {
+        if epoch == self.epoch() {
+            return Some(self.resumption_psk_secret());
+        }
         self.group.resumption_psk_store.get(epoch)
     }
 
     /// Export a group info object for this group.
+    ///
+    /// The group info is signed with the `signer` for the current epoch and
+    /// can be exported at any time, so that external commits are possible
+    /// without a recent commit of this member. It contains the external
+    /// public key of the epoch and, if `with_ratchet_tree` is set, the
+    /// ratchet tree extension (compressed if the group requires the
+    /// compressed ratchet tree capability). Without the extension, joiners
+    /// need to get the ratchet tree in some other way.
+    ///
+    /// Returns [`MlsGroupStateError::UseAfterEviction`] if the own client
+    /// was removed from the group.
     pub fn export_group_info(
         &self,
         backend: &impl OpenMlsCryptoProvider,
         signer: &impl Signer,
         with_ratchet_tree: bool,
     ) -> Result<MlsMessageOut, ExportGroupInfoError> {
+        if !self.is_active() {
+            return Err(MlsGroupStateError::UseAfterEviction.into());
+        }
         Ok(self
             .group
             .export_group_info(backend, signer, with_ratchet_tree)?
             .into())
     }
+
+    /// Export a [`GroupInfoSummary`] for this group, signed with the leaf
+    /// signature key of this member.
+    ///
+    /// In contrast to the group info, the summary doesn't allow to join the
+    /// group and can be handed to directory or search services. Only the
+    /// group context extensions with a type in `extension_types` are included
+    /// in the summary.
+    pub fn export_group_info_summary(
+        &self,
+        signer: &impl Signer,
+        extension_types: &[ExtensionType],
+    ) -> Result<GroupInfoSummary, ExportGroupInfoError> {
+        Ok(self.group.public_group().sign_group_info_summary(
+            signer,
+            GroupInfoSummarySigner::Member(self.own_leaf_index()),
+            extension_types,
+        )?)
+    }
 }
@@ -0,0 +1,303 @@
+//! MLS group external commits
+//!
+//! This module contains the functions to join a group and to re-join it
+//! through an External Commit. They require the `external-commit` feature.
+//! Processing External Commits of other clients doesn't.
+
+use openmls_traits::signatures::Signer;
+
+use super::*;
+use crate::{
+    credentials::CredentialWithKey,
+    group::{
+        core_group::create_commit_params::CreateCommitParams,
+        errors::{ExternalCommitError, RejoinError},
+    },
+    messages::group_info::{GroupInfo, VerifiableGroupInfo},
+    treesync::{RatchetTreeFetcher, RatchetTreeIn, RatchetTreeSource},
+};
+
+/// A re-join of an [`MlsGroup`] through an External Commit that hasn't been
+/// accepted yet. See [`MlsGroup::rejoin_via_external_commit()`].
+#[derive(Debug)]
+pub struct PendingRejoin {
+    group: MlsGroup,
+}
+
+impl PendingRejoin {
+    /// Returns the External Commit that re-joins the group as a
+    /// [`StagedCommit`], e.g. to inspect which leaves it removes.
+    pub fn staged_commit(&self) -> Option<&StagedCommit> {
+        self.group.pending_commit()
+    }
+}
+
+impl MlsGroup {
+    /// Join an existing group through an External Commit.
+    /// The resulting [`MlsGroup`] instance starts off with a pending
+    /// commit (the external commit, which adds this client to the group).
+    /// Merging this commit is necessary for this [`MlsGroup`] instance to
+    /// function properly, as, for example, this client is not yet part of the
+    /// tree. As a result, it is not possible to clear the pending commit. If
+    /// the external commit was rejected due to an epoch change, the
+    /// [`MlsGroup`] instance has to be discarded and a new one has to be
+    /// created using this function based on the latest `ratchet_tree` and
+    /// group info. For more information on the external init process,
+    /// please see Section 11.2.1 in the MLS specification.
+    ///
+    /// Note: If there is a group member in the group with the same identity as us,
+    /// this will create a remove proposal.
+    #[allow(clippy::type_complexity)]
+    pub fn join_by_external_commit<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        ratchet_tree: Option<RatchetTreeIn>,
+        verifiable_group_info: VerifiableGroupInfo,
+        mls_group_config: &MlsGroupConfig,
+        aad: &[u8],
+        credential_with_key: CredentialWithKey,
+    ) -> Result<(Self, MlsMessageOut, Option<GroupInfo>), ExternalCommitError<KeyStore::Error>>
+    {
+        Self::join_by_external_commit_internal(
+            backend,
+            signer,
+            ratchet_tree.into(),
+            verifiable_group_info,
+            mls_group_config,
+            aad,
+            credential_with_key,
+        )
+    }
+
+    /// Join an existing group through an External Commit like
+    /// [`MlsGroup::join_by_external_commit()`], but fetch the ratchet tree
+    /// through the `ratchet_tree_fetcher` if the group info doesn't contain a
+    /// ratchet tree extension.
+    ///
+    /// The fetcher is called with the group ID and the epoch of the group
+    /// info. If it returns `None`, joining fails with
+    /// [`ExternalCommitError::MissingRatchetTree`].
+    #[allow(clippy::type_complexity)]
+    pub fn join_by_external_commit_with_ratchet_tree_fetcher<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        ratchet_tree_fetcher: impl RatchetTreeFetcher,
+        verifiable_group_info: VerifiableGroupInfo,
+        mls_group_config: &MlsGroupConfig,
+        aad: &[u8],
+        credential_with_key: CredentialWithKey,
+    ) -> Result<(Self, MlsMessageOut, Option<GroupInfo>), ExternalCommitError<KeyStore::Error>>
+    {
+        Self::join_by_external_commit_internal(
+            backend,
+            signer,
+            RatchetTreeSource::Fetched(Box::new(ratchet_tree_fetcher)),
+            verifiable_group_info,
+            mls_group_config,
+            aad,
+            credential_with_key,
+        )
+    }
+
+    /// Join an existing group through an External Commit with the ratchet
+    /// tree from the given source. See [`MlsGroup::join_by_external_commit()`].
+    #[allow(clippy::type_complexity)]
+    fn join_by_external_commit_internal<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        ratchet_tree: RatchetTreeSource<'_>,
+        verifiable_group_info: VerifiableGroupInfo,
+        mls_group_config: &MlsGroupConfig,
+        aad: &[u8],
+        credential_with_key: CredentialWithKey,
+    ) -> Result<(Self, MlsMessageOut, Option<GroupInfo>), ExternalCommitError<KeyStore::Error>>
+    {
+        mls_group_config
+            .ciphersuite_policy()
+            .check(verifiable_group_info.ciphersuite())?;
+
+        // Prepare the commit parameters
+        let framing_parameters = FramingParameters::new(aad, WireFormat::PublicMessage);
+
+        let proposal_store = ProposalStore::new();
+        let params = CreateCommitParams::builder()
+            .framing_parameters(framing_parameters)
+            .proposal_store(&proposal_store)
+            .credential_with_key(credential_with_key)
+            .build();
+        let (mut group, create_commit_result) = CoreGroup::join_by_external_commit(
+            backend,
+            signer,
+            params,
+            ratchet_tree,
+            verifiable_group_info,
+            None,
+        )
+        .map_err(ExternalCommitError::with_storage_error)?;
+        group.set_past_epoch_retention(&mls_group_config.past_epoch_retention());
+        group.set_lifetime_policy(*mls_group_config.lifetime_policy());
+        group.set_leaf_placement_strategy(mls_group_config.leaf_placement_strategy());
+        group.set_decode_limits(*mls_group_config.decode_limits());
+        group.set_number_of_resumption_psks(mls_group_config.number_of_resumption_psks());
+
+        let mls_group = MlsGroup {
+            mls_group_config: mls_group_config.clone(),
+            group,
+            proposal_store: ProposalStore::new(),
+            own_leaf_nodes: vec![],
+            aad: vec![],
+            group_state: MlsGroupState::PendingCommit(Box::new(PendingCommitState::External(
+                create_commit_result.staged_commit,
+            ))),
+            departure: None,
+            receiver_state: Mutex::default(),
+            message_buffer: Mutex::default(),
+            processed_buffered_messages: vec![],
+            own_messages: None,
+            lost_commit: None,
+            credential_validator: None,
+            commit_validator: None,
+            observer: None,
+            psk_resolver: None,
+            event_log: VecDeque::new(),
+            event_timestamp: None,
+            exporter_cache: Mutex::default(),
+            store_lock: Mutex::new(()),
+        };
+        mls_group
+            .store(backend)
+            .map_err(ExternalCommitError::StorageError)?;
+
+        let public_message: PublicMessage = create_commit_result.commit.into();
+
+        Ok((
+            mls_group,
+            public_message.into(),
+            create_commit_result.group_info,
+        ))
+    }
+
+    /// Re-join this group through an External Commit, e.g. to recover from a
+    /// group state that is out of sync with the rest of the group.
+    ///
+    /// The configuration and the AAD of this group are used for the new group
+    /// state. If the own leaf of this group is still part of the given ratchet
+    /// tree, the External Commit also removes it. Other members with the same
+    /// identity are left untouched.
+    ///
+    /// This group is not modified. Once the External Commit was accepted by
+    /// the DS, the returned [`PendingRejoin`] has to be passed to
+    /// [`MlsGroup::complete_rejoin()`], which replaces the state of this group
+    /// with the new one. If the External Commit was rejected, the
+    /// [`PendingRejoin`] can simply be dropped.
+    ///
+    /// Returns an error if the group info belongs to a different group.
+    #[allow(clippy::type_complexity)]
+    pub fn rejoin_via_external_commit<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        ratchet_tree: Option<RatchetTreeIn>,
+        verifiable_group_info: VerifiableGroupInfo,
+        credential_with_key: CredentialWithKey,
+    ) -> Result<(PendingRejoin, MlsMessageOut, Option<GroupInfo>), RejoinError<KeyStore::Error>>
+    {
+        if verifiable_group_info.group_id() != self.group_id() {
+            return Err(RejoinError::GroupIdMismatch);
+        }
+        self.mls_group_config
+            .ciphersuite_policy()
+            .check(verifiable_group_info.ciphersuite())
+            .map_err(ExternalCommitError::from)?;
+
+        // Our previous leaf is only meaningful as long as we haven't been
+        // removed from the group.
+        let previous_signature_key = if self.is_active() {
+            self.own_leaf_node()
+                .map(|leaf_node| leaf_node.signature_key().as_slice())
+        } else {
+            None
+        };
+
+        // Prepare the commit parameters
+        let framing_parameters = FramingParameters::new(&self.aad, WireFormat::PublicMessage);
+
+        let proposal_store = ProposalStore::new();
+        let params = CreateCommitParams::builder()
+            .framing_parameters(framing_parameters)
+            .proposal_store(&proposal_store)
+            .credential_with_key(credential_with_key)
+            .build();
+        let (mut group, create_commit_result) = CoreGroup::join_by_external_commit(
+            backend,
+            signer,
+            params,
+            ratchet_tree.into(),
+            verifiable_group_info,
+            previous_signature_key,
+        )?;
+        group.set_past_epoch_retention(&self.mls_group_config.past_epoch_retention());
+        group.set_lifetime_policy(*self.mls_group_config.lifetime_policy());
+        group.set_leaf_placement_strategy(self.mls_group_config.leaf_placement_strategy());
+        group.set_decode_limits(*self.mls_group_config.decode_limits());
+        group.set_number_of_resumption_psks(self.mls_group_config.number_of_resumption_psks());
+
+        let mls_group = MlsGroup {
+            mls_group_config: self.mls_group_config.clone(),
+            group,
+            proposal_store: ProposalStore::new(),
+            own_leaf_nodes: vec![],
+            aad: self.aad.clone(),
+            group_state: MlsGroupState::PendingCommit(Box::new(PendingCommitState::External(
+                create_commit_result.staged_commit,
+            ))),
+            departure: None,
+            receiver_state: Mutex::default(),
+            message_buffer: Mutex::default(),
+            processed_buffered_messages: vec![],
+            own_messages: None,
+            lost_commit: None,
+            credential_validator: self.credential_validator.clone(),
+            commit_validator: self.commit_validator.clone(),
+            observer: self.observer.clone(),
+            psk_resolver: self.psk_resolver.clone(),
+            event_log: self.event_log.clone(),
+            event_timestamp: self.event_timestamp,
+            exporter_cache: Mutex::default(),
+            store_lock: Mutex::new(()),
+        };
+
+        let public_message: PublicMessage = create_commit_result.commit.into();
+
+        Ok((
+            PendingRejoin { group: mls_group },
+            public_message.into(),
+            create_commit_result.group_info,
+        ))
+    }
+
+    /// Completes a re-join started with
+    /// [`MlsGroup::rejoin_via_external_commit()`] after the External Commit
+    /// was accepted by the DS.
+    ///
+    /// The External Commit is merged and the state of this group is replaced
+    /// with the new group state. If merging fails, this group is left
+    /// untouched.
+    ///
+    /// Returns an error if the [`PendingRejoin`] belongs to a different group.
+    pub fn complete_rejoin<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        pending_rejoin: PendingRejoin,
+    ) -> Result<(), RejoinError<KeyStore::Error>> {
+        if pending_rejoin.group.group_id() != self.group_id() {
+            return Err(RejoinError::GroupIdMismatch);
+        }
+
+        let mut group = pending_rejoin.group;
+        group.merge_pending_commit(backend)?;
+        *self = group;
+
+        self.store(backend).map_err(RejoinError::StorageError)
+    }
+}
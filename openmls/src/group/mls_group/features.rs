@@ -0,0 +1,136 @@
+//! MLS group feature negotiation
+//!
+//! This module contains [`MlsGroup::negotiate_feature()`], which tells the
+//! application whether an MLS-level feature, i.e. an extension or a proposal
+//! type, can be used in the group.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::extensions::{ExtensionType, RequiredCapabilitiesExtension};
+
+use super::*;
+
+/// An MLS-level feature, i.e. an extension type or a proposal type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    /// An extension type.
+    Extension(ExtensionType),
+    /// A proposal type.
+    Proposal(ProposalType),
+}
+
+impl From<ExtensionType> for Feature {
+    fn from(extension_type: ExtensionType) -> Self {
+        Feature::Extension(extension_type)
+    }
+}
+
+impl From<ProposalType> for Feature {
+    fn from(proposal_type: ProposalType) -> Self {
+        Feature::Proposal(proposal_type)
+    }
+}
+
+impl Feature {
+    fn is_supported_by(&self, leaf_node: &LeafNode) -> bool {
+        match self {
+            Feature::Extension(extension_type) => leaf_node.supports_extension(extension_type),
+            Feature::Proposal(proposal_type) => leaf_node.supports_proposal(proposal_type),
+        }
+    }
+
+    fn is_required_by(&self, required_capabilities: &RequiredCapabilitiesExtension) -> bool {
+        match self {
+            Feature::Extension(extension_type) => required_capabilities
+                .extension_types()
+                .contains(extension_type),
+            Feature::Proposal(proposal_type) => required_capabilities
+                .proposal_types()
+                .contains(proposal_type),
+        }
+    }
+}
+
+/// The status of a [`Feature`] in a group as returned by
+/// [`MlsGroup::negotiate_feature()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatureStatus {
+    /// The feature is part of the group's required capabilities. All current
+    /// and future members support it.
+    Required,
+    /// All members support the feature, including the members added by
+    /// pending proposals. The feature can be used right away.
+    Available,
+    /// Not all members support the feature, but all members that remain in
+    /// the group after the pending proposals are committed do. The feature
+    /// can be used after the next commit.
+    AvailableAfterNextCommit,
+    /// Some members don't support the feature, even after the pending
+    /// proposals are committed, or a member added by a pending proposal
+    /// doesn't support it.
+    Unavailable,
+}
+
+impl MlsGroup {
+    /// Returns the [`FeatureStatus`] of the given [`Feature`], i.e. an
+    /// [`ExtensionType`] or a [`ProposalType`].
+    ///
+    /// The status is determined from the group's required capabilities, the
+    /// capabilities of the leaf nodes of all members and the pending
+    /// proposals in the proposal store. Pending Add proposals add leaf nodes,
+    /// pending Remove proposals remove leaf nodes and pending Update proposals
+    /// replace leaf nodes.
+    pub fn negotiate_feature(&self, feature: impl Into<Feature>) -> FeatureStatus {
+        let feature = feature.into();
+        let public_group = self.group.public_group();
+
+        if public_group
+            .required_capabilities()
+            .map(|required_capabilities| feature.is_required_by(required_capabilities))
+            .unwrap_or(false)
+        {
+            return FeatureStatus::Required;
+        }
+
+        // Collect the changes of the pending proposals
+        let mut removed = HashSet::new();
+        let mut updated = HashMap::new();
+        let mut added = vec![];
+        for queued_proposal in self.proposal_store.proposals() {
+            match (queued_proposal.proposal(), queued_proposal.sender()) {
+                (Proposal::Add(add_proposal), _) => {
+                    added.push(add_proposal.key_package().leaf_node())
+                }
+                (Proposal::Remove(remove_proposal), _) => {
+                    removed.insert(remove_proposal.removed());
+                }
+                (Proposal::Update(update_proposal), Sender::Member(leaf_index)) => {
+                    updated.insert(*leaf_index, update_proposal.leaf_node());
+                }
+                _ => (),
+            }
+        }
+
+        let mut supported_now = true;
+        let mut supported_after_commit = true;
+        for member in public_group.members() {
+            let Some(leaf_node) = public_group.leaf(member.index) else {
+                continue;
+            };
+            supported_now &= feature.is_supported_by(leaf_node);
+            if !removed.contains(&member.index) {
+                let leaf_node = updated.get(&member.index).copied().unwrap_or(leaf_node);
+                supported_after_commit &= feature.is_supported_by(leaf_node);
+            }
+        }
+        supported_after_commit &= added
+            .into_iter()
+            .all(|leaf_node| feature.is_supported_by(leaf_node));
+
+        match (supported_now, supported_after_commit) {
+            (true, true) => FeatureStatus::Available,
+            (false, true) => FeatureStatus::AvailableAfterNextCommit,
+            (_, false) => FeatureStatus::Unavailable,
+        }
+    }
+}
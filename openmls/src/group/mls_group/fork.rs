@@ -0,0 +1,135 @@
+//! Detection of and recovery from forks of an [`MlsGroup`].
+//!
+//! A group forks if members merge different commits for the same epoch,
+//! e.g. because a client merged its own commit before the DS accepted it.
+//! The members then end up in different group states for the next epoch and
+//! can't read each other's messages anymore.
+//!
+//! A fork is detected by comparing epoch authenticators, which members
+//! exchange in authenticated messages of the current epoch, e.g. in the
+//! authenticated data of application messages, with
+//! [`MlsGroup::check_epoch_authenticator()`].
+//!
+//! A commit of the previous epoch that differs from the commit that started
+//! the current epoch is not enough evidence on its own, since its sender
+//! usually discarded it after the DS rejected it. Processing such a commit
+//! fails with [`ProcessMessageError::CompetingCommit`] once its signature
+//! was verified against the tree of the previous epoch. This requires the
+//! secrets of the previous epoch (see
+//! [`MlsGroupConfig::max_past_epochs()`]). The sender of the competing commit
+//! is a good candidate to compare epoch authenticators with.
+//!
+//! The members of one of the branches then have to re-join the group, e.g.
+//! with [`MlsGroup::recover_from_fork()`].
+
+#[cfg(feature = "external-commit")]
+use openmls_traits::signatures::Signer;
+
+use crate::ciphersuite::equal_ct;
+#[cfg(feature = "external-commit")]
+use crate::{
+    credentials::CredentialWithKey,
+    group::PendingRejoin,
+    messages::group_info::{GroupInfo, VerifiableGroupInfo},
+    treesync::RatchetTreeIn,
+};
+
+use super::*;
+
+impl MlsGroup {
+    /// Compares the `epoch_authenticator` that another member derived in
+    /// `epoch` with the own
+    /// [`EpochAuthenticator`](crate::schedule::EpochAuthenticator) of the
+    /// current epoch.
+    ///
+    /// # Errors
+    /// Returns [`CheckEpochAuthenticatorError::WrongEpoch`] if `epoch` is not
+    /// the current epoch, in which case the authenticators can't be compared,
+    /// and [`CheckEpochAuthenticatorError::ForkDetected`] if the
+    /// authenticators differ.
+    pub fn check_epoch_authenticator(
+        &self,
+        epoch: GroupEpoch,
+        epoch_authenticator: &[u8],
+    ) -> Result<(), CheckEpochAuthenticatorError> {
+        if epoch != self.epoch() {
+            return Err(CheckEpochAuthenticatorError::WrongEpoch);
+        }
+        let own_epoch_authenticator = self.epoch_authenticator().as_slice();
+        if own_epoch_authenticator.len() != epoch_authenticator.len()
+            || !equal_ct(own_epoch_authenticator, epoch_authenticator)
+        {
+            return Err(CheckEpochAuthenticatorError::ForkDetected);
+        }
+        Ok(())
+    }
+
+    /// Checks whether the `message` is a commit of the previous epoch that
+    /// differs from the commit that started the current epoch and that was
+    /// signed by a member of the previous epoch.
+    pub(super) fn detect_competing_commit<KeyStoreError>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        message: &ProtocolMessage,
+    ) -> Result<(), ProcessMessageError<KeyStoreError>> {
+        if message.content_type() != ContentType::Commit
+            || message.group_id() != self.group_id()
+            || message.epoch().as_u64().checked_add(1) != Some(self.epoch().as_u64())
+        {
+            return Ok(());
+        }
+        let confirmation_tag = self.group.verify_previous_epoch_commit(
+            backend,
+            message.clone(),
+            self.configuration().sender_ratchet_configuration(),
+        );
+        match confirmation_tag {
+            Some(confirmation_tag)
+                if &confirmation_tag != self.group.public_group().confirmation_tag() =>
+            {
+                Err(ProcessMessageError::CompetingCommit {
+                    epoch: message.epoch(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Recovers from a fork by re-joining the group through an External
+    /// Commit with the own credential, based on the `verifiable_group_info`
+    /// of the other branch of the fork.
+    ///
+    /// This is a shorthand for [`MlsGroup::rejoin_via_external_commit()`]
+    /// with the credential and signature key of the own leaf. Like there, the
+    /// returned [`PendingRejoin`] has to be passed to
+    /// [`MlsGroup::complete_rejoin()`] once the DS accepted the External
+    /// Commit.
+    ///
+    /// Returns an error if the group info belongs to a different group or if
+    /// the own client is no longer a member of the group.
+    #[cfg(feature = "external-commit")]
+    #[allow(clippy::type_complexity)]
+    pub fn recover_from_fork<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        ratchet_tree: Option<RatchetTreeIn>,
+        verifiable_group_info: VerifiableGroupInfo,
+    ) -> Result<(PendingRejoin, MlsMessageOut, Option<GroupInfo>), RejoinError<KeyStore::Error>>
+    {
+        let own_leaf = self
+            .own_leaf_node()
+            .ok_or(MlsGroupStateError::UseAfterEviction)?;
+        let credential_with_key = CredentialWithKey {
+            credential: own_leaf.credential().clone(),
+            signature_key: own_leaf.signature_key().clone(),
+        };
+        self.rejoin_via_external_commit(
+            backend,
+            signer,
+            ratchet_tree,
+            verifiable_group_info,
+            credential_with_key,
+        )
+    }
+}
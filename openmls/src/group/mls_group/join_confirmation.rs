@@ -0,0 +1,105 @@
+//! MLS group join confirmation
+//!
+//! This module contains an optional post-join handshake. After joining a
+//! group, a new member computes a confirmation with
+//! [`MlsGroup::join_confirmation()`] and sends it as its first application
+//! message. Existing members check it with
+//! [`MlsGroup::verify_join_confirmation()`].
+//!
+//! The confirmation is a MAC under a key that is exported from the epoch's
+//! exporter secret. A valid confirmation therefore proves that the joiner
+//! derived the secrets of the current epoch. Applications can use this to
+//! only show a new member as "active" once the confirmation was verified.
+//!
+//! The confirmation is bound to the epoch and to the leaf index of the
+//! joiner. It has to be verified in the epoch it was created in.
+
+use tls_codec::Serialize as TlsSerializeTrait;
+
+use crate::{
+    ciphersuite::{Mac, Secret},
+    group::errors::ExporterError,
+};
+
+use super::*;
+
+/// The exporter label used to derive the join confirmation key.
+const JOIN_CONFIRMATION_LABEL: &str = "MLS 1.0 join confirmation";
+
+impl MlsGroup {
+    /// Returns the join confirmation of the own client for the current epoch.
+    ///
+    /// The confirmation is meant to be sent as the first application message
+    /// after joining the group, e.g. with [`MlsGroup::create_message()`].
+    ///
+    /// Returns [`JoinConfirmationError::GroupStateError(MlsGroupStateError::UseAfterEviction)`](MlsGroupStateError::UseAfterEviction)
+    /// if the group is not active.
+    pub fn join_confirmation(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<Vec<u8>, JoinConfirmationError> {
+        let mac = self.compute_join_confirmation(backend, self.own_leaf_index())?;
+        Ok(mac.mac_value.into())
+    }
+
+    /// Verifies the join `confirmation` of the member at `sender` for the
+    /// current epoch.
+    ///
+    /// Returns [`JoinConfirmationError::UnknownMember`] if there is no member
+    /// at `sender` and [`JoinConfirmationError::InvalidConfirmation`] if the
+    /// confirmation is not valid.
+    pub fn verify_join_confirmation(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        sender: LeafNodeIndex,
+        confirmation: &[u8],
+    ) -> Result<(), JoinConfirmationError> {
+        if self.group.public_group().leaf(sender).is_none() {
+            return Err(JoinConfirmationError::UnknownMember);
+        }
+        let expected = self.compute_join_confirmation(backend, sender)?;
+        let received = Mac {
+            mac_value: confirmation.into(),
+        };
+        if expected != received {
+            return Err(JoinConfirmationError::InvalidConfirmation);
+        }
+        Ok(())
+    }
+
+    /// Computes the join confirmation of the member at `leaf_index`.
+    fn compute_join_confirmation(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        leaf_index: LeafNodeIndex,
+    ) -> Result<Mac, JoinConfirmationError> {
+        if !self.is_active() {
+            return Err(MlsGroupStateError::UseAfterEviction.into());
+        }
+        let ciphersuite = self.ciphersuite();
+        let key = self
+            .group
+            .export_secret(
+                backend,
+                JOIN_CONFIRMATION_LABEL,
+                &[],
+                ciphersuite.hash_length(),
+            )
+            .map_err(|e| match e {
+                ExporterError::LibraryError(e) => e,
+                ExporterError::KeyLengthTooLong => {
+                    LibraryError::custom("The hash length is always a valid key length.")
+                }
+            })?;
+        let key = Secret::from_slice(&key, self.group.version(), ciphersuite);
+        let mut confirmation_input = self
+            .epoch()
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+        leaf_index
+            .tls_serialize(&mut confirmation_input)
+            .map_err(LibraryError::missing_bound_check)?;
+        Mac::new(backend, &key, &confirmation_input)
+            .map_err(|e| LibraryError::unexpected_crypto_error(e).into())
+    }
+}
@@ -1,16 +1,19 @@
 //! MLS group membership
 //!
-//! This module contains membership-related operations and exposes [`RemoveOperation`].
+//! This module contains membership-related operations and exposes [`RemoveOperation`]
+//! and [`Departure`].
 
 use core_group::create_commit_params::CreateCommitParams;
-use openmls_traits::signatures::Signer;
+use openmls_traits::signatures::{AsyncSigner, Signer};
 
 use super::{
-    errors::{AddMembersError, LeaveGroupError, RemoveMembersError},
+    errors::{AddMembersError, LeaveGroupError, ProcessWelcomeDeclineError, RemoveMembersError},
     *,
 };
 use crate::{
-    binary_tree::array_representation::LeafNodeIndex, messages::group_info::GroupInfo,
+    binary_tree::array_representation::LeafNodeIndex,
+    ciphersuite::{signable::Verifiable, OpenMlsSignaturePublicKey},
+    messages::{group_info::GroupInfo, welcome_decline::WelcomeDecline},
     treesync::LeafNode,
 };
 
@@ -35,6 +38,18 @@ impl MlsGroup {
         signer: &impl Signer,
         key_packages: &[KeyPackage],
     ) -> Result<(MlsMessageOut, MlsMessageOut, Option<GroupInfo>), AddMembersError<KeyStore::Error>>
+    {
+        crate::utils::poll_ready(self.add_members_internal(backend, signer, key_packages))
+    }
+
+    /// Like [`MlsGroup::add_members()`], but signs with an [`AsyncSigner`].
+    #[allow(clippy::type_complexity)]
+    pub(super) async fn add_members_internal<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl AsyncSigner,
+        key_packages: &[KeyPackage],
+    ) -> Result<(MlsMessageOut, MlsMessageOut, Option<GroupInfo>), AddMembersError<KeyStore::Error>>
     {
         self.is_operational()?;
 
@@ -57,9 +72,13 @@ impl MlsGroup {
         let params = CreateCommitParams::builder()
             .framing_parameters(self.framing_parameters())
             .proposal_store(&self.proposal_store)
+            .psk_resolver(self.psk_resolver.as_deref())
             .inline_proposals(inline_proposals)
             .build();
-        let create_commit_result = self.group.create_commit(params, backend, signer)?;
+        let create_commit_result = self
+            .group
+            .create_commit_async(params, backend, signer)
+            .await?;
 
         let welcome = match create_commit_result.welcome_option {
             Some(welcome) => welcome,
@@ -77,9 +96,7 @@ impl MlsGroup {
         self.group_state = MlsGroupState::PendingCommit(Box::new(PendingCommitState::Member(
             create_commit_result.staged_commit,
         )));
-
-        // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.store(backend).map_err(AddMembersError::StorageError)?;
 
         Ok((
             mls_messages,
@@ -116,6 +133,20 @@ impl MlsGroup {
     ) -> Result<
         (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
         RemoveMembersError<KeyStore::Error>,
+    > {
+        crate::utils::poll_ready(self.remove_members_internal(backend, signer, members))
+    }
+
+    /// Like [`MlsGroup::remove_members()`], but signs with an [`AsyncSigner`].
+    #[allow(clippy::type_complexity)]
+    pub(super) async fn remove_members_internal<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl AsyncSigner,
+        members: &[LeafNodeIndex],
+    ) -> Result<
+        (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
+        RemoveMembersError<KeyStore::Error>,
     > {
         self.is_operational()?;
 
@@ -136,9 +167,13 @@ impl MlsGroup {
         let params = CreateCommitParams::builder()
             .framing_parameters(self.framing_parameters())
             .proposal_store(&self.proposal_store)
+            .psk_resolver(self.psk_resolver.as_deref())
             .inline_proposals(inline_proposals)
             .build();
-        let create_commit_result = self.group.create_commit(params, backend, signer)?;
+        let create_commit_result = self
+            .group
+            .create_commit_async(params, backend, signer)
+            .await?;
 
         // Convert PublicMessage messages to MLSMessage and encrypt them if required by
         // the configuration
@@ -149,9 +184,8 @@ impl MlsGroup {
         self.group_state = MlsGroupState::PendingCommit(Box::new(PendingCommitState::Member(
             create_commit_result.staged_commit,
         )));
-
-        // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.store(backend)
+            .map_err(RemoveMembersError::StorageError)?;
 
         Ok((
             mls_message,
@@ -167,12 +201,17 @@ impl MlsGroup {
     /// Creates a Remove Proposal that needs to be covered by a Commit from a different member.
     /// The Remove Proposal is returned as a [`MlsMessageOut`].
     ///
-    /// Returns an error if there is a pending commit.
-    pub fn leave_group(
+    /// Afterwards, the group is in the [`MlsGroupState::PendingLeave`] state
+    /// until a commit that removes the client is merged. See
+    /// [`MlsGroup::departure()`] for the outcome.
+    ///
+    /// Returns an error if there is a pending commit or if the client is
+    /// already leaving the group.
+    pub fn leave_group<KeyStore: OpenMlsKeyStore>(
         &mut self,
-        backend: &impl OpenMlsCryptoProvider,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         signer: &impl Signer,
-    ) -> Result<MlsMessageOut, LeaveGroupError> {
+    ) -> Result<MlsMessageOut, LeaveGroupError<KeyStore::Error>> {
         self.is_operational()?;
 
         let removed = self.group.own_leaf_index();
@@ -188,7 +227,54 @@ impl MlsGroup {
                 remove_proposal.clone(),
             )?);
 
-        Ok(self.content_to_mls_message(remove_proposal, backend)?)
+        let message = self.content_to_mls_message(remove_proposal, backend)?;
+
+        // Set the current group state to [`MlsGroupState::PendingLeave`] until
+        // a commit that removes us is merged
+        self.group_state = MlsGroupState::PendingLeave;
+        self.store(backend).map_err(LeaveGroupError::StorageError)?;
+
+        Ok(message)
+    }
+
+    /// Verifies a [`WelcomeDecline`] notice and returns the leaf index of the
+    /// invited member that declined the invitation. The leaf can then be
+    /// removed, e.g. through [`MlsGroup::remove_members()`].
+    ///
+    /// Returns an error if the notice is for a different group, if no member
+    /// has the encryption key of the notice, or if the notice wasn't signed
+    /// with the signature key of that member.
+    pub fn process_welcome_decline(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        welcome_decline: &WelcomeDecline,
+    ) -> Result<LeafNodeIndex, ProcessWelcomeDeclineError> {
+        if welcome_decline.group_id() != self.group_id() {
+            return Err(ProcessWelcomeDeclineError::WrongGroupId);
+        }
+
+        let public_group = self.group.public_group();
+        let (leaf_index, leaf_node) = public_group
+            .members()
+            .find_map(|member| {
+                public_group
+                    .leaf(member.index)
+                    .filter(|leaf_node| {
+                        leaf_node.encryption_key() == welcome_decline.encryption_key()
+                    })
+                    .map(|leaf_node| (member.index, leaf_node))
+            })
+            .ok_or(ProcessWelcomeDeclineError::UnknownLeaf)?;
+
+        let signature_public_key = OpenMlsSignaturePublicKey::from_signature_key(
+            leaf_node.signature_key().clone(),
+            self.ciphersuite().signature_algorithm(),
+        );
+        welcome_decline
+            .verify_no_out(backend.crypto(), &signature_public_key)
+            .map_err(|_| ProcessWelcomeDeclineError::InvalidSignature)?;
+
+        Ok(leaf_index)
     }
 
     /// Returns a list of [`Member`]s in the group.
@@ -269,3 +355,50 @@ impl RemoveOperation {
         }
     }
 }
+
+/// Information on why and when the own client left a group. See
+/// [`MlsGroup::departure()`].
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Departure {
+    pub(super) reason: DepartureReason,
+    pub(super) epoch: GroupEpoch,
+}
+
+impl Departure {
+    /// Returns the reason why the client left the group.
+    pub fn reason(&self) -> &DepartureReason {
+        &self.reason
+    }
+
+    /// Returns the last epoch in which the client was a member of the group,
+    /// i.e. the epoch of the commit that removed it.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+}
+
+/// The reason why the own client left a group.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub enum DepartureReason {
+    /// We issued a remove proposal for ourselves and the proposal has been
+    /// committed.
+    Left,
+    /// Someone else (indicated by the [`Sender`]) removed us from the group.
+    RemovedBy(Sender),
+}
+
+impl TryFrom<RemoveOperation> for DepartureReason {
+    type Error = LibraryError;
+
+    fn try_from(remove_operation: RemoveOperation) -> Result<Self, Self::Error> {
+        match remove_operation {
+            RemoveOperation::WeLeft => Ok(Self::Left),
+            RemoveOperation::WeWereRemovedBy(sender) => Ok(Self::RemovedBy(sender)),
+            RemoveOperation::TheyLeft(_)
+            | RemoveOperation::TheyWereRemovedBy(_)
+            | RemoveOperation::WeRemovedThem(_) => Err(LibraryError::custom(
+                "Remove operation doesn't remove the own client",
+            )),
+        }
+    }
+}
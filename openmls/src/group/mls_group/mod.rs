@@ -3,36 +3,78 @@
 //! This module contains [`MlsGroup`] and its submodules.
 
 use super::proposals::{ProposalStore, QueuedProposal};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
 use crate::{
     binary_tree::array_representation::LeafNodeIndex,
-    ciphersuite::hash_ref::ProposalRef,
-    credentials::Credential,
+    ciphersuite::{hash_ref::ProposalRef, signature_cache::SignatureCache},
+    credentials::{Credential, CredentialValidator},
     error::LibraryError,
+    extensions::ExternalSendersExtension,
     framing::{mls_auth_content::AuthenticatedContent, *},
     group::*,
     key_packages::{KeyPackage, KeyPackageBundle},
     messages::{proposals::*, Welcome},
-    schedule::ResumptionPskSecret,
-    treesync::{node::leaf_node::LeafNode, RatchetTree},
+    schedule::{PskResolver, ResumptionPskSecret},
+    tree::secret_tree::SenderRatchets,
+    treesync::{
+        node::{encryption_keys::EncryptionKeyPair, leaf_node::LeafNode},
+        LeafPlacement, RatchetTree,
+    },
+};
+use openmls_traits::{
+    crypto::OpenMlsCrypto,
+    key_store::OpenMlsKeyStore,
+    storage::{StorageKey, StorageProvider},
+    types::Ciphersuite,
+    OpenMlsCryptoProvider,
 };
-use openmls_traits::{key_store::OpenMlsKeyStore, types::Ciphersuite, OpenMlsCryptoProvider};
+use tls_codec::Serialize as TlsSerializeTrait;
 
 // Private
+mod admins;
 mod application;
 mod creation;
 mod exporting;
+mod fork;
 mod updates;
 
 use config::*;
 use errors::*;
 
 // Crate
+#[cfg(feature = "async")]
+pub(crate) mod asynchronous;
+pub(crate) mod auditor;
+pub(crate) mod backup;
+pub(crate) mod client;
+pub(crate) mod commit_builder;
+pub(crate) mod commit_validator;
 pub(crate) mod config;
+pub(crate) mod draining;
 pub(crate) mod errors;
+pub(crate) mod event_log;
+pub(crate) mod exporter;
+#[cfg(feature = "external-commit")]
+pub(crate) mod external_commit;
+pub(crate) mod features;
+pub(crate) mod join_confirmation;
 pub(crate) mod membership;
+pub(crate) mod observer;
+pub(crate) mod pairwise;
 pub(crate) mod processing;
 pub(crate) mod proposal;
+pub(crate) mod rebase;
 pub(crate) mod ser;
+#[cfg(feature = "sframe")]
+pub(crate) mod sframe;
+#[cfg(feature = "speculation")]
+pub(crate) mod speculation;
+pub(crate) mod streaming;
+pub(crate) mod welcome_processing;
 
 // Tests
 #[cfg(test)]
@@ -40,7 +82,7 @@ mod test_mls_group;
 
 /// Pending Commit state. Differentiates between Commits issued by group members
 /// and External Commits.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PendingCommitState {
     /// Commit from a group member
     Member(StagedCommit),
@@ -78,14 +120,28 @@ impl From<PendingCommitState> for StagedCommit {
 /// [`MlsGroup::join_by_external_commit()`], see the functions documentation for
 /// more information). From this `Operational`, the group state can either
 /// transition to [`MlsGroupState::Inactive`], when it processes a commit that
-/// removes this client from the group, or to [`MlsGroupState::PendingCommit`],
-/// when this client creates a commit.
+/// removes this client from the group, to [`MlsGroupState::PendingCommit`],
+/// when this client creates a commit, or to [`MlsGroupState::PendingLeave`],
+/// when this client requests to leave the group.
+///
+/// * [`MlsGroupState::PendingLeave`]: The client requested to leave the group
+/// through [`MlsGroup::leave_group()`]. In this state, the client can still
+/// process incoming messages, but it cannot create proposals or commits.
+/// Since its remove proposal is pending, it cannot send application messages
+/// either (see [`MlsGroupStateError::PendingProposal`]). When a commit that
+/// removes this client is merged, the group transitions to
+/// [`MlsGroupState::Inactive`]. If a commit is merged that doesn't remove
+/// this client, the remove proposal has expired and the group transitions
+/// back to [`MlsGroupState::Operational`].
 ///
 /// * [`MlsGroupState::Inactive`]: A group can enter this state from any other
 /// state when it processes a commit that removes this client from the group.
 /// This is a terminal state that the group can not exit from. If the clients
 /// wants to re-join the group, it can either be added by a group member or it
-/// can join via external commit.
+/// can join via external commit. Why and when the client left the group can
+/// be queried through [`MlsGroup::departure()`]. If the [`RemovalGrace`] of
+/// the configuration is enabled, application messages of the final epoch of
+/// the client can still be processed for a limited time.
 ///
 /// * [`MlsGroupState::PendingCommit`]: This state is split into two possible
 /// sub-states, one for each Commit type:
@@ -112,7 +168,7 @@ impl From<PendingCommitState> for StagedCommit {
 ///   state to [`MlsGroupState::PendingCommit`]. For more information on the
 ///   external commit process, see [`MlsGroup::join_by_external_commit()`] or
 ///   Section 11.2.1 of the MLS specification.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum MlsGroupState {
     /// There is currently a pending Commit that hasn't been merged yet.
     PendingCommit(Box<PendingCommitState>),
@@ -120,6 +176,9 @@ pub enum MlsGroupState {
     Operational,
     /// The group is inactive because the member has been removed.
     Inactive,
+    /// The member requested to leave the group and waits for a commit that
+    /// removes it.
+    PendingLeave,
 }
 
 /// A `MlsGroup` represents an MLS group with a high-level API. The API exposes
@@ -133,11 +192,11 @@ pub enum MlsGroupState {
 /// into [process_message()](`MlsGroup::process_message()`).
 ///
 /// An `MlsGroup` has an internal queue of pending proposals that builds up as
-/// new messages are processed. When creating proposals, those messages are not
-/// automatically appended to this queue, instead they have to be processed
-/// again through [process_message()](`MlsGroup::process_message()`). This
-/// allows the Delivery Service to reject them (e.g. if they reference the wrong
-/// epoch).
+/// new messages are processed. Proposals created by the group itself are added
+/// to this queue when they are created. If the Delivery Service echoes them,
+/// [process_message()](`MlsGroup::process_message()`) returns
+/// [`ProcessMessageError::OwnProposal`], and
+/// [`ProcessMessageError::OwnCommit`] for the echo of the pending commit.
 ///
 /// If incoming messages or applied operations are semantically or syntactically
 /// incorrect, an error event will be returned with a corresponding error
@@ -166,10 +225,51 @@ pub struct MlsGroup {
     // A variable that indicates the state of the group. See [`MlsGroupState`]
     // for more information.
     group_state: MlsGroupState,
-    // A flag that indicates if the group state has changed and needs to be persisted again. The value
-    // is set to `InnerState::Changed` whenever an the internal group state is change and is set to
-    // `InnerState::Persisted` once the state has been persisted.
-    state_changed: InnerState,
+    // Information on why and when the own client left the group. This is
+    // only set if the group is inactive.
+    departure: Option<Departure>,
+    // The state that is changed when application messages are processed
+    // through a shared reference. See [`processing::ReceiverState`].
+    receiver_state: Mutex<processing::ReceiverState>,
+    // Messages from future epochs that are processed once the group reaches
+    // their epoch. See [`MlsGroup::process_buffered_messages()`].
+    message_buffer: Mutex<Vec<ProtocolMessage>>,
+    // The buffered messages that were processed when a commit was merged. It
+    // is not part of the stored group state. See
+    // [`MlsGroup::take_processed_buffered_messages()`].
+    processed_buffered_messages: Vec<ProcessedMessage>,
+    // The digests of the handshake messages created by the own client in the
+    // current epoch, which are used to recognize them when the DS echoes
+    // them. See [`ProcessMessageError::OwnCommit`].
+    own_messages: Option<processing::OwnMessageDigests>,
+    // The proposals of the last pending commit that was discarded because
+    // another commit was merged for its epoch. See
+    // [`MlsGroup::rebase_pending_commit()`].
+    lost_commit: Option<rebase::LostCommit>,
+    // The policy for the credentials of new leaf nodes. It is not part of the
+    // stored group state. See [`MlsGroup::set_credential_validator()`].
+    credential_validator: Option<Arc<dyn CredentialValidator>>,
+    // The policy for the commits processed by the group. It is not part of
+    // the stored group state. See [`MlsGroup::set_commit_validator()`].
+    commit_validator: Option<Arc<dyn CommitValidator>>,
+    // Notified about the changes of the group. It is not part of the stored
+    // group state. See [`MlsGroup::set_observer()`].
+    observer: Option<Arc<dyn GroupObserver>>,
+    // Resolves the PSKs that are not in the key store. It is not part of the
+    // stored group state. See [`MlsGroup::set_psk_resolver()`].
+    psk_resolver: Option<Arc<dyn PskResolver>>,
+    // The log of the operations of the group. See [`MlsGroup::event_log()`].
+    event_log: VecDeque<event_log::GroupEvent>,
+    // The timestamp of new events. It is not part of the stored group state.
+    // See [`MlsGroup::set_event_timestamp()`].
+    event_timestamp: Option<u64>,
+    // The secrets exported for typed exporter labels in the current epoch.
+    // It is not part of the stored group state. See [`MlsGroup::export()`].
+    exporter_cache: Mutex<exporter::ExporterCache>,
+    // Serializes the writes of the group state and the receiver state, which
+    // can happen concurrently through
+    // [`MlsGroup::process_application_message()`].
+    store_lock: Mutex<()>,
 }
 
 impl MlsGroup {
@@ -180,12 +280,122 @@ impl MlsGroup {
         &self.mls_group_config
     }
 
-    /// Sets the configuration.
-    pub fn set_configuration(&mut self, mls_group_config: &MlsGroupConfig) {
+    /// Sets the configuration and writes the group state to the storage of
+    /// the `backend`.
+    ///
+    /// The [`MessageSecretsPolicy`] of the new configuration is applied
+    /// immediately, i.e. the secrets of past epochs and the keys of skipped
+    /// messages that it no longer permits to keep are dropped, and so are the
+    /// oldest resumption PSKs beyond the configured number. The
+    /// [`LifetimePolicy`](crate::key_packages::LifetimePolicy) applies to all
+    /// Add proposals that are committed afterwards.
+    pub fn set_configuration<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        mls_group_config: &MlsGroupConfig,
+    ) -> Result<(), KeyStore::Error> {
         self.mls_group_config = mls_group_config.clone();
+        self.group
+            .set_past_epoch_retention(&self.mls_group_config.past_epoch_retention());
+        self.group
+            .message_secrets_store_mut()
+            .prune_skipped_keys(self.mls_group_config.sender_ratchet_configuration());
+        self.group
+            .set_lifetime_policy(*self.mls_group_config.lifetime_policy());
+        self.group
+            .set_leaf_placement_strategy(self.mls_group_config.leaf_placement_strategy());
+        self.group
+            .set_decode_limits(*self.mls_group_config.decode_limits());
+        self.group
+            .set_number_of_resumption_psks(self.mls_group_config.number_of_resumption_psks());
+        self.truncate_event_log();
+        // The exporter quirks of the labels may have changed.
+        self.clear_exporter_cache();
+        self.store(backend)
+    }
 
-        // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+    /// Sets the [`CredentialValidator`] that is called for the credential of
+    /// every leaf node that enters the tree through a commit processed with
+    /// [`MlsGroup::process_message()`]. If the validator rejects a credential,
+    /// processing fails with [`ProcessMessageError::CredentialRejected`].
+    ///
+    /// The validator is not part of the stored group state and has to be set
+    /// again after [`MlsGroup::load()`].
+    pub fn set_credential_validator(&mut self, validator: impl CredentialValidator + 'static) {
+        self.credential_validator = Some(Arc::new(validator));
+    }
+
+    /// Removes the [`CredentialValidator`] of the group, if any.
+    pub fn clear_credential_validator(&mut self) {
+        self.credential_validator = None;
+    }
+
+    /// Sets the [`CommitValidator`] that is called for every commit processed
+    /// with [`MlsGroup::process_message()`], after the commit was staged and
+    /// its credentials were validated. If the validator rejects the commit,
+    /// processing fails with [`ProcessMessageError::CommitRejected`] and the
+    /// commit can't be merged.
+    ///
+    /// The validator is not part of the stored group state and has to be set
+    /// again after [`MlsGroup::load()`].
+    pub fn set_commit_validator(&mut self, validator: impl CommitValidator + 'static) {
+        self.commit_validator = Some(Arc::new(validator));
+    }
+
+    /// Removes the [`CommitValidator`] of the group, if any.
+    pub fn clear_commit_validator(&mut self) {
+        self.commit_validator = None;
+    }
+
+    /// Sets the [`PskResolver`] that is queried for the PSKs of commits that
+    /// can't be found in the key store, both when creating commits and when
+    /// processing them with [`MlsGroup::process_message()`].
+    ///
+    /// The resolver is not part of the stored group state and has to be set
+    /// again after [`MlsGroup::load()`].
+    pub fn set_psk_resolver(&mut self, psk_resolver: impl PskResolver + 'static) {
+        self.psk_resolver = Some(Arc::new(psk_resolver));
+    }
+
+    /// Removes the [`PskResolver`] of the group, if any.
+    pub fn clear_psk_resolver(&mut self) {
+        self.psk_resolver = None;
+    }
+
+    /// Sets a custom [`LeafPlacement`] that decides where the leaf nodes of
+    /// added members are put in the tree, instead of the
+    /// [`LeafPlacementStrategy`](crate::treesync::LeafPlacementStrategy) of
+    /// the [`MlsGroupConfig`]. It is used both when creating commits and when
+    /// processing them, so all members of the group must use the same
+    /// placement.
+    ///
+    /// The placement is not part of the stored group state and has to be set
+    /// again after [`MlsGroup::load()`].
+    pub fn set_leaf_placement(&mut self, leaf_placement: impl LeafPlacement + 'static) {
+        self.group.set_leaf_placement(leaf_placement);
+    }
+
+    /// Removes the custom [`LeafPlacement`] of the group, if any, so that the
+    /// strategy of the [`MlsGroupConfig`] is used again.
+    pub fn clear_leaf_placement(&mut self) {
+        self.group.clear_leaf_placement();
+    }
+
+    /// Sets the [`SignatureCache`] that is consulted before verifying a
+    /// signature of a message processed with [`MlsGroup::process_message()`],
+    /// e.g. the signatures of a commit and of the leaf nodes and proposals it
+    /// contains. Signatures that were verified successfully are added to the
+    /// cache. The same cache can be shared by several groups.
+    ///
+    /// The cache is not part of the stored group state and has to be set
+    /// again after [`MlsGroup::load()`].
+    pub fn set_signature_cache(&mut self, signature_cache: Arc<SignatureCache>) {
+        self.group.set_signature_cache(signature_cache);
+    }
+
+    /// Removes the [`SignatureCache`] of the group, if any.
+    pub fn clear_signature_cache(&mut self) {
+        self.group.clear_signature_cache();
     }
 
     /// Returns the AAD used in the framing.
@@ -193,12 +403,15 @@ impl MlsGroup {
         &self.aad
     }
 
-    /// Sets the AAD used in the framing.
-    pub fn set_aad(&mut self, aad: &[u8]) {
+    /// Sets the AAD used in the framing and writes the group state to the
+    /// storage of the `backend`.
+    pub fn set_aad<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        aad: &[u8],
+    ) -> Result<(), KeyStore::Error> {
         self.aad = aad.to_vec();
-
-        // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.store(backend)
     }
 
     // === Advanced functions ===
@@ -214,6 +427,18 @@ impl MlsGroup {
         !matches!(self.group_state, MlsGroupState::Inactive)
     }
 
+    /// Returns whether the own client requested to leave the group and is
+    /// waiting for a commit that removes it. See [`MlsGroupState::PendingLeave`].
+    pub fn is_leaving(&self) -> bool {
+        matches!(self.group_state, MlsGroupState::PendingLeave)
+    }
+
+    /// Returns information on why and when the own client left the group, or
+    /// `None` if the group is still active.
+    pub fn departure(&self) -> Option<&Departure> {
+        self.departure.as_ref()
+    }
+
     /// Returns own credential. If the group is inactive, it returns a
     /// `UseAfterEviction` error.
     pub fn credential(&self) -> Result<&Credential, MlsGroupStateError> {
@@ -252,11 +477,38 @@ impl MlsGroup {
         self.group.context().epoch()
     }
 
+    /// Returns the external senders of the group, if the group has an
+    /// [`ExternalSendersExtension`].
+    pub fn external_senders(&self) -> Option<&ExternalSendersExtension> {
+        self.group.context().extensions().external_senders()
+    }
+
     /// Returns an `Iterator` over pending proposals.
     pub fn pending_proposals(&self) -> impl Iterator<Item = &QueuedProposal> {
         self.proposal_store.proposals()
     }
 
+    /// Returns the [`ProposalStore`] with the pending proposals of the current
+    /// epoch, e.g. to look up a proposal by its reference or to check the
+    /// proposals for conflicts with [`ProposalStore::conflicts()`].
+    ///
+    /// The store is written to the storage together with the group state and
+    /// restored by [`MlsGroup::load()`]. Proposals are removed with
+    /// [`MlsGroup::remove_pending_proposal()`].
+    pub fn proposal_store(&self) -> &ProposalStore {
+        &self.proposal_store
+    }
+
+    /// Returns an `Iterator` over the pending proposals that were sent by the
+    /// own client, e.g. to cancel them with
+    /// [`MlsGroup::cancel_own_proposal()`].
+    pub fn own_pending_proposals(&self) -> impl Iterator<Item = &QueuedProposal> {
+        let own_leaf_index = self.own_leaf_index();
+        self.proposal_store
+            .proposals()
+            .filter(move |proposal| proposal.sender() == &Sender::Member(own_leaf_index))
+    }
+
     /// Returns a reference to the [`StagedCommit`] of the most recently created
     /// commit. If there was no commit created in this epoch, either because
     /// this commit or another commit was merged, it returns `None`.
@@ -265,8 +517,9 @@ impl MlsGroup {
             MlsGroupState::PendingCommit(ref pending_commit_state) => {
                 Some(pending_commit_state.staged_commit())
             }
-            MlsGroupState::Operational => None,
-            MlsGroupState::Inactive => None,
+            MlsGroupState::Operational | MlsGroupState::Inactive | MlsGroupState::PendingLeave => {
+                None
+            }
         }
     }
 
@@ -281,41 +534,191 @@ impl MlsGroup {
     /// the pending commit will not be used in the group. In particular, if a
     /// pending commit is later accepted by the group, this client will lack the
     /// key material to encrypt or decrypt group messages.
-    pub fn clear_pending_commit(&mut self) {
+    ///
+    /// The group state is written to the storage of the `backend`.
+    pub fn clear_pending_commit<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<(), KeyStore::Error> {
+        self.discard_pending_commit();
+        self.store(backend)
+    }
+
+    /// Clears a pending commit of the own client without writing the group
+    /// state. See [`MlsGroup::clear_pending_commit()`].
+    fn discard_pending_commit(&mut self) {
         match self.group_state {
             MlsGroupState::PendingCommit(ref pending_commit_state) => {
                 if let PendingCommitState::Member(_) = **pending_commit_state {
                     self.group_state = MlsGroupState::Operational
                 }
             }
-            MlsGroupState::Operational | MlsGroupState::Inactive => (),
+            MlsGroupState::Operational | MlsGroupState::Inactive | MlsGroupState::PendingLeave => {}
         }
     }
 
-    // === Load & save ===
+    // === Past epoch secrets ===
 
-    /// Loads the state from persisted state.
-    pub fn load(group_id: &GroupId, backend: &impl OpenMlsCryptoProvider) -> Option<MlsGroup> {
-        backend.key_store().read(group_id.as_slice())
+    /// Returns the past epochs for which message secrets are kept, from the
+    /// oldest to the newest. Application messages from these epochs can still
+    /// be decrypted. See [`PastEpochRetention`].
+    pub fn past_epochs(&self) -> Vec<GroupEpoch> {
+        self.group.message_secrets_store().past_epochs()
+    }
+
+    /// Returns the [`MessageSecretsPolicy`] of the group.
+    pub fn message_secrets_policy(&self) -> MessageSecretsPolicy {
+        self.mls_group_config.message_secrets_policy()
     }
 
-    /// Persists the state.
-    pub fn save<KeyStore: OpenMlsKeyStore>(
+    /// Sets the [`MessageSecretsPolicy`] of the group, e.g. to tolerate more
+    /// delayed messages during a known network outage and fewer afterwards.
+    ///
+    /// A tightened policy is applied immediately, i.e. the secrets of past
+    /// epochs and the keys of skipped messages that it no longer permits to
+    /// keep are dropped. A relaxed policy only applies to secrets of future
+    /// epochs and messages, since dropped secrets can't be recovered.
+    ///
+    /// The group state is written to the storage of the `backend`.
+    pub fn set_message_secrets_policy<KeyStore: OpenMlsKeyStore>(
         &mut self,
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        message_secrets_policy: MessageSecretsPolicy,
     ) -> Result<(), KeyStore::Error> {
-        backend
-            .key_store()
-            .store(self.group_id().as_slice(), &*self)?;
+        let mut mls_group_config = self.mls_group_config.clone();
+        let past_epoch_retention = message_secrets_policy.past_epoch_retention();
+        mls_group_config.max_past_epochs = past_epoch_retention.max_epochs();
+        mls_group_config.max_past_epoch_age = past_epoch_retention.max_age();
+        mls_group_config.sender_ratchet_configuration = message_secrets_policy
+            .sender_ratchet_configuration()
+            .clone();
+        self.set_configuration(backend, &mls_group_config)
+    }
 
-        self.state_changed = InnerState::Persisted;
-        Ok(())
+    /// Drops the message secrets of all past epochs before `epoch`, e.g. to
+    /// enforce forward secrecy for these epochs before an audit. Passing the
+    /// current [`MlsGroup::epoch()`] drops the secrets of all past epochs.
+    ///
+    /// The group state is written to the storage of the `backend`. Returns
+    /// the number of epochs for which secrets were dropped.
+    pub fn drop_past_epochs<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        epoch: GroupEpoch,
+    ) -> Result<usize, KeyStore::Error> {
+        let dropped = self
+            .group
+            .message_secrets_store_mut()
+            .drop_epochs_before(epoch);
+        self.store(backend)?;
+        Ok(dropped)
+    }
+
+    /// Drops the message secrets of all past epochs that are older than the
+    /// maximum age of the [`PastEpochRetention`] policy.
+    ///
+    /// Expired secrets are also dropped whenever the group processes a message
+    /// or merges a commit. This function allows applications to drop them
+    /// while the group is idle.
+    ///
+    /// The group state is written to the storage of the `backend`. Returns
+    /// the number of epochs for which secrets were dropped.
+    pub fn drop_expired_past_epochs<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<usize, KeyStore::Error> {
+        let dropped = self.group.message_secrets_store_mut().drop_expired();
+        self.store(backend)?;
+        Ok(dropped)
+    }
+
+    // === Storage ===
+
+    /// Loads the group with the given [`GroupId`] from the storage of the
+    /// `backend`.
+    ///
+    /// All functions that change the state of an [`MlsGroup`] write it to the
+    /// storage of the `backend` they are called with. The stored group
+    /// state therefore always reflects the last successful operation.
+    ///
+    /// The proposals that were queued in the current epoch are stored
+    /// separately and loaded together with the group state, so they can still
+    /// be committed after a restart.
+    ///
+    /// So are the sender ratchets and the rest of the state that
+    /// [`MlsGroup::process_application_message()`] writes without the group
+    /// state.
+    pub fn load(group_id: &GroupId, backend: &impl OpenMlsCryptoProvider) -> Option<MlsGroup> {
+        let mut mls_group: MlsGroup = backend.key_store().load(StorageKey::GroupState {
+            group_id: group_id.as_slice(),
+        })?;
+        let proposal_store = backend.key_store().load(mls_group.proposal_store_key());
+        if let Some(proposal_store) = proposal_store {
+            mls_group.proposal_store = proposal_store;
+        }
+        let receiver_state: Option<processing::ReceiverState> =
+            backend.key_store().load(StorageKey::ReceiverState {
+                group_id: group_id.as_slice(),
+            });
+        if let Some(receiver_state) = receiver_state {
+            let mut sender_ratchets = Vec::new();
+            for &(epoch, leaf_index) in receiver_state.sender_ratchets() {
+                if let Some(ratchets) = backend
+                    .key_store()
+                    .load(mls_group.sender_ratchets_key(epoch, leaf_index))
+                {
+                    sender_ratchets.push((epoch, leaf_index, ratchets));
+                }
+            }
+            mls_group.restore_receiver_state(receiver_state, sender_ratchets);
+        }
+        Some(mls_group)
     }
 
-    /// Returns `true` if the internal state has changed and needs to be persisted and
-    /// `false` otherwise. Calling [`Self::save()`] resets the value to `false`.
-    pub fn state_changed(&self) -> InnerState {
-        self.state_changed
+    /// Deletes the state of this group from the storage of the `backend`,
+    /// i.e. the group state, the proposals queued in the current epoch, the
+    /// encryption key pairs of the own leaf in the current epoch and the
+    /// sender ratchets that were written without the group state.
+    ///
+    /// The in-memory group is not changed, but [`MlsGroup::load()`] won't find
+    /// the group anymore. This should be called once the group isn't needed
+    /// anymore, e.g. after the client left it.
+    ///
+    /// Returns an error if access to the storage fails.
+    pub fn delete<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<(), KeyStore::Error> {
+        let _guard = lock(&self.store_lock);
+        let group_id = self.group_id().as_slice();
+        // The stored receiver state may list sender ratchets that this
+        // instance of the group doesn't know about
+        let stored_receiver_state: Option<processing::ReceiverState> = backend
+            .key_store()
+            .load(StorageKey::ReceiverState { group_id });
+        let receiver_state = self.receiver_state();
+        let sender_ratchets = receiver_state.sender_ratchets().iter().chain(
+            stored_receiver_state
+                .iter()
+                .flat_map(|receiver_state| receiver_state.sender_ratchets()),
+        );
+        for &(epoch, leaf_index) in sender_ratchets {
+            backend
+                .key_store()
+                .remove::<SenderRatchets>(self.sender_ratchets_key(epoch, leaf_index))?;
+        }
+        backend
+            .key_store()
+            .remove::<processing::ReceiverState>(StorageKey::ReceiverState { group_id })?;
+        backend
+            .key_store()
+            .remove::<Vec<EncryptionKeyPair>>(self.group.epoch_keypairs_key())?;
+        backend
+            .key_store()
+            .remove::<ProposalStore>(self.proposal_store_key())?;
+        backend
+            .key_store()
+            .remove::<MlsGroup>(StorageKey::GroupState { group_id })
     }
 
     // === Extensions ===
@@ -324,6 +727,38 @@ impl MlsGroup {
     pub fn export_ratchet_tree(&self) -> RatchetTree {
         self.group.public_group().export_ratchet_tree()
     }
+
+    /// Returns the size in bytes of the serialized extension that carries the
+    /// ratchet tree of the current epoch in GroupInfos and Welcomes, e.g. to
+    /// check it against the message size limit of the DS.
+    ///
+    /// The tree is compressed if the group requires
+    /// [`ExtensionType::CompressedRatchetTree`](crate::extensions::ExtensionType::CompressedRatchetTree).
+    pub fn ratchet_tree_extension_size(&self) -> Result<usize, LibraryError> {
+        self.group
+            .context()
+            .ratchet_tree_extension(self.export_ratchet_tree())
+            .map(|extension| extension.tls_serialized_len())
+    }
+
+    /// Returns the size in bytes of the serialized
+    /// [`CompressedRatchetTreeExtension`](crate::extensions::CompressedRatchetTreeExtension)
+    /// of the current epoch, independent of whether the group requires it.
+    ///
+    /// This can be compared with [`MlsGroup::ratchet_tree_extension_size()`]
+    /// to decide whether to require the compressed ratchet tree.
+    #[cfg(feature = "tree-compression")]
+    pub fn compressed_ratchet_tree_extension_size(&self) -> Result<usize, LibraryError> {
+        crate::extensions::CompressedRatchetTreeExtension::new(self.export_ratchet_tree())
+            .map(|extension| Extension::CompressedRatchetTree(extension).tls_serialized_len())
+    }
+}
+
+/// Locks a mutex of an [`MlsGroup`]. The values behind the locks are never
+/// left half-written, so a lock that was poisoned by a panic of another
+/// thread is recovered.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
 }
 
 // Private methods of MlsGroup
@@ -336,7 +771,20 @@ impl MlsGroup {
         mls_auth_content: AuthenticatedContent,
         backend: &impl OpenMlsCryptoProvider,
     ) -> Result<MlsMessageOut, LibraryError> {
-        let msg = match self.configuration().wire_format_policy().outgoing() {
+        let wire_format = self.configuration().wire_format_policy().outgoing();
+        self.content_to_mls_message_with_wire_format(mls_auth_content, wire_format, backend)
+    }
+
+    /// Same as [`MlsGroup::content_to_mls_message()`], but with the given
+    /// `wire_format` instead of the one of the outgoing wire format policy.
+    fn content_to_mls_message_with_wire_format(
+        &mut self,
+        mls_auth_content: AuthenticatedContent,
+        wire_format: OutgoingWireFormatPolicy,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<MlsMessageOut, LibraryError> {
+        let content_type = mls_auth_content.content().content_type();
+        let msg = match wire_format {
             OutgoingWireFormatPolicy::AlwaysPlaintext => {
                 let mut plaintext: PublicMessage = mls_auth_content.into();
                 // Set the membership tag only if the sender type is `Member`.
@@ -354,7 +802,7 @@ impl MlsGroup {
                     .group
                     .encrypt(
                         mls_auth_content,
-                        self.configuration().padding_size(),
+                        self.configuration().padding_policy(),
                         backend,
                     )
                     // We can be sure the encryption will work because the plaintext was created by us
@@ -362,12 +810,228 @@ impl MlsGroup {
                 MlsMessageOut::from_private_message(ciphertext, self.group.version())
             }
         };
+        // Remember the handshake message to recognize it when the DS echoes it
+        if content_type.is_handshake_message() {
+            let serialized = msg
+                .tls_serialize_detached()
+                .map_err(LibraryError::missing_bound_check)?;
+            let digest = backend
+                .crypto()
+                .hash(self.ciphersuite().hash_algorithm(), &serialized)
+                .map_err(LibraryError::unexpected_crypto_error)?;
+            self.record_own_message(content_type, digest);
+        }
         Ok(msg)
     }
 
-    /// Arm the state changed flag function
-    fn flag_state_change(&mut self) {
-        self.state_changed = InnerState::Changed;
+    /// Write the group state and the proposals queued in the current epoch to
+    /// the storage of the `backend`.
+    ///
+    /// This must be called after every change of the group state.
+    fn store<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<(), KeyStore::Error> {
+        // The state is serialized while the lock is held, so that it can't
+        // interleave with the writes of `store_receiver_state()`.
+        let _guard = lock(&self.store_lock);
+        backend.key_store().write(
+            StorageKey::GroupState {
+                group_id: self.group_id().as_slice(),
+            },
+            self,
+        )?;
+        backend
+            .key_store()
+            .write(self.proposal_store_key(), &self.proposal_store)?;
+
+        // The group state contains everything that was written without it
+        let mut receiver_state = self.receiver_state();
+        if !receiver_state.is_written() {
+            return Ok(());
+        }
+        for &(epoch, leaf_index) in receiver_state.sender_ratchets() {
+            backend
+                .key_store()
+                .remove::<SenderRatchets>(self.sender_ratchets_key(epoch, leaf_index))?;
+        }
+        backend
+            .key_store()
+            .remove::<processing::ReceiverState>(StorageKey::ReceiverState {
+                group_id: self.group_id().as_slice(),
+            })?;
+        receiver_state.clear_written();
+        Ok(())
+    }
+
+    /// Writes the sender ratchets of the `sender`, i.e. its leaf index in an
+    /// epoch, and the [`processing::ReceiverState`] to the storage of the
+    /// `backend`, without the rest of the group state. They are loaded
+    /// together with the group state and deleted when it is written the next
+    /// time. See [`MlsGroup::process_application_message()`].
+    fn store_receiver_state<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        sender: Option<(GroupEpoch, LeafNodeIndex)>,
+    ) -> Result<(), KeyStore::Error> {
+        // The ratchets are copied while the lock is held, so that the last
+        // write contains all changes of concurrent writers.
+        let _guard = lock(&self.store_lock);
+        let mut receiver_state = self.receiver_state();
+        if let Some((epoch, leaf_index)) = sender {
+            let sender_ratchets =
+                self.group
+                    .message_secrets_for_epoch(epoch)
+                    .ok()
+                    .and_then(|message_secrets| {
+                        message_secrets.secret_tree().sender_ratchets(leaf_index)
+                    });
+            if let Some(sender_ratchets) = sender_ratchets {
+                backend.key_store().write(
+                    self.sender_ratchets_key(epoch, leaf_index),
+                    &sender_ratchets,
+                )?;
+                receiver_state.add_sender_ratchets(epoch, leaf_index);
+            }
+        }
+        receiver_state.mark_written();
+        backend.key_store().write(
+            StorageKey::ReceiverState {
+                group_id: self.group_id().as_slice(),
+            },
+            &*receiver_state,
+        )
+    }
+
+    /// Restores the state that was written by
+    /// [`MlsGroup::store_receiver_state()`] after the group state was
+    /// loaded.
+    pub(crate) fn restore_receiver_state(
+        &mut self,
+        receiver_state: processing::ReceiverState,
+        sender_ratchets: Vec<(GroupEpoch, LeafNodeIndex, SenderRatchets)>,
+    ) {
+        self.receiver_state_mut().merge(receiver_state);
+        // The secrets are not restored after the removal grace period ended
+        if !self.is_active() && !self.is_draining() {
+            return;
+        }
+        for (epoch, leaf_index, sender_ratchets) in sender_ratchets {
+            // The secrets of the epoch might have been deleted since
+            if let Ok(message_secrets) = self.group.message_secrets_mut(epoch) {
+                message_secrets
+                    .secret_tree_mut()
+                    .restore_sender_ratchets(leaf_index, sender_ratchets);
+            }
+        }
+    }
+
+    /// Locks the [`processing::ReceiverState`] of the group.
+    pub(crate) fn receiver_state(&self) -> MutexGuard<'_, processing::ReceiverState> {
+        lock(&self.receiver_state)
+    }
+
+    /// Returns the [`processing::ReceiverState`] of the group without locking
+    /// it.
+    pub(crate) fn receiver_state_mut(&mut self) -> &mut processing::ReceiverState {
+        self.receiver_state
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Restores the group from its state in the storage of the `backend`,
+    /// e.g. after the writes of a failed merge were discarded. The validators,
+    /// the observer and the other settings that are not part of the stored
+    /// state are kept.
+    ///
+    /// Returns an error if the storage doesn't contain the group.
+    pub(crate) fn reload(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+    ) -> Result<(), LibraryError> {
+        let stored = MlsGroup::load(self.group_id(), backend)
+            .ok_or_else(|| LibraryError::custom("The group is missing from the storage."))?;
+        *self = MlsGroup {
+            credential_validator: self.credential_validator.take(),
+            commit_validator: self.commit_validator.take(),
+            observer: self.observer.take(),
+            psk_resolver: self.psk_resolver.take(),
+            event_timestamp: self.event_timestamp,
+            // The buffer is only written with the next change of the group
+            // state, so it may be newer than the stored one
+            message_buffer: std::mem::take(&mut self.message_buffer),
+            processed_buffered_messages: std::mem::take(&mut self.processed_buffered_messages),
+            ..stored
+        };
+        Ok(())
+    }
+
+    /// Returns a copy of this group, including the settings that are not part
+    /// of the stored state. The copy shares the nodes of the ratchet tree
+    /// with this group until they are changed.
+    pub(crate) fn copy(&self) -> MlsGroup {
+        MlsGroup {
+            mls_group_config: self.mls_group_config.clone(),
+            group: self.group.clone(),
+            proposal_store: self.proposal_store.clone(),
+            own_leaf_nodes: self.own_leaf_nodes.clone(),
+            aad: self.aad.clone(),
+            group_state: self.group_state.clone(),
+            departure: self.departure.clone(),
+            receiver_state: Mutex::new(self.receiver_state().clone()),
+            message_buffer: Mutex::new(lock(&self.message_buffer).clone()),
+            processed_buffered_messages: Vec::new(),
+            own_messages: self.own_messages.clone(),
+            lost_commit: self.lost_commit.clone(),
+            credential_validator: self.credential_validator.clone(),
+            commit_validator: self.commit_validator.clone(),
+            observer: self.observer.clone(),
+            psk_resolver: self.psk_resolver.clone(),
+            event_log: self.event_log.clone(),
+            event_timestamp: self.event_timestamp,
+            // The cached secrets are taken over by `replace_with_copy()` if
+            // they are still valid.
+            exporter_cache: Mutex::default(),
+            store_lock: Mutex::new(()),
+        }
+    }
+
+    /// Replaces this group with a modified copy of it (see
+    /// [`MlsGroup::copy()`]). The observer of this group is kept, and so are
+    /// the cached exported secrets if the epoch didn't change. The processed
+    /// buffered messages of both are kept.
+    pub(crate) fn replace_with_copy(&mut self, mut group: MlsGroup) {
+        group.observer = self.observer.take();
+        group
+            .processed_buffered_messages
+            .splice(0..0, self.processed_buffered_messages.drain(..));
+        if group.epoch() == self.epoch() {
+            group.exporter_cache = std::mem::take(&mut self.exporter_cache);
+        }
+        *self = group;
+    }
+
+    /// Returns the [`StorageKey`] of the sender ratchets of the leaf with the
+    /// given index in the `epoch` that were written without the group state.
+    pub(crate) fn sender_ratchets_key(
+        &self,
+        epoch: GroupEpoch,
+        leaf_index: LeafNodeIndex,
+    ) -> StorageKey<'_> {
+        StorageKey::SenderRatchets {
+            group_id: self.group_id().as_slice(),
+            epoch: epoch.as_u64(),
+            leaf_index: leaf_index.u32(),
+        }
+    }
+
+    /// Returns the [`StorageKey`] of the proposals queued in this group's
+    /// current [`GroupEpoch`].
+    pub(crate) fn proposal_store_key(&self) -> StorageKey<'_> {
+        StorageKey::QueuedProposals {
+            group_id: self.group_id().as_slice(),
+            epoch: self.epoch().as_u64(),
+        }
     }
 
     /// Group framing parameters
@@ -379,11 +1043,13 @@ impl MlsGroup {
     }
 
     /// Check if the group is operational. Throws an error if the group is
-    /// inactive or if there is a pending commit.
+    /// inactive, if there is a pending commit or if the client is leaving the
+    /// group.
     fn is_operational(&self) -> Result<(), MlsGroupStateError> {
         match self.group_state {
             MlsGroupState::PendingCommit(_) => Err(MlsGroupStateError::PendingCommit),
             MlsGroupState::Inactive => Err(MlsGroupStateError::UseAfterEviction),
+            MlsGroupState::PendingLeave => Err(MlsGroupStateError::PendingLeave),
             MlsGroupState::Operational => Ok(()),
         }
     }
@@ -418,24 +1084,17 @@ impl MlsGroup {
         self.proposal_store.empty()
     }
 
-    /// Removes a specific proposal from the store.
-    pub fn remove_pending_proposal(
+    /// Removes a specific proposal from the store and writes the group state
+    /// to the storage of the `backend`.
+    pub fn remove_pending_proposal<KeyStore: OpenMlsKeyStore>(
         &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         proposal_ref: ProposalRef,
-    ) -> Result<(), MlsGroupStateError> {
+    ) -> Result<(), RemoveProposalError<KeyStore::Error>> {
         self.proposal_store
             .remove(proposal_ref)
-            .ok_or(MlsGroupStateError::PendingProposalNotFound)
+            .ok_or(MlsGroupStateError::PendingProposalNotFound)?;
+        self.store(backend)
+            .map_err(RemoveProposalError::StorageError)
     }
 }
-
-/// `Enum` that indicates whether the inner group state has been modified since the last time it was persisted.
-/// `InnerState::Changed` indicates that the state has changed and that [`.save()`] should be called.
-/// `InnerState::Persisted` indicates that the state has not been modified and therefore doesn't need to be persisted.
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-pub enum InnerState {
-    /// The inner group state has changed and needs to be persisted.
-    Changed,
-    /// The inner group state hasn't changed and doesn't need to be persisted.
-    Persisted,
-}
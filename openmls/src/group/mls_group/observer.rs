@@ -0,0 +1,188 @@
+//! # Group observers
+//!
+//! Applications often need to react to changes of a group, e.g. to update a
+//! member list in the UI, without inspecting every [`ProcessedMessage`] and
+//! [`StagedCommit`] themselves. A [`GroupObserver`] that is registered with
+//! [`MlsGroup::set_observer()`](crate::group::MlsGroup::set_observer()) is
+//! notified with a [`GroupNotification`]
+//!  - for every proposal that is processed with
+//!    [`MlsGroup::process_message()`](crate::group::MlsGroup::process_message()),
+//!    and
+//!  - after a commit was merged, for each added and removed member, for the
+//!    own removal, for a `ReInit` proposal and finally for the new epoch.
+//!    A client that was removed doesn't move to the new epoch.
+//!
+//! Functions and closures with the signature of [`GroupObserver::notify()`]
+//! are observers:
+//!
+//! ```
+//! use openmls::prelude::*;
+//!
+//! fn observe(group: &mut MlsGroup) {
+//!     group.set_observer(|group_id: &GroupId, notification: &GroupNotification| {
+//!         if let GroupNotification::MemberAdded { credential, .. } = notification {
+//!             println!("{:?} joined {:?}", credential.identity(), group_id);
+//!         }
+//!     });
+//! }
+//! ```
+
+use std::fmt::Debug;
+
+use super::*;
+
+/// A change of a group that a [`GroupObserver`] is notified about. See the
+/// [module documentation](self) for when which notification is sent.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GroupNotification {
+    /// A proposal was processed. It only takes effect once it is committed.
+    ProposalReceived {
+        /// The reference of the proposal.
+        proposal_ref: ProposalRef,
+        /// The type of the proposal.
+        proposal_type: ProposalType,
+        /// The sender of the proposal.
+        sender: Sender,
+    },
+    /// A member was added by a merged commit.
+    MemberAdded {
+        /// The leaf index of the new member.
+        leaf_index: LeafNodeIndex,
+        /// The credential of the new member.
+        credential: Credential,
+    },
+    /// A member was removed by a merged commit.
+    MemberRemoved {
+        /// The leaf index of the member in the epoch of the commit.
+        leaf_index: LeafNodeIndex,
+        /// The credential of the removed member.
+        credential: Credential,
+    },
+    /// The own client was removed by a merged commit. See
+    /// [`MlsGroup::departure()`](crate::group::MlsGroup::departure()).
+    OwnRemoval(DepartureReason),
+    /// A merged commit covers a `ReInit` proposal, so the members are
+    /// expected to move to a new group.
+    ReInit(ReInitProposal),
+    /// A commit was merged and the group moved to the given epoch. Not sent
+    /// if the commit removed the own client.
+    EpochChanged(GroupEpoch),
+}
+
+/// An observer of the changes of a group.
+///
+/// See the [module documentation](self) for when it is called.
+pub trait GroupObserver: Send + Sync {
+    /// Notifies the observer about a change of the group with the given ID.
+    fn notify(&self, group_id: &GroupId, notification: &GroupNotification);
+}
+
+impl<F> GroupObserver for F
+where
+    F: Fn(&GroupId, &GroupNotification) + Send + Sync,
+{
+    fn notify(&self, group_id: &GroupId, notification: &GroupNotification) {
+        self(group_id, notification)
+    }
+}
+
+impl Debug for dyn GroupObserver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("GroupObserver")
+    }
+}
+
+impl MlsGroup {
+    /// Sets the [`GroupObserver`] that is notified about the changes of the
+    /// group. See the [module documentation](self) for when it is called.
+    ///
+    /// The observer is not part of the stored group state and has to be set
+    /// again after [`MlsGroup::load()`]. Operations on a speculative copy of
+    /// the group are not observed.
+    pub fn set_observer(&mut self, observer: impl GroupObserver + 'static) {
+        self.observer = Some(Arc::new(observer));
+    }
+
+    /// Removes the [`GroupObserver`] of the group, if any.
+    pub fn clear_observer(&mut self) {
+        self.observer = None;
+    }
+
+    /// Notifies the observer about the proposal of a processed message, if
+    /// any.
+    pub(super) fn notify_processed_message(&self, processed_message: &ProcessedMessage) {
+        let Some(observer) = &self.observer else {
+            return;
+        };
+        let (ProcessedMessageContent::ProposalMessage(queued_proposal)
+        | ProcessedMessageContent::ExternalJoinProposalMessage(queued_proposal)) =
+            processed_message.content()
+        else {
+            return;
+        };
+        observer.notify(
+            self.group_id(),
+            &GroupNotification::ProposalReceived {
+                proposal_ref: queued_proposal.proposal_reference(),
+                proposal_type: queued_proposal.proposal().proposal_type(),
+                sender: queued_proposal.sender().clone(),
+            },
+        );
+    }
+
+    /// Returns the notifications about the members added and removed by a
+    /// staged commit and its `ReInit` proposal. Must be called before the
+    /// commit is merged.
+    pub(super) fn staged_commit_notifications(
+        &self,
+        staged_commit: &StagedCommit,
+    ) -> Vec<GroupNotification> {
+        if self.observer.is_none() {
+            return vec![];
+        }
+        let added_members =
+            staged_commit
+                .added_members()
+                .map(|member| GroupNotification::MemberAdded {
+                    leaf_index: member.leaf_index(),
+                    credential: member.credential().clone(),
+                });
+        let removed_members =
+            staged_commit
+                .removed_members()
+                .iter()
+                .map(|member| GroupNotification::MemberRemoved {
+                    leaf_index: member.leaf_index(),
+                    credential: member.credential().clone(),
+                });
+        let reinit = staged_commit
+            .queued_proposals()
+            .filter_map(|queued_proposal| match queued_proposal.proposal() {
+                Proposal::ReInit(reinit) => Some(GroupNotification::ReInit(reinit.clone())),
+                _ => None,
+            });
+        added_members.chain(removed_members).chain(reinit).collect()
+    }
+
+    /// Notifies the observer about a merged commit, given the notifications
+    /// of [`MlsGroup::staged_commit_notifications()`] and whether the commit
+    /// removed the own client.
+    pub(super) fn notify_merged_commit(
+        &self,
+        notifications: Vec<GroupNotification>,
+        self_removed: bool,
+    ) {
+        let Some(observer) = &self.observer else {
+            return;
+        };
+        let last = if self_removed {
+            self.departure()
+                .map(|departure| GroupNotification::OwnRemoval(departure.reason().clone()))
+        } else {
+            Some(GroupNotification::EpochChanged(self.epoch()))
+        };
+        for notification in notifications.into_iter().chain(last) {
+            observer.notify(self.group_id(), &notification);
+        }
+    }
+}
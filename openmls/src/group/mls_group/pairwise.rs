@@ -0,0 +1,98 @@
+//! MLS group pairwise secrets
+//!
+//! This module derives secrets for a pair of members from the exporter
+//! secret of the current epoch with [`MlsGroup::pairwise_secret()`].
+//!
+//! **The secrets are not private to the pair.** The exporter secret is shared
+//! by all members of the group, so every member can compute the pairwise
+//! secret of any two members. The secrets only separate the keys of different
+//! pairs, e.g. to key a side channel between two members that doesn't need to
+//! be confidential towards the rest of the group. Secrets that only the two
+//! members know have to be established with a separate key exchange, e.g. a
+//! second group of the two.
+//!
+//! The secret is bound to the epoch and to the current leaf nodes of both
+//! members, so it changes whenever one of them updates its leaf.
+
+use tls_codec::Serialize as TlsSerializeTrait;
+
+use crate::group::errors::ExporterError;
+
+use super::*;
+
+/// The prefix of the exporter label used to derive pairwise secrets.
+const PAIRWISE_LABEL_PREFIX: &str = "MLS 1.0 pairwise ";
+
+impl MlsGroup {
+    /// Derives a secret of length `key_length` for the own client and the
+    /// member at `peer` in the current epoch.
+    ///
+    /// The secret is derived from the exporter secret of the group, so every
+    /// member of the group can compute it, not only the two members of the
+    /// pair. It must not be used to keep data confidential from the rest of
+    /// the group.
+    ///
+    /// The member at `peer` derives the same secret when called with the own
+    /// leaf index and the same `label`. The secret is exported with the label
+    /// `"MLS 1.0 pairwise " || label` and the following context, where the
+    /// leaf with the smaller leaf index comes first:
+    ///
+    /// ```c
+    /// struct {
+    ///     uint32 first_leaf_index;
+    ///     LeafNode first_leaf_node;
+    ///     uint32 second_leaf_index;
+    ///     LeafNode second_leaf_node;
+    /// } PairwiseContext;
+    /// ```
+    ///
+    /// Returns [`PairwiseSecretError::UnknownMember`] if there is no other
+    /// member at `peer` and [`PairwiseSecretError::KeyLengthTooLong`] if the
+    /// requested key length is too long.
+    /// Returns [`PairwiseSecretError::GroupStateError(MlsGroupStateError::UseAfterEviction)`](MlsGroupStateError::UseAfterEviction)
+    /// if the group is not active.
+    pub fn pairwise_secret(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        peer: LeafNodeIndex,
+        label: &str,
+        key_length: usize,
+    ) -> Result<Vec<u8>, PairwiseSecretError> {
+        if !self.is_active() {
+            return Err(MlsGroupStateError::UseAfterEviction.into());
+        }
+        let own_leaf_index = self.own_leaf_index();
+        if peer == own_leaf_index {
+            return Err(PairwiseSecretError::UnknownMember);
+        }
+        let mut leaves = [own_leaf_index, peer];
+        leaves.sort();
+
+        let mut context = Vec::new();
+        for leaf_index in leaves {
+            let leaf_node = self
+                .group
+                .public_group()
+                .leaf(leaf_index)
+                .ok_or(PairwiseSecretError::UnknownMember)?;
+            leaf_index
+                .tls_serialize(&mut context)
+                .map_err(LibraryError::missing_bound_check)?;
+            leaf_node
+                .tls_serialize(&mut context)
+                .map_err(LibraryError::missing_bound_check)?;
+        }
+
+        self.group
+            .export_secret(
+                backend,
+                &format!("{PAIRWISE_LABEL_PREFIX}{label}"),
+                &context,
+                key_length,
+            )
+            .map_err(|e| match e {
+                ExporterError::LibraryError(e) => e.into(),
+                ExporterError::KeyLengthTooLong => PairwiseSecretError::KeyLengthTooLong,
+            })
+    }
+}
@@ -3,13 +3,13 @@
 use std::mem;
 
 use core_group::staged_commit::StagedCommit;
-use openmls_traits::signatures::Signer;
+use openmls_traits::signatures::{AsyncSigner, Signer};
 
 use crate::{
     group::core_group::create_commit_params::CreateCommitParams, messages::group_info::GroupInfo,
 };
 
-use crate::group::errors::MergeCommitError;
+use crate::{group::errors::MergeCommitError, write_batch::BatchProvider};
 
 use super::{errors::ProcessMessageError, *};
 
@@ -20,21 +20,28 @@ impl MlsGroup {
     /// and semantic validation of the message. It returns a [ProcessedMessage]
     /// enum.
     ///
+    /// The group state is written to the storage of the `backend`, since
+    /// processing a message ratchets the secret tree.
+    ///
+    /// After the own client was removed from the group, only application
+    /// messages of its final epoch can be processed, and only while the
+    /// [`RemovalGrace`] of the configuration permits it.
+    ///
     /// # Errors:
     /// Returns an [`ProcessMessageError`] when the validation checks fail
     /// with the exact reason of the failure.
-    pub fn process_message(
+    pub fn process_message<KeyStore: OpenMlsKeyStore>(
         &mut self,
-        backend: &impl OpenMlsCryptoProvider,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         message: impl Into<ProtocolMessage>,
-    ) -> Result<ProcessedMessage, ProcessMessageError> {
-        // Make sure we are still a member of the group
+    ) -> Result<ProcessedMessage, ProcessMessageError<KeyStore::Error>> {
+        let message = message.into();
+
+        // Make sure we are still a member of the group, or that application
+        // messages of the final epoch may still be processed
         if !self.is_active() {
-            return Err(ProcessMessageError::GroupStateError(
-                MlsGroupStateError::UseAfterEviction,
-            ));
+            return self.process_message_after_removal(backend, message);
         }
-        let message = message.into();
 
         // Check that handshake messages are compatible with the incoming wire format policy
         if !message.is_external()
@@ -42,34 +49,510 @@ impl MlsGroup {
             && !self
                 .configuration()
                 .wire_format_policy()
-                .incoming()
-                .is_compatible_with(message.wire_format())
+                .accepts(message.wire_format(), message.content_type())
         {
             return Err(ProcessMessageError::IncompatibleWireFormat);
         }
 
-        // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        // Messages from future epochs are buffered if the buffer is enabled
+        let message = self.buffer_future_message(message)?;
+
+        // The DS might echo the messages of the own client
+        self.check_own_message(backend, &message)?;
+
+        // Secrets of past epochs that expired must not be used anymore
+        self.group.message_secrets_store_mut().drop_expired();
+
+        // A different commit for the previous epoch competed with the one
+        // that was merged
+        if let Err(e) = self.detect_competing_commit(backend, &message) {
+            self.store(backend)
+                .map_err(ProcessMessageError::StorageError)?;
+            return Err(e);
+        }
 
         // Parse the message
         let sender_ratchet_configuration =
             self.configuration().sender_ratchet_configuration().clone();
-        self.group.process_message(
+        let result = self.group.process_message(
             backend,
             message,
             &sender_ratchet_configuration,
             &self.proposal_store,
             &self.own_leaf_nodes,
-        )
+            self.psk_resolver.as_deref(),
+        );
+        if let Ok(processed_message) = &result {
+            self.log_processed_message(processed_message);
+            self.notify_processed_message(processed_message);
+        }
+
+        // The secret tree might have been ratcheted, even if processing failed
+        self.store(backend)
+            .map_err(ProcessMessageError::StorageError)?;
+
+        let processed_message = result.map_err(ProcessMessageError::with_storage_error)?;
+        self.validate_new_credentials(&processed_message)?;
+        self.validate_commit(&processed_message)?;
+        Ok(processed_message)
+    }
+
+    /// Processes an incoming application message like
+    /// [`MlsGroup::process_message()`], but through a shared reference, so
+    /// that several threads can decrypt the messages of a group at the same
+    /// time, e.g. while holding the read lock of an `RwLock` whose write lock
+    /// is only taken to process handshake messages.
+    ///
+    /// Each sender has its own ratchet in the secret tree, so messages of
+    /// different senders are decrypted concurrently, while messages of the
+    /// same sender are decrypted one after another. Afterwards, only the
+    /// sender ratchets of the sender and the small state that this function
+    /// changes, e.g. the sequence number, are written to the storage of the
+    /// `backend`. They are loaded with [`MlsGroup::load()`] and become part
+    /// of the stored group state the next time it is written.
+    ///
+    /// The message passes the same checks as with
+    /// [`MlsGroup::process_message()`]. Messages of future epochs are
+    /// buffered, messages of past epochs are accepted as long as their
+    /// secrets are kept (see [`MlsGroupConfig::max_past_epochs()`]), and
+    /// after the own client was removed from the group, application messages
+    /// of its final epoch are processed while the [`RemovalGrace`] permits
+    /// it. The buffer and the secrets that expired or whose removal grace
+    /// ended are only written or deleted with the next change of the group
+    /// state, e.g. by [`MlsGroup::process_message()`].
+    ///
+    /// # Errors:
+    /// Returns [`ProcessMessageError::NotAnApplicationMessage`] for handshake
+    /// messages, which have to be processed with
+    /// [`MlsGroup::process_message()`]. Otherwise, see
+    /// [`MlsGroup::process_message()`].
+    pub fn process_application_message<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        message: impl Into<ProtocolMessage>,
+    ) -> Result<ProcessedMessage, ProcessMessageError<KeyStore::Error>> {
+        let message = message.into();
+
+        let message = if !self.is_active() {
+            // Application messages of the final epoch may still be processed
+            self.count_message_after_removal(&message)?;
+            message
+        } else if message.is_handshake_message() {
+            return Err(ProcessMessageError::NotAnApplicationMessage);
+        } else {
+            // Messages from future epochs are buffered if the buffer is enabled
+            self.buffer_future_message(message)?
+        };
+
+        // Secrets of past epochs that expired must not be used anymore. They
+        // are dropped by the next call of `process_message()`.
+        if message.group_id() == self.group_id()
+            && self
+                .group
+                .message_secrets_store()
+                .is_expired(message.epoch())
+        {
+            return Err(ValidationError::NoPastEpochData.into());
+        }
+
+        // The sender whose ratchets are used to decrypt the message
+        let sender = match &message {
+            ProtocolMessage::PrivateMessage(private_message) => self
+                .group
+                .message_secrets_for_epoch(message.epoch())
+                .ok()
+                .and_then(|message_secrets| {
+                    private_message
+                        .sender_data(message_secrets, backend, self.ciphersuite())
+                        .ok()
+                })
+                .map(|sender_data| (message.epoch(), sender_data.leaf_index)),
+            ProtocolMessage::PublicMessage(_) => None,
+        };
+
+        // Parse the message
+        let result = self.group.process_message(
+            backend,
+            message,
+            self.configuration().sender_ratchet_configuration(),
+            &self.proposal_store,
+            &self.own_leaf_nodes,
+            self.psk_resolver.as_deref(),
+        );
+
+        // The sender ratchets might have been ratcheted, even if processing
+        // failed
+        self.store_receiver_state(backend, sender)
+            .map_err(ProcessMessageError::StorageError)?;
+
+        result.map_err(ProcessMessageError::with_storage_error)
+    }
+
+    /// Like [`MlsGroup::process_application_message()`], but checks the
+    /// `sequence_number` of the message like
+    /// [`MlsGroup::process_message_with_sequence_number()`].
+    ///
+    /// Messages of the current epoch are only processed in the order of
+    /// their sequence numbers, so messages that are processed concurrently
+    /// may fail with [`ProcessMessageError::OutOfOrder`] and have to be
+    /// requeued.
+    ///
+    /// # Errors:
+    /// See [`MlsGroup::process_application_message()`] and
+    /// [`MlsGroup::process_message_with_sequence_number()`].
+    pub fn process_application_message_with_sequence_number<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        message: impl Into<ProtocolMessage>,
+        sequence_number: u64,
+    ) -> Result<ProcessedMessage, ProcessMessageError<KeyStore::Error>> {
+        let message = message.into();
+
+        // The sequence number of a handshake message must not be consumed
+        if self.is_active() && message.is_handshake_message() {
+            return Err(ProcessMessageError::NotAnApplicationMessage);
+        }
+
+        if message.epoch() == self.epoch() {
+            let epoch = self.epoch();
+            let mut receiver_state = self.receiver_state();
+            let expected = receiver_state.next_sequence_number(epoch);
+            if sequence_number != expected {
+                return Err(ProcessMessageError::OutOfOrder {
+                    expected,
+                    got: sequence_number,
+                });
+            }
+            receiver_state.set_next_sequence_number(epoch, expected + 1);
+        }
+
+        self.process_application_message(backend, message)
+    }
+
+    /// Buffers the `message` if it is from a future epoch and the message
+    /// buffer is enabled. They can't be authenticated before the group
+    /// reaches their epoch, so the number of messages per sender and epoch
+    /// is bounded, and the buffer is only written with the next change of
+    /// the group state.
+    ///
+    /// Returns the `message` if it isn't buffered, and
+    /// [`ProcessMessageError::MessageBuffered`] or
+    /// [`ProcessMessageError::MessageBufferFull`] otherwise.
+    fn buffer_future_message<KeyStoreError>(
+        &self,
+        message: ProtocolMessage,
+    ) -> Result<ProtocolMessage, ProcessMessageError<KeyStoreError>> {
+        let message_buffer_configuration = self.configuration().message_buffer_configuration();
+        if message_buffer_configuration.max_messages() == 0
+            || message.group_id() != self.group_id()
+            || message.epoch() <= self.epoch()
+            || message.epoch().as_u64() - self.epoch().as_u64()
+                > message_buffer_configuration.max_epochs()
+        {
+            return Ok(message);
+        }
+
+        let mut message_buffer = lock(&self.message_buffer);
+        let buffered_from_sender = message_buffer
+            .iter()
+            .filter(|buffered| {
+                buffered.epoch() == message.epoch() && buffered.sender() == message.sender()
+            })
+            .count();
+        if message_buffer.len() >= message_buffer_configuration.max_messages()
+            || buffered_from_sender >= message_buffer_configuration.max_messages_per_sender()
+        {
+            return Err(ProcessMessageError::MessageBufferFull);
+        }
+        message_buffer.push(message);
+
+        Err(ProcessMessageError::MessageBuffered)
+    }
+
+    /// Checks whether the `message` is a handshake message that the own client
+    /// sent in the current epoch, which the group can't process.
+    ///
+    /// Only exact copies of the messages the own client created are
+    /// recognized, so that other members can't make the group take a forged
+    /// message for an own one.
+    ///
+    /// Returns [`ProcessMessageError::OwnCommit`] if the message is the
+    /// pending commit, [`ProcessMessageError::UnknownOwnCommit`] if it is
+    /// another commit of the own client and
+    /// [`ProcessMessageError::OwnProposal`] for own proposals.
+    fn check_own_message<KeyStoreError>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        message: &ProtocolMessage,
+    ) -> Result<(), ProcessMessageError<KeyStoreError>> {
+        let Some(own_messages) = &self.own_messages else {
+            return Ok(());
+        };
+        if !message.is_handshake_message()
+            || message.group_id() != self.group_id()
+            || message.epoch() != self.epoch()
+            || own_messages.epoch != self.epoch()
+        {
+            return Ok(());
+        }
+        let digest = message.digest(backend.crypto(), self.ciphersuite(), self.group.version())?;
+        if own_messages.proposals.contains(&digest) {
+            return Err(ProcessMessageError::OwnProposal);
+        }
+        match own_messages
+            .commits
+            .iter()
+            .position(|commit| commit == &digest)
+        {
+            // Only the last commit can be the pending one
+            Some(position)
+                if position + 1 == own_messages.commits.len()
+                    && matches!(self.group_state, MlsGroupState::PendingCommit(_)) =>
+            {
+                Err(ProcessMessageError::OwnCommit)
+            }
+            Some(_) => Err(ProcessMessageError::UnknownOwnCommit),
+            None => Ok(()),
+        }
+    }
+
+    /// Remembers the `digest` of a handshake message of the `content_type`
+    /// that the own client created in the current epoch. See
+    /// [`MlsGroup::check_own_message()`].
+    pub(super) fn record_own_message(&mut self, content_type: ContentType, digest: Vec<u8>) {
+        let epoch = self.epoch();
+        let own_messages = match &mut self.own_messages {
+            Some(own_messages) if own_messages.epoch == epoch => own_messages,
+            own_messages => own_messages.insert(OwnMessageDigests {
+                epoch,
+                commits: Vec::new(),
+                proposals: Vec::new(),
+            }),
+        };
+        match content_type {
+            ContentType::Commit => own_messages.commits.push(digest),
+            ContentType::Proposal => own_messages.proposals.push(digest),
+            ContentType::Application => {}
+        }
+    }
+
+    /// Calls the [`CredentialValidator`] of the group, if any, for the leaf
+    /// nodes that enter the tree through the processed commit.
+    fn validate_new_credentials<KeyStoreError>(
+        &self,
+        processed_message: &ProcessedMessage,
+    ) -> Result<(), ProcessMessageError<KeyStoreError>> {
+        let (Some(validator), ProcessedMessageContent::StagedCommitMessage(staged_commit)) =
+            (&self.credential_validator, processed_message.content())
+        else {
+            return Ok(());
+        };
+        for (leaf_index, leaf_node) in staged_commit.new_leaf_nodes() {
+            validator
+                .validate(leaf_node.credential(), leaf_node.signature_key())
+                .map_err(|rejection| ProcessMessageError::CredentialRejected {
+                    leaf_index,
+                    rejection,
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Calls the [`CommitValidator`] of the group, if any, for the processed
+    /// commit.
+    fn validate_commit<KeyStoreError>(
+        &self,
+        processed_message: &ProcessedMessage,
+    ) -> Result<(), ProcessMessageError<KeyStoreError>> {
+        let (Some(validator), ProcessedMessageContent::StagedCommitMessage(staged_commit)) =
+            (&self.commit_validator, processed_message.content())
+        else {
+            return Ok(());
+        };
+        validator
+            .validate(staged_commit, self.group.public_group())
+            .map_err(ProcessMessageError::CommitRejected)
+    }
+
+    /// Parses incoming messages from the DS like
+    /// [`MlsGroup::process_message()`], but additionally checks the sequence
+    /// number the DS assigned to the message.
+    ///
+    /// The DS is expected to number the messages of each epoch consecutively,
+    /// starting at 0. Messages of the current epoch have to be processed in
+    /// that order, while messages of other epochs are not checked. The
+    /// sequence number is consumed as soon as the check passes, even if
+    /// processing the message fails afterwards.
+    ///
+    /// # Errors:
+    /// Returns [`ProcessMessageError::OutOfOrder`] without processing the
+    /// message if the sequence number is not the expected one. This allows the
+    /// application to requeue the message instead of misinterpreting the
+    /// resulting decryption failure. Otherwise, see
+    /// [`MlsGroup::process_message()`].
+    pub fn process_message_with_sequence_number<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        message: impl Into<ProtocolMessage>,
+        sequence_number: u64,
+    ) -> Result<ProcessedMessage, ProcessMessageError<KeyStore::Error>> {
+        let message = message.into();
+
+        if message.epoch() == self.epoch() {
+            let expected = self.next_sequence_number();
+            if sequence_number != expected {
+                return Err(ProcessMessageError::OutOfOrder {
+                    expected,
+                    got: sequence_number,
+                });
+            }
+            self.set_next_sequence_number(backend, expected + 1)
+                .map_err(ProcessMessageError::StorageError)?;
+        }
+
+        self.process_message(backend, message)
+    }
+
+    /// Returns the sequence number that is expected for the next message of
+    /// the current epoch. See
+    /// [`MlsGroup::process_message_with_sequence_number()`].
+    pub fn next_sequence_number(&self) -> u64 {
+        self.receiver_state().next_sequence_number(self.epoch())
+    }
+
+    /// Sets the sequence number that is expected for the next message of the
+    /// current epoch, e.g. to skip the sequence numbers of own messages that
+    /// are not processed. See
+    /// [`MlsGroup::process_message_with_sequence_number()`].
+    ///
+    /// The group state is written to the storage of the `backend`.
+    pub fn set_next_sequence_number<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        sequence_number: u64,
+    ) -> Result<(), KeyStore::Error> {
+        let epoch = self.epoch();
+        self.receiver_state_mut()
+            .set_next_sequence_number(epoch, sequence_number);
+        self.store(backend)
+    }
+
+    /// Processes the buffered messages of the current epoch. Messages are
+    /// buffered by [`MlsGroup::process_message()`] if they are from a future
+    /// epoch and the buffer is enabled through the
+    /// [`MessageBufferConfiguration`]. Merging a commit processes the
+    /// buffered messages of the new epoch automatically (see
+    /// [`MlsGroup::take_processed_buffered_messages()`]), so this function is
+    /// only needed if that failed, e.g. because the storage was unavailable.
+    ///
+    /// Returns the results of processing the messages of the current epoch.
+    /// Commits are processed last, since they may refer to the proposals of
+    /// the epoch. The encrypted messages of each sender are processed in the
+    /// order of their generations, so that the secret tree doesn't skip the
+    /// generations of messages that are still in the buffer. Messages from
+    /// later epochs stay in the buffer.
+    ///
+    /// Each message is removed from the buffer when it is processed, so that
+    /// the stored group state never contains a message that was already
+    /// processed.
+    pub fn process_buffered_messages<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Vec<Result<ProcessedMessage, ProcessMessageError<KeyStore::Error>>> {
+        let epoch = self.epoch();
+        let ciphersuite = self.ciphersuite();
+        let (current, later): (Vec<_>, Vec<_>) = mem::take(self.message_buffer_mut())
+            .into_iter()
+            .filter(|message| message.epoch() >= epoch)
+            .partition(|message| message.epoch() == epoch);
+
+        // The sort is stable, so that public messages keep the order in which
+        // they were buffered
+        let mut current: Vec<_> = current
+            .into_iter()
+            .map(|message| {
+                let generation = match &message {
+                    ProtocolMessage::PrivateMessage(private_message) => private_message
+                        .sender_data(self.group.message_secrets(), backend, ciphersuite)
+                        .ok()
+                        .map(|sender_data| (sender_data.leaf_index, sender_data.generation)),
+                    ProtocolMessage::PublicMessage(_) => None,
+                };
+                (
+                    message.content_type() == ContentType::Commit,
+                    generation,
+                    message,
+                )
+            })
+            .collect();
+        current.sort_by_key(|(is_commit, generation, _)| (*is_commit, *generation));
+        *self.message_buffer_mut() = current
+            .into_iter()
+            .map(|(_, _, message)| message)
+            .chain(later)
+            .collect();
+
+        let mut results = Vec::new();
+        while self
+            .message_buffer_mut()
+            .first()
+            .map_or(false, |message| message.epoch() == epoch)
+        {
+            let message = self.message_buffer_mut().remove(0);
+            results.push(self.process_message(backend, message));
+        }
+        results
+    }
+
+    /// Returns the buffered messages of the new epoch that were processed
+    /// successfully when the last commits were merged, and removes them from
+    /// the group. See [`MlsGroup::process_buffered_messages()`].
+    ///
+    /// The processed messages aren't part of the stored group state, so they
+    /// should be taken after each merge.
+    pub fn take_processed_buffered_messages(&mut self) -> Vec<ProcessedMessage> {
+        mem::take(&mut self.processed_buffered_messages)
+    }
+
+    /// Processes the buffered messages of the epoch that was just merged.
+    /// Messages that fail to process are dropped, since they would fail
+    /// again.
+    fn retry_buffered_messages<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) {
+        for result in self.process_buffered_messages(backend) {
+            match result {
+                Ok(processed_message) => self.processed_buffered_messages.push(processed_message),
+                Err(e) => log::debug!("Dropping a buffered message: {e:?}"),
+            }
+        }
+    }
+
+    /// Returns a copy of the buffered messages from future epochs. See
+    /// [`MlsGroup::process_buffered_messages()`].
+    pub fn buffered_messages(&self) -> Vec<ProtocolMessage> {
+        lock(&self.message_buffer).clone()
+    }
+
+    /// Returns the buffered messages without locking them.
+    fn message_buffer_mut(&mut self) -> &mut Vec<ProtocolMessage> {
+        self.message_buffer
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
     }
 
     /// Stores a standalone proposal in the internal [ProposalStore]
-    pub fn store_pending_proposal(&mut self, proposal: QueuedProposal) {
+    ///
+    /// The group state is written to the storage of the `backend`.
+    pub fn store_pending_proposal<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        proposal: QueuedProposal,
+    ) -> Result<(), KeyStore::Error> {
         // Store the proposal in in the internal ProposalStore
         self.proposal_store.add(proposal);
-
-        // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.store(backend)
     }
 
     /// Creates a Commit message that covers the pending proposals that are
@@ -88,6 +571,19 @@ impl MlsGroup {
     ) -> Result<
         (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
         CommitToPendingProposalsError<KeyStore::Error>,
+    > {
+        crate::utils::poll_ready(self.commit_to_pending_proposals_internal(backend, signer))
+    }
+
+    /// Like [`MlsGroup::commit_to_pending_proposals()`], but signs with an [`AsyncSigner`].
+    #[allow(clippy::type_complexity)]
+    pub(super) async fn commit_to_pending_proposals_internal<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl AsyncSigner,
+    ) -> Result<
+        (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
+        CommitToPendingProposalsError<KeyStore::Error>,
     > {
         self.is_operational()?;
 
@@ -96,8 +592,12 @@ impl MlsGroup {
         let params = CreateCommitParams::builder()
             .framing_parameters(self.framing_parameters())
             .proposal_store(&self.proposal_store)
+            .psk_resolver(self.psk_resolver.as_deref())
             .build();
-        let create_commit_result = self.group.create_commit(params, backend, signer)?;
+        let create_commit_result = self
+            .group
+            .create_commit_async(params, backend, signer)
+            .await?;
 
         // Convert PublicMessage messages to MLSMessage and encrypt them if required by
         // the configuration
@@ -108,9 +608,8 @@ impl MlsGroup {
         self.group_state = MlsGroupState::PendingCommit(Box::new(PendingCommitState::Member(
             create_commit_result.staged_commit,
         )));
-
-        // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.store(backend)
+            .map_err(CommitToPendingProposalsError::StorageError)?;
 
         Ok((
             mls_message,
@@ -121,6 +620,31 @@ impl MlsGroup {
         ))
     }
 
+    /// Creates a Commit message that covers exactly the stored proposals with
+    /// the given references, in the given order, instead of all pending
+    /// proposals, e.g. if the DS determines which proposals are committed.
+    ///
+    /// This is a shorthand for [`CommitBuilder::include_proposals()`]. Returns
+    /// [`CommitBuilderError::ProposalNotFound`] if one of the proposals is not
+    /// in the [`ProposalStore`] and
+    /// [`CommitBuilderError::ConflictingProposals`] if two of them conflict,
+    /// see [`ProposalStore::conflicts()`]. Otherwise, see
+    /// [`MlsGroup::commit_to_pending_proposals()`].
+    #[allow(clippy::type_complexity)]
+    pub fn commit_to_proposals<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        proposal_refs: &[ProposalRef],
+    ) -> Result<
+        (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
+        CommitBuilderError<KeyStore::Error>,
+    > {
+        self.commit_builder()
+            .include_proposals(proposal_refs.to_vec())
+            .finalize(backend, signer)
+    }
+
     /// Merge a [StagedCommit] into the group after inspection. As this advances
     /// the epoch of the group, it also clears any pending commits.
     pub fn merge_staged_commit<KeyStore: OpenMlsKeyStore>(
@@ -128,17 +652,93 @@ impl MlsGroup {
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         staged_commit: StagedCommit,
     ) -> Result<(), MergeCommitError<KeyStore::Error>> {
+        // Merging replaces the encryption key pairs of the group together with
+        // the group state, so that all writes are applied in one batch.
+        let notifications = self.staged_commit_notifications(&staged_commit);
+        let self_removed = staged_commit.self_removed();
+        let batch_backend = BatchProvider::new(backend);
+        let result = self
+            .apply_staged_commit(&batch_backend, staged_commit)
+            .and_then(|()| {
+                batch_backend
+                    .apply()
+                    .map_err(MergeCommitError::StorageError)
+            });
+        match result {
+            Ok(()) => {
+                self.notify_merged_commit(notifications, self_removed);
+                self.retry_buffered_messages(backend);
+                Ok(())
+            }
+            Err(e) => {
+                // Nothing was written, so the stored state is the one from
+                // before the merge. The error of the merge is more relevant
+                // than a failing reload.
+                let _ = self.reload(backend);
+                Err(e)
+            }
+        }
+    }
+
+    /// Applies a [StagedCommit] to the group and writes all changes to the
+    /// key store.
+    fn apply_staged_commit<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        staged_commit: StagedCommit,
+    ) -> Result<(), MergeCommitError<KeyStore::Error>> {
+        // The events are resolved against the tree of the current epoch
+        let events = self.staged_commit_events(&staged_commit);
+
+        // A pending commit of the own client loses against the merged commit
+        let lost_commit = self.lost_pending_commit();
+
         // Check if we were removed from the group
+        let final_epoch_members = staged_commit
+            .self_removed()
+            .then(|| self.group.public_group().members().collect::<Vec<_>>());
         if staged_commit.self_removed() {
+            let reason: DepartureReason = staged_commit
+                .remove_proposals()
+                .find(|proposal| proposal.remove_proposal().removed() == self.own_leaf_index())
+                .ok_or_else(|| LibraryError::custom("Missing remove proposal for own leaf"))
+                .and_then(|proposal| RemoveOperation::new(proposal, self))?
+                .try_into()?;
+            self.departure = Some(Departure {
+                reason,
+                epoch: self.epoch(),
+            });
             self.group_state = MlsGroupState::Inactive;
+        } else if self.is_leaving() {
+            // Our remove proposal was not committed and expires with the
+            // epoch change
+            self.group_state = MlsGroupState::Operational;
         }
 
-        // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        // The exported secrets belong to the previous epoch
+        self.clear_exporter_cache();
 
         // Merge staged commit
+        let previous_epoch = self.epoch();
+        let compromise_recovery = staged_commit.is_compromise_recovery();
         self.group
             .merge_staged_commit(backend, staged_commit, &mut self.proposal_store)?;
+        self.push_events(events);
+
+        // Keep the secrets of the final epoch for the removal grace period
+        // or delete them right away
+        if let Some(members) = final_epoch_members {
+            self.mark_inactive(previous_epoch, members);
+        }
+
+        // After an emergency re-keying, the secrets of past epochs must not
+        // be kept around until the retention policy drops them.
+        if compromise_recovery {
+            let epoch = self.epoch();
+            self.group
+                .message_secrets_store_mut()
+                .drop_epochs_before(epoch);
+        }
 
         // Extract and store the resumption psk for the current epoch
         let resumption_psk = self.group.group_epoch_secrets().resumption_psk();
@@ -149,10 +749,28 @@ impl MlsGroup {
         // Delete own KeyPackageBundles
         self.own_leaf_nodes.clear();
 
-        // Delete a potential pending commit
-        self.clear_pending_commit();
+        // The proposals of the previous epoch can't be committed anymore
+        backend
+            .key_store()
+            .remove::<ProposalStore>(StorageKey::QueuedProposals {
+                group_id: self.group_id().as_slice(),
+                epoch: previous_epoch.as_u64(),
+            })
+            .map_err(MergeCommitError::StorageError)?;
 
-        Ok(())
+        // Drop buffered messages from past epochs
+        let epoch = self.epoch();
+        self.message_buffer_mut()
+            .retain(|message| message.epoch() >= epoch);
+
+        // Delete a potential pending commit, but keep its proposals for a
+        // rebase
+        self.discard_pending_commit();
+        if lost_commit.is_some() {
+            self.lost_commit = lost_commit;
+        }
+
+        self.store(backend).map_err(MergeCommitError::StorageError)
     }
 
     /// Merges the pending [`StagedCommit`] if there is one, and
@@ -165,12 +783,125 @@ impl MlsGroup {
             MlsGroupState::PendingCommit(_) => {
                 let old_state = mem::replace(&mut self.group_state, MlsGroupState::Operational);
                 if let MlsGroupState::PendingCommit(pending_commit_state) = old_state {
+                    // A discarded commit of an earlier epoch is superseded.
+                    // A failed merge restores both from the stored state.
+                    self.lost_commit = None;
                     self.merge_staged_commit(backend, (*pending_commit_state).into())?;
                 }
                 Ok(())
             }
             MlsGroupState::Inactive => Err(MlsGroupStateError::UseAfterEviction)?,
-            MlsGroupState::Operational => Ok(()),
+            MlsGroupState::Operational | MlsGroupState::PendingLeave => Ok(()),
+        }
+    }
+}
+
+/// The digests of the handshake messages that the own client created in one
+/// epoch, which are used to recognize them when the DS echoes them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct OwnMessageDigests {
+    epoch: GroupEpoch,
+    // The commits in the order they were created, the pending one last.
+    commits: Vec<Vec<u8>>,
+    proposals: Vec<Vec<u8>>,
+}
+
+/// The state that [`MlsGroup::process_application_message()`] changes
+/// through a shared reference. It is part of the stored group state, but is
+/// also written without it, see [`MlsGroup::store_receiver_state()`].
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub(crate) struct ReceiverState {
+    // The epoch and the next expected DS sequence number in that epoch. See
+    // [`MlsGroup::process_message_with_sequence_number()`].
+    next_sequence_number: Option<(GroupEpoch, u64)>,
+    // The state of the removal grace period. This is only set if the group is
+    // inactive and application messages of the final epoch can still be
+    // processed. See [`RemovalGrace`].
+    pub(super) drain: Option<draining::Drain>,
+    // The epochs and leaf indices of the sender ratchets that were written
+    // without the group state since it was written the last time.
+    sender_ratchets: Vec<(GroupEpoch, LeafNodeIndex)>,
+    // Whether this state was written without the group state since it was
+    // written the last time.
+    #[serde(skip)]
+    written: bool,
+}
+
+impl ReceiverState {
+    /// Creates the state that was stored as part of the group state.
+    pub(super) fn new(
+        next_sequence_number: Option<(GroupEpoch, u64)>,
+        drain: Option<draining::Drain>,
+    ) -> Self {
+        Self {
+            next_sequence_number,
+            drain,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the epoch and the next expected sequence number in that epoch.
+    pub(super) fn stored_sequence_number(&self) -> Option<(GroupEpoch, u64)> {
+        self.next_sequence_number
+    }
+
+    /// Returns the sequence number that is expected next in the `epoch`.
+    fn next_sequence_number(&self, epoch: GroupEpoch) -> u64 {
+        match self.next_sequence_number {
+            Some((sequence_number_epoch, sequence_number)) if sequence_number_epoch == epoch => {
+                sequence_number
+            }
+            _ => 0,
+        }
+    }
+
+    /// Sets the sequence number that is expected next in the `epoch`.
+    fn set_next_sequence_number(&mut self, epoch: GroupEpoch, sequence_number: u64) {
+        self.next_sequence_number = Some((epoch, sequence_number));
+    }
+
+    /// Returns the epochs and leaf indices of the sender ratchets that were
+    /// written without the group state.
+    pub(crate) fn sender_ratchets(&self) -> &[(GroupEpoch, LeafNodeIndex)] {
+        &self.sender_ratchets
+    }
+
+    /// Records that the sender ratchets of the leaf with the given index in
+    /// the `epoch` were written without the group state.
+    pub(super) fn add_sender_ratchets(&mut self, epoch: GroupEpoch, leaf_index: LeafNodeIndex) {
+        if !self.sender_ratchets.contains(&(epoch, leaf_index)) {
+            self.sender_ratchets.push((epoch, leaf_index));
+        }
+    }
+
+    /// Returns whether this state was written without the group state since
+    /// it was written the last time.
+    pub(super) fn is_written(&self) -> bool {
+        self.written
+    }
+
+    /// Records that this state was written without the group state.
+    pub(super) fn mark_written(&mut self) {
+        self.written = true;
+    }
+
+    /// Records that the group state, which contains this state, was written.
+    pub(super) fn clear_written(&mut self) {
+        self.sender_ratchets.clear();
+        self.written = false;
+    }
+
+    /// Merges the `stored` state that was written without the group state
+    /// into the state of the loaded group state. The stored state is usually
+    /// newer, but it might be stale if the group state was written
+    /// afterwards and deleting the stored state failed. The later sequence
+    /// number and removal grace period are therefore kept.
+    pub(super) fn merge(&mut self, stored: ReceiverState) {
+        self.next_sequence_number = self.next_sequence_number.max(stored.next_sequence_number);
+        if let (Some(drain), Some(stored_drain)) = (self.drain.as_mut(), stored.drain.as_ref()) {
+            drain.merge(stored_drain);
         }
+        self.sender_ratchets = stored.sender_ratchets;
+        self.written = true;
     }
 }
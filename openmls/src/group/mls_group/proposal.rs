@@ -3,18 +3,24 @@ use openmls_traits::{
 };
 
 use super::{
-    errors::{ProposalError, ProposeAddMemberError, ProposeRemoveMemberError},
+    errors::{
+        MlsGroupStateError, ProposalError, ProposeAddMemberError, ProposeRemoveMemberError,
+        RemoveProposalError,
+    },
     MlsGroup,
 };
 use crate::{
     binary_tree::LeafNodeIndex,
     ciphersuite::hash_ref::ProposalRef,
     credentials::Credential,
-    extensions::Extensions,
-    framing::MlsMessageOut,
+    extensions::{Extension, ExtensionType, Extensions, ExternalSender, SenderExtensionIndex},
+    framing::{MlsMessageOut, Sender},
     group::{errors::CreateAddProposalError, GroupId, QueuedProposal},
     key_packages::KeyPackage,
-    messages::proposals::ProposalOrRefType,
+    messages::{
+        proposal_retraction::ProposalRetraction,
+        proposals::{ProposalOrRefType, ReInitProposal},
+    },
     prelude::LibraryError,
     schedule::PreSharedKeyId,
     treesync::LeafNode,
@@ -40,6 +46,7 @@ pub enum Propose {
     PreSharedKey(PreSharedKeyId),
 
     /// A re-init proposal gets the [`GroupId`], [`ProtocolVersion`], [`Ciphersuite`], and [`Extensions`].
+    #[cfg(feature = "reinit")]
     ReInit {
         group_id: GroupId,
         version: ProtocolVersion,
@@ -83,9 +90,7 @@ macro_rules! impl_propose_fun {
             self.proposal_store.add(queued_proposal);
 
             let mls_message = self.content_to_mls_message(proposal, backend)?;
-
-            // Since the state of the group might be changed, arm the state flag
-            self.flag_state_change();
+            self.store(backend).map_err(ProposalError::StorageError)?;
 
             Ok((mls_message, proposal_ref))
         }
@@ -107,6 +112,7 @@ impl MlsGroup {
         ProposalOrRefType::Proposal
     );
 
+    #[cfg(feature = "psk")]
     impl_propose_fun!(
         propose_external_psk,
         PreSharedKeyId,
@@ -114,6 +120,7 @@ impl MlsGroup {
         ProposalOrRefType::Reference
     );
 
+    #[cfg(feature = "psk")]
     impl_propose_fun!(
         propose_external_psk_by_value,
         PreSharedKeyId,
@@ -121,6 +128,29 @@ impl MlsGroup {
         ProposalOrRefType::Proposal
     );
 
+    impl_propose_fun!(
+        propose_group_context_extensions_by_value,
+        Extensions,
+        create_group_context_ext_proposal,
+        ProposalOrRefType::Proposal
+    );
+
+    #[cfg(feature = "reinit")]
+    impl_propose_fun!(
+        propose_reinit,
+        ReInitProposal,
+        create_reinit_proposal,
+        ProposalOrRefType::Reference
+    );
+
+    #[cfg(feature = "reinit")]
+    impl_propose_fun!(
+        propose_reinit_by_value,
+        ReInitProposal,
+        create_reinit_proposal,
+        ProposalOrRefType::Proposal
+    );
+
     /// Generate a proposal
     pub fn propose<KeyStore: OpenMlsKeyStore>(
         &mut self,
@@ -167,45 +197,55 @@ impl MlsGroup {
                     .propose_remove_member_by_credential(backend, signer, &credential)
                     .map_err(|e| e.into()),
             },
-            Propose::PreSharedKey(psk_id) => match psk_id.psk() {
-                crate::schedule::Psk::External(_) => match ref_or_value {
-                    ProposalOrRefType::Proposal => {
-                        self.propose_external_psk_by_value(backend, signer, psk_id)
-                    }
-                    ProposalOrRefType::Reference => {
-                        self.propose_external_psk(backend, signer, psk_id)
-                    }
-                },
-                crate::schedule::Psk::Resumption(_) => Err(ProposalError::LibraryError(
-                    LibraryError::custom("Invalid PSk argument"),
-                )),
+            #[cfg(feature = "psk")]
+            Propose::PreSharedKey(psk_id) => match ref_or_value {
+                ProposalOrRefType::Proposal => {
+                    self.propose_external_psk_by_value(backend, signer, psk_id)
+                }
+                ProposalOrRefType::Reference => self.propose_external_psk(backend, signer, psk_id),
             },
-            Propose::ReInit {
-                group_id: _,
-                version: _,
-                ciphersuite: _,
-                extensions: _,
-            } => Err(ProposalError::LibraryError(LibraryError::custom(
-                "Unsupported proposal type ReInit",
+            #[cfg(not(feature = "psk"))]
+            Propose::PreSharedKey(_) => Err(ProposalError::LibraryError(LibraryError::custom(
+                "Unsupported proposal type PreSharedKey",
             ))),
+            #[cfg(feature = "reinit")]
+            Propose::ReInit {
+                group_id,
+                version,
+                ciphersuite,
+                extensions,
+            } => {
+                let reinit = ReInitProposal::new(group_id, version, ciphersuite, extensions);
+                match ref_or_value {
+                    ProposalOrRefType::Proposal => {
+                        self.propose_reinit_by_value(backend, signer, reinit)
+                    }
+                    ProposalOrRefType::Reference => self.propose_reinit(backend, signer, reinit),
+                }
+            }
             Propose::ExternalInit(_) => Err(ProposalError::LibraryError(LibraryError::custom(
                 "Unsupported proposal type ExternalInit",
             ))),
-            Propose::GroupContextExtensions(_) => Err(ProposalError::LibraryError(
-                LibraryError::custom("Unsupported proposal type GroupContextExtensions"),
-            )),
+            Propose::GroupContextExtensions(extensions) => match ref_or_value {
+                ProposalOrRefType::Proposal => {
+                    self.propose_group_context_extensions_by_value(backend, signer, extensions)
+                }
+                ProposalOrRefType::Reference => {
+                    self.propose_group_context_extensions(backend, signer, extensions)
+                }
+            },
         }
     }
 
     /// Creates proposals to add members to the group.
     ///
     /// Returns an error if there is a pending commit.
-    pub fn propose_add_member(
+    pub fn propose_add_member<KeyStore: OpenMlsKeyStore>(
         &mut self,
-        backend: &impl OpenMlsCryptoProvider,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         signer: &impl Signer,
         key_package: &KeyPackage,
-    ) -> Result<(MlsMessageOut, ProposalRef), ProposeAddMemberError> {
+    ) -> Result<(MlsMessageOut, ProposalRef), ProposeAddMemberError<KeyStore::Error>> {
         self.is_operational()?;
 
         let add_proposal = self
@@ -227,9 +267,8 @@ impl MlsGroup {
         self.proposal_store.add(proposal);
 
         let mls_message = self.content_to_mls_message(add_proposal, backend)?;
-
-        // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.store(backend)
+            .map_err(ProposeAddMemberError::StorageError)?;
 
         Ok((mls_message, proposal_ref))
     }
@@ -238,12 +277,12 @@ impl MlsGroup {
     /// The `member` has to be the member's leaf index.
     ///
     /// Returns an error if there is a pending commit.
-    pub fn propose_remove_member(
+    pub fn propose_remove_member<KeyStore: OpenMlsKeyStore>(
         &mut self,
-        backend: &impl OpenMlsCryptoProvider,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         signer: &impl Signer,
         member: LeafNodeIndex,
-    ) -> Result<(MlsMessageOut, ProposalRef), ProposeRemoveMemberError> {
+    ) -> Result<(MlsMessageOut, ProposalRef), ProposeRemoveMemberError<KeyStore::Error>> {
         self.is_operational()?;
 
         let remove_proposal = self
@@ -260,9 +299,8 @@ impl MlsGroup {
         self.proposal_store.add(proposal);
 
         let mls_message = self.content_to_mls_message(remove_proposal, backend)?;
-
-        // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.store(backend)
+            .map_err(ProposeRemoveMemberError::StorageError)?;
 
         Ok((mls_message, proposal_ref))
     }
@@ -271,12 +309,12 @@ impl MlsGroup {
     /// The `member` has to be the member's credential.
     ///
     /// Returns an error if there is a pending commit.
-    pub fn propose_remove_member_by_credential(
+    pub fn propose_remove_member_by_credential<KeyStore: OpenMlsKeyStore>(
         &mut self,
-        backend: &impl OpenMlsCryptoProvider,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         signer: &impl Signer,
         member: &Credential,
-    ) -> Result<(MlsMessageOut, ProposalRef), ProposeRemoveMemberError> {
+    ) -> Result<(MlsMessageOut, ProposalRef), ProposeRemoveMemberError<KeyStore::Error>> {
         // Find the user for the credential first.
         let member_index = self
             .group
@@ -319,19 +357,27 @@ impl MlsGroup {
         }
     }
 
-    #[cfg(test)]
-    pub fn propose_group_context_extensions(
+    /// Creates a proposal to replace the group context extensions with the
+    /// given [`Extensions`].
+    ///
+    /// Note that the proposed extensions replace all current group context
+    /// extensions once the proposal is committed.
+    ///
+    /// Returns an error if there is a pending commit or if a member of the
+    /// group doesn't support one of the proposed extensions.
+    pub fn propose_group_context_extensions<KeyStore: OpenMlsKeyStore>(
         &mut self,
-        backend: &impl OpenMlsCryptoProvider,
-        extensions: Extensions,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         signer: &impl Signer,
-    ) -> Result<(MlsMessageOut, ProposalRef), ProposalError<()>> {
+        extensions: Extensions,
+    ) -> Result<(MlsMessageOut, ProposalRef), ProposalError<KeyStore::Error>> {
         self.is_operational()?;
 
-        let proposal = self
-            .group
-            .create_group_context_ext_proposal(self.framing_parameters(), extensions, signer)
-            .unwrap();
+        let proposal = self.group.create_group_context_ext_proposal(
+            self.framing_parameters(),
+            extensions,
+            signer,
+        )?;
 
         let queued_proposal = QueuedProposal::from_authenticated_content_by_ref(
             self.ciphersuite(),
@@ -343,10 +389,130 @@ impl MlsGroup {
         self.proposal_store.add(queued_proposal);
 
         let mls_message = self.content_to_mls_message(proposal, backend)?;
-
-        // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.store(backend).map_err(ProposalError::StorageError)?;
 
         Ok((mls_message, proposal_ref))
     }
+
+    /// Creates a proposal to add the given [`ExternalSender`] to the group's
+    /// [`ExternalSendersExtension`](crate::extensions::ExternalSendersExtension).
+    /// The new external sender is appended, i.e. the [`SenderExtensionIndex`]es
+    /// of the existing external senders don't change.
+    ///
+    /// All other group context extensions are kept. See
+    /// [`MlsGroup::propose_group_context_extensions()`] for more details.
+    pub fn propose_add_external_sender<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        external_sender: ExternalSender,
+    ) -> Result<(MlsMessageOut, ProposalRef), ProposalError<KeyStore::Error>> {
+        let mut external_senders = self.external_senders().cloned().unwrap_or_default();
+        external_senders.push(external_sender);
+
+        let mut extensions = self.group.context().extensions().clone();
+        extensions.add_or_replace(Extension::ExternalSenders(external_senders));
+
+        self.propose_group_context_extensions(backend, signer, extensions)
+    }
+
+    /// Creates a proposal to remove the external sender with the given
+    /// [`SenderExtensionIndex`] from the group's
+    /// [`ExternalSendersExtension`](crate::extensions::ExternalSendersExtension).
+    /// Note that this changes the indices of all subsequent external senders.
+    /// If the last external sender is removed, the extension is removed
+    /// altogether.
+    ///
+    /// All other group context extensions are kept. See
+    /// [`MlsGroup::propose_group_context_extensions()`] for more details.
+    ///
+    /// Returns an error if there is no external sender with the given index.
+    pub fn propose_remove_external_sender<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        sender_index: SenderExtensionIndex,
+    ) -> Result<(MlsMessageOut, ProposalRef), ProposalError<KeyStore::Error>> {
+        let mut external_senders = self.external_senders().cloned().unwrap_or_default();
+        if sender_index.index() >= external_senders.len() {
+            return Err(ProposalError::UnknownExternalSender);
+        }
+        external_senders.remove(sender_index.index());
+
+        let mut extensions = self.group.context().extensions().clone();
+        if external_senders.is_empty() {
+            extensions.remove(ExtensionType::ExternalSenders);
+        } else {
+            extensions.add_or_replace(Extension::ExternalSenders(external_senders));
+        }
+
+        self.propose_group_context_extensions(backend, signer, extensions)
+    }
+
+    /// Cancels the own pending proposal with the given [`ProposalRef`], i.e.
+    /// removes it from the proposal store so that the next commit of the own
+    /// client doesn't include it, and writes the group state to the storage
+    /// of the `backend`.
+    ///
+    /// The other members still have the proposal in their proposal stores.
+    /// The returned [`ProposalRetraction`] has to be sent to them, e.g. TLS
+    /// serialized in an application message through
+    /// [`MlsGroup::create_message()`], so that they can remove it as well
+    /// through [`MlsGroup::process_proposal_retraction()`].
+    ///
+    /// Returns an error if the group is not operational or if there is no own
+    /// pending proposal with the given reference.
+    pub fn cancel_own_proposal<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        proposal_ref: ProposalRef,
+    ) -> Result<ProposalRetraction, RemoveProposalError<KeyStore::Error>> {
+        self.is_operational()?;
+        if !self
+            .own_pending_proposals()
+            .any(|proposal| proposal.proposal_reference() == proposal_ref)
+        {
+            return Err(MlsGroupStateError::PendingProposalNotFound.into());
+        }
+        self.proposal_store
+            .remove(proposal_ref.clone())
+            .ok_or_else(|| LibraryError::custom("The proposal was found above."))?;
+        self.store(backend)
+            .map_err(RemoveProposalError::StorageError)?;
+
+        Ok(ProposalRetraction::new(self.epoch(), proposal_ref))
+    }
+
+    /// Processes a [`ProposalRetraction`] that was received from the member
+    /// at `sender`, i.e. removes the retracted proposal from the proposal
+    /// store and writes the group state to the storage of the `backend`.
+    ///
+    /// The `sender` has to be the authenticated sender of the message that
+    /// carried the retraction, e.g. of the application message. Members can
+    /// only retract their own proposals.
+    ///
+    /// Returns [`RemoveProposalError::GroupStateError(MlsGroupStateError::PendingProposalNotFound)`](MlsGroupStateError::PendingProposalNotFound)
+    /// if the retraction is for a different epoch or if there is no pending
+    /// proposal from `sender` with the retracted reference.
+    pub fn process_proposal_retraction<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        sender: LeafNodeIndex,
+        retraction: &ProposalRetraction,
+    ) -> Result<(), RemoveProposalError<KeyStore::Error>> {
+        let proposal_ref = retraction.proposal_ref().clone();
+        if retraction.epoch() != self.epoch()
+            || !self.pending_proposals().any(|proposal| {
+                proposal.proposal_reference() == proposal_ref
+                    && proposal.sender() == &Sender::Member(sender)
+            })
+        {
+            return Err(MlsGroupStateError::PendingProposalNotFound.into());
+        }
+        self.proposal_store
+            .remove(proposal_ref)
+            .ok_or_else(|| LibraryError::custom("The proposal was found above."))?;
+        self.store(backend)
+            .map_err(RemoveProposalError::StorageError)
+    }
 }
@@ -0,0 +1,134 @@
+//! Rebasing a pending commit that lost the race for its epoch.
+//!
+//! If the DS accepts the commit of another member instead of the own pending
+//! commit, the own commit is discarded when the other commit is merged. The
+//! proposals of the discarded commit are kept, so that
+//! [`MlsGroup::rebase_pending_commit()`] can commit them again in the new
+//! epoch.
+
+use openmls_traits::signatures::Signer;
+use serde::{Deserialize, Serialize};
+
+use super::{errors::RebasePendingCommitError, *};
+use crate::{ciphersuite::SignaturePublicKey, messages::group_info::GroupInfo};
+
+/// The proposals of a pending commit that was discarded because another
+/// commit was merged for its epoch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct LostCommit {
+    // The proposals that are committed again by value. Remove proposals are
+    // kept in `removed_members` instead.
+    proposals: Vec<Proposal>,
+    // The signature keys of the members removed by the commit, since their
+    // leaf indices might change in the new epoch.
+    removed_members: Vec<SignaturePublicKey>,
+}
+
+impl MlsGroup {
+    /// Creates a new commit that replays the discarded pending commit of the
+    /// own client on top of the current epoch, after the commit of another
+    /// member was merged for the epoch of the pending commit.
+    ///
+    /// The new commit contains the Add, Remove, `PreSharedKey` and
+    /// `GroupContextExtensions` proposals of the discarded commit by value,
+    /// together with the pending proposals of the current epoch and an update
+    /// path. Members that were already added or removed by the other commit
+    /// are skipped. Update proposals are not replayed, since the update path
+    /// of the new commit updates the own leaf anyway, and neither are the
+    /// Update proposals of other members, which can only be committed by
+    /// reference.
+    ///
+    /// Note that a replayed `GroupContextExtensions` proposal replaces the
+    /// extensions set by the other commit.
+    ///
+    /// If successful, it returns a triple of [`MlsMessageOut`] (containing the
+    /// commit), an optional [`MlsMessageOut`] (containing the [`Welcome`]) and
+    /// an optional [`GroupInfo`], like [`MlsGroup::commit_to_pending_proposals()`].
+    ///
+    /// Returns [`RebasePendingCommitError::NoLostCommit`] if no pending commit
+    /// was discarded since the last own commit was merged.
+    #[allow(clippy::type_complexity)]
+    pub fn rebase_pending_commit<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+    ) -> Result<
+        (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
+        RebasePendingCommitError<KeyStore::Error>,
+    > {
+        self.is_operational()?;
+        let lost_commit = self
+            .lost_commit
+            .take()
+            .ok_or(RebasePendingCommitError::NoLostCommit)?;
+
+        let mut proposals: Vec<Proposal> = lost_commit
+            .proposals
+            .iter()
+            .filter(|proposal| match proposal {
+                Proposal::Add(add_proposal) => {
+                    let encryption_key = add_proposal
+                        .key_package()
+                        .leaf_node()
+                        .encryption_key()
+                        .as_slice();
+                    !self
+                        .members()
+                        .any(|member| member.encryption_key == encryption_key)
+                }
+                _ => true,
+            })
+            .cloned()
+            .collect();
+        for signature_key in &lost_commit.removed_members {
+            if let Some(member) = self
+                .members()
+                .find(|member| member.signature_key == signature_key.as_slice())
+            {
+                proposals.push(Proposal::Remove(RemoveProposal {
+                    removed: member.index,
+                }));
+            }
+        }
+
+        let result = self
+            .commit_builder()
+            .add_proposals(proposals)
+            .finalize(backend, signer);
+        if result.is_err() {
+            // Keep the proposals for another attempt
+            self.lost_commit = Some(lost_commit);
+        }
+        result.map_err(RebasePendingCommitError::from)
+    }
+
+    /// Returns the proposals of the pending commit of the own client, if any,
+    /// which is discarded when another commit is merged.
+    pub(super) fn lost_pending_commit(&self) -> Option<LostCommit> {
+        let MlsGroupState::PendingCommit(pending_commit_state) = &self.group_state else {
+            return None;
+        };
+        let PendingCommitState::Member(staged_commit) = pending_commit_state.as_ref() else {
+            return None;
+        };
+        let mut proposals = vec![];
+        let mut removed_members = vec![];
+        for queued_proposal in staged_commit.queued_proposals() {
+            match queued_proposal.proposal() {
+                Proposal::Remove(remove_proposal) => {
+                    if let Some(leaf) = self.group.public_group().leaf(remove_proposal.removed()) {
+                        removed_members.push(leaf.signature_key().clone());
+                    }
+                }
+                proposal @ (Proposal::Add(_)
+                | Proposal::PreSharedKey(_)
+                | Proposal::GroupContextExtensions(_)) => proposals.push(proposal.clone()),
+                _ => (),
+            }
+        }
+        Some(LostCommit {
+            proposals,
+            removed_members,
+        })
+    }
+}
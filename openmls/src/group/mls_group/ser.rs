@@ -19,11 +19,28 @@ use serde::{
 pub struct SerializedMlsGroup {
     mls_group_config: MlsGroupConfig,
     group: CoreGroup,
+    // The proposal store is written separately, see `MlsGroup::store()`. It
+    // is only read here to load states written by earlier versions.
+    #[serde(default)]
     proposal_store: ProposalStore,
     own_leaf_nodes: Vec<LeafNode>,
     aad: Vec<u8>,
     resumption_psk_store: ResumptionPskStore,
     group_state: MlsGroupState,
+    #[serde(default)]
+    departure: Option<Departure>,
+    #[serde(default)]
+    next_sequence_number: Option<(GroupEpoch, u64)>,
+    #[serde(default)]
+    message_buffer: Vec<ProtocolMessage>,
+    #[serde(default)]
+    drain: Option<draining::Drain>,
+    #[serde(default)]
+    own_messages: Option<processing::OwnMessageDigests>,
+    #[serde(default)]
+    lost_commit: Option<rebase::LostCommit>,
+    #[serde(default)]
+    event_log: VecDeque<event_log::GroupEvent>,
 }
 
 #[allow(clippy::from_over_into)]
@@ -36,7 +53,23 @@ impl Into<MlsGroup> for SerializedMlsGroup {
             own_leaf_nodes: self.own_leaf_nodes,
             aad: self.aad,
             group_state: self.group_state,
-            state_changed: InnerState::Persisted,
+            departure: self.departure,
+            receiver_state: Mutex::new(processing::ReceiverState::new(
+                self.next_sequence_number,
+                self.drain,
+            )),
+            message_buffer: Mutex::new(self.message_buffer),
+            processed_buffered_messages: Vec::new(),
+            own_messages: self.own_messages,
+            lost_commit: self.lost_commit,
+            credential_validator: None,
+            commit_validator: None,
+            observer: None,
+            psk_resolver: None,
+            event_log: self.event_log,
+            event_timestamp: None,
+            exporter_cache: Mutex::default(),
+            store_lock: Mutex::new(()),
         }
     }
 }
@@ -45,19 +78,41 @@ impl MlsEntity for MlsGroup {
     const ID: MlsEntityId = MlsEntityId::GroupState;
 }
 
+impl MlsEntity for ProposalStore {
+    const ID: MlsEntityId = MlsEntityId::QueuedProposals;
+}
+
+impl MlsEntity for processing::ReceiverState {
+    const ID: MlsEntityId = MlsEntityId::ReceiverState;
+}
+
+impl MlsEntity for SenderRatchets {
+    const ID: MlsEntityId = MlsEntityId::SenderRatchets;
+}
+
 impl Serialize for MlsGroup {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("SerializedMlsGroup", 6)?;
+        let mut state = serializer.serialize_struct("SerializedMlsGroup", 13)?;
         state.serialize_field("mls_group_config", &self.mls_group_config)?;
         state.serialize_field("group", &self.group)?;
-        state.serialize_field("proposal_store", &self.proposal_store)?;
         state.serialize_field("own_leaf_nodes", &self.own_leaf_nodes)?;
         state.serialize_field("aad", &self.aad)?;
         state.serialize_field("resumption_psk_store", &self.group.resumption_psk_store)?;
         state.serialize_field("group_state", &self.group_state)?;
+        state.serialize_field("departure", &self.departure)?;
+        let receiver_state = self.receiver_state();
+        state.serialize_field(
+            "next_sequence_number",
+            &receiver_state.stored_sequence_number(),
+        )?;
+        state.serialize_field("message_buffer", &*lock(&self.message_buffer))?;
+        state.serialize_field("drain", &receiver_state.drain)?;
+        state.serialize_field("own_messages", &self.own_messages)?;
+        state.serialize_field("lost_commit", &self.lost_commit)?;
+        state.serialize_field("event_log", &self.event_log)?;
         state.end()
     }
 }
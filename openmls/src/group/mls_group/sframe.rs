@@ -0,0 +1,314 @@
+//! # SFrame keys
+//!
+//! Conferencing applications encrypt their media with SFrame
+//! ([RFC 9605](https://www.rfc-editor.org/rfc/rfc9605.html)) and can use the
+//! group to manage the SFrame keys as described in Section 5.2 of the RFC.
+//! In every epoch, all members export the same SFrame epoch secret
+//!
+//! ```text
+//! sframe_epoch_secret = MLS-Exporter("SFrame 1.0", "", AEAD.Nk)
+//! ```
+//!
+//! and derive the base key of the sender with leaf index `index` from it:
+//!
+//! ```text
+//! sender_base_key[index] = HKDF-Expand(sframe_epoch_secret,
+//!                            encode_big_endian(index, 4), AEAD.Nk)
+//! ```
+//!
+//! The key ID (KID) of a frame consists of an application-chosen context ID,
+//! the leaf index of the sender (`S` bits) and the lowest `E` bits of the
+//! epoch:
+//!
+//! ```text
+//! KID = (context_id << S + index) << E + (epoch % (1 << E))
+//! ```
+//!
+//! The [`SFrameKeys`] keep the epoch secrets of the most recent epochs of a
+//! group, so that frames that were encrypted before an epoch change can still
+//! be decrypted. Base keys are only derived for the senders that are members
+//! of the group in the epoch of the KID.
+
+use std::{collections::VecDeque, fmt::Debug};
+
+use openmls_traits::types::HashType;
+
+use super::{errors::SFrameError, *};
+
+/// The exporter label of the SFrame epoch secret.
+const SFRAME_EXPORTER_LABEL: &str = "SFrame 1.0";
+
+/// The SFrame cipher suites of RFC 9605.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum SFrameCipherSuite {
+    /// AES-128 in CTR mode with an 80 bit HMAC-SHA256 tag.
+    AES_128_CTR_HMAC_SHA256_80 = 0x0001,
+    /// AES-128 in CTR mode with a 64 bit HMAC-SHA256 tag.
+    AES_128_CTR_HMAC_SHA256_64 = 0x0002,
+    /// AES-128 in CTR mode with a 32 bit HMAC-SHA256 tag.
+    AES_128_CTR_HMAC_SHA256_32 = 0x0003,
+    /// AES-128-GCM with SHA-256.
+    AES_128_GCM_SHA256_128 = 0x0004,
+    /// AES-256-GCM with SHA-512.
+    AES_256_GCM_SHA512_128 = 0x0005,
+}
+
+impl SFrameCipherSuite {
+    /// Returns the length of the AEAD key (`AEAD.Nk`) of the cipher suite,
+    /// which is also the length of the SFrame epoch secret and the base keys.
+    pub fn key_length(&self) -> usize {
+        match self {
+            SFrameCipherSuite::AES_128_CTR_HMAC_SHA256_80
+            | SFrameCipherSuite::AES_128_CTR_HMAC_SHA256_64
+            | SFrameCipherSuite::AES_128_CTR_HMAC_SHA256_32 => 48,
+            SFrameCipherSuite::AES_128_GCM_SHA256_128 => 16,
+            SFrameCipherSuite::AES_256_GCM_SHA512_128 => 32,
+        }
+    }
+
+    /// Returns the hash function of the cipher suite's KDF.
+    pub fn hash_type(&self) -> HashType {
+        match self {
+            SFrameCipherSuite::AES_256_GCM_SHA512_128 => HashType::Sha2_512,
+            _ => HashType::Sha2_256,
+        }
+    }
+}
+
+/// The configuration of [`SFrameKeys`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SFrameConfig {
+    ciphersuite: SFrameCipherSuite,
+    epoch_bits: u32,
+    index_bits: u32,
+    epochs_retained: usize,
+}
+
+impl SFrameConfig {
+    /// Creates a new configuration for the SFrame `ciphersuite`, with
+    /// `epoch_bits` (`E`) bits of the epoch and `index_bits` (`S`) bits of the
+    /// sender's leaf index in the KID. The remaining bits of the KID are used
+    /// for the context ID.
+    ///
+    /// The keys of `epochs_retained` epochs are kept, including the current
+    /// one. Since epochs are identified by their lowest `E` bits, at most
+    /// `2^E` epochs can be retained.
+    ///
+    /// Returns [`SFrameError::InvalidConfiguration`] if `E` or `S` is zero,
+    /// `E + S` is larger than 64, or the number of retained epochs is zero or
+    /// too large.
+    pub fn new(
+        ciphersuite: SFrameCipherSuite,
+        epoch_bits: u32,
+        index_bits: u32,
+        epochs_retained: usize,
+    ) -> Result<Self, SFrameError> {
+        if epoch_bits == 0 || index_bits == 0 || epoch_bits + index_bits > u64::BITS {
+            return Err(SFrameError::InvalidConfiguration);
+        }
+        let max_epochs = 1u64 << epoch_bits;
+        if epochs_retained == 0 || epochs_retained as u64 > max_epochs {
+            return Err(SFrameError::InvalidConfiguration);
+        }
+        Ok(Self {
+            ciphersuite,
+            epoch_bits,
+            index_bits,
+            epochs_retained,
+        })
+    }
+
+    /// Returns the SFrame cipher suite.
+    pub fn ciphersuite(&self) -> SFrameCipherSuite {
+        self.ciphersuite
+    }
+
+    /// Returns the number of bits of the epoch in the KID (`E`).
+    pub fn epoch_bits(&self) -> u32 {
+        self.epoch_bits
+    }
+
+    /// Returns the number of bits of the sender's leaf index in the KID (`S`).
+    pub fn index_bits(&self) -> u32 {
+        self.index_bits
+    }
+
+    /// Returns the number of epochs whose keys are kept.
+    pub fn epochs_retained(&self) -> usize {
+        self.epochs_retained
+    }
+
+    /// Returns the KID of the sender with the leaf index `index` in the
+    /// `epoch` for the `context_id`.
+    ///
+    /// Returns [`SFrameError::IndexTooLarge`] or
+    /// [`SFrameError::ContextIdTooLarge`] if the index or the context ID
+    /// doesn't fit into its bits of the KID.
+    pub fn kid(
+        &self,
+        epoch: GroupEpoch,
+        index: LeafNodeIndex,
+        context_id: u64,
+    ) -> Result<u64, SFrameError> {
+        let index = index.u32() as u64;
+        if index > mask(self.index_bits) {
+            return Err(SFrameError::IndexTooLarge);
+        }
+        let context_bits = u64::BITS - self.epoch_bits - self.index_bits;
+        if context_id > mask(context_bits) {
+            return Err(SFrameError::ContextIdTooLarge);
+        }
+        let context_id = context_id
+            .checked_shl(self.index_bits + self.epoch_bits)
+            .unwrap_or(0);
+        Ok(context_id | (index << self.epoch_bits) | (epoch.as_u64() & mask(self.epoch_bits)))
+    }
+
+    /// Returns the lowest bits of the epoch and the leaf index of the sender
+    /// of a KID.
+    fn parse_kid(&self, kid: u64) -> (u64, LeafNodeIndex) {
+        let epoch = kid & mask(self.epoch_bits);
+        let index = (kid >> self.epoch_bits) & mask(self.index_bits);
+        (epoch, LeafNodeIndex::new(index as u32))
+    }
+}
+
+/// Returns a mask of the lowest `bits` bits.
+fn mask(bits: u32) -> u64 {
+    u64::MAX.checked_shr(u64::BITS - bits).unwrap_or(0)
+}
+
+/// The SFrame secret and members of one epoch.
+struct SFrameEpoch {
+    epoch: GroupEpoch,
+    epoch_authenticator: Vec<u8>,
+    own_index: LeafNodeIndex,
+    members: Vec<LeafNodeIndex>,
+    epoch_secret: Vec<u8>,
+}
+
+/// The SFrame keys of a group, following its epochs.
+///
+/// [`SFrameKeys::update()`] has to be called with the group whenever it may
+/// have moved to a new epoch, e.g. after every merged commit. The keys of the
+/// previous epochs are kept as configured in the [`SFrameConfig`], and older
+/// ones are dropped. See the [module documentation](self) for the derivation
+/// of the keys.
+pub struct SFrameKeys {
+    config: SFrameConfig,
+    // The retained epochs, the current one last.
+    epochs: VecDeque<SFrameEpoch>,
+}
+
+impl Debug for SFrameKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SFrameKeys")
+            .field("config", &self.config)
+            .field("epochs", &self.epochs().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl SFrameKeys {
+    /// Creates SFrame keys with the given configuration. They don't contain
+    /// any keys before the first [`SFrameKeys::update()`].
+    pub fn new(config: SFrameConfig) -> Self {
+        Self {
+            config,
+            epochs: VecDeque::new(),
+        }
+    }
+
+    /// Returns the configuration.
+    pub fn config(&self) -> &SFrameConfig {
+        &self.config
+    }
+
+    /// Returns the epochs whose keys are retained, the current one last.
+    pub fn epochs(&self) -> impl Iterator<Item = GroupEpoch> + '_ {
+        self.epochs.iter().map(|epoch| epoch.epoch)
+    }
+
+    /// Derives the SFrame epoch secret of the current epoch of the `group`,
+    /// if it wasn't derived before, and drops the keys of the epochs that are
+    /// no longer retained.
+    ///
+    /// Returns whether the keys moved to a new epoch.
+    pub fn update(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        group: &MlsGroup,
+    ) -> Result<bool, SFrameError> {
+        let epoch_authenticator = group.epoch_authenticator().as_slice();
+        if self
+            .epochs
+            .back()
+            .map(|epoch| epoch.epoch_authenticator.as_slice())
+            == Some(epoch_authenticator)
+        {
+            return Ok(false);
+        }
+        let epoch_secret = group.export_secret(
+            backend,
+            SFRAME_EXPORTER_LABEL,
+            &[],
+            self.config.ciphersuite.key_length(),
+        )?;
+        // A group that was rolled back or rejoined can reach an epoch again.
+        let epoch = group.epoch();
+        self.epochs.retain(|retained| retained.epoch != epoch);
+        self.epochs.push_back(SFrameEpoch {
+            epoch,
+            epoch_authenticator: epoch_authenticator.to_vec(),
+            own_index: group.own_leaf_index(),
+            members: group.members().map(|member| member.index).collect(),
+            epoch_secret,
+        });
+        while self.epochs.len() > self.config.epochs_retained {
+            self.epochs.pop_front();
+        }
+        Ok(true)
+    }
+
+    /// Returns the KID of the own client in the current epoch for the
+    /// `context_id`, see [`SFrameConfig::kid()`].
+    pub fn own_kid(&self, context_id: u64) -> Result<u64, SFrameError> {
+        let current = self.epochs.back().ok_or(SFrameError::UnknownEpoch)?;
+        self.config
+            .kid(current.epoch, current.own_index, context_id)
+    }
+
+    /// Returns the base key of the sender of the `kid`.
+    ///
+    /// Returns [`SFrameError::UnknownEpoch`] if the epoch of the KID is not
+    /// retained and [`SFrameError::UnknownSender`] if the sender was not a
+    /// member of the group in that epoch.
+    pub fn base_key(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        kid: u64,
+    ) -> Result<Vec<u8>, SFrameError> {
+        let (epoch_bits, index) = self.config.parse_kid(kid);
+        let epoch = self
+            .epochs
+            .iter()
+            .rev()
+            .find(|retained| (retained.epoch.as_u64() & mask(self.config.epoch_bits)) == epoch_bits)
+            .ok_or(SFrameError::UnknownEpoch)?;
+        if !epoch.members.contains(&index) {
+            return Err(SFrameError::UnknownSender);
+        }
+        let key = backend
+            .crypto()
+            .hkdf_expand(
+                self.config.ciphersuite.hash_type(),
+                &epoch.epoch_secret,
+                &index.u32().to_be_bytes(),
+                self.config.ciphersuite.key_length(),
+            )
+            .map_err(LibraryError::unexpected_crypto_error)?;
+        Ok(key.as_slice().to_vec())
+    }
+}
@@ -0,0 +1,239 @@
+//! Speculative processing
+//!
+//! This module contains the [`Speculation`] handle returned by
+//! [`MlsGroup::speculate()`]. It allows processing and merging messages on a
+//! copy of an [`MlsGroup`] without affecting the group itself or the key
+//! store, e.g. to find out what a commit would do before applying it.
+
+use std::sync::{PoisonError, RwLock};
+
+use openmls_traits::key_store::{MlsEntity, MlsEntityId, WriteBatch};
+use thiserror::Error;
+
+use super::*;
+
+/// A speculative copy of an [`MlsGroup`].
+///
+/// The speculation is created through [`MlsGroup::speculate()`] and holds a
+/// copy of the group state as well as a copy-on-write view of the key store
+/// (see [`SpeculativeKeyStore`]). Messages can be processed and commits
+/// merged on the copy without modifying the original group or deleting any
+/// secrets from the key store.
+///
+/// The speculation has to be finished explicitly, either through
+/// [`Speculation::discard()`] or through [`Speculation::promote()`], which
+/// applies all changes to the original group and the key store.
+#[derive(Debug)]
+pub struct Speculation<'a, Provider: OpenMlsCryptoProvider> {
+    original: &'a mut MlsGroup,
+    group: MlsGroup,
+    backend: SpeculativeProvider<'a, Provider>,
+}
+
+impl MlsGroup {
+    /// Returns a [`Speculation`] on a copy of this group.
+    ///
+    /// The copy shares the nodes of the ratchet tree with this group until
+    /// they are changed, and the key store is only copied on write. This
+    /// group can't be used until the speculation is discarded or promoted.
+    pub fn speculate<'a, Provider: OpenMlsCryptoProvider>(
+        &'a mut self,
+        backend: &'a Provider,
+    ) -> Speculation<'a, Provider> {
+        let mut group = self.copy();
+        // The speculative copy doesn't notify the observer.
+        group.observer = None;
+
+        Speculation {
+            original: self,
+            group,
+            backend: SpeculativeProvider {
+                backend,
+                key_store: SpeculativeKeyStore::new(backend.key_store()),
+            },
+        }
+    }
+}
+
+impl<'a, Provider: OpenMlsCryptoProvider> Speculation<'a, Provider> {
+    /// Returns the speculative copy of the group.
+    pub fn group(&self) -> &MlsGroup {
+        &self.group
+    }
+
+    /// Returns the speculative copy of the group together with the
+    /// [`SpeculativeProvider`] that must be used with it.
+    pub fn group_and_backend(&mut self) -> (&mut MlsGroup, &SpeculativeProvider<'a, Provider>) {
+        (&mut self.group, &self.backend)
+    }
+
+    /// Processes an incoming message on the speculative copy of the group.
+    /// See [`MlsGroup::process_message()`] for details.
+    pub fn process_message(
+        &mut self,
+        message: impl Into<ProtocolMessage>,
+    ) -> Result<ProcessedMessage, ProcessMessageError<SpeculativeKeyStoreError>> {
+        self.group.process_message(&self.backend, message)
+    }
+
+    /// Merges a [`StagedCommit`] into the speculative copy of the group. See
+    /// [`MlsGroup::merge_staged_commit()`] for details.
+    pub fn merge_staged_commit(
+        &mut self,
+        staged_commit: StagedCommit,
+    ) -> Result<(), MergeCommitError<SpeculativeKeyStoreError>> {
+        self.group.merge_staged_commit(&self.backend, staged_commit)
+    }
+
+    /// Discards the speculation. The original group and the key store are
+    /// left untouched.
+    pub fn discard(self) {}
+
+    /// Promotes the speculation, i.e. replaces the original group with the
+    /// speculative copy and applies all changes to the key store.
+    ///
+    /// All changes are written in one batch (see
+    /// [`OpenMlsKeyStore::apply_batch()`]).
+    ///
+    /// Returns an error if writing to the key store fails. In that case the
+    /// original group is left untouched and nothing is written.
+    pub fn promote(
+        self,
+    ) -> Result<(), SpeculationError<<Provider::KeyStoreProvider as OpenMlsKeyStore>::Error>> {
+        let Speculation {
+            original,
+            group,
+            backend,
+        } = self;
+        let batch = backend
+            .key_store
+            .batch
+            .into_inner()
+            .map_err(|_| LibraryError::custom("Speculative key store lock is poisoned."))?;
+        backend
+            .backend
+            .key_store()
+            .apply_batch(batch)
+            .map_err(SpeculationError::KeyStoreError)?;
+
+        original.replace_with_copy(group);
+
+        Ok(())
+    }
+}
+
+/// A crypto provider that uses the crypto and randomness providers of the
+/// wrapped provider and a [`SpeculativeKeyStore`] on top of its key store.
+#[derive(Debug)]
+pub struct SpeculativeProvider<'a, Provider: OpenMlsCryptoProvider> {
+    backend: &'a Provider,
+    key_store: SpeculativeKeyStore<'a, Provider::KeyStoreProvider>,
+}
+
+impl<'a, Provider: OpenMlsCryptoProvider> OpenMlsCryptoProvider
+    for SpeculativeProvider<'a, Provider>
+{
+    type CryptoProvider = Provider::CryptoProvider;
+    type RandProvider = Provider::RandProvider;
+    type KeyStoreProvider = SpeculativeKeyStore<'a, Provider::KeyStoreProvider>;
+
+    fn crypto(&self) -> &Self::CryptoProvider {
+        self.backend.crypto()
+    }
+
+    fn rand(&self) -> &Self::RandProvider {
+        self.backend.rand()
+    }
+
+    fn key_store(&self) -> &Self::KeyStoreProvider {
+        &self.key_store
+    }
+}
+
+/// Error of the [`SpeculativeKeyStore`].
+#[derive(Error, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SpeculativeKeyStoreError {
+    /// Error serializing a value.
+    #[error("Error serializing a value.")]
+    SerializationError,
+    /// The lock of the speculative key store is poisoned.
+    #[error("The lock of the speculative key store is poisoned.")]
+    PoisonedLock,
+}
+
+/// A copy-on-write view of a key store.
+///
+/// Reads fall through to the underlying key store unless the key was written
+/// or deleted during the speculation. Writes and deletions are only recorded
+/// in a [`WriteBatch`] and applied to the underlying key store when the
+/// [`Speculation`] is promoted.
+pub struct SpeculativeKeyStore<'a, KeyStore: OpenMlsKeyStore> {
+    key_store: &'a KeyStore,
+    batch: RwLock<WriteBatch>,
+}
+
+impl<'a, KeyStore: OpenMlsKeyStore> SpeculativeKeyStore<'a, KeyStore> {
+    fn new(key_store: &'a KeyStore) -> Self {
+        Self {
+            key_store,
+            batch: RwLock::new(WriteBatch::new()),
+        }
+    }
+}
+
+impl<'a, KeyStore: OpenMlsKeyStore> std::fmt::Debug for SpeculativeKeyStore<'a, KeyStore> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpeculativeKeyStore")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, KeyStore: OpenMlsKeyStore> OpenMlsKeyStore for SpeculativeKeyStore<'a, KeyStore> {
+    type Error = SpeculativeKeyStoreError;
+
+    fn store<V: MlsEntity>(&self, k: &[u8], v: &V) -> Result<(), Self::Error> {
+        let value = self.encode(v)?;
+        self.store_encoded(V::ID, k, &value)
+    }
+
+    fn read<V: MlsEntity>(&self, k: &[u8]) -> Option<V> {
+        // Reads can't fail. The batch is never left half-updated, so it can
+        // still be read if the lock is poisoned.
+        let batch = self.batch.read().unwrap_or_else(PoisonError::into_inner);
+        match batch.read::<V>(k) {
+            Some(value) => value.and_then(|value| self.key_store.decode(value)),
+            None => self.key_store.read(k),
+        }
+    }
+
+    fn delete<V: MlsEntity>(&self, k: &[u8]) -> Result<(), Self::Error> {
+        self.batch
+            .write()
+            .map_err(|_| SpeculativeKeyStoreError::PoisonedLock)?
+            .delete::<V>(k);
+        Ok(())
+    }
+
+    fn encode<V: MlsEntity>(&self, v: &V) -> Result<Vec<u8>, Self::Error> {
+        self.key_store
+            .encode(v)
+            .map_err(|_| SpeculativeKeyStoreError::SerializationError)
+    }
+
+    fn decode<V: MlsEntity>(&self, value: &[u8]) -> Option<V> {
+        self.key_store.decode(value)
+    }
+
+    fn store_encoded(
+        &self,
+        entity_id: MlsEntityId,
+        k: &[u8],
+        value: &[u8],
+    ) -> Result<(), Self::Error> {
+        self.batch
+            .write()
+            .map_err(|_| SpeculativeKeyStoreError::PoisonedLock)?
+            .store(entity_id, k, value.to_vec());
+        Ok(())
+    }
+}
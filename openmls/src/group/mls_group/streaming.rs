@@ -0,0 +1,466 @@
+//! MLS group streaming encryption
+//!
+//! This module allows encrypting large application payloads, e.g. files or
+//! images, in chunks, so that they don't have to be held in memory at once.
+//!
+//! [`MlsGroup::encrypt_stream()`] takes the next application key of the own
+//! sender ratchet from the secret tree and returns an [`EncryptStream`]
+//! together with a [`StreamHeader`] that identifies the key. Every chunk is
+//! encrypted under its own key and nonce that are derived from the
+//! application key and the index of the chunk, and the last chunk is
+//! marked as such. Receivers pass the header and the first chunk to
+//! [`MlsGroup::decrypt_stream()`] and decrypt the remaining chunks in order
+//! with the returned [`DecryptStream`]. The header is bound to the keys of
+//! the chunks, so the application key is only used up once the first chunk
+//! was decrypted. Reordered, modified or dropped chunks, as well as a
+//! truncated stream, are detected.
+//!
+//! Like all application keys, the key of a stream is shared by the group. The
+//! chunks prove that the stream was created by a member of the group in the
+//! epoch of the header, but not by which member. Applications that need to
+//! authenticate the sender should send the [`StreamHeader`] in an application
+//! message, which is signed by the sender.
+
+use serde::{Deserialize, Serialize};
+use tls_codec::{
+    Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait, TlsDeserialize,
+    TlsSerialize, TlsSize, VLBytes,
+};
+
+use crate::{
+    ciphersuite::{AeadKey, AeadNonce, Secret},
+    tree::secret_tree::SecretType,
+    versions::ProtocolVersion,
+};
+
+use super::*;
+
+/// The label used to derive the secret of a stream from the application key.
+const STREAM_SECRET_LABEL: &str = "stream";
+/// The label used to derive the key of a chunk from the stream secret.
+const CHUNK_KEY_LABEL: &str = "stream chunk key";
+/// The label used to derive the nonce of a chunk from the stream secret.
+const CHUNK_NONCE_LABEL: &str = "stream chunk nonce";
+
+/// The header of a stream of encrypted chunks. It identifies the application
+/// key of the stream and has to be passed to [`MlsGroup::decrypt_stream()`]
+/// by the receivers.
+///
+/// ```c
+/// struct {
+///     opaque group_id<V>;
+///     uint64 epoch;
+///     uint32 sender;
+///     uint32 generation;
+/// } StreamHeader;
+/// ```
+#[derive(
+    Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+pub struct StreamHeader {
+    group_id: GroupId,
+    epoch: GroupEpoch,
+    sender: LeafNodeIndex,
+    generation: u32,
+}
+
+impl StreamHeader {
+    /// Returns the [`GroupId`] of the group the stream belongs to.
+    pub fn group_id(&self) -> &GroupId {
+        &self.group_id
+    }
+
+    /// Returns the epoch of the application key of the stream.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the leaf index of the member that encrypts the stream, as
+    /// claimed by the header.
+    pub fn sender(&self) -> LeafNodeIndex {
+        self.sender
+    }
+
+    /// Returns the generation of the application key of the stream.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+/// An encrypted chunk of a stream.
+///
+/// ```c
+/// struct {
+///     bool last;
+///     opaque ciphertext<V>;
+/// } StreamChunk;
+/// ```
+#[derive(Debug, TlsSerialize, TlsDeserialize, TlsSize)]
+struct StreamChunk {
+    last: bool,
+    ciphertext: VLBytes,
+}
+
+/// The AAD of an encrypted chunk, which binds the chunk to its position in
+/// the stream.
+///
+/// ```c
+/// struct {
+///     uint32 index;
+///     bool last;
+/// } StreamChunkAad;
+/// ```
+#[derive(TlsSerialize, TlsSize)]
+struct StreamChunkAad {
+    index: u32,
+    last: bool,
+}
+
+/// The secret of a stream that the keys of the chunks are derived from.
+#[derive(Debug)]
+struct StreamSecret {
+    secret: Secret,
+    next_index: u32,
+    finished: bool,
+}
+
+impl StreamSecret {
+    /// Derives the stream secret from the application key of the stream:
+    ///
+    /// ```text
+    /// stream_secret = ExpandWithLabel(Extract(0, application_key), "stream",
+    ///                                 StreamHeader, KDF.Nh)
+    /// chunk_key[i] = ExpandWithLabel(stream_secret, "stream chunk key",
+    ///                                uint32(i), AEAD.Nk)
+    /// chunk_nonce[i] = ExpandWithLabel(stream_secret, "stream chunk nonce",
+    ///                                  uint32(i), AEAD.Nn)
+    /// ```
+    fn new(
+        backend: &impl OpenMlsCryptoProvider,
+        ciphersuite: Ciphersuite,
+        mls_version: ProtocolVersion,
+        application_key: &AeadKey,
+        header: &StreamHeader,
+    ) -> Result<Self, LibraryError> {
+        let header = header
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+        // The application key may be shorter than the hash length, so it is
+        // extracted first.
+        let secret = Secret::zero(ciphersuite, mls_version)
+            .hkdf_extract(
+                backend,
+                &application_key.to_secret(ciphersuite, mls_version),
+            )
+            .and_then(|prk| {
+                prk.kdf_expand_label(
+                    backend,
+                    STREAM_SECRET_LABEL,
+                    &header,
+                    ciphersuite.hash_length(),
+                )
+            })
+            .map_err(LibraryError::unexpected_crypto_error)?;
+        Ok(Self {
+            secret,
+            next_index: 0,
+            finished: false,
+        })
+    }
+
+    /// Returns the key, the nonce and the AAD of the next chunk.
+    fn next_chunk(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        last: bool,
+    ) -> Result<(AeadKey, AeadNonce, Vec<u8>), StreamChunkError> {
+        if self.finished {
+            return Err(StreamChunkError::Finished);
+        }
+        let index = self.next_index;
+        self.next_index = index
+            .checked_add(1)
+            .ok_or(StreamChunkError::TooManyChunks)?;
+
+        let ciphersuite = self.secret.ciphersuite();
+        let context = index.to_be_bytes();
+        let key = self
+            .secret
+            .kdf_expand_label(
+                backend,
+                CHUNK_KEY_LABEL,
+                &context,
+                ciphersuite.aead_key_length(),
+            )
+            .map_err(LibraryError::unexpected_crypto_error)?;
+        let nonce = self
+            .secret
+            .kdf_expand_label(
+                backend,
+                CHUNK_NONCE_LABEL,
+                &context,
+                ciphersuite.aead_nonce_length(),
+            )
+            .map_err(LibraryError::unexpected_crypto_error)?;
+        let aad = StreamChunkAad { index, last }
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+        Ok((
+            AeadKey::from_secret(key),
+            AeadNonce::from_secret(nonce),
+            aad,
+        ))
+    }
+}
+
+/// Encrypts a stream of chunks. See [`MlsGroup::encrypt_stream()`].
+#[derive(Debug)]
+pub struct EncryptStream {
+    header: StreamHeader,
+    secret: StreamSecret,
+}
+
+impl EncryptStream {
+    /// Returns the [`StreamHeader`] that the receivers need to decrypt the
+    /// stream.
+    pub fn header(&self) -> &StreamHeader {
+        &self.header
+    }
+
+    /// Encrypts the next `chunk` of the stream. Chunks may have different
+    /// lengths, including zero.
+    ///
+    /// Returns [`StreamChunkError::Finished`] if the stream was already
+    /// finished with [`Self::encrypt_last_chunk()`].
+    pub fn encrypt_chunk(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        chunk: &[u8],
+    ) -> Result<Vec<u8>, StreamChunkError> {
+        self.encrypt(backend, chunk, false)
+    }
+
+    /// Encrypts the last `chunk` of the stream and finishes the stream. Every
+    /// stream has to end with a last chunk, so that the receivers can detect
+    /// a truncated stream.
+    pub fn encrypt_last_chunk(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        chunk: &[u8],
+    ) -> Result<Vec<u8>, StreamChunkError> {
+        let ciphertext = self.encrypt(backend, chunk, true)?;
+        self.secret.finished = true;
+        Ok(ciphertext)
+    }
+
+    /// Returns `true` if the last chunk of the stream was encrypted.
+    pub fn is_finished(&self) -> bool {
+        self.secret.finished
+    }
+
+    fn encrypt(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        chunk: &[u8],
+        last: bool,
+    ) -> Result<Vec<u8>, StreamChunkError> {
+        let (key, nonce, aad) = self.secret.next_chunk(backend, last)?;
+        let ciphertext = key
+            .aead_seal(backend, chunk, &aad, &nonce)
+            .map_err(LibraryError::unexpected_crypto_error)?;
+        StreamChunk {
+            last,
+            ciphertext: ciphertext.into(),
+        }
+        .tls_serialize_detached()
+        .map_err(|e| LibraryError::missing_bound_check(e).into())
+    }
+}
+
+/// Decrypts a stream of chunks. See [`MlsGroup::decrypt_stream()`].
+#[derive(Debug)]
+pub struct DecryptStream {
+    header: StreamHeader,
+    secret: StreamSecret,
+}
+
+impl DecryptStream {
+    /// Returns the [`StreamHeader`] of the stream.
+    pub fn header(&self) -> &StreamHeader {
+        &self.header
+    }
+
+    /// Decrypts the next chunk of the stream. The chunks have to be decrypted
+    /// in the order in which they were encrypted.
+    ///
+    /// Returns [`StreamChunkError::InvalidChunk`] if the chunk was modified,
+    /// is not the next chunk of the stream or belongs to a different stream,
+    /// and [`StreamChunkError::Finished`] if the last chunk of the stream was
+    /// already decrypted. A chunk that fails to decrypt doesn't advance the
+    /// stream.
+    pub fn decrypt_chunk(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider,
+        chunk: &[u8],
+    ) -> Result<Vec<u8>, StreamChunkError> {
+        let chunk = StreamChunk::tls_deserialize_exact(chunk)
+            .map_err(|_| StreamChunkError::MalformedChunk)?;
+        let next_index = self.secret.next_index;
+        let (key, nonce, aad) = self.secret.next_chunk(backend, chunk.last)?;
+        let plaintext = key
+            .aead_open(backend, chunk.ciphertext.as_slice(), &aad, &nonce)
+            .map_err(|_| {
+                self.secret.next_index = next_index;
+                StreamChunkError::InvalidChunk
+            })?;
+        self.secret.finished = chunk.last;
+        Ok(plaintext)
+    }
+
+    /// Returns `true` if the last chunk of the stream was decrypted.
+    pub fn is_finished(&self) -> bool {
+        self.secret.finished
+    }
+
+    /// Consumes the stream after all chunks were decrypted.
+    ///
+    /// Returns [`StreamChunkError::Truncated`] if the last chunk of the
+    /// stream was not decrypted, i.e. if the stream is incomplete.
+    pub fn finish(self) -> Result<(), StreamChunkError> {
+        if !self.secret.finished {
+            return Err(StreamChunkError::Truncated);
+        }
+        Ok(())
+    }
+}
+
+impl MlsGroup {
+    /// Starts a stream of encrypted chunks for a large application payload.
+    ///
+    /// The stream uses the next application key of the own sender ratchet,
+    /// i.e. it takes the place of one application message. Receivers see a
+    /// gap in the generations of the own application messages until they
+    /// decrypt the stream, so the header should be delivered within the
+    /// `out_of_order_tolerance` of their [`SenderRatchetConfiguration`].
+    ///
+    /// Returns [`StreamError::GroupStateError(MlsGroupStateError::UseAfterEviction)`](MlsGroupStateError::UseAfterEviction)
+    /// if the member is no longer part of the group and
+    /// [`StreamError::GroupStateError(MlsGroupStateError::PendingProposal)`](MlsGroupStateError::PendingProposal)
+    /// if pending proposals exist, like [`MlsGroup::create_message()`].
+    pub fn encrypt_stream<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<EncryptStream, StreamError<KeyStore::Error>> {
+        if !self.is_active() {
+            return Err(MlsGroupStateError::UseAfterEviction.into());
+        }
+        if !self.proposal_store.is_empty() {
+            return Err(MlsGroupStateError::PendingProposal.into());
+        }
+
+        let ciphersuite = self.ciphersuite();
+        let sender = self.own_leaf_index();
+        let (generation, (application_key, _nonce)) = self
+            .group
+            .message_secrets_store_mut()
+            .message_secrets_mut()
+            .secret_tree_mut()
+            .secret_for_encryption(ciphersuite, backend, sender, SecretType::ApplicationSecret)?;
+        // The ratchet moved forward, so the key is never used again
+        self.store(backend).map_err(StreamError::StorageError)?;
+
+        let header = StreamHeader {
+            group_id: self.group_id().clone(),
+            epoch: self.epoch(),
+            sender,
+            generation,
+        };
+        let secret = StreamSecret::new(
+            backend,
+            ciphersuite,
+            self.group.version(),
+            &application_key,
+            &header,
+        )?;
+        Ok(EncryptStream { header, secret })
+    }
+
+    /// Starts decrypting the stream with the given `header`, which was
+    /// created by another member with [`MlsGroup::encrypt_stream()`], and
+    /// decrypts the `first_chunk` of the stream.
+    ///
+    /// The application key of the stream is taken from the secret tree of the
+    /// epoch of the stream, which may be a past epoch as long as its secrets
+    /// are kept (see [`MlsGroupConfig::max_past_epochs()`]). Like the key of
+    /// an application message, it can only be taken once, so a stream can
+    /// only be decrypted once.
+    ///
+    /// The header is only authenticated by the first chunk, so the key is
+    /// taken from a copy of the secret tree and the ratchet of the sender is
+    /// only advanced and written to the storage once the first chunk was
+    /// decrypted. A forged header or a first chunk that doesn't match the
+    /// header leave the group unchanged.
+    ///
+    /// Returns [`StreamError::WrongGroupId`] if the stream belongs to a
+    /// different group, [`StreamError::WrongEpoch`] if the epoch of the stream
+    /// is in the future, [`StreamError::InvalidSender`] if the stream was
+    /// created by the own client and [`StreamError::StreamChunkError`] if the
+    /// first chunk can't be decrypted with the key of the header.
+    pub fn decrypt_stream<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        header: &StreamHeader,
+        first_chunk: &[u8],
+    ) -> Result<(DecryptStream, Vec<u8>), StreamError<KeyStore::Error>> {
+        if header.group_id() != self.group_id() {
+            return Err(StreamError::WrongGroupId);
+        }
+        if header.epoch() > self.epoch() {
+            return Err(StreamError::WrongEpoch);
+        }
+        if header.sender() == self.own_leaf_index() {
+            return Err(StreamError::InvalidSender);
+        }
+
+        // Secrets of past epochs that expired must not be used anymore
+        self.group.message_secrets_store_mut().drop_expired();
+
+        let ciphersuite = self.ciphersuite();
+        let sender_ratchet_configuration =
+            self.configuration().sender_ratchet_configuration().clone();
+        let secret_tree = self
+            .group
+            .message_secrets_for_epoch(header.epoch())?
+            .secret_tree()
+            .clone();
+        let (application_key, _nonce) = secret_tree.secret_for_decryption(
+            ciphersuite,
+            backend,
+            header.sender(),
+            SecretType::ApplicationSecret,
+            header.generation(),
+            &sender_ratchet_configuration,
+        )?;
+        let secret = StreamSecret::new(
+            backend,
+            ciphersuite,
+            self.group.version(),
+            &application_key,
+            header,
+        )?;
+        let mut decrypt_stream = DecryptStream {
+            header: header.clone(),
+            secret,
+        };
+        let plaintext = decrypt_stream.decrypt_chunk(backend, first_chunk)?;
+
+        // The header is authentic, so the key is used up
+        *self
+            .group
+            .message_secrets_mut(header.epoch())?
+            .secret_tree_mut() = secret_tree;
+        self.store(backend).map_err(StreamError::StorageError)?;
+
+        Ok((decrypt_stream, plaintext))
+    }
+}
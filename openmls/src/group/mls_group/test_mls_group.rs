@@ -34,13 +34,7 @@ fn test_mls_group_persistence(ciphersuite: Ciphersuite, backend: &impl OpenMlsCr
     )
     .expect("An unexpected error occurred.");
 
-    // Check the internal state has changed
-    assert_eq!(alice_group.state_changed(), InnerState::Changed);
-
-    alice_group
-        .save(backend)
-        .expect("Could not write group state to file");
-
+    // The group state was written when the group was created
     let alice_group_deserialized =
         MlsGroup::load(&group_id, backend).expect("Could not deserialize MlsGroup");
 
@@ -54,6 +48,27 @@ fn test_mls_group_persistence(ciphersuite: Ciphersuite, backend: &impl OpenMlsCr
             alice_group_deserialized.export_secret(backend, "test", &[], 32)
         )
     );
+
+    // Every operation writes the new group state
+    alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not update own key package.");
+    let alice_group_deserialized =
+        MlsGroup::load(&group_id, backend).expect("Could not deserialize MlsGroup");
+    assert!(alice_group_deserialized.pending_commit().is_some());
+
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let alice_group_deserialized =
+        MlsGroup::load(&group_id, backend).expect("Could not deserialize MlsGroup");
+
+    assert_eq!(alice_group_deserialized.epoch(), alice_group.epoch());
+    assert!(alice_group_deserialized.pending_commit().is_none());
+    assert_eq!(
+        alice_group.export_secret(backend, "test", &[], 32),
+        alice_group_deserialized.export_secret(backend, "test", &[], 32)
+    );
 }
 
 // This tests if the remover is correctly passed to the callback when one member
@@ -158,7 +173,9 @@ fn remover(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
             // Check that Bob was removed
             assert_eq!(remove_proposal.removed(), LeafNodeIndex::new(1));
             // Store proposal
-            charlie_group.store_pending_proposal(*staged_proposal.clone());
+            charlie_group
+                .store_pending_proposal(backend, *staged_proposal.clone())
+                .expect("Could not store proposal.");
         } else {
             unreachable!("Expected a Proposal.");
         }
@@ -372,7 +389,9 @@ fn test_pending_commit_logic(ciphersuite: Ciphersuite, backend: &impl OpenMlsCry
     if let ProcessedMessageContent::ProposalMessage(staged_proposal) =
         alice_processed_message.into_content()
     {
-        alice_group.store_pending_proposal(*staged_proposal);
+        alice_group
+            .store_pending_proposal(backend, *staged_proposal)
+            .expect("Could not store proposal.");
     } else {
         unreachable!("Expected a StagedCommit.");
     }
@@ -442,7 +461,9 @@ fn test_pending_commit_logic(ciphersuite: Ciphersuite, backend: &impl OpenMlsCry
     );
 
     // Clearing the pending commit should actually clear it.
-    alice_group.clear_pending_commit();
+    alice_group
+        .clear_pending_commit(backend)
+        .expect("Could not clear pending commit.");
     assert!(alice_group.pending_commit().is_none());
 
     // Creating a new commit should commit the same proposals.
@@ -616,13 +637,18 @@ fn remove_prosposal_by_ref(ciphersuite: Ciphersuite, backend: &impl OpenMlsCrypt
     assert_eq!(alice_group.proposal_store.proposals().count(), 1);
     // clearing the proposal by reference
     alice_group
-        .remove_pending_proposal(reference.clone())
+        .remove_pending_proposal(backend, reference.clone())
         .unwrap();
     assert!(alice_group.proposal_store.is_empty());
 
     // the proposal should not be stored anymore
-    let err = alice_group.remove_pending_proposal(reference).unwrap_err();
-    assert_eq!(err, MlsGroupStateError::PendingProposalNotFound);
+    let err = alice_group
+        .remove_pending_proposal(backend, reference)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        RemoveProposalError::GroupStateError(MlsGroupStateError::PendingProposalNotFound)
+    );
 
     // the commit should have no proposal
     let (commit, _, _) = alice_group
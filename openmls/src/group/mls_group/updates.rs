@@ -1,7 +1,13 @@
 use core_group::create_commit_params::CreateCommitParams;
-use openmls_traits::signatures::Signer;
+use openmls_traits::signatures::{AsyncSigner, Signer};
 
-use crate::{messages::group_info::GroupInfo, treesync::LeafNode, versions::ProtocolVersion};
+use crate::{
+    credentials::CredentialWithKey,
+    extensions::{CompromiseRecoveryExtension, Extension, Extensions},
+    messages::group_info::GroupInfo,
+    treesync::LeafNode,
+    versions::ProtocolVersion,
+};
 
 use super::*;
 
@@ -28,16 +34,33 @@ impl MlsGroup {
     ) -> Result<
         (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
         SelfUpdateError<KeyStore::Error>,
+    > {
+        crate::utils::poll_ready(self.self_update_internal(backend, signer))
+    }
+
+    /// Like [`MlsGroup::self_update()`], but signs with an [`AsyncSigner`].
+    #[allow(clippy::type_complexity)]
+    pub(super) async fn self_update_internal<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl AsyncSigner,
+    ) -> Result<
+        (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
+        SelfUpdateError<KeyStore::Error>,
     > {
         self.is_operational()?;
 
         let params = CreateCommitParams::builder()
             .framing_parameters(self.framing_parameters())
             .proposal_store(&self.proposal_store)
+            .psk_resolver(self.psk_resolver.as_deref())
             .build();
         // Create Commit over all proposals.
         // TODO #751
-        let create_commit_result = self.group.create_commit(params, backend, signer)?;
+        let create_commit_result = self
+            .group
+            .create_commit_async(params, backend, signer)
+            .await?;
 
         // Convert PublicMessage messages to MLSMessage and encrypt them if required by
         // the configuration
@@ -48,9 +71,65 @@ impl MlsGroup {
         self.group_state = MlsGroupState::PendingCommit(Box::new(PendingCommitState::Member(
             create_commit_result.staged_commit,
         )));
+        self.store(backend).map_err(SelfUpdateError::StorageError)?;
+
+        Ok((
+            mls_message,
+            create_commit_result
+                .welcome_option
+                .map(|w| MlsMessageOut::from_welcome(w, self.group.version())),
+            create_commit_result.group_info,
+        ))
+    }
 
-        // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+    /// Replaces the group context extensions with the given [`Extensions`]
+    /// by committing an inline `GroupContextExtensions` proposal together
+    /// with all pending proposals.
+    ///
+    /// If successful, it returns a tuple of [`MlsMessageOut`] (containing the
+    /// commit), an optional [`MlsMessageOut`] (containing the [`Welcome`]) and the [GroupInfo].
+    /// The [Welcome] is [Some] when the queue of pending proposals contained
+    /// add proposals
+    /// The [GroupInfo] is [Some] if the group has the `use_ratchet_tree_extension` flag set.
+    ///
+    /// Returns an error if there is a pending commit or if a member of the
+    /// group doesn't support one of the extensions.
+    #[allow(clippy::type_complexity)]
+    pub fn update_group_context_extensions<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        extensions: Extensions,
+    ) -> Result<
+        (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
+        UpdateGroupContextExtensionsError<KeyStore::Error>,
+    > {
+        self.is_operational()?;
+
+        self.group.validate_group_context_extensions(&extensions)?;
+        let inline_proposals = vec![Proposal::GroupContextExtensions(
+            GroupContextExtensionProposal::new(extensions),
+        )];
+
+        let params = CreateCommitParams::builder()
+            .framing_parameters(self.framing_parameters())
+            .proposal_store(&self.proposal_store)
+            .psk_resolver(self.psk_resolver.as_deref())
+            .inline_proposals(inline_proposals)
+            .build();
+        let create_commit_result = self.group.create_commit(params, backend, signer)?;
+
+        // Convert PublicMessage messages to MLSMessage and encrypt them if required by
+        // the configuration
+        let mls_message = self.content_to_mls_message(create_commit_result.commit, backend)?;
+
+        // Set the current group state to [`MlsGroupState::PendingCommit`],
+        // storing the current [`StagedCommit`] from the commit results
+        self.group_state = MlsGroupState::PendingCommit(Box::new(PendingCommitState::Member(
+            create_commit_result.staged_commit,
+        )));
+        self.store(backend)
+            .map_err(UpdateGroupContextExtensionsError::StorageError)?;
 
         Ok((
             mls_message,
@@ -64,10 +143,10 @@ impl MlsGroup {
     /// Creates a proposal to update the own leaf node. Optionally, a
     /// [`LeafNode`] can be provided to update the leaf node. Note that its
     /// private key must be manually added to the key store.
-    fn _propose_self_udpate<KeyStore: OpenMlsKeyStore>(
+    pub(super) async fn _propose_self_udpate<KeyStore: OpenMlsKeyStore>(
         &mut self,
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
-        signer: &impl Signer,
+        signer: &impl AsyncSigner,
         leaf_node: Option<LeafNode>,
     ) -> Result<AuthenticatedContent, ProposeSelfUpdateError<KeyStore::Error>> {
         self.is_operational()?;
@@ -83,33 +162,36 @@ impl MlsGroup {
             .ok_or_else(|| LibraryError::custom("The tree is broken. Couldn't find own leaf."))?
             .clone();
         if let Some(leaf) = leaf_node {
-            own_leaf.update_and_re_sign(
-                None,
-                leaf,
-                self.group_id().clone(),
-                self.own_leaf_index(),
-                signer,
-            )?
+            own_leaf
+                .update_and_re_sign(
+                    None,
+                    leaf,
+                    self.group_id().clone(),
+                    self.own_leaf_index(),
+                    signer,
+                )
+                .await?
         } else {
-            let keypair = own_leaf.rekey(
-                self.group_id(),
-                self.own_leaf_index(),
-                self.ciphersuite(),
-                ProtocolVersion::default(), // XXX: openmls/openmls#1065
-                backend,
-                signer,
-            )?;
+            let keypair = own_leaf
+                .rekey(
+                    self.group_id(),
+                    self.own_leaf_index(),
+                    self.ciphersuite(),
+                    ProtocolVersion::default(), // XXX: openmls/openmls#1065
+                    backend,
+                    signer,
+                )
+                .await?;
             // TODO #1207: Move to the top of the function.
             keypair
                 .write_to_key_store(backend)
                 .map_err(ProposeSelfUpdateError::KeyStoreError)?;
         };
 
-        let update_proposal = self.group.create_update_proposal(
-            self.framing_parameters(),
-            own_leaf.clone(),
-            signer,
-        )?;
+        let update_proposal = self
+            .group
+            .create_update_proposal_async(self.framing_parameters(), own_leaf.clone(), signer)
+            .await?;
 
         self.own_leaf_nodes.push(own_leaf);
 
@@ -123,7 +205,8 @@ impl MlsGroup {
         signer: &impl Signer,
         leaf_node: Option<LeafNode>,
     ) -> Result<(MlsMessageOut, ProposalRef), ProposeSelfUpdateError<KeyStore::Error>> {
-        let update_proposal = self._propose_self_udpate(backend, signer, leaf_node)?;
+        let update_proposal =
+            crate::utils::poll_ready(self._propose_self_udpate(backend, signer, leaf_node))?;
         let proposal = QueuedProposal::from_authenticated_content_by_ref(
             self.ciphersuite(),
             backend,
@@ -133,9 +216,8 @@ impl MlsGroup {
         self.proposal_store.add(proposal);
 
         let mls_message = self.content_to_mls_message(update_proposal, backend)?;
-
-        // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        self.store(backend)
+            .map_err(ProposeSelfUpdateError::StorageError)?;
 
         Ok((mls_message, proposal_ref))
     }
@@ -147,7 +229,8 @@ impl MlsGroup {
         signer: &impl Signer,
         leaf_node: Option<LeafNode>,
     ) -> Result<(MlsMessageOut, ProposalRef), ProposeSelfUpdateError<KeyStore::Error>> {
-        let update_proposal = self._propose_self_udpate(backend, signer, leaf_node)?;
+        let update_proposal =
+            crate::utils::poll_ready(self._propose_self_udpate(backend, signer, leaf_node))?;
         let proposal = QueuedProposal::from_authenticated_content_by_value(
             self.ciphersuite(),
             backend,
@@ -157,10 +240,182 @@ impl MlsGroup {
         self.proposal_store.add(proposal);
 
         let mls_message = self.content_to_mls_message(update_proposal, backend)?;
+        self.store(backend)
+            .map_err(ProposeSelfUpdateError::StorageError)?;
 
-        // Since the state of the group might be changed, arm the state flag
-        self.flag_state_change();
+        Ok((mls_message, proposal_ref))
+    }
+
+    /// Replaces the own credential and signature key with the given
+    /// [`CredentialWithKey`] by committing all pending proposals together
+    /// with an update path.
+    ///
+    /// The commit is signed with the current `signer`, while the new leaf node
+    /// in the update path is signed with the `new_signer`, which has to
+    /// belong to the signature key in `credential_with_key`. The application
+    /// has to store the `new_signer` and use it for this group once the
+    /// commit is merged. Other members see the rotation in
+    /// [`StagedCommit::credential_updates()`].
+    ///
+    /// If successful, it returns a tuple of [`MlsMessageOut`] (containing the
+    /// commit), an optional [`MlsMessageOut`] (containing the [`Welcome`]) and the [GroupInfo].
+    /// The [Welcome] is [Some] when the queue of pending proposals contained
+    /// add proposals
+    /// The [GroupInfo] is [Some] if the group has the `use_ratchet_tree_extension` flag set.
+    ///
+    /// Returns an error if there is a pending commit or if the signature
+    /// scheme of the `new_signer` doesn't match the ciphersuite of the group.
+    #[allow(clippy::type_complexity)]
+    pub fn update_credential<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        new_signer: &impl Signer,
+        credential_with_key: CredentialWithKey,
+    ) -> Result<
+        (MlsMessageOut, Option<MlsMessageOut>, Option<GroupInfo>),
+        SelfUpdateError<KeyStore::Error>,
+    > {
+        self.is_operational()?;
+
+        let params = CreateCommitParams::builder()
+            .framing_parameters(self.framing_parameters())
+            .proposal_store(&self.proposal_store)
+            .psk_resolver(self.psk_resolver.as_deref())
+            .force_self_update(true)
+            .credential_with_key(credential_with_key)
+            .build();
+        let create_commit_result = crate::utils::poll_ready(
+            self.group
+                .create_commit_with_leaf_signer_async(params, backend, signer, new_signer),
+        )?;
+
+        // Convert PublicMessage messages to MLSMessage and encrypt them if required by
+        // the configuration
+        let mls_message = self.content_to_mls_message(create_commit_result.commit, backend)?;
+
+        // Set the current group state to [`MlsGroupState::PendingCommit`],
+        // storing the current [`StagedCommit`] from the commit results
+        self.group_state = MlsGroupState::PendingCommit(Box::new(PendingCommitState::Member(
+            create_commit_result.staged_commit,
+        )));
+        self.store(backend).map_err(SelfUpdateError::StorageError)?;
+
+        Ok((
+            mls_message,
+            create_commit_result
+                .welcome_option
+                .map(|w| MlsMessageOut::from_welcome(w, self.group.version())),
+            create_commit_result.group_info,
+        ))
+    }
+
+    /// Creates an Update proposal that replaces the own credential and
+    /// signature key with the given [`CredentialWithKey`].
+    ///
+    /// The proposal is signed with the current `signer`, while the new leaf
+    /// node in the proposal is signed with the `new_signer`, which has to
+    /// belong to the signature key in `credential_with_key`. The private key
+    /// of the new encryption key is written to the key store. The application
+    /// has to store the `new_signer` and use it for this group once a commit
+    /// covering the proposal is merged. Other members see the rotation in
+    /// [`StagedCommit::credential_updates()`].
+    ///
+    /// Returns an error if the group is not operational or if the signature
+    /// scheme of the `new_signer` doesn't match the ciphersuite of the group.
+    pub fn propose_credential_update<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        new_signer: &impl Signer,
+        credential_with_key: CredentialWithKey,
+    ) -> Result<(MlsMessageOut, ProposalRef), ProposeSelfUpdateError<KeyStore::Error>> {
+        self.is_operational()?;
+
+        if self.ciphersuite().signature_algorithm() != new_signer.signature_scheme() {
+            return Err(ProposeSelfUpdateError::CiphersuiteSignatureSchemeMismatch);
+        }
+
+        let mut own_leaf = self
+            .group
+            .public_group()
+            .leaf(self.own_leaf_index())
+            .ok_or_else(|| LibraryError::custom("The tree is broken. Couldn't find own leaf."))?
+            .clone();
+        own_leaf.set_credential_with_key(credential_with_key);
+        let keypair = crate::utils::poll_ready(own_leaf.rekey(
+            self.group_id(),
+            self.own_leaf_index(),
+            self.ciphersuite(),
+            ProtocolVersion::default(), // XXX: openmls/openmls#1065
+            backend,
+            new_signer,
+        ))?;
+        keypair
+            .write_to_key_store(backend)
+            .map_err(ProposeSelfUpdateError::KeyStoreError)?;
+
+        let update_proposal = crate::utils::poll_ready(self.group.create_update_proposal_async(
+            self.framing_parameters(),
+            own_leaf.clone(),
+            signer,
+        ))?;
+        self.own_leaf_nodes.push(own_leaf);
+
+        let proposal = QueuedProposal::from_authenticated_content_by_ref(
+            self.ciphersuite(),
+            backend,
+            update_proposal.clone(),
+        )?;
+        let proposal_ref = proposal.proposal_reference();
+        self.proposal_store.add(proposal);
+
+        let mls_message = self.content_to_mls_message(update_proposal, backend)?;
+        self.store(backend)
+            .map_err(ProposeSelfUpdateError::StorageError)?;
 
         Ok((mls_message, proposal_ref))
     }
+
+    /// Re-keys the group after a suspected compromise of one of the own
+    /// devices.
+    ///
+    /// This creates a commit with a fresh update path that only covers a
+    /// `GroupContextExtensions` proposal setting a
+    /// [`CompromiseRecoveryExtension`] for the new epoch. Pending proposals
+    /// are not committed. Other members can recognize the commit with
+    /// [`StagedCommit::is_compromise_recovery()`], e.g. to surface it to the
+    /// user. When the commit is merged, the message secrets of all past
+    /// epochs are dropped immediately, regardless of the
+    /// [`PastEpochRetention`] of the group.
+    ///
+    /// If successful, it returns a tuple of [`MlsMessageOut`] (containing the
+    /// commit) and an optional [`GroupInfo`], which is [Some] if the group has
+    /// the `use_ratchet_tree_extension` flag set. The group is in the
+    /// [`MlsGroupState::PendingCommit`] state afterwards.
+    ///
+    /// Returns an error if there is a pending commit.
+    pub fn emergency_rekey<KeyStore: OpenMlsKeyStore>(
+        &mut self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+    ) -> Result<(MlsMessageOut, Option<GroupInfo>), CommitBuilderError<KeyStore::Error>> {
+        let mut epoch = self.epoch();
+        epoch.increment();
+        let mut extensions = self.group.context().extensions().clone();
+        extensions.add_or_replace(Extension::CompromiseRecovery(
+            CompromiseRecoveryExtension::new(epoch),
+        ));
+
+        let (commit, _welcome, group_info) = self
+            .commit_builder()
+            .include_proposals(vec![])
+            .add_proposal(Proposal::GroupContextExtensions(
+                GroupContextExtensionProposal::new(extensions),
+            ))
+            .force_self_update(true)
+            .finalize(backend, signer)?;
+
+        Ok((commit, group_info))
+    }
 }
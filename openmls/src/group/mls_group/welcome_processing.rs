@@ -0,0 +1,228 @@
+//! MLS group Welcome processing
+//!
+//! This module contains the [`WelcomeProcessingBuilder`] that allows
+//! configuring how a group is joined from a [`Welcome`] message.
+
+use super::*;
+use crate::{
+    group::{core_group::new_from_welcome::WelcomeOptions, errors::WelcomeError},
+    key_packages::LifetimePolicy,
+    schedule::psk::store::ResumptionPskStore,
+    treesync::{RatchetTreeFetcher, RatchetTreeIn, RatchetTreeSource},
+    write_batch::BatchProvider,
+};
+
+/// Builder for joining a group from a [`Welcome`] message.
+///
+/// The builder is created through [`MlsGroup::welcome_processing_builder()`]
+/// and allows choosing where the ratchet tree comes from, checking the
+/// lifetimes of the leaf nodes in the tree, resolving external PSKs and
+/// keeping the key package the [`Welcome`] was created for.
+///
+/// By default the ratchet tree is only taken from the ratchet tree extension
+/// of the group info, lifetimes are not checked, PSKs are only looked up in
+/// the key store and in stored groups, and the key package is deleted unless
+/// it is a last resort key package.
+#[derive(Debug)]
+pub struct WelcomeProcessingBuilder<'a> {
+    mls_group_config: &'a MlsGroupConfig,
+    welcome: Welcome,
+    ratchet_tree: RatchetTreeSource<'a>,
+    lifetime_policy: Option<LifetimePolicy>,
+    psk_resolver: Option<Arc<dyn PskResolver>>,
+    keep_key_package: Option<bool>,
+    signature_cache: Option<Arc<SignatureCache>>,
+    replace_existing_group: bool,
+}
+
+impl MlsGroup {
+    /// Returns a [`WelcomeProcessingBuilder`] for joining the group of the
+    /// `welcome` with the given `mls_group_config`.
+    pub fn welcome_processing_builder(
+        mls_group_config: &MlsGroupConfig,
+        welcome: Welcome,
+    ) -> WelcomeProcessingBuilder<'_> {
+        WelcomeProcessingBuilder {
+            mls_group_config,
+            welcome,
+            ratchet_tree: RatchetTreeSource::Extension,
+            lifetime_policy: None,
+            psk_resolver: None,
+            keep_key_package: None,
+            signature_cache: None,
+            replace_existing_group: true,
+        }
+    }
+}
+
+impl<'a> WelcomeProcessingBuilder<'a> {
+    /// Use the given ratchet tree if the group info doesn't contain a
+    /// ratchet tree extension.
+    pub fn ratchet_tree(mut self, ratchet_tree: RatchetTreeIn) -> Self {
+        self.ratchet_tree = RatchetTreeSource::Provided(ratchet_tree);
+        self
+    }
+
+    /// Fetch the ratchet tree through the `ratchet_tree_fetcher` if the
+    /// group info doesn't contain a ratchet tree extension, e.g. from the
+    /// delivery service.
+    ///
+    /// The fetcher is called with the group ID and the epoch of the group
+    /// after the [`Welcome`] was decrypted. If it returns `None`, joining
+    /// fails with [`WelcomeError::MissingRatchetTree`].
+    pub fn fetch_ratchet_tree(
+        mut self,
+        ratchet_tree_fetcher: impl RatchetTreeFetcher + 'a,
+    ) -> Self {
+        self.ratchet_tree = RatchetTreeSource::Fetched(Box::new(ratchet_tree_fetcher));
+        self
+    }
+
+    /// Check the lifetimes of the leaf nodes in the ratchet tree that were
+    /// created from key packages against the `lifetime_policy`.
+    ///
+    /// Joining fails with [`WelcomeError::InvalidLifetime`] if the policy
+    /// doesn't accept one of the lifetimes. The clock skew tolerance and the
+    /// enforcement of the policy determine how strict the check is.
+    pub fn check_lifetimes(mut self, lifetime_policy: LifetimePolicy) -> Self {
+        self.lifetime_policy = Some(lifetime_policy);
+        self
+    }
+
+    /// Query the `psk_resolver` for the PSKs of the group that can't be
+    /// found in the key store. See
+    /// [`MlsGroup::new_from_welcome_with_psk_resolver()`].
+    pub fn psk_resolver(mut self, psk_resolver: impl PskResolver + 'static) -> Self {
+        self.psk_resolver = Some(Arc::new(psk_resolver));
+        self
+    }
+
+    /// Set whether the key package and its private keys are kept in the key
+    /// store after joining, so that they can be used for another [`Welcome`].
+    ///
+    /// Defaults to `true` for last resort key packages (see
+    /// [`KeyPackageBuilder::mark_as_last_resort()`](crate::key_packages::KeyPackageBuilder::mark_as_last_resort()))
+    /// and `false` otherwise.
+    pub fn keep_key_package(mut self, keep_key_package: bool) -> Self {
+        self.keep_key_package = Some(keep_key_package);
+        self
+    }
+
+    /// Use the `signature_cache` to skip the verification of the signatures
+    /// of the ratchet tree and the group info that were already verified,
+    /// e.g. when joining several groups with the same members. The cache is
+    /// also set as the [`SignatureCache`] of the new group (see
+    /// [`MlsGroup::set_signature_cache()`]).
+    pub fn signature_cache(mut self, signature_cache: Arc<SignatureCache>) -> Self {
+        self.signature_cache = Some(signature_cache);
+        self
+    }
+
+    /// Set whether a group with the same ID in the storage of the backend is
+    /// replaced by the joined group.
+    ///
+    /// Defaults to `true`. Otherwise joining fails with
+    /// [`WelcomeError::GroupAlreadyExists`], so that whoever has one of the
+    /// key packages of the client can't overwrite a group the client is in.
+    pub fn replace_existing_group(mut self, replace_existing_group: bool) -> Self {
+        self.replace_existing_group = replace_existing_group;
+        self
+    }
+
+    /// Join the group.
+    ///
+    /// Returns an error ([`WelcomeError::NoMatchingKeyPackage`]) if no
+    /// [`KeyPackage`] can be found and [`WelcomeError::CiphersuitePolicy`] if
+    /// the ciphersuite of the group is not permitted by the
+    /// [`CiphersuitePolicy`](crate::ciphersuite::policy::CiphersuitePolicy)
+    /// of the group configuration.
+    ///
+    /// Deleting the key package is applied in one batch together with writing
+    /// the new group state, so that the key package can be used again if
+    /// joining fails.
+    pub fn build<KeyStore: OpenMlsKeyStore>(
+        self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<MlsGroup, WelcomeError<KeyStore::Error>> {
+        let batch_backend = BatchProvider::new(backend);
+        let mls_group = self.join(&batch_backend)?;
+        batch_backend.apply().map_err(WelcomeError::KeyStoreError)?;
+        Ok(mls_group)
+    }
+
+    /// Joins the group and writes all changes to the key store.
+    fn join<KeyStore: OpenMlsKeyStore>(
+        self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+    ) -> Result<MlsGroup, WelcomeError<KeyStore::Error>> {
+        let mls_group_config = self.mls_group_config;
+        mls_group_config
+            .ciphersuite_policy()
+            .check(self.welcome.ciphersuite())?;
+        let resumption_psk_store =
+            ResumptionPskStore::new(mls_group_config.number_of_resumption_psks);
+        let key_package_bundle = MlsGroup::key_package_bundle_from_welcome(backend, &self.welcome)?;
+        let keep_key_package = self
+            .keep_key_package
+            .unwrap_or_else(|| key_package_bundle.key_package.last_resort());
+
+        // Delete the [`KeyPackage`] and the corresponding private key from the
+        // key store, unless it should be kept
+        if !keep_key_package {
+            key_package_bundle
+                .key_package
+                .delete(backend)
+                .map_err(WelcomeError::KeyStoreError)?;
+        }
+
+        let mut group = CoreGroup::new_from_welcome_with_options(
+            self.welcome,
+            key_package_bundle,
+            backend,
+            resumption_psk_store,
+            WelcomeOptions {
+                ratchet_tree: self.ratchet_tree,
+                lifetime_policy: self.lifetime_policy,
+                psk_resolver: self.psk_resolver.as_deref(),
+                keep_key_package,
+                signature_cache: self.signature_cache,
+            },
+        )?;
+        if !self.replace_existing_group && MlsGroup::load(group.group_id(), backend).is_some() {
+            // The writes of the new group are rolled back
+            return Err(WelcomeError::GroupAlreadyExists(group.group_id().clone()));
+        }
+        group.set_past_epoch_retention(&mls_group_config.past_epoch_retention());
+        group.set_lifetime_policy(*mls_group_config.lifetime_policy());
+        group.set_leaf_placement_strategy(mls_group_config.leaf_placement_strategy());
+        group.set_decode_limits(*mls_group_config.decode_limits());
+
+        let mls_group = MlsGroup {
+            mls_group_config: mls_group_config.clone(),
+            group,
+            proposal_store: ProposalStore::new(),
+            own_leaf_nodes: vec![],
+            aad: vec![],
+            group_state: MlsGroupState::Operational,
+            departure: None,
+            receiver_state: Mutex::default(),
+            message_buffer: Mutex::default(),
+            processed_buffered_messages: vec![],
+            own_messages: None,
+            lost_commit: None,
+            credential_validator: None,
+            commit_validator: None,
+            observer: None,
+            psk_resolver: self.psk_resolver,
+            event_log: VecDeque::new(),
+            event_timestamp: None,
+            exporter_cache: Mutex::default(),
+            store_lock: Mutex::new(()),
+        };
+        mls_group
+            .store(backend)
+            .map_err(WelcomeError::KeyStoreError)?;
+
+        Ok(mls_group)
+    }
+}
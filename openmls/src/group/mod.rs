@@ -27,12 +27,35 @@ pub mod config;
 pub(crate) mod errors;
 
 pub use core_group::proposals::*;
-pub use core_group::staged_commit::StagedCommit;
+pub use core_group::staged_commit::{
+    AddedMember, AppStateComparison, CredentialUpdate, GroupContextExtensionChanges,
+    ModifiedExtension, OwnLeafChange, RemovedMember, StagedCommit, UpdatedMember,
+};
 pub use errors::*;
 pub use group_context::*;
+#[cfg(feature = "async")]
+pub use mls_group::asynchronous::*;
+pub use mls_group::auditor::*;
+pub use mls_group::backup::*;
+pub use mls_group::client::{ClientMessage, MlsClient};
+pub use mls_group::commit_builder::*;
+pub use mls_group::commit_validator::*;
 pub use mls_group::config::*;
+pub use mls_group::event_log::{GroupEvent, GroupEventKind};
+pub use mls_group::exporter::*;
+#[cfg(feature = "external-commit")]
+pub use mls_group::external_commit::PendingRejoin;
+pub use mls_group::features::*;
 pub use mls_group::membership::*;
+pub use mls_group::observer::*;
 pub use mls_group::processing::*;
+pub use mls_group::proposal::Propose;
+#[cfg(feature = "sframe")]
+pub use mls_group::sframe::*;
+#[cfg(feature = "speculation")]
+pub use mls_group::speculation::*;
+pub use mls_group::streaming::{DecryptStream, EncryptStream, StreamHeader};
+pub use mls_group::welcome_processing::WelcomeProcessingBuilder;
 pub use mls_group::*;
 pub use public_group::*;
 
@@ -0,0 +1,129 @@
+//! Group administration through the [`AdminsExtension`].
+//!
+//! If the group context contains an [`AdminsExtension`], only the admins of
+//! the group may send proposals that change the membership or the group
+//! context:
+//!
+//!  - Add, Remove and `GroupContextExtensions` proposals of members have to be
+//!    sent by an admin, except for Remove proposals of members that remove
+//!    themselves.
+//!  - Proposals of the external senders of the group are accepted, since the
+//!    external senders are configured through the group context as well.
+//!  - External join proposals have to be committed by an admin.
+//!  - External commits are only accepted from joiners whose credential is an
+//!    admin, or from joiners that re-join the group and remove their previous
+//!    leaf, as permitted by ValSem243, which doesn't change the membership.
+//!
+//! All other proposals are not restricted.
+
+use super::PublicGroup;
+use crate::{
+    binary_tree::LeafNodeIndex,
+    extensions::AdminsExtension,
+    framing::Sender,
+    group::{
+        errors::{ExternalCommitValidationError, ProposalValidationError},
+        ProposalQueue,
+    },
+    messages::proposals::{Proposal, ProposalType},
+    treesync::node::leaf_node::LeafNode,
+};
+
+impl PublicGroup {
+    /// Returns the [`AdminsExtension`] of the group context, if any.
+    pub fn admins(&self) -> Option<&AdminsExtension> {
+        self.group_context().extensions().admins()
+    }
+
+    /// Returns `true` if the credential of the member at `leaf_index` is in
+    /// the [`AdminsExtension`] of the group context.
+    ///
+    /// Returns `false` if the group has no admins or if there is no member at
+    /// `leaf_index`.
+    pub fn is_admin(&self, leaf_index: LeafNodeIndex) -> bool {
+        match (self.admins(), self.leaf(leaf_index)) {
+            (Some(admins), Some(leaf)) => admins.contains(leaf.credential()),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the group permits the `sender` to send the
+    /// `proposal`. The `committer` is the sender of the commit that covers
+    /// the proposal, or `None` if the proposal is not committed yet.
+    pub(crate) fn is_authorized_proposal(
+        &self,
+        sender: &Sender,
+        proposal: &Proposal,
+        committer: Option<&Sender>,
+    ) -> bool {
+        if self.admins().is_none()
+            || !matches!(
+                proposal,
+                Proposal::Add(_) | Proposal::Remove(_) | Proposal::GroupContextExtensions(_)
+            )
+        {
+            return true;
+        }
+        match sender {
+            Sender::Member(leaf_index) => {
+                matches!(proposal, Proposal::Remove(remove) if remove.removed() == *leaf_index)
+                    || self.is_admin(*leaf_index)
+            }
+            Sender::External(_) => true,
+            Sender::NewMemberProposal => match committer {
+                Some(Sender::Member(leaf_index)) => self.is_admin(*leaf_index),
+                Some(_) => false,
+                None => true,
+            },
+            Sender::NewMemberCommit => matches!(proposal, Proposal::Remove(_)),
+        }
+    }
+
+    /// Validate that all proposals in the `proposal_queue` are permitted by
+    /// the [`AdminsExtension`] of the group, if any.
+    pub(crate) fn validate_admin_proposals(
+        &self,
+        proposal_queue: &ProposalQueue,
+        committer: &Sender,
+    ) -> Result<(), ProposalValidationError> {
+        for queued_proposal in proposal_queue.queued_proposals() {
+            if !self.is_authorized_proposal(
+                queued_proposal.sender(),
+                queued_proposal.proposal(),
+                Some(committer),
+            ) {
+                return Err(ProposalValidationError::SenderNotAdmin);
+            }
+        }
+        Ok(())
+    }
+
+    /// Validate that the External Commit with the `proposal_queue` and the
+    /// `path_leaf_node` of the joiner is permitted by the [`AdminsExtension`]
+    /// of the group, if any.
+    ///
+    /// The commit is permitted if the credential of the joiner is an admin,
+    /// or if it removes the previous leaf of the joiner, which ValSem243
+    /// checks in [`PublicGroup::validate_external_commit()`].
+    pub(crate) fn validate_admin_external_commit(
+        &self,
+        proposal_queue: &ProposalQueue,
+        path_leaf_node: Option<&LeafNode>,
+    ) -> Result<(), ExternalCommitValidationError> {
+        let Some(admins) = self.admins() else {
+            return Ok(());
+        };
+        let Some(path_leaf_node) = path_leaf_node else {
+            return Err(ExternalCommitValidationError::JoinerNotAdmin);
+        };
+        let is_rejoin = proposal_queue
+            .filtered_by_type(ProposalType::Remove)
+            .next()
+            .is_some();
+        if admins.contains(path_leaf_node.credential()) || is_rejoin {
+            Ok(())
+        } else {
+            Err(ExternalCommitValidationError::JoinerNotAdmin)
+        }
+    }
+}
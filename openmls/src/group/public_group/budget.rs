@@ -0,0 +1,97 @@
+//! # Validation budgets
+//!
+//! A [`ValidationBudget`] bounds the work a [`PublicGroup`] spends on
+//! validating a single message, e.g. so that a Delivery Service can bound the
+//! CPU time an adversarial commit with a pathological number of proposals
+//! can consume.
+
+use std::time::{Duration, Instant};
+
+use super::PublicGroup;
+use crate::group::mls_group::errors::ProcessMessageError;
+
+/// Budget for validating a single message with
+/// [`PublicGroup::process_message_with_budget()`].
+///
+///  - max_duration:
+/// The maximum time spent on validating the message. The time is checked
+/// between the validation steps, so validation is aborted after the first step
+/// that exceeds the budget. The default value is `None`, i.e. no limit.
+///  - max_proposals:
+/// The maximum number of proposals a commit may cover. Commits covering more
+/// proposals are rejected before the proposals are validated. The default
+/// value is `None`, i.e. no limit.
+///
+/// Messages exceeding the budget are rejected with
+/// [`ProcessMessageError::BudgetExceeded`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ValidationBudget {
+    max_duration: Option<Duration>,
+    max_proposals: Option<usize>,
+}
+
+impl ValidationBudget {
+    /// Create a new budget without limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum time spent on validating a message.
+    pub fn with_max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    /// Set the maximum number of proposals a commit may cover.
+    pub fn with_max_proposals(mut self, max_proposals: usize) -> Self {
+        self.max_proposals = Some(max_proposals);
+        self
+    }
+
+    /// Get the maximum time spent on validating a message.
+    pub fn max_duration(&self) -> Option<Duration> {
+        self.max_duration
+    }
+
+    /// Get the maximum number of proposals a commit may cover.
+    pub fn max_proposals(&self) -> Option<usize> {
+        self.max_proposals
+    }
+
+    /// Start spending the budget on the validation of a message.
+    pub(crate) fn start(&self) -> BudgetTracker {
+        BudgetTracker {
+            budget: *self,
+            start: Instant::now(),
+        }
+    }
+}
+
+/// Tracks the spending of a [`ValidationBudget`] while a message is validated.
+pub(crate) struct BudgetTracker {
+    budget: ValidationBudget,
+    start: Instant,
+}
+
+impl BudgetTracker {
+    /// Returns an error if the validation took longer than the budget allows.
+    pub(crate) fn check_time(&self) -> Result<(), ProcessMessageError> {
+        match self.budget.max_duration {
+            Some(max_duration) if self.start.elapsed() > max_duration => {
+                Err(ProcessMessageError::BudgetExceeded)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns an error if a commit covers more proposals than the budget
+    /// allows.
+    pub(crate) fn check_proposals(&self, proposals: usize) -> Result<(), ProcessMessageError> {
+        match self.budget.max_proposals {
+            Some(max_proposals) if proposals > max_proposals => {
+                Err(ProcessMessageError::BudgetExceeded)
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -24,7 +24,7 @@ use crate::{
             parent_node::PlainUpdatePathNode,
         },
         treekem::{DecryptPathParams, UpdatePath, UpdatePathNode},
-        RatchetTree,
+        LeafPlacement, RatchetTree,
     },
 };
 
@@ -37,6 +37,8 @@ pub(crate) struct PublicGroupDiff<'a> {
     interim_transcript_hash: Vec<u8>,
     // Most recent confirmation tag. Kept here for verification purposes.
     confirmation_tag: ConfirmationTag,
+    // The placement of the leaf nodes of added members.
+    leaf_placement: &'a dyn LeafPlacement,
 }
 
 impl<'a> PublicGroupDiff<'a> {
@@ -47,6 +49,7 @@ impl<'a> PublicGroupDiff<'a> {
             group_context: public_group.group_context().clone(),
             interim_transcript_hash: public_group.interim_transcript_hash().to_vec(),
             confirmation_tag: public_group.confirmation_tag().clone(),
+            leaf_placement: public_group.leaf_placement(),
         }
     }
 
@@ -232,7 +235,8 @@ impl<'a> PublicGroupDiff<'a> {
 
 /// The staged version of a [`PublicGroupDiff`], which means it can no longer be
 /// modified. Its only use is to merge it into the original [`PublicGroup`].
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
 pub(crate) struct StagedPublicGroupDiff {
     pub(super) staged_diff: StagedTreeSyncDiff,
     pub(super) group_context: GroupContext,
@@ -245,4 +249,10 @@ impl StagedPublicGroupDiff {
     pub(crate) fn group_context(&self) -> &GroupContext {
         &self.group_context
     }
+
+    /// Returns an iterator over the leaf nodes that enter the tree with this
+    /// diff, i.e. new and replaced leaf nodes.
+    pub(crate) fn new_leaf_nodes(&self) -> impl Iterator<Item = (LeafNodeIndex, &LeafNode)> {
+        self.staged_diff.changed_leaf_nodes()
+    }
 }
@@ -3,6 +3,7 @@ use std::collections::HashSet;
 use crate::{
     binary_tree::LeafNodeIndex,
     error::LibraryError,
+    extensions::ExtensionType,
     framing::Sender,
     group::ProposalQueue,
     messages::proposals::{AddProposal, ExternalInitProposal, Proposal, ProposalType},
@@ -115,19 +116,46 @@ impl<'a> PublicGroupDiff<'a> {
                 }
             });
 
+        // If all members support the service member extension, service
+        // members are placed at the right edge of the tree.
+        let service_member_placement = self
+            .group_context
+            .extensions()
+            .required_capabilities()
+            .map_or(false, |required_capabilities| {
+                required_capabilities
+                    .extension_types()
+                    .contains(&ExtensionType::ServiceMember)
+            });
+
         // Extract KeyPackages from proposals
         let mut invitation_list = Vec::new();
         for add_proposal in add_proposals {
             // XXX: There are too many clones here.
             let leaf_node = add_proposal.key_package.leaf_node();
-            let leaf_index = self
-                .diff
-                .add_leaf(leaf_node.clone())
-                // TODO #810
-                .map_err(|_| LibraryError::custom("Tree full: cannot add more members"))?;
+            let leaf_index = if service_member_placement && leaf_node.is_service_member() {
+                self.diff.add_service_leaf(leaf_node.clone())
+            } else {
+                self.diff
+                    .add_leaf_with_placement(leaf_node.clone(), self.leaf_placement)
+            }
+            // TODO #810
+            .map_err(|_| LibraryError::custom("Tree full: cannot add more members"))?;
             invitation_list.push((leaf_index, add_proposal.clone()))
         }
 
+        // Process group context extensions proposals. Only a single one is
+        // allowed per commit.
+        if let Some(Proposal::GroupContextExtensions(group_context_extensions_proposal)) =
+            proposal_queue
+                .filtered_by_type(ProposalType::GroupContextExtensions)
+                .next()
+                .map(|queued_proposal| queued_proposal.proposal())
+        {
+            self.group_context
+                .set_extensions(group_context_extensions_proposal.extensions().clone());
+        }
+
         // Process PSK proposals
         let presharedkeys: Vec<PreSharedKeyId> = proposal_queue
             .filtered_by_type(ProposalType::PreSharedKey)
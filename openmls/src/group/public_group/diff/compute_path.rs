@@ -1,21 +1,23 @@
 use std::collections::HashSet;
 
-use openmls_traits::{key_store::OpenMlsKeyStore, signatures::Signer, OpenMlsCryptoProvider};
+use openmls_traits::{key_store::OpenMlsKeyStore, signatures::AsyncSigner, OpenMlsCryptoProvider};
 use tls_codec::Serialize;
 
 use crate::{
     binary_tree::LeafNodeIndex,
     credentials::CredentialWithKey,
     error::LibraryError,
+    extensions::Extensions,
     group::{
         config::CryptoConfig, core_group::create_commit_params::CommitType,
         errors::CreateCommitError,
     },
-    key_packages::{KeyPackage, KeyPackageCreationResult},
+    key_packages::{errors::KeyPackageNewError, Lifetime},
     schedule::CommitSecret,
     treesync::{
         node::{
-            encryption_keys::EncryptionKeyPair, leaf_node::LeafNode,
+            encryption_keys::EncryptionKeyPair,
+            leaf_node::{Capabilities, LeafNode},
             parent_node::PlainUpdatePathNode,
         },
         treekem::UpdatePath,
@@ -35,13 +37,13 @@ pub(crate) struct PathComputationResult {
 }
 
 impl<'a> PublicGroupDiff<'a> {
-    pub(crate) fn compute_path<KeyStore: OpenMlsKeyStore>(
+    pub(crate) async fn compute_path<KeyStore: OpenMlsKeyStore>(
         &mut self,
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         leaf_index: LeafNodeIndex,
         exclusion_list: HashSet<&LeafNodeIndex>,
         commit_type: CommitType,
-        signer: &impl Signer,
+        signer: &impl AsyncSigner,
         credential_with_key: Option<CredentialWithKey>,
     ) -> Result<PathComputationResult, CreateCommitError<KeyStore::Error>> {
         let version = self.group_context().protocol_version();
@@ -50,25 +52,28 @@ impl<'a> PublicGroupDiff<'a> {
 
         let mut new_keypairs = if commit_type == CommitType::External {
             // If this is an external commit we add a fresh leaf to the diff.
-            // Generate a KeyPackageBundle to generate a payload from for later
-            // path generation.
-            let KeyPackageCreationResult {
-                key_package,
-                encryption_keypair,
-                // The KeyPackage is immediately put into the group. No need for
-                // the init key.
-                init_private_key: _,
-            } = KeyPackage::builder().build_without_key_storage(
+            // The leaf is signed below, once its parent hash is known, so we
+            // don't sign it here.
+            if ciphersuite.signature_algorithm() != signer.signature_scheme() {
+                return Err(CreateCommitError::KeyPackageGenerationError(
+                    KeyPackageNewError::CiphersuiteSignatureSchemeMismatch,
+                ));
+            }
+            let encryption_keypair = EncryptionKeyPair::random(
+                backend,
                 CryptoConfig {
                     ciphersuite,
                     version,
                 },
-                backend,
-                signer,
+            )?;
+            let leaf_node = LeafNode::new_unsigned(
+                encryption_keypair.public_key().clone(),
                 credential_with_key.ok_or(CreateCommitError::MissingCredential)?,
+                Lifetime::default(),
+                Capabilities::default(),
+                Extensions::default(),
             )?;
 
-            let leaf_node: LeafNode = key_package.into();
             self.diff
                 .add_leaf(leaf_node)
                 .map_err(|_| LibraryError::custom("Tree full: cannot add more members"))?;
@@ -79,14 +84,20 @@ impl<'a> PublicGroupDiff<'a> {
                 .diff
                 .leaf_mut(leaf_index)
                 .ok_or_else(|| LibraryError::custom("Unable to get own leaf from diff"))?;
-            let encryption_keypair = own_diff_leaf.rekey(
-                &group_id,
-                leaf_index,
-                ciphersuite,
-                version,
-                backend,
-                signer,
-            )?;
+            // A credential for a member commit replaces the own credential
+            // and signature key. The `signer` has to belong to the new
+            // signature key.
+            if let Some(credential_with_key) = credential_with_key {
+                if ciphersuite.signature_algorithm() != signer.signature_scheme() {
+                    return Err(CreateCommitError::KeyPackageGenerationError(
+                        KeyPackageNewError::CiphersuiteSignatureSchemeMismatch,
+                    ));
+                }
+                own_diff_leaf.set_credential_with_key(credential_with_key);
+            }
+            let encryption_keypair = own_diff_leaf
+                .rekey(&group_id, leaf_index, ciphersuite, version, backend, signer)
+                .await?;
             vec![encryption_keypair]
         };
 
@@ -94,7 +105,8 @@ impl<'a> PublicGroupDiff<'a> {
         // generated new leaf.
         let (plain_path, mut new_parent_keypairs, commit_secret) = self
             .diff
-            .apply_own_update_path(backend, signer, ciphersuite, group_id, leaf_index)?;
+            .apply_own_update_path(backend, signer, ciphersuite, group_id, leaf_index)
+            .await?;
 
         new_keypairs.append(&mut new_parent_keypairs);
 
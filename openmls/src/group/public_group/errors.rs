@@ -1,8 +1,8 @@
 use thiserror::Error;
 
 use crate::{
-    error::LibraryError, extensions::errors::InvalidExtensionError,
-    treesync::errors::TreeSyncFromNodesError,
+    ciphersuite::policy::CiphersuitePolicyError, error::LibraryError,
+    extensions::errors::InvalidExtensionError, treesync::errors::TreeSyncFromNodesError,
 };
 
 /// Public group creation from external error.
@@ -26,6 +26,9 @@ pub enum CreationFromExternalError {
     /// We don't support the version of the group we are trying to join.
     #[error("We don't support the version of the group we are trying to join.")]
     UnsupportedMlsVersion,
+    /// See [`CiphersuitePolicyError`] for more details.
+    #[error(transparent)]
+    CiphersuitePolicy(#[from] CiphersuitePolicyError),
 }
 
 /// Public group builder error.
@@ -44,3 +47,37 @@ pub enum PublicGroupBuildError {
     #[error("Invalid extensions set in configuration")]
     InvalidExtensions(#[from] InvalidExtensionError),
 }
+
+/// External proposal creation error.
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum ExternalProposalError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// The group has no external senders extension.
+    #[error("The group has no external senders extension.")]
+    NoExternalSendersExtension,
+    /// The sender index is not part of the external senders extension.
+    #[error("The sender index is not part of the external senders extension.")]
+    UnknownExternalSender,
+    /// The key package's ciphersuite doesn't match the group's ciphersuite.
+    #[error("The key package's ciphersuite doesn't match the group's ciphersuite.")]
+    CiphersuiteMismatch,
+    /// The member to remove is not part of the group.
+    #[error("The member to remove is not part of the group.")]
+    UnknownMember,
+}
+
+/// Group info summary export error.
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum ExportGroupInfoSummaryError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// The group has no external senders extension.
+    #[error("The group has no external senders extension.")]
+    NoExternalSendersExtension,
+    /// The sender index is not part of the external senders extension.
+    #[error("The sender index is not part of the external senders extension.")]
+    UnknownExternalSender,
+}
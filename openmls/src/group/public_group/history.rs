@@ -0,0 +1,116 @@
+//! # Epoch history
+//!
+//! A [`PublicGroup`] can optionally keep the history of its public state, so
+//! that audit or debug tooling of a Delivery Service can inspect the group at
+//! a past epoch, e.g. to find out who was a member of the group when a
+//! message was sent.
+//!
+//! The history consists of the state at the epoch in which it was enabled
+//! and the staged diffs of all commits merged since. The state at an earlier
+//! epoch is reconstructed by merging the diffs up to that epoch.
+
+use serde::{Deserialize, Serialize};
+
+use super::{diff::StagedPublicGroupDiff, PublicGroup};
+use crate::{
+    binary_tree::LeafNodeIndex,
+    extensions::Extensions,
+    group::{GroupContext, GroupEpoch, Member},
+    treesync::{node::leaf_node::LeafNode, RatchetTree, TreeSync},
+};
+
+/// The public state of a [`PublicGroup`] since the history was enabled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
+pub(crate) struct EpochHistory {
+    treesync: TreeSync,
+    group_context: GroupContext,
+    diffs: Vec<StagedPublicGroupDiff>,
+}
+
+impl EpochHistory {
+    /// Start the history at the current epoch of the `public_group`.
+    pub(super) fn new(public_group: &PublicGroup) -> Self {
+        Self {
+            treesync: public_group.treesync().clone(),
+            group_context: public_group.group_context().clone(),
+            diffs: Vec::new(),
+        }
+    }
+
+    /// Record the `diff` of the next epoch.
+    pub(super) fn push(&mut self, diff: StagedPublicGroupDiff) {
+        self.diffs.push(diff);
+    }
+
+    /// Reconstruct the state at the given `epoch`.
+    ///
+    /// Returns [`None`] if the epoch is not part of the history.
+    pub(super) fn at_epoch(&self, epoch: GroupEpoch) -> Option<PublicGroupEpochView> {
+        let steps = epoch
+            .as_u64()
+            .checked_sub(self.group_context.epoch().as_u64())?;
+        let steps = usize::try_from(steps).ok()?;
+        if steps > self.diffs.len() {
+            return None;
+        }
+
+        let mut treesync = self.treesync.clone();
+        let mut group_context = &self.group_context;
+        for diff in &self.diffs[..steps] {
+            treesync.merge_diff(diff.staged_diff.clone());
+            group_context = &diff.group_context;
+        }
+        Some(PublicGroupEpochView {
+            treesync,
+            group_context: group_context.clone(),
+        })
+    }
+}
+
+/// A read-only view of the public state of a [`PublicGroup`] at a past epoch.
+///
+/// See [`PublicGroup::at_epoch()`].
+#[derive(Debug)]
+pub struct PublicGroupEpochView {
+    treesync: TreeSync,
+    group_context: GroupContext,
+}
+
+impl PublicGroupEpochView {
+    /// Get the epoch of this view.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.group_context.epoch()
+    }
+
+    /// Get the group context at this epoch.
+    pub fn group_context(&self) -> &GroupContext {
+        &self.group_context
+    }
+
+    /// Get the group context extensions at this epoch.
+    pub fn extensions(&self) -> &Extensions {
+        self.group_context.extensions()
+    }
+
+    /// Get the tree hash at this epoch.
+    pub fn tree_hash(&self) -> &[u8] {
+        self.treesync.tree_hash()
+    }
+
+    /// Get an iterator over all [`Member`]s of the group at this epoch.
+    pub fn members(&self) -> impl Iterator<Item = Member> + '_ {
+        self.treesync.full_leave_members()
+    }
+
+    /// Return a reference to the leaf at the given `LeafNodeIndex` or `None`
+    /// if the leaf was blank at this epoch.
+    pub fn leaf(&self, leaf_index: LeafNodeIndex) -> Option<&LeafNode> {
+        self.treesync.leaf(leaf_index)
+    }
+
+    /// Export the nodes of the public tree at this epoch.
+    pub fn export_ratchet_tree(&self) -> RatchetTree {
+        self.treesync.export_ratchet_tree()
+    }
+}
@@ -0,0 +1,203 @@
+//! # Commit linting
+//!
+//! [`PublicGroup::lint_commit()`] inspects the content of a commit for
+//! findings that don't make it invalid, but that a Delivery Service operator
+//! may want to monitor, e.g. to flag misbehaving clients. Linting never
+//! rejects a message and doesn't verify it; use
+//! [`PublicGroup::process_message()`] for that.
+
+use std::time::SystemTime;
+
+use super::PublicGroup;
+use crate::{
+    binary_tree::LeafNodeIndex,
+    framing::{mls_content_in::FramedContentBodyIn, PublicMessageIn, Sender},
+    key_packages::Lifetime,
+    messages::{
+        proposals::Proposal,
+        proposals_in::{ProposalIn, ProposalOrRefIn},
+    },
+};
+
+/// The default maximum number of proposals of a commit before
+/// [`CommitLint::ManyProposals`] is reported.
+const DEFAULT_MAX_PROPOSALS: usize = 100;
+
+/// A non-fatal finding about a commit. See [`PublicGroup::lint_commit()`].
+///
+/// Proposals are identified by their index in the commit's list of proposals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommitLint {
+    /// The commit has no update path, so the committer doesn't refresh its
+    /// keys and the commit doesn't improve post-compromise security.
+    MissingPath,
+    /// The commit covers more proposals than the configured maximum.
+    ManyProposals {
+        /// The number of proposals the commit covers.
+        proposals: usize,
+    },
+    /// An Add proposal adds a key package whose lifetime doesn't cover the
+    /// current time, taking the clock skew tolerance of the group's
+    /// [`LifetimePolicy`](crate::key_packages::LifetimePolicy) into account.
+    /// This is reported even if the policy doesn't enforce lifetimes.
+    InvalidKeyPackageLifetime {
+        /// The index of the Add proposal.
+        proposal_index: usize,
+    },
+    /// A member has more than one Update proposal covered by the commit.
+    RepeatedUpdates {
+        /// The leaf index of the member.
+        sender: LeafNodeIndex,
+        /// The number of Update proposals of the member.
+        updates: usize,
+    },
+    /// A proposal reference isn't in the proposal store of the group, e.g.
+    /// because the proposal was never sent to the Delivery Service.
+    UnknownProposalReference {
+        /// The index of the proposal reference.
+        proposal_index: usize,
+    },
+}
+
+/// Configuration of [`PublicGroup::lint_commit_with_config()`].
+///
+///  - max_proposals:
+/// The maximum number of proposals a commit may cover before
+/// [`CommitLint::ManyProposals`] is reported. The default value is 100.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommitLintConfig {
+    max_proposals: usize,
+}
+
+impl Default for CommitLintConfig {
+    fn default() -> Self {
+        Self {
+            max_proposals: DEFAULT_MAX_PROPOSALS,
+        }
+    }
+}
+
+impl CommitLintConfig {
+    /// Create a new configuration with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of proposals a commit may cover.
+    pub fn with_max_proposals(mut self, max_proposals: usize) -> Self {
+        self.max_proposals = max_proposals;
+        self
+    }
+
+    /// Get the maximum number of proposals a commit may cover.
+    pub fn max_proposals(&self) -> usize {
+        self.max_proposals
+    }
+}
+
+impl PublicGroup {
+    /// Lints the commit in `message` with the default [`CommitLintConfig`].
+    /// See [`Self::lint_commit_with_config()`].
+    pub fn lint_commit(&self, message: &PublicMessageIn) -> Vec<CommitLint> {
+        self.lint_commit_with_config(message, &CommitLintConfig::default())
+    }
+
+    /// Returns the non-fatal findings about the commit in `message`, e.g. so
+    /// that a Delivery Service can monitor the health of the group without
+    /// rejecting traffic. Returns no findings if the message isn't a commit.
+    ///
+    /// Proposal references are resolved with the proposals stored in the
+    /// group (see [`Self::add_proposal()`]). The message is not verified, so
+    /// the findings should only be attributed to the sender after the
+    /// message was processed with [`Self::process_message()`].
+    pub fn lint_commit_with_config(
+        &self,
+        message: &PublicMessageIn,
+        config: &CommitLintConfig,
+    ) -> Vec<CommitLint> {
+        let FramedContentBodyIn::Commit(commit) = message.content() else {
+            return vec![];
+        };
+
+        let mut lints = vec![];
+        if !commit.has_path() {
+            lints.push(CommitLint::MissingPath);
+        }
+        let proposals = commit.proposals();
+        if proposals.len() > config.max_proposals() {
+            lints.push(CommitLint::ManyProposals {
+                proposals: proposals.len(),
+            });
+        }
+
+        // The senders of the Update proposals in the order of the proposals
+        let mut update_senders: Vec<LeafNodeIndex> = vec![];
+        for (proposal_index, proposal_or_ref) in proposals.iter().enumerate() {
+            match proposal_or_ref {
+                ProposalOrRefIn::Proposal(ProposalIn::Add(add_proposal)) => {
+                    if !self.is_valid_lifetime(add_proposal.key_package().life_time()) {
+                        lints.push(CommitLint::InvalidKeyPackageLifetime { proposal_index });
+                    }
+                }
+                ProposalOrRefIn::Proposal(ProposalIn::Update(_)) => {
+                    if let Sender::Member(sender) = message.sender() {
+                        update_senders.push(*sender);
+                    }
+                }
+                ProposalOrRefIn::Proposal(_) => {}
+                ProposalOrRefIn::Reference(proposal_reference) => {
+                    let Some(queued_proposal) =
+                        self.proposal_store.proposals().find(|queued_proposal| {
+                            &queued_proposal.proposal_reference() == proposal_reference
+                        })
+                    else {
+                        lints.push(CommitLint::UnknownProposalReference { proposal_index });
+                        continue;
+                    };
+                    match queued_proposal.proposal() {
+                        Proposal::Add(add_proposal)
+                            if !self.is_valid_lifetime(
+                                add_proposal.key_package().leaf_node().life_time(),
+                            ) =>
+                        {
+                            lints.push(CommitLint::InvalidKeyPackageLifetime { proposal_index });
+                        }
+                        Proposal::Update(_) => {
+                            if let Sender::Member(sender) = queued_proposal.sender() {
+                                update_senders.push(*sender);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        let mut reported_senders: Vec<LeafNodeIndex> = vec![];
+        for sender in &update_senders {
+            let updates = update_senders.iter().filter(|s| *s == sender).count();
+            if updates > 1 && !reported_senders.contains(sender) {
+                reported_senders.push(*sender);
+                lints.push(CommitLint::RepeatedUpdates {
+                    sender: *sender,
+                    updates,
+                });
+            }
+        }
+
+        lints
+    }
+
+    /// Returns `true` if the `lifetime` of a key package covers the current
+    /// time, regardless of whether the lifetime policy is enforced.
+    fn is_valid_lifetime(&self, lifetime: Option<&Lifetime>) -> bool {
+        lifetime
+            .map(|lifetime| {
+                lifetime.is_valid_at(
+                    SystemTime::now(),
+                    self.lifetime_policy().clock_skew_tolerance(),
+                )
+            })
+            .unwrap_or(false)
+    }
+}
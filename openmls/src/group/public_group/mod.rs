@@ -12,27 +12,51 @@
 //! relies on a [`PublicGroup`] as well.
 
 #[cfg(test)]
-use std::collections::HashSet;
+use std::{
+    collections::HashSet,
+    sync::{Arc, OnceLock},
+};
 
-use openmls_traits::{crypto::OpenMlsCrypto, types::Ciphersuite, OpenMlsCryptoProvider};
+use openmls_traits::{
+    crypto::OpenMlsCrypto, signatures::Signer, types::Ciphersuite, OpenMlsCryptoProvider,
+};
 use serde::{Deserialize, Serialize};
+use tls_codec::Serialize as TlsSerializeTrait;
 
 use self::{
     diff::{PublicGroupDiff, StagedPublicGroupDiff},
-    errors::CreationFromExternalError,
+    errors::{CreationFromExternalError, ExportGroupInfoSummaryError, ExternalProposalError},
+    history::EpochHistory,
 };
+#[cfg(feature = "public-group-tracking")]
+use super::GroupEpoch;
 use super::{GroupContext, GroupId, Member, ProposalStore, QueuedProposal, StagedCommit};
+#[cfg(doc)]
+use crate::group::CoreGroup;
 #[cfg(test)]
 use crate::treesync::{node::parent_node::PlainUpdatePathNode, treekem::UpdatePathNode};
 use crate::{
     binary_tree::{array_representation::TreeSize, LeafNodeIndex},
-    ciphersuite::signable::Verifiable,
+    ciphersuite::{
+        policy::CiphersuitePolicy,
+        signable::{Signable, Verifiable},
+        signature_cache::SignatureCache,
+        CachingCrypto,
+    },
     error::LibraryError,
-    extensions::RequiredCapabilitiesExtension,
-    framing::InterimTranscriptHashInput,
+    extensions::{
+        ExtensionType, Extensions, ExternalSendersExtension, RequiredCapabilitiesExtension,
+        SenderExtensionIndex,
+    },
+    framing::{
+        mls_auth_content::AuthenticatedContent, DecodeLimits, InterimTranscriptHashInput,
+        MlsMessageOut, PublicMessage,
+    },
+    key_packages::{KeyPackage, LifetimePolicy},
     messages::{
         group_info::{GroupInfo, VerifiableGroupInfo},
-        proposals::{Proposal, ProposalOrRefType, ProposalType},
+        group_info_summary::{GroupInfoSummary, GroupInfoSummarySigner, GroupInfoSummaryTBS},
+        proposals::{AddProposal, Proposal, ProposalOrRefType, ProposalType, RemoveProposal},
         ConfirmationTag, PathSecret,
     },
     schedule::CommitSecret,
@@ -42,25 +66,36 @@ use crate::{
             encryption_keys::{EncryptionKey, EncryptionKeyPair},
             leaf_node::LeafNode,
         },
-        RatchetTree, RatchetTreeIn, TreeSync,
+        placement::CustomLeafPlacement,
+        LeafPlacement, LeafPlacementStrategy, RatchetTree, RatchetTreeIn, TreeSync,
     },
     versions::ProtocolVersion,
 };
-#[cfg(doc)]
-use crate::{framing::PublicMessage, group::CoreGroup};
 
+mod admins;
+#[cfg(feature = "public-group-tracking")]
+mod budget;
 pub(crate) mod builder;
 pub(crate) mod diff;
 pub mod errors;
+mod history;
+#[cfg(feature = "public-group-tracking")]
+mod lint;
 pub mod process;
 pub(crate) mod staged_commit;
-#[cfg(test)]
+#[cfg(all(test, feature = "public-group-tracking"))]
 mod tests;
 mod validation;
 
+#[cfg(feature = "public-group-tracking")]
+pub use budget::ValidationBudget;
+pub use history::PublicGroupEpochView;
+#[cfg(feature = "public-group-tracking")]
+pub use lint::{CommitLint, CommitLintConfig};
+
 /// This struct holds all public values of an MLS group.
-#[derive(Debug, Serialize, Deserialize)]
-#[cfg_attr(test, derive(PartialEq, Clone))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
 pub struct PublicGroup {
     treesync: TreeSync,
     proposal_store: ProposalStore,
@@ -68,6 +103,30 @@ pub struct PublicGroup {
     interim_transcript_hash: Vec<u8>,
     // Most recent confirmation tag. Kept here for verification purposes.
     confirmation_tag: ConfirmationTag,
+    // The history of past epochs, if enabled.
+    #[serde(default)]
+    epoch_history: Option<EpochHistory>,
+    // The policy for the lifetimes of key packages in Add proposals.
+    #[serde(default)]
+    lifetime_policy: LifetimePolicy,
+    // The limits for the structure of incoming messages.
+    #[serde(default)]
+    decode_limits: DecodeLimits,
+    // The strategy to place the leaf nodes of added members.
+    #[serde(default)]
+    leaf_placement_strategy: LeafPlacementStrategy,
+    // The placement set by the application, which takes precedence over the
+    // strategy. It has to be set again after the group was loaded.
+    #[serde(skip)]
+    custom_leaf_placement: Option<CustomLeafPlacement>,
+    // The cache for verified signatures set by the application. It has to be
+    // set again after the group was loaded.
+    #[serde(skip)]
+    signature_cache: Option<Arc<SignatureCache>>,
+    // The serialized group context, which is computed when it's first needed
+    // and reset whenever the group context changes.
+    #[serde(skip)]
+    serialized_group_context: OnceLock<Vec<u8>>,
 }
 
 impl PublicGroup {
@@ -95,6 +154,13 @@ impl PublicGroup {
             group_context,
             interim_transcript_hash,
             confirmation_tag: initial_confirmation_tag,
+            epoch_history: None,
+            lifetime_policy: LifetimePolicy::default(),
+            decode_limits: DecodeLimits::default(),
+            leaf_placement_strategy: LeafPlacementStrategy::default(),
+            custom_leaf_placement: None,
+            signature_cache: None,
+            serialized_group_context: OnceLock::new(),
         })
     }
 
@@ -108,12 +174,55 @@ impl PublicGroup {
         ratchet_tree: RatchetTreeIn,
         verifiable_group_info: VerifiableGroupInfo,
         proposal_store: ProposalStore,
+    ) -> Result<(Self, GroupInfo), CreationFromExternalError> {
+        Self::from_external_with_policy(
+            backend,
+            ratchet_tree,
+            verifiable_group_info,
+            proposal_store,
+            &CiphersuitePolicy::default(),
+        )
+    }
+
+    /// Create a [`PublicGroup`] instance to start tracking an existing MLS
+    /// group, if its ciphersuite is permitted by the `ciphersuite_policy`.
+    /// See [`PublicGroup::from_external()`].
+    pub fn from_external_with_policy(
+        backend: &impl OpenMlsCryptoProvider,
+        ratchet_tree: RatchetTreeIn,
+        verifiable_group_info: VerifiableGroupInfo,
+        proposal_store: ProposalStore,
+        ciphersuite_policy: &CiphersuitePolicy,
+    ) -> Result<(Self, GroupInfo), CreationFromExternalError> {
+        Self::from_external_internal(
+            backend,
+            ratchet_tree,
+            verifiable_group_info,
+            proposal_store,
+            ciphersuite_policy,
+            None,
+        )
+    }
+
+    /// Create a [`PublicGroup`] like
+    /// [`PublicGroup::from_external_with_policy()`]. If a `signature_cache`
+    /// is given, it is used to verify the ratchet tree and the group info and
+    /// set as the [`SignatureCache`] of the group.
+    pub(crate) fn from_external_internal(
+        backend: &impl OpenMlsCryptoProvider,
+        ratchet_tree: RatchetTreeIn,
+        verifiable_group_info: VerifiableGroupInfo,
+        proposal_store: ProposalStore,
+        ciphersuite_policy: &CiphersuitePolicy,
+        signature_cache: Option<Arc<SignatureCache>>,
     ) -> Result<(Self, GroupInfo), CreationFromExternalError> {
         let ciphersuite = verifiable_group_info.ciphersuite();
+        ciphersuite_policy.check(ciphersuite)?;
+        let crypto = CachingCrypto::new(backend.crypto(), signature_cache.as_deref());
 
         let group_id = verifiable_group_info.group_id();
         let ratchet_tree = ratchet_tree
-            .into_verified(ciphersuite, backend.crypto(), group_id)
+            .into_verified(ciphersuite, &crypto, group_id)
             .map_err(|e| {
                 CreationFromExternalError::TreeSyncError(TreeSyncFromNodesError::RatchetTreeError(
                     e,
@@ -134,7 +243,7 @@ impl PublicGroup {
                 .into_signature_public_key_enriched(ciphersuite.signature_algorithm());
 
             verifiable_group_info
-                .verify(backend.crypto(), &signer_signature_key)
+                .verify(&crypto, &signer_signature_key)
                 .map_err(|_| CreationFromExternalError::InvalidGroupInfoSignature)?
         };
 
@@ -165,6 +274,12 @@ impl PublicGroup {
                 interim_transcript_hash,
                 confirmation_tag: group_info.confirmation_tag().clone(),
                 proposal_store,
+                epoch_history: None,
+                lifetime_policy: LifetimePolicy::default(),
+                decode_limits: DecodeLimits::default(),
+                leaf_placement_strategy: LeafPlacementStrategy::default(),
+                custom_leaf_placement: None,
+                signature_cache,
             },
             group_info,
         ))
@@ -229,8 +344,12 @@ impl PublicGroup {
     /// Merge the changes performed on the [`PublicGroupDiff`] into this
     /// [`PublicGroup`].
     pub(crate) fn merge_diff(&mut self, diff: StagedPublicGroupDiff) {
+        if let Some(epoch_history) = &mut self.epoch_history {
+            epoch_history.push(diff.clone());
+        }
         self.treesync.merge_diff(diff.staged_diff);
         self.group_context = diff.group_context;
+        self.serialized_group_context = OnceLock::new();
         self.interim_transcript_hash = diff.interim_transcript_hash;
         self.confirmation_tag = diff.confirmation_tag;
     }
@@ -276,9 +395,245 @@ impl PublicGroup {
     }
 
     /// Add the [`QueuedProposal`] to the [`PublicGroup`]s internal [`ProposalStore`].
+    #[cfg(feature = "public-group-tracking")]
     pub fn add_proposal(&mut self, proposal: QueuedProposal) {
         self.proposal_store.add(proposal)
     }
+
+    /// Start keeping the history of the public state of this group, beginning
+    /// with the current epoch. Past epochs can then be inspected with
+    /// [`PublicGroup::at_epoch()`].
+    ///
+    /// The history keeps the staged diff of every merged commit, so its size
+    /// grows with the number of epochs. Does nothing if the history is
+    /// already enabled.
+    #[cfg(feature = "public-group-tracking")]
+    pub fn enable_epoch_history(&mut self) {
+        if self.epoch_history.is_none() {
+            self.epoch_history = Some(EpochHistory::new(self));
+        }
+    }
+
+    /// Stop keeping the history of the public state of this group and drop
+    /// the history recorded so far.
+    #[cfg(feature = "public-group-tracking")]
+    pub fn disable_epoch_history(&mut self) {
+        self.epoch_history = None;
+    }
+
+    /// Set the [`LifetimePolicy`] that is used to check the lifetimes of the
+    /// key packages in Add proposals, e.g. to tolerate skewed clocks.
+    pub fn set_lifetime_policy(&mut self, lifetime_policy: LifetimePolicy) {
+        self.lifetime_policy = lifetime_policy;
+    }
+
+    /// Set the [`DecodeLimits`] that bound the structure of incoming
+    /// messages, e.g. the number of proposals of a commit. Messages that
+    /// exceed them are rejected with
+    /// [`ValidationError::DecodeLimitExceeded`](crate::group::errors::ValidationError::DecodeLimitExceeded).
+    pub fn set_decode_limits(&mut self, decode_limits: DecodeLimits) {
+        self.decode_limits = decode_limits;
+    }
+
+    /// Set the [`LeafPlacementStrategy`] that decides where the leaf nodes of
+    /// added members are put in the tree. All members of the group must use
+    /// the same strategy.
+    pub fn set_leaf_placement_strategy(&mut self, leaf_placement_strategy: LeafPlacementStrategy) {
+        self.leaf_placement_strategy = leaf_placement_strategy;
+    }
+
+    /// Set a custom [`LeafPlacement`] that is used instead of the
+    /// [`LeafPlacementStrategy`]. All members of the group must use the same
+    /// placement.
+    ///
+    /// The placement is not part of the persisted group state and has to be
+    /// set again after the group was loaded.
+    pub fn set_leaf_placement(&mut self, leaf_placement: impl LeafPlacement + 'static) {
+        self.custom_leaf_placement = Some(CustomLeafPlacement::new(leaf_placement));
+    }
+
+    /// Removes the custom [`LeafPlacement`], so that the
+    /// [`LeafPlacementStrategy`] is used again.
+    pub fn clear_leaf_placement(&mut self) {
+        self.custom_leaf_placement = None;
+    }
+
+    /// Set the [`SignatureCache`] that is used to skip the verification of
+    /// signatures that were already verified when processing messages.
+    ///
+    /// The cache is not part of the persisted group state and has to be set
+    /// again after the group was loaded.
+    pub fn set_signature_cache(&mut self, signature_cache: Arc<SignatureCache>) {
+        self.signature_cache = Some(signature_cache);
+    }
+
+    /// Removes the [`SignatureCache`], so that all signatures are verified
+    /// again.
+    pub fn clear_signature_cache(&mut self) {
+        self.signature_cache = None;
+    }
+
+    /// Returns the [`SignatureCache`], if any.
+    pub(crate) fn signature_cache(&self) -> Option<&SignatureCache> {
+        self.signature_cache.as_deref()
+    }
+
+    /// Returns a read-only view of the public state of this group at the given
+    /// `epoch`, e.g. to find out who was a member when a message was sent.
+    ///
+    /// Returns [`None`] if the epoch history is not enabled, or if the `epoch`
+    /// is before the history was enabled or after the current epoch. See
+    /// [`PublicGroup::enable_epoch_history()`].
+    #[cfg(feature = "public-group-tracking")]
+    pub fn at_epoch(&self, epoch: GroupEpoch) -> Option<PublicGroupEpochView> {
+        self.epoch_history.as_ref()?.at_epoch(epoch)
+    }
+
+    /// Creates an external Add proposal for the current epoch of this group,
+    /// signed by the external sender with the given `sender_index` in the
+    /// group's [`ExternalSendersExtension`]. This is useful for a DS that
+    /// wants to add a client to the group. The proposal has to be committed
+    /// by a group member.
+    ///
+    /// Returns an error if the sender index is not part of the
+    /// [`ExternalSendersExtension`] or if the ciphersuite of the
+    /// [`KeyPackage`] doesn't match the group's ciphersuite.
+    pub fn create_external_add_proposal(
+        &self,
+        key_package: KeyPackage,
+        signer: &impl Signer,
+        sender_index: SenderExtensionIndex,
+    ) -> Result<MlsMessageOut, ExternalProposalError> {
+        self.check_external_sender(sender_index)?;
+        if key_package.ciphersuite() != self.ciphersuite() {
+            return Err(ExternalProposalError::CiphersuiteMismatch);
+        }
+
+        self.create_external_proposal(
+            Proposal::Add(AddProposal { key_package }),
+            signer,
+            sender_index,
+        )
+    }
+
+    /// Creates an external Remove proposal for the current epoch of this
+    /// group, signed by the external sender with the given `sender_index` in
+    /// the group's [`ExternalSendersExtension`]. This is useful for a DS that
+    /// wants to remove a client from the group. The proposal has to be
+    /// committed by a group member.
+    ///
+    /// Returns an error if the sender index is not part of the
+    /// [`ExternalSendersExtension`] or if there is no member at the given
+    /// leaf index.
+    pub fn create_external_remove_proposal(
+        &self,
+        removed: LeafNodeIndex,
+        signer: &impl Signer,
+        sender_index: SenderExtensionIndex,
+    ) -> Result<MlsMessageOut, ExternalProposalError> {
+        self.check_external_sender(sender_index)?;
+        if self.leaf(removed).is_none() {
+            return Err(ExternalProposalError::UnknownMember);
+        }
+
+        self.create_external_proposal(
+            Proposal::Remove(RemoveProposal { removed }),
+            signer,
+            sender_index,
+        )
+    }
+
+    fn check_external_sender(
+        &self,
+        sender_index: SenderExtensionIndex,
+    ) -> Result<(), ExternalProposalError> {
+        self.external_senders()
+            .ok_or(ExternalProposalError::NoExternalSendersExtension)?
+            .get(sender_index.index())
+            .map(|_| ())
+            .ok_or(ExternalProposalError::UnknownExternalSender)
+    }
+
+    fn create_external_proposal(
+        &self,
+        proposal: Proposal,
+        signer: &impl Signer,
+        sender_index: SenderExtensionIndex,
+    ) -> Result<MlsMessageOut, ExternalProposalError> {
+        let proposal = AuthenticatedContent::new_external_proposal(
+            proposal,
+            self.group_id().clone(),
+            self.group_context().epoch(),
+            signer,
+            sender_index,
+        )?;
+        Ok(PublicMessage::from(proposal).into())
+    }
+
+    /// Exports a [`GroupInfoSummary`] of the current epoch of this group,
+    /// signed by the external sender with the given `sender_index` in the
+    /// group's [`ExternalSendersExtension`]. This is useful for a DS that
+    /// publishes groups in a directory. Only the group context extensions
+    /// with a type in `extension_types` are included in the summary.
+    ///
+    /// Returns an error if the sender index is not part of the
+    /// [`ExternalSendersExtension`].
+    pub fn export_group_info_summary(
+        &self,
+        signer: &impl Signer,
+        sender_index: SenderExtensionIndex,
+        extension_types: &[ExtensionType],
+    ) -> Result<GroupInfoSummary, ExportGroupInfoSummaryError> {
+        self.check_external_sender(sender_index)
+            .map_err(|e| match e {
+                ExternalProposalError::NoExternalSendersExtension => {
+                    ExportGroupInfoSummaryError::NoExternalSendersExtension
+                }
+                ExternalProposalError::UnknownExternalSender => {
+                    ExportGroupInfoSummaryError::UnknownExternalSender
+                }
+                _ => LibraryError::custom("Unexpected external sender error").into(),
+            })?;
+
+        Ok(self.sign_group_info_summary(
+            signer,
+            GroupInfoSummarySigner::External(sender_index),
+            extension_types,
+        )?)
+    }
+
+    /// Creates and signs a [`GroupInfoSummary`] of the current epoch with the
+    /// given `signer`.
+    pub(crate) fn sign_group_info_summary(
+        &self,
+        signer: &impl Signer,
+        summary_signer: GroupInfoSummarySigner,
+        extension_types: &[ExtensionType],
+    ) -> Result<GroupInfoSummary, LibraryError> {
+        let extensions = Extensions::from_vec(
+            self.group_context
+                .extensions()
+                .iter()
+                .filter(|extension| extension_types.contains(&extension.extension_type()))
+                .cloned()
+                .collect(),
+        )
+        .map_err(|_| LibraryError::custom("The group context has duplicate extensions."))?;
+        let member_count = u32::try_from(self.members().count())
+            .map_err(|_| LibraryError::custom("Too many members."))?;
+
+        GroupInfoSummaryTBS::new(
+            self.group_id().clone(),
+            self.ciphersuite(),
+            self.group_context.epoch(),
+            member_count,
+            self.required_capabilities().cloned(),
+            extensions,
+            summary_signer,
+        )
+        .sign(signer)
+        .map_err(|_| LibraryError::custom("Signing failed"))
+    }
 }
 
 // Getters
@@ -303,11 +658,56 @@ impl PublicGroup {
         &self.group_context
     }
 
+    /// Get the TLS serialization of the group context. It's only computed
+    /// once per epoch.
+    pub(crate) fn serialized_group_context(&self) -> Result<&[u8], LibraryError> {
+        if let Some(serialized_group_context) = self.serialized_group_context.get() {
+            return Ok(serialized_group_context);
+        }
+        let serialized_group_context = self
+            .group_context
+            .tls_serialize_detached()
+            .map_err(LibraryError::missing_bound_check)?;
+        Ok(self
+            .serialized_group_context
+            .get_or_init(|| serialized_group_context))
+    }
+
     /// Get the required capabilities.
     pub fn required_capabilities(&self) -> Option<&RequiredCapabilitiesExtension> {
         self.group_context.required_capabilities()
     }
 
+    /// Get the external senders.
+    pub fn external_senders(&self) -> Option<&ExternalSendersExtension> {
+        self.group_context.extensions().external_senders()
+    }
+
+    /// Get the [`LifetimePolicy`] for the key packages in Add proposals.
+    pub fn lifetime_policy(&self) -> &LifetimePolicy {
+        &self.lifetime_policy
+    }
+
+    /// Get the [`DecodeLimits`] for incoming messages.
+    pub fn decode_limits(&self) -> &DecodeLimits {
+        &self.decode_limits
+    }
+
+    /// Get the [`LeafPlacementStrategy`] for the leaf nodes of added members.
+    pub fn leaf_placement_strategy(&self) -> LeafPlacementStrategy {
+        self.leaf_placement_strategy
+    }
+
+    /// Returns the placement for the leaf nodes of added members, i.e. the
+    /// custom [`LeafPlacement`] if one is set, and the
+    /// [`LeafPlacementStrategy`] otherwise.
+    pub(crate) fn leaf_placement(&self) -> &dyn LeafPlacement {
+        match &self.custom_leaf_placement {
+            Some(custom_leaf_placement) => &**custom_leaf_placement,
+            None => &self.leaf_placement_strategy,
+        }
+    }
+
     /// Get treesync.
     fn treesync(&self) -> &TreeSync {
         &self.treesync
@@ -344,12 +744,14 @@ impl PublicGroup {
 #[cfg(any(feature = "test-utils", test))]
 impl PublicGroup {
     pub(crate) fn context_mut(&mut self) -> &mut GroupContext {
+        self.serialized_group_context = OnceLock::new();
         &mut self.group_context
     }
 
     #[cfg(test)]
     pub(crate) fn set_group_context(&mut self, group_context: GroupContext) {
         self.group_context = group_context;
+        self.serialized_group_context = OnceLock::new();
     }
 
     #[cfg(test)]
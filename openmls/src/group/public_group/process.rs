@@ -1,5 +1,4 @@
 use openmls_traits::OpenMlsCryptoProvider;
-use tls_codec::Serialize;
 
 use crate::{
     ciphersuite::OpenMlsSignaturePublicKey,
@@ -18,7 +17,11 @@ use crate::{
     messages::proposals::Proposal,
 };
 
+#[cfg(feature = "public-group-tracking")]
+use super::budget::{BudgetTracker, ValidationBudget};
 use super::PublicGroup;
+#[cfg(feature = "public-group-tracking")]
+use crate::ciphersuite::CachingCrypto;
 
 impl PublicGroup {
     /// This function is used to parse messages from the DS.
@@ -42,6 +45,11 @@ impl PublicGroup {
         message_secrets_store_option: impl Into<Option<&'a MessageSecretsStore>>,
     ) -> Result<UnverifiedMessage, ValidationError> {
         let message_secrets_store_option = message_secrets_store_option.into();
+        // The content of private messages can only be checked against the
+        // decode limits after decryption.
+        self.decode_limits
+            .check_content_body(decrypted_message.verifiable_content().body())?;
+
         // Checks the following semantic validation:
         //  - ValSem004
         //  - ValSem005
@@ -130,12 +138,32 @@ impl PublicGroup {
     ///  - ValSem244
     ///  - ValSem245
     ///  - ValSem246 (as part of ValSem010)
+    #[cfg(feature = "public-group-tracking")]
     pub fn process_message(
         &self,
         backend: &impl OpenMlsCryptoProvider,
         message: impl Into<ProtocolMessage>,
     ) -> Result<ProcessedMessage, ProcessMessageError> {
-        let protocol_message = message.into();
+        self.process_message_with_budget(backend, message, &ValidationBudget::default())
+    }
+
+    /// Like [`Self::process_message()`], but aborts the validation early with
+    /// [`ProcessMessageError::BudgetExceeded`] if the message exceeds the
+    /// given [`ValidationBudget`].
+    ///
+    /// This allows a Delivery Service to bound the work spent on a single
+    /// message, e.g. on a commit with a pathological number of proposals.
+    #[cfg(feature = "public-group-tracking")]
+    pub fn process_message_with_budget(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        message: impl Into<ProtocolMessage>,
+        budget: &ValidationBudget,
+    ) -> Result<ProcessedMessage, ProcessMessageError> {
+        let budget = budget.start();
+        let protocol_message: ProtocolMessage = message.into();
+        let message_digest =
+            protocol_message.digest(backend.crypto(), self.ciphersuite(), self.version())?;
         // Checks the following semantic validation:
         //  - ValSem002
         //  - ValSem003
@@ -149,18 +177,21 @@ impl PublicGroup {
                 DecryptedMessage::from_inbound_public_message(
                     public_message,
                     None,
-                    self.group_context()
-                        .tls_serialize_detached()
-                        .map_err(LibraryError::missing_bound_check)?,
+                    self.serialized_group_context()?.to_vec(),
                     backend,
                 )?
             }
         };
 
+        budget.check_time()?;
+
         let unverified_message = self
             .parse_message(decrypted_message, None)
             .map_err(ProcessMessageError::from)?;
-        self.process_unverified_message(backend, unverified_message, &self.proposal_store)
+        budget.check_time()?;
+
+        self.process_unverified_message(backend, unverified_message, &self.proposal_store, &budget)
+            .map(|processed_message| processed_message.with_message_digest(message_digest))
     }
 }
 
@@ -192,17 +223,21 @@ impl PublicGroup {
     ///  - ValSem243
     ///  - ValSem244
     ///  - ValSem246 (as part of ValSem010)
+    #[cfg(feature = "public-group-tracking")]
     pub(crate) fn process_unverified_message(
         &self,
         backend: &impl OpenMlsCryptoProvider,
         unverified_message: UnverifiedMessage,
         proposal_store: &ProposalStore,
+        budget: &BudgetTracker,
     ) -> Result<ProcessedMessage, ProcessMessageError> {
         // Checks the following semantic validation:
         //  - ValSem010
         //  - ValSem246 (as part of ValSem010)
+        let crypto = CachingCrypto::new(backend.crypto(), self.signature_cache());
         let (content, credential) =
-            unverified_message.verify(self.ciphersuite(), backend.crypto(), self.version())?;
+            unverified_message.verify(self.ciphersuite(), &crypto, self.version())?;
+        budget.check_time()?;
 
         match content.sender() {
             Sender::Member(_) | Sender::NewMemberCommit | Sender::NewMemberProposal => {
@@ -210,12 +245,19 @@ impl PublicGroup {
                 let authenticated_data = content.authenticated_data().to_owned();
 
                 let content = match content.content() {
-                    FramedContentBody::Application(application_message) => {
+                    FramedContentBody::Application(_) => {
+                        let application_data =
+                            content.into_application_data().ok_or_else(|| {
+                                LibraryError::custom("The content is an application message")
+                            })?;
                         ProcessedMessageContent::ApplicationMessage(ApplicationMessage::new(
-                            application_message.as_slice().to_owned(),
+                            application_data,
                         ))
                     }
-                    FramedContentBody::Proposal(_) => {
+                    FramedContentBody::Proposal(proposal) => {
+                        if !self.is_authorized_proposal(&sender, proposal, None) {
+                            return Err(ValidationError::SenderNotAdmin.into());
+                        }
                         let proposal = Box::new(QueuedProposal::from_authenticated_content_by_ref(
                             self.ciphersuite(),
                             backend,
@@ -227,8 +269,12 @@ impl PublicGroup {
                             ProcessedMessageContent::ProposalMessage(proposal)
                         }
                     }
-                    FramedContentBody::Commit(_) => {
-                        let staged_commit = self.stage_commit(&content, proposal_store, backend)?;
+                    FramedContentBody::Commit(commit) => {
+                        // Reject commits with too many proposals before
+                        // validating any of them.
+                        budget.check_proposals(commit.proposals.len())?;
+                        let staged_commit =
+                            self.stage_commit(&content, proposal_store, backend, budget)?;
                         ProcessedMessageContent::StagedCommitMessage(Box::new(staged_commit))
                     }
                 };
@@ -249,7 +295,7 @@ impl PublicGroup {
                     FramedContentBody::Application(_) => {
                         Err(ProcessMessageError::UnauthorizedExternalApplicationMessage)
                     }
-                    FramedContentBody::Proposal(Proposal::Remove(_)) => {
+                    FramedContentBody::Proposal(Proposal::Add(_) | Proposal::Remove(_)) => {
                         let content = ProcessedMessageContent::ProposalMessage(Box::new(
                             QueuedProposal::from_authenticated_content_by_ref(
                                 self.ciphersuite(),
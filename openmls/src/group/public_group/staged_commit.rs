@@ -1,3 +1,5 @@
+#[cfg(feature = "public-group-tracking")]
+use super::budget::BudgetTracker;
 use super::{super::errors::*, *};
 use crate::{
     framing::{mls_auth_content::AuthenticatedContent, mls_content::FramedContentBody, Sender},
@@ -91,6 +93,12 @@ impl PublicGroup {
         // ValSem402
         // ValSem403
         self.validate_pre_shared_key_proposals(&proposal_queue)?;
+        // ValSem113
+        // ValSem114
+        // ValSem115
+        self.validate_group_context_extensions_proposals(&proposal_queue)?;
+        // Only admins may add and remove members if the group has admins
+        self.validate_admin_proposals(&proposal_queue, sender)?;
 
         match sender {
             Sender::Member(leaf_index) => {
@@ -114,6 +122,11 @@ impl PublicGroup {
                 // ValSem243: External Commit, inline Remove Proposal: The identity and the endpoint_id of the removed
                 //            leaf are identical to the ones in the path KeyPackage.
                 self.validate_external_commit(&proposal_queue, commit_update_leaf_node.as_ref())?;
+                // Only admins may join if the group has admins
+                self.validate_admin_external_commit(
+                    &proposal_queue,
+                    commit_update_leaf_node.as_ref(),
+                )?;
             }
         }
 
@@ -176,28 +189,34 @@ impl PublicGroup {
     ///  - ValSem243
     ///  - ValSem244
     /// Returns an error if the given commit was sent by the owner of this
-    /// group.
-    /// TODO #1255: This will be used by the `process_message` function of the
-    /// `PublicGroup` later on.
-    #[allow(unused)]
+    /// group or if staging the commit exceeds the `budget`.
+    #[cfg(feature = "public-group-tracking")]
     pub(crate) fn stage_commit(
         &self,
         mls_content: &AuthenticatedContent,
         proposal_store: &ProposalStore,
         backend: &impl OpenMlsCryptoProvider,
-    ) -> Result<StagedCommit, StageCommitError> {
-        let ciphersuite = self.ciphersuite();
-
-        let (commit, proposal_queue, sender_index) =
+        budget: &BudgetTracker,
+    ) -> Result<StagedCommit, ProcessMessageError> {
+        let (_commit, proposal_queue, sender_index) =
             self.validate_commit(mls_content, proposal_store, backend)?;
+        budget.check_time()?;
 
         let staged_diff = self.stage_diff(mls_content, &proposal_queue, sender_index, backend)?;
+        budget.check_time()?;
 
         let staged_commit_state = StagedCommitState::PublicState(Box::new(staged_diff));
 
-        Ok(StagedCommit::new(proposal_queue, staged_commit_state))
+        Ok(StagedCommit::new(
+            proposal_queue,
+            staged_commit_state,
+            sender_index,
+            self,
+            None,
+        ))
     }
 
+    #[cfg(feature = "public-group-tracking")]
     fn stage_diff(
         &self,
         mls_content: &AuthenticatedContent,
@@ -252,6 +271,7 @@ impl PublicGroup {
     ///
     /// This function should not fail and only returns a [`Result`], because it
     /// might throw a `LibraryError`.
+    #[cfg(feature = "public-group-tracking")]
     pub fn merge_commit(&mut self, staged_commit: StagedCommit) {
         match staged_commit.into_state() {
             StagedCommitState::PublicState(staged_diff) => self.merge_diff(*staged_diff),
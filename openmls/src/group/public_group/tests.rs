@@ -1,7 +1,10 @@
+use std::time::Duration;
+
 use openmls_rust_crypto::OpenMlsRustCrypto;
 use openmls_traits::{types::Ciphersuite, OpenMlsCryptoProvider};
 use rstest::*;
 use rstest_reuse::{self, *};
+use tls_codec::Serialize;
 
 use crate::{
     binary_tree::LeafNodeIndex,
@@ -10,13 +13,15 @@ use crate::{
         ProcessedMessageContent, ProtocolMessage, Sender,
     },
     group::{
-        config::CryptoConfig, test_core_group::setup_client, GroupId, MlsGroup,
-        MlsGroupConfigBuilder, ProposalStore, StagedCommit, PURE_PLAINTEXT_WIRE_FORMAT_POLICY,
+        config::CryptoConfig, errors::ProcessMessageError, test_core_group::setup_client, GroupId,
+        MlsGroup, MlsGroupConfigBuilder, ProposalStore, StagedCommit,
+        PURE_PLAINTEXT_WIRE_FORMAT_POLICY,
     },
+    key_packages::{KeyPackage, Lifetime, LifetimePolicy},
     messages::proposals::Proposal,
 };
 
-use super::PublicGroup;
+use super::{CommitLint, CommitLintConfig, PublicGroup, ValidationBudget};
 
 #[apply(ciphersuites_and_backends)]
 fn public_group(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
@@ -186,7 +191,9 @@ fn public_group(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider)
             // Check that Bob was removed
             assert_eq!(remove_proposal.removed(), LeafNodeIndex::new(1));
             // Store proposal
-            charlie_group.store_pending_proposal(*staged_proposal.clone());
+            charlie_group
+                .store_pending_proposal(backend, *staged_proposal.clone())
+                .expect("Could not store proposal.");
         } else {
             unreachable!("Expected a Proposal.");
         }
@@ -285,3 +292,343 @@ fn extract_staged_commit(ppm: ProcessedMessage) -> StagedCommit {
         ProcessedMessageContent::StagedCommitMessage(staged_content) => *staged_content,
     }
 }
+
+#[apply(ciphersuites_and_backends)]
+fn validation_budget(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charly", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        GroupId::from_slice(b"Test Group"),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let verifiable_group_info = alice_group
+        .export_group_info(backend, &alice_signer, false)
+        .unwrap()
+        .into_verifiable_group_info()
+        .unwrap();
+    let (public_group, _extensions) = PublicGroup::from_external(
+        backend,
+        alice_group.export_ratchet_tree().into(),
+        verifiable_group_info,
+        ProposalStore::new(),
+    )
+    .unwrap();
+
+    // Alice adds Bob and Charlie in one commit
+    let (message, _welcome, _group_info) = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[
+                bob_kpb.key_package().clone(),
+                charlie_kpb.key_package().clone(),
+            ],
+        )
+        .expect("Could not add members to group.");
+    let public_message = into_public_message(message);
+
+    // The commit covers more proposals than the budget allows
+    let err = public_group
+        .process_message_with_budget(
+            backend,
+            public_message.clone(),
+            &ValidationBudget::new().with_max_proposals(1),
+        )
+        .expect_err("Processed a commit exceeding the budget.");
+    assert_eq!(err, ProcessMessageError::BudgetExceeded);
+
+    // The validation takes longer than the budget allows
+    let err = public_group
+        .process_message_with_budget(
+            backend,
+            public_message.clone(),
+            &ValidationBudget::new().with_max_duration(Duration::ZERO),
+        )
+        .expect_err("Processed a commit exceeding the budget.");
+    assert_eq!(err, ProcessMessageError::BudgetExceeded);
+
+    // The commit is processed within a sufficient budget
+    let ppm = public_group
+        .process_message_with_budget(
+            backend,
+            public_message,
+            &ValidationBudget::new()
+                .with_max_proposals(2)
+                .with_max_duration(Duration::from_secs(60)),
+        )
+        .unwrap();
+    let staged_commit = extract_staged_commit(ppm);
+    assert_eq!(staged_commit.add_proposals().count(), 2);
+}
+
+#[apply(ciphersuites_and_backends)]
+fn epoch_history(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charly", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        GroupId::from_slice(b"Test Group"),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let verifiable_group_info = alice_group
+        .export_group_info(backend, &alice_signer, false)
+        .unwrap()
+        .into_verifiable_group_info()
+        .unwrap();
+    let (mut public_group, _extensions) = PublicGroup::from_external(
+        backend,
+        alice_group.export_ratchet_tree().into(),
+        verifiable_group_info,
+        ProposalStore::new(),
+    )
+    .unwrap();
+    let initial_epoch = public_group.group_context().epoch();
+
+    // Without the history, past epochs are not available
+    assert!(public_group.at_epoch(initial_epoch).is_none());
+    public_group.enable_epoch_history();
+    let initial_tree_hash = public_group.group_context().tree_hash().to_vec();
+
+    // Alice adds Bob and then Charlie
+    for key_package in [bob_kpb.key_package(), charlie_kpb.key_package()] {
+        let (message, _welcome, _group_info) = alice_group
+            .add_members(backend, &alice_signer, &[key_package.clone()])
+            .expect("Could not add member to group.");
+        alice_group.merge_pending_commit(backend).unwrap();
+        let ppm = public_group
+            .process_message(backend, into_public_message(message))
+            .unwrap();
+        public_group.merge_commit(extract_staged_commit(ppm));
+    }
+
+    // The members at each epoch
+    let view = public_group.at_epoch(initial_epoch).unwrap();
+    assert_eq!(view.epoch(), initial_epoch);
+    assert_eq!(view.members().count(), 1);
+    assert_eq!(view.tree_hash(), initial_tree_hash.as_slice());
+
+    let view = public_group
+        .at_epoch((initial_epoch.as_u64() + 1).into())
+        .unwrap();
+    assert_eq!(view.members().count(), 2);
+    assert!(view.leaf(LeafNodeIndex::new(1)).is_some());
+    assert!(view.leaf(LeafNodeIndex::new(2)).is_none());
+
+    // The current epoch matches the group
+    let view = public_group
+        .at_epoch((initial_epoch.as_u64() + 2).into())
+        .unwrap();
+    assert_eq!(view.group_context(), public_group.group_context());
+    assert_eq!(view.members().count(), 3);
+    assert_eq!(
+        view.export_ratchet_tree(),
+        public_group.export_ratchet_tree()
+    );
+
+    // Future epochs are not available
+    assert!(public_group
+        .at_epoch((initial_epoch.as_u64() + 3).into())
+        .is_none());
+}
+
+#[apply(ciphersuites_and_backends)]
+fn commit_lints(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (bob_credential_with_key, _bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    // Bob's key package expires right away.
+    let bob_key_package = KeyPackage::builder()
+        .key_package_lifetime(Lifetime::new(0))
+        .build(
+            CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &bob_signer,
+            bob_credential_with_key,
+        )
+        .expect("Error creating key package.");
+    std::thread::sleep(Duration::from_secs(1));
+
+    // Lifetimes are not enforced, so that the expired key package is accepted.
+    let lenient_policy = LifetimePolicy::default().with_enforcement(false);
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .lifetime_policy(lenient_policy)
+        .build();
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        GroupId::from_slice(b"Test Group"),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let verifiable_group_info = alice_group
+        .export_group_info(backend, &alice_signer, false)
+        .unwrap()
+        .into_verifiable_group_info()
+        .unwrap();
+    let (mut public_group, _extensions) = PublicGroup::from_external(
+        backend,
+        alice_group.export_ratchet_tree().into(),
+        verifiable_group_info,
+        ProposalStore::new(),
+    )
+    .unwrap();
+    public_group.set_lifetime_policy(lenient_policy);
+
+    // Alice proposes to add Bob and commits the proposal without a path
+    let (proposal_message, _proposal_ref) = alice_group
+        .propose_add_member(backend, &alice_signer, &bob_key_package)
+        .expect("Could not propose to add Bob.");
+    let (commit_message, _welcome, _group_info) = alice_group
+        .commit_builder()
+        .force_self_update(false)
+        .finalize(backend, &alice_signer)
+        .expect("Error creating commit");
+    let proposal_message = into_public_message(proposal_message);
+    let commit_message = into_public_message(commit_message);
+
+    // Messages other than commits have no findings
+    assert!(public_group.lint_commit(&proposal_message).is_empty());
+
+    // The public group doesn't know the proposal yet
+    assert_eq!(
+        public_group.lint_commit(&commit_message),
+        vec![
+            CommitLint::MissingPath,
+            CommitLint::UnknownProposalReference { proposal_index: 0 }
+        ]
+    );
+
+    // Once the proposal is stored, the expired key package is found
+    match public_group
+        .process_message(backend, proposal_message)
+        .unwrap()
+        .into_content()
+    {
+        ProcessedMessageContent::ProposalMessage(queued_proposal) => {
+            public_group.add_proposal(*queued_proposal)
+        }
+        _ => panic!("Unexpected message type."),
+    }
+    assert_eq!(
+        public_group.lint_commit(&commit_message),
+        vec![
+            CommitLint::MissingPath,
+            CommitLint::InvalidKeyPackageLifetime { proposal_index: 0 }
+        ]
+    );
+    assert_eq!(
+        public_group.lint_commit_with_config(
+            &commit_message,
+            &CommitLintConfig::new().with_max_proposals(0)
+        ),
+        vec![
+            CommitLint::MissingPath,
+            CommitLint::ManyProposals { proposals: 1 },
+            CommitLint::InvalidKeyPackageLifetime { proposal_index: 0 }
+        ]
+    );
+
+    // Linting doesn't prevent processing the commit
+    public_group
+        .process_message(backend, commit_message)
+        .expect("Could not process commit.");
+}
+
+#[apply(ciphersuites_and_backends)]
+fn serialized_group_context(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        GroupId::from_slice(b"Test Group"),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let verifiable_group_info = alice_group
+        .export_group_info(backend, &alice_signer, false)
+        .unwrap()
+        .into_verifiable_group_info()
+        .unwrap();
+    let (mut public_group, _extensions) = PublicGroup::from_external(
+        backend,
+        alice_group.export_ratchet_tree().into(),
+        verifiable_group_info,
+        ProposalStore::new(),
+    )
+    .unwrap();
+
+    let initial_serialized_group_context =
+        public_group.serialized_group_context().unwrap().to_vec();
+    assert_eq!(
+        initial_serialized_group_context,
+        public_group
+            .group_context()
+            .tls_serialize_detached()
+            .unwrap()
+    );
+
+    // The serialized group context is updated with the epoch.
+    let (message, _welcome, _group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Could not update own leaf.");
+    alice_group.merge_pending_commit(backend).unwrap();
+    let ppm = public_group
+        .process_message(backend, into_public_message(message))
+        .unwrap();
+    public_group.merge_commit(extract_staged_commit(ppm));
+
+    let serialized_group_context = public_group.serialized_group_context().unwrap();
+    assert_ne!(serialized_group_context, initial_serialized_group_context);
+    assert_eq!(
+        serialized_group_context,
+        alice_group
+            .export_group_context()
+            .tls_serialize_detached()
+            .unwrap()
+    );
+}
@@ -6,10 +6,10 @@ use std::collections::{BTreeSet, HashSet};
 use openmls_traits::types::VerifiableCiphersuite;
 
 use super::PublicGroup;
-#[cfg(test)]
-use crate::treesync::errors::LeafNodeValidationError;
 use crate::{
     binary_tree::array_representation::LeafNodeIndex,
+    credentials::CredentialType,
+    extensions::ExtensionType,
     framing::{
         mls_auth_content_in::VerifiableAuthenticatedContentIn, ContentType, ProtocolMessage,
         Sender, WireFormat,
@@ -17,14 +17,17 @@ use crate::{
     group::{
         errors::{ExternalCommitValidationError, ProposalValidationError, ValidationError},
         past_secrets::MessageSecretsStore,
-        Member, ProposalQueue,
+        GroupEpoch, Member, ProposalQueue,
     },
     messages::{
         proposals::{Proposal, ProposalOrRefType, ProposalType},
         Commit,
     },
-    schedule::errors::PskError,
-    treesync::node::leaf_node::LeafNode,
+    schedule::{
+        errors::PskError,
+        psk::{Psk, ResumptionPskUsage},
+    },
+    treesync::{errors::LeafNodeValidationError, node::leaf_node::LeafNode},
 };
 
 impl PublicGroup {
@@ -53,7 +56,10 @@ impl PublicGroup {
                         message.epoch(),
                         self.group_context().epoch()
                     );
-                    return Err(ValidationError::WrongEpoch);
+                    return Err(ValidationError::WrongEpoch {
+                        received: message.epoch(),
+                        expected: self.group_context().epoch(),
+                    });
                 }
             }
             // For all other messages we only only accept the current epoch
@@ -64,7 +70,10 @@ impl PublicGroup {
                         message.epoch(),
                         self.group_context().epoch()
                     );
-                    return Err(ValidationError::WrongEpoch);
+                    return Err(ValidationError::WrongEpoch {
+                        received: message.epoch(),
+                        expected: self.group_context().epoch(),
+                    });
                 }
             }
         }
@@ -346,6 +355,8 @@ impl PublicGroup {
 
     /// Validate Add proposals. This function implements the following checks:
     ///  - ValSem105: Add Proposal: Ciphersuite & protocol version must match the group
+    ///  - The lifetime of the key package must be acceptable under the
+    ///    group's [`LifetimePolicy`](crate::key_packages::LifetimePolicy)
     pub(crate) fn validate_add_proposals(
         &self,
         proposal_queue: &ProposalQueue,
@@ -353,12 +364,16 @@ impl PublicGroup {
         let add_proposals = proposal_queue.add_proposals();
 
         for add_proposal in add_proposals {
+            let key_package = add_proposal.add_proposal().key_package();
             // ValSem105: Check if ciphersuite and version of the group are correct:
-            if add_proposal.add_proposal().key_package().ciphersuite() != self.ciphersuite()
-                || add_proposal.add_proposal().key_package().protocol_version() != self.version()
+            if key_package.ciphersuite() != self.ciphersuite()
+                || key_package.protocol_version() != self.version()
             {
                 return Err(ProposalValidationError::InvalidAddProposalCiphersuiteOrVersion);
             }
+            if !key_package.has_acceptable_lifetime(self.lifetime_policy()) {
+                return Err(ProposalValidationError::InvalidAddProposalLifetime);
+            }
         }
         Ok(())
     }
@@ -418,12 +433,65 @@ impl PublicGroup {
         Ok(())
     }
 
+    /// Validate GroupContextExtensions proposals. This function implements
+    /// the following checks:
+    ///  - ValSem113: GroupContextExtensions Proposal: A commit must not cover
+    ///    more than one GroupContextExtensions proposal
+    ///  - ValSem114: GroupContextExtensions Proposal: All members must support
+    ///    the required capabilities and the extensions not defined in the spec
+    ///  - ValSem115: GroupContextExtensions Proposal: All members must support
+    ///    the credential types of the external senders
+    pub(crate) fn validate_group_context_extensions_proposals(
+        &self,
+        proposal_queue: &ProposalQueue,
+    ) -> Result<(), ProposalValidationError> {
+        let mut proposals = proposal_queue.filtered_by_type(ProposalType::GroupContextExtensions);
+        let proposal = proposals.next();
+
+        // ValSem113
+        if proposals.next().is_some() {
+            return Err(ProposalValidationError::MultipleGroupContextExtensionsProposals);
+        }
+
+        // ValSem114
+        if let Some(Proposal::GroupContextExtensions(proposal)) =
+            proposal.map(|queued_proposal| queued_proposal.proposal())
+        {
+            let mut extension_types: Vec<ExtensionType> = proposal
+                .extensions()
+                .iter()
+                .map(|extension| extension.extension_type())
+                .filter(|extension_type| matches!(extension_type, ExtensionType::Unknown(_)))
+                .collect();
+            if let Some(required_capabilities) = proposal.extensions().required_capabilities() {
+                extension_types.extend_from_slice(required_capabilities.extension_types());
+            }
+            self.check_extension_support(&extension_types)
+                .map_err(|_| ProposalValidationError::UnsupportedGroupContextExtensions)?;
+
+            // ValSem115
+            if let Some(external_senders) = proposal.extensions().external_senders() {
+                let credential_types: Vec<CredentialType> = external_senders
+                    .iter()
+                    .map(|external_sender| external_sender.credential().credential_type())
+                    .collect();
+                self.check_credential_support(&credential_types)
+                    .map_err(|_| ProposalValidationError::UnsupportedExternalSenderCredential)?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Validate PreSharedKey proposals.
     ///
     /// This method implements the following checks:
     ///
     /// * ValSem401: The nonce of a PreSharedKeyID must have length KDF.Nh.
     /// * ValSem402: PSK in proposal must be of type Resumption (with usage Application) or External.
+    ///   The first commit of a group, i.e. in epoch 0, may contain a single
+    ///   Resumption PSK with usage ReInit or Branch, as in the first commit of
+    ///   a reinitialized group or a subgroup branch.
     /// * ValSem403: Proposal list must not contain multiple PreSharedKey proposals that reference the same PreSharedKeyID.
     pub(crate) fn validate_pre_shared_key_proposals(
         &self,
@@ -433,13 +501,20 @@ impl PublicGroup {
         // TODO(#1335): Duplicate proposals are (likely) filtered.
         //              Let's do this check here until we haven't made sure.
         let mut visited_psk_ids = BTreeSet::new();
+        let mut first_commit = self.group_context().epoch() == GroupEpoch::from(0);
 
         for proposal in proposal_queue.psk_proposals() {
             let psk_id = proposal.psk_proposal().clone().into_psk_id();
 
             // ValSem401
             // ValSem402
-            let psk_id = psk_id.validate_in_proposal(self.ciphersuite())?;
+            let psk_id = psk_id.validate_in_proposal(self.ciphersuite(), first_commit)?;
+            if let Psk::Resumption(resumption_psk) = psk_id.psk() {
+                if resumption_psk.usage() != ResumptionPskUsage::Application {
+                    // Only one ReInit or Branch PSK is allowed.
+                    first_commit = false;
+                }
+            }
 
             // ValSem403 (2/2)
             if !visited_psk_ids.contains(&psk_id) {
@@ -513,12 +588,28 @@ impl PublicGroup {
         Ok(())
     }
 
+    /// Returns a [`LeafNodeValidationError`] if a [`CredentialType`] in
+    /// `credential_types` is not supported by a leaf in this tree.
+    pub(crate) fn check_credential_support(
+        &self,
+        credential_types: &[CredentialType],
+    ) -> Result<(), LeafNodeValidationError> {
+        for leaf in self.treesync().full_leaves() {
+            if credential_types
+                .iter()
+                .any(|credential_type| !leaf.capabilities().credentials().contains(credential_type))
+            {
+                return Err(LeafNodeValidationError::UnsupportedCredentials);
+            }
+        }
+        Ok(())
+    }
+
     /// Returns a [`LeafNodeValidationError`] if an [`ExtensionType`]
     /// in `extensions` is not supported by a leaf in this tree.
-    #[cfg(test)]
     pub(crate) fn check_extension_support(
         &self,
-        extensions: &[crate::extensions::ExtensionType],
+        extensions: &[ExtensionType],
     ) -> Result<(), LeafNodeValidationError> {
         for leaf in self.treesync().full_leaves() {
             leaf.check_extension_support(extensions)?;
@@ -6,7 +6,7 @@ use rstest_reuse::{self, *};
 use crate::{
     binary_tree::LeafNodeIndex,
     framing::*,
-    group::{config::CryptoConfig, *},
+    group::{config::CryptoConfig, public_group::errors::ExternalProposalError, *},
     messages::{
         external_proposals::*,
         proposals::{AddProposal, Proposal, ProposalType},
@@ -161,7 +161,9 @@ fn external_add_proposal_should_succeed(
                     proposal.proposal(),
                     Proposal::Add(AddProposal { key_package }) if key_package == &charlie_kp
                 ));
-                alice_group.store_pending_proposal(*proposal)
+                alice_group
+                    .store_pending_proposal(backend, *proposal)
+                    .expect("Could not store proposal.")
             }
             _ => unreachable!(),
         }
@@ -171,9 +173,9 @@ fn external_add_proposal_should_succeed(
             .unwrap();
 
         match msg.into_content() {
-            ProcessedMessageContent::ExternalJoinProposalMessage(proposal) => {
-                bob_group.store_pending_proposal(*proposal)
-            }
+            ProcessedMessageContent::ExternalJoinProposalMessage(proposal) => bob_group
+                .store_pending_proposal(backend, *proposal)
+                .expect("Could not store proposal."),
             _ => unreachable!(),
         }
 
@@ -338,3 +340,102 @@ fn new_member_proposal_sender_should_be_reserved_for_join_proposals(
     };
     alice_group.clear_pending_proposals();
 }
+
+#[apply(ciphersuites_and_backends)]
+fn external_sender_add_proposal_should_succeed(
+    ciphersuite: Ciphersuite,
+    backend: &impl OpenMlsCryptoProvider,
+) {
+    let ProposalValidationTestSetup {
+        alice_group,
+        bob_group: _,
+    } = validation_test_setup(PURE_PLAINTEXT_WIRE_FORMAT_POLICY, ciphersuite, backend);
+    let (mut alice_group, alice_signer) = alice_group;
+
+    // The delivery service becomes an external sender of the group
+    let ds_credential = generate_credential_with_key(
+        "delivery-service".into(),
+        ciphersuite.signature_algorithm(),
+        backend,
+    );
+    let ds_sender = ExternalSender::new(
+        ds_credential.credential_with_key.signature_key.clone(),
+        ds_credential.credential_with_key.credential.clone(),
+    );
+    assert!(alice_group.external_senders().is_none());
+    alice_group
+        .propose_add_external_sender(backend, &alice_signer, ds_sender.clone())
+        .unwrap();
+    alice_group
+        .commit_to_pending_proposals(backend, &alice_signer)
+        .unwrap();
+    alice_group.merge_pending_commit(backend).unwrap();
+    assert_eq!(alice_group.external_senders(), Some(&vec![ds_sender]));
+
+    // The delivery service asks to add Charlie
+    let charlie_credential =
+        generate_credential_with_key("Charlie".into(), ciphersuite.signature_algorithm(), backend);
+    let charlie_kp = generate_key_package(
+        ciphersuite,
+        Extensions::empty(),
+        backend,
+        charlie_credential,
+    );
+
+    let public_group = alice_group.group().public_group();
+    assert_eq!(
+        public_group
+            .create_external_add_proposal(
+                charlie_kp.clone(),
+                &ds_credential.signer,
+                SenderExtensionIndex::new(1),
+            )
+            .unwrap_err(),
+        ExternalProposalError::UnknownExternalSender
+    );
+    let proposal = public_group
+        .create_external_add_proposal(
+            charlie_kp.clone(),
+            &ds_credential.signer,
+            SenderExtensionIndex::new(0),
+        )
+        .unwrap();
+
+    let msg = alice_group
+        .process_message(backend, proposal.into_protocol_message().unwrap())
+        .unwrap();
+    match msg.into_content() {
+        ProcessedMessageContent::ProposalMessage(proposal) => {
+            assert!(matches!(proposal.sender(), Sender::External(_)));
+            assert!(matches!(
+                proposal.proposal(),
+                Proposal::Add(AddProposal { key_package }) if key_package == &charlie_kp
+            ));
+            alice_group
+                .store_pending_proposal(backend, *proposal)
+                .expect("Could not store proposal.")
+        }
+        _ => unreachable!(),
+    }
+    alice_group
+        .commit_to_pending_proposals(backend, &alice_signer)
+        .unwrap();
+    alice_group.merge_pending_commit(backend).unwrap();
+    assert_eq!(alice_group.members().count(), 3);
+
+    // The delivery service is no longer an external sender of the group
+    assert_eq!(
+        alice_group
+            .propose_remove_external_sender(backend, &alice_signer, SenderExtensionIndex::new(1))
+            .unwrap_err(),
+        ProposalError::UnknownExternalSender
+    );
+    alice_group
+        .propose_remove_external_sender(backend, &alice_signer, SenderExtensionIndex::new(0))
+        .unwrap();
+    alice_group
+        .commit_to_pending_proposals(backend, &alice_signer)
+        .unwrap();
+    alice_group.merge_pending_commit(backend).unwrap();
+    assert!(alice_group.external_senders().is_none());
+}
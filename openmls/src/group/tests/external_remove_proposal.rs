@@ -141,8 +141,14 @@ fn external_remove_proposal_should_remove_member(
         .process_message(backend, bob_external_remove_proposal)
         .unwrap();
     // commit the proposal
-    let ProcessedMessageContent::ProposalMessage(remove_proposal) = processed_message.into_content() else { panic!("Not a remove proposal");};
-    alice_group.store_pending_proposal(*remove_proposal);
+    let ProcessedMessageContent::ProposalMessage(remove_proposal) =
+        processed_message.into_content()
+    else {
+        panic!("Not a remove proposal");
+    };
+    alice_group
+        .store_pending_proposal(backend, *remove_proposal)
+        .unwrap();
     alice_group
         .commit_to_pending_proposals(backend, &alice_credential.signer)
         .unwrap();
@@ -163,8 +169,14 @@ fn external_remove_proposal_should_remove_member(
         .process_message(backend, invalid_bob_external_remove_proposal)
         .unwrap();
     // commit the proposal
-    let ProcessedMessageContent::ProposalMessage(remove_proposal) = processed_message.into_content() else { panic!("Not a remove proposal");};
-    alice_group.store_pending_proposal(*remove_proposal);
+    let ProcessedMessageContent::ProposalMessage(remove_proposal) =
+        processed_message.into_content()
+    else {
+        panic!("Not a remove proposal");
+    };
+    alice_group
+        .store_pending_proposal(backend, *remove_proposal)
+        .unwrap();
     assert_eq!(
         alice_group
             .commit_to_pending_proposals(backend, &alice_credential.signer)
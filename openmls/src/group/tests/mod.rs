@@ -9,25 +9,149 @@ pub mod kat_messages;
 #[cfg(test)]
 pub mod kat_transcript_hashes;
 #[cfg(test)]
+mod test_admins;
+#[cfg(test)]
+mod test_app_state_hash;
+#[cfg(test)]
+mod test_async;
+#[cfg(test)]
+mod test_atomic_merge;
+#[cfg(test)]
+mod test_auditor_tags;
+#[cfg(test)]
+mod test_backup_keys;
+#[cfg(test)]
+mod test_ciphersuite_policy;
+#[cfg(test)]
+mod test_commit_builder;
+#[cfg(test)]
+mod test_commit_to_proposals;
+#[cfg(test)]
 mod test_commit_validation;
 #[cfg(test)]
+mod test_commit_validator;
+#[cfg(test)]
+mod test_compressed_ratchet_tree;
+#[cfg(test)]
+mod test_concurrent_decryption;
+#[cfg(test)]
+mod test_credential_update;
+#[cfg(test)]
+mod test_credential_validator;
+#[cfg(test)]
+mod test_decode_limits;
+#[cfg(test)]
+mod test_emergency_rekey;
+#[cfg(test)]
 mod test_encoding;
 #[cfg(test)]
+mod test_event_log;
+#[cfg(test)]
+mod test_exporter;
+#[cfg(all(test, feature = "external-commit"))]
 mod test_external_commit_validation;
 #[cfg(test)]
+mod test_feature_negotiation;
+#[cfg(test)]
+mod test_fork_detection;
+#[cfg(test)]
 mod test_framing;
 #[cfg(test)]
 mod test_framing_validation;
 #[cfg(test)]
 mod test_group;
 #[cfg(test)]
+mod test_group_context_extensions;
+#[cfg(test)]
+mod test_group_info_summary;
+#[cfg(test)]
+mod test_group_observer;
+#[cfg(test)]
+mod test_handle_signer;
+#[cfg(test)]
+mod test_join_confirmation;
+#[cfg(test)]
+mod test_key_package_lifetime;
+#[cfg(test)]
+mod test_last_resort_key_packages;
+#[cfg(test)]
+mod test_leaf_placement;
+#[cfg(test)]
+mod test_message_buffer;
+#[cfg(test)]
+mod test_message_metadata;
+#[cfg(test)]
+mod test_message_refs;
+#[cfg(test)]
+mod test_message_secrets_policy;
+#[cfg(test)]
+mod test_messages_from_bytes;
+#[cfg(test)]
+mod test_mls_client;
+#[cfg(test)]
+mod test_new_direct;
+#[cfg(test)]
+mod test_own_message_echo;
+#[cfg(test)]
+mod test_padding_policy;
+#[cfg(test)]
+mod test_pairwise_secrets;
+#[cfg(test)]
+mod test_past_epoch_retention;
+#[cfg(test)]
 mod test_past_secrets;
 #[cfg(test)]
+mod test_plaintext_commit_override;
+#[cfg(test)]
+mod test_process_message_errors;
+#[cfg(test)]
+mod test_processed_message_digest;
+#[cfg(test)]
+mod test_proposal_retraction;
+#[cfg(test)]
+mod test_proposal_store;
+#[cfg(test)]
 mod test_proposal_validation;
 #[cfg(test)]
+mod test_pseudonymous_key_packages;
+#[cfg(test)]
+mod test_psk_resolver;
+#[cfg(test)]
+mod test_rebase;
+#[cfg(all(test, feature = "reinit"))]
+mod test_reinit_proposal;
+#[cfg(all(test, feature = "external-commit"))]
+mod test_rejoin;
+#[cfg(test)]
+mod test_removal_grace;
+#[cfg(test)]
 mod test_remove_operation;
 #[cfg(test)]
+mod test_resumption_psks;
+#[cfg(all(test, feature = "secp256k1"))]
+mod test_secp256k1;
+#[cfg(test)]
+mod test_sequence_numbers;
+#[cfg(test)]
+mod test_sframe;
+#[cfg(test)]
+mod test_signature_cache;
+#[cfg(test)]
+mod test_speculation;
+#[cfg(test)]
+mod test_staged_commit_changes;
+#[cfg(test)]
+mod test_storage_faults;
+#[cfg(test)]
+mod test_streaming_encryption;
+#[cfg(test)]
+mod test_welcome_decline;
+#[cfg(test)]
+mod test_welcome_processing;
+#[cfg(test)]
 mod test_wire_format_policy;
+#[cfg(all(test, feature = "pq-kem"))]
+mod test_xwing_ciphersuite;
 #[cfg(test)]
 pub(crate) mod utils;
 
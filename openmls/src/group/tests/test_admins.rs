@@ -0,0 +1,256 @@
+//! This module tests the admins of a group and the commits and external
+//! commits that they authorize.
+
+use crate::{
+    framing::*,
+    group::{core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+#[cfg(feature = "external-commit")]
+#[apply(ciphersuites_and_backends)]
+fn admin_external_commits(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (dave_credential_with_key, _dave_kpb, dave_signer, _dave_pk) =
+        setup_client("Dave", ciphersuite, backend);
+    let alice_credential = alice_credential_with_key.credential.clone();
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group, makes herself the admin and adds Bob ===
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    alice_group
+        .set_admins(backend, &alice_signer, vec![alice_credential])
+        .expect("Could not set admins.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let (_msg, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+
+    // === Alice rejects the external commit of Dave, who isn't an admin ===
+    let verifiable_group_info = alice_group
+        .export_group_info(backend, &alice_signer, true)
+        .expect("Error exporting group info.")
+        .into_verifiable_group_info()
+        .expect("Unexpected message type.");
+    let (_dave_group, commit, _group_info) = MlsGroup::join_by_external_commit(
+        backend,
+        &dave_signer,
+        None,
+        verifiable_group_info,
+        &mls_group_config,
+        &[],
+        dave_credential_with_key,
+    )
+    .expect("Error joining from external commit.");
+    let err = alice_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect_err("Accepted an external commit of a non-admin.");
+    assert_eq!(
+        err,
+        ProcessMessageError::InvalidCommit(StageCommitError::ExternalCommitValidation(
+            ExternalCommitValidationError::JoinerNotAdmin
+        ))
+    );
+
+    // === Bob can still re-join, since the membership doesn't change ===
+    let (bob_new_credential_with_key, _bob_new_kpb, bob_new_signer, _bob_new_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let verifiable_group_info = alice_group
+        .export_group_info(backend, &alice_signer, true)
+        .expect("Error exporting group info.")
+        .into_verifiable_group_info()
+        .expect("Unexpected message type.");
+    let (pending_rejoin, commit, _group_info) = bob_group
+        .rejoin_via_external_commit(
+            backend,
+            &bob_new_signer,
+            None,
+            verifiable_group_info,
+            bob_new_credential_with_key,
+        )
+        .expect("Error re-joining the group.");
+    let processed_message = alice_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process messages.");
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    else {
+        panic!("Expected a StagedCommit.");
+    };
+    alice_group
+        .merge_staged_commit(backend, *staged_commit)
+        .expect("Error merging commit.");
+    bob_group
+        .complete_rejoin(backend, pending_rejoin)
+        .expect("Error completing the re-join.");
+    assert_eq!(alice_group.members().count(), 2);
+    assert_eq!(bob_group.epoch(), alice_group.epoch());
+}
+
+#[apply(ciphersuites_and_backends)]
+fn admins(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential_with_key, charlie_kpb, charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+    let alice_credential = alice_credential_with_key.credential.clone();
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group with Bob and Charlie ===
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_msg, welcome, _group_info) = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[
+                bob_kpb.key_package().clone(),
+                charlie_kpb.key_package().clone(),
+            ],
+        )
+        .expect("Could not add members.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let welcome = welcome.into_welcome().expect("Unexpected message type.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.clone(),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+    let mut charlie_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome,
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+    let charlie_index = charlie_group.own_leaf_index();
+    assert!(alice_group.admins().is_none());
+
+    // === Alice makes herself the admin ===
+    let (commit, _welcome, _group_info) = alice_group
+        .set_admins(backend, &alice_signer, vec![alice_credential.clone()])
+        .expect("Could not set admins.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    for group in [&mut bob_group, &mut charlie_group] {
+        let processed_message = group
+            .process_message(backend, commit.clone().into_protocol_message().unwrap())
+            .expect("Could not process message.");
+        let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+            processed_message.into_content()
+        else {
+            panic!("Expected a StagedCommit.");
+        };
+        group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging staged commit.");
+    }
+    assert_eq!(
+        bob_group.admins().map(|admins| admins.admins()),
+        Some(&[alice_credential][..])
+    );
+    assert!(bob_group.is_admin(alice_group.own_leaf_index()));
+    assert!(!bob_group.is_admin(bob_group.own_leaf_index()));
+
+    // === Bob can neither remove Charlie nor change the admins ===
+    let err = bob_group
+        .remove_members(backend, &bob_signer, &[charlie_index])
+        .expect_err("A non-admin removed a member.");
+    assert_eq!(
+        err,
+        RemoveMembersError::CreateCommitError(CreateCommitError::ProposalValidationError(
+            ProposalValidationError::SenderNotAdmin
+        ))
+    );
+    assert!(bob_group.set_admins(backend, &bob_signer, vec![]).is_err());
+
+    // === Alice rejects Bob's proposal to remove Charlie ===
+    let (proposal, _proposal_ref) = bob_group
+        .propose_remove_member(backend, &bob_signer, charlie_index)
+        .expect("Could not create proposal.");
+    let err = alice_group
+        .process_message(backend, proposal.into_protocol_message().unwrap())
+        .expect_err("Accepted a proposal of a non-admin.");
+    assert_eq!(
+        err,
+        ProcessMessageError::ValidationError(ValidationError::SenderNotAdmin)
+    );
+    bob_group.clear_pending_proposals();
+
+    // === Charlie can still leave the group ===
+    let proposal = charlie_group
+        .leave_group(backend, &charlie_signer)
+        .expect("Could not leave group.");
+    assert!(charlie_group.is_leaving());
+
+    // The pending remove proposal blocks application messages
+    assert_eq!(
+        charlie_group.create_message(backend, &charlie_signer, b"bye"),
+        Err(CreateMessageError::GroupStateError(
+            MlsGroupStateError::PendingProposal
+        ))
+    );
+    for group in [&mut alice_group, &mut bob_group] {
+        let processed_message = group
+            .process_message(backend, proposal.clone().into_protocol_message().unwrap())
+            .expect("Could not process proposal.");
+        let ProcessedMessageContent::ProposalMessage(proposal) = processed_message.into_content()
+        else {
+            panic!("Expected a proposal.");
+        };
+        group
+            .store_pending_proposal(backend, *proposal)
+            .expect("Could not store proposal.");
+    }
+    let (commit, _welcome, _group_info) = alice_group
+        .commit_to_pending_proposals(backend, &alice_signer)
+        .expect("Could not commit to proposals.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    assert_eq!(alice_group.members().count(), 2);
+
+    // === Bob accepts the removal committed by Alice ===
+    let processed_message = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    assert!(matches!(
+        processed_message.into_content(),
+        ProcessedMessageContent::StagedCommitMessage(_)
+    ));
+}
@@ -0,0 +1,89 @@
+//! This module tests the hash of the application state that members agree on
+//! in the group context.
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{framing::*, group::*, test_utils::*};
+
+#[apply(ciphersuites_and_backends)]
+fn app_state_hash(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        mut bob_group,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+
+    // === Alice registers the hash of her application state ===
+    let (commit, _welcome, _group_info) = alice_group
+        .commit_builder()
+        .app_state_hash(b"app state 1")
+        .finalize(backend, &alice_signer)
+        .expect("Error creating commit");
+    assert_eq!(
+        alice_group
+            .pending_commit()
+            .expect("No pending commit")
+            .compare_app_state(b"app state 1"),
+        AppStateComparison::Match
+    );
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let processed_message = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process messages.");
+    let staged_commit = match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => staged_commit,
+        _ => unreachable!("Expected a StagedCommit."),
+    };
+    assert_eq!(staged_commit.app_state_hash(), Some(&b"app state 1"[..]));
+    assert_eq!(
+        staged_commit.compare_app_state(b"app state 1"),
+        AppStateComparison::Match
+    );
+    assert_eq!(
+        staged_commit.compare_app_state(b"app state 2"),
+        AppStateComparison::Mismatch
+    );
+    bob_group
+        .merge_staged_commit(backend, *staged_commit)
+        .expect("Error merging commit.");
+
+    for group in [&alice_group, &bob_group] {
+        let extension = group
+            .export_group_context()
+            .extensions()
+            .app_state_hash()
+            .expect("No app state hash extension");
+        assert_eq!(extension.as_slice(), b"app state 1");
+    }
+
+    // === A commit without a hash keeps the previous one ===
+    let (commit, _welcome, _group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Error creating self update.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let processed_message = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process messages.");
+    let staged_commit = match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => staged_commit,
+        _ => unreachable!("Expected a StagedCommit."),
+    };
+    assert_eq!(
+        staged_commit.compare_app_state(b"app state 1"),
+        AppStateComparison::NotIncluded
+    );
+    assert!(staged_commit
+        .group_context()
+        .extensions()
+        .app_state_hash()
+        .is_some());
+}
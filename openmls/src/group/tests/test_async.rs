@@ -0,0 +1,226 @@
+//! This module tests the async variants of the `MlsGroup` operations and
+//! remote signers.
+
+use std::{
+    future::Future,
+    task::{Context, Poll},
+};
+
+use openmls_basic_credential::SignatureKeyPair;
+use openmls_traits::{signatures::AsyncSigner, types::SignatureScheme};
+
+use super::utils::{assert_send, block_on, AsyncBackend};
+use crate::{
+    framing::*,
+    group::{core_group::test_core_group::setup_client, *},
+    messages::proposals::*,
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn async_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let async_backend = AsyncBackend::new(backend);
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds Bob ===
+    let mut alice_group = block_on(MlsGroup::new_async(
+        &async_backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    ))
+    .expect("An unexpected error occurred.");
+
+    let (_msg, welcome, _group_info) = block_on(alice_group.add_members_async(
+        &async_backend,
+        &alice_signer,
+        &[bob_kpb.key_package().clone()],
+    ))
+    .expect("Could not add member.");
+    block_on(alice_group.merge_pending_commit_async(&async_backend))
+        .expect("error merging pending commit");
+
+    let mut bob_group = block_on(MlsGroup::new_from_welcome_async(
+        &async_backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    ))
+    .expect("error creating group from welcome");
+
+    // === Bob updates and Alice processes the commit ===
+    let (commit, _welcome, _group_info) =
+        block_on(bob_group.self_update_async(&async_backend, &bob_signer))
+            .expect("Error creating self-update.");
+
+    let processed_message = block_on(assert_send(
+        alice_group.process_message_async(&async_backend, commit.into_protocol_message().unwrap()),
+    ))
+    .expect("Could not process message.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+            block_on(alice_group.merge_staged_commit_async(&async_backend, *staged_commit))
+                .expect("Error merging commit.")
+        }
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+    block_on(bob_group.merge_pending_commit_async(&async_backend))
+        .expect("error merging pending commit");
+
+    // === Alice sends a message to Bob in the new epoch ===
+    let message = alice_group
+        .create_message(backend, &alice_signer, b"Hello Bob")
+        .expect("Error creating application message.");
+    let processed_message = block_on(
+        bob_group.process_message_async(&async_backend, message.into_protocol_message().unwrap()),
+    )
+    .expect("Could not process message.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::ApplicationMessage(application_message) => {
+            assert_eq!(application_message.into_bytes(), b"Hello Bob")
+        }
+        _ => unreachable!("Expected an application message."),
+    }
+
+    // === The key store contains the state written by the last async operation ===
+    let loaded_group =
+        MlsGroup::load(bob_group.group_id(), backend).expect("Could not load group.");
+    assert_eq!(loaded_group.epoch(), alice_group.epoch());
+    assert_eq!(loaded_group.own_leaf_index(), bob_group.own_leaf_index());
+}
+
+/// Completes after being polled twice, waking the task in between.
+struct YieldOnce(bool);
+
+impl Future for YieldOnce {
+    type Output = ();
+
+    fn poll(mut self: std::pin::Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+/// An [`AsyncSigner`] that suspends before signing, like a signer that
+/// delegates signing to a remote service.
+struct RemoteSigner<'a>(&'a SignatureKeyPair);
+
+impl AsyncSigner for RemoteSigner<'_> {
+    fn sign(
+        &self,
+        payload: &[u8],
+    ) -> impl Future<Output = Result<Vec<u8>, openmls_traits::types::Error>> + Send {
+        async move {
+            YieldOnce(false).await;
+            openmls_traits::signatures::Signer::sign(self.0, payload)
+        }
+    }
+
+    fn signature_scheme(&self) -> SignatureScheme {
+        openmls_traits::signatures::Signer::signature_scheme(self.0)
+    }
+}
+
+#[apply(ciphersuites_and_backends)]
+fn async_signer(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let async_backend = AsyncBackend::new(backend);
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let alice_remote_signer = RemoteSigner(&alice_signer);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds Bob ===
+    let mut alice_group = block_on(MlsGroup::new_async(
+        &async_backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    ))
+    .expect("An unexpected error occurred.");
+
+    let (_msg, welcome, _group_info) = block_on(assert_send(alice_group.add_members_async(
+        &async_backend,
+        &alice_remote_signer,
+        &[bob_kpb.key_package().clone()],
+    )))
+    .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+
+    // === Alice proposes an update and commits to it ===
+    let (proposal, _proposal_ref) = block_on(alice_group.propose_async(
+        &async_backend,
+        &alice_remote_signer,
+        Propose::Update(None),
+        ProposalOrRefType::Reference,
+    ))
+    .expect("Could not create proposal.");
+    let processed_message = bob_group
+        .process_message(backend, proposal.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::ProposalMessage(proposal) => bob_group
+            .store_pending_proposal(backend, *proposal)
+            .expect("Could not store proposal."),
+        _ => unreachable!("Expected a proposal."),
+    }
+
+    let (commit, _welcome, _group_info) = block_on(
+        alice_group.commit_to_pending_proposals_async(&async_backend, &alice_remote_signer),
+    )
+    .expect("Could not commit to pending proposals.");
+    let processed_message = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => bob_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging commit."),
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    // === Alice sends a message to Bob in the new epoch ===
+    let message = block_on(alice_group.create_message_async(
+        &async_backend,
+        &alice_remote_signer,
+        b"Hello Bob",
+    ))
+    .expect("Error creating application message.");
+    let processed_message = bob_group
+        .process_message(backend, message.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::ApplicationMessage(application_message) => {
+            assert_eq!(application_message.into_bytes(), b"Hello Bob")
+        }
+        _ => unreachable!("Expected an application message."),
+    }
+    assert_eq!(bob_group.epoch(), alice_group.epoch());
+}
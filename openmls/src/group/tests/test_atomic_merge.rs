@@ -0,0 +1,233 @@
+//! This module tests that merging a commit writes the group state and the
+//! key material of the new epoch atomically.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use openmls_traits::{
+    key_store::{BatchOperation, MlsEntity, MlsEntityId, OpenMlsKeyStore, WriteBatch},
+    storage::{StorageKey, StorageProvider},
+};
+
+use super::utils::{block_on, AsyncBackend};
+use crate::{
+    group::{core_group::test_core_group::setup_client, *},
+    test_utils::*,
+    treesync::node::encryption_keys::EncryptionKeyPair,
+};
+
+/// Error of the [`FailingKeyStore`].
+#[derive(thiserror::Error, Debug, PartialEq)]
+enum FailingKeyStoreError<KeyStoreError> {
+    #[error("Writing the group state failed.")]
+    GroupStateWriteFailed,
+    #[error(transparent)]
+    KeyStoreError(KeyStoreError),
+}
+
+/// Wraps a key store and fails to write group states while `fail` is set.
+struct FailingKeyStore<'a, KeyStore: OpenMlsKeyStore> {
+    key_store: &'a KeyStore,
+    fail: AtomicBool,
+}
+
+impl<'a, KeyStore: OpenMlsKeyStore> OpenMlsKeyStore for FailingKeyStore<'a, KeyStore> {
+    type Error = FailingKeyStoreError<KeyStore::Error>;
+
+    fn store<V: MlsEntity>(&self, k: &[u8], v: &V) -> Result<(), Self::Error> {
+        let value = self.encode(v)?;
+        self.store_encoded(V::ID, k, &value)
+    }
+
+    fn read<V: MlsEntity>(&self, k: &[u8]) -> Option<V> {
+        self.key_store.read(k)
+    }
+
+    fn delete<V: MlsEntity>(&self, k: &[u8]) -> Result<(), Self::Error> {
+        self.key_store
+            .delete::<V>(k)
+            .map_err(FailingKeyStoreError::KeyStoreError)
+    }
+
+    fn encode<V: MlsEntity>(&self, v: &V) -> Result<Vec<u8>, Self::Error> {
+        self.key_store
+            .encode(v)
+            .map_err(FailingKeyStoreError::KeyStoreError)
+    }
+
+    fn decode<V: MlsEntity>(&self, value: &[u8]) -> Option<V> {
+        self.key_store.decode(value)
+    }
+
+    fn store_encoded(
+        &self,
+        entity_id: MlsEntityId,
+        k: &[u8],
+        value: &[u8],
+    ) -> Result<(), Self::Error> {
+        if entity_id == MlsEntityId::GroupState && self.fail.load(Ordering::SeqCst) {
+            return Err(FailingKeyStoreError::GroupStateWriteFailed);
+        }
+        self.key_store
+            .store_encoded(entity_id, k, value)
+            .map_err(FailingKeyStoreError::KeyStoreError)
+    }
+
+    fn apply_batch(&self, batch: WriteBatch) -> Result<(), Self::Error> {
+        let writes_group_state = batch.operations().iter().any(|operation| {
+            matches!(operation, BatchOperation::Store { .. })
+                && operation.entity_id() == MlsEntityId::GroupState
+        });
+        if writes_group_state && self.fail.load(Ordering::SeqCst) {
+            return Err(FailingKeyStoreError::GroupStateWriteFailed);
+        }
+        self.key_store
+            .apply_batch(batch)
+            .map_err(FailingKeyStoreError::KeyStoreError)
+    }
+}
+
+/// A backend with a [`FailingKeyStore`] in front of its key store.
+struct FailingBackend<'a, Backend: OpenMlsCryptoProvider> {
+    backend: &'a Backend,
+    key_store: FailingKeyStore<'a, Backend::KeyStoreProvider>,
+}
+
+impl<'a, Backend: OpenMlsCryptoProvider> FailingBackend<'a, Backend> {
+    fn new(backend: &'a Backend) -> Self {
+        Self {
+            backend,
+            key_store: FailingKeyStore {
+                key_store: backend.key_store(),
+                fail: AtomicBool::new(false),
+            },
+        }
+    }
+
+    fn fail(&self, fail: bool) {
+        self.key_store.fail.store(fail, Ordering::SeqCst)
+    }
+}
+
+impl<'a, Backend: OpenMlsCryptoProvider> OpenMlsCryptoProvider for FailingBackend<'a, Backend> {
+    type CryptoProvider = Backend::CryptoProvider;
+    type RandProvider = Backend::RandProvider;
+    type KeyStoreProvider = FailingKeyStore<'a, Backend::KeyStoreProvider>;
+
+    fn crypto(&self) -> &Self::CryptoProvider {
+        self.backend.crypto()
+    }
+
+    fn rand(&self) -> &Self::RandProvider {
+        self.backend.rand()
+    }
+
+    fn key_store(&self) -> &Self::KeyStoreProvider {
+        &self.key_store
+    }
+}
+
+#[apply(ciphersuites_and_backends)]
+fn atomic_merge(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let backend = FailingBackend::new(backend);
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, &backend);
+
+    let mut alice_group = MlsGroup::new(
+        &backend,
+        &alice_signer,
+        &MlsGroupConfig::test_default(ciphersuite),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let group_id = alice_group.group_id().clone();
+    let epoch_keypairs_key = |epoch: u64| StorageKey::EpochKeyPairs {
+        group_id: group_id.as_slice(),
+        epoch,
+        leaf_index: 0,
+    };
+
+    alice_group
+        .self_update(&backend, &alice_signer)
+        .expect("Error creating self update.");
+
+    // === Writing the group state fails while merging ===
+    backend.fail(true);
+    let err = alice_group
+        .merge_pending_commit(&backend)
+        .expect_err("Merged the commit without writing the group state.");
+    assert_eq!(
+        err,
+        MergePendingCommitError::MergeCommitError(MergeCommitError::StorageError(
+            FailingKeyStoreError::GroupStateWriteFailed
+        ))
+    );
+
+    // The key store still contains the key pairs of the previous epoch and
+    // none of the new epoch
+    let key_store = backend.key_store();
+    assert!(key_store
+        .load::<Vec<EncryptionKeyPair>>(epoch_keypairs_key(0))
+        .is_some());
+    assert!(key_store
+        .load::<Vec<EncryptionKeyPair>>(epoch_keypairs_key(1))
+        .is_none());
+
+    // The group is restored to the state from before the merge
+    assert_eq!(alice_group.epoch(), GroupEpoch::from(0));
+    assert!(alice_group.pending_commit().is_some());
+
+    // === The group can still merge the commit ===
+    backend.fail(false);
+    alice_group
+        .merge_pending_commit(&backend)
+        .expect("error merging pending commit");
+    assert_eq!(alice_group.epoch(), GroupEpoch::from(1));
+    assert!(key_store
+        .load::<Vec<EncryptionKeyPair>>(epoch_keypairs_key(0))
+        .is_none());
+    assert!(key_store
+        .load::<Vec<EncryptionKeyPair>>(epoch_keypairs_key(1))
+        .is_some());
+}
+
+#[apply(ciphersuites_and_backends)]
+fn async_atomic_merge(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let failing_backend = FailingBackend::new(backend);
+    let async_backend = AsyncBackend::new(&failing_backend);
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+
+    let mut alice_group = block_on(MlsGroup::new_async(
+        &async_backend,
+        &alice_signer,
+        &MlsGroupConfig::test_default(ciphersuite),
+        alice_credential_with_key,
+    ))
+    .expect("An unexpected error occurred.");
+    block_on(alice_group.self_update_async(&async_backend, &alice_signer))
+        .expect("Error creating self update.");
+
+    // === Writing the group state fails while merging ===
+    failing_backend.fail(true);
+    let err = block_on(alice_group.merge_pending_commit_async(&async_backend))
+        .expect_err("Merged the commit without writing the group state.");
+    assert_eq!(
+        err,
+        AsyncOperationError::KeyStoreError(FailingKeyStoreError::GroupStateWriteFailed)
+    );
+
+    // The group is left untouched
+    assert_eq!(alice_group.epoch(), GroupEpoch::from(0));
+    assert!(alice_group.pending_commit().is_some());
+
+    // === The group can still merge the commit ===
+    failing_backend.fail(false);
+    block_on(alice_group.merge_pending_commit_async(&async_backend))
+        .expect("error merging pending commit");
+    assert_eq!(alice_group.epoch(), GroupEpoch::from(1));
+    let loaded_group =
+        MlsGroup::load(alice_group.group_id(), backend).expect("Could not load group.");
+    assert_eq!(loaded_group.epoch(), GroupEpoch::from(1));
+}
@@ -0,0 +1,143 @@
+//! This module tests the tags that members attach to messages for auditors.
+
+use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait};
+
+use crate::{
+    framing::*,
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn auditor_tags(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    // Auditor tags are disabled by default
+    let mut untagged_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &MlsGroupConfig::test_default(ciphersuite),
+        alice_credential_with_key.clone(),
+    )
+    .expect("An unexpected error occurred.");
+    assert_eq!(
+        untagged_group.auditor_key(backend),
+        Err(AuditorTagError::Disabled)
+    );
+    let (commit, _welcome, _group_info) = untagged_group
+        .self_update(backend, &alice_signer)
+        .expect("Error creating self update.");
+    assert_eq!(
+        untagged_group
+            .tag_for_auditor(backend, &commit)
+            .expect_err("Tagged a message without auditor tags."),
+        AuditorTagError::Disabled
+    );
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .wire_format_policy(MIXED_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_auditor_tags(true)
+        .build();
+
+    // === Alice creates a group and adds Bob ===
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let (_msg, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+
+    // Both members derive the same key
+    let auditor_key = alice_group
+        .auditor_key(backend)
+        .expect("Error deriving auditor key.");
+    assert_eq!(
+        bob_group
+            .auditor_key(backend)
+            .expect("Error deriving auditor key."),
+        auditor_key
+    );
+    assert_eq!(auditor_key.epoch(), alice_group.epoch());
+
+    // === Bob commits and tags the commit for the auditor ===
+    let (commit, _welcome, _group_info) = bob_group
+        .self_update(backend, &bob_signer)
+        .expect("Error creating self update.");
+    let tagged_message = bob_group
+        .tag_for_auditor(backend, &commit)
+        .expect("Error tagging message.");
+
+    // The auditor verifies the tag
+    auditor_key
+        .verify(backend, &tagged_message)
+        .expect("Invalid auditor tag.");
+
+    // A modified tag doesn't verify
+    let mut tampered_bytes = tagged_message
+        .tls_serialize_detached()
+        .expect("Error serializing message.");
+    let last = tampered_bytes.len() - 1;
+    tampered_bytes[last] ^= 0xff;
+    let tampered_message = AuditorTaggedMessage::tls_deserialize_exact(&tampered_bytes)
+        .expect("Error deserializing message.");
+    assert_eq!(
+        auditor_key.verify(backend, &tampered_message),
+        Err(AuditorTagError::InvalidTag)
+    );
+
+    // Alice processes the message the auditor has seen
+    let processed_message = alice_group
+        .process_message(
+            backend,
+            tagged_message
+                .clone()
+                .into_message()
+                .expect("Error deserializing message.")
+                .into_protocol_message()
+                .expect("Unexpected message type."),
+        )
+        .expect("Could not process message.");
+    let staged_commit = match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => staged_commit,
+        _ => unreachable!("Expected a staged commit."),
+    };
+    alice_group
+        .merge_staged_commit(backend, *staged_commit)
+        .expect("Error merging staged commit.");
+    bob_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    // The key is bound to the epoch
+    let next_auditor_key = alice_group
+        .auditor_key(backend)
+        .expect("Error deriving auditor key.");
+    assert_ne!(next_auditor_key, auditor_key);
+    assert_eq!(
+        next_auditor_key.verify(backend, &tagged_message),
+        Err(AuditorTagError::WrongEpoch)
+    );
+}
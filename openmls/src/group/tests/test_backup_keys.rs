@@ -0,0 +1,77 @@
+//! This module tests the ratchet of backup keys that members derive from the
+//! group.
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{group::*, test_utils::*};
+
+#[apply(ciphersuites_and_backends)]
+fn backup_keys(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        bob_group,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+
+    // === Alice creates two backups ===
+    let mut ratchet = alice_group
+        .backup_key_ratchet(backend)
+        .expect("error creating backup key ratchet");
+    assert_eq!(ratchet.epoch(), alice_group.epoch());
+    let first_key = ratchet
+        .next_key(backend)
+        .expect("error deriving backup key");
+    let second_key = ratchet
+        .next_key(backend)
+        .expect("error deriving backup key");
+    assert_eq!(first_key.generation(), 0);
+    assert_eq!(second_key.generation(), 1);
+    assert_eq!(ratchet.generation(), 2);
+    assert_eq!(first_key.key().len(), ciphersuite.aead_key_length());
+    assert_eq!(first_key.nonce().len(), ciphersuite.aead_nonce_length());
+    assert_ne!(first_key.key(), second_key.key());
+    assert_ne!(first_key.nonce(), second_key.nonce());
+
+    // The ratchet can be stored and continues with the next generation
+    let serialized = serde_json::to_vec(&ratchet).expect("error serializing ratchet");
+    let mut restored_ratchet: BackupKeyRatchet =
+        serde_json::from_slice(&serialized).expect("error deserializing ratchet");
+    assert_eq!(
+        restored_ratchet.next_key(backend),
+        ratchet.next_key(backend)
+    );
+
+    // === Bob restores the backups ===
+    assert_eq!(bob_group.backup_key(backend, 0), Ok(first_key.clone()));
+    let mut bob_ratchet = bob_group
+        .backup_key_ratchet(backend)
+        .expect("error creating backup key ratchet");
+    assert_eq!(
+        bob_ratchet.key_for_generation(backend, 1),
+        Ok(second_key.clone())
+    );
+    assert_eq!(
+        bob_ratchet.key_for_generation(backend, 0),
+        Err(BackupKeyError::GenerationInThePast)
+    );
+    assert_eq!(
+        bob_ratchet.key_for_generation(backend, 2000),
+        Err(BackupKeyError::TooDistantInTheFuture)
+    );
+
+    // === The keys change with the epoch ===
+    alice_group
+        .self_update(backend, &alice_signer)
+        .expect("error creating self-update commit");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let new_key = alice_group
+        .backup_key(backend, 0)
+        .expect("error deriving backup key");
+    assert_eq!(new_key.epoch(), alice_group.epoch());
+    assert_ne!(new_key.key(), first_key.key());
+}
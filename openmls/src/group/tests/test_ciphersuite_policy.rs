@@ -0,0 +1,106 @@
+//! This module tests the policy of ciphersuites that a member accepts.
+
+use crate::{
+    ciphersuite::policy::{CiphersuitePolicy, CiphersuitePolicyError},
+    group::{
+        config::CryptoConfig, core_group::test_core_group::setup_client,
+        public_group::errors::CreationFromExternalError, *,
+    },
+    key_packages::{errors::KeyPackageNewError, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn ciphersuite_policy(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (bob_credential_with_key, _bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    // The policy permits no ciphersuite at all.
+    let forbidding_policy = CiphersuitePolicy::default().with_allowed_ciphersuites(vec![]);
+    let policy_error = CiphersuitePolicyError::NotAllowed(ciphersuite);
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .build();
+    let forbidding_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .ciphersuite_policy(forbidding_policy.clone())
+        .build();
+
+    // Creating groups and key packages fails.
+    let err = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &forbidding_config,
+        alice_credential_with_key.clone(),
+    )
+    .expect_err("Group created with a forbidden ciphersuite.");
+    assert_eq!(err, NewGroupError::CiphersuitePolicy(policy_error.clone()));
+
+    let err = KeyPackage::builder()
+        .ciphersuite_policy(forbidding_policy.clone())
+        .build(
+            CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &bob_signer,
+            bob_credential_with_key.clone(),
+        )
+        .expect_err("Key package created with a forbidden ciphersuite.");
+    assert_eq!(
+        err,
+        KeyPackageNewError::CiphersuitePolicy(policy_error.clone())
+    );
+
+    // Joining a group from a Welcome fails, but leaves the key package
+    // untouched.
+    let bob_key_package = KeyPackage::builder()
+        .build(
+            CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &bob_signer,
+            bob_credential_with_key,
+        )
+        .expect("Error creating key package.");
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("Error creating group.");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_key_package])
+        .expect("Error adding Bob.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Error merging pending commit.");
+    let welcome = welcome.into_welcome().expect("Unexpected message type.");
+
+    let err = MlsGroup::new_from_welcome(backend, &forbidding_config, welcome.clone(), None)
+        .expect_err("Joined a group with a forbidden ciphersuite.");
+    assert_eq!(err, WelcomeError::CiphersuitePolicy(policy_error.clone()));
+    MlsGroup::new_from_welcome(backend, &mls_group_config, welcome, None)
+        .expect("Error joining group.");
+
+    // Tracking the group fails as well.
+    let verifiable_group_info = alice_group
+        .export_group_info(backend, &alice_signer, false)
+        .expect("Error exporting group info.")
+        .into_verifiable_group_info()
+        .expect("Unexpected message type.");
+    let err = PublicGroup::from_external_with_policy(
+        backend,
+        alice_group.export_ratchet_tree().into(),
+        verifiable_group_info,
+        ProposalStore::new(),
+        &forbidding_policy,
+    )
+    .expect_err("Tracked a group with a forbidden ciphersuite.");
+    assert_eq!(
+        err,
+        CreationFromExternalError::CiphersuitePolicy(policy_error)
+    );
+}
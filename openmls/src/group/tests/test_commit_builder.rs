@@ -0,0 +1,91 @@
+//! This module tests building commits with the `CommitBuilder`.
+
+use crate::{
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn commit_builder(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    // Define the MlsGroup configuration
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+
+    // === Alice creates a group ===
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    // Alice proposes to add Bob and Charlie
+    let (_, bob_reference) = alice_group
+        .propose_add_member(backend, &alice_signer, bob_kpb.key_package())
+        .unwrap();
+    let (_, charlie_reference) = alice_group
+        .propose_add_member(backend, &alice_signer, charlie_kpb.key_package())
+        .unwrap();
+    assert_eq!(alice_group.pending_proposals().count(), 2);
+
+    // Alice only commits to adding Bob and asks for a group info, even
+    // though the group doesn't use the ratchet tree extension.
+    let (_commit, welcome, group_info) = alice_group
+        .commit_builder()
+        .include_proposals(vec![bob_reference])
+        .aad(b"commit aad")
+        .create_group_info(true)
+        .finalize(backend, &alice_signer)
+        .expect("Error creating commit");
+    assert!(welcome.is_some());
+    assert!(group_info.is_some());
+    // The group's AAD is left untouched.
+    assert!(alice_group.aad().is_empty());
+
+    let pending_commit = alice_group.pending_commit().expect("No pending commit");
+    assert_eq!(pending_commit.add_proposals().count(), 1);
+
+    // Creating another commit while one is pending fails.
+    let err = alice_group
+        .commit_builder()
+        .finalize(backend, &alice_signer)
+        .expect_err("Created a commit while another one was pending");
+    assert_eq!(
+        err,
+        CommitBuilderError::GroupStateError(MlsGroupStateError::PendingCommit)
+    );
+
+    alice_group.merge_pending_commit(backend).unwrap();
+    assert_eq!(alice_group.members().count(), 2);
+
+    // The proposal store was emptied when merging, so Charlie's proposal
+    // can't be committed anymore.
+    let err = alice_group
+        .commit_builder()
+        .include_proposals(vec![charlie_reference.clone()])
+        .finalize(backend, &alice_signer)
+        .expect_err("Committed to a proposal that isn't in the store");
+    assert_eq!(err, CommitBuilderError::ProposalNotFound(charlie_reference));
+
+    // Committing without any proposals and without forcing a path yields an
+    // empty commit without a group info.
+    let (_commit, welcome, group_info) = alice_group
+        .commit_builder()
+        .force_self_update(false)
+        .finalize(backend, &alice_signer)
+        .expect("Error creating commit");
+    assert!(welcome.is_none());
+    assert!(group_info.is_none());
+}
@@ -0,0 +1,94 @@
+//! This module tests committing to a subset of the pending proposals.
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{
+    framing::*,
+    group::{core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn commit_to_proposals(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+    let (_dave_credential_with_key, dave_kpb, _dave_signer, _dave_pk) =
+        setup_client("Dave", ciphersuite, backend);
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        mut bob_group,
+        bob_signer,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+
+    // === Bob proposes to add Charlie and Dave, Alice to add Charlie ===
+    let (charlie_add, charlie_ref) = bob_group
+        .propose_add_member(backend, &bob_signer, charlie_kpb.key_package())
+        .expect("Error creating proposal.");
+    let (dave_add, dave_ref) = bob_group
+        .propose_add_member(backend, &bob_signer, dave_kpb.key_package())
+        .expect("Error creating proposal.");
+    for proposal in [charlie_add, dave_add] {
+        let processed_message = alice_group
+            .process_message(backend, proposal.into_protocol_message().unwrap())
+            .expect("Could not process proposal.");
+        match processed_message.into_content() {
+            ProcessedMessageContent::ProposalMessage(proposal) => alice_group
+                .store_pending_proposal(backend, *proposal)
+                .expect("Could not store proposal."),
+            _ => unreachable!("Expected a proposal."),
+        }
+    }
+    let (_proposal, alice_charlie_ref) = alice_group
+        .propose_add_member(backend, &alice_signer, charlie_kpb.key_package())
+        .expect("Error creating proposal.");
+
+    // === Unknown and conflicting proposals are rejected ===
+    let (_proposal, unknown_ref) = bob_group
+        .propose_self_update(backend, &bob_signer, None)
+        .expect("Error creating proposal.");
+    let err = alice_group
+        .commit_to_proposals(backend, &alice_signer, &[unknown_ref.clone()])
+        .expect_err("Committed to an unknown proposal.");
+    assert_eq!(err, CommitBuilderError::ProposalNotFound(unknown_ref));
+    let err = alice_group
+        .commit_to_proposals(
+            backend,
+            &alice_signer,
+            &[alice_charlie_ref.clone(), charlie_ref.clone()],
+        )
+        .expect_err("Committed to conflicting proposals.");
+    assert_eq!(
+        err,
+        CommitBuilderError::ConflictingProposals(ProposalConflict::DuplicateAdd(
+            alice_charlie_ref,
+            charlie_ref.clone()
+        ))
+    );
+
+    // === Alice commits to Bob's proposals only ===
+    let (commit, welcome, _group_info) = alice_group
+        .commit_to_proposals(backend, &alice_signer, &[charlie_ref, dave_ref])
+        .expect("Error committing to proposals.");
+    assert!(welcome.is_some());
+    let processed_message = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process commit.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+            assert_eq!(staged_commit.add_proposals().count(), 2);
+            bob_group
+                .merge_staged_commit(backend, *staged_commit)
+                .expect("Error merging commit.")
+        }
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    assert_eq!(alice_group.members().count(), 4);
+    assert_eq!(bob_group.members().count(), 4);
+}
@@ -329,9 +329,11 @@ fn test_valsem201(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
 
     for (proposal, is_path_required) in cases {
         // create a commit containing the proposals
-        proposal
-            .into_iter()
-            .for_each(|p| alice_group.store_pending_proposal(p));
+        proposal.into_iter().for_each(|p| {
+            alice_group
+                .store_pending_proposal(backend, p)
+                .expect("Could not store proposal.")
+        });
 
         let params = CreateCommitParams::builder()
             .framing_parameters(alice_group.framing_parameters())
@@ -382,8 +384,12 @@ fn test_valsem201(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
 
         // cleanup & restore for next iteration
         alice_group.clear_pending_proposals();
-        alice_group.clear_pending_commit();
-        bob_group.clear_pending_commit();
+        alice_group
+            .clear_pending_commit(backend)
+            .expect("Could not clear pending commit.");
+        bob_group
+            .clear_pending_commit(backend)
+            .expect("Could not clear pending commit.");
     }
 }
 
@@ -769,7 +775,9 @@ fn test_partial_proposal_commit(ciphersuite: Ciphersuite, backend: &impl OpenMls
         .unwrap();
     let proposal_1 = bob_group.process_message(backend, proposal_1).unwrap();
     match proposal_1.into_content() {
-        ProcessedMessageContent::ProposalMessage(p) => bob_group.store_pending_proposal(*p),
+        ProcessedMessageContent::ProposalMessage(p) => bob_group
+            .store_pending_proposal(backend, *p)
+            .expect("Could not store proposal."),
         _ => unreachable!(),
     }
 
@@ -780,7 +788,9 @@ fn test_partial_proposal_commit(ciphersuite: Ciphersuite, backend: &impl OpenMls
         .unwrap();
     let proposal_2 = bob_group.process_message(backend, proposal_2).unwrap();
     match proposal_2.into_content() {
-        ProcessedMessageContent::ProposalMessage(p) => bob_group.store_pending_proposal(*p),
+        ProcessedMessageContent::ProposalMessage(p) => bob_group
+            .store_pending_proposal(backend, *p)
+            .expect("Could not store proposal."),
         _ => unreachable!(),
     }
 
@@ -0,0 +1,107 @@
+//! This module tests the application callback that validates commits before
+//! they are merged.
+
+use crate::{
+    binary_tree::LeafNodeIndex,
+    framing::*,
+    group::{core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+/// Rejects commits that remove the creator of the group, i.e. the member at
+/// leaf index 0.
+fn keep_admin(staged_commit: &StagedCommit, group: &PublicGroup) -> Result<(), CommitRejection> {
+    let admin = LeafNodeIndex::new(0);
+    if group.leaf(admin).is_some()
+        && staged_commit
+            .removed_members()
+            .iter()
+            .any(|member| member.leaf_index() == admin)
+    {
+        return Err(CommitRejection::new("the admin can't be removed"));
+    }
+    Ok(())
+}
+
+#[apply(ciphersuites_and_backends)]
+fn commit_validator(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential_with_key, charlie_kpb, charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group with Bob and Charlie ===
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_msg, welcome, _group_info) = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[
+                bob_kpb.key_package().clone(),
+                charlie_kpb.key_package().clone(),
+            ],
+        )
+        .expect("Could not add members.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let welcome = welcome.into_welcome().expect("Unexpected message type.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.clone(),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+    let mut charlie_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome,
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+    bob_group.set_commit_validator(keep_admin);
+
+    // === The validator rejects Charlie's removal of Alice ===
+    let (commit, _welcome, _group_info) = charlie_group
+        .remove_members(backend, &charlie_signer, &[alice_group.own_leaf_index()])
+        .expect("Could not remove Alice.");
+    let err = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect_err("Accepted a rejected commit.");
+    assert_eq!(
+        err,
+        ProcessMessageError::CommitRejected(CommitRejection::new("the admin can't be removed"))
+    );
+    assert_eq!(bob_group.members().count(), 3);
+
+    // === The validator accepts Alice's removal of Charlie ===
+    let (commit, _welcome, _group_info) = alice_group
+        .remove_members(backend, &alice_signer, &[charlie_group.own_leaf_index()])
+        .expect("Could not remove Charlie.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let processed_message = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    else {
+        panic!("Expected a StagedCommit.");
+    };
+    bob_group
+        .merge_staged_commit(backend, *staged_commit)
+        .expect("Error merging staged commit.");
+    assert_eq!(bob_group.members().count(), 2);
+}
@@ -0,0 +1,92 @@
+//! This module tests compressed ratchet trees in Welcomes and GroupInfos.
+
+use crate::{
+    extensions::*,
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    key_packages::*,
+    test_utils::*,
+    treesync::node::leaf_node::Capabilities,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn compressed_ratchet_tree(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (bob_credential_with_key, _bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    // Bob supports the compressed ratchet tree, Charlie doesn't
+    let bob_key_package = KeyPackage::builder()
+        .leaf_node_capabilities(Capabilities::new(
+            None,
+            Some(&[ciphersuite]),
+            Some(&[ExtensionType::CompressedRatchetTree]),
+            None,
+            None,
+        ))
+        .build(
+            CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &bob_signer,
+            bob_credential_with_key,
+        )
+        .expect("Error creating key package.");
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .wire_format_policy(MIXED_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .required_capabilities(RequiredCapabilitiesExtension::new(
+            &[ExtensionType::CompressedRatchetTree],
+            &[],
+            &[],
+        ))
+        .build();
+
+    // === Alice creates a group that requires the compressed ratchet tree ===
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    assert_eq!(
+        alice_group.ratchet_tree_extension_size(),
+        alice_group.compressed_ratchet_tree_extension_size()
+    );
+
+    // === Charlie can't be added ===
+    assert!(alice_group
+        .add_members(backend, &alice_signer, &[charlie_kpb.key_package().clone()])
+        .is_err());
+
+    // === Alice adds Bob, who gets the tree from the Welcome ===
+    let (_msg, welcome, group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_key_package])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let group_info = group_info.expect("Expected a group info.");
+    assert!(group_info.extensions().compressed_ratchet_tree().is_some());
+    assert!(group_info.extensions().ratchet_tree().is_none());
+
+    let bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    )
+    .expect("error creating group from welcome");
+    assert_eq!(
+        bob_group.export_ratchet_tree(),
+        alice_group.export_ratchet_tree()
+    );
+}
@@ -0,0 +1,196 @@
+//! This module tests decrypting messages of a group on multiple threads.
+
+use crate::{
+    framing::*,
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn concurrent_decryption(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential_with_key, charlie_kpb, charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .wire_format_policy(PURE_CIPHERTEXT_WIRE_FORMAT_POLICY)
+        .message_buffer_configuration(MessageBufferConfiguration::new(1, 1))
+        .build();
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_msg, welcome, _group_info) = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[
+                bob_kpb.key_package().clone(),
+                charlie_kpb.key_package().clone(),
+            ],
+        )
+        .expect("Could not add members.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let welcome = welcome.into_welcome().expect("Unexpected message type.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.clone(),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+    let mut charlie_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome,
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+
+    let bob_messages: Vec<MlsMessageOut> = (0..5)
+        .map(|i| {
+            bob_group
+                .create_message(backend, &bob_signer, format!("Bob {i}").as_bytes())
+                .expect("error creating message")
+        })
+        .collect();
+    let charlie_messages: Vec<MlsMessageOut> = (0..5)
+        .map(|i| {
+            charlie_group
+                .create_message(backend, &charlie_signer, format!("Charlie {i}").as_bytes())
+                .expect("error creating message")
+        })
+        .collect();
+    let replayed_message = bob_messages[0].clone();
+
+    // The messages of Bob and Charlie are decrypted by two threads through a
+    // shared reference to Alice's group
+    let alice_group = &alice_group;
+    let (bob_plaintexts, charlie_plaintexts) = std::thread::scope(|scope| {
+        let decrypt = |messages: Vec<MlsMessageOut>| {
+            scope.spawn(move || {
+                messages
+                    .into_iter()
+                    .map(|message| {
+                        let processed_message = alice_group
+                            .process_application_message(
+                                backend,
+                                message.into_protocol_message().unwrap(),
+                            )
+                            .expect("error processing application message");
+                        match processed_message.into_content() {
+                            ProcessedMessageContent::ApplicationMessage(application_message) => {
+                                application_message.into_bytes()
+                            }
+                            _ => panic!("Expected an application message."),
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+        };
+        let bob_thread = decrypt(bob_messages);
+        let charlie_thread = decrypt(charlie_messages);
+        (bob_thread.join().unwrap(), charlie_thread.join().unwrap())
+    });
+    assert_eq!(
+        bob_plaintexts,
+        (0..5)
+            .map(|i| format!("Bob {i}").into_bytes())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        charlie_plaintexts,
+        (0..5)
+            .map(|i| format!("Charlie {i}").into_bytes())
+            .collect::<Vec<_>>()
+    );
+
+    // The ratchets were written to the storage, so the keys can't be reused
+    alice_group
+        .process_application_message(
+            backend,
+            replayed_message.clone().into_protocol_message().unwrap(),
+        )
+        .expect_err("A message was decrypted twice.");
+    let loaded_group =
+        MlsGroup::load(alice_group.group_id(), backend).expect("Could not deserialize MlsGroup");
+    loaded_group
+        .process_application_message(backend, replayed_message.into_protocol_message().unwrap())
+        .expect_err("A message was decrypted twice after loading the group.");
+
+    // Sequence numbers are checked like with exclusive access
+    let message = bob_group
+        .create_message(backend, &bob_signer, b"Bob 5")
+        .expect("error creating message")
+        .into_protocol_message()
+        .unwrap();
+    assert_eq!(
+        charlie_group
+            .process_application_message_with_sequence_number(backend, message.clone(), 1)
+            .expect_err("A message was processed out of order."),
+        ProcessMessageError::OutOfOrder {
+            expected: 0,
+            got: 1
+        }
+    );
+    charlie_group
+        .process_application_message_with_sequence_number(backend, message, 0)
+        .expect("error processing application message");
+    assert_eq!(charlie_group.next_sequence_number(), 1);
+
+    // Handshake messages need exclusive access to the group
+    let (commit, _welcome, _group_info) = bob_group
+        .self_update(backend, &bob_signer)
+        .expect("error creating self update");
+    let commit = commit.into_protocol_message().unwrap();
+    assert_eq!(
+        alice_group
+            .process_application_message(backend, commit.clone())
+            .expect_err("A handshake message was processed through a shared reference."),
+        ProcessMessageError::NotAnApplicationMessage
+    );
+    assert_eq!(
+        charlie_group
+            .process_application_message_with_sequence_number(backend, commit.clone(), 1)
+            .expect_err("A handshake message was processed through a shared reference."),
+        ProcessMessageError::NotAnApplicationMessage
+    );
+    assert_eq!(charlie_group.next_sequence_number(), 1);
+
+    // Messages of the next epoch are buffered until the commit is merged
+    bob_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let message = bob_group
+        .create_message(backend, &bob_signer, b"Bob 6")
+        .expect("error creating message");
+    assert_eq!(
+        charlie_group
+            .process_application_message(backend, message.into_protocol_message().unwrap())
+            .expect_err("A message of a future epoch was processed."),
+        ProcessMessageError::MessageBuffered
+    );
+    assert_eq!(charlie_group.buffered_messages().len(), 1);
+    let processed_message = charlie_group
+        .process_message(backend, commit)
+        .expect("error processing commit");
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    else {
+        panic!("Expected a StagedCommit.");
+    };
+    charlie_group
+        .merge_staged_commit(backend, *staged_commit)
+        .expect("error merging commit");
+    let processed_messages = charlie_group.take_processed_buffered_messages();
+    assert_eq!(processed_messages.len(), 1);
+}
@@ -0,0 +1,150 @@
+//! This module tests updating the credential and signature key of a member.
+
+use openmls_basic_credential::SignatureKeyPair;
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{
+    binary_tree::LeafNodeIndex,
+    credentials::{Credential, CredentialType, CredentialWithKey},
+    framing::*,
+    group::*,
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn credential_update(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        alice_credential_with_key,
+        mut bob_group,
+        bob_signer,
+        bob_credential_with_key,
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+
+    // === Alice rotates her credential and signature key with a commit ===
+    let new_alice_signer = SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
+    let new_alice_credential_with_key = CredentialWithKey {
+        credential: Credential::new(b"Alice (new device)".to_vec(), CredentialType::Basic).unwrap(),
+        signature_key: new_alice_signer.to_public_vec().into(),
+    };
+    let (commit, _welcome, _group_info) = alice_group
+        .update_credential(
+            backend,
+            &alice_signer,
+            &new_alice_signer,
+            new_alice_credential_with_key.clone(),
+        )
+        .expect("Error updating the credential.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let processed_message = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    let staged_commit = match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => staged_commit,
+        _ => unreachable!("Expected a StagedCommit."),
+    };
+    let credential_updates = staged_commit.credential_updates();
+    assert_eq!(credential_updates.len(), 1);
+    assert_eq!(credential_updates[0].leaf_index(), LeafNodeIndex::new(0));
+    assert_eq!(
+        credential_updates[0].previous_credential(),
+        &alice_credential_with_key.credential
+    );
+    assert_eq!(
+        credential_updates[0].previous_signature_key(),
+        &alice_credential_with_key.signature_key
+    );
+    assert_eq!(
+        credential_updates[0].credential(),
+        &new_alice_credential_with_key.credential
+    );
+    assert_eq!(
+        credential_updates[0].signature_key(),
+        &new_alice_credential_with_key.signature_key
+    );
+    bob_group
+        .merge_staged_commit(backend, *staged_commit)
+        .expect("Error merging staged commit.");
+
+    // Alice signs with the new signature key from now on.
+    let message = alice_group
+        .create_message(backend, &new_alice_signer, b"Hello from the new device")
+        .expect("Error creating application message.");
+    let processed_message = bob_group
+        .process_message(backend, message.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    assert!(matches!(
+        processed_message.into_content(),
+        ProcessedMessageContent::ApplicationMessage(_)
+    ));
+
+    // === Bob rotates his signature key with a proposal ===
+    let new_bob_signer = SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
+    let new_bob_credential_with_key = CredentialWithKey {
+        credential: bob_credential_with_key.credential.clone(),
+        signature_key: new_bob_signer.to_public_vec().into(),
+    };
+    let (proposal, _proposal_ref) = bob_group
+        .propose_credential_update(
+            backend,
+            &bob_signer,
+            &new_bob_signer,
+            new_bob_credential_with_key.clone(),
+        )
+        .expect("Error proposing the credential update.");
+
+    let processed_message = alice_group
+        .process_message(backend, proposal.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::ProposalMessage(staged_proposal) => alice_group
+            .store_pending_proposal(backend, *staged_proposal)
+            .expect("Could not store proposal."),
+        _ => unreachable!("Expected a QueuedProposal."),
+    }
+    let (commit, _welcome, _group_info) = alice_group
+        .commit_to_pending_proposals(backend, &new_alice_signer)
+        .expect("Could not commit to pending proposals.");
+
+    let credential_updates = alice_group
+        .pending_commit()
+        .expect("No pending commit.")
+        .credential_updates();
+    assert_eq!(credential_updates.len(), 1);
+    assert_eq!(credential_updates[0].leaf_index(), LeafNodeIndex::new(1));
+    assert_eq!(
+        credential_updates[0].credential(),
+        &bob_credential_with_key.credential
+    );
+    assert_eq!(
+        credential_updates[0].signature_key(),
+        &new_bob_credential_with_key.signature_key
+    );
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let processed_message = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => bob_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging staged commit."),
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+
+    let message = bob_group
+        .create_message(backend, &new_bob_signer, b"Hello with a new key")
+        .expect("Error creating application message.");
+    alice_group
+        .process_message(backend, message.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+}
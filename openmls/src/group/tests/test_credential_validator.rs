@@ -0,0 +1,87 @@
+//! This module tests the application callback that validates the credentials
+//! of members.
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{
+    ciphersuite::SignaturePublicKey,
+    credentials::{Credential, CredentialRejection, CredentialValidator},
+    framing::*,
+    group::{core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+/// A credential validator that rejects a single identity.
+struct DenyIdentity(&'static [u8]);
+
+impl CredentialValidator for DenyIdentity {
+    fn validate(
+        &self,
+        credential: &Credential,
+        _signature_key: &SignaturePublicKey,
+    ) -> Result<(), CredentialRejection> {
+        if credential.identity() == self.0 {
+            Err(CredentialRejection::new("identity not allowed"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[apply(ciphersuites_and_backends)]
+fn credential_validator(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        mut bob_group,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+    bob_group.set_credential_validator(DenyIdentity(b"Charlie"));
+
+    // === The validator accepts Alice's updated leaf node ===
+    let (commit, _welcome, _group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Error creating self update.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let processed_message = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => bob_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging staged commit."),
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+
+    // === The validator rejects Charlie ===
+    let (commit, _welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[charlie_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let charlie_index = alice_group
+        .members()
+        .find(|member| member.credential.identity() == b"Charlie")
+        .expect("Charlie is not a member.")
+        .index;
+
+    let err = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect_err("Accepted a rejected credential.");
+    assert_eq!(
+        err,
+        ProcessMessageError::CredentialRejected {
+            leaf_index: charlie_index,
+            rejection: CredentialRejection::new("identity not allowed"),
+        }
+    );
+    assert_eq!(bob_group.members().count(), 2);
+}
@@ -0,0 +1,144 @@
+//! This module tests the limits that apply when decoding messages.
+
+use tls_codec::Serialize as TlsSerializeTrait;
+
+use crate::{
+    framing::*,
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn decode_limits(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+    let (_dave_credential_with_key, dave_kpb, _dave_signer, _dave_pk) =
+        setup_client("Dave", ciphersuite, backend);
+
+    // === Decoding with limits ===
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key.clone(),
+    )
+    .expect("An unexpected error occurred.");
+    let (commit, _welcome, _group_info) = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[
+                charlie_kpb.key_package().clone(),
+                dave_kpb.key_package().clone(),
+            ],
+        )
+        .expect("Could not add members.");
+    let bytes = commit
+        .tls_serialize_detached()
+        .expect("Error serializing commit.");
+
+    MlsMessageIn::tls_deserialize_with_limits(&bytes, &DecodeLimits::default())
+        .expect("Error decoding commit within the default limits.");
+    assert_eq!(
+        MlsMessageIn::tls_deserialize_with_limits(
+            &bytes,
+            &DecodeLimits::new().with_max_message_size(bytes.len() - 1)
+        ),
+        Err(MessageDecodeError::LimitExceeded(
+            DecodeLimitError::MessageTooLarge {
+                size: bytes.len(),
+                max: bytes.len() - 1
+            }
+        ))
+    );
+    assert_eq!(
+        MlsMessageIn::tls_deserialize_with_limits(
+            &bytes,
+            &DecodeLimits::new().with_max_proposals(1)
+        ),
+        Err(MessageDecodeError::LimitExceeded(
+            DecodeLimitError::TooManyProposals {
+                proposals: 2,
+                max: 1
+            }
+        ))
+    );
+    assert!(matches!(
+        MlsMessageIn::tls_deserialize_with_limits(
+            &bytes,
+            &DecodeLimits::new().with_max_path_length(0)
+        ),
+        Err(MessageDecodeError::LimitExceeded(
+            DecodeLimitError::PathTooLong { max: 0, .. }
+        ))
+    ));
+    assert!(matches!(
+        MlsMessageIn::tls_deserialize_with_limits(
+            &bytes[..bytes.len() - 1],
+            &DecodeLimits::default()
+        ),
+        Err(MessageDecodeError::DecodingError(_))
+    ));
+
+    // === Processing encrypted messages with limits ===
+    let alice_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .wire_format_policy(PURE_CIPHERTEXT_WIRE_FORMAT_POLICY)
+        .build();
+    let bob_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .wire_format_policy(PURE_CIPHERTEXT_WIRE_FORMAT_POLICY)
+        .decode_limits(DecodeLimits::new().with_max_proposals(1))
+        .build();
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &alice_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_msg, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add members.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &bob_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+    assert_eq!(bob_group.configuration().decode_limits().max_proposals(), 1);
+
+    // The number of proposals of a commit in a PrivateMessage is checked
+    // after decryption
+    let (commit, _welcome, _group_info) = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[
+                charlie_kpb.key_package().clone(),
+                dave_kpb.key_package().clone(),
+            ],
+        )
+        .expect("Could not add members.");
+    let err = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect_err("Bob processed a commit with too many proposals.");
+    assert_eq!(
+        err,
+        ProcessMessageError::ValidationError(ValidationError::DecodeLimitExceeded(
+            DecodeLimitError::TooManyProposals {
+                proposals: 2,
+                max: 1
+            }
+        ))
+    );
+}
@@ -0,0 +1,86 @@
+//! This module tests the emergency rekeying of a group.
+
+use crate::{
+    framing::*,
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn emergency_rekey(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .max_past_epochs(5)
+        .use_ratchet_tree_extension(true)
+        .build();
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        GroupId::from_slice(b"Test Group"),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_, welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .unwrap();
+    alice_group.merge_pending_commit(backend).unwrap();
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().unwrap(),
+        None,
+    )
+    .unwrap();
+
+    // Processes and merges the given commit on Bob's side and returns whether
+    // it was a compromise recovery.
+    let process_commit = |bob_group: &mut MlsGroup, commit: MlsMessageOut| {
+        let processed_message = bob_group
+            .process_message(backend, commit.into_protocol_message().unwrap())
+            .unwrap();
+        match processed_message.into_content() {
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+                let compromise_recovery = staged_commit.is_compromise_recovery();
+                bob_group
+                    .merge_staged_commit(backend, *staged_commit)
+                    .unwrap();
+                compromise_recovery
+            }
+            _ => unreachable!("Expected a StagedCommit."),
+        }
+    };
+
+    // A regular update keeps the secrets of the past epoch.
+    let (commit, _, _) = alice_group.self_update(backend, &alice_signer).unwrap();
+    alice_group.merge_pending_commit(backend).unwrap();
+    assert!(!process_commit(&mut bob_group, commit));
+    assert!(!alice_group.past_epochs().is_empty());
+    assert!(!bob_group.past_epochs().is_empty());
+
+    // The emergency re-keying drops them on both sides.
+    let epoch_before = alice_group.epoch();
+    let (commit, _group_info) = alice_group.emergency_rekey(backend, &alice_signer).unwrap();
+    alice_group.merge_pending_commit(backend).unwrap();
+    assert!(process_commit(&mut bob_group, commit));
+    assert_eq!(alice_group.epoch().as_u64(), epoch_before.as_u64() + 1);
+    assert!(alice_group.past_epochs().is_empty());
+    assert!(bob_group.past_epochs().is_empty());
+    assert_eq!(
+        alice_group.epoch_authenticator().as_slice(),
+        bob_group.epoch_authenticator().as_slice()
+    );
+
+    // The marker stays in the group context, but later commits are no
+    // compromise recovery.
+    let (commit, _, _) = alice_group.self_update(backend, &alice_signer).unwrap();
+    alice_group.merge_pending_commit(backend).unwrap();
+    assert!(!process_commit(&mut bob_group, commit));
+    assert_eq!(bob_group.past_epochs().len(), 1);
+}
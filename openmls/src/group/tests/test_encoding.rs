@@ -74,7 +74,7 @@ fn test_application_message_encoding(backend: &impl OpenMlsCryptoProvider) {
                 .create_application_message(
                     &aad,
                     &message,
-                    0,
+                    PaddingPolicy::None,
                     backend,
                     &credential_with_key_and_signer.signer,
                 )
@@ -428,6 +428,7 @@ fn test_welcome_message_encoding(backend: &impl OpenMlsCryptoProvider) {
             charlie_key_package_bundle,
             backend,
             ResumptionPskStore::new(1024),
+            None,
         )
         .is_ok());
     }
@@ -0,0 +1,187 @@
+//! This module tests the log of group events.
+
+use crate::{
+    binary_tree::LeafNodeIndex,
+    framing::*,
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    messages::proposals::*,
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn event_log(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (bob_credential_with_key, bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+    let alice_credential = alice_credential_with_key.credential.clone();
+    let bob_credential = bob_credential_with_key.credential.clone();
+    let charlie_credential = charlie_credential_with_key.credential.clone();
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .event_log_capacity(16)
+        .build();
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    // === Alice adds Bob ===
+    alice_group.set_event_timestamp(Some(100));
+    let (_msg, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add members.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+
+    let events: Vec<&GroupEvent> = alice_group.event_log(..).collect();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].epoch(), GroupEpoch::from(0));
+    assert_eq!(events[0].kind(), GroupEventKind::Commit);
+    assert_eq!(events[0].actor(), &Sender::Member(LeafNodeIndex::new(0)));
+    assert_eq!(events[0].actor_credential(), Some(&alice_credential));
+    assert!(events[0].targets().is_empty());
+    assert_eq!(events[0].timestamp(), Some(100));
+    assert_eq!(
+        events[1].kind(),
+        GroupEventKind::Committed(ProposalType::Add)
+    );
+    assert_eq!(events[1].actor_credential(), Some(&alice_credential));
+    assert_eq!(events[1].targets(), &[bob_credential.clone()]);
+
+    // === Bob proposes to add Charlie and Alice commits ===
+    alice_group.set_event_timestamp(Some(200));
+    let (proposal, _proposal_ref) = bob_group
+        .propose_add_member(backend, &bob_signer, charlie_kpb.key_package())
+        .expect("Could not create proposal.");
+    let processed_message = alice_group
+        .process_message(backend, proposal.into_protocol_message().unwrap())
+        .expect("Could not process proposal.");
+    let ProcessedMessageContent::ProposalMessage(queued_proposal) =
+        processed_message.into_content()
+    else {
+        panic!("Expected a proposal.");
+    };
+    alice_group
+        .store_pending_proposal(backend, *queued_proposal)
+        .expect("error storing proposal");
+
+    let events: Vec<&GroupEvent> = alice_group.event_log(GroupEpoch::from(1)..).collect();
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        events[0].kind(),
+        GroupEventKind::Proposed(ProposalType::Add)
+    );
+    assert_eq!(events[0].actor(), &Sender::Member(LeafNodeIndex::new(1)));
+    assert_eq!(events[0].actor_credential(), Some(&bob_credential));
+    assert_eq!(events[0].targets(), &[charlie_credential.clone()]);
+    assert_eq!(events[0].timestamp(), Some(200));
+
+    let (commit, _welcome, _group_info) = alice_group
+        .commit_to_pending_proposals(backend, &alice_signer)
+        .expect("Could not commit to pending proposals.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let processed_message = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process commit.");
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    else {
+        panic!("Expected a commit.");
+    };
+    bob_group
+        .merge_staged_commit(backend, *staged_commit)
+        .expect("error merging staged commit");
+
+    // The proposal of Bob is committed by Alice
+    let events: Vec<&GroupEvent> = bob_group.event_log(GroupEpoch::from(1)..).collect();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].kind(), GroupEventKind::Commit);
+    assert_eq!(events[0].actor(), &Sender::Member(LeafNodeIndex::new(0)));
+    assert_eq!(events[0].actor_credential(), Some(&alice_credential));
+    assert_eq!(events[0].timestamp(), None);
+    assert_eq!(
+        events[1].kind(),
+        GroupEventKind::Committed(ProposalType::Add)
+    );
+    assert_eq!(events[1].actor(), &Sender::Member(LeafNodeIndex::new(1)));
+    assert_eq!(events[1].actor_credential(), Some(&bob_credential));
+    assert_eq!(events[1].targets(), &[charlie_credential]);
+
+    // === Alice removes Bob ===
+    let (commit, _welcome, _group_info) = alice_group
+        .remove_members(backend, &alice_signer, &[LeafNodeIndex::new(1)])
+        .expect("Could not remove member.");
+    let processed_message = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process commit.");
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    else {
+        panic!("Expected a commit.");
+    };
+    bob_group
+        .merge_staged_commit(backend, *staged_commit)
+        .expect("error merging staged commit");
+
+    // The removed member is resolved before the commit is merged
+    let events: Vec<&GroupEvent> = bob_group.event_log(GroupEpoch::from(2)..).collect();
+    assert_eq!(events.len(), 2);
+    assert_eq!(
+        events[1].kind(),
+        GroupEventKind::Committed(ProposalType::Remove)
+    );
+    assert_eq!(events[1].actor_credential(), Some(&alice_credential));
+    assert_eq!(events[1].targets(), &[bob_credential]);
+    assert_eq!(bob_group.event_log(..GroupEpoch::from(2)).count(), 2);
+
+    // === Capacity ===
+    // Alice logged the two commits with their proposals and Bob's proposal
+    assert_eq!(alice_group.event_log(..).count(), 5);
+    let small_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .event_log_capacity(2)
+        .build();
+    alice_group
+        .set_configuration(backend, &small_config)
+        .expect("error setting configuration");
+    let events: Vec<&GroupEvent> = alice_group.event_log(..).collect();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].kind(), GroupEventKind::Commit);
+    assert_eq!(events[0].epoch(), GroupEpoch::from(1));
+
+    // The log is stored with the group
+    let loaded_group =
+        MlsGroup::load(alice_group.group_id(), backend).expect("Could not deserialize MlsGroup");
+    assert_eq!(
+        loaded_group.event_log(..).collect::<Vec<_>>(),
+        alice_group.event_log(..).collect::<Vec<_>>()
+    );
+
+    // === Disabled log ===
+    alice_group
+        .set_configuration(backend, &MlsGroupConfig::test_default(ciphersuite))
+        .expect("error setting configuration");
+    assert_eq!(alice_group.event_log(..).count(), 0);
+    alice_group
+        .clear_event_log(backend)
+        .expect("error clearing event log");
+}
@@ -0,0 +1,178 @@
+//! This module tests the typed exporter labels and the compatibility quirks of
+//! the exporter.
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    schedule::{ExporterCompatibility, ExporterQuirk},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn typed_exporter(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Namespaces ===
+    let mut registry = ExporterLabelRegistry::new();
+    let chat = registry
+        .register("chat")
+        .expect("error registering namespace");
+    let calls = registry
+        .register("calls")
+        .expect("error registering namespace");
+    assert_eq!(
+        registry.register("chat"),
+        Err(ExporterLabelError::DuplicateNamespace)
+    );
+    assert_eq!(
+        registry.register(""),
+        Err(ExporterLabelError::EmptyNamespace)
+    );
+    assert_eq!(
+        registry.register("chat:keys"),
+        Err(ExporterLabelError::InvalidNamespace)
+    );
+    assert_eq!(
+        registry.register("OpenMLS"),
+        Err(ExporterLabelError::ReservedNamespace)
+    );
+    assert_eq!(registry.namespaces().collect::<Vec<_>>(), ["calls", "chat"]);
+
+    let chat_key = chat.label("key", ExporterLength::Key256);
+    let calls_key = calls.label("key", ExporterLength::Key256);
+    let chat_hash = chat.label("key hash", ExporterLength::HashLength);
+    assert_eq!(chat_key.as_str(), "chat:key");
+
+    // === Exporting ===
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let secret = alice_group
+        .export(backend, &chat_key, b"context")
+        .expect("error exporting secret");
+    assert_eq!(
+        secret,
+        alice_group
+            .export_secret(backend, "chat:key", b"context", 32)
+            .expect("error exporting secret")
+    );
+    let array: [u8; 32] = alice_group
+        .export_array(backend, &chat_key, b"context")
+        .expect("error exporting secret");
+    assert_eq!(array.as_slice(), secret);
+    assert_ne!(
+        alice_group
+            .export(backend, &calls_key, b"context")
+            .expect("error exporting secret"),
+        secret
+    );
+    assert_eq!(
+        alice_group
+            .export(backend, &chat_hash, b"context")
+            .expect("error exporting secret")
+            .len(),
+        ciphersuite.hash_length()
+    );
+    assert_eq!(
+        alice_group.export_array::<16>(backend, &chat_key, b"context"),
+        Err(ExportSecretError::KeyLengthMismatch)
+    );
+
+    // === The cache is bounded ===
+    for i in 0..100u8 {
+        alice_group
+            .export(backend, &chat_key, &[i])
+            .expect("error exporting secret");
+    }
+    assert_eq!(alice_group.cached_exported_secrets(), 64);
+
+    // === The cached secrets are dropped in the new epoch ===
+    alice_group
+        .self_update(backend, &alice_signer)
+        .expect("error creating self-update commit");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    assert_eq!(alice_group.cached_exported_secrets(), 0);
+    let new_secret = alice_group
+        .export(backend, &chat_key, b"context")
+        .expect("error exporting secret");
+    assert_ne!(new_secret, secret);
+    assert_eq!(
+        new_secret,
+        alice_group
+            .export_secret(backend, "chat:key", b"context", 32)
+            .expect("error exporting secret")
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn exporter_compatibility(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let TwoMemberGroup {
+        mut alice_group,
+        mut bob_group,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+
+    let export = |group: &MlsGroup, label: &str| {
+        group
+            .export_secret(backend, label, b"context", 32)
+            .expect("Error exporting secret.")
+    };
+    let rfc_secret = export(&alice_group, "legacy");
+    let other_secret = export(&alice_group, "other");
+
+    // Every quirk and combination of quirks changes the exported secret
+    let quirk_combinations: &[&[ExporterQuirk]] = &[
+        &[ExporterQuirk::ExporterLabel],
+        &[ExporterQuirk::UnhashedContext],
+        &[ExporterQuirk::DraftLabelPrefix],
+        &[ExporterQuirk::ExporterLabel, ExporterQuirk::UnhashedContext],
+        &[
+            ExporterQuirk::ExporterLabel,
+            ExporterQuirk::UnhashedContext,
+            ExporterQuirk::DraftLabelPrefix,
+        ],
+    ];
+    let mut secrets = vec![rfc_secret.clone()];
+    for quirks in quirk_combinations {
+        let mls_group_config = MlsGroupConfig::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .exporter_compatibility(ExporterCompatibility::new().with_label("legacy", quirks))
+            .build();
+        alice_group
+            .set_configuration(backend, &mls_group_config)
+            .expect("Error setting configuration.");
+        bob_group
+            .set_configuration(backend, &mls_group_config)
+            .expect("Error setting configuration.");
+
+        // Members with the same quirks derive the same secret
+        let secret = export(&alice_group, "legacy");
+        assert_eq!(secret, export(&bob_group, "legacy"));
+        assert!(!secrets.contains(&secret));
+        secrets.push(secret);
+
+        // Other labels are not affected
+        assert_eq!(export(&alice_group, "other"), other_secret);
+    }
+
+    // Without quirks, the secret is exported as specified in the RFC
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .exporter_compatibility(ExporterCompatibility::new().with_label("legacy", &[]))
+        .build();
+    alice_group
+        .set_configuration(backend, &mls_group_config)
+        .expect("Error setting configuration.");
+    assert!(mls_group_config.exporter_compatibility().is_empty());
+    assert_eq!(export(&alice_group, "legacy"), rfc_secret);
+}
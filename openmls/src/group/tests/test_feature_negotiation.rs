@@ -0,0 +1,112 @@
+//! This module tests the negotiation of features between the members of a
+//! group.
+
+use crate::{
+    extensions::*,
+    framing::*,
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    key_packages::*,
+    messages::proposals::*,
+    test_utils::*,
+    treesync::node::leaf_node::Capabilities,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn negotiate_feature(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let group_id = GroupId::from_slice(b"Test Group");
+    let feature = ExtensionType::Unknown(0xff00);
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (bob_credential_with_key, _bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    // Only Bob supports the feature
+    let bob_key_package = KeyPackage::builder()
+        .leaf_node_capabilities(Capabilities::new(
+            None,
+            Some(&[ciphersuite]),
+            Some(&[feature]),
+            None,
+            None,
+        ))
+        .build(
+            CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &bob_signer,
+            bob_credential_with_key,
+        )
+        .expect("Error creating key package.");
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds Bob ===
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let (_msg, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_key_package])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+
+    // Default extensions are supported by everyone
+    assert_eq!(
+        bob_group.negotiate_feature(ExtensionType::ApplicationId),
+        FeatureStatus::Available
+    );
+    assert_eq!(
+        bob_group.negotiate_feature(ProposalType::Remove),
+        FeatureStatus::Available
+    );
+    assert_eq!(
+        bob_group.negotiate_feature(feature),
+        FeatureStatus::Unavailable
+    );
+
+    // === Alice leaves the group ===
+    let proposal = alice_group
+        .leave_group(backend, &alice_signer)
+        .expect("Error leaving group.");
+    let processed_message = bob_group
+        .process_message(backend, proposal.into_protocol_message().unwrap())
+        .expect("Could not process messages.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::ProposalMessage(proposal) => bob_group
+            .store_pending_proposal(backend, *proposal)
+            .expect("Could not store proposal."),
+        _ => unreachable!("Expected a proposal."),
+    }
+
+    // Once Alice is removed, all members support the feature
+    assert_eq!(
+        bob_group.negotiate_feature(feature),
+        FeatureStatus::AvailableAfterNextCommit
+    );
+
+    let (_commit, _welcome, _group_info) = bob_group
+        .commit_to_pending_proposals(backend, &bob_signer)
+        .expect("Error committing to pending proposals.");
+    bob_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    assert_eq!(
+        bob_group.negotiate_feature(feature),
+        FeatureStatus::Available
+    );
+}
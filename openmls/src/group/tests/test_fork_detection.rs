@@ -0,0 +1,162 @@
+//! This module tests the detection of forks, where members merged different
+//! commits for the same epoch.
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{
+    framing::*,
+    group::{config::CryptoConfig, *},
+    test_utils::*,
+};
+
+#[cfg(feature = "external-commit")]
+#[apply(ciphersuites_and_backends)]
+fn fork_detection(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    // The secrets of the previous epoch are needed to authenticate the
+    // diverging commit
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .max_past_epochs(1)
+        .build();
+
+    // === Alice creates a group and adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        mut bob_group,
+        bob_signer,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+    assert_eq!(
+        bob_group.check_epoch_authenticator(
+            alice_group.epoch(),
+            alice_group.epoch_authenticator().as_slice()
+        ),
+        Ok(())
+    );
+
+    // === Alice and Bob both merge their own commits for the same epoch ===
+    let fork_epoch = alice_group.epoch();
+    let (_alice_commit, _welcome, _group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Error creating self-update.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let (bob_commit, _welcome, _group_info) = bob_group
+        .self_update(backend, &bob_signer)
+        .expect("Error creating self-update.");
+    bob_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    // === Bob's commit competes with Alice's, and the epoch authenticators
+    // show that Bob merged it ===
+    let err = alice_group
+        .process_message(backend, bob_commit.into_protocol_message().unwrap())
+        .expect_err("Processed a diverging commit.");
+    assert_eq!(
+        err,
+        ProcessMessageError::CompetingCommit { epoch: fork_epoch }
+    );
+    assert_eq!(
+        alice_group.check_epoch_authenticator(
+            bob_group.epoch(),
+            bob_group.epoch_authenticator().as_slice()
+        ),
+        Err(CheckEpochAuthenticatorError::ForkDetected)
+    );
+    assert_eq!(
+        alice_group
+            .check_epoch_authenticator(fork_epoch, bob_group.epoch_authenticator().as_slice()),
+        Err(CheckEpochAuthenticatorError::WrongEpoch)
+    );
+
+    // === Bob recovers by re-joining Alice's branch ===
+    let verifiable_group_info = alice_group
+        .export_group_info(backend, &alice_signer, true)
+        .expect("Error exporting group info.")
+        .into_verifiable_group_info()
+        .expect("Unexpected message type.");
+    let (pending_rejoin, commit, _group_info) = bob_group
+        .recover_from_fork(backend, &bob_signer, None, verifiable_group_info)
+        .expect("Error recovering from the fork.");
+    let processed_message = alice_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process messages.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => alice_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging commit."),
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+    bob_group
+        .complete_rejoin(backend, pending_rejoin)
+        .expect("Error completing the re-join.");
+
+    assert_eq!(alice_group.members().count(), 2);
+    assert_eq!(
+        alice_group.check_epoch_authenticator(
+            bob_group.epoch(),
+            bob_group.epoch_authenticator().as_slice()
+        ),
+        Ok(())
+    );
+}
+
+// A commit that the DS rejected competes with the merged one, but is no
+// evidence of a fork.
+#[apply(ciphersuites_and_backends)]
+fn competing_commit_without_fork(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .max_past_epochs(1)
+        .build();
+
+    // === Alice creates a group and adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        mut bob_group,
+        bob_signer,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+
+    // === Both commit, and the DS accepts Alice's commit ===
+    let competing_epoch = alice_group.epoch();
+    let (alice_commit, _welcome, _group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Error creating self-update.");
+    let (bob_commit, _welcome, _group_info) = bob_group
+        .self_update(backend, &bob_signer)
+        .expect("Error creating self-update.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let processed_message = bob_group
+        .process_message(backend, alice_commit.into_protocol_message().unwrap())
+        .expect("Could not process messages.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => bob_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging commit."),
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+
+    // === Alice receives the rejected commit, but the group didn't fork ===
+    let err = alice_group
+        .process_message(backend, bob_commit.into_protocol_message().unwrap())
+        .expect_err("Processed a competing commit.");
+    assert_eq!(
+        err,
+        ProcessMessageError::CompetingCommit {
+            epoch: competing_epoch
+        }
+    );
+    assert_eq!(
+        alice_group.check_epoch_authenticator(
+            bob_group.epoch(),
+            bob_group.epoch_authenticator().as_slice()
+        ),
+        Ok(())
+    );
+}
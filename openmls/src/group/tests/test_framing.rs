@@ -75,7 +75,7 @@ fn padding(backend: &impl OpenMlsCryptoProvider) {
                     .create_application_message(
                         &aad,
                         &message,
-                        padding_size,
+                        PaddingPolicy::BlockSize(padding_size),
                         backend,
                         &credential.signer,
                     )
@@ -95,6 +95,29 @@ fn padding(backend: &impl OpenMlsCryptoProvider) {
             }
         }
     }
+
+    // With PADMÉ, the ciphertext length doesn't need further padding.
+    for group_state in alice.group_states.borrow_mut().values_mut() {
+        let credential = alice
+            .credentials
+            .get(&group_state.ciphersuite())
+            .expect("An unexpected error occurred.");
+        for _ in 0..100 {
+            let message = randombytes(random_usize() % 10_000);
+            let private_message = group_state
+                .create_application_message(
+                    &[],
+                    &message,
+                    PaddingPolicy::Padme,
+                    backend,
+                    &credential.signer,
+                )
+                .expect("An unexpected error occurred.");
+            let length = private_message.ciphertext().len();
+            assert!(length > message.len());
+            assert_eq!(PaddingPolicy::Padme.padding_length(length), 0);
+        }
+    }
 }
 
 /// Check that PrivateMessageContent's padding field is verified to be all-zero.
@@ -329,7 +352,7 @@ fn bad_padding(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
         let verifiable_plaintext_result = tampered_ciphertext.to_verifiable_content(
             ciphersuite,
             backend,
-            &mut message_secrets,
+            &message_secrets,
             LeafNodeIndex::new(0),
             &SenderRatchetConfiguration::default(),
             sender_data,
@@ -210,7 +210,10 @@ fn test_valsem003(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
         .expect_err("Could parse message despite wrong epoch.");
     assert_eq!(
         err,
-        ProcessMessageError::ValidationError(ValidationError::WrongEpoch)
+        ProcessMessageError::ValidationError(ValidationError::WrongEpoch {
+            received: GroupEpoch::from(current_epoch.as_u64() + 1),
+            expected: current_epoch,
+        })
     );
 
     // Set the epoch too low
@@ -220,7 +223,10 @@ fn test_valsem003(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
         .expect_err("Could parse message despite wrong epoch.");
     assert_eq!(
         err,
-        ProcessMessageError::ValidationError(ValidationError::WrongEpoch)
+        ProcessMessageError::ValidationError(ValidationError::WrongEpoch {
+            received: GroupEpoch::from(current_epoch.as_u64() - 1),
+            expected: current_epoch,
+        })
     );
 
     // Positive case
@@ -242,7 +248,10 @@ fn test_valsem003(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
     let process_twice = bob_group.process_message(backend, original_message);
     assert_eq!(
         process_twice.unwrap_err(),
-        ProcessMessageError::ValidationError(ValidationError::WrongEpoch)
+        ProcessMessageError::ValidationError(ValidationError::WrongEpoch {
+            received: current_epoch,
+            expected: GroupEpoch::from(current_epoch.as_u64() + 1),
+        })
     );
 }
 
@@ -139,6 +139,7 @@ fn create_commit_optional_path(ciphersuite: Ciphersuite, backend: &impl OpenMlsC
         bob_key_package_bundle,
         backend,
         ResumptionPskStore::new(1024),
+        None,
     ) {
         Ok(group) => group,
         Err(e) => panic!("Error creating group from Welcome: {e:?}"),
@@ -350,6 +351,7 @@ fn group_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvid
         bob_key_package_bundle,
         backend,
         ResumptionPskStore::new(1024),
+        None,
     ) {
         Ok(group) => group,
         Err(e) => panic!("Error creating group from Welcome: {e:?}"),
@@ -372,7 +374,7 @@ fn group_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvid
         .create_application_message(
             &[],
             &message_alice,
-            0,
+            PaddingPolicy::None,
             backend,
             &alice_credential_with_keys.signer,
         )
@@ -680,6 +682,7 @@ fn group_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvid
         charlie_key_package_bundle,
         backend,
         ResumptionPskStore::new(1024),
+        None,
     ) {
         Ok(group) => group,
         Err(e) => panic!("Error creating group from Welcome: {e:?}"),
@@ -701,7 +704,7 @@ fn group_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvid
         .create_application_message(
             &[],
             &message_charlie,
-            0,
+            PaddingPolicy::None,
             backend,
             &charlie_credential_with_keys.signer,
         )
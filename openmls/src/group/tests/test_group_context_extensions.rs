@@ -0,0 +1,173 @@
+//! This module tests proposals and commits that update the group context
+//! extensions.
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{
+    extensions::*, framing::*, group::*, test_utils::*, treesync::errors::LeafNodeValidationError,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn group_context_extensions(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        mut bob_group,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+
+    // === Alice proposes to require the application id extension ===
+    let required_capabilities = Extensions::single(Extension::RequiredCapabilities(
+        RequiredCapabilitiesExtension::new(&[ExtensionType::ApplicationId], &[], &[]),
+    ));
+    let (proposal, _proposal_ref) = alice_group
+        .propose_group_context_extensions(backend, &alice_signer, required_capabilities.clone())
+        .expect("Error proposing group context extensions.");
+
+    let processed_message = bob_group
+        .process_message(backend, proposal.into_protocol_message().unwrap())
+        .expect("Could not process messages.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::ProposalMessage(proposal) => bob_group
+            .store_pending_proposal(backend, *proposal)
+            .expect("Could not store proposal."),
+        _ => unreachable!("Expected a proposal."),
+    }
+
+    let (commit, _welcome, _group_info) = alice_group
+        .commit_to_pending_proposals(backend, &alice_signer)
+        .expect("Error committing to pending proposals.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let processed_message = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process messages.");
+    let staged_commit = match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => staged_commit,
+        _ => unreachable!("Expected a StagedCommit."),
+    };
+    let changes = staged_commit.group_context_extension_changes();
+    assert_eq!(
+        changes.added(),
+        required_capabilities.iter().cloned().collect::<Vec<_>>()
+    );
+    assert!(changes.removed().is_empty());
+    assert!(changes.modified().is_empty());
+    bob_group
+        .merge_staged_commit(backend, *staged_commit)
+        .expect("Error merging commit.");
+
+    assert_eq!(
+        alice_group.export_group_context().extensions(),
+        &required_capabilities
+    );
+    assert_eq!(
+        bob_group.export_group_context().extensions(),
+        &required_capabilities
+    );
+
+    // === Alice tries to use an extension that Bob doesn't support ===
+    let unsupported_extension =
+        Extensions::single(Extension::Unknown(0xff00, UnknownExtension(vec![1, 2, 3])));
+    let err = alice_group
+        .update_group_context_extensions(backend, &alice_signer, unsupported_extension.clone())
+        .expect_err("Updated the group context with an unsupported extension.");
+    assert_eq!(
+        err,
+        UpdateGroupContextExtensionsError::CreateGroupContextExtProposalError(
+            CreateGroupContextExtProposalError::LeafNodeValidation(
+                LeafNodeValidationError::UnsupportedExtensions
+            )
+        )
+    );
+    let err = alice_group
+        .propose_group_context_extensions(backend, &alice_signer, unsupported_extension)
+        .expect_err("Proposed an unsupported extension.");
+    assert_eq!(
+        err,
+        ProposalError::CreateGroupContextExtProposalError(
+            CreateGroupContextExtProposalError::LeafNodeValidation(
+                LeafNodeValidationError::UnsupportedExtensions
+            )
+        )
+    );
+
+    // === Alice relaxes the required capabilities and adds external senders ===
+    let relaxed_required_capabilities =
+        Extension::RequiredCapabilities(RequiredCapabilitiesExtension::new(&[], &[], &[]));
+    let external_senders = Extension::ExternalSenders(Vec::new());
+    let (_commit, _welcome, _group_info) = alice_group
+        .update_group_context_extensions(
+            backend,
+            &alice_signer,
+            Extensions::from_vec(vec![
+                relaxed_required_capabilities.clone(),
+                external_senders.clone(),
+            ])
+            .expect("Error creating extensions."),
+        )
+        .expect("Error updating group context extensions.");
+
+    let changes = alice_group
+        .pending_commit()
+        .expect("No pending commit.")
+        .group_context_extension_changes();
+    assert_eq!(changes.added(), &[external_senders.clone()]);
+    assert!(changes.removed().is_empty());
+    assert_eq!(changes.modified().len(), 1);
+    assert_eq!(
+        changes.modified()[0].extension_type(),
+        ExtensionType::RequiredCapabilities
+    );
+    assert_eq!(
+        changes.modified()[0].previous(),
+        required_capabilities.iter().next().unwrap()
+    );
+    assert_eq!(
+        changes.modified()[0].extension(),
+        &relaxed_required_capabilities
+    );
+    alice_group
+        .clear_pending_commit(backend)
+        .expect("Error clearing pending commit.");
+
+    // === Alice removes the extension again with a commit ===
+    let (commit, _welcome, _group_info) = alice_group
+        .update_group_context_extensions(backend, &alice_signer, Extensions::empty())
+        .expect("Error updating group context extensions.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let processed_message = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process messages.");
+    let staged_commit = match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => staged_commit,
+        _ => unreachable!("Expected a StagedCommit."),
+    };
+    let changes = staged_commit.group_context_extension_changes();
+    assert!(changes.added().is_empty());
+    assert_eq!(
+        changes.removed(),
+        required_capabilities.iter().cloned().collect::<Vec<_>>()
+    );
+    assert!(changes.modified().is_empty());
+    bob_group
+        .merge_staged_commit(backend, *staged_commit)
+        .expect("Error merging commit.");
+
+    assert!(alice_group
+        .export_group_context()
+        .extensions()
+        .required_capabilities()
+        .is_none());
+    assert_eq!(
+        bob_group.export_secret(backend, "test", &[], ciphersuite.hash_length()),
+        alice_group.export_secret(backend, "test", &[], ciphersuite.hash_length())
+    );
+}
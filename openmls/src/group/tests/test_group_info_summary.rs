@@ -0,0 +1,112 @@
+//! This module tests the summaries of GroupInfos that members export.
+
+use openmls_basic_credential::SignatureKeyPair;
+use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait};
+
+use crate::{
+    ciphersuite::{signable::Verifiable, OpenMlsSignaturePublicKey},
+    credentials::{Credential, CredentialType},
+    extensions::*,
+    group::{
+        config::CryptoConfig, core_group::test_core_group::setup_client,
+        public_group::errors::ExportGroupInfoSummaryError, *,
+    },
+    messages::group_info_summary::{
+        GroupInfoSummary, GroupInfoSummarySigner, VerifiableGroupInfoSummary,
+    },
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn group_info_summary(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    // The DS is an external sender of the group.
+    let ds_signer = SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
+    let ds_sender = ExternalSender::new(
+        ds_signer.to_public_vec().into(),
+        Credential::new(b"DS".to_vec(), CredentialType::Basic).unwrap(),
+    );
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .external_senders(vec![ds_sender])
+        .build();
+
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    // === Alice exports a summary with the external senders extension ===
+    let summary = alice_group
+        .export_group_info_summary(&alice_signer, &[ExtensionType::ExternalSenders])
+        .expect("Error exporting group info summary.");
+    let encoded = summary
+        .tls_serialize_detached()
+        .expect("An unexpected error occurred.");
+    let verifiable = VerifiableGroupInfoSummary::tls_deserialize(&mut encoded.as_slice())
+        .expect("An unexpected error occurred.");
+    assert_eq!(
+        verifiable.signer(),
+        GroupInfoSummarySigner::Member(alice_group.own_leaf_index())
+    );
+    let alice_pk = OpenMlsSignaturePublicKey::from_signature_key(
+        alice_signer.to_public_vec().into(),
+        ciphersuite.signature_algorithm(),
+    );
+    let verified: GroupInfoSummary = verifiable
+        .verify(backend.crypto(), &alice_pk)
+        .expect("Invalid group info summary signature.");
+    assert_eq!(verified, summary);
+    assert_eq!(summary.group_id(), alice_group.group_id());
+    assert_eq!(summary.ciphersuite(), ciphersuite);
+    assert_eq!(summary.epoch(), alice_group.epoch());
+    assert_eq!(summary.member_count(), 2);
+    assert_eq!(
+        summary.extensions().external_senders(),
+        alice_group.external_senders()
+    );
+
+    // === The DS exports a summary without extensions ===
+    let public_group = alice_group.group().public_group();
+    let summary = public_group
+        .export_group_info_summary(&ds_signer, SenderExtensionIndex::new(0), &[])
+        .expect("Error exporting group info summary.");
+    assert_eq!(
+        summary.signer(),
+        GroupInfoSummarySigner::External(SenderExtensionIndex::new(0))
+    );
+    assert!(summary.extensions().iter().next().is_none());
+
+    let ds_pk = OpenMlsSignaturePublicKey::from_signature_key(
+        ds_signer.to_public_vec().into(),
+        ciphersuite.signature_algorithm(),
+    );
+    let mut verifiable = VerifiableGroupInfoSummary::from(summary);
+    verifiable
+        .verify_no_out(backend.crypto(), &ds_pk)
+        .expect("Invalid group info summary signature.");
+    verifiable.break_signature();
+    assert!(verifiable
+        .verify::<GroupInfoSummary>(backend.crypto(), &ds_pk)
+        .is_err());
+
+    assert_eq!(
+        public_group
+            .export_group_info_summary(&ds_signer, SenderExtensionIndex::new(1), &[])
+            .expect_err("Unknown external sender accepted."),
+        ExportGroupInfoSummaryError::UnknownExternalSender
+    );
+}
@@ -0,0 +1,136 @@
+//! This module tests the observers that are notified of changes to a group.
+
+use std::sync::{Arc, Mutex};
+
+use crate::{
+    binary_tree::LeafNodeIndex,
+    framing::*,
+    group::{core_group::test_core_group::setup_client, *},
+    messages::proposals::*,
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn group_observer(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (bob_credential_with_key, bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    let observer = |notifications: Arc<Mutex<Vec<GroupNotification>>>| {
+        move |_group_id: &GroupId, notification: &GroupNotification| {
+            notifications.lock().unwrap().push(notification.clone())
+        }
+    };
+    let alice_notifications = Arc::new(Mutex::new(vec![]));
+    let bob_notifications = Arc::new(Mutex::new(vec![]));
+
+    // === Alice adds Bob ===
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    alice_group.set_observer(observer(alice_notifications.clone()));
+    let (_msg, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    assert!(alice_notifications.lock().unwrap().is_empty());
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    assert_eq!(
+        alice_notifications
+            .lock()
+            .unwrap()
+            .drain(..)
+            .collect::<Vec<_>>(),
+        vec![
+            GroupNotification::MemberAdded {
+                leaf_index: LeafNodeIndex::new(1),
+                credential: bob_credential_with_key.credential.clone(),
+            },
+            GroupNotification::EpochChanged(GroupEpoch::from(1)),
+        ]
+    );
+
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+    bob_group.set_observer(observer(bob_notifications.clone()));
+
+    // === Alice receives a proposal of Bob ===
+    let (proposal, proposal_ref) = bob_group
+        .propose_self_update(backend, &bob_signer, None)
+        .expect("Error creating proposal.");
+    alice_group
+        .process_message(backend, proposal.into_protocol_message().unwrap())
+        .expect("Could not process proposal.");
+    assert_eq!(
+        alice_notifications
+            .lock()
+            .unwrap()
+            .drain(..)
+            .collect::<Vec<_>>(),
+        vec![GroupNotification::ProposalReceived {
+            proposal_ref,
+            proposal_type: ProposalType::Update,
+            sender: Sender::Member(LeafNodeIndex::new(1)),
+        }]
+    );
+
+    // === Alice removes Bob ===
+    let (commit, _welcome, _group_info) = alice_group
+        .remove_members(backend, &alice_signer, &[LeafNodeIndex::new(1)])
+        .expect("Could not remove member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let processed_message = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process commit.");
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    else {
+        panic!("Expected a commit.");
+    };
+    bob_group
+        .merge_staged_commit(backend, *staged_commit)
+        .expect("Error merging commit.");
+
+    let removed_bob = GroupNotification::MemberRemoved {
+        leaf_index: LeafNodeIndex::new(1),
+        credential: bob_credential_with_key.credential,
+    };
+    assert_eq!(
+        alice_notifications
+            .lock()
+            .unwrap()
+            .drain(..)
+            .collect::<Vec<_>>(),
+        vec![
+            removed_bob.clone(),
+            GroupNotification::EpochChanged(GroupEpoch::from(2)),
+        ]
+    );
+    assert_eq!(
+        bob_notifications
+            .lock()
+            .unwrap()
+            .drain(..)
+            .collect::<Vec<_>>(),
+        vec![
+            removed_bob,
+            GroupNotification::OwnRemoval(DepartureReason::RemovedBy(Sender::Member(
+                LeafNodeIndex::new(0)
+            ))),
+        ]
+    );
+}
@@ -0,0 +1,138 @@
+//! This module tests signing with keys that are referenced by a handle.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use openmls_basic_credential::SignatureKeyPair;
+use openmls_traits::{
+    signatures::{HandleSigner, SignatureKeyProvider},
+    types::{CryptoError, SignatureScheme},
+};
+
+use crate::{
+    credentials::{Credential, CredentialType, CredentialWithKey},
+    framing::*,
+    group::{core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+/// A [`SignatureKeyProvider`] that keeps the private keys to itself, like a
+/// platform key store would.
+#[derive(Default)]
+struct TestKeyProvider {
+    keys: Mutex<HashMap<Vec<u8>, SignatureKeyPair>>,
+}
+
+impl SignatureKeyProvider for TestKeyProvider {
+    type KeyHandle = Vec<u8>;
+    type Error = CryptoError;
+
+    fn generate_key(
+        &self,
+        key_id: &[u8],
+        signature_scheme: SignatureScheme,
+    ) -> Result<(Self::KeyHandle, Vec<u8>), Self::Error> {
+        let key_pair = SignatureKeyPair::new(signature_scheme)?;
+        let public = key_pair.to_public_vec();
+        self.keys.lock().unwrap().insert(key_id.to_vec(), key_pair);
+        Ok((key_id.to_vec(), public))
+    }
+
+    fn find_key(
+        &self,
+        key_id: &[u8],
+        signature_scheme: SignatureScheme,
+    ) -> Result<Option<(Self::KeyHandle, Vec<u8>)>, Self::Error> {
+        Ok(self
+            .keys
+            .lock()
+            .unwrap()
+            .get(key_id)
+            .filter(|key_pair| key_pair.signature_scheme() == signature_scheme)
+            .map(|key_pair| (key_id.to_vec(), key_pair.to_public_vec())))
+    }
+
+    fn sign(
+        &self,
+        key: &Self::KeyHandle,
+        signature_scheme: SignatureScheme,
+        payload: &[u8],
+    ) -> Result<Vec<u8>, Self::Error> {
+        let keys = self.keys.lock().unwrap();
+        let key_pair = keys
+            .get(key)
+            .filter(|key_pair| key_pair.signature_scheme() == signature_scheme)
+            .ok_or(CryptoError::CryptoLibraryError)?;
+        openmls_traits::signatures::Signer::sign(key_pair, payload)
+            .map_err(|_| CryptoError::CryptoLibraryError)
+    }
+}
+
+#[apply(ciphersuites_and_backends)]
+fn handle_signer(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let provider = Arc::new(TestKeyProvider::default());
+    let alice_signer = HandleSigner::generate(
+        provider.clone(),
+        b"alice",
+        ciphersuite.signature_algorithm(),
+    )
+    .unwrap();
+    let alice_credential_with_key = CredentialWithKey {
+        credential: Credential::new(b"Alice".to_vec(), CredentialType::Basic).unwrap(),
+        signature_key: alice_signer.public().into(),
+    };
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    // The key can be loaded again by its key ID
+    let loaded = HandleSigner::load(
+        provider.clone(),
+        b"alice",
+        ciphersuite.signature_algorithm(),
+    )
+    .unwrap()
+    .unwrap();
+    assert_eq!(loaded.public(), alice_signer.public());
+    assert!(
+        HandleSigner::load(provider, b"bob", ciphersuite.signature_algorithm())
+            .unwrap()
+            .is_none()
+    );
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds Bob, signing with the handle ===
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        GroupId::from_slice(b"Test Group"),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_, welcome, _) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .unwrap();
+    alice_group.merge_pending_commit(backend).unwrap();
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().unwrap(),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .unwrap();
+
+    // Bob verifies messages signed with the handle
+    let message = alice_group
+        .create_message(backend, &alice_signer, b"Hello Bob")
+        .unwrap();
+    let processed_message = bob_group
+        .process_message(backend, message.into_protocol_message().unwrap())
+        .unwrap();
+    assert!(matches!(
+        processed_message.into_content(),
+        ProcessedMessageContent::ApplicationMessage(_)
+    ));
+}
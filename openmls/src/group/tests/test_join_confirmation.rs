@@ -0,0 +1,64 @@
+//! This module tests the confirmations that new members send after joining.
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{binary_tree::LeafNodeIndex, framing::*, group::*, test_utils::*};
+
+#[apply(ciphersuites_and_backends)]
+fn join_confirmation(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        mut bob_group,
+        bob_signer,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+
+    // === Bob sends his join confirmation as first application message ===
+    let confirmation = bob_group
+        .join_confirmation(backend)
+        .expect("Error computing join confirmation.");
+    let message = bob_group
+        .create_message(backend, &bob_signer, &confirmation)
+        .expect("Error creating application message.");
+
+    let processed_message = alice_group
+        .process_message(backend, message.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    let sender = match processed_message.sender() {
+        Sender::Member(leaf_index) => *leaf_index,
+        _ => unreachable!("Expected a member sender."),
+    };
+    assert_eq!(sender, bob_group.own_leaf_index());
+    let received = match processed_message.into_content() {
+        ProcessedMessageContent::ApplicationMessage(message) => message.into_bytes(),
+        _ => unreachable!("Expected an application message."),
+    };
+    alice_group
+        .verify_join_confirmation(backend, sender, &received)
+        .expect("Invalid join confirmation.");
+
+    // The confirmation is bound to the sender
+    assert_eq!(
+        alice_group.verify_join_confirmation(backend, alice_group.own_leaf_index(), &received),
+        Err(JoinConfirmationError::InvalidConfirmation)
+    );
+    assert_eq!(
+        alice_group.verify_join_confirmation(backend, LeafNodeIndex::new(5), &received),
+        Err(JoinConfirmationError::UnknownMember)
+    );
+
+    // The confirmation is bound to the epoch
+    let (_commit, _welcome, _group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Error creating self update.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    assert_eq!(
+        alice_group.verify_join_confirmation(backend, sender, &received),
+        Err(JoinConfirmationError::InvalidConfirmation)
+    );
+}
@@ -0,0 +1,80 @@
+//! This module tests the policy for the lifetimes of KeyPackages.
+
+use std::time::Duration;
+
+use crate::{
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    key_packages::*,
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn key_package_lifetime_policy(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (bob_credential_with_key, _bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    // Bob's key package expires right away.
+    let bob_key_package = KeyPackage::builder()
+        .key_package_lifetime(Lifetime::new(0))
+        .build(
+            CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &bob_signer,
+            bob_credential_with_key,
+        )
+        .expect("Error creating key package.");
+    std::thread::sleep(Duration::from_secs(1));
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    // The default policy rejects the expired key package.
+    let err = alice_group
+        .add_members(backend, &alice_signer, &[bob_key_package.clone()])
+        .expect_err("Expired key package accepted.");
+    assert_eq!(
+        err,
+        AddMembersError::CreateCommitError(CreateCommitError::ProposalValidationError(
+            ProposalValidationError::InvalidAddProposalLifetime
+        ))
+    );
+
+    // A clock skew tolerance covers the expired lifetime.
+    let tolerant_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .lifetime_policy(
+            LifetimePolicy::default().with_clock_skew_tolerance(Duration::from_secs(60)),
+        )
+        .build();
+    alice_group
+        .set_configuration(backend, &tolerant_config)
+        .expect("Error setting configuration.");
+    alice_group
+        .add_members(backend, &alice_signer, &[bob_key_package.clone()])
+        .expect("Error adding Bob.");
+    alice_group
+        .clear_pending_commit(backend)
+        .expect("Error clearing pending commit.");
+
+    // Without enforcement, the key package is accepted as well.
+    let lenient_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .lifetime_policy(LifetimePolicy::default().with_enforcement(false))
+        .build();
+    alice_group
+        .set_configuration(backend, &lenient_config)
+        .expect("Error setting configuration.");
+    alice_group
+        .add_members(backend, &alice_signer, &[bob_key_package])
+        .expect("Error adding Bob.");
+}
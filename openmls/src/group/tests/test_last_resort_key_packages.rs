@@ -0,0 +1,65 @@
+//! This module tests last resort KeyPackages.
+
+use openmls_traits::key_store::OpenMlsKeyStore;
+
+use crate::{
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    key_packages::*,
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn last_resort_key_package(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (bob_credential_with_key, _bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    // === Bob publishes a last resort key package ===
+    let key_package = KeyPackage::builder()
+        .mark_as_last_resort()
+        .build(
+            CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &bob_signer,
+            bob_credential_with_key,
+        )
+        .expect("Error creating key package.");
+    assert!(key_package.last_resort());
+    let hash_ref = key_package.hash_ref(backend.crypto()).unwrap();
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice adds Bob to two groups with the same key package ===
+    for group_id in [b"First Group".as_slice(), b"Second Group".as_slice()] {
+        let mut alice_group = MlsGroup::new_with_group_id(
+            backend,
+            &alice_signer,
+            &mls_group_config,
+            GroupId::from_slice(group_id),
+            alice_credential_with_key.clone(),
+        )
+        .expect("An unexpected error occurred.");
+        let (_msg, welcome, _group_info) = alice_group
+            .add_members(backend, &alice_signer, &[key_package.clone()])
+            .expect("Could not add member.");
+        alice_group
+            .merge_pending_commit(backend)
+            .expect("error merging pending commit");
+
+        let bob_group = MlsGroup::new_from_welcome(
+            backend,
+            &mls_group_config,
+            welcome.into_welcome().expect("Unexpected message type."),
+            Some(alice_group.export_ratchet_tree().into()),
+        )
+        .expect("error creating group from welcome");
+        assert_eq!(bob_group.group_id().as_slice(), group_id);
+
+        // The key package is still available for the next Welcome.
+        assert!(backend
+            .key_store()
+            .read::<KeyPackage>(hash_ref.as_slice())
+            .is_some());
+    }
+}
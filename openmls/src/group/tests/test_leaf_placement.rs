@@ -0,0 +1,272 @@
+//! This module tests where new members and service members are placed in the
+//! tree.
+
+use crate::{
+    binary_tree::LeafNodeIndex,
+    extensions::*,
+    framing::*,
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    key_packages::*,
+    test_utils::*,
+    treesync::{node::leaf_node::Capabilities, LeafNode, LeafPlacement, LeafPlacementStrategy},
+};
+
+#[apply(ciphersuites_and_backends)]
+fn service_member_placement(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+
+    // All members support the service member extension
+    let key_package = |name: &str, service_member: bool| {
+        let (credential_with_key, _kpb, signer, _pk) = setup_client(name, ciphersuite, backend);
+        let extensions = if service_member {
+            Extensions::single(Extension::ServiceMember(ServiceMemberExtension::new()))
+        } else {
+            Extensions::empty()
+        };
+        KeyPackage::builder()
+            .leaf_node_capabilities(Capabilities::new(
+                None,
+                Some(&[ciphersuite]),
+                Some(&[ExtensionType::ServiceMember]),
+                None,
+                None,
+            ))
+            .leaf_node_extensions(extensions)
+            .build(
+                CryptoConfig::with_default_version(ciphersuite),
+                backend,
+                &signer,
+                credential_with_key,
+            )
+            .expect("Error creating key package.")
+    };
+    let bob_key_package = key_package("Bob", false);
+    let charlie_key_package = key_package("Charlie", false);
+    let bot_key_package = key_package("Bot", true);
+    let dave_key_package = key_package("Dave", false);
+    assert!(bot_key_package.leaf_node().is_service_member());
+    assert!(!dave_key_package.leaf_node().is_service_member());
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .required_capabilities(RequiredCapabilitiesExtension::new(
+            &[ExtensionType::ServiceMember],
+            &[],
+            &[],
+        ))
+        .build();
+
+    // === Alice creates a group and adds Bob and Charlie ===
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_msg, welcome, _group_info) = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[bob_key_package, charlie_key_package],
+        )
+        .expect("Could not add members.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let mut charlie_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+
+    let mut commit_and_process = |alice_group: &mut MlsGroup, commit: MlsMessageOut| {
+        alice_group
+            .merge_pending_commit(backend)
+            .expect("error merging pending commit");
+        let processed_message = charlie_group
+            .process_message(backend, commit.into_protocol_message().unwrap())
+            .expect("Could not process message.");
+        match processed_message.into_content() {
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => charlie_group
+                .merge_staged_commit(backend, *staged_commit)
+                .expect("Error merging staged commit."),
+            _ => unreachable!("Expected a StagedCommit."),
+        }
+    };
+
+    // === Alice removes Bob, which leaves the leaves 1 and 3 blank ===
+    let (commit, _welcome, _group_info) = alice_group
+        .remove_members(backend, &alice_signer, &[LeafNodeIndex::new(1)])
+        .expect("Could not remove member.");
+    commit_and_process(&mut alice_group, commit);
+
+    // === Alice adds the bot and Dave ===
+    // The bot takes the right-most blank leaf, Dave the left-most one.
+    let (commit, _welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bot_key_package, dave_key_package])
+        .expect("Could not add members.");
+    commit_and_process(&mut alice_group, commit);
+
+    let leaf_index = |group: &MlsGroup, identity: &[u8]| {
+        group
+            .members()
+            .find(|member| member.credential.identity() == identity)
+            .expect("Member not found.")
+            .index
+    };
+    for group in [&alice_group, &charlie_group] {
+        assert_eq!(leaf_index(group, b"Bot"), LeafNodeIndex::new(3));
+        assert_eq!(leaf_index(group, b"Dave"), LeafNodeIndex::new(1));
+    }
+}
+
+/// A leaf placement that always extends the tree.
+struct ExtendingPlacement;
+
+impl LeafPlacement for ExtendingPlacement {
+    fn leaf_index(&self, leaves: &[Option<&LeafNode>], _leaf_node: &LeafNode) -> LeafNodeIndex {
+        LeafNodeIndex::new(leaves.len() as u32)
+    }
+}
+
+#[apply(ciphersuites_and_backends)]
+fn leaf_placement(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+    let (_dave_credential_with_key, dave_kpb, _dave_signer, _dave_pk) =
+        setup_client("Dave", ciphersuite, backend);
+    let (_eve_credential_with_key, eve_kpb, _eve_signer, _eve_pk) =
+        setup_client("Eve", ciphersuite, backend);
+    // A second device of Eve
+    let (_eve_credential_with_key, eve_kpb_2, _eve_signer, _eve_pk) =
+        setup_client("Eve", ciphersuite, backend);
+    let (_frank_credential_with_key, frank_kpb, _frank_signer, _frank_pk) =
+        setup_client("Frank", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .leaf_placement_strategy(LeafPlacementStrategy::Locality)
+        .build();
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    assert_eq!(
+        alice_group.group().public_group().leaf_placement_strategy(),
+        LeafPlacementStrategy::Locality
+    );
+
+    // Without related leaves, members are added at the left-most free leaves
+    let (_msg, welcome, _group_info) = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[
+                bob_kpb.key_package().clone(),
+                charlie_kpb.key_package().clone(),
+                dave_kpb.key_package().clone(),
+                eve_kpb.key_package().clone(),
+            ],
+        )
+        .expect("Could not add members.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let mut charlie_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    )
+    .expect("error creating group from welcome");
+
+    let member_index = |group: &MlsGroup, key_package_bundle: &KeyPackageBundle| {
+        group
+            .members()
+            .find(|member| {
+                member.signature_key
+                    == key_package_bundle
+                        .key_package()
+                        .leaf_node()
+                        .signature_key()
+                        .as_slice()
+            })
+            .map(|member| member.index)
+            .expect("Member not found.")
+    };
+    assert_eq!(member_index(&alice_group, &eve_kpb), LeafNodeIndex::new(4));
+
+    let process_commit = |group: &mut MlsGroup, commit: MlsMessageOut| {
+        let processed_message = group
+            .process_message(backend, commit.into_protocol_message().unwrap())
+            .expect("Could not process commit.");
+        match processed_message.into_content() {
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => group
+                .merge_staged_commit(backend, *staged_commit)
+                .expect("Error merging commit."),
+            _ => unreachable!("Expected a StagedCommit."),
+        }
+    };
+
+    // Remove Bob, so that there is a blank leaf on the left
+    let (commit, _welcome, _group_info) = alice_group
+        .remove_members(backend, &alice_signer, &[LeafNodeIndex::new(1)])
+        .expect("Could not remove member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    process_commit(&mut charlie_group, commit);
+
+    // The second device of Eve is added next to the first one instead of at
+    // the left-most blank leaf
+    let (commit, _welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[eve_kpb_2.key_package().clone()])
+        .expect("Could not add members.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    process_commit(&mut charlie_group, commit);
+    assert_eq!(
+        member_index(&alice_group, &eve_kpb_2),
+        LeafNodeIndex::new(5)
+    );
+    assert_eq!(
+        member_index(&charlie_group, &eve_kpb_2),
+        LeafNodeIndex::new(5)
+    );
+
+    // A custom placement takes precedence over the strategy
+    alice_group.set_leaf_placement(ExtendingPlacement);
+    charlie_group.set_leaf_placement(ExtendingPlacement);
+    let (commit, _welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[frank_kpb.key_package().clone()])
+        .expect("Could not add members.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    process_commit(&mut charlie_group, commit);
+    assert_eq!(
+        member_index(&alice_group, &frank_kpb),
+        LeafNodeIndex::new(8)
+    );
+    assert_eq!(
+        member_index(&charlie_group, &frank_kpb),
+        LeafNodeIndex::new(8)
+    );
+    assert_eq!(
+        alice_group.export_ratchet_tree(),
+        charlie_group.export_ratchet_tree()
+    );
+}
@@ -0,0 +1,177 @@
+//! This module tests buffering messages of future epochs.
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{
+    framing::*,
+    group::{config::CryptoConfig, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn message_buffer(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    // Buffer a single message from the next epoch
+    let mls_group_config = MlsGroupConfig::builder()
+        .wire_format_policy(MIXED_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .message_buffer_configuration(MessageBufferConfiguration::new(1, 1))
+        .build();
+
+    // === Alice creates a group and adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        mut bob_group,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+
+    // === Alice sends a commit and messages in the next two epochs ===
+    let mut commits = vec![];
+    let mut messages = vec![];
+    for _ in 0..2 {
+        let (commit, _welcome, _group_info) = alice_group
+            .self_update(backend, &alice_signer)
+            .expect("Error creating self-update.");
+        alice_group
+            .merge_pending_commit(backend)
+            .expect("error merging pending commit");
+        commits.push(commit.into_protocol_message().unwrap());
+        messages.push(
+            alice_group
+                .create_message(backend, &alice_signer, b"Hello Bob")
+                .expect("Error creating application message.")
+                .into_protocol_message()
+                .unwrap(),
+        );
+    }
+
+    // === Bob receives the messages before the commits ===
+    let err = bob_group
+        .process_message(backend, messages[0].clone())
+        .expect_err("Expected the message to be buffered.");
+    assert_eq!(err, ProcessMessageError::MessageBuffered);
+
+    // The buffer is full
+    let err = bob_group
+        .process_message(backend, messages[0].clone())
+        .expect_err("Expected the buffer to be full.");
+    assert_eq!(err, ProcessMessageError::MessageBufferFull);
+
+    // The second message is too far ahead to be buffered
+    let err = bob_group
+        .process_message(backend, messages[1].clone())
+        .expect_err("Expected the message to be rejected.");
+    assert_ne!(err, ProcessMessageError::MessageBuffered);
+    assert_ne!(err, ProcessMessageError::MessageBufferFull);
+    assert_eq!(bob_group.buffered_messages().len(), 1);
+
+    // === Bob merges the first commit, which processes the buffered message ===
+    let processed_message = bob_group
+        .process_message(backend, commits[0].clone())
+        .expect("Could not process message.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => bob_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging commit."),
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+    assert!(bob_group.process_buffered_messages(backend).is_empty());
+
+    let mut processed_messages = bob_group.take_processed_buffered_messages();
+    assert_eq!(processed_messages.len(), 1);
+    assert!(bob_group.take_processed_buffered_messages().is_empty());
+    match processed_messages.remove(0).into_content() {
+        ProcessedMessageContent::ApplicationMessage(application_message) => {
+            assert_eq!(application_message.into_bytes(), b"Hello Bob")
+        }
+        _ => unreachable!("Expected an application message."),
+    }
+    assert!(bob_group.buffered_messages().is_empty());
+}
+
+#[apply(ciphersuites_and_backends)]
+fn message_buffer_per_sender(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    // Buffer at most two messages per sender of the next epoch
+    let mls_group_config = MlsGroupConfig::builder()
+        .wire_format_policy(MIXED_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .message_buffer_configuration(
+            MessageBufferConfiguration::new(8, 1).with_max_messages_per_sender(2),
+        )
+        .build();
+
+    // === Alice creates a group and adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        mut bob_group,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+
+    // === Alice sends a commit and three messages in the next epoch ===
+    let (commit, _welcome, _group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Error creating self-update.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let messages: Vec<ProtocolMessage> = (0..3u8)
+        .map(|i| {
+            alice_group
+                .create_message(backend, &alice_signer, &[i])
+                .expect("Error creating application message.")
+                .into_protocol_message()
+                .unwrap()
+        })
+        .collect();
+
+    // === Bob receives the last two messages in reverse order ===
+    for message in [&messages[2], &messages[1]] {
+        let err = bob_group
+            .process_message(backend, message.clone())
+            .expect_err("Expected the message to be buffered.");
+        assert_eq!(err, ProcessMessageError::MessageBuffered);
+    }
+
+    // The buffer still has room, but not for another message of the epoch
+    // from the same sender
+    let err = bob_group
+        .process_message(backend, messages[0].clone())
+        .expect_err("Expected the buffer to be full.");
+    assert_eq!(err, ProcessMessageError::MessageBufferFull);
+
+    // === Bob merges the commit, which processes the buffered messages in
+    // the order of their generations ===
+    let processed_message = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => bob_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging commit."),
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+    assert!(bob_group.buffered_messages().is_empty());
+
+    let contents: Vec<Vec<u8>> = bob_group
+        .take_processed_buffered_messages()
+        .into_iter()
+        .map(|processed_message| match processed_message.into_content() {
+            ProcessedMessageContent::ApplicationMessage(application_message) => {
+                application_message.into_bytes()
+            }
+            _ => unreachable!("Expected an application message."),
+        })
+        .collect();
+    assert_eq!(contents, vec![vec![1], vec![2]]);
+
+    // The skipped first message can still be processed
+    let processed_message = bob_group
+        .process_message(backend, messages[0].clone())
+        .expect("Could not process message.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::ApplicationMessage(application_message) => {
+            assert_eq!(application_message.into_bytes(), vec![0])
+        }
+        _ => unreachable!("Expected an application message."),
+    }
+}
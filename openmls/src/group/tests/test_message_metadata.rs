@@ -0,0 +1,79 @@
+//! This module tests the metadata of messages that is available before
+//! processing.
+
+use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait};
+
+use crate::{
+    binary_tree::LeafNodeIndex,
+    framing::*,
+    group::{core_group::test_core_group::setup_client, *},
+    test_utils::*,
+    versions::ProtocolVersion,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn message_metadata(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let application_message = alice_group
+        .create_message(backend, &alice_signer, b"Hello, Bob!")
+        .expect("Could not create message.");
+
+    let deserialize = |message: MlsMessageOut| {
+        MlsMessageIn::tls_deserialize_exact(message.tls_serialize_detached().unwrap().as_slice())
+            .unwrap()
+    };
+
+    // === The commit is a PublicMessage with a visible sender ===
+    let commit = deserialize(commit);
+    assert_eq!(commit.version(), ProtocolVersion::Mls10);
+    assert_eq!(commit.wire_format(), WireFormat::PublicMessage);
+    assert_eq!(commit.group_id(), Some(alice_group.group_id()));
+    assert_eq!(commit.epoch(), Some(GroupEpoch::from(0)));
+    assert_eq!(commit.content_type(), Some(ContentType::Commit));
+    assert_eq!(
+        commit.sender(),
+        Some(&Sender::Member(LeafNodeIndex::new(0)))
+    );
+
+    // === The sender of a PrivateMessage is encrypted ===
+    let application_message = deserialize(application_message);
+    assert_eq!(
+        application_message.wire_format(),
+        WireFormat::PrivateMessage
+    );
+    assert_eq!(application_message.group_id(), Some(alice_group.group_id()));
+    assert_eq!(application_message.epoch(), Some(GroupEpoch::from(1)));
+    assert_eq!(
+        application_message.content_type(),
+        Some(ContentType::Application)
+    );
+    assert_eq!(application_message.sender(), None);
+    let protocol_message = application_message.into_protocol_message().unwrap();
+    assert_eq!(protocol_message.sender(), None);
+
+    // === A Welcome doesn't belong to a group yet ===
+    let welcome = deserialize(welcome);
+    assert_eq!(welcome.wire_format(), WireFormat::Welcome);
+    assert_eq!(welcome.group_id(), None);
+    assert_eq!(welcome.epoch(), None);
+    assert_eq!(welcome.content_type(), None);
+    assert_eq!(welcome.sender(), None);
+}
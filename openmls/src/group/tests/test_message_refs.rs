@@ -0,0 +1,57 @@
+//! This module tests the references of messages on the wire.
+
+use crate::{
+    framing::*,
+    group::{core_group::test_core_group::setup_client, *},
+    key_packages::*,
+    messages::proposals::*,
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn wire_message_refs(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    // === The key package reference doesn't depend on validation ===
+    let key_package_in = KeyPackageIn::from(bob_kpb.key_package().clone());
+    assert_eq!(
+        key_package_in.hash_ref(backend.crypto()).unwrap(),
+        bob_kpb.key_package().hash_ref(backend.crypto()).unwrap()
+    );
+
+    // === The proposal reference matches the one of the proposal store ===
+    let (proposal, proposal_ref) = alice_group
+        .propose_add_member(backend, &alice_signer, bob_kpb.key_package())
+        .expect("Error creating proposal.");
+    let proposal = proposal.into_protocol_message().unwrap();
+    assert_eq!(
+        proposal
+            .proposal_ref(backend.crypto(), ciphersuite)
+            .unwrap(),
+        proposal_ref
+    );
+
+    // === Other messages don't have a proposal reference ===
+    let (commit, _welcome, _group_info) = alice_group
+        .commit_to_pending_proposals(backend, &alice_signer)
+        .expect("Could not commit.");
+    let commit = commit.into_protocol_message().unwrap();
+    assert_eq!(
+        commit.proposal_ref(backend.crypto(), ciphersuite),
+        Err(ProposalRefError::AuthenticatedContentHasWrongType {
+            wrong: ContentType::Commit
+        })
+    );
+}
@@ -0,0 +1,133 @@
+//! This module tests the policy for deleting message secrets.
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{
+    framing::*,
+    group::{config::CryptoConfig, *},
+    test_utils::*,
+    tree::sender_ratchet::SenderRatchetConfiguration,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn message_secrets_policy(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let policy = MessageSecretsPolicy::new(
+        PastEpochRetention::new(2),
+        SenderRatchetConfiguration::default(),
+    );
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .message_secrets_policy(policy.clone())
+        .build();
+    assert_eq!(mls_group_config.message_secrets_policy(), policy);
+
+    // === Alice creates a group and adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        mut bob_group,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+    assert_eq!(bob_group.message_secrets_policy(), policy);
+
+    let next_epoch = |alice_group: &mut MlsGroup, bob_group: &mut MlsGroup| {
+        let (commit, _welcome, _group_info) = alice_group
+            .self_update(backend, &alice_signer)
+            .expect("Error creating self update.");
+        alice_group
+            .merge_pending_commit(backend)
+            .expect("error merging pending commit");
+        let processed_message = bob_group
+            .process_message(backend, commit.into_protocol_message().unwrap())
+            .expect("Could not process commit.");
+        match processed_message.into_content() {
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => bob_group
+                .merge_staged_commit(backend, *staged_commit)
+                .expect("Error merging commit."),
+            _ => unreachable!("Expected a StagedCommit."),
+        }
+    };
+
+    // === Bob receives messages out of order and keeps the skipped keys ===
+    next_epoch(&mut alice_group, &mut bob_group);
+    let messages = [&b"first"[..], b"second", b"third", b"fourth"].map(|message| {
+        alice_group
+            .create_message(backend, &alice_signer, message)
+            .expect("Error creating application message.")
+            .into_protocol_message()
+            .expect("Unexpected message type.")
+    });
+    for message in [&messages[3], &messages[0]] {
+        bob_group
+            .process_message(backend, message.clone())
+            .expect("Could not process message.");
+    }
+    next_epoch(&mut alice_group, &mut bob_group);
+    next_epoch(&mut alice_group, &mut bob_group);
+    assert_eq!(
+        bob_group.past_epochs(),
+        vec![GroupEpoch::from(2), GroupEpoch::from(3)]
+    );
+
+    // === Bob tightens the policy, which applies immediately ===
+    let tightened_policy = policy
+        .with_past_epoch_retention(PastEpochRetention::new(1))
+        .with_sender_ratchet_configuration(
+            SenderRatchetConfiguration::default().with_maximum_skipped_keys(1),
+        );
+    bob_group
+        .set_message_secrets_policy(backend, tightened_policy.clone())
+        .expect("error setting message secrets policy");
+    assert_eq!(bob_group.message_secrets_policy(), tightened_policy);
+    assert_eq!(bob_group.past_epochs(), vec![GroupEpoch::from(3)]);
+    let loaded_group = MlsGroup::load(bob_group.group_id(), backend).expect("error loading group");
+    assert_eq!(loaded_group.message_secrets_policy(), tightened_policy);
+
+    // The messages of epoch 2 can't be decrypted anymore
+    let err = bob_group
+        .process_message(backend, messages[1].clone())
+        .expect_err("Bob decrypted a message from a dropped epoch.");
+    assert_eq!(
+        err,
+        ProcessMessageError::ValidationError(ValidationError::UnableToDecrypt(
+            MessageDecryptionError::AeadError
+        ))
+    );
+
+    // === Bob relaxes the policy again, which only applies to new secrets ===
+    bob_group
+        .set_message_secrets_policy(backend, MessageSecretsPolicy::default())
+        .expect("error setting message secrets policy");
+    let messages = [&b"fifth"[..], b"sixth", b"seventh"].map(|message| {
+        alice_group
+            .create_message(backend, &alice_signer, message)
+            .expect("Error creating application message.")
+            .into_protocol_message()
+            .expect("Unexpected message type.")
+    });
+    bob_group
+        .process_message(backend, messages[2].clone())
+        .expect("Could not process message.");
+
+    // Only the key of the newest skipped message is kept once tightened
+    bob_group
+        .set_message_secrets_policy(backend, tightened_policy)
+        .expect("error setting message secrets policy");
+    let err = bob_group
+        .process_message(backend, messages[0].clone())
+        .expect_err("Bob decrypted a message with an evicted key.");
+    assert_eq!(
+        err,
+        ProcessMessageError::ValidationError(ValidationError::UnableToDecrypt(
+            MessageDecryptionError::GenerationOutOfBound
+        ))
+    );
+    let processed_message = bob_group
+        .process_message(backend, messages[1].clone())
+        .expect("Could not process message.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::ApplicationMessage(application_message) => {
+            assert_eq!(application_message.into_bytes(), b"sixth")
+        }
+        _ => unreachable!("Expected an application message."),
+    }
+}
@@ -0,0 +1,77 @@
+//! This module tests decoding messages from bytes.
+
+use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait};
+
+use crate::{
+    framing::*,
+    group::{core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn messages_from_bytes(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        GroupId::from_slice(b"Test Group"),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let (_msg, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    // Messages that aren't private messages are deserialized as usual.
+    let welcome = MlsMessageIn::from_bytes(welcome.tls_serialize_detached().unwrap())
+        .expect("Could not deserialize the welcome.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+
+    let message = alice_group
+        .create_message(backend, &alice_signer, b"Hello Bob")
+        .expect("Error creating application message.");
+    let serialized_message = message.tls_serialize_detached().unwrap();
+
+    // The message is the same as with the regular deserialization.
+    let message = MlsMessageIn::from_bytes(serialized_message.clone())
+        .expect("Could not deserialize the message.");
+    assert_eq!(
+        message,
+        MlsMessageIn::tls_deserialize_exact(&serialized_message).unwrap()
+    );
+
+    // Trailing data is rejected.
+    let mut trailing_data = serialized_message;
+    trailing_data.push(0);
+    assert_eq!(
+        MlsMessageIn::from_bytes(trailing_data),
+        Err(tls_codec::Error::TrailingData)
+    );
+
+    let processed_message = bob_group
+        .process_message(backend, message.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::ApplicationMessage(application_message) => {
+            assert_eq!(application_message.into_bytes(), b"Hello Bob")
+        }
+        _ => panic!("Expected an application message."),
+    }
+}
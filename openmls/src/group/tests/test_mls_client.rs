@@ -0,0 +1,192 @@
+//! This module tests the `MlsClient`, which manages the groups of a client.
+
+use std::time::SystemTime;
+
+use openmls_traits::storage::{StorageKey, StorageProvider};
+
+use crate::{
+    framing::*,
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    test_utils::*,
+    tree::secret_tree::SenderRatchets,
+};
+
+#[apply(ciphersuites)]
+fn mls_client(ciphersuite: Ciphersuite) {
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .build();
+    let alice_backend = OpenMlsRustCrypto::default();
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, &alice_backend);
+    let mut alice = MlsClient::new(
+        alice_backend,
+        alice_signer,
+        alice_credential_with_key,
+        mls_group_config.clone(),
+    );
+    let bob_backend = OpenMlsRustCrypto::default();
+    let (bob_credential_with_key, _bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, &bob_backend);
+    let mut bob = MlsClient::new(
+        bob_backend,
+        bob_signer,
+        bob_credential_with_key,
+        mls_group_config,
+    );
+
+    // === Bob publishes key packages ===
+    let key_packages = bob
+        .replenish_key_packages(2, SystemTime::now())
+        .expect("Error creating key packages.");
+    assert_eq!(key_packages.len(), 2);
+    assert!(bob
+        .replenish_key_packages(2, SystemTime::now())
+        .expect("Error creating key packages.")
+        .is_empty());
+
+    // === Alice creates a group and adds Bob ===
+    let group_id = alice.create_group().expect("Error creating group.");
+    let (alice_group, backend, signer) = alice.group_mut(&group_id).unwrap();
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, signer, &key_packages[..1])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let joined = bob
+        .process_message(welcome.into())
+        .expect("Error processing welcome.");
+    assert!(matches!(joined, ClientMessage::Joined(id) if id == group_id));
+    assert_eq!(bob.key_packages(), &key_packages[1..]);
+
+    // === A Welcome can't replace a group of the client ===
+    let mallory_backend = OpenMlsRustCrypto::default();
+    let (mallory_credential_with_key, _mallory_kpb, mallory_signer, _mallory_pk) =
+        setup_client("Mallory", ciphersuite, &mallory_backend);
+    let mut mallory_group = MlsGroup::new_with_group_id(
+        &mallory_backend,
+        &mallory_signer,
+        alice.mls_group_config(),
+        group_id.clone(),
+        mallory_credential_with_key,
+    )
+    .expect("Error creating group.");
+    let (_commit, welcome, _group_info) = mallory_group
+        .add_members(&mallory_backend, &mallory_signer, &key_packages[1..])
+        .expect("Could not add member.");
+    assert_eq!(
+        bob.process_message(welcome.into()).unwrap_err(),
+        ClientProcessMessageError::WelcomeError(WelcomeError::GroupAlreadyExists(group_id.clone()))
+    );
+    assert_eq!(bob.group(&group_id).unwrap().epoch(), GroupEpoch::from(1));
+    assert_eq!(bob.key_packages(), &key_packages[1..]);
+
+    // === Messages are routed to their group ===
+    let (alice_group, backend, signer) = alice.group_mut(&group_id).unwrap();
+    let message = alice_group
+        .create_message(backend, signer, b"Hello, Bob!")
+        .expect("Could not create message.");
+    let ClientMessage::Processed(processed_message) = bob
+        .process_message(message.into())
+        .expect("Error processing message.")
+    else {
+        panic!("Expected a processed message.");
+    };
+    assert_eq!(processed_message.group_id(), &group_id);
+    match processed_message.into_content() {
+        ProcessedMessageContent::ApplicationMessage(message) => {
+            assert_eq!(message.into_bytes(), b"Hello, Bob!")
+        }
+        _ => panic!("Expected an application message."),
+    }
+
+    let other_group_id = alice.create_group().expect("Error creating group.");
+    let (other_group, backend, signer) = alice.group_mut(&other_group_id).unwrap();
+    let message = other_group
+        .create_message(backend, signer, b"Hello?")
+        .expect("Could not create message.");
+    assert!(matches!(
+        bob.process_message(message.into()),
+        Err(ClientProcessMessageError::UnknownGroup(id)) if id == other_group_id
+    ));
+
+    // === Groups are restored from the storage ===
+    assert_eq!(alice.group_ids().count(), 2);
+    let unknown_group_id = GroupId::from_slice(b"Unknown Group");
+    let missing = alice
+        .load_groups([&group_id, &unknown_group_id])
+        .expect("Error loading groups.");
+    assert_eq!(missing, vec![unknown_group_id]);
+    assert_eq!(
+        alice.group(&group_id).map(|group| group.epoch()),
+        Some(GroupEpoch::from(1))
+    );
+
+    // === Removed groups are deleted from the storage ===
+    alice
+        .remove_group(&other_group_id)
+        .expect("Error removing group.")
+        .expect("Unknown group.");
+    assert_eq!(alice.group_ids().collect::<Vec<_>>(), vec![&group_id]);
+    let missing = alice
+        .load_groups([&other_group_id])
+        .expect("Error loading groups.");
+    assert_eq!(missing, vec![other_group_id.clone()]);
+    assert!(MlsGroup::load(&other_group_id, alice.backend()).is_none());
+
+    // === The whole client is restored from the storage ===
+    let mls_group_config = bob.mls_group_config().clone();
+    let (bob_backend, bob_signer, bob_credential_with_key) = bob.into_parts();
+    let (mut bob, missing) = MlsClient::load(
+        bob_backend,
+        bob_signer,
+        bob_credential_with_key,
+        mls_group_config,
+    );
+    assert!(missing.is_empty());
+    assert_eq!(bob.group_ids().collect::<Vec<_>>(), vec![&group_id]);
+    assert_eq!(bob.key_packages(), &key_packages[1..]);
+
+    // === Removing a group also deletes the sender ratchets written for it ===
+    let (alice_group, backend, signer) = alice.group_mut(&group_id).unwrap();
+    let message = alice_group
+        .create_message(backend, signer, b"Still there?")
+        .expect("Could not create message.");
+    bob.group(&group_id)
+        .unwrap()
+        .process_application_message(bob.backend(), message.into_protocol_message().unwrap())
+        .expect("Error processing message.");
+    let receiver_state_key = StorageKey::ReceiverState {
+        group_id: group_id.as_slice(),
+    };
+    let receiver_state: ReceiverState = bob
+        .backend()
+        .key_store()
+        .load(receiver_state_key)
+        .expect("The receiver state wasn't stored.");
+    assert_eq!(receiver_state.sender_ratchets().len(), 1);
+    bob.remove_group(&group_id)
+        .expect("Error removing group.")
+        .expect("Unknown group.");
+    assert!(MlsGroup::load(&group_id, bob.backend()).is_none());
+    assert!(bob
+        .backend()
+        .key_store()
+        .load::<ReceiverState>(receiver_state_key)
+        .is_none());
+    for &(epoch, leaf_index) in receiver_state.sender_ratchets() {
+        let sender_ratchets_key = StorageKey::SenderRatchets {
+            group_id: group_id.as_slice(),
+            epoch: epoch.as_u64(),
+            leaf_index: leaf_index.u32(),
+        };
+        assert!(bob
+            .backend()
+            .key_store()
+            .load::<SenderRatchets>(sender_ratchets_key)
+            .is_none());
+    }
+}
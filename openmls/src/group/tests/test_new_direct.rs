@@ -0,0 +1,61 @@
+//! This module tests creating a group directly with its initial members.
+
+use crate::{
+    framing::*,
+    group::{core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn new_direct(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential, bob_kpb, bob_signer, _bob_pk) = setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a 1:1 group with Bob ===
+    let (mut alice_group, welcome) = MlsGroup::new_direct(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+        bob_kpb.key_package().clone(),
+    )
+    .expect("Error creating direct group.");
+    assert!(alice_group.pending_commit().is_none());
+    assert_eq!(alice_group.members().count(), 2);
+
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("Error creating group from Welcome");
+    assert_eq!(
+        alice_group.export_secret(backend, "test", &[], 32).unwrap(),
+        bob_group.export_secret(backend, "test", &[], 32).unwrap()
+    );
+
+    // === Bob updates, which encrypts to the single copath node ===
+    let (commit, _welcome, _group_info) = bob_group
+        .self_update(backend, &bob_signer)
+        .expect("Error updating.");
+    bob_group
+        .merge_pending_commit(backend)
+        .expect("Error merging pending commit.");
+    let processed_message = alice_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process commit.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => alice_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging commit."),
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+    assert_eq!(
+        alice_group.export_secret(backend, "test", &[], 32).unwrap(),
+        bob_group.export_secret(backend, "test", &[], 32).unwrap()
+    );
+}
@@ -0,0 +1,80 @@
+//! This module tests processing own messages that the DS echoes.
+
+use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait};
+
+use crate::{
+    framing::*,
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn own_message_echo(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    for wire_format_policy in [
+        PURE_PLAINTEXT_WIRE_FORMAT_POLICY,
+        PURE_CIPHERTEXT_WIRE_FORMAT_POLICY,
+    ] {
+        let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+            setup_client("Alice", ciphersuite, backend);
+        let mls_group_config = MlsGroupConfig::builder()
+            .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+            .wire_format_policy(wire_format_policy)
+            .build();
+        let mut alice_group = MlsGroup::new(
+            backend,
+            &alice_signer,
+            &mls_group_config,
+            alice_credential_with_key,
+        )
+        .expect("An unexpected error occurred.");
+
+        // === The DS echoes an own proposal ===
+        let (proposal, _proposal_ref) = alice_group
+            .propose_self_update(backend, &alice_signer, None)
+            .expect("Error creating proposal.");
+        let err = alice_group
+            .process_message(backend, proposal.clone().into_protocol_message().unwrap())
+            .expect_err("Processed an own proposal.");
+        assert_eq!(err, ProcessMessageError::OwnProposal);
+
+        // A modified copy of an own message is not recognized, but processed
+        // and rejected like any other forged message.
+        let mut tampered = proposal.tls_serialize_detached().unwrap();
+        *tampered.last_mut().unwrap() ^= 1;
+        let tampered = MlsMessageIn::tls_deserialize(&mut tampered.as_slice())
+            .expect("Error deserializing message.");
+        let err = alice_group
+            .process_message(backend, tampered.into_protocol_message().unwrap())
+            .expect_err("Processed a tampered proposal.");
+        assert_ne!(err, ProcessMessageError::OwnProposal);
+        assert_ne!(err, ProcessMessageError::OwnCommit);
+        assert_ne!(err, ProcessMessageError::UnknownOwnCommit);
+        alice_group.clear_pending_proposals();
+
+        // === A replaced commit is echoed ===
+        let (replaced_commit, _welcome, _group_info) = alice_group
+            .self_update(backend, &alice_signer)
+            .expect("Error creating self-update.");
+        alice_group
+            .clear_pending_commit(backend)
+            .expect("Error clearing pending commit.");
+        let (commit, _welcome, _group_info) = alice_group
+            .self_update(backend, &alice_signer)
+            .expect("Error creating self-update.");
+        let err = alice_group
+            .process_message(backend, replaced_commit.into_protocol_message().unwrap())
+            .expect_err("Processed a replaced own commit.");
+        assert_eq!(err, ProcessMessageError::UnknownOwnCommit);
+
+        // === The pending commit is echoed and merged ===
+        let epoch = alice_group.epoch();
+        let err = alice_group
+            .process_message(backend, commit.into_protocol_message().unwrap())
+            .expect_err("Processed the pending commit.");
+        assert_eq!(err, ProcessMessageError::OwnCommit);
+        alice_group
+            .merge_pending_commit(backend)
+            .expect("error merging pending commit");
+        assert_eq!(alice_group.epoch().as_u64(), epoch.as_u64() + 1);
+    }
+}
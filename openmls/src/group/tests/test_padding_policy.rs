@@ -0,0 +1,61 @@
+//! This module tests the padding of private messages.
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{
+    framing::*,
+    group::{config::CryptoConfig, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn padding_policy(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    // The padding size and the padding policy are two views of the same setting
+    let config = MlsGroupConfig::builder().padding_size(64).build();
+    assert_eq!(config.padding_policy(), PaddingPolicy::BlockSize(64));
+    let config = MlsGroupConfig::builder()
+        .padding_size(64)
+        .padding_policy(PaddingPolicy::Padme)
+        .build();
+    assert_eq!(config.padding_policy(), PaddingPolicy::Padme);
+    assert_eq!(config.padding_size(), 0);
+    assert_eq!(
+        MlsGroupConfig::default().padding_policy(),
+        PaddingPolicy::None
+    );
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .padding_policy(PaddingPolicy::Padme)
+        .build();
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        mut bob_group,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+
+    // Padded messages of different lengths are processed as usual
+    for length in [0, 1, 100, 1_000, 10_000] {
+        let payload = vec![0x2a; length];
+        let message = alice_group
+            .create_message(backend, &alice_signer, &payload)
+            .expect("Error creating message.");
+        let ciphertext_length = match &message.body {
+            MlsMessageOutBody::PrivateMessage(private_message) => {
+                private_message.ciphertext().len()
+            }
+            _ => panic!("Unexpected message type."),
+        };
+        assert_eq!(PaddingPolicy::Padme.padding_length(ciphertext_length), 0);
+
+        let processed_message = bob_group
+            .process_message(backend, message.into_protocol_message().unwrap())
+            .expect("Error processing message.");
+        match processed_message.into_content() {
+            ProcessedMessageContent::ApplicationMessage(application_message) => {
+                assert_eq!(application_message.into_bytes(), payload)
+            }
+            _ => panic!("Unexpected message type."),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! This module tests the secrets that members derive for pairs of members.
+
+use crate::{
+    binary_tree::LeafNodeIndex,
+    framing::*,
+    group::{core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn pairwise_secret(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds Bob and Charlie ===
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let (_msg, welcome, _group_info) = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[
+                bob_kpb.key_package().clone(),
+                charlie_kpb.key_package().clone(),
+            ],
+        )
+        .expect("Could not add members.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let welcome = welcome.into_welcome().expect("Unexpected message type.");
+
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.clone(),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+    let charlie_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome,
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+
+    let alice = alice_group.own_leaf_index();
+    let bob = bob_group.own_leaf_index();
+    let charlie = charlie_group.own_leaf_index();
+
+    // Both members of a pair derive the same secret
+    let alice_bob = alice_group
+        .pairwise_secret(backend, bob, "file transfer", 32)
+        .expect("Error deriving pairwise secret.");
+    assert_eq!(alice_bob.len(), 32);
+    assert_eq!(
+        bob_group
+            .pairwise_secret(backend, alice, "file transfer", 32)
+            .expect("Error deriving pairwise secret."),
+        alice_bob
+    );
+
+    // The secret is bound to the pair and the label
+    let alice_charlie = alice_group
+        .pairwise_secret(backend, charlie, "file transfer", 32)
+        .expect("Error deriving pairwise secret.");
+    assert_eq!(
+        charlie_group
+            .pairwise_secret(backend, alice, "file transfer", 32)
+            .expect("Error deriving pairwise secret."),
+        alice_charlie
+    );
+    assert_ne!(alice_bob, alice_charlie);
+    assert_ne!(
+        alice_group
+            .pairwise_secret(backend, bob, "other", 32)
+            .expect("Error deriving pairwise secret."),
+        alice_bob
+    );
+
+    assert_eq!(
+        alice_group.pairwise_secret(backend, alice, "file transfer", 32),
+        Err(PairwiseSecretError::UnknownMember)
+    );
+    assert_eq!(
+        alice_group.pairwise_secret(backend, LeafNodeIndex::new(5), "file transfer", 32),
+        Err(PairwiseSecretError::UnknownMember)
+    );
+
+    // The secret changes when one of the leaves is updated
+    let (commit, _welcome, _group_info) = bob_group
+        .self_update(backend, &bob_signer)
+        .expect("Error creating self update.");
+    bob_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let processed_message = alice_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process message.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => alice_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging staged commit."),
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+    let new_alice_bob = alice_group
+        .pairwise_secret(backend, bob, "file transfer", 32)
+        .expect("Error deriving pairwise secret.");
+    assert_ne!(new_alice_bob, alice_bob);
+    assert_eq!(
+        bob_group
+            .pairwise_secret(backend, alice, "file transfer", 32)
+            .expect("Error deriving pairwise secret."),
+        new_alice_bob
+    );
+}
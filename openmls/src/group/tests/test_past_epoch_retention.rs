@@ -0,0 +1,85 @@
+//! This module tests retaining the state of past epochs.
+
+use std::time::Duration;
+
+use crate::{
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn past_epoch_retention(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .past_epoch_retention(PastEpochRetention::new(2))
+        .build();
+    assert_eq!(
+        mls_group_config.past_epoch_retention(),
+        PastEpochRetention::new(2)
+    );
+
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    for _ in 0..3 {
+        alice_group
+            .self_update(backend, &alice_signer)
+            .expect("Error creating self update.");
+        alice_group
+            .merge_pending_commit(backend)
+            .expect("error merging pending commit");
+    }
+
+    // Only the secrets of the last two past epochs are kept
+    assert_eq!(
+        alice_group.past_epochs(),
+        vec![GroupEpoch::from(1), GroupEpoch::from(2)]
+    );
+
+    // Force-drop the secrets of epoch 1
+    assert_eq!(
+        alice_group
+            .drop_past_epochs(backend, GroupEpoch::from(2))
+            .expect("error dropping past epochs"),
+        1
+    );
+    assert_eq!(alice_group.past_epochs(), vec![GroupEpoch::from(2)]);
+    let loaded_group =
+        MlsGroup::load(alice_group.group_id(), backend).expect("error loading group");
+    assert_eq!(loaded_group.past_epochs(), vec![GroupEpoch::from(2)]);
+
+    // No maximum age is set, so nothing expires
+    assert_eq!(
+        alice_group
+            .drop_expired_past_epochs(backend)
+            .expect("error dropping expired past epochs"),
+        0
+    );
+
+    // A maximum age of zero drops all secrets of past epochs
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .past_epoch_retention(PastEpochRetention::new(2).with_max_age(Duration::ZERO))
+        .build();
+    alice_group
+        .set_configuration(backend, &mls_group_config)
+        .expect("error setting configuration");
+    assert!(alice_group.past_epochs().is_empty());
+
+    // New past epochs are not kept either
+    alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Error creating self update.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    assert!(alice_group.past_epochs().is_empty());
+}
@@ -0,0 +1,121 @@
+//! This module tests sending commits as public messages regardless of the
+//! wire format policy.
+
+use crate::{
+    framing::*,
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn plaintext_commit_override(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let strict_config = MlsGroupConfig::builder()
+        .wire_format_policy(PURE_CIPHERTEXT_WIRE_FORMAT_POLICY)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+    let mls_group_config = MlsGroupConfig::builder()
+        .wire_format_policy(PURE_CIPHERTEXT_WIRE_FORMAT_POLICY.with_plaintext_commits(true))
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+
+    // === Alice creates a group and adds Bob and Charlie ===
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key.clone(),
+    )
+    .expect("An unexpected error occurred.");
+    let (_msg, welcome, _group_info) = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[
+                bob_kpb.key_package().clone(),
+                charlie_kpb.key_package().clone(),
+            ],
+        )
+        .expect("Could not add members.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let welcome = welcome.into_welcome().expect("Unexpected message type.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.clone(),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+    // Charlie doesn't accept plaintext commits.
+    let mut charlie_group = MlsGroup::new_from_welcome(
+        backend,
+        &strict_config,
+        welcome,
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+
+    // === Alice sends a single commit as PublicMessage ===
+    let (commit, _welcome, _group_info) = alice_group
+        .commit_builder()
+        .wire_format(OutgoingWireFormatPolicy::AlwaysPlaintext)
+        .finalize(backend, &alice_signer)
+        .expect("Error creating commit.");
+    let commit = commit.into_protocol_message().unwrap();
+    assert_eq!(commit.wire_format(), WireFormat::PublicMessage);
+
+    assert_eq!(
+        charlie_group
+            .process_message(backend, commit.clone())
+            .expect_err("Plaintext commit accepted."),
+        ProcessMessageError::IncompatibleWireFormat
+    );
+
+    let processed_message = bob_group
+        .process_message(backend, commit)
+        .expect("Could not process plaintext commit.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => bob_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging staged commit."),
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    assert_eq!(alice_group.epoch(), bob_group.epoch());
+
+    // The next commit is encrypted again.
+    let (commit, _welcome, _group_info) = alice_group
+        .commit_builder()
+        .finalize(backend, &alice_signer)
+        .expect("Error creating commit.");
+    assert_eq!(
+        commit.into_protocol_message().unwrap().wire_format(),
+        WireFormat::PrivateMessage
+    );
+
+    // Without the exception in the policy, the override is rejected.
+    let mut strict_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &strict_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let err = strict_group
+        .commit_builder()
+        .wire_format(OutgoingWireFormatPolicy::AlwaysPlaintext)
+        .finalize(backend, &alice_signer)
+        .expect_err("Plaintext commit created.");
+    assert_eq!(err, CommitBuilderError::IncompatibleWireFormat);
+}
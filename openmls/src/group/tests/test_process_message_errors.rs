@@ -0,0 +1,96 @@
+//! This module tests the kinds of errors that processing a message returns.
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{
+    framing::*,
+    group::{core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn process_message_error_kind(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        mut bob_group,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+
+    // === Bob processes a commit before the proposal it covers ===
+    let (proposal, _proposal_ref) = alice_group
+        .propose_add_member(backend, &alice_signer, charlie_kpb.key_package())
+        .expect("Error creating proposal.");
+    let (commit, _welcome, _group_info) = alice_group
+        .commit_to_pending_proposals(backend, &alice_signer)
+        .expect("Error committing proposals.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let commit = commit.into_protocol_message().unwrap();
+    let err = bob_group
+        .process_message(backend, commit.clone())
+        .expect_err("Processed a commit with a missing proposal.");
+    assert_eq!(err.kind(), ProcessMessageErrorKind::StaleProposal);
+
+    // === The commit is processed once the proposal was ===
+    let processed_message = bob_group
+        .process_message(backend, proposal.into_protocol_message().unwrap())
+        .expect("Could not process proposal.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::ProposalMessage(proposal) => bob_group
+            .store_pending_proposal(backend, *proposal)
+            .expect("Could not store proposal."),
+        _ => unreachable!("Expected a proposal."),
+    }
+    let epoch = bob_group.epoch();
+    let processed_message = bob_group
+        .process_message(backend, commit.clone())
+        .expect("Could not process commit.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => bob_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging commit."),
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+
+    // === The commit is stale in the next epoch ===
+    let err = bob_group
+        .process_message(backend, commit)
+        .expect_err("Processed a commit twice.");
+    assert_eq!(
+        err.kind(),
+        ProcessMessageErrorKind::WrongEpoch {
+            received: epoch,
+            expected: bob_group.epoch(),
+        }
+    );
+
+    // === Bob is removed and can't process handshake messages anymore ===
+    let (commit, _welcome, _group_info) = alice_group
+        .remove_members(backend, &alice_signer, &[bob_group.own_leaf_index()])
+        .expect("Could not remove member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let processed_message = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process commit.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => bob_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging commit."),
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+    let (commit, _welcome, _group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Error creating self-update.");
+    let err = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect_err("Processed a commit after the removal.");
+    assert_eq!(err.kind(), ProcessMessageErrorKind::UseAfterEviction);
+}
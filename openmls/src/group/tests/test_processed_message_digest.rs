@@ -0,0 +1,54 @@
+//! This module tests the digest of processed messages.
+
+use openmls_traits::crypto::OpenMlsCrypto;
+use tls_codec::Serialize as TlsSerializeTrait;
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{framing::*, group::*, test_utils::*};
+
+#[apply(ciphersuites_and_backends)]
+fn processed_message_digest(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        mut bob_group,
+        bob_signer,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+
+    let expected_digest = |message: &MlsMessageOut| {
+        backend
+            .crypto()
+            .hash(
+                ciphersuite.hash_algorithm(),
+                &message.tls_serialize_detached().unwrap(),
+            )
+            .unwrap()
+    };
+
+    // The digest of an application message is the hash of the serialized
+    // message.
+    let message = alice_group
+        .create_message(backend, &alice_signer, b"Hello Bob")
+        .unwrap();
+    let processed_message = bob_group
+        .process_message(backend, message.clone().into_protocol_message().unwrap())
+        .unwrap();
+    assert_eq!(
+        processed_message.message_digest(),
+        expected_digest(&message).as_slice()
+    );
+
+    // The same holds for a commit.
+    let (commit, _, _) = bob_group.self_update(backend, &bob_signer).unwrap();
+    let processed_message = alice_group
+        .process_message(backend, commit.clone().into_protocol_message().unwrap())
+        .unwrap();
+    assert_eq!(
+        processed_message.message_digest(),
+        expected_digest(&commit).as_slice()
+    );
+}
@@ -0,0 +1,107 @@
+//! This module tests retracting own proposals.
+
+use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait};
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{
+    framing::*,
+    group::{core_group::test_core_group::setup_client, *},
+    messages::proposal_retraction::ProposalRetraction,
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn cancel_own_proposal(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        mut bob_group,
+        bob_signer,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+
+    // === Bob proposes to add Charlie and Alice stores the proposal ===
+    let (proposal, reference) = bob_group
+        .propose_add_member(backend, &bob_signer, charlie_kpb.key_package())
+        .unwrap();
+    let processed_message = alice_group
+        .process_message(backend, proposal.into_protocol_message().unwrap())
+        .unwrap();
+    match processed_message.into_content() {
+        ProcessedMessageContent::ProposalMessage(proposal) => alice_group
+            .store_pending_proposal(backend, *proposal)
+            .unwrap(),
+        _ => unreachable!("Expected a proposal."),
+    }
+    assert_eq!(bob_group.own_pending_proposals().count(), 1);
+    assert_eq!(alice_group.pending_proposals().count(), 1);
+    assert_eq!(alice_group.own_pending_proposals().count(), 0);
+
+    // Members can only cancel their own proposals
+    assert_eq!(
+        alice_group.cancel_own_proposal(backend, reference.clone()),
+        Err(RemoveProposalError::GroupStateError(
+            MlsGroupStateError::PendingProposalNotFound
+        ))
+    );
+
+    // === Bob cancels the proposal and sends the retraction ===
+    let retraction = bob_group
+        .cancel_own_proposal(backend, reference.clone())
+        .unwrap();
+    assert_eq!(retraction.proposal_ref(), &reference);
+    assert_eq!(retraction.epoch(), bob_group.epoch());
+    assert_eq!(bob_group.own_pending_proposals().count(), 0);
+
+    let message = bob_group
+        .create_message(
+            backend,
+            &bob_signer,
+            &retraction.tls_serialize_detached().unwrap(),
+        )
+        .unwrap();
+    let processed_message = alice_group
+        .process_message(backend, message.into_protocol_message().unwrap())
+        .unwrap();
+    let sender = match processed_message.sender() {
+        Sender::Member(leaf_index) => *leaf_index,
+        _ => unreachable!("Expected a member sender."),
+    };
+    let received = match processed_message.into_content() {
+        ProcessedMessageContent::ApplicationMessage(message) => message.into_bytes(),
+        _ => unreachable!("Expected an application message."),
+    };
+    let received = ProposalRetraction::tls_deserialize_exact(received).unwrap();
+
+    // Only the sender of the proposal can retract it
+    assert_eq!(
+        alice_group.process_proposal_retraction(backend, alice_group.own_leaf_index(), &received),
+        Err(RemoveProposalError::GroupStateError(
+            MlsGroupStateError::PendingProposalNotFound
+        ))
+    );
+    alice_group
+        .process_proposal_retraction(backend, sender, &received)
+        .unwrap();
+    assert_eq!(alice_group.pending_proposals().count(), 0);
+
+    // The next commit doesn't add Charlie
+    let (commit, _, _) = alice_group
+        .commit_to_pending_proposals(backend, &alice_signer)
+        .unwrap();
+    let processed_message = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .unwrap();
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(commit) => {
+            assert!(commit.add_proposals().next().is_none())
+        }
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+}
@@ -0,0 +1,140 @@
+//! This module tests storing queued proposals and the conflicts between them.
+
+use openmls_traits::storage::{StorageKey, StorageProvider};
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{
+    framing::*,
+    group::{core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+// Queued proposals are stored per epoch and survive reloading the group.
+#[apply(ciphersuites_and_backends)]
+fn queued_proposals_persistence(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &MlsGroupConfig::test_default(ciphersuite),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let group_id = alice_group.group_id().clone();
+
+    let (_message, proposal_ref) = alice_group
+        .propose_self_update(backend, &alice_signer, None)
+        .expect("Could not create update proposal.");
+
+    // The proposal is still queued after reloading the group
+    let mut alice_group = MlsGroup::load(&group_id, backend).expect("Could not load MlsGroup");
+    let pending_proposals: Vec<_> = alice_group
+        .pending_proposals()
+        .map(|proposal| proposal.proposal_reference())
+        .collect();
+    assert_eq!(pending_proposals, vec![proposal_ref]);
+
+    let epoch = alice_group.epoch().as_u64();
+    assert!(backend
+        .key_store()
+        .load::<ProposalStore>(StorageKey::QueuedProposals {
+            group_id: group_id.as_slice(),
+            epoch,
+        })
+        .is_some());
+
+    alice_group
+        .commit_to_pending_proposals(backend, &alice_signer)
+        .expect("Could not commit to pending proposals.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    // The proposals of the previous epoch were deleted when merging
+    assert!(backend
+        .key_store()
+        .load::<ProposalStore>(StorageKey::QueuedProposals {
+            group_id: group_id.as_slice(),
+            epoch,
+        })
+        .is_none());
+    let alice_group = MlsGroup::load(&group_id, backend).expect("Could not load MlsGroup");
+    assert_eq!(alice_group.pending_proposals().count(), 0);
+}
+
+#[apply(ciphersuites_and_backends)]
+fn proposal_store_conflicts(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        mut bob_group,
+        bob_signer,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+
+    // === Alice and Bob both propose to add Charlie, Bob proposes an update
+    // and Alice proposes to remove Bob ===
+    let (_proposal, alice_add_ref) = alice_group
+        .propose_add_member(backend, &alice_signer, charlie_kpb.key_package())
+        .expect("Error creating proposal.");
+    let (_proposal, remove_ref) = alice_group
+        .propose_remove_member(backend, &alice_signer, bob_group.own_leaf_index())
+        .expect("Error creating proposal.");
+    let (bob_add, bob_add_ref) = bob_group
+        .propose_add_member(backend, &bob_signer, charlie_kpb.key_package())
+        .expect("Error creating proposal.");
+    let (bob_update, update_ref) = bob_group
+        .propose_self_update(backend, &bob_signer, None)
+        .expect("Error creating proposal.");
+    for proposal in [bob_add, bob_update] {
+        let processed_message = alice_group
+            .process_message(backend, proposal.into_protocol_message().unwrap())
+            .expect("Could not process proposal.");
+        match processed_message.into_content() {
+            ProcessedMessageContent::ProposalMessage(proposal) => alice_group
+                .store_pending_proposal(backend, *proposal)
+                .expect("Could not store proposal."),
+            _ => unreachable!("Expected a proposal."),
+        }
+    }
+
+    // === The store can be queried and is restored with the group ===
+    let proposal_store = alice_group.proposal_store();
+    assert_eq!(proposal_store.len(), 4);
+    assert_eq!(
+        proposal_store
+            .get(&update_ref)
+            .expect("Missing proposal.")
+            .sender(),
+        &Sender::Member(bob_group.own_leaf_index())
+    );
+    assert_eq!(
+        proposal_store.conflicts(),
+        vec![
+            ProposalConflict::DuplicateAdd(alice_add_ref, bob_add_ref.clone()),
+            ProposalConflict::RemoveAndUpdate {
+                remove: remove_ref,
+                update: update_ref.clone(),
+            },
+        ]
+    );
+    let loaded_group =
+        MlsGroup::load(alice_group.group_id(), backend).expect("Could not load the group.");
+    assert_eq!(loaded_group.proposal_store(), alice_group.proposal_store());
+
+    // === Removing one proposal of each pair resolves the conflicts ===
+    alice_group
+        .remove_pending_proposal(backend, bob_add_ref)
+        .expect("Could not remove proposal.");
+    alice_group
+        .remove_pending_proposal(backend, update_ref)
+        .expect("Could not remove proposal.");
+    assert!(alice_group.proposal_store().conflicts().is_empty());
+}
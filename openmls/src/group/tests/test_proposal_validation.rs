@@ -1164,7 +1164,9 @@ fn test_valsem105(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
                 }
             };
             // Reset alice's group state for the next test case.
-            alice_group.clear_pending_commit();
+            alice_group
+                .clear_pending_commit(backend)
+                .expect("Could not clear pending commit.");
         }
         // Now we create a valid commit and add the proposal afterwards. Once by value, once by reference.
         alice_group.clear_pending_proposals();
@@ -1211,15 +1213,18 @@ fn test_valsem105(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
             // If we're including by reference, we have to sneak the proposal
             // into Bob's queue.
             if matches!(proposal_inclusion, ProposalInclusion::ByReference) {
-                bob_group.store_pending_proposal(
-                    QueuedProposal::from_proposal_and_sender(
-                        ciphersuite,
+                bob_group
+                    .store_pending_proposal(
                         backend,
-                        add_proposal.clone(),
-                        &Sender::build_member(alice_group.own_leaf_index()),
+                        QueuedProposal::from_proposal_and_sender(
+                            ciphersuite,
+                            backend,
+                            add_proposal.clone(),
+                            &Sender::build_member(alice_group.own_leaf_index()),
+                        )
+                        .unwrap(),
                     )
-                    .unwrap(),
-                )
+                    .expect("Could not store proposal.")
             }
 
             // Have bob process the resulting plaintext
@@ -1311,7 +1316,9 @@ fn test_valsem105(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
                 .unwrap();
         }
 
-        alice_group.clear_pending_commit();
+        alice_group
+            .clear_pending_commit(backend)
+            .expect("Could not clear pending commit.");
     }
 }
 
@@ -1389,7 +1396,9 @@ fn test_valsem107(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
         .expect("error while trying to commit to colliding remove proposals");
 
     // Clear commit to try another way of committing two identical removes.
-    alice_group.clear_pending_commit();
+    alice_group
+        .clear_pending_commit(backend)
+        .expect("Could not clear pending commit.");
 
     // Now let's verify that both commits only contain one proposal.
     let (commit_inline_remove, _welcome, _group_info) = alice_group
@@ -1496,7 +1505,9 @@ fn test_valsem108(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
         .commit_to_pending_proposals(backend, &alice_credential_with_key_and_signer.signer)
         .expect("No error while committing empty proposals");
     // FIXME: #1098 This shouldn't be necessary. Something is broken in the state logic.
-    alice_group.clear_pending_commit();
+    alice_group
+        .clear_pending_commit(backend)
+        .expect("Could not clear pending commit.");
 
     // Creating the proposal should fail already because the member is not known.
     let err = alice_group
@@ -1510,7 +1521,9 @@ fn test_valsem108(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
     assert_eq!(err, ProposeRemoveMemberError::UnknownMember);
 
     // Clear commit to try another way of committing a remove of a non-member.
-    alice_group.clear_pending_commit();
+    alice_group
+        .clear_pending_commit(backend)
+        .expect("Could not clear pending commit.");
     alice_group.clear_pending_proposals();
 
     let err = alice_group
@@ -1622,15 +1635,14 @@ fn test_valsem110(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
         .clone();
 
     let mut update_leaf_node = bob_leaf_node;
-    update_leaf_node
-        .update_and_re_sign(
-            alice_encryption_key.clone(),
-            None,
-            bob_group.group_id().clone(),
-            LeafNodeIndex::new(1),
-            &bob_credential_with_key_and_signer.signer,
-        )
-        .unwrap();
+    crate::utils::poll_ready(update_leaf_node.update_and_re_sign(
+        alice_encryption_key.clone(),
+        None,
+        bob_group.group_id().clone(),
+        LeafNodeIndex::new(1),
+        &bob_credential_with_key_and_signer.signer,
+    ))
+    .unwrap();
 
     // We first go the manual route
     let update_proposal: MlsMessageIn = bob_group
@@ -1648,7 +1660,9 @@ fn test_valsem110(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
         .expect("error processing proposal")
         .into_content()
     {
-        alice_group.store_pending_proposal(*proposal)
+        alice_group
+            .store_pending_proposal(backend, *proposal)
+            .expect("Could not store proposal.")
     } else {
         panic!("Unexpected message type");
     };
@@ -1669,7 +1683,9 @@ fn test_valsem110(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
 
     // Clear commit to see if Bob will process a commit containing two colliding
     // keys.
-    alice_group.clear_pending_commit();
+    alice_group
+        .clear_pending_commit(backend)
+        .expect("Could not clear pending commit.");
     alice_group.clear_pending_proposals();
 
     // We now have Alice create a commit. Then we artificially add an
@@ -1821,21 +1837,26 @@ fn test_valsem111(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider
 
     // Now we insert the proposal into Bob's proposal store so we can include it
     // in the commit by reference.
-    bob_group.store_pending_proposal(
-        QueuedProposal::from_proposal_and_sender(
-            ciphersuite,
+    bob_group
+        .store_pending_proposal(
             backend,
-            update_proposal.clone(),
-            &Sender::build_member(alice_group.own_leaf_index()),
+            QueuedProposal::from_proposal_and_sender(
+                ciphersuite,
+                backend,
+                update_proposal.clone(),
+                &Sender::build_member(alice_group.own_leaf_index()),
+            )
+            .expect("error creating queued proposal"),
         )
-        .expect("error creating queued proposal"),
-    );
+        .expect("Could not store proposal.");
 
     // Now we can have Alice create a new commit and insert the proposal by
     // reference.
 
     // Wipe any pending commit first.
-    alice_group.clear_pending_commit();
+    alice_group
+        .clear_pending_commit(backend)
+        .expect("Could not clear pending commit.");
 
     let commit = alice_group
         .self_update(backend, &alice_credential_with_key_and_signer.signer)
@@ -1970,7 +1991,7 @@ fn test_valsem401_valsem402(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryp
     let bob_backend = OpenMlsRustCrypto::default();
 
     // TODO(#1354): This is currently not tested because we can't easily create invalid commits.
-    let bad_psks: [(Vec<PreSharedKeyId>, ProcessMessageError); 0] = [
+    let bad_psks: [(Vec<PreSharedKeyId>, ProcessMessageError<_>); 0] = [
         // // ValSem401
         // (
         //     vec![PreSharedKeyId::external(
@@ -2075,7 +2096,9 @@ fn test_valsem401_valsem402(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryp
             .unwrap();
 
         alice_group.clear_pending_proposals();
-        alice_group.clear_pending_commit();
+        alice_group
+            .clear_pending_commit(&alice_backend)
+            .expect("Could not clear pending commit.");
 
         for psk_proposal in proposals.into_iter() {
             let processed_message = bob_group
@@ -2084,7 +2107,9 @@ fn test_valsem401_valsem402(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryp
 
             match processed_message.into_content() {
                 ProcessedMessageContent::ProposalMessage(queued_proposal) => {
-                    bob_group.store_pending_proposal(*queued_proposal);
+                    bob_group
+                        .store_pending_proposal(&bob_backend, *queued_proposal)
+                        .expect("Could not store proposal.");
                 }
                 _ => unreachable!(),
             }
@@ -2098,6 +2123,8 @@ fn test_valsem401_valsem402(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryp
         );
 
         bob_group.clear_pending_proposals();
-        bob_group.clear_pending_commit();
+        bob_group
+            .clear_pending_commit(&bob_backend)
+            .expect("Could not clear pending commit.");
     }
 }
@@ -0,0 +1,106 @@
+//! This module tests KeyPackages with pseudonymous credentials.
+
+use openmls_basic_credential::SignatureKeyPair;
+
+use crate::{
+    extensions::*,
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    key_packages::*,
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn pseudonymous_key_package(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (bob_credential_with_key, _bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    // Bob publishes a key package under a pseudonym and keeps the binding to
+    // his real credential for Alice.
+    let pseudonym_signer = SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
+    let (key_package, binding) = KeyPackage::builder()
+        .build_pseudonymous(
+            CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &pseudonym_signer,
+            pseudonym_signer.to_public_vec().into(),
+            &bob_signer,
+            bob_credential_with_key.clone(),
+        )
+        .unwrap();
+    assert_ne!(
+        key_package.leaf_node().credential(),
+        &bob_credential_with_key.credential
+    );
+    assert_eq!(binding.credential(), &bob_credential_with_key.credential);
+    binding
+        .verify(backend.crypto(), ciphersuite.signature_algorithm())
+        .unwrap();
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        GroupId::from_slice(b"Test Group"),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    alice_group
+        .propose_add_member(backend, &alice_signer, &key_package)
+        .unwrap();
+
+    // A binding for a leaf that isn't added is rejected.
+    let other_signer = SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
+    let unrelated_binding = CredentialBinding::new(
+        other_signer.to_public_vec().into(),
+        bob_credential_with_key,
+        &bob_signer,
+    )
+    .unwrap();
+    let err = alice_group
+        .commit_builder()
+        .credential_bindings([unrelated_binding])
+        .finalize(backend, &alice_signer)
+        .expect_err("Committed with an unrelated credential binding");
+    assert_eq!(
+        err,
+        CommitBuilderError::CreateCommitError(CreateCommitError::InvalidCredentialBinding)
+    );
+
+    // The binding is only revealed in the welcome, not in the group info.
+    let (_commit, welcome, group_info) = alice_group
+        .commit_builder()
+        .credential_bindings([binding.clone()])
+        .create_group_info(true)
+        .finalize(backend, &alice_signer)
+        .unwrap();
+    assert!(group_info
+        .unwrap()
+        .extensions()
+        .credential_bindings()
+        .is_none());
+    alice_group.merge_pending_commit(backend).unwrap();
+
+    let welcome = welcome.unwrap().into_welcome().unwrap();
+    let bindings = MlsGroup::credential_bindings_from_welcome(backend, &welcome).unwrap();
+    assert_eq!(bindings, vec![binding]);
+
+    let bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome,
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .unwrap();
+    assert_eq!(bob_group.members().count(), 2);
+    assert_eq!(
+        bob_group
+            .own_leaf_node()
+            .unwrap()
+            .signature_key()
+            .as_slice(),
+        pseudonym_signer.public()
+    );
+}
@@ -0,0 +1,384 @@
+//! This module tests resolving external pre-shared keys.
+
+use super::utils::{assert_send, block_on, AsyncBackend};
+use crate::{
+    framing::*,
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    messages::proposals::*,
+    schedule::{errors::PskError, PreSharedKeyId, Psk, PskResolver},
+    test_utils::*,
+};
+
+/// Resolves external PSKs from a fixed list.
+struct ExternalPsks(Vec<(Vec<u8>, Vec<u8>)>);
+
+impl PskResolver for ExternalPsks {
+    fn resolve(&self, psk_id: &PreSharedKeyId) -> Option<Vec<u8>> {
+        let Psk::External(external_psk) = psk_id.psk() else {
+            return None;
+        };
+        self.0
+            .iter()
+            .find(|(id, _)| id.as_slice() == external_psk.psk_id())
+            .map(|(_, psk)| psk.clone())
+    }
+}
+
+#[apply(ciphersuites_and_backends)]
+fn psk_resolver(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .build();
+    let psk_id =
+        PreSharedKeyId::external(b"external psk".to_vec(), vec![0; ciphersuite.hash_length()]);
+    let external_psks = || ExternalPsks(vec![(b"external psk".to_vec(), vec![1; 32])]);
+
+    // === Alice creates a group and adds Bob ===
+    let (mut alice_group, welcome) = MlsGroup::new_direct(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+        bob_kpb.key_package().clone(),
+    )
+    .expect("Error creating group.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    )
+    .expect("Error joining group.");
+
+    // === Alice adds Charlie together with an external PSK ===
+    let proposals = [
+        Propose::PreSharedKey(psk_id.clone()),
+        Propose::Add(charlie_kpb.key_package().clone()),
+    ];
+    for propose in proposals {
+        let (proposal, _proposal_ref) = alice_group
+            .propose(
+                backend,
+                &alice_signer,
+                propose,
+                ProposalOrRefType::Reference,
+            )
+            .expect("Error creating proposal.");
+        let processed_message = bob_group
+            .process_message(backend, proposal.into_protocol_message().unwrap())
+            .expect("Error processing proposal.");
+        let ProcessedMessageContent::ProposalMessage(proposal) = processed_message.into_content()
+        else {
+            panic!("Expected a proposal.");
+        };
+        bob_group
+            .store_pending_proposal(backend, *proposal)
+            .expect("Error storing proposal.");
+    }
+
+    // The PSK is neither in the key store nor can it be resolved.
+    let err = alice_group
+        .commit_to_pending_proposals(backend, &alice_signer)
+        .expect_err("Committed without the PSK.");
+    assert_eq!(
+        err,
+        CommitToPendingProposalsError::CreateCommitError(CreateCommitError::PskError(
+            PskError::MissingPsks(vec![psk_id.clone()])
+        ))
+    );
+
+    alice_group.set_psk_resolver(external_psks());
+    let (commit, welcome, _group_info) = alice_group
+        .commit_to_pending_proposals(backend, &alice_signer)
+        .expect("Error committing with the resolved PSK.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Error merging pending commit.");
+
+    // === Bob processes the commit ===
+    let commit = commit.into_protocol_message().unwrap();
+    let err = bob_group
+        .process_message(backend, commit.clone())
+        .expect_err("Processed the commit without the PSK.");
+    assert_eq!(
+        err,
+        ProcessMessageError::InvalidCommit(StageCommitError::PskError(PskError::MissingPsks(
+            vec![psk_id.clone()]
+        )))
+    );
+
+    bob_group.set_psk_resolver(external_psks());
+    let processed_message = bob_group
+        .process_message(backend, commit)
+        .expect("Error processing the commit with the resolved PSK.");
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    else {
+        panic!("Expected a StagedCommit.");
+    };
+    bob_group
+        .merge_staged_commit(backend, *staged_commit)
+        .expect("Error merging commit.");
+    assert_eq!(
+        bob_group.epoch_authenticator(),
+        alice_group.epoch_authenticator()
+    );
+
+    // === Charlie joins ===
+    let welcome = welcome
+        .expect("No Welcome.")
+        .into_welcome()
+        .expect("Unexpected message type.");
+    let err = MlsGroup::new_from_welcome(backend, &mls_group_config, welcome.clone(), None)
+        .expect_err("Joined without the PSK.");
+    assert_eq!(err, WelcomeError::Psk(PskError::MissingPsks(vec![psk_id])));
+
+    // The key package is still available after the failed attempt.
+    let charlie_group = MlsGroup::new_from_welcome_with_psk_resolver(
+        backend,
+        &mls_group_config,
+        welcome,
+        None,
+        external_psks(),
+    )
+    .expect("Error joining with the resolved PSK.");
+    assert_eq!(
+        charlie_group.epoch_authenticator(),
+        alice_group.epoch_authenticator()
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn async_psk_resolver(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let async_backend = AsyncBackend::new(backend);
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .build();
+    let psk_id =
+        PreSharedKeyId::external(b"external psk".to_vec(), vec![0; ciphersuite.hash_length()]);
+    let external_psks = ExternalPsks(vec![(b"external psk".to_vec(), vec![1; 32])]);
+
+    // === Alice creates a group with Bob and adds Charlie with a PSK ===
+    let (mut alice_group, welcome) = MlsGroup::new_direct(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+        bob_kpb.key_package().clone(),
+    )
+    .expect("Error creating group.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    )
+    .expect("Error joining group.");
+
+    let proposals = [
+        Propose::PreSharedKey(psk_id),
+        Propose::Add(charlie_kpb.key_package().clone()),
+    ];
+    for propose in proposals {
+        let (proposal, _proposal_ref) = alice_group
+            .propose(
+                backend,
+                &alice_signer,
+                propose,
+                ProposalOrRefType::Reference,
+            )
+            .expect("Error creating proposal.");
+        let processed_message = bob_group
+            .process_message(backend, proposal.into_protocol_message().unwrap())
+            .expect("Error processing proposal.");
+        let ProcessedMessageContent::ProposalMessage(proposal) = processed_message.into_content()
+        else {
+            panic!("Expected a proposal.");
+        };
+        bob_group
+            .store_pending_proposal(backend, *proposal)
+            .expect("Error storing proposal.");
+    }
+
+    // Without a resolver, the PSK is missing.
+    let result =
+        block_on(alice_group.commit_to_pending_proposals_async(&async_backend, &alice_signer));
+    assert!(matches!(
+        result,
+        Err(AsyncOperationError::OperationError(
+            CommitToPendingProposalsError::CreateCommitError(CreateCommitError::PskError(
+                PskError::MissingPsks(_)
+            ))
+        ))
+    ));
+
+    let (commit, welcome, _group_info) = block_on(assert_send(
+        alice_group.commit_to_pending_proposals_with_psk_resolver_async(
+            &async_backend,
+            &alice_signer,
+            &external_psks,
+        ),
+    ))
+    .expect("Error committing with the resolved PSK.");
+    block_on(alice_group.merge_pending_commit_async(&async_backend))
+        .expect("Error merging pending commit.");
+
+    // === Bob processes the commit and Charlie joins ===
+    let processed_message = block_on(assert_send(
+        bob_group.process_message_with_psk_resolver_async(
+            &async_backend,
+            commit.into_protocol_message().unwrap(),
+            &external_psks,
+        ),
+    ))
+    .expect("Error processing the commit with the resolved PSK.");
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    else {
+        panic!("Expected a StagedCommit.");
+    };
+    block_on(bob_group.merge_staged_commit_async(&async_backend, *staged_commit))
+        .expect("Error merging commit.");
+    assert_eq!(
+        bob_group.epoch_authenticator(),
+        alice_group.epoch_authenticator()
+    );
+
+    let charlie_group = block_on(assert_send(
+        MlsGroup::new_from_welcome_with_psk_resolver_async(
+            &async_backend,
+            &mls_group_config,
+            welcome
+                .expect("No Welcome.")
+                .into_welcome()
+                .expect("Unexpected message type."),
+            None,
+            &external_psks,
+        ),
+    ))
+    .expect("Error joining with the resolved PSK.");
+    assert_eq!(
+        charlie_group.epoch_authenticator(),
+        alice_group.epoch_authenticator()
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn async_external_psks(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let async_backend = AsyncBackend::new(backend);
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .build();
+    let psk_id =
+        PreSharedKeyId::external(b"external psk".to_vec(), vec![0; ciphersuite.hash_length()]);
+    psk_id
+        .write_to_key_store(backend, ciphersuite, &[1; 32])
+        .expect("Error storing the PSK.");
+
+    // === Alice creates a group with Bob and adds Charlie with a PSK ===
+    let (mut alice_group, welcome) = MlsGroup::new_direct(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+        bob_kpb.key_package().clone(),
+    )
+    .expect("Error creating group.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    )
+    .expect("Error joining group.");
+
+    let proposals = [
+        Propose::PreSharedKey(psk_id),
+        Propose::Add(charlie_kpb.key_package().clone()),
+    ];
+    for propose in proposals {
+        let (proposal, _proposal_ref) = alice_group
+            .propose(
+                backend,
+                &alice_signer,
+                propose,
+                ProposalOrRefType::Reference,
+            )
+            .expect("Error creating proposal.");
+        let processed_message = bob_group
+            .process_message(backend, proposal.into_protocol_message().unwrap())
+            .expect("Error processing proposal.");
+        let ProcessedMessageContent::ProposalMessage(proposal) = processed_message.into_content()
+        else {
+            panic!("Expected a proposal.");
+        };
+        bob_group
+            .store_pending_proposal(backend, *proposal)
+            .expect("Error storing proposal.");
+    }
+
+    // The PSK is read from the async key store without a resolver.
+    let (commit, welcome, _group_info) =
+        block_on(alice_group.commit_to_pending_proposals_async(&async_backend, &alice_signer))
+            .expect("Error committing with the stored PSK.");
+    block_on(alice_group.merge_pending_commit_async(&async_backend))
+        .expect("Error merging pending commit.");
+
+    // === Bob processes the commit and Charlie joins ===
+    let processed_message = block_on(
+        bob_group.process_message_async(&async_backend, commit.into_protocol_message().unwrap()),
+    )
+    .expect("Error processing the commit with the stored PSK.");
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    else {
+        panic!("Expected a StagedCommit.");
+    };
+    block_on(bob_group.merge_staged_commit_async(&async_backend, *staged_commit))
+        .expect("Error merging commit.");
+    assert_eq!(
+        bob_group.epoch_authenticator(),
+        alice_group.epoch_authenticator()
+    );
+
+    let charlie_group = block_on(MlsGroup::new_from_welcome_async(
+        &async_backend,
+        &mls_group_config,
+        welcome
+            .expect("No Welcome.")
+            .into_welcome()
+            .expect("Unexpected message type."),
+        None,
+    ))
+    .expect("Error joining with the stored PSK.");
+    assert_eq!(
+        charlie_group.epoch_authenticator(),
+        alice_group.epoch_authenticator()
+    );
+}
@@ -0,0 +1,74 @@
+//! This module tests rebasing a pending commit on a new epoch.
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{
+    framing::*,
+    group::{core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn rebase_pending_commit(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        mut bob_group,
+        bob_signer,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+    assert_eq!(
+        alice_group
+            .rebase_pending_commit(backend, &alice_signer)
+            .expect_err("Rebased without a discarded commit."),
+        RebasePendingCommitError::NoLostCommit
+    );
+
+    // === Alice's commit loses against Bob's commit ===
+    let (_alice_commit, _welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[charlie_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    let (bob_commit, _welcome, _group_info) = bob_group
+        .self_update(backend, &bob_signer)
+        .expect("Error creating self-update.");
+    bob_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let processed_message = alice_group
+        .process_message(backend, bob_commit.into_protocol_message().unwrap())
+        .expect("Could not process commit.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => alice_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging commit."),
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+    assert!(alice_group.pending_commit().is_none());
+
+    // === Alice replays the addition of Charlie in the new epoch ===
+    let (alice_commit, welcome, _group_info) = alice_group
+        .rebase_pending_commit(backend, &alice_signer)
+        .expect("Error rebasing the pending commit.");
+    assert!(welcome.is_some());
+    let processed_message = bob_group
+        .process_message(backend, alice_commit.into_protocol_message().unwrap())
+        .expect("Could not process commit.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
+            assert_eq!(staged_commit.add_proposals().count(), 1);
+            bob_group
+                .merge_staged_commit(backend, *staged_commit)
+                .expect("Error merging commit.")
+        }
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    assert_eq!(alice_group.members().count(), 3);
+    assert_eq!(bob_group.members().count(), 3);
+}
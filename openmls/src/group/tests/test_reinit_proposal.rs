@@ -0,0 +1,71 @@
+//! This module tests ReInit proposals.
+
+use crate::{
+    extensions::*,
+    framing::*,
+    group::{core_group::test_core_group::setup_client, *},
+    messages::proposals::*,
+    test_utils::*,
+    versions::ProtocolVersion,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn reinit_proposal(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group with Bob ===
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        GroupId::from_slice(b"Test Group"),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_msg, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let welcome = welcome.into_welcome().expect("Unexpected message type.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome,
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+
+    // === Alice proposes to reinitialize the group ===
+    let reinit = ReInitProposal::new(
+        GroupId::from_slice(b"New Group"),
+        ProtocolVersion::default(),
+        ciphersuite,
+        Extensions::empty(),
+    );
+    let (proposal, _proposal_ref) = alice_group
+        .propose_reinit(backend, &alice_signer, reinit.clone())
+        .expect("Could not create re-init proposal.");
+    assert_eq!(alice_group.pending_proposals().count(), 1);
+
+    // Bob receives the proposal.
+    let processed_message = bob_group
+        .process_message(backend, proposal.into_protocol_message().unwrap())
+        .expect("Could not process proposal.");
+    let ProcessedMessageContent::ProposalMessage(proposal) = processed_message.into_content()
+    else {
+        panic!("Expected a proposal.");
+    };
+    let Proposal::ReInit(received) = proposal.proposal() else {
+        panic!("Expected a re-init proposal.");
+    };
+    assert_eq!(received, &reinit);
+    assert_eq!(received.group_id().as_slice(), b"New Group");
+    assert_eq!(received.ciphersuite(), ciphersuite);
+}
@@ -0,0 +1,199 @@
+//! This module tests rejoining a group with an external commit.
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{
+    framing::*,
+    group::{core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn rejoin_via_external_commit(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        mut bob_group,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+    let bob_old_index = bob_group.own_leaf_index();
+
+    // === Alice updates twice and Bob misses the commits ===
+    for _ in 0..2 {
+        alice_group
+            .self_update(backend, &alice_signer)
+            .expect("Error creating self-update.");
+        alice_group
+            .merge_pending_commit(backend)
+            .expect("error merging pending commit");
+    }
+
+    // === Bob re-joins with a new signature key ===
+    let (bob_new_credential_with_key, _bob_new_kpb, bob_new_signer, _bob_new_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let verifiable_group_info = alice_group
+        .export_group_info(backend, &alice_signer, true)
+        .expect("Error exporting group info.")
+        .into_verifiable_group_info()
+        .expect("Unexpected message type.");
+    let bob_epoch = bob_group.epoch();
+    let (pending_rejoin, commit, _group_info) = bob_group
+        .rejoin_via_external_commit(
+            backend,
+            &bob_new_signer,
+            None,
+            verifiable_group_info,
+            bob_new_credential_with_key,
+        )
+        .expect("Error re-joining the group.");
+
+    // The old leaf is removed and Bob's group is left untouched
+    let removed = pending_rejoin
+        .staged_commit()
+        .expect("Expected a staged commit.")
+        .remove_proposals()
+        .map(|proposal| proposal.remove_proposal().removed())
+        .collect::<Vec<_>>();
+    assert_eq!(removed, vec![bob_old_index]);
+    assert_eq!(bob_group.epoch(), bob_epoch);
+
+    // === Alice accepts the commit and Bob completes the re-join ===
+    let processed_message = alice_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process messages.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => alice_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging commit."),
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+    bob_group
+        .complete_rejoin(backend, pending_rejoin)
+        .expect("Error completing the re-join.");
+
+    assert_eq!(alice_group.members().count(), 2);
+    assert_eq!(bob_group.epoch(), alice_group.epoch());
+    assert_eq!(
+        alice_group
+            .export_secret(backend, "test", &[], 32)
+            .expect("Error exporting secret."),
+        bob_group
+            .export_secret(backend, "test", &[], 32)
+            .expect("Error exporting secret.")
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn rejoin_with_duplicate_identities(
+    ciphersuite: Ciphersuite,
+    backend: &impl OpenMlsCryptoProvider,
+) {
+    let group_id = GroupId::from_slice(b"Test Group");
+
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    // A second client with the same identity as Bob
+    let (_bob2_credential_with_key, bob2_kpb, _bob2_signer, _bob2_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds both Bob clients ===
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        group_id,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+
+    let (_msg, welcome, _group_info) = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[
+                bob_kpb.key_package().clone(),
+                bob2_kpb.key_package().clone(),
+            ],
+        )
+        .expect("Could not add members.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    let bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+    let bob_old_index = bob_group.own_leaf_index();
+
+    // === Bob re-joins with a new signature key ===
+    let (bob_new_credential_with_key, _bob_new_kpb, bob_new_signer, _bob_new_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let verifiable_group_info = alice_group
+        .export_group_info(backend, &alice_signer, true)
+        .expect("Error exporting group info.")
+        .into_verifiable_group_info()
+        .expect("Unexpected message type.");
+    let (pending_rejoin, commit, _group_info) = bob_group
+        .rejoin_via_external_commit(
+            backend,
+            &bob_new_signer,
+            None,
+            verifiable_group_info,
+            bob_new_credential_with_key,
+        )
+        .expect("Error re-joining the group.");
+
+    // Only Bob's previous leaf is removed
+    let removed = pending_rejoin
+        .staged_commit()
+        .expect("Expected a staged commit.")
+        .remove_proposals()
+        .map(|proposal| proposal.remove_proposal().removed())
+        .collect::<Vec<_>>();
+    assert_eq!(removed, vec![bob_old_index]);
+
+    // === Alice accepts the commit ===
+    let processed_message = alice_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process messages.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => alice_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging commit."),
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+
+    // The other Bob client is still a member
+    assert_eq!(alice_group.members().count(), 3);
+    let bob_old_signature_key = bob_group
+        .own_leaf_node()
+        .expect("Bob's leaf is missing.")
+        .signature_key()
+        .as_slice()
+        .to_vec();
+    let bob_signature_keys = [&bob_kpb, &bob2_kpb].map(|kpb| {
+        kpb.key_package()
+            .leaf_node()
+            .signature_key()
+            .as_slice()
+            .to_vec()
+    });
+    for signature_key in bob_signature_keys {
+        assert_eq!(
+            alice_group
+                .members()
+                .any(|member| member.signature_key == signature_key),
+            signature_key != bob_old_signature_key
+        );
+    }
+}
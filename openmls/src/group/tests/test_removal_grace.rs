@@ -0,0 +1,178 @@
+//! This module tests the grace period after a member was removed.
+
+use std::time::Duration;
+
+use openmls_basic_credential::SignatureKeyPair;
+
+use crate::{
+    binary_tree::LeafNodeIndex,
+    framing::*,
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn removal_grace(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential_with_key, charlie_kpb, charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    // Bob may process three messages after being removed, Charlie none
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+    let bob_mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .removal_grace(RemovalGrace::new(3).with_max_age(Duration::from_secs(60)))
+        .build();
+    assert_eq!(
+        bob_mls_group_config.removal_grace(),
+        &RemovalGrace::new(3).with_max_age(Duration::from_secs(60))
+    );
+    assert!(!mls_group_config.removal_grace().is_enabled());
+
+    // === Alice creates a group and adds Bob and Charlie ===
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[
+                bob_kpb.key_package().clone(),
+                charlie_kpb.key_package().clone(),
+            ],
+        )
+        .expect("Could not add members.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let welcome = welcome.into_welcome().expect("Unexpected message type.");
+
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &bob_mls_group_config,
+        welcome.clone(),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+    let mut charlie_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome,
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+
+    // === Bob receives a message from Alice, others are still in flight ===
+    let create_message = |group: &mut MlsGroup, signer: &SignatureKeyPair, message: &[u8]| {
+        group
+            .create_message(backend, signer, message)
+            .expect("Error creating application message.")
+            .into_protocol_message()
+            .expect("Unexpected message type.")
+    };
+    let message = create_message(&mut alice_group, &alice_signer, b"first");
+    bob_group
+        .process_message(backend, message)
+        .expect("Could not process message.");
+    let in_flight = [&b"second"[..], b"third", b"fourth"]
+        .map(|message| create_message(&mut alice_group, &alice_signer, message));
+    let charlie_message = create_message(&mut charlie_group, &charlie_signer, b"hello");
+
+    // === Alice removes Bob and Charlie ===
+    let final_epoch = alice_group.epoch();
+    let (commit, _welcome, _group_info) = alice_group
+        .remove_members(
+            backend,
+            &alice_signer,
+            &[LeafNodeIndex::new(1), LeafNodeIndex::new(2)],
+        )
+        .expect("Could not remove members.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let commit = commit
+        .into_protocol_message()
+        .expect("Unexpected message type.");
+    for group in [&mut bob_group, &mut charlie_group] {
+        let processed_message = group
+            .process_message(backend, commit.clone())
+            .expect("Could not process message.");
+        let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+            processed_message.into_content()
+        else {
+            panic!("Expected a StagedCommit.");
+        };
+        group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging commit.");
+        assert!(!group.is_active());
+        assert_eq!(
+            group.departure().expect("No departure.").epoch(),
+            final_epoch
+        );
+    }
+
+    // === Charlie's secrets were deleted right away ===
+    assert!(!charlie_group.is_draining());
+    let err = charlie_group
+        .process_message(backend, in_flight[0].clone())
+        .expect_err("Charlie processed a message after being removed.");
+    assert_eq!(
+        err,
+        ProcessMessageError::GroupStateError(MlsGroupStateError::UseAfterEviction)
+    );
+
+    // === Bob drains the application messages of the final epoch ===
+    assert!(bob_group.is_draining());
+
+    // Handshake messages are not processed
+    let err = bob_group
+        .process_message(backend, commit)
+        .expect_err("Bob processed a commit after being removed.");
+    assert_eq!(
+        err,
+        ProcessMessageError::GroupStateError(MlsGroupStateError::UseAfterEviction)
+    );
+
+    // Charlie's sender ratchet was not derived before the removal. Application
+    // messages can also be processed through a shared reference.
+    let err = bob_group
+        .process_application_message(backend, charlie_message)
+        .expect_err("Bob derived a new sender ratchet after being removed.");
+    assert_eq!(
+        err,
+        ProcessMessageError::ValidationError(ValidationError::UnableToDecrypt(
+            MessageDecryptionError::GenerationOutOfBound
+        ))
+    );
+
+    for (message, expected) in in_flight[..2].iter().zip([&b"second"[..], b"third"]) {
+        let processed_message = bob_group
+            .process_message(backend, message.clone())
+            .expect("Could not process message.");
+        match processed_message.into_content() {
+            ProcessedMessageContent::ApplicationMessage(application_message) => {
+                assert_eq!(application_message.into_bytes(), expected)
+            }
+            _ => unreachable!("Expected an application message."),
+        }
+    }
+
+    // The third message ended the grace period
+    assert!(!bob_group.is_draining());
+    let err = bob_group
+        .process_message(backend, in_flight[2].clone())
+        .expect_err("Bob processed a message after the grace period.");
+    assert_eq!(
+        err,
+        ProcessMessageError::GroupStateError(MlsGroupStateError::UseAfterEviction)
+    );
+}
@@ -139,7 +139,9 @@ fn test_remove_operation_variants(ciphersuite: Ciphersuite, backend: &impl OpenM
 
                     match processed_message.into_content() {
                         ProcessedMessageContent::ProposalMessage(proposal) => {
-                            group.store_pending_proposal(*proposal);
+                            group
+                                .store_pending_proposal(&charlie_backend, *proposal)
+                                .expect("Could not store proposal.");
                         }
                         _ => unreachable!(),
                     }
@@ -0,0 +1,213 @@
+//! This module tests that resumption PSKs are kept according to the
+//! configuration and can be injected into other groups.
+
+use openmls_rust_crypto::OpenMlsRustCrypto;
+use openmls_traits::{types::Ciphersuite, OpenMlsCryptoProvider};
+
+use rstest::*;
+use rstest_reuse::{self, *};
+
+use super::utils::{
+    generate_credential_with_key, generate_key_package, CredentialWithKeyAndSigner,
+};
+use crate::{
+    extensions::Extensions,
+    group::{config::CryptoConfig, errors::WelcomeError, *},
+    messages::proposals::ProposalOrRefType,
+    schedule::{
+        errors::PskError,
+        psk::{ResumptionPsk, ResumptionPskUsage},
+        PreSharedKeyId, Psk,
+    },
+};
+
+fn group_config(ciphersuite: Ciphersuite, number_of_resumption_psks: usize) -> MlsGroupConfig {
+    MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .number_of_resumption_psks(number_of_resumption_psks)
+        .build()
+}
+
+fn new_member(
+    name: &str,
+    ciphersuite: Ciphersuite,
+    backend: &impl OpenMlsCryptoProvider,
+) -> CredentialWithKeyAndSigner {
+    generate_credential_with_key(
+        name.as_bytes().to_vec(),
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+}
+
+// The group keeps the resumption PSKs of the configured number of epochs.
+#[apply(ciphersuites)]
+fn resumption_psk_retention(ciphersuite: Ciphersuite) {
+    let backend = OpenMlsRustCrypto::default();
+    let alice = new_member("Alice", ciphersuite, &backend);
+
+    let mut alice_group = MlsGroup::new(
+        &backend,
+        &alice.signer,
+        &group_config(ciphersuite, 2),
+        alice.credential_with_key.clone(),
+    )
+    .expect("Error creating group.");
+    for _ in 0..3 {
+        alice_group
+            .self_update(&backend, &alice.signer)
+            .expect("Error updating own leaf.");
+        alice_group
+            .merge_pending_commit(&backend)
+            .expect("Error merging pending commit.");
+    }
+    assert_eq!(alice_group.epoch(), GroupEpoch::from(3));
+
+    let available_epochs = |group: &MlsGroup| {
+        (0..4)
+            .filter(|&epoch| {
+                group
+                    .get_past_resumption_psk(GroupEpoch::from(epoch))
+                    .is_some()
+            })
+            .collect::<Vec<u64>>()
+    };
+    assert_eq!(available_epochs(&alice_group), vec![2, 3]);
+    assert_eq!(
+        alice_group.get_past_resumption_psk(GroupEpoch::from(3)),
+        Some(alice_group.resumption_psk_secret())
+    );
+
+    // The retention survives storing and loading the group.
+    let loaded_group =
+        MlsGroup::load(alice_group.group_id(), &backend).expect("Error loading group.");
+    assert_eq!(available_epochs(&loaded_group), vec![2, 3]);
+
+    // Lowering the number drops the oldest PSKs right away. The PSK of the
+    // current epoch stays available.
+    alice_group
+        .set_configuration(&backend, &group_config(ciphersuite, 0))
+        .expect("Error setting configuration.");
+    assert_eq!(available_epochs(&alice_group), vec![3]);
+}
+
+// Alice and Bob are members of group A. Alice creates group B and injects a
+// resumption PSK of group A when adding Bob and Charlie. Bob finds the PSK in
+// the stored state of group A. Charlie, who isn't a member of group A, can
+// only join once the PSK was written to the key store.
+#[apply(ciphersuites)]
+fn resumption_psk_in_welcome(ciphersuite: Ciphersuite) {
+    let alice_backend = OpenMlsRustCrypto::default();
+    let bob_backend = OpenMlsRustCrypto::default();
+    let charlie_backend = OpenMlsRustCrypto::default();
+    let alice = new_member("Alice", ciphersuite, &alice_backend);
+    let bob = new_member("Bob", ciphersuite, &bob_backend);
+    let charlie = new_member("Charlie", ciphersuite, &charlie_backend);
+    let config = group_config(ciphersuite, 4);
+
+    // === Group A with Alice and Bob ===
+    let bob_key_package =
+        generate_key_package(ciphersuite, Extensions::empty(), &bob_backend, bob.clone());
+    let mut alice_group_a = MlsGroup::new(
+        &alice_backend,
+        &alice.signer,
+        &config,
+        alice.credential_with_key.clone(),
+    )
+    .expect("Error creating group.");
+    let (_commit, welcome, _group_info) = alice_group_a
+        .add_members(&alice_backend, &alice.signer, &[bob_key_package])
+        .expect("Error adding Bob.");
+    alice_group_a
+        .merge_pending_commit(&alice_backend)
+        .expect("Error merging pending commit.");
+    let _bob_group_a = MlsGroup::new_from_welcome(
+        &bob_backend,
+        &config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    )
+    .expect("Error joining group A.");
+
+    // === Group B with a resumption PSK of group A ===
+    let psk_epoch = alice_group_a.epoch();
+    let psk_id = PreSharedKeyId::new(
+        ciphersuite,
+        alice_backend.rand(),
+        Psk::Resumption(ResumptionPsk::new(
+            ResumptionPskUsage::Application,
+            alice_group_a.group_id().clone(),
+            psk_epoch,
+        )),
+    )
+    .expect("Error creating PSK ID.");
+
+    let mut alice_group_b = MlsGroup::new(
+        &alice_backend,
+        &alice.signer,
+        &config,
+        alice.credential_with_key.clone(),
+    )
+    .expect("Error creating group.");
+    alice_group_b
+        .propose(
+            &alice_backend,
+            &alice.signer,
+            Propose::PreSharedKey(psk_id.clone()),
+            ProposalOrRefType::Reference,
+        )
+        .expect("Error proposing resumption PSK.");
+
+    let bob_key_package =
+        generate_key_package(ciphersuite, Extensions::empty(), &bob_backend, bob.clone());
+    let charlie_key_package = generate_key_package(
+        ciphersuite,
+        Extensions::empty(),
+        &charlie_backend,
+        charlie.clone(),
+    );
+    let (_commit, welcome, _group_info) = alice_group_b
+        .add_members(
+            &alice_backend,
+            &alice.signer,
+            &[bob_key_package, charlie_key_package],
+        )
+        .expect("Error adding Bob and Charlie.");
+    let staged_commit = alice_group_b.pending_commit().expect("No pending commit.");
+    assert_eq!(staged_commit.psk_proposals().count(), 1);
+    alice_group_b
+        .merge_pending_commit(&alice_backend)
+        .expect("Error merging pending commit.");
+    let welcome = welcome.into_welcome().expect("Unexpected message type.");
+
+    // Bob is a member of group A and finds the PSK without further ado.
+    let bob_group_b = MlsGroup::new_from_welcome(&bob_backend, &config, welcome.clone(), None)
+        .expect("Error joining group B.");
+    assert_eq!(
+        bob_group_b.epoch_authenticator(),
+        alice_group_b.epoch_authenticator()
+    );
+
+    // Charlie doesn't know the PSK.
+    let error = MlsGroup::new_from_welcome(&charlie_backend, &config, welcome.clone(), None)
+        .expect_err("Charlie joined without the resumption PSK.");
+    assert_eq!(
+        error,
+        WelcomeError::Psk(PskError::MissingPsks(vec![psk_id.clone()]))
+    );
+
+    // Alice hands the PSK to Charlie out of band.
+    let resumption_psk = alice_group_a
+        .get_past_resumption_psk(psk_epoch)
+        .expect("Resumption PSK not found.");
+    psk_id
+        .write_to_key_store(&charlie_backend, ciphersuite, resumption_psk.as_slice())
+        .expect("Error writing PSK to the key store.");
+    let charlie_group_b = MlsGroup::new_from_welcome(&charlie_backend, &config, welcome, None)
+        .expect("Error joining group B.");
+    assert_eq!(
+        charlie_group_b.epoch_authenticator(),
+        alice_group_b.epoch_authenticator()
+    );
+}
@@ -0,0 +1,77 @@
+//! This module tests group operations with the secp256k1 ciphersuite.
+
+use openmls_traits::{crypto::OpenMlsCrypto, types::SignatureScheme};
+
+use super::utils::{generate_credential_with_key, generate_key_package};
+use crate::{
+    framing::*,
+    group::{config::CryptoConfig, *},
+    test_utils::*,
+    *,
+};
+
+const CIPHERSUITE: Ciphersuite = Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_SECP256K1;
+
+#[apply(backends)]
+fn secp256k1_group_operations(backend: &impl OpenMlsCryptoProvider) {
+    if backend.crypto().supports(CIPHERSUITE).is_err() {
+        return;
+    }
+    assert_eq!(
+        CIPHERSUITE.signature_algorithm(),
+        SignatureScheme::ECDSA_SECP256K1_SHA256
+    );
+
+    let alice = generate_credential_with_key(
+        b"Alice".to_vec(),
+        CIPHERSUITE.signature_algorithm(),
+        backend,
+    );
+    let bob =
+        generate_credential_with_key(b"Bob".to_vec(), CIPHERSUITE.signature_algorithm(), backend);
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(CIPHERSUITE))
+        .use_ratchet_tree_extension(true)
+        .build();
+
+    // === Alice creates a group and adds Bob ===
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice.signer,
+        &mls_group_config,
+        alice.credential_with_key.clone(),
+    )
+    .expect("Error creating group.");
+    let bob_key_package = generate_key_package(CIPHERSUITE, Extensions::empty(), backend, bob);
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice.signer, &[bob_key_package])
+        .expect("Error adding Bob.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("Error merging commit.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    )
+    .expect("Error joining group.");
+
+    // The leaf carries the uncompressed SEC1 encoding of Alice's key.
+    let alice_leaf = alice_group.own_leaf().expect("Alice has no leaf.");
+    assert_eq!(alice_leaf.signature_key().as_slice().len(), 65);
+
+    // === Alice and Bob exchange application messages ===
+    let message = alice_group
+        .create_message(backend, &alice.signer, b"Hello Bob")
+        .expect("Error creating message.");
+    let processed_message = bob_group
+        .process_message(backend, message.into_protocol_message().unwrap())
+        .expect("Error processing message.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::ApplicationMessage(message) => {
+            assert_eq!(message.into_bytes(), b"Hello Bob")
+        }
+        _ => panic!("Expected an application message."),
+    }
+}
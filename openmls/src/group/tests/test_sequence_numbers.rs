@@ -0,0 +1,93 @@
+//! This module tests the sequence numbers that the DS assigns to messages.
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{framing::*, group::*, test_utils::*};
+
+#[apply(ciphersuites_and_backends)]
+fn sequence_numbers(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        mut bob_group,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+
+    // === Alice sends three messages, numbered 0 to 2 by the DS ===
+    let messages = (0..3)
+        .map(|_| {
+            alice_group
+                .create_message(backend, &alice_signer, b"Hello Bob")
+                .expect("Error creating application message.")
+        })
+        .collect::<Vec<_>>();
+
+    // A gap is detected
+    let err = bob_group
+        .process_message_with_sequence_number(
+            backend,
+            messages[1].clone().into_protocol_message().unwrap(),
+            1,
+        )
+        .expect_err("Expected an out-of-order error.");
+    assert_eq!(
+        err,
+        ProcessMessageError::OutOfOrder {
+            expected: 0,
+            got: 1
+        }
+    );
+
+    bob_group
+        .process_message_with_sequence_number(
+            backend,
+            messages[0].clone().into_protocol_message().unwrap(),
+            0,
+        )
+        .expect("Could not process message.");
+
+    // A regression is detected
+    let err = bob_group
+        .process_message_with_sequence_number(
+            backend,
+            messages[0].clone().into_protocol_message().unwrap(),
+            0,
+        )
+        .expect_err("Expected an out-of-order error.");
+    assert_eq!(
+        err,
+        ProcessMessageError::OutOfOrder {
+            expected: 1,
+            got: 0
+        }
+    );
+
+    // The requeued messages are processed in order
+    for (sequence_number, message) in messages.into_iter().enumerate().skip(1) {
+        bob_group
+            .process_message_with_sequence_number(
+                backend,
+                message.into_protocol_message().unwrap(),
+                sequence_number as u64,
+            )
+            .expect("Could not process message.");
+    }
+    assert_eq!(bob_group.next_sequence_number(), 3);
+
+    // === Sequence numbers start at 0 in a new epoch ===
+    let (commit, _welcome, _group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Error creating self-update.");
+    let processed_message = bob_group
+        .process_message_with_sequence_number(backend, commit.into_protocol_message().unwrap(), 3)
+        .expect("Could not process message.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => bob_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging commit."),
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+    assert_eq!(bob_group.next_sequence_number(), 0);
+}
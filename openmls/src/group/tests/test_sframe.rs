@@ -0,0 +1,157 @@
+//! This module tests the SFrame keys that members derive from the group.
+
+use openmls_traits::crypto::OpenMlsCrypto;
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{binary_tree::LeafNodeIndex, group::*, test_utils::*};
+
+#[apply(ciphersuites_and_backends)]
+fn sframe_keys(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Configuration ===
+    let config = SFrameConfig::new(SFrameCipherSuite::AES_128_GCM_SHA256_128, 4, 8, 2)
+        .expect("error creating SFrame configuration");
+    for (epoch_bits, index_bits, epochs_retained) in [(0, 8, 1), (60, 8, 1), (2, 8, 5), (4, 8, 0)] {
+        assert_eq!(
+            SFrameConfig::new(
+                SFrameCipherSuite::AES_128_GCM_SHA256_128,
+                epoch_bits,
+                index_bits,
+                epochs_retained
+            ),
+            Err(SFrameError::InvalidConfiguration)
+        );
+    }
+    assert_eq!(
+        config.kid(GroupEpoch::from(1), LeafNodeIndex::new(256), 0),
+        Err(SFrameError::IndexTooLarge)
+    );
+    assert_eq!(
+        config.kid(GroupEpoch::from(1), LeafNodeIndex::new(0), 1 << 52),
+        Err(SFrameError::ContextIdTooLarge)
+    );
+
+    // === Alice adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        bob_group,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+
+    let mut alice_keys = SFrameKeys::new(config);
+    let mut bob_keys = SFrameKeys::new(config);
+    assert_eq!(alice_keys.own_kid(0), Err(SFrameError::UnknownEpoch));
+    assert!(alice_keys
+        .update(backend, &alice_group)
+        .expect("error updating SFrame keys"));
+    assert!(!alice_keys
+        .update(backend, &alice_group)
+        .expect("error updating SFrame keys"));
+    bob_keys
+        .update(backend, &bob_group)
+        .expect("error updating SFrame keys");
+
+    // The KIDs contain the context ID, the leaf index and the epoch
+    let alice_kid = alice_keys.own_kid(3).expect("error computing KID");
+    let bob_kid = bob_keys.own_kid(0).expect("error computing KID");
+    assert_eq!(alice_kid, (3 << 12) | 1);
+    assert_eq!(bob_kid, (1 << 4) | 1);
+
+    // Both members derive the same base keys
+    let alice_key = alice_keys
+        .base_key(backend, alice_kid)
+        .expect("error deriving base key");
+    assert_eq!(alice_key.len(), 16);
+    assert_eq!(
+        bob_keys
+            .base_key(backend, alice_kid)
+            .expect("error deriving base key"),
+        alice_key
+    );
+    let bob_key = alice_keys
+        .base_key(backend, bob_kid)
+        .expect("error deriving base key");
+    assert_ne!(bob_key, alice_key);
+    let epoch_secret = alice_group
+        .export_secret(backend, "SFrame 1.0", &[], 16)
+        .expect("error exporting secret");
+    let expected_key = backend
+        .crypto()
+        .hkdf_expand(
+            config.ciphersuite().hash_type(),
+            &epoch_secret,
+            &0u32.to_be_bytes(),
+            16,
+        )
+        .expect("error expanding key");
+    assert_eq!(alice_key, expected_key.as_slice());
+    assert_eq!(
+        alice_keys.base_key(
+            backend,
+            config
+                .kid(alice_group.epoch(), LeafNodeIndex::new(2), 0)
+                .unwrap()
+        ),
+        Err(SFrameError::UnknownSender)
+    );
+
+    // === Alice removes Bob ===
+    alice_group
+        .remove_members(backend, &alice_signer, &[LeafNodeIndex::new(1)])
+        .expect("error removing member");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    assert!(alice_keys
+        .update(backend, &alice_group)
+        .expect("error updating SFrame keys"));
+    assert_eq!(
+        alice_keys.epochs().collect::<Vec<_>>(),
+        [GroupEpoch::from(1), GroupEpoch::from(2)]
+    );
+
+    // Frames of the previous epoch can still be decrypted
+    assert_eq!(
+        alice_keys.base_key(backend, alice_kid),
+        Ok(alice_key.clone())
+    );
+    assert_eq!(alice_keys.base_key(backend, bob_kid), Ok(bob_key));
+    assert_eq!(
+        alice_keys.base_key(
+            backend,
+            config
+                .kid(alice_group.epoch(), LeafNodeIndex::new(1), 0)
+                .unwrap()
+        ),
+        Err(SFrameError::UnknownSender)
+    );
+    let new_kid = alice_keys.own_kid(3).expect("error computing KID");
+    assert_ne!(new_kid, alice_kid);
+    assert_ne!(
+        alice_keys
+            .base_key(backend, new_kid)
+            .expect("error deriving base key"),
+        alice_key
+    );
+
+    // === The keys of the oldest epoch are dropped ===
+    alice_group
+        .self_update(backend, &alice_signer)
+        .expect("error creating self-update commit");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    alice_keys
+        .update(backend, &alice_group)
+        .expect("error updating SFrame keys");
+    assert_eq!(
+        alice_keys.epochs().collect::<Vec<_>>(),
+        [GroupEpoch::from(2), GroupEpoch::from(3)]
+    );
+    assert_eq!(
+        alice_keys.base_key(backend, alice_kid),
+        Err(SFrameError::UnknownEpoch)
+    );
+}
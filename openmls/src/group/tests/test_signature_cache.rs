@@ -0,0 +1,106 @@
+//! This module tests caching verified signatures.
+
+use std::sync::Arc;
+
+use crate::{
+    ciphersuite::signature_cache::SignatureCache,
+    framing::*,
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    test_utils::*,
+    treesync::RatchetTreeIn,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn signature_cache(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, _bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (_charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_msg, welcome, _group_info) = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[
+                bob_kpb.key_package().clone(),
+                charlie_kpb.key_package().clone(),
+            ],
+        )
+        .expect("Could not add members.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let welcome = welcome.into_welcome().expect("Unexpected message type.");
+    let ratchet_tree: RatchetTreeIn = alice_group.export_ratchet_tree().into();
+
+    // Bob and Charlie share a cache. Bob's join caches the signatures of the
+    // three leaf nodes and of the group info, which Charlie's join reuses.
+    let signature_cache = Arc::new(SignatureCache::new(100));
+    let mut bob_group = MlsGroup::welcome_processing_builder(&mls_group_config, welcome.clone())
+        .ratchet_tree(ratchet_tree.clone())
+        .signature_cache(signature_cache.clone())
+        .build(backend)
+        .expect("Error joining the group.");
+    assert_eq!(signature_cache.len(), 4);
+    let mut charlie_group = MlsGroup::welcome_processing_builder(&mls_group_config, welcome)
+        .ratchet_tree(ratchet_tree)
+        .signature_cache(signature_cache.clone())
+        .build(backend)
+        .expect("Error joining the group.");
+    assert_eq!(signature_cache.len(), 4);
+
+    // The signatures of the commit and of the leaf node in its path are
+    // cached when Bob processes it and found in the cache when Charlie does.
+    let (commit, _welcome, _group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("error creating self update");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    for group in [&mut bob_group, &mut charlie_group] {
+        let processed_message = group
+            .process_message(backend, commit.clone().into_protocol_message().unwrap())
+            .expect("error processing commit");
+        match processed_message.into_content() {
+            ProcessedMessageContent::StagedCommitMessage(staged_commit) => group
+                .merge_staged_commit(backend, *staged_commit)
+                .expect("error merging staged commit"),
+            _ => panic!("Expected a staged commit."),
+        }
+        assert_eq!(signature_cache.len(), 6);
+        assert_eq!(
+            group.epoch_authenticator(),
+            alice_group.epoch_authenticator()
+        );
+    }
+
+    // Without a cache, nothing is added. With a full cache, the oldest
+    // signatures are dropped.
+    signature_cache.clear();
+    bob_group.clear_signature_cache();
+    let small_cache = Arc::new(SignatureCache::new(1));
+    charlie_group.set_signature_cache(small_cache.clone());
+    let (commit, _welcome, _group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("error creating self update");
+    bob_group
+        .process_message(backend, commit.clone().into_protocol_message().unwrap())
+        .expect("error processing commit");
+    assert!(signature_cache.is_empty());
+    charlie_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("error processing commit");
+    assert_eq!(small_cache.len(), small_cache.capacity());
+}
@@ -0,0 +1,101 @@
+//! This module tests speculatively processing messages on a copy of a group.
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{
+    framing::*,
+    group::{config::CryptoConfig, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn speculation(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .event_log_capacity(16)
+        .build();
+
+    // === Alice creates a group and adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        mut bob_group,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+
+    // === Alice updates ===
+    let (commit, _welcome, _group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Error creating self-update.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    // === Bob speculatively processes and merges the commit, then discards ===
+    let epoch = bob_group.epoch();
+    let mut speculation = bob_group.speculate(backend);
+    let processed_message = speculation
+        .process_message(commit.clone().into_protocol_message().unwrap())
+        .expect("Could not process messages.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => speculation
+            .merge_staged_commit(*staged_commit)
+            .expect("Error merging commit."),
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+    assert_eq!(speculation.group().epoch().as_u64(), epoch.as_u64() + 1);
+    speculation.discard();
+
+    // Neither the group nor the key store were changed, so Bob can process
+    // the commit again.
+    assert_eq!(bob_group.epoch(), epoch);
+
+    // === Bob processes the commit again and promotes the speculation ===
+    // The runtime state of the group is carried across the speculation.
+    bob_group.set_event_timestamp(Some(42));
+    let mut speculation = bob_group.speculate(backend);
+    let processed_message = speculation
+        .process_message(commit.into_protocol_message().unwrap())
+        .expect("Could not process messages.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => speculation
+            .merge_staged_commit(*staged_commit)
+            .expect("Error merging commit."),
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+    speculation.promote().expect("Error promoting speculation.");
+
+    assert_eq!(bob_group.epoch(), alice_group.epoch());
+    let latest_event = bob_group
+        .event_log(..)
+        .last()
+        .expect("Expected a logged event.");
+    assert_eq!(latest_event.timestamp(), Some(42));
+    assert_eq!(
+        alice_group
+            .export_secret(backend, "test", &[], 32)
+            .expect("Error exporting secret."),
+        bob_group
+            .export_secret(backend, "test", &[], 32)
+            .expect("Error exporting secret.")
+    );
+
+    // === Bob's promoted group keeps working ===
+    let (commit, _welcome, _group_info) = alice_group
+        .self_update(backend, &alice_signer)
+        .expect("Error creating self-update.");
+    let processed_message = bob_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process messages.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => bob_group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging commit."),
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+    let latest_event = bob_group
+        .event_log(..)
+        .last()
+        .expect("Expected a logged event.");
+    assert_eq!(latest_event.timestamp(), Some(42));
+}
@@ -0,0 +1,159 @@
+//! This module tests the changes that a staged commit reports.
+
+use crate::{
+    binary_tree::LeafNodeIndex,
+    framing::*,
+    group::{core_group::test_core_group::setup_client, *},
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn staged_commit_changes(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+    let (charlie_credential_with_key, charlie_kpb, _charlie_signer, _charlie_pk) =
+        setup_client("Charlie", ciphersuite, backend);
+    let (dave_credential_with_key, dave_kpb, _dave_signer, _dave_pk) =
+        setup_client("Dave", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group with Bob and Charlie ===
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        GroupId::from_slice(b"Test Group"),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_msg, welcome, _group_info) = alice_group
+        .add_members(
+            backend,
+            &alice_signer,
+            &[
+                bob_kpb.key_package().clone(),
+                charlie_kpb.key_package().clone(),
+            ],
+        )
+        .expect("Could not add members.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let welcome = welcome.into_welcome().expect("Unexpected message type.");
+    let mut bob_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.clone(),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+    let mut charlie_group = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome,
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+    let charlie_index = charlie_group.own_leaf_index();
+
+    // === Bob proposes an update and Alice proposes to add Dave ===
+    let (bob_update, _proposal_ref) = bob_group
+        .propose_self_update(backend, &bob_signer, None)
+        .expect("Could not create update proposal.");
+    let (add_dave, _proposal_ref) = alice_group
+        .propose_add_member(backend, &alice_signer, dave_kpb.key_package())
+        .expect("Could not create add proposal.");
+    for (group, proposal) in [
+        (&mut alice_group, bob_update.clone()),
+        (&mut bob_group, add_dave.clone()),
+        (&mut charlie_group, bob_update),
+        (&mut charlie_group, add_dave),
+    ] {
+        let processed_message = group
+            .process_message(backend, proposal.into_protocol_message().unwrap())
+            .expect("Could not process proposal.");
+        let ProcessedMessageContent::ProposalMessage(proposal) = processed_message.into_content()
+        else {
+            panic!("Expected a proposal.");
+        };
+        group
+            .store_pending_proposal(backend, *proposal)
+            .expect("Could not store proposal.");
+    }
+
+    // === Alice commits to the proposals and removes Charlie ===
+    let (commit, _welcome, _group_info) = alice_group
+        .remove_members(backend, &alice_signer, &[charlie_index])
+        .expect("Could not remove Charlie.");
+    let staged_commit = alice_group.pending_commit().unwrap();
+    assert_eq!(
+        staged_commit.own_leaf_change(),
+        Some(OwnLeafChange::Updated)
+    );
+
+    // Bob sees all changes of the commit.
+    let processed_message = bob_group
+        .process_message(backend, commit.clone().into_protocol_message().unwrap())
+        .expect("Could not process commit.");
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    else {
+        panic!("Expected a commit.");
+    };
+
+    let added_members: Vec<AddedMember> = staged_commit.added_members().collect();
+    assert_eq!(added_members.len(), 1);
+    assert_eq!(
+        added_members[0].credential(),
+        &dave_credential_with_key.credential
+    );
+    assert_eq!(added_members[0].key_package(), dave_kpb.key_package());
+    assert!(staged_commit
+        .new_leaf_nodes()
+        .any(|(leaf_index, _)| leaf_index == added_members[0].leaf_index()));
+
+    let removed_members = staged_commit.removed_members();
+    assert_eq!(removed_members.len(), 1);
+    assert_eq!(removed_members[0].leaf_index(), charlie_index);
+    assert_eq!(
+        removed_members[0].credential(),
+        &charlie_credential_with_key.credential
+    );
+
+    let mut updated_members: Vec<(LeafNodeIndex, bool)> = staged_commit
+        .updated_members()
+        .map(|updated_member| (updated_member.leaf_index(), updated_member.is_committer()))
+        .collect();
+    updated_members.sort_by_key(|(leaf_index, _)| leaf_index.u32());
+    assert_eq!(
+        updated_members,
+        vec![
+            (alice_group.own_leaf_index(), true),
+            (bob_group.own_leaf_index(), false)
+        ]
+    );
+
+    assert_eq!(staged_commit.psk_ids().count(), 0);
+    assert!(staged_commit.group_context_extension_changes().is_empty());
+    assert_eq!(
+        staged_commit.own_leaf_change(),
+        Some(OwnLeafChange::Updated)
+    );
+
+    // Charlie sees that they are removed.
+    let processed_message = charlie_group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Could not process commit.");
+    let ProcessedMessageContent::StagedCommitMessage(staged_commit) =
+        processed_message.into_content()
+    else {
+        panic!("Expected a commit.");
+    };
+    assert_eq!(
+        staged_commit.own_leaf_change(),
+        Some(OwnLeafChange::Removed)
+    );
+}
@@ -0,0 +1,380 @@
+//! This module tests that groups can be recovered from their stored state
+//! after the key store failed or the process crashed. See
+//! [`crate::test_utils::storage_faults`].
+
+use openmls_rust_crypto::OpenMlsRustCrypto;
+use openmls_traits::{signatures::Signer, types::Ciphersuite, OpenMlsCryptoProvider};
+
+use rstest::*;
+use rstest_reuse::{self, *};
+
+use super::utils::{
+    generate_credential_with_key, generate_key_package, CredentialWithKeyAndSigner,
+};
+use crate::{
+    extensions::Extensions,
+    framing::*,
+    group::{config::CryptoConfig, *},
+    test_utils::storage_faults::*,
+};
+
+/// Commits are sent as [`PublicMessage`]s, so that a member can process a
+/// commit again after a crash. The keys of a [`PrivateMessage`] are consumed
+/// when it is processed for the first time.
+fn group_config(ciphersuite: Ciphersuite) -> MlsGroupConfig {
+    MlsGroupConfig::builder()
+        .wire_format_policy(PURE_PLAINTEXT_WIRE_FORMAT_POLICY)
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .build()
+}
+
+fn new_member(
+    name: &str,
+    ciphersuite: Ciphersuite,
+    backend: &impl OpenMlsCryptoProvider,
+) -> CredentialWithKeyAndSigner {
+    generate_credential_with_key(
+        name.as_bytes().to_vec(),
+        ciphersuite.signature_algorithm(),
+        backend,
+    )
+}
+
+/// Sends an application message from the `sender` to the `receiver` and
+/// checks that it is received.
+fn send_message(
+    sender: &mut MlsGroup,
+    sender_backend: &impl OpenMlsCryptoProvider,
+    sender_signer: &impl Signer,
+    receiver: &mut MlsGroup,
+    receiver_backend: &impl OpenMlsCryptoProvider,
+) {
+    let message = sender
+        .create_message(sender_backend, sender_signer, b"Hello")
+        .expect("Error creating message.");
+    let processed_message = receiver
+        .process_message(receiver_backend, message.into_protocol_message().unwrap())
+        .expect("Error processing message.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::ApplicationMessage(message) => {
+            assert_eq!(message.into_bytes(), b"Hello")
+        }
+        _ => unreachable!("Expected an application message."),
+    }
+}
+
+/// Processes and merges a commit.
+fn process_commit(
+    group: &mut MlsGroup,
+    backend: &impl OpenMlsCryptoProvider,
+    commit: MlsMessageOut,
+) {
+    let processed_message = group
+        .process_message(backend, commit.into_protocol_message().unwrap())
+        .expect("Error processing commit.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => group
+            .merge_staged_commit(backend, *staged_commit)
+            .expect("Error merging staged commit."),
+        _ => unreachable!("Expected a StagedCommit."),
+    }
+}
+
+/// Alice creates a group and adds Bob.
+fn alice_and_bob(
+    ciphersuite: Ciphersuite,
+    alice_backend: &impl OpenMlsCryptoProvider,
+    alice: &CredentialWithKeyAndSigner,
+    bob_backend: &impl OpenMlsCryptoProvider,
+    bob: &CredentialWithKeyAndSigner,
+) -> (MlsGroup, MlsGroup) {
+    let bob_key_package =
+        generate_key_package(ciphersuite, Extensions::empty(), bob_backend, bob.clone());
+    let mut alice_group = MlsGroup::new(
+        alice_backend,
+        &alice.signer,
+        &group_config(ciphersuite),
+        alice.credential_with_key.clone(),
+    )
+    .expect("Error creating group.");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(alice_backend, &alice.signer, &[bob_key_package])
+        .expect("Error adding Bob.");
+    alice_group
+        .merge_pending_commit(alice_backend)
+        .expect("Error merging pending commit.");
+    let bob_group = MlsGroup::new_from_welcome(
+        bob_backend,
+        &group_config(ciphersuite),
+        welcome.into_welcome().expect("Unexpected message type."),
+        None,
+    )
+    .expect("Error joining group.");
+    (alice_group, bob_group)
+}
+
+// Bob crashes after the key package was deleted, but before the new group
+// state was written. The same Welcome can still be used after a restart.
+#[apply(ciphersuites)]
+fn crash_while_joining(ciphersuite: Ciphersuite) {
+    let alice_crypto = OpenMlsRustCrypto::default();
+    let alice_backend = FaultyBackend::new(&alice_crypto);
+    let bob_crypto = OpenMlsRustCrypto::default();
+    let bob_backend = FaultyBackend::new(&bob_crypto);
+    let alice = new_member("Alice", ciphersuite, &alice_backend);
+    let bob = new_member("Bob", ciphersuite, &bob_backend);
+
+    let bob_key_package = generate_key_package(ciphersuite, Extensions::empty(), &bob_backend, bob);
+    let mut alice_group = MlsGroup::new(
+        &alice_backend,
+        &alice.signer,
+        &group_config(ciphersuite),
+        alice.credential_with_key.clone(),
+    )
+    .expect("Error creating group.");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(&alice_backend, &alice.signer, &[bob_key_package])
+        .expect("Error adding Bob.");
+    alice_group
+        .merge_pending_commit(&alice_backend)
+        .expect("Error merging pending commit.");
+    let welcome = welcome.into_welcome().expect("Unexpected message type.");
+
+    // === Bob crashes while joining ===
+    bob_backend.inject(StorageFault::CrashAfterDeletes(1));
+    MlsGroup::new_from_welcome(
+        &bob_backend,
+        &group_config(ciphersuite),
+        welcome.clone(),
+        None,
+    )
+    .expect_err("Joined the group despite the crash.");
+    assert!(bob_backend.crashed());
+
+    // === Bob restarts and joins again ===
+    bob_backend.restart();
+    assert!(MlsGroup::load(alice_group.group_id(), &bob_backend).is_none());
+    let mut bob_group =
+        MlsGroup::new_from_welcome(&bob_backend, &group_config(ciphersuite), welcome, None)
+            .expect("Error joining group after the restart.");
+
+    send_message(
+        &mut alice_group,
+        &alice_backend,
+        &alice.signer,
+        &mut bob_group,
+        &bob_backend,
+    );
+}
+
+// Alice and Bob crash while merging a commit, after the keys of the previous
+// epoch were deleted. The commit is merged after a restart.
+#[apply(ciphersuites)]
+fn crash_while_merging(ciphersuite: Ciphersuite) {
+    let alice_crypto = OpenMlsRustCrypto::default();
+    let alice_backend = FaultyBackend::new(&alice_crypto);
+    let bob_crypto = OpenMlsRustCrypto::default();
+    let bob_backend = FaultyBackend::new(&bob_crypto);
+    let alice = new_member("Alice", ciphersuite, &alice_backend);
+    let bob = new_member("Bob", ciphersuite, &bob_backend);
+    let (mut alice_group, mut bob_group) =
+        alice_and_bob(ciphersuite, &alice_backend, &alice, &bob_backend, &bob);
+    let group_id = alice_group.group_id().clone();
+    let epoch = alice_group.epoch();
+
+    // === Alice crashes while merging the own commit ===
+    let (commit, _welcome, _group_info) = alice_group
+        .self_update(&alice_backend, &alice.signer)
+        .expect("Error creating self update.");
+    alice_backend.inject(StorageFault::CrashAfterDeletes(1));
+    alice_group
+        .merge_pending_commit(&alice_backend)
+        .expect_err("Merged the commit despite the crash.");
+    assert!(alice_backend.crashed());
+
+    alice_backend.restart();
+    let mut alice_group =
+        MlsGroup::load(&group_id, &alice_backend).expect("Could not load the group state.");
+    assert_eq!(alice_group.epoch(), epoch);
+    alice_group
+        .merge_pending_commit(&alice_backend)
+        .expect("Error merging pending commit.");
+
+    // === Bob crashes while merging Alice's commit ===
+    let processed_message = bob_group
+        .process_message(
+            &bob_backend,
+            commit.clone().into_protocol_message().unwrap(),
+        )
+        .expect("Error processing commit.");
+    let staged_commit = match processed_message.into_content() {
+        ProcessedMessageContent::StagedCommitMessage(staged_commit) => staged_commit,
+        _ => unreachable!("Expected a StagedCommit."),
+    };
+    bob_backend.inject(StorageFault::CrashAfterDeletes(1));
+    bob_group
+        .merge_staged_commit(&bob_backend, *staged_commit)
+        .expect_err("Merged the commit despite the crash.");
+    assert!(bob_backend.crashed());
+
+    bob_backend.restart();
+    let mut bob_group =
+        MlsGroup::load(&group_id, &bob_backend).expect("Could not load the group state.");
+    assert_eq!(bob_group.epoch(), epoch);
+    process_commit(&mut bob_group, &bob_backend, commit);
+    assert_eq!(alice_group.epoch(), bob_group.epoch());
+
+    send_message(
+        &mut alice_group,
+        &alice_backend,
+        &alice.signer,
+        &mut bob_group,
+        &bob_backend,
+    );
+    send_message(
+        &mut bob_group,
+        &bob_backend,
+        &bob.signer,
+        &mut alice_group,
+        &alice_backend,
+    );
+}
+
+// Writes fail at different points while Alice commits. After a restart, Alice
+// either merges the commit that was already sent or creates a new one.
+#[apply(ciphersuites)]
+fn write_failures_while_committing(ciphersuite: Ciphersuite) {
+    let alice_crypto = OpenMlsRustCrypto::default();
+    let alice_backend = FaultyBackend::new(&alice_crypto);
+    let bob_crypto = OpenMlsRustCrypto::default();
+    let bob_backend = FaultyBackend::new(&bob_crypto);
+    let alice = new_member("Alice", ciphersuite, &alice_backend);
+    let bob = new_member("Bob", ciphersuite, &bob_backend);
+    let (mut alice_group, mut bob_group) =
+        alice_and_bob(ciphersuite, &alice_backend, &alice, &bob_backend, &bob);
+    let group_id = alice_group.group_id().clone();
+
+    for budget in [0, 64, 1024, 16 * 1024, 256 * 1024] {
+        alice_backend.inject(StorageFault::FailWriteAfterBytes(budget));
+        let commit = alice_group
+            .self_update(&alice_backend, &alice.signer)
+            .ok()
+            .map(|(commit, _welcome, _group_info)| commit);
+        let merged = commit.is_some() && alice_group.merge_pending_commit(&alice_backend).is_ok();
+
+        // === Alice restarts and completes the commit ===
+        alice_backend.restart();
+        alice_group =
+            MlsGroup::load(&group_id, &alice_backend).expect("Could not load the group state.");
+        let commit = match commit {
+            Some(commit) => {
+                if !merged {
+                    alice_group
+                        .merge_pending_commit(&alice_backend)
+                        .expect("Error merging pending commit.");
+                }
+                commit
+            }
+            None => {
+                // The commit was never sent, so it must not be merged.
+                alice_group
+                    .clear_pending_commit(&alice_backend)
+                    .expect("Error clearing pending commit.");
+                let (commit, _welcome, _group_info) = alice_group
+                    .self_update(&alice_backend, &alice.signer)
+                    .expect("Error creating self update.");
+                alice_group
+                    .merge_pending_commit(&alice_backend)
+                    .expect("Error merging pending commit.");
+                commit
+            }
+        };
+
+        process_commit(&mut bob_group, &bob_backend, commit);
+        assert_eq!(alice_group.epoch(), bob_group.epoch());
+        send_message(
+            &mut alice_group,
+            &alice_backend,
+            &alice.signer,
+            &mut bob_group,
+            &bob_backend,
+        );
+    }
+}
+
+// Bob's key store persists the cached group state and queued proposals in
+// the wrong order and crashes in between. Bob ends up with an older group
+// state, from which the lost messages can be processed again.
+#[apply(ciphersuites)]
+fn reordered_flushes(ciphersuite: Ciphersuite) {
+    let alice_crypto = OpenMlsRustCrypto::default();
+    let alice_backend = FaultyBackend::new(&alice_crypto);
+    let bob_crypto = OpenMlsRustCrypto::default();
+    let bob_backend = FaultyBackend::new(&bob_crypto);
+    let alice = new_member("Alice", ciphersuite, &alice_backend);
+    let bob = new_member("Bob", ciphersuite, &bob_backend);
+    let (mut alice_group, mut bob_group) =
+        alice_and_bob(ciphersuite, &alice_backend, &alice, &bob_backend, &bob);
+    let group_id = alice_group.group_id().clone();
+
+    // === Bob processes messages, but only the queued proposals are persisted ===
+    bob_backend.inject(StorageFault::ReorderFlushes {
+        persisted_on_crash: 1,
+    });
+    let messages: Vec<MlsMessageOut> = (0..3)
+        .map(|_| {
+            alice_group
+                .create_message(&alice_backend, &alice.signer, b"Hello")
+                .expect("Error creating message.")
+        })
+        .collect();
+    for message in messages.iter().cloned() {
+        bob_group
+            .process_message(&bob_backend, message.into_protocol_message().unwrap())
+            .expect("Error processing message.");
+    }
+    bob_backend.crash();
+
+    // === Bob restarts and processes the messages again ===
+    bob_backend.restart();
+    let mut bob_group =
+        MlsGroup::load(&group_id, &bob_backend).expect("Could not load the group state.");
+    for message in messages {
+        bob_group
+            .process_message(&bob_backend, message.into_protocol_message().unwrap())
+            .expect("Error processing message again.");
+    }
+
+    // === The group is still functional ===
+    bob_backend.inject(StorageFault::ReorderFlushes {
+        persisted_on_crash: 0,
+    });
+    let (commit, _welcome, _group_info) = alice_group
+        .self_update(&alice_backend, &alice.signer)
+        .expect("Error creating self update.");
+    alice_group
+        .merge_pending_commit(&alice_backend)
+        .expect("Error merging pending commit.");
+    // Merging applies a batch, which persists the cache first.
+    process_commit(&mut bob_group, &bob_backend, commit);
+    bob_backend.restart();
+    let mut bob_group =
+        MlsGroup::load(&group_id, &bob_backend).expect("Could not load the group state.");
+    assert_eq!(alice_group.epoch(), bob_group.epoch());
+
+    send_message(
+        &mut alice_group,
+        &alice_backend,
+        &alice.signer,
+        &mut bob_group,
+        &bob_backend,
+    );
+    send_message(
+        &mut bob_group,
+        &bob_backend,
+        &bob.signer,
+        &mut alice_group,
+        &alice_backend,
+    );
+}
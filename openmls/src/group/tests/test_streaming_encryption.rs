@@ -0,0 +1,174 @@
+//! This module tests encrypting large payloads in chunks.
+
+use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait};
+
+use super::utils::{setup_alice_and_bob, TwoMemberGroup};
+use crate::{framing::*, group::*, test_utils::*};
+
+#[apply(ciphersuites_and_backends)]
+fn streaming_encryption(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds Bob ===
+    let TwoMemberGroup {
+        mut alice_group,
+        alice_signer,
+        mut bob_group,
+        ..
+    } = setup_alice_and_bob(ciphersuite, backend, &mls_group_config);
+
+    // === Alice encrypts a payload in chunks ===
+    let payload: Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+    let mut encrypt_stream = alice_group
+        .encrypt_stream(backend)
+        .expect("Error starting stream.");
+    let header = encrypt_stream.header().clone();
+    let mut chunks = payload[..9_000]
+        .chunks(1_000)
+        .map(|chunk| {
+            encrypt_stream
+                .encrypt_chunk(backend, chunk)
+                .expect("Error encrypting chunk.")
+        })
+        .collect::<Vec<_>>();
+    chunks.push(
+        encrypt_stream
+            .encrypt_last_chunk(backend, &payload[9_000..])
+            .expect("Error encrypting chunk."),
+    );
+    assert!(encrypt_stream.is_finished());
+    assert_eq!(
+        encrypt_stream
+            .encrypt_chunk(backend, b"too late")
+            .expect_err("Encrypted a chunk after the last one."),
+        StreamChunkError::Finished
+    );
+
+    // The header can be sent in any way, e.g. in a signed application message
+    let header =
+        StreamHeader::tls_deserialize_exact(header.tls_serialize_detached().unwrap().as_slice())
+            .expect("Error deserializing header.");
+    assert_eq!(header.sender(), alice_group.own_leaf_index());
+
+    // Alice can't decrypt her own stream
+    assert_eq!(
+        alice_group
+            .decrypt_stream(backend, &header, &chunks[0])
+            .expect_err("Decrypted own stream."),
+        StreamError::InvalidSender
+    );
+
+    // === A tampered header doesn't use up any key of Alice ===
+    let secret_tree = bob_group.group().message_secrets().secret_tree().clone();
+    let mut serialized_header = header.tls_serialize_detached().unwrap();
+    // The generation is at the end of the header
+    *serialized_header.last_mut().unwrap() ^= 4;
+    let tampered_header = StreamHeader::tls_deserialize_exact(serialized_header.as_slice())
+        .expect("Error deserializing header.");
+    assert_eq!(tampered_header.generation(), header.generation() ^ 4);
+    assert_eq!(
+        bob_group
+            .decrypt_stream(backend, &tampered_header, &chunks[0])
+            .expect_err("Decrypted a stream with a tampered header."),
+        StreamError::StreamChunkError(StreamChunkError::InvalidChunk)
+    );
+    assert_eq!(
+        bob_group.group().message_secrets().secret_tree(),
+        &secret_tree
+    );
+    let stored_group = MlsGroup::load(bob_group.group_id(), backend).expect("Group not stored.");
+    assert_eq!(
+        stored_group.group().message_secrets().secret_tree(),
+        &secret_tree
+    );
+
+    // A first chunk that isn't the first one of the stream is rejected as well
+    assert_eq!(
+        bob_group
+            .decrypt_stream(backend, &header, &chunks[1])
+            .expect_err("Decrypted a chunk out of order."),
+        StreamError::StreamChunkError(StreamChunkError::InvalidChunk)
+    );
+    assert_eq!(
+        bob_group.group().message_secrets().secret_tree(),
+        &secret_tree
+    );
+
+    // === Bob decrypts the chunks ===
+    let (mut decrypt_stream, mut decrypted) = bob_group
+        .decrypt_stream(backend, &header, &chunks[0])
+        .expect("Error starting stream.");
+
+    // Chunks out of order and modified chunks are rejected without advancing
+    // the stream
+    assert_eq!(
+        decrypt_stream
+            .decrypt_chunk(backend, &chunks[2])
+            .expect_err("Decrypted a chunk out of order."),
+        StreamChunkError::InvalidChunk
+    );
+    let mut modified_chunk = chunks[1].clone();
+    *modified_chunk.last_mut().unwrap() ^= 1;
+    assert_eq!(
+        decrypt_stream
+            .decrypt_chunk(backend, &modified_chunk)
+            .expect_err("Decrypted a modified chunk."),
+        StreamChunkError::InvalidChunk
+    );
+
+    for chunk in &chunks[1..chunks.len() - 1] {
+        decrypted.extend(
+            decrypt_stream
+                .decrypt_chunk(backend, chunk)
+                .expect("Error decrypting chunk."),
+        );
+    }
+    assert!(!decrypt_stream.is_finished());
+    decrypted.extend(
+        decrypt_stream
+            .decrypt_chunk(backend, chunks.last().unwrap())
+            .expect("Error decrypting chunk."),
+    );
+    assert_eq!(decrypted, payload);
+    decrypt_stream.finish().expect("Stream is incomplete.");
+
+    // The key of the stream can only be used once
+    assert_eq!(
+        bob_group
+            .decrypt_stream(backend, &header, &chunks[0])
+            .expect_err("Decrypted a stream twice."),
+        StreamError::SecretTreeError(SecretTreeError::SecretReuseError)
+    );
+
+    // === A truncated stream is detected ===
+    let mut encrypt_stream = alice_group
+        .encrypt_stream(backend)
+        .expect("Error starting stream.");
+    let chunk = encrypt_stream
+        .encrypt_chunk(backend, &payload)
+        .expect("Error encrypting chunk.");
+    let (decrypt_stream, decrypted) = bob_group
+        .decrypt_stream(backend, encrypt_stream.header(), &chunk)
+        .expect("Error starting stream.");
+    assert_eq!(decrypted, payload);
+    assert_eq!(
+        decrypt_stream
+            .finish()
+            .expect_err("Finished a truncated stream."),
+        StreamChunkError::Truncated
+    );
+
+    // Application messages still work after the streams
+    let message = alice_group
+        .create_message(backend, &alice_signer, b"Hello Bob")
+        .expect("Error creating message.");
+    let processed_message = bob_group
+        .process_message(backend, message.into_protocol_message().unwrap())
+        .expect("Error processing message.");
+    match processed_message.into_content() {
+        ProcessedMessageContent::ApplicationMessage(application_message) => {
+            assert_eq!(application_message.into_bytes(), b"Hello Bob")
+        }
+        _ => panic!("Unexpected message type."),
+    }
+}
@@ -0,0 +1,98 @@
+//! This module tests declining Welcomes.
+
+use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait};
+
+use crate::{
+    binary_tree::LeafNodeIndex,
+    group::{core_group::test_core_group::setup_client, *},
+    messages::welcome_decline::WelcomeDecline,
+    test_utils::*,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn welcome_decline(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (_bob_credential_with_key, bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mls_group_config = MlsGroupConfig::test_default(ciphersuite);
+
+    // === Alice creates a group and adds Bob ===
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key.clone(),
+    )
+    .expect("An unexpected error occurred.");
+
+    let (_msg, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let welcome = welcome.into_welcome().expect("Unexpected message type.");
+
+    // === Bob declines the invitation ===
+    let welcome_decline = MlsGroup::decline_welcome(backend, &bob_signer, &welcome)
+        .expect("Could not decline welcome.");
+    assert_eq!(welcome_decline.group_id(), alice_group.group_id());
+    assert_eq!(
+        welcome_decline.key_package_ref(),
+        &bob_kpb
+            .key_package()
+            .hash_ref(backend.crypto())
+            .expect("Could not compute hash reference.")
+    );
+
+    // The key package was deleted, so Bob can't join anymore
+    let err = MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome,
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect_err("Joined the group with a declined welcome.");
+    assert_eq!(err, WelcomeError::NoMatchingKeyPackage);
+
+    // === Alice verifies the decline and removes Bob's leaf ===
+    let serialized_decline = welcome_decline
+        .tls_serialize_detached()
+        .expect("Could not serialize decline.");
+    let welcome_decline = WelcomeDecline::tls_deserialize(&mut serialized_decline.as_slice())
+        .expect("Could not deserialize decline.");
+
+    let bob_index = alice_group
+        .process_welcome_decline(backend, &welcome_decline)
+        .expect("Could not process decline.");
+    assert_eq!(bob_index, LeafNodeIndex::new(1));
+
+    alice_group
+        .remove_members(backend, &alice_signer, &[bob_index])
+        .expect("Could not remove member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    assert_eq!(alice_group.members().count(), 1);
+
+    // The decline no longer matches a leaf
+    assert_eq!(
+        alice_group.process_welcome_decline(backend, &welcome_decline),
+        Err(ProcessWelcomeDeclineError::UnknownLeaf)
+    );
+
+    // The decline is bound to the group
+    let other_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    assert_eq!(
+        other_group.process_welcome_decline(backend, &welcome_decline),
+        Err(ProcessWelcomeDeclineError::WrongGroupId)
+    );
+}
@@ -0,0 +1,95 @@
+//! This module tests the builder for processing Welcomes.
+
+use std::time::Duration;
+
+use crate::{
+    binary_tree::LeafNodeIndex,
+    group::{config::CryptoConfig, core_group::test_core_group::setup_client, *},
+    key_packages::*,
+    test_utils::*,
+    treesync::RatchetTreeIn,
+};
+
+#[apply(ciphersuites_and_backends)]
+fn welcome_processing_builder(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (bob_credential_with_key, _bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    // Bob's key package expires right away.
+    let bob_key_package = KeyPackage::builder()
+        .key_package_lifetime(Lifetime::new(0))
+        .build(
+            CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &bob_signer,
+            bob_credential_with_key,
+        )
+        .expect("Error creating key package.");
+    std::thread::sleep(Duration::from_secs(1));
+
+    // The group doesn't use the ratchet tree extension and accepts the
+    // expired key package.
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .lifetime_policy(LifetimePolicy::default().with_enforcement(false))
+        .build();
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_key_package])
+        .expect("Error adding Bob.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let welcome = welcome.into_welcome().expect("Unexpected message type.");
+    let ratchet_tree: RatchetTreeIn = alice_group.export_ratchet_tree().into();
+
+    // Without a ratchet tree, joining fails.
+    let err = MlsGroup::welcome_processing_builder(&mls_group_config, welcome.clone())
+        .build(backend)
+        .expect_err("Joined without a ratchet tree.");
+    assert_eq!(err, WelcomeError::MissingRatchetTree);
+
+    // The lifetime of Bob's own leaf node is checked if requested.
+    let err = MlsGroup::welcome_processing_builder(&mls_group_config, welcome.clone())
+        .ratchet_tree(ratchet_tree.clone())
+        .check_lifetimes(LifetimePolicy::default())
+        .build(backend)
+        .expect_err("Joined with an expired leaf node.");
+    assert_eq!(err, WelcomeError::InvalidLifetime(LeafNodeIndex::new(1)));
+
+    // Bob fetches the ratchet tree and keeps his key package.
+    let bob_group = MlsGroup::welcome_processing_builder(&mls_group_config, welcome.clone())
+        .fetch_ratchet_tree(|group_id: &GroupId, epoch: GroupEpoch| {
+            assert_eq!(group_id, alice_group.group_id());
+            assert_eq!(epoch, alice_group.epoch());
+            Some(ratchet_tree.clone())
+        })
+        .check_lifetimes(LifetimePolicy::default().with_enforcement(false))
+        .keep_key_package(true)
+        .build(backend)
+        .expect("Error joining the group.");
+    assert_eq!(
+        bob_group.epoch_authenticator(),
+        alice_group.epoch_authenticator()
+    );
+
+    // The key package can be used once more and is deleted afterwards.
+    MlsGroup::new_from_welcome(
+        backend,
+        &mls_group_config,
+        welcome.clone(),
+        Some(ratchet_tree.clone()),
+    )
+    .expect("Error joining the group.");
+    let err = MlsGroup::new_from_welcome(backend, &mls_group_config, welcome, Some(ratchet_tree))
+        .expect_err("Joined with a deleted key package.");
+    assert_eq!(err, WelcomeError::NoMatchingKeyPackage);
+}
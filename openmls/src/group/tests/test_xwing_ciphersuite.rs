@@ -0,0 +1,83 @@
+//! This module tests that the experimental X-Wing ciphersuite is rejected by
+//! the crypto providers in this repository, none of which implement the
+//! X-Wing KEM.
+
+use openmls_traits::{
+    crypto::OpenMlsCrypto,
+    types::{CryptoError, HpkeKemType},
+};
+
+use super::utils::generate_credential_with_key;
+use crate::{
+    group::{config::CryptoConfig, *},
+    key_packages::{errors::KeyPackageNewError, *},
+    test_utils::*,
+};
+
+#[apply(backends)]
+fn xwing_ciphersuite_is_rejected(backend: &impl OpenMlsCryptoProvider) {
+    let ciphersuite = Ciphersuite::MLS_128_XWING_AES128GCM_SHA256_Ed25519;
+    let kem = ciphersuite.hpke_kem_algorithm();
+    assert_eq!(kem, HpkeKemType::XWingKemDraft06);
+    assert!(kem.is_hybrid());
+
+    // The provider rejects the ciphersuite and its KEM ...
+    assert_eq!(
+        backend.crypto().supports(ciphersuite),
+        Err(CryptoError::UnsupportedCiphersuite)
+    );
+    assert!(!backend
+        .crypto()
+        .supported_ciphersuites()
+        .contains(&ciphersuite));
+    assert_eq!(
+        backend.crypto().supports_hpke_kem(kem),
+        Err(CryptoError::UnsupportedKem)
+    );
+
+    // ... and the HPKE operations fail instead of panicking.
+    assert_eq!(
+        backend
+            .crypto()
+            .derive_hpke_keypair(ciphersuite.hpke_config(), &[0x01; 32])
+            .err(),
+        Some(CryptoError::UnsupportedKem)
+    );
+    assert_eq!(
+        backend.crypto().hpke_seal(
+            ciphersuite.hpke_config(),
+            &[0x02; 1216],
+            b"info",
+            b"aad",
+            b"plaintext"
+        ),
+        Err(CryptoError::UnsupportedKem)
+    );
+
+    // Groups and key packages with the ciphersuite are rejected up front.
+    let alice = generate_credential_with_key(
+        b"Alice".to_vec(),
+        ciphersuite.signature_algorithm(),
+        backend,
+    );
+    let mls_group_config = MlsGroupConfig::builder()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+    let err = MlsGroup::new(
+        backend,
+        &alice.signer,
+        &mls_group_config,
+        alice.credential_with_key.clone(),
+    )
+    .expect_err("Created a group with an unsupported ciphersuite.");
+    assert!(matches!(err, NewGroupError::UnsupportedCiphersuite));
+    let err = KeyPackage::builder()
+        .build(
+            CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &alice.signer,
+            alice.credential_with_key,
+        )
+        .expect_err("Created a key package with an unsupported ciphersuite.");
+    assert!(matches!(err, KeyPackageNewError::UnsupportedCiphersuite));
+}
@@ -4,20 +4,36 @@
 //! Most tests require to set up groups, clients, credentials, and identities.
 //! This module implements helpers to do that.
 
-use std::{cell::RefCell, collections::HashMap};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    future::Future,
+    sync::Arc,
+    task::{Context, Poll, Wake},
+};
 
 use config::CryptoConfig;
 use openmls_basic_credential::SignatureKeyPair;
 use openmls_traits::{
-    key_store::OpenMlsKeyStore, signatures::Signer, types::SignatureScheme, OpenMlsCryptoProvider,
+    key_store::{MlsEntity, MlsEntityId, OpenMlsAsyncKeyStore, OpenMlsKeyStore, WriteBatch},
+    signatures::Signer,
+    types::SignatureScheme,
+    OpenMlsAsyncCryptoProvider, OpenMlsCryptoProvider,
 };
 use rand::{rngs::OsRng, RngCore};
 use tls_codec::Serialize;
 
 use crate::{
-    ciphersuite::signable::Signable, credentials::*, framing::*, group::*, key_packages::*,
-    messages::ConfirmationTag, schedule::psk::store::ResumptionPskStore, test_utils::*,
-    versions::ProtocolVersion, *,
+    ciphersuite::signable::Signable,
+    credentials::*,
+    framing::*,
+    group::{core_group::test_core_group::setup_client, *},
+    key_packages::*,
+    messages::ConfirmationTag,
+    schedule::psk::store::ResumptionPskStore,
+    test_utils::*,
+    versions::ProtocolVersion,
+    *,
 };
 
 /// Configuration of a client meant to be used in a test setup.
@@ -263,6 +279,7 @@ pub(crate) fn setup(config: TestSetupConfig, backend: &impl OpenMlsCryptoProvide
                     key_package_bundle,
                     backend,
                     ResumptionPskStore::new(1024),
+                    None,
                 ) {
                     Ok(group) => group,
                     Err(err) => panic!("Error creating new group from Welcome: {err:?}"),
@@ -373,6 +390,59 @@ pub(crate) fn generate_key_package<KeyStore: OpenMlsKeyStore>(
         .unwrap()
 }
 
+/// A group of Alice and Bob, as set up by [`setup_alice_and_bob`].
+pub(crate) struct TwoMemberGroup {
+    pub(crate) alice_group: MlsGroup,
+    pub(crate) alice_signer: SignatureKeyPair,
+    pub(crate) alice_credential_with_key: CredentialWithKey,
+    pub(crate) bob_group: MlsGroup,
+    pub(crate) bob_signer: SignatureKeyPair,
+    pub(crate) bob_credential_with_key: CredentialWithKey,
+}
+
+/// Alice creates a group with the `mls_group_config` and adds Bob, who joins
+/// from the Welcome with the ratchet tree of Alice.
+pub(crate) fn setup_alice_and_bob(
+    ciphersuite: Ciphersuite,
+    backend: &impl OpenMlsCryptoProvider,
+    mls_group_config: &MlsGroupConfig,
+) -> TwoMemberGroup {
+    let (alice_credential_with_key, _alice_kpb, alice_signer, _alice_pk) =
+        setup_client("Alice", ciphersuite, backend);
+    let (bob_credential_with_key, bob_kpb, bob_signer, _bob_pk) =
+        setup_client("Bob", ciphersuite, backend);
+
+    let mut alice_group = MlsGroup::new(
+        backend,
+        &alice_signer,
+        mls_group_config,
+        alice_credential_with_key.clone(),
+    )
+    .expect("An unexpected error occurred.");
+    let (_msg, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &[bob_kpb.key_package().clone()])
+        .expect("Could not add member.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+    let bob_group = MlsGroup::new_from_welcome(
+        backend,
+        mls_group_config,
+        welcome.into_welcome().expect("Unexpected message type."),
+        Some(alice_group.export_ratchet_tree().into()),
+    )
+    .expect("error creating group from welcome");
+
+    TwoMemberGroup {
+        alice_group,
+        alice_signer,
+        alice_credential_with_key,
+        bob_group,
+        bob_signer,
+        bob_credential_with_key,
+    }
+}
+
 #[cfg(test)]
 pub(crate) fn resign_message(
     alice_group: &MlsGroup,
@@ -434,3 +504,113 @@ pub(crate) fn resign_external_commit(
 
     public_message.into()
 }
+
+/// Wraps the key store of a synchronous backend in an
+/// [`OpenMlsAsyncKeyStore`].
+pub(crate) struct AsyncKeyStore<'a, KeyStore: OpenMlsKeyStore>(&'a KeyStore);
+
+impl<'a, KeyStore: OpenMlsKeyStore> OpenMlsAsyncKeyStore for AsyncKeyStore<'a, KeyStore> {
+    type Error = KeyStore::Error;
+
+    fn store<V: MlsEntity + Sync>(
+        &self,
+        k: &[u8],
+        v: &V,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        async move { self.0.store(k, v) }
+    }
+
+    fn read<V: MlsEntity + Send>(&self, k: &[u8]) -> impl Future<Output = Option<V>> + Send {
+        async move { self.0.read(k) }
+    }
+
+    fn delete<V: MlsEntity>(
+        &self,
+        k: &[u8],
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        async move { self.0.delete::<V>(k) }
+    }
+
+    fn encode<V: MlsEntity>(&self, v: &V) -> Result<Vec<u8>, Self::Error> {
+        self.0.encode(v)
+    }
+
+    fn decode<V: MlsEntity>(&self, value: &[u8]) -> Option<V> {
+        self.0.decode(value)
+    }
+
+    fn store_encoded(
+        &self,
+        entity_id: MlsEntityId,
+        k: &[u8],
+        value: &[u8],
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        async move { self.0.store_encoded(entity_id, k, value) }
+    }
+
+    fn apply_batch(
+        &self,
+        batch: WriteBatch,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        async move { self.0.apply_batch(batch) }
+    }
+}
+
+/// Wraps a synchronous backend in an [`OpenMlsAsyncCryptoProvider`].
+pub(crate) struct AsyncBackend<'a, Backend: OpenMlsCryptoProvider> {
+    backend: &'a Backend,
+    key_store: AsyncKeyStore<'a, Backend::KeyStoreProvider>,
+}
+
+impl<'a, Backend: OpenMlsCryptoProvider> AsyncBackend<'a, Backend> {
+    pub(crate) fn new(backend: &'a Backend) -> Self {
+        Self {
+            backend,
+            key_store: AsyncKeyStore(backend.key_store()),
+        }
+    }
+}
+
+impl<'a, Backend: OpenMlsCryptoProvider> OpenMlsAsyncCryptoProvider for AsyncBackend<'a, Backend> {
+    type CryptoProvider = Backend::CryptoProvider;
+    type RandProvider = Backend::RandProvider;
+    type KeyStoreProvider = AsyncKeyStore<'a, Backend::KeyStoreProvider>;
+
+    fn crypto(&self) -> &Self::CryptoProvider {
+        self.backend.crypto()
+    }
+
+    fn rand(&self) -> &Self::RandProvider {
+        self.backend.rand()
+    }
+
+    fn key_store(&self) -> &Self::KeyStoreProvider {
+        &self.key_store
+    }
+}
+
+/// Runs a future to completion on the current thread.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(std::thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark()
+        }
+    }
+
+    let waker = Arc::new(ThreadWaker(std::thread::current())).into();
+    let mut context = Context::from_waker(&waker);
+    let mut future = std::pin::pin!(future);
+    loop {
+        match future.as_mut().poll(&mut context) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+/// Makes sure that `future` can be spawned on a multi-threaded runtime.
+pub(crate) fn assert_send<F: Future + Send>(future: F) -> F {
+    future
+}
@@ -0,0 +1,223 @@
+//! # Key package batches
+//!
+//! Clients usually publish a number of key packages at once. A
+//! [`KeyPackageBatch`] is created with [`KeyPackageBuilder::build_batch()`]
+//! and contains the key packages together with their hash references, which
+//! a delivery service can use to deduplicate them. The delivery service
+//! deserializes the batch as a [`KeyPackageBatchIn`] and validates it with
+//! [`KeyPackageBatchIn::validate()`].
+
+use openmls_traits::{
+    crypto::OpenMlsCrypto, key_store::OpenMlsKeyStore, signatures::Signer, OpenMlsCryptoProvider,
+};
+use serde::{Deserialize, Serialize};
+use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
+
+use super::{
+    errors::{KeyPackageBatchVerifyError, KeyPackageNewError, KeyPackageVerifyError},
+    KeyPackage, KeyPackageBuilder, KeyPackageIn, LifetimePolicy,
+};
+use crate::{
+    ciphersuite::{hash_ref::KeyPackageRef, signable::SignatureBatch},
+    credentials::CredentialWithKey,
+    group::config::CryptoConfig,
+    versions::ProtocolVersion,
+    write_batch::BatchProvider,
+};
+
+/// A batch of key packages and their hash references.
+///
+/// ```c
+/// struct {
+///     KeyPackageRef hash_ref;
+///     KeyPackage key_package;
+/// } KeyPackageBatchEntry;
+///
+/// struct {
+///     KeyPackageBatchEntry entries<V>;
+/// } KeyPackageBatch;
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TlsSerialize, TlsSize)]
+pub struct KeyPackageBatch {
+    entries: Vec<KeyPackageBatchEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TlsSerialize, TlsSize)]
+struct KeyPackageBatchEntry {
+    hash_ref: KeyPackageRef,
+    key_package: KeyPackage,
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+struct KeyPackageBatchEntryIn {
+    hash_ref: KeyPackageRef,
+    key_package: KeyPackageIn,
+}
+
+impl KeyPackageBatch {
+    /// Returns an iterator over the hash references and the key packages in
+    /// this batch.
+    pub fn entries(&self) -> impl Iterator<Item = (&KeyPackageRef, &KeyPackage)> {
+        self.entries
+            .iter()
+            .map(|entry| (&entry.hash_ref, &entry.key_package))
+    }
+
+    /// Returns the number of key packages in this batch.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if this batch doesn't contain any key packages.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Consumes the batch and returns the key packages.
+    pub fn into_key_packages(self) -> Vec<KeyPackage> {
+        self.entries
+            .into_iter()
+            .map(|entry| entry.key_package)
+            .collect()
+    }
+}
+
+impl From<KeyPackageBatch> for KeyPackageBatchIn {
+    fn from(batch: KeyPackageBatch) -> Self {
+        Self {
+            entries: batch
+                .entries
+                .into_iter()
+                .map(|entry| KeyPackageBatchEntryIn {
+                    hash_ref: entry.hash_ref,
+                    key_package: entry.key_package.into(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// An unverified [`KeyPackageBatch`], e.g. as received by a delivery
+/// service.
+#[derive(
+    Debug, Clone, PartialEq, Serialize, Deserialize, TlsSerialize, TlsDeserialize, TlsSize,
+)]
+pub struct KeyPackageBatchIn {
+    entries: Vec<KeyPackageBatchEntryIn>,
+}
+
+impl KeyPackageBatchIn {
+    /// Validates all key packages in this batch (see
+    /// [`KeyPackageIn::validate()`]) and checks that each hash reference
+    /// belongs to its key package. The signatures of all key packages are
+    /// verified together.
+    ///
+    /// Returns the [`KeyPackageBatch`] or a [`KeyPackageBatchVerifyError`] if
+    /// a key package is invalid or a hash reference doesn't match.
+    pub fn validate(
+        self,
+        crypto: &impl OpenMlsCrypto,
+        protocol_version: ProtocolVersion,
+    ) -> Result<KeyPackageBatch, KeyPackageBatchVerifyError> {
+        self.validate_with_lifetime_policy(crypto, protocol_version, &LifetimePolicy::default())
+    }
+
+    /// Same as [`KeyPackageBatchIn::validate()`], except that the lifetimes
+    /// are checked according to the given [`LifetimePolicy`].
+    pub fn validate_with_lifetime_policy(
+        self,
+        crypto: &impl OpenMlsCrypto,
+        protocol_version: ProtocolVersion,
+        lifetime_policy: &LifetimePolicy,
+    ) -> Result<KeyPackageBatch, KeyPackageBatchVerifyError> {
+        let mut signatures = SignatureBatch::new();
+        let entries = self
+            .entries
+            .into_iter()
+            .map(|entry| {
+                let key_package = entry
+                    .key_package
+                    .validate_batched(protocol_version, &mut signatures)?;
+                if key_package.hash_ref(crypto)? != entry.hash_ref {
+                    return Err(KeyPackageBatchVerifyError::HashReferenceMismatch);
+                }
+                Ok(KeyPackageBatchEntry {
+                    hash_ref: entry.hash_ref,
+                    key_package,
+                })
+            })
+            .collect::<Result<Vec<_>, KeyPackageBatchVerifyError>>()?;
+        signatures.verify(crypto)?;
+        if entries
+            .iter()
+            .any(|entry| !entry.key_package.has_acceptable_lifetime(lifetime_policy))
+        {
+            return Err(KeyPackageVerifyError::InvalidLifetime.into());
+        }
+        Ok(KeyPackageBatch { entries })
+    }
+}
+
+impl KeyPackageBuilder {
+    /// Finalize and build `count` key packages that share the configuration
+    /// of this builder, the `credential_with_key` and the `signer`.
+    ///
+    /// Each key package gets its own init and encryption keys. All key
+    /// packages and their private keys are written to the key store in a
+    /// single batch (see [`OpenMlsKeyStore::apply_batch()`]), so that either
+    /// all of them are stored or none.
+    ///
+    /// Returns a [`KeyPackageBatch`] with the key packages and their hash
+    /// references, which can be serialized and uploaded to a delivery
+    /// service.
+    pub fn build_batch<KeyStore: OpenMlsKeyStore>(
+        mut self,
+        count: usize,
+        config: CryptoConfig,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+        credential_with_key: CredentialWithKey,
+    ) -> Result<KeyPackageBatch, KeyPackageNewError<KeyStore::Error>> {
+        self.ciphersuite_policy.check(config.ciphersuite)?;
+        let key_package_extensions = self.take_key_package_extensions();
+        let lifetime = self.key_package_lifetime.unwrap_or_default();
+        let leaf_node_capabilities = self.leaf_node_capabilities.unwrap_or_default();
+        let leaf_node_extensions = self.leaf_node_extensions.unwrap_or_default();
+
+        let mut creation_results = Vec::with_capacity(count);
+        for _ in 0..count {
+            let creation_result = KeyPackage::create(
+                config,
+                backend,
+                signer,
+                credential_with_key.clone(),
+                lifetime,
+                key_package_extensions.clone(),
+                leaf_node_capabilities.clone(),
+                leaf_node_extensions.clone(),
+            )?;
+            let hash_ref = creation_result.key_package.hash_ref(backend.crypto())?;
+            creation_results.push((hash_ref, creation_result));
+        }
+
+        let batch_backend = BatchProvider::new(backend);
+        for (hash_ref, creation_result) in &creation_results {
+            creation_result.write_to_key_store(&batch_backend, hash_ref)?;
+        }
+        batch_backend
+            .apply()
+            .map_err(KeyPackageNewError::KeyStoreError)?;
+
+        Ok(KeyPackageBatch {
+            entries: creation_results
+                .into_iter()
+                .map(|(hash_ref, creation_result)| KeyPackageBatchEntry {
+                    hash_ref,
+                    key_package: creation_result.key_package,
+                })
+                .collect(),
+        })
+    }
+}
@@ -4,7 +4,10 @@
 
 use thiserror::Error;
 
-use crate::{ciphersuite::signable::SignatureError, error::LibraryError};
+use crate::{
+    ciphersuite::{policy::CiphersuitePolicyError, signable::SignatureError},
+    error::LibraryError,
+};
 
 /// KeyPackage verify error
 #[derive(Error, Debug, PartialEq, Clone)]
@@ -36,6 +39,10 @@ pub enum KeyPackageVerifyError {
     /// The protocol version is not valid.
     #[error("The protocol version is not valid.")]
     InvalidProtocolVersion,
+    /// The capabilities of the leaf node don't include the ciphersuite or the
+    /// protocol version of the key package, or an extension of the leaf node.
+    #[error("The capabilities of the leaf node don't match the key package.")]
+    InsufficientCapabilities,
 }
 
 /// KeyPackage extension support error
@@ -55,10 +62,44 @@ pub enum KeyPackageNewError<KeyStoreError> {
     /// The ciphersuite does not match the signature scheme.
     #[error("The ciphersuite does not match the signature scheme.")]
     CiphersuiteSignatureSchemeMismatch,
+    /// The ciphersuite is not supported by the crypto provider.
+    #[error("The ciphersuite is not supported by the crypto provider.")]
+    UnsupportedCiphersuite,
     /// Accessing the key store failed.
     #[error("Accessing the key store failed.")]
     KeyStoreError(KeyStoreError),
     /// See [`SignatureError`] for more details.
     #[error(transparent)]
     SignatureError(#[from] SignatureError),
+    /// See [`CiphersuitePolicyError`] for more details.
+    #[error(transparent)]
+    CiphersuitePolicy(#[from] CiphersuitePolicyError),
+}
+
+/// KeyPackage from signed parts error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum KeyPackageFromSignedPartsError<KeyStoreError> {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`KeyPackageVerifyError`] for more details.
+    #[error(transparent)]
+    KeyPackageVerifyError(#[from] KeyPackageVerifyError),
+    /// Accessing the key store failed.
+    #[error("Accessing the key store failed.")]
+    KeyStoreError(KeyStoreError),
+}
+
+/// KeyPackage batch verify error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum KeyPackageBatchVerifyError {
+    /// See [`LibraryError`] for more details.
+    #[error(transparent)]
+    LibraryError(#[from] LibraryError),
+    /// See [`KeyPackageVerifyError`] for more details.
+    #[error(transparent)]
+    KeyPackageVerifyError(#[from] KeyPackageVerifyError),
+    /// A hash reference doesn't match its key package.
+    #[error("A hash reference doesn't match its key package.")]
+    HashReferenceMismatch,
 }
@@ -2,20 +2,32 @@
 //! of KeyPackages.
 
 use crate::{
-    ciphersuite::{signable::*, *},
+    ciphersuite::{
+        hash_ref::{make_key_package_ref, KeyPackageRef},
+        signable::*,
+        *,
+    },
     credentials::*,
+    error::LibraryError,
     extensions::Extensions,
     treesync::node::leaf_node::{LeafNode, LeafNodeIn, VerifiableLeafNode},
     versions::ProtocolVersion,
 };
-use openmls_traits::{crypto::OpenMlsCrypto, types::Ciphersuite};
+use openmls_traits::{
+    crypto::OpenMlsCrypto,
+    types::{Ciphersuite, VerifiableCiphersuite},
+};
 use serde::{Deserialize, Serialize};
 use tls_codec::{Serialize as TlsSerializeTrait, TlsDeserialize, TlsSerialize, TlsSize};
 
 use super::{
-    errors::KeyPackageVerifyError, KeyPackage, KeyPackageTbs, SIGNATURE_KEY_PACKAGE_LABEL,
+    errors::KeyPackageVerifyError, KeyPackage, KeyPackageTbs, Lifetime, LifetimePolicy,
+    SIGNATURE_KEY_PACKAGE_LABEL,
 };
 
+#[cfg(feature = "diagnostics")]
+use crate::framing::{errors::DecodeError, tracing_decoder};
+
 /// Intermediary struct for deserialization of a [`KeyPackageIn`].
 struct VerifiableKeyPackage {
     payload: KeyPackageTbs,
@@ -42,6 +54,27 @@ impl Verifiable for VerifiableKeyPackage {
     }
 }
 
+/// The signed part of a [`KeyPackageIn`], to check its signature without
+/// verifying the leaf node first.
+struct UnverifiedKeyPackage<'a> {
+    payload: &'a KeyPackageTbsIn,
+    signature: &'a Signature,
+}
+
+impl Verifiable for UnverifiedKeyPackage<'_> {
+    fn unsigned_payload(&self) -> Result<Vec<u8>, tls_codec::Error> {
+        self.payload.tls_serialize_detached()
+    }
+
+    fn signature(&self) -> &Signature {
+        self.signature
+    }
+
+    fn label(&self) -> &str {
+        SIGNATURE_KEY_PACKAGE_LABEL
+    }
+}
+
 impl VerifiedStruct<VerifiableKeyPackage> for KeyPackage {
     type SealingType = private_mod::Seal;
 
@@ -90,8 +123,36 @@ pub struct KeyPackageIn {
 }
 
 impl KeyPackageIn {
-    /// Returns a [`CredentialWithKey`] from the unverified payload
-    pub fn unverified_credential(&self) -> CredentialWithKey {
+    /// Deserialize a [`KeyPackageIn`] from `bytes`.
+    ///
+    /// In contrast to the regular deserialization, the returned
+    /// [`DecodeError`] contains the byte offset and the path of the field that
+    /// could not be decoded, e.g. `KeyPackage.leaf_node.capabilities`.
+    #[cfg(feature = "diagnostics")]
+    pub fn tls_deserialize_traced(bytes: &[u8]) -> Result<Self, DecodeError> {
+        tracing_decoder::deserialize_traced(bytes, "KeyPackage", tracing_decoder::trace_key_package)
+    }
+
+    /// Compute the [`KeyPackageRef`] of this key package without validating
+    /// it, e.g. so that a DS can reference the key packages it stores. The
+    /// reference is the same as the one of the [`KeyPackage`] returned by
+    /// [`KeyPackageIn::validate()`].
+    pub fn hash_ref(&self, crypto: &impl OpenMlsCrypto) -> Result<KeyPackageRef, LibraryError> {
+        make_key_package_ref(
+            &self
+                .tls_serialize_detached()
+                .map_err(LibraryError::missing_bound_check)?,
+            self.payload.ciphersuite,
+            crypto,
+        )
+        .map_err(LibraryError::unexpected_crypto_error)
+    }
+
+    /// Returns a [`CredentialWithKey`] from the unverified payload.
+    ///
+    /// The credential is only exposed through the [`KeyPackage`] returned by
+    /// [`KeyPackageIn::validate()`].
+    pub(crate) fn unverified_credential(&self) -> CredentialWithKey {
         let credential = self.payload.leaf_node.credential().clone();
         let signature_key = self.payload.leaf_node.signature_key().clone();
         CredentialWithKey {
@@ -100,6 +161,21 @@ impl KeyPackageIn {
         }
     }
 
+    /// Returns the (unverified) leaf node of this key package.
+    pub(crate) fn leaf_node(&self) -> &LeafNodeIn {
+        &self.payload.leaf_node
+    }
+
+    /// Returns the (unverified) extensions of this key package.
+    pub(crate) fn extensions(&self) -> &Extensions {
+        &self.payload.extensions
+    }
+
+    /// Returns the (unverified) [`Lifetime`] of this key package if present.
+    pub(crate) fn life_time(&self) -> Option<&Lifetime> {
+        self.payload.leaf_node.life_time()
+    }
+
     /// Verify that this key package is valid:
     /// * verify that the signature on this key package is valid
     /// * verify that the signature on the leaf node is valid
@@ -115,6 +191,118 @@ impl KeyPackageIn {
         crypto: &impl OpenMlsCrypto,
         protocol_version: ProtocolVersion,
     ) -> Result<KeyPackage, KeyPackageVerifyError> {
+        self.validate_with_lifetime_policy(crypto, protocol_version, &LifetimePolicy::default())
+    }
+
+    /// Same as [`KeyPackageIn::validate()`], except that the lifetime is
+    /// checked according to the given [`LifetimePolicy`], e.g. with a
+    /// tolerance for skewed clocks.
+    pub fn validate_with_lifetime_policy(
+        self,
+        crypto: &impl OpenMlsCrypto,
+        protocol_version: ProtocolVersion,
+        lifetime_policy: &LifetimePolicy,
+    ) -> Result<KeyPackage, KeyPackageVerifyError> {
+        let mut signatures = SignatureBatch::new();
+        let key_package = self.validate_batched(protocol_version, &mut signatures)?;
+        signatures.verify(crypto)?;
+        if !key_package.has_acceptable_lifetime(lifetime_policy) {
+            return Err(KeyPackageVerifyError::InvalidLifetime);
+        }
+        Ok(key_package)
+    }
+
+    /// Checks this key package without consuming it, e.g. when a client
+    /// uploads it to a DS, and returns the errors of all failed checks instead
+    /// of only the first one. Returns an empty vector if the key package is
+    /// valid.
+    ///
+    /// The key package is checked like in
+    /// [`KeyPackageIn::validate_with_lifetime_policy()`]. In addition, the
+    /// capabilities of the leaf node must include the ciphersuite and the
+    /// protocol version of the key package as well as the extensions of the
+    /// leaf node, since groups reject key packages that don't when they are
+    /// added ([`KeyPackageVerifyError::InsufficientCapabilities`]).
+    pub fn check(
+        &self,
+        crypto: &impl OpenMlsCrypto,
+        protocol_version: ProtocolVersion,
+        lifetime_policy: &LifetimePolicy,
+    ) -> Vec<KeyPackageVerifyError> {
+        let mut errors = vec![];
+        let leaf_node = &self.payload.leaf_node;
+        let signature_key = OpenMlsSignaturePublicKey::from_signature_key(
+            leaf_node.signature_key().clone(),
+            self.payload.ciphersuite.signature_algorithm(),
+        );
+
+        match leaf_node.clone().into_verifiable_leaf_node() {
+            VerifiableLeafNode::KeyPackage(verifiable) => {
+                if verifiable.verify_no_out(crypto, &signature_key).is_err() {
+                    errors.push(KeyPackageVerifyError::InvalidLeafNodeSignature);
+                }
+            }
+            _ => errors.push(KeyPackageVerifyError::InvalidLeafNodeSourceType),
+        }
+        let unverified = UnverifiedKeyPackage {
+            payload: &self.payload,
+            signature: &self.signature,
+        };
+        if unverified.verify_no_out(crypto, &signature_key).is_err() {
+            errors.push(KeyPackageVerifyError::InvalidSignature);
+        }
+
+        if !self.version_is_supported(protocol_version) {
+            errors.push(KeyPackageVerifyError::InvalidProtocolVersion);
+        }
+        if leaf_node.encryption_key().key() == &self.payload.init_key {
+            errors.push(KeyPackageVerifyError::InitKeyEqualsEncryptionKey);
+        }
+        if self
+            .payload
+            .extensions
+            .iter()
+            .any(|extension| !leaf_node.supports_extension(&extension.extension_type()))
+        {
+            errors.push(KeyPackageVerifyError::UnsupportedExtension);
+        }
+        match leaf_node.life_time() {
+            None => errors.push(KeyPackageVerifyError::MissingLifetime),
+            Some(lifetime) if !lifetime_policy.accepts(lifetime) => {
+                errors.push(KeyPackageVerifyError::InvalidLifetime)
+            }
+            Some(_) => (),
+        }
+
+        let capabilities = leaf_node.capabilities();
+        if !capabilities
+            .ciphersuites()
+            .contains(&VerifiableCiphersuite::from(self.payload.ciphersuite))
+            || !capabilities
+                .versions()
+                .contains(&self.payload.protocol_version)
+            || !capabilities.contain_extensions(leaf_node.extensions())
+        {
+            errors.push(KeyPackageVerifyError::InsufficientCapabilities);
+        }
+
+        errors
+    }
+
+    /// Same as [`KeyPackageIn::validate()`], except that the signature checks
+    /// are added to `signatures` instead of being performed right away.
+    ///
+    /// Only the presence of the lifetime is checked. Whether it is acceptable
+    /// depends on the [`LifetimePolicy`] of the caller, see
+    /// [`KeyPackage::has_acceptable_lifetime()`].
+    ///
+    /// The returned [`KeyPackage`] MUST be discarded unless `signatures` is
+    /// verified successfully.
+    pub(crate) fn validate_batched<E: From<KeyPackageVerifyError>>(
+        self,
+        protocol_version: ProtocolVersion,
+        signatures: &mut SignatureBatch<E>,
+    ) -> Result<KeyPackage, E> {
         // We first need to verify the LeafNode inside the KeyPackage
         let leaf_node = self.payload.leaf_node.clone().into_verifiable_leaf_node();
 
@@ -123,21 +311,23 @@ impl KeyPackageIn {
             self.payload.ciphersuite.signature_algorithm(),
         );
 
-        let leaf_node = match leaf_node {
-            VerifiableLeafNode::KeyPackage(leaf_node) => leaf_node
-                .verify::<LeafNode>(crypto, signature_key)
-                .map_err(|_| KeyPackageVerifyError::InvalidLeafNodeSignature)?,
-            _ => return Err(KeyPackageVerifyError::InvalidLeafNodeSourceType),
+        let leaf_node: LeafNode = match leaf_node {
+            VerifiableLeafNode::KeyPackage(leaf_node) => signatures.add(
+                leaf_node,
+                signature_key,
+                KeyPackageVerifyError::InvalidLeafNodeSignature.into(),
+            )?,
+            _ => return Err(KeyPackageVerifyError::InvalidLeafNodeSourceType.into()),
         };
 
         // Verify that the protocol version is valid
         if !self.version_is_supported(protocol_version) {
-            return Err(KeyPackageVerifyError::InvalidProtocolVersion);
+            return Err(KeyPackageVerifyError::InvalidProtocolVersion.into());
         }
 
         // Verify that the encryption key and the init key are different
         if leaf_node.encryption_key().key() == &self.payload.init_key {
-            return Err(KeyPackageVerifyError::InitKeyEqualsEncryptionKey);
+            return Err(KeyPackageVerifyError::InitKeyEqualsEncryptionKey.into());
         }
 
         let key_package_tbs = KeyPackageTbs {
@@ -149,9 +339,11 @@ impl KeyPackageIn {
         };
 
         // Verify the KeyPackage signature
-        let key_package = VerifiableKeyPackage::new(key_package_tbs, self.signature)
-            .verify::<KeyPackage>(crypto, signature_key)
-            .map_err(|_| KeyPackageVerifyError::InvalidSignature)?;
+        let key_package: KeyPackage = signatures.add(
+            VerifiableKeyPackage::new(key_package_tbs, self.signature),
+            signature_key,
+            KeyPackageVerifyError::InvalidSignature.into(),
+        )?;
 
         // Extension included in the extensions or leaf_node.extensions fields
         // MUST be included in the leaf_node.capabilities field.
@@ -161,19 +353,15 @@ impl KeyPackageIn {
                 .leaf_node
                 .supports_extension(&extension.extension_type())
             {
-                return Err(KeyPackageVerifyError::UnsupportedExtension);
+                return Err(KeyPackageVerifyError::UnsupportedExtension.into());
             }
         }
 
-        // Ensure validity of the life time extension in the leaf node.
-        if let Some(life_time) = key_package.payload.leaf_node.life_time() {
-            if !life_time.is_valid() {
-                return Err(KeyPackageVerifyError::InvalidLifetime);
-            }
-        } else {
+        // Ensure presence of the life time extension in the leaf node.
+        if key_package.payload.leaf_node.life_time().is_none() {
             // This assumes that we only verify key packages with leaf nodes
             // that were created for the key package.
-            return Err(KeyPackageVerifyError::MissingLifetime);
+            return Err(KeyPackageVerifyError::MissingLifetime.into());
         }
 
         Ok(key_package)
@@ -1,4 +1,4 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use serde::{Deserialize, Serialize};
 use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
@@ -12,6 +12,10 @@ const DEFAULT_KEY_PACKAGE_LIFETIME_SECONDS: u64 = 60 * 60 * 24 * 28 * 3;
 /// value is in seconds and amounts to 1h.
 const DEFAULT_KEY_PACKAGE_LIFETIME_MARGIN_SECONDS: u64 = 60 * 60;
 
+/// This value is used as the default remaining lifetime (in seconds) below
+/// which a `KeyPackage` should be replaced. The value amounts to 7 days.
+const DEFAULT_KEY_PACKAGE_REFRESH_THRESHOLD_SECONDS: u64 = 60 * 60 * 24 * 7;
+
 /// The maximum total lifetime range that is acceptable for a leaf node.
 /// The value is in seconds and amounts to 3 * 28 Days, i.e., about 3 months.
 const MAX_LEAF_NODE_LIFETIME_RANGE_SECONDS: u64 =
@@ -60,13 +64,30 @@ impl Lifetime {
         }
     }
 
-    /// Returns true if this lifetime is valid.
-    pub(crate) fn is_valid(&self) -> bool {
-        match SystemTime::now()
+    /// Returns the time (in seconds since the Unix epoch) before which the
+    /// lifetime isn't valid.
+    pub fn not_before(&self) -> u64 {
+        self.not_before
+    }
+
+    /// Returns the time (in seconds since the Unix epoch) after which the
+    /// lifetime isn't valid.
+    pub fn not_after(&self) -> u64 {
+        self.not_after
+    }
+
+    /// Returns true if this lifetime is valid at the time `now`, where both
+    /// ends of the lifetime are extended by the `clock_skew_tolerance`.
+    pub fn is_valid_at(&self, now: SystemTime, clock_skew_tolerance: Duration) -> bool {
+        match now
             .duration_since(UNIX_EPOCH)
             .map(|duration| duration.as_secs())
         {
-            Ok(elapsed) => self.not_before < elapsed && elapsed < self.not_after,
+            Ok(elapsed) => {
+                let tolerance = clock_skew_tolerance.as_secs();
+                self.not_before.saturating_sub(tolerance) < elapsed
+                    && elapsed < self.not_after.saturating_add(tolerance)
+            }
             Err(_) => {
                 log::error!("SystemTime before UNIX EPOCH.");
                 false
@@ -74,6 +95,21 @@ impl Lifetime {
         }
     }
 
+    /// Returns true if this lifetime ends within the `refresh_threshold`
+    /// after the time `now`.
+    pub fn needs_refresh(&self, now: SystemTime, refresh_threshold: Duration) -> bool {
+        match now
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+        {
+            Ok(elapsed) => elapsed.saturating_add(refresh_threshold.as_secs()) >= self.not_after,
+            Err(_) => {
+                log::error!("SystemTime before UNIX EPOCH.");
+                true
+            }
+        }
+    }
+
     /// ValSem(openmls/annotations#32):
     /// Applications MUST define a maximum total lifetime that is acceptable for a LeafNode,
     /// and reject any LeafNode where the total lifetime is longer than this duration.
@@ -88,22 +124,136 @@ impl Default for Lifetime {
     }
 }
 
+/// Policy for the lifetimes of key packages.
+///
+///  - lifetime:
+/// The lifetime of new key packages, see [`LifetimePolicy::new_lifetime()`].
+/// The default value is 3 * 28 days, i.e. about 3 months.
+///  - refresh_threshold:
+/// The remaining lifetime below which a key package should be replaced, see
+/// [`KeyPackage::needs_refresh()`](super::KeyPackage::needs_refresh()). The
+/// default value is 7 days.
+///  - clock_skew_tolerance:
+/// How far the local clock may be off when checking the lifetime of a
+/// received key package. The default value is 0.
+///  - enforce:
+/// Whether received key packages with a lifetime that doesn't cover the
+/// current time are rejected. If set to `false`, they are accepted and only a
+/// warning is logged. The default value is `true`.
+///
+/// Clocks on mobile devices are often unreliable. A clock skew tolerance or
+/// disabling the enforcement avoids that members can't be added to groups
+/// because the clock of the adding member or of the new member is off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LifetimePolicy {
+    lifetime: Duration,
+    refresh_threshold: Duration,
+    clock_skew_tolerance: Duration,
+    enforce: bool,
+}
+
+impl Default for LifetimePolicy {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(DEFAULT_KEY_PACKAGE_LIFETIME_SECONDS))
+    }
+}
+
+impl LifetimePolicy {
+    /// Create a new policy for key packages with the given `lifetime`.
+    pub fn new(lifetime: Duration) -> Self {
+        Self {
+            lifetime,
+            refresh_threshold: Duration::from_secs(DEFAULT_KEY_PACKAGE_REFRESH_THRESHOLD_SECONDS),
+            clock_skew_tolerance: Duration::ZERO,
+            enforce: true,
+        }
+    }
+
+    /// Set the remaining lifetime below which a key package should be
+    /// replaced.
+    pub fn with_refresh_threshold(mut self, refresh_threshold: Duration) -> Self {
+        self.refresh_threshold = refresh_threshold;
+        self
+    }
+
+    /// Set how far the local clock may be off when checking the lifetime of a
+    /// received key package.
+    pub fn with_clock_skew_tolerance(mut self, clock_skew_tolerance: Duration) -> Self {
+        self.clock_skew_tolerance = clock_skew_tolerance;
+        self
+    }
+
+    /// Set whether received key packages with an invalid lifetime are
+    /// rejected.
+    pub fn with_enforcement(mut self, enforce: bool) -> Self {
+        self.enforce = enforce;
+        self
+    }
+
+    /// Get the lifetime of new key packages.
+    pub fn lifetime(&self) -> Duration {
+        self.lifetime
+    }
+
+    /// Get the remaining lifetime below which a key package should be
+    /// replaced.
+    pub fn refresh_threshold(&self) -> Duration {
+        self.refresh_threshold
+    }
+
+    /// Get the clock skew tolerance.
+    pub fn clock_skew_tolerance(&self) -> Duration {
+        self.clock_skew_tolerance
+    }
+
+    /// Get whether received key packages with an invalid lifetime are
+    /// rejected.
+    pub fn enforce(&self) -> bool {
+        self.enforce
+    }
+
+    /// Returns a new [`Lifetime`] that starts now and lasts for the lifetime
+    /// of this policy.
+    pub fn new_lifetime(&self) -> Lifetime {
+        Lifetime::new(self.lifetime.as_secs())
+    }
+
+    /// Returns true if the `lifetime` of a received key package is acceptable
+    /// under this policy.
+    pub(crate) fn accepts(&self, lifetime: &Lifetime) -> bool {
+        if lifetime.is_valid_at(SystemTime::now(), self.clock_skew_tolerance) {
+            return true;
+        }
+        if self.enforce {
+            return false;
+        }
+        log::warn!(
+            "Accepting a key package with an invalid lifetime ({} - {}).",
+            lifetime.not_before,
+            lifetime.not_after
+        );
+        true
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::time::{Duration, SystemTime};
+
     use tls_codec::{Deserialize, Serialize};
 
-    use super::Lifetime;
+    use super::{Lifetime, LifetimePolicy};
 
     #[test]
     fn lifetime() {
         // A freshly created extensions must be valid.
         let ext = Lifetime::default();
-        assert!(ext.is_valid());
+        assert!(ext.is_valid_at(SystemTime::now(), Duration::ZERO));
 
         // An extension without lifetime is invalid (waiting for 1 second).
         let ext = Lifetime::new(0);
         std::thread::sleep(std::time::Duration::from_secs(1));
-        assert!(!ext.is_valid());
+        assert!(!ext.is_valid_at(SystemTime::now(), Duration::ZERO));
 
         // Test (de)serializing invalid extension
         let serialized = ext
@@ -111,6 +261,31 @@ mod tests {
             .expect("error encoding life time extension");
         let ext_deserialized = Lifetime::tls_deserialize(&mut serialized.as_slice())
             .expect("Error deserializing lifetime");
-        assert!(!ext_deserialized.is_valid());
+        assert!(!ext_deserialized.is_valid_at(SystemTime::now(), Duration::ZERO));
+    }
+
+    #[test]
+    fn lifetime_policy() {
+        let now = SystemTime::now();
+        let lifetime = Lifetime::new(60);
+        assert!(lifetime.is_valid_at(now, Duration::ZERO));
+        assert!(!lifetime.is_valid_at(now + Duration::from_secs(120), Duration::ZERO));
+        assert!(lifetime.is_valid_at(now + Duration::from_secs(120), Duration::from_secs(600)));
+
+        // The lifetime ends within the default refresh threshold.
+        let policy = LifetimePolicy::default();
+        assert!(lifetime.needs_refresh(now, policy.refresh_threshold()));
+        assert!(!policy
+            .new_lifetime()
+            .needs_refresh(now, policy.refresh_threshold()));
+
+        // Invalid lifetimes are only accepted if the policy doesn't enforce
+        // them.
+        let expired = Lifetime {
+            not_before: 0,
+            not_after: 1,
+        };
+        assert!(!policy.accepts(&expired));
+        assert!(policy.with_enforcement(false).accepts(&expired));
     }
 }
@@ -59,6 +59,53 @@
 //!
 //! See [`KeyPackage`] for more details and other ways to create key packages.
 //!
+//! ## Creating key packages with an external signer
+//!
+//! If the signature key is not available to OpenMLS, e.g. because it lives on
+//! an air-gapped signer, the key package can be created in steps. The leaf
+//! node and the key package are signed separately, and the key package can
+//! only be signed after the leaf node signature has been attached.
+//!
+//! ```
+//! use openmls::prelude::*;
+//! use openmls_rust_crypto::OpenMlsRustCrypto;
+//! use openmls_basic_credential::SignatureKeyPair;
+//! use openmls_traits::signatures::Signer;
+//!
+//! let ciphersuite = Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
+//! let backend = OpenMlsRustCrypto::default();
+//!
+//! // The signer is only used to simulate the external signer here.
+//! let signer =
+//!     SignatureKeyPair::new(ciphersuite.signature_algorithm())
+//!         .expect("Error generating a signature key pair.");
+//! let credential = Credential::new("identity".into(), CredentialType::Basic).unwrap();
+//! let credential_with_key = CredentialWithKey {
+//!     credential,
+//!     signature_key: signer.public().into(),
+//! };
+//! let mut key_package_tbs = KeyPackageTbs::new(
+//!     CryptoConfig {
+//!         ciphersuite,
+//!         version: ProtocolVersion::default(),
+//!     },
+//!     &backend,
+//!     credential_with_key,
+//! )
+//! .unwrap();
+//!
+//! // Sign the leaf node ...
+//! let leaf_node_sign_content = key_package_tbs.leaf_node_sign_content().unwrap();
+//! let leaf_node_signature = signer.sign(&leaf_node_sign_content).unwrap();
+//! key_package_tbs.set_leaf_node_signature(leaf_node_signature.into());
+//!
+//! // ... and then the key package.
+//! let sign_content = key_package_tbs.sign_content().unwrap();
+//! let signature = signer.sign(&sign_content).unwrap();
+//! let key_package =
+//!     KeyPackage::from_signed_parts(&backend, key_package_tbs, signature.into()).unwrap();
+//! ```
+//!
 //! ## Loading key packages
 //!
 //! When getting key packages from another user the serialized bytes are parsed
@@ -98,13 +145,14 @@ use crate::treesync::node::encryption_keys::EncryptionKey;
 use crate::{
     ciphersuite::{
         hash_ref::{make_key_package_ref, KeyPackageRef},
+        policy::CiphersuitePolicy,
         signable::*,
         *,
     },
     credentials::*,
     error::LibraryError,
-    extensions::ExtensionType,
     extensions::Extensions,
+    extensions::{CredentialBinding, Extension, ExtensionType, LastResortExtension},
     group::config::CryptoConfig,
     treesync::{
         node::{
@@ -118,11 +166,13 @@ use crate::{
 use openmls_traits::{
     crypto::OpenMlsCrypto,
     key_store::{MlsEntity, MlsEntityId, OpenMlsKeyStore},
+    random::OpenMlsRand,
     signatures::Signer,
     types::Ciphersuite,
     OpenMlsCryptoProvider,
 };
 use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
 use tls_codec::{Serialize as TlsSerializeTrait, TlsSerialize, TlsSize};
 
 // Private
@@ -132,6 +182,7 @@ use errors::*;
 pub mod errors;
 pub mod key_package_in;
 
+mod batch;
 mod lifetime;
 
 // Tests
@@ -139,13 +190,19 @@ mod lifetime;
 pub(crate) mod test_key_packages;
 
 // Public types
+pub use batch::{KeyPackageBatch, KeyPackageBatchIn};
 pub use key_package_in::KeyPackageIn;
-pub use lifetime::Lifetime;
+pub use lifetime::{Lifetime, LifetimePolicy};
 
 /// The unsigned payload of a key package.
 /// Any modification must happen on this unsigned struct. Use `sign` to get a
 /// signed key package.
 ///
+/// A [`KeyPackageTbs`] can also be created with [`KeyPackageTbs::new()`] or
+/// [`KeyPackageBuilder::build_tbs()`] to sign it outside of OpenMLS. The
+/// signed key package is then assembled with
+/// [`KeyPackage::from_signed_parts()`].
+///
 /// ```text
 /// struct {
 ///     ProtocolVersion version;
@@ -156,7 +213,7 @@ pub use lifetime::Lifetime;
 /// } KeyPackageTBS;
 /// ```
 #[derive(Debug, Clone, PartialEq, TlsSize, TlsSerialize, Serialize, Deserialize)]
-struct KeyPackageTbs {
+pub struct KeyPackageTbs {
     protocol_version: ProtocolVersion,
     ciphersuite: Ciphersuite,
     init_key: HpkePublicKey,
@@ -176,6 +233,43 @@ impl Signable for KeyPackageTbs {
     }
 }
 
+impl KeyPackageTbs {
+    /// Create a new unsigned key package with the default lifetime,
+    /// capabilities and extensions. Use [`KeyPackageBuilder::build_tbs()`]
+    /// to configure them.
+    ///
+    /// The private init key and the private encryption key of the leaf node
+    /// are stored in the key store right away. The key package itself is
+    /// stored when it is assembled with [`KeyPackage::from_signed_parts()`].
+    pub fn new<KeyStore: OpenMlsKeyStore>(
+        config: CryptoConfig,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        credential_with_key: CredentialWithKey,
+    ) -> Result<Self, KeyPackageNewError<KeyStore::Error>> {
+        KeyPackageBuilder::new().build_tbs(config, backend, credential_with_key)
+    }
+
+    /// Returns the bytes the external signer has to sign to produce the
+    /// signature of the leaf node.
+    pub fn leaf_node_sign_content(&self) -> Result<Vec<u8>, LibraryError> {
+        self.leaf_node.key_package_sign_content()
+    }
+
+    /// Set the signature of the leaf node. This has to be done before
+    /// calling [`KeyPackageTbs::sign_content()`], because the leaf node
+    /// signature is part of the signed key package content.
+    pub fn set_leaf_node_signature(&mut self, signature: Signature) {
+        self.leaf_node.set_signature(signature)
+    }
+
+    /// Returns the bytes the external signer has to sign to produce the
+    /// signature of the key package.
+    pub fn sign_content(&self) -> Result<Vec<u8>, LibraryError> {
+        Signable::sign_content(self)
+            .map_err(|_| LibraryError::custom("Could not serialize the key package."))
+    }
+}
+
 impl From<KeyPackage> for KeyPackageTbs {
     fn from(kp: KeyPackage) -> Self {
         kp.payload
@@ -205,6 +299,9 @@ impl SignedStruct<KeyPackageTbs> for KeyPackage {
 
 const SIGNATURE_KEY_PACKAGE_LABEL: &str = "KeyPackageTBS";
 
+/// The length of the random identity of a pseudonymous credential.
+const PSEUDONYM_IDENTITY_LENGTH: usize = 32;
+
 impl MlsEntity for KeyPackage {
     const ID: MlsEntityId = MlsEntityId::KeyPackage;
 }
@@ -216,6 +313,38 @@ pub(crate) struct KeyPackageCreationResult {
     pub init_private_key: HpkePrivateKey,
 }
 
+impl KeyPackageCreationResult {
+    /// Store the key package and its private keys in the key store.
+    fn write_to_key_store<KeyStore: OpenMlsKeyStore>(
+        &self,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        hash_ref: &KeyPackageRef,
+    ) -> Result<(), KeyPackageNewError<KeyStore::Error>> {
+        // Store the key package in the key store with the hash reference as id
+        // for retrieval when parsing welcome messages.
+        backend
+            .key_store()
+            .store(hash_ref.as_slice(), &self.key_package)
+            .map_err(KeyPackageNewError::KeyStoreError)?;
+
+        // Store the encryption key pair in the key store.
+        self.encryption_keypair
+            .write_to_key_store(backend)
+            .map_err(KeyPackageNewError::KeyStoreError)?;
+
+        // Store the private part of the init_key into the key store.
+        // The key is the public key.
+        backend
+            .key_store()
+            .store::<HpkePrivateKey>(
+                self.key_package.hpke_init_key().as_slice(),
+                &self.init_private_key,
+            )
+            .map_err(KeyPackageNewError::KeyStoreError)?;
+        Ok(())
+    }
+}
+
 // Public `KeyPackage` functions.
 impl KeyPackage {
     /// Create a key package builder.
@@ -237,6 +366,12 @@ impl KeyPackage {
         leaf_node_capabilities: Capabilities,
         leaf_node_extensions: Extensions,
     ) -> Result<KeyPackageCreationResult, KeyPackageNewError<KeyStore::Error>> {
+        // Check up front that the provider can actually handle the
+        // ciphersuite, rather than failing somewhere in the middle.
+        backend
+            .crypto()
+            .supports(config.ciphersuite)
+            .map_err(|_| KeyPackageNewError::UnsupportedCiphersuite)?;
         if config.ciphersuite.signature_algorithm() != signer.signature_scheme() {
             return Err(KeyPackageNewError::CiphersuiteSignatureSchemeMismatch);
         }
@@ -246,7 +381,8 @@ impl KeyPackage {
             .map_err(LibraryError::unexpected_crypto_error)?;
         let init_key = backend
             .crypto()
-            .derive_hpke_keypair(config.ciphersuite.hpke_config(), ikm.as_slice());
+            .derive_hpke_keypair(config.ciphersuite.hpke_config(), ikm.as_slice())
+            .map_err(LibraryError::unexpected_crypto_error)?;
         let (key_package, encryption_keypair) = Self::new_from_keys(
             config,
             backend,
@@ -315,6 +451,34 @@ impl KeyPackage {
         Ok((key_package, encryption_key_pair))
     }
 
+    /// Assemble a key package from a [`KeyPackageTbs`] and its externally
+    /// produced signature.
+    ///
+    /// The signatures of the leaf node and the key package are verified
+    /// against the signature key of the credential before the key package is
+    /// stored in the key store.
+    pub fn from_signed_parts<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        key_package_tbs: KeyPackageTbs,
+        signature: Signature,
+    ) -> Result<Self, KeyPackageFromSignedPartsError<KeyStore::Error>> {
+        let protocol_version = key_package_tbs.protocol_version;
+        let key_package = KeyPackageIn::from(Self::from_payload(key_package_tbs, signature))
+            .validate(backend.crypto(), protocol_version)?;
+
+        // Store the key package in the key store with the hash reference as id
+        // for retrieval when parsing welcome messages.
+        backend
+            .key_store()
+            .store(
+                key_package.hash_ref(backend.crypto())?.as_slice(),
+                &key_package,
+            )
+            .map_err(KeyPackageFromSignedPartsError::KeyStoreError)?;
+
+        Ok(key_package)
+    }
+
     /// Delete this key package and its private key from the key store.
     pub fn delete<KeyStore: OpenMlsKeyStore>(
         &self,
@@ -333,6 +497,12 @@ impl KeyPackage {
         &self.payload.extensions
     }
 
+    /// Returns `true` if this is a last resort key package, i.e. if it has a
+    /// [`LastResortExtension`]. See [`KeyPackageBuilder::mark_as_last_resort()`].
+    pub fn last_resort(&self) -> bool {
+        self.extensions().last_resort().is_some()
+    }
+
     /// Check whether the this key package supports all the required extensions
     /// in the provided list.
     pub fn check_extension_support(
@@ -376,6 +546,54 @@ impl KeyPackage {
     pub fn hpke_init_key(&self) -> &HpkePublicKey {
         &self.payload.init_key
     }
+
+    /// Returns `true` if the lifetime of this key package ends within the
+    /// refresh threshold of the `policy` after `now`, i.e. if it should be
+    /// replaced through [`KeyPackage::refresh()`].
+    pub fn needs_refresh(&self, now: SystemTime, policy: &LifetimePolicy) -> bool {
+        self.leaf_node()
+            .life_time()
+            .map(|lifetime| lifetime.needs_refresh(now, policy.refresh_threshold()))
+            .unwrap_or(true)
+    }
+
+    /// Create a new key package that replaces this one.
+    ///
+    /// The new key package has the same ciphersuite, protocol version,
+    /// credential, capabilities and extensions, but fresh init and
+    /// encryption keys and a new lifetime according to the `policy`. The
+    /// `signer` must hold the signature key of this key package.
+    ///
+    /// The new key package and its private keys are written to the key store.
+    /// This key package is kept, since it may still be used in a [`Welcome`]
+    /// that is on its way. It can be removed through [`KeyPackage::delete()`]
+    /// once it was taken down from the delivery service.
+    ///
+    /// [`Welcome`]: crate::messages::Welcome
+    pub fn refresh<KeyStore: OpenMlsKeyStore>(
+        &self,
+        policy: &LifetimePolicy,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        signer: &impl Signer,
+    ) -> Result<KeyPackage, KeyPackageNewError<KeyStore::Error>> {
+        KeyPackageBuilder::new()
+            .key_package_lifetime(policy.new_lifetime())
+            .key_package_extensions(self.extensions().clone())
+            .leaf_node_capabilities(self.leaf_node().capabilities().clone())
+            .leaf_node_extensions(self.leaf_node().extensions().clone())
+            .build(
+                CryptoConfig {
+                    ciphersuite: self.ciphersuite(),
+                    version: self.protocol_version(),
+                },
+                backend,
+                signer,
+                CredentialWithKey {
+                    credential: self.leaf_node().credential().clone(),
+                    signature_key: self.leaf_node().signature_key().clone(),
+                },
+            )
+    }
 }
 
 /// Crate visible `KeyPackage` functions.
@@ -384,6 +602,15 @@ impl KeyPackage {
     pub(crate) fn protocol_version(&self) -> ProtocolVersion {
         self.payload.protocol_version
     }
+
+    /// Returns `true` if the lifetime of this key package is acceptable under
+    /// the `policy`.
+    pub(crate) fn has_acceptable_lifetime(&self, policy: &LifetimePolicy) -> bool {
+        self.leaf_node()
+            .life_time()
+            .map(|lifetime| policy.accepts(lifetime))
+            .unwrap_or(false)
+    }
 }
 
 /// Helpers for testing.
@@ -449,7 +676,8 @@ impl KeyPackage {
         let ikm = Secret::random(config.ciphersuite, backend, config.version).unwrap();
         let init_key = backend
             .crypto()
-            .derive_hpke_keypair(config.ciphersuite.hpke_config(), ikm.as_slice());
+            .derive_hpke_keypair(config.ciphersuite.hpke_config(), ikm.as_slice())
+            .map_err(LibraryError::unexpected_crypto_error)?;
 
         // Store the private part of the init_key into the key store.
         // The key is the public key.
@@ -555,6 +783,10 @@ pub struct KeyPackageBuilder {
     key_package_extensions: Option<Extensions>,
     leaf_node_capabilities: Option<Capabilities>,
     leaf_node_extensions: Option<Extensions>,
+    #[serde(default)]
+    last_resort: bool,
+    #[serde(default)]
+    ciphersuite_policy: CiphersuitePolicy,
 }
 
 impl KeyPackageBuilder {
@@ -565,6 +797,8 @@ impl KeyPackageBuilder {
             key_package_extensions: None,
             leaf_node_capabilities: None,
             leaf_node_extensions: None,
+            last_resort: false,
+            ciphersuite_policy: CiphersuitePolicy::default(),
         }
     }
 
@@ -592,71 +826,185 @@ impl KeyPackageBuilder {
         self
     }
 
+    /// Mark the key package as a last resort key package by adding a
+    /// [`LastResortExtension`] to the key package extensions.
+    ///
+    /// Unlike other key packages, a last resort key package and its private
+    /// keys are not deleted from the key store when a [`Welcome`] for it is
+    /// processed, so that it can be used to join more than one group.
+    ///
+    /// [`Welcome`]: crate::messages::Welcome
+    pub fn mark_as_last_resort(mut self) -> Self {
+        self.last_resort = true;
+        self
+    }
+
+    /// Set the [`CiphersuitePolicy`] that the ciphersuite of the key package
+    /// has to satisfy. Building a key package with a ciphersuite that isn't
+    /// permitted fails with [`KeyPackageNewError::CiphersuitePolicy`].
+    pub fn ciphersuite_policy(mut self, ciphersuite_policy: CiphersuitePolicy) -> Self {
+        self.ciphersuite_policy = ciphersuite_policy;
+        self
+    }
+
+    /// Returns the key package extensions, including the
+    /// [`LastResortExtension`] if the key package is marked as last resort.
+    fn take_key_package_extensions(&mut self) -> Extensions {
+        let mut extensions = self.key_package_extensions.take().unwrap_or_default();
+        if self.last_resort {
+            extensions.add_or_replace(Extension::LastResort(LastResortExtension::new()));
+        }
+        extensions
+    }
+
     pub(crate) fn build_without_key_storage<KeyStore: OpenMlsKeyStore>(
-        self,
+        mut self,
         config: CryptoConfig,
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         signer: &impl Signer,
         credential_with_key: CredentialWithKey,
     ) -> Result<KeyPackageCreationResult, KeyPackageNewError<KeyStore::Error>> {
+        self.ciphersuite_policy.check(config.ciphersuite)?;
+        let key_package_extensions = self.take_key_package_extensions();
         KeyPackage::create(
             config,
             backend,
             signer,
             credential_with_key,
             self.key_package_lifetime.unwrap_or_default(),
-            self.key_package_extensions.unwrap_or_default(),
+            key_package_extensions,
             self.leaf_node_capabilities.unwrap_or_default(),
             self.leaf_node_extensions.unwrap_or_default(),
         )
     }
 
+    /// Finalize the unsigned key package for signing it outside of OpenMLS.
+    /// See [`KeyPackageTbs::new()`] for details.
+    pub fn build_tbs<KeyStore: OpenMlsKeyStore>(
+        mut self,
+        config: CryptoConfig,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        credential_with_key: CredentialWithKey,
+    ) -> Result<KeyPackageTbs, KeyPackageNewError<KeyStore::Error>> {
+        self.ciphersuite_policy.check(config.ciphersuite)?;
+        let key_package_extensions = self.take_key_package_extensions();
+        backend
+            .crypto()
+            .supports(config.ciphersuite)
+            .map_err(|_| KeyPackageNewError::UnsupportedCiphersuite)?;
+
+        // Create a new HPKE key pair for the init key and one for the leaf
+        // node.
+        let ikm = Secret::random(config.ciphersuite, backend, config.version)
+            .map_err(LibraryError::unexpected_crypto_error)?;
+        let init_key = backend
+            .crypto()
+            .derive_hpke_keypair(config.ciphersuite.hpke_config(), ikm.as_slice())
+            .map_err(LibraryError::unexpected_crypto_error)?;
+        let encryption_keypair = EncryptionKeyPair::random(backend, config)?;
+
+        let leaf_node = LeafNode::new_unsigned(
+            encryption_keypair.public_key().clone(),
+            credential_with_key,
+            self.key_package_lifetime.unwrap_or_default(),
+            self.leaf_node_capabilities.unwrap_or_default(),
+            self.leaf_node_extensions.unwrap_or_default(),
+        )?;
+
+        // Store the private keys in the key store.
+        encryption_keypair
+            .write_to_key_store(backend)
+            .map_err(KeyPackageNewError::KeyStoreError)?;
+        backend
+            .key_store()
+            .store::<HpkePrivateKey>(&init_key.public, &init_key.private)
+            .map_err(KeyPackageNewError::KeyStoreError)?;
+
+        Ok(KeyPackageTbs {
+            protocol_version: config.version,
+            ciphersuite: config.ciphersuite,
+            init_key: init_key.public.into(),
+            leaf_node,
+            extensions: key_package_extensions,
+        })
+    }
+
     /// Finalize and build the key package.
     pub fn build<KeyStore: OpenMlsKeyStore>(
-        self,
+        mut self,
         config: CryptoConfig,
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
         signer: &impl Signer,
         credential_with_key: CredentialWithKey,
     ) -> Result<KeyPackage, KeyPackageNewError<KeyStore::Error>> {
-        let KeyPackageCreationResult {
-            key_package,
-            encryption_keypair,
-            init_private_key,
-        } = KeyPackage::create(
+        self.ciphersuite_policy.check(config.ciphersuite)?;
+        let key_package_extensions = self.take_key_package_extensions();
+        let creation_result = KeyPackage::create(
             config,
             backend,
             signer,
             credential_with_key,
             self.key_package_lifetime.unwrap_or_default(),
-            self.key_package_extensions.unwrap_or_default(),
+            key_package_extensions,
             self.leaf_node_capabilities.unwrap_or_default(),
             self.leaf_node_extensions.unwrap_or_default(),
         )?;
+        let hash_ref = creation_result.key_package.hash_ref(backend.crypto())?;
+        creation_result.write_to_key_store(backend, &hash_ref)?;
 
-        // Store the key package in the key store with the hash reference as id
-        // for retrieval when parsing welcome messages.
-        backend
-            .key_store()
-            .store(
-                key_package.hash_ref(backend.crypto())?.as_slice(),
-                &key_package,
-            )
-            .map_err(KeyPackageNewError::KeyStoreError)?;
-
-        // Store the encryption key pair in the key store.
-        encryption_keypair
-            .write_to_key_store(backend)
-            .map_err(KeyPackageNewError::KeyStoreError)?;
+        Ok(creation_result.key_package)
+    }
 
-        // Store the private part of the init_key into the key store.
-        // The key is the public key.
-        backend
-            .key_store()
-            .store::<HpkePrivateKey>(key_package.hpke_init_key().as_slice(), &init_private_key)
-            .map_err(KeyPackageNewError::KeyStoreError)?;
+    /// Finalize and build a key package under a fresh pseudonymous
+    /// credential.
+    ///
+    /// The leaf of the key package uses a [`BasicCredential`] with a random
+    /// identity and the `pseudonym_signature_key`, so that key package
+    /// directories can't link the key package to the real
+    /// `credential_with_key`. The key package is signed with the
+    /// `pseudonym_signer`, which has to hold the private part of the
+    /// `pseudonym_signature_key` and is used for all signatures in the group
+    /// after joining.
+    ///
+    /// Alongside the key package, this returns a [`CredentialBinding`] signed
+    /// with the `signer` of the real credential. The binding must not be
+    /// published with the key package. Instead, it is handed to the adder,
+    /// who passes it to
+    /// [`CommitBuilder::credential_bindings()`](crate::group::CommitBuilder::credential_bindings())
+    /// so that it is only revealed to the joiners in the encrypted GroupInfo of
+    /// the [`Welcome`](crate::messages::Welcome).
+    pub fn build_pseudonymous<KeyStore: OpenMlsKeyStore>(
+        self,
+        config: CryptoConfig,
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        pseudonym_signer: &impl Signer,
+        pseudonym_signature_key: SignaturePublicKey,
+        signer: &impl Signer,
+        credential_with_key: CredentialWithKey,
+    ) -> Result<(KeyPackage, CredentialBinding), KeyPackageNewError<KeyStore::Error>> {
+        if config.ciphersuite.signature_algorithm() != signer.signature_scheme() {
+            return Err(KeyPackageNewError::CiphersuiteSignatureSchemeMismatch);
+        }
+        let identity = backend
+            .rand()
+            .random_vec(PSEUDONYM_IDENTITY_LENGTH)
+            .map_err(|_| LibraryError::custom("Not enough randomness for the pseudonym"))?;
+        let pseudonym_credential = Credential::new(identity, CredentialType::Basic)
+            .map_err(|_| LibraryError::custom("Basic credentials are always supported"))?;
+
+        let binding =
+            CredentialBinding::new(pseudonym_signature_key.clone(), credential_with_key, signer)?;
+        let key_package = self.build(
+            config,
+            backend,
+            pseudonym_signer,
+            CredentialWithKey {
+                credential: pseudonym_credential,
+                signature_key: pseudonym_signature_key,
+            },
+        )?;
 
-        Ok(key_package)
+        Ok((key_package, binding))
     }
 }
 
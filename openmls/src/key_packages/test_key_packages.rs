@@ -3,7 +3,12 @@ use openmls_basic_credential::SignatureKeyPair;
 use openmls_rust_crypto::OpenMlsRustCrypto;
 use tls_codec::Deserialize;
 
-use crate::{extensions::*, key_packages::*};
+use crate::{
+    ciphersuite::signable::SignatureBatch,
+    extensions::*,
+    framing::{MlsMessageIn, MlsMessageOut},
+    key_packages::*,
+};
 
 /// Helper function to generate key packages
 pub(crate) fn key_package(
@@ -144,3 +149,300 @@ fn key_package_validation(ciphersuite: Ciphersuite, backend: &impl OpenMlsCrypto
     // Expect an invalid init/encryption key error
     assert_eq!(err, KeyPackageVerifyError::InitKeyEqualsEncryptionKey);
 }
+
+/// Test that all failed checks of a key package are reported.
+#[apply(ciphersuites_and_backends)]
+fn key_package_check(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (key_package, _, _) = key_package(ciphersuite, backend);
+
+    // === A valid key package passes all checks ===
+
+    let key_package_in = KeyPackageIn::from(key_package.clone());
+    assert!(key_package_in
+        .check(
+            backend.crypto(),
+            ProtocolVersion::Mls10,
+            &LifetimePolicy::default()
+        )
+        .is_empty());
+    assert!(key_package_in
+        .validate(backend.crypto(), ProtocolVersion::Mls10)
+        .is_ok());
+
+    // === An invalid key package fails several checks ===
+
+    let mut key_package = key_package;
+    key_package.set_version(ProtocolVersion::Mls10Draft11);
+    key_package.set_init_key(key_package.leaf_node().encryption_key().key().clone());
+
+    let key_package_in = KeyPackageIn::from(key_package);
+    assert_eq!(
+        key_package_in.check(
+            backend.crypto(),
+            ProtocolVersion::Mls10,
+            &LifetimePolicy::default()
+        ),
+        vec![
+            KeyPackageVerifyError::InvalidSignature,
+            KeyPackageVerifyError::InvalidProtocolVersion,
+            KeyPackageVerifyError::InitKeyEqualsEncryptionKey,
+            KeyPackageVerifyError::InsufficientCapabilities,
+        ]
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn batched_validation(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let key_packages: Vec<KeyPackage> = (0..3)
+        .map(|_| key_package(ciphersuite, backend).0)
+        .collect();
+
+    // All signatures are verified together.
+    let mut signatures = SignatureBatch::new();
+    for key_package in key_packages.iter().cloned() {
+        KeyPackageIn::from(key_package)
+            .validate_batched::<KeyPackageVerifyError>(ProtocolVersion::Mls10, &mut signatures)
+            .expect("Error validating key package.");
+    }
+    signatures
+        .verify(backend.crypto())
+        .expect("Error verifying batch.");
+
+    // Replace the init key of one of the key packages to invalidate its
+    // signature.
+    let mut invalid_key_package = key_packages[1].clone();
+    invalid_key_package.set_init_key(key_packages[0].hpke_init_key().clone());
+
+    let mut signatures = SignatureBatch::new();
+    for key_package in [key_packages[0].clone(), invalid_key_package] {
+        KeyPackageIn::from(key_package)
+            .validate_batched::<KeyPackageVerifyError>(ProtocolVersion::Mls10, &mut signatures)
+            .expect("Error validating key package.");
+    }
+    assert_eq!(
+        signatures.verify(backend.crypto()),
+        Err(KeyPackageVerifyError::InvalidSignature)
+    );
+
+    // Oversized signatures are rejected before they are added to the batch.
+    let signature_scheme = ciphersuite.signature_algorithm();
+    let mut leaf_node = key_packages[2].leaf_node().clone();
+    leaf_node.set_signature(vec![0u8; signature_scheme.max_signature_size() + 1].into());
+    let mut oversized_key_package = key_packages[2].clone();
+    oversized_key_package.set_leaf_node(leaf_node);
+
+    let mut signatures = SignatureBatch::new();
+    assert_eq!(
+        KeyPackageIn::from(oversized_key_package)
+            .validate_batched::<KeyPackageVerifyError>(ProtocolVersion::Mls10, &mut signatures),
+        Err(KeyPackageVerifyError::InvalidLeafNodeSignature)
+    );
+    signatures
+        .verify(backend.crypto())
+        .expect("Error verifying empty batch.");
+}
+
+#[cfg(feature = "diagnostics")]
+#[apply(ciphersuites_and_backends)]
+fn traced_deserialization(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    use tls_codec::Size;
+
+    let (key_package, _, _) = key_package(ciphersuite, backend);
+    let encoded = key_package
+        .tls_serialize_detached()
+        .expect("An unexpected error occurred.");
+
+    // A valid key package is decoded as usual.
+    let decoded_key_package =
+        KeyPackageIn::tls_deserialize_traced(&encoded).expect("Error decoding key package.");
+    assert_eq!(KeyPackageIn::from(key_package.clone()), decoded_key_package);
+
+    // Truncate the key package in the middle of the capabilities.
+    let leaf_node = key_package.leaf_node();
+    let capabilities_offset = 2
+        + 2
+        + key_package.hpke_init_key().tls_serialized_len()
+        + leaf_node.encryption_key().tls_serialized_len()
+        + leaf_node.signature_key().tls_serialized_len()
+        + leaf_node.credential().tls_serialized_len();
+    let truncated =
+        &encoded[..capabilities_offset + leaf_node.capabilities().tls_serialized_len() - 1];
+
+    let error = KeyPackageIn::tls_deserialize_traced(truncated)
+        .expect_err("Decoding a truncated key package succeeded.");
+    assert_eq!(
+        error.path(),
+        "KeyPackage.leaf_node.capabilities.credentials"
+    );
+    assert!(error.offset() >= capabilities_offset);
+    assert!(error.offset() <= truncated.len());
+
+    // The same key package wrapped in an MlsMessage.
+    let message = MlsMessageOut::from(key_package)
+        .tls_serialize_detached()
+        .expect("An unexpected error occurred.");
+    let truncated = &message[..4 + truncated.len()];
+
+    let error = MlsMessageIn::tls_deserialize_traced(truncated)
+        .expect_err("Decoding a truncated message succeeded.");
+    assert_eq!(
+        error.path(),
+        "MlsMessage.KeyPackage.leaf_node.capabilities.credentials"
+    );
+    assert!(error.offset() >= 4 + capabilities_offset);
+}
+
+#[apply(ciphersuites_and_backends)]
+fn external_signing(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let credential = Credential::new(b"Sasha".to_vec(), CredentialType::Basic).unwrap();
+    let signer = SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
+    let config = CryptoConfig {
+        ciphersuite,
+        version: ProtocolVersion::default(),
+    };
+    let credential_with_key = CredentialWithKey {
+        credential,
+        signature_key: signer.to_public_vec().into(),
+    };
+
+    let mut key_package_tbs = KeyPackageTbs::new(config, backend, credential_with_key.clone())
+        .expect("Error creating unsigned key package.");
+    let leaf_node_signature = signer
+        .sign(&key_package_tbs.leaf_node_sign_content().unwrap())
+        .unwrap();
+    key_package_tbs.set_leaf_node_signature(leaf_node_signature.into());
+    let signature = signer
+        .sign(&key_package_tbs.sign_content().unwrap())
+        .unwrap();
+
+    let key_package = KeyPackage::from_signed_parts(backend, key_package_tbs, signature.into())
+        .expect("Error assembling key package.");
+
+    // The key package and its private keys are in the key store.
+    let stored_key_package: KeyPackage = backend
+        .key_store()
+        .read(key_package.hash_ref(backend.crypto()).unwrap().as_slice())
+        .expect("Key package is not in the key store.");
+    assert_eq!(stored_key_package, key_package);
+    assert!(backend
+        .key_store()
+        .read::<HpkePrivateKey>(key_package.hpke_init_key().as_slice())
+        .is_some());
+    KeyPackageIn::from(key_package)
+        .validate(backend.crypto(), ProtocolVersion::Mls10)
+        .expect("Error validating key package.");
+
+    // A key package without a valid leaf node signature is rejected.
+    let key_package_tbs = KeyPackageBuilder::new()
+        .build_tbs(config, backend, credential_with_key)
+        .expect("Error creating unsigned key package.");
+    let signature = signer
+        .sign(&key_package_tbs.sign_content().unwrap())
+        .unwrap();
+
+    assert_eq!(
+        KeyPackage::from_signed_parts(backend, key_package_tbs, signature.into()),
+        Err(KeyPackageFromSignedPartsError::KeyPackageVerifyError(
+            KeyPackageVerifyError::InvalidLeafNodeSignature
+        ))
+    );
+}
+
+#[apply(ciphersuites_and_backends)]
+fn key_package_batch(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let credential = Credential::new(b"Sasha".to_vec(), CredentialType::Basic).unwrap();
+    let signer = SignatureKeyPair::new(ciphersuite.signature_algorithm()).unwrap();
+
+    let batch = KeyPackage::builder()
+        .build_batch(
+            5,
+            CryptoConfig::with_default_version(ciphersuite),
+            backend,
+            &signer,
+            CredentialWithKey {
+                credential,
+                signature_key: signer.to_public_vec().into(),
+            },
+        )
+        .expect("Error building key package batch.");
+    assert_eq!(batch.len(), 5);
+
+    for (hash_ref, key_package) in batch.entries() {
+        assert_eq!(hash_ref, &key_package.hash_ref(backend.crypto()).unwrap());
+        let stored_key_package: KeyPackage = backend
+            .key_store()
+            .read(hash_ref.as_slice())
+            .expect("Key package is not in the key store.");
+        assert_eq!(&stored_key_package, key_package);
+        assert!(backend
+            .key_store()
+            .read::<HpkePrivateKey>(key_package.hpke_init_key().as_slice())
+            .is_some());
+    }
+
+    // The batch survives a roundtrip through the wire format.
+    let encoded = batch
+        .tls_serialize_detached()
+        .expect("An unexpected error occurred.");
+    let decoded = KeyPackageBatchIn::tls_deserialize(&mut encoded.as_slice())
+        .expect("An unexpected error occurred.")
+        .validate(backend.crypto(), ProtocolVersion::Mls10)
+        .expect("Error validating key package batch.");
+    assert_eq!(decoded, batch);
+
+    // A hash reference that doesn't belong to its key package is rejected.
+    let hash_refs: Vec<Vec<u8>> = batch
+        .entries()
+        .map(|(hash_ref, _)| hash_ref.tls_serialize_detached().unwrap())
+        .collect();
+    let position = encoded
+        .windows(hash_refs[0].len())
+        .position(|window| window == hash_refs[0].as_slice())
+        .expect("The hash reference is not in the encoded batch.");
+    let mut tampered = encoded;
+    tampered[position..position + hash_refs[1].len()].copy_from_slice(&hash_refs[1]);
+    let err = KeyPackageBatchIn::tls_deserialize(&mut tampered.as_slice())
+        .expect("An unexpected error occurred.")
+        .validate(backend.crypto(), ProtocolVersion::Mls10)
+        .expect_err("A mismatching hash reference was accepted.");
+    assert_eq!(err, KeyPackageBatchVerifyError::HashReferenceMismatch);
+}
+
+#[apply(ciphersuites_and_backends)]
+fn key_package_refresh(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let (key_package, credential, signer) = key_package(ciphersuite, backend);
+
+    // A fresh key package doesn't need to be refreshed under the default
+    // policy, but one that expires within the refresh threshold does.
+    let policy = LifetimePolicy::default();
+    let now = std::time::SystemTime::now();
+    assert!(!key_package.needs_refresh(now, &policy));
+    assert!(key_package.needs_refresh(now + policy.lifetime(), &policy));
+
+    let refreshed = key_package
+        .refresh(&policy, backend, &signer)
+        .expect("Error refreshing key package.");
+    assert_ne!(refreshed.hpke_init_key(), key_package.hpke_init_key());
+    assert_eq!(refreshed.leaf_node().credential(), &credential);
+    assert_eq!(refreshed.ciphersuite(), key_package.ciphersuite());
+    assert_eq!(refreshed.extensions(), key_package.extensions());
+    assert!(!refreshed.needs_refresh(now, &policy));
+
+    // The refreshed key package and its private init key are in the key
+    // store.
+    let hash_ref = refreshed.hash_ref(backend.crypto()).unwrap();
+    let stored_key_package: KeyPackage = backend
+        .key_store()
+        .read(hash_ref.as_slice())
+        .expect("Key package is not in the key store.");
+    assert_eq!(stored_key_package, refreshed);
+    assert!(backend
+        .key_store()
+        .read::<HpkePrivateKey>(refreshed.hpke_init_key().as_slice())
+        .is_some());
+
+    // The refreshed key package is valid.
+    KeyPackageIn::from(refreshed)
+        .validate(backend.crypto(), ProtocolVersion::Mls10)
+        .expect("Error validating refreshed key package.");
+}
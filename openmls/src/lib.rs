@@ -140,6 +140,17 @@
 #![cfg_attr(not(feature = "test-utils"), deny(missing_docs))]
 #![deny(rustdoc::broken_intra_doc_links)]
 #![deny(rustdoc::private_intra_doc_links)]
+// Subsystems that are compiled out leave some of their helpers unused.
+#![cfg_attr(
+    not(all(
+        feature = "external-commit",
+        feature = "psk",
+        feature = "public-group-tracking",
+        feature = "reinit",
+        feature = "diagnostics"
+    )),
+    allow(dead_code, unused_imports)
+)]
 #![cfg(any(
     target_pointer_width = "32",
     target_pointer_width = "64",
@@ -168,6 +179,7 @@ pub mod error;
 
 // Public
 pub mod ciphersuite;
+pub mod codec;
 pub mod credentials;
 pub mod extensions;
 pub mod framing;
@@ -181,6 +193,7 @@ pub mod versions;
 // Private
 mod binary_tree;
 mod tree;
+mod write_batch;
 
 /// Single place, re-exporting the most used public functions.
 pub mod prelude;
@@ -2,7 +2,7 @@
 //!
 //! Contains the types and methods to build external proposal to add/remove a client from a MLS group
 //!
-//! `Add` (from external sender), `Remove` & `ReInit are not yet implemented`
+//! `ReInit` is not yet implemented
 
 use crate::{
     binary_tree::LeafNodeIndex,
@@ -59,6 +59,35 @@ impl JoinProposal {
 }
 
 impl ExternalProposal {
+    /// Creates an external Add proposal. For delivery services requesting to add a client.
+    /// This proposal will have to be committed later by a group member.
+    ///
+    /// # Arguments
+    /// * `key_package` - of the client to add
+    /// * `group_id` - unique group identifier of the group to join
+    /// * `epoch` - group's epoch
+    /// * `signer` - of the sender to sign the message
+    /// * `sender` - index of the sender of the proposal (in the [crate::extensions::ExternalSendersExtension] array
+    /// from the Group Context)
+    pub fn new_add(
+        key_package: KeyPackage,
+        group_id: GroupId,
+        epoch: GroupEpoch,
+        signer: &impl Signer,
+        sender_index: SenderExtensionIndex,
+    ) -> Result<MlsMessageOut, ProposeAddMemberError> {
+        AuthenticatedContent::new_external_proposal(
+            Proposal::Add(AddProposal { key_package }),
+            group_id,
+            epoch,
+            signer,
+            sender_index,
+        )
+        .map(PublicMessage::from)
+        .map(MlsMessageOut::from)
+        .map_err(ProposeAddMemberError::from)
+    }
+
     /// Creates an external Remove proposal. For delivery services requesting to remove a client.
     /// This proposal will have to be committed later by a group member.
     ///
@@ -12,7 +12,7 @@ use crate::{
         AeadKey, AeadNonce, Signature,
     },
     extensions::Extensions,
-    group::{GroupContext, GroupId},
+    group::{GroupContext, GroupEpoch, GroupId},
     messages::ConfirmationTag,
 };
 
@@ -85,7 +85,8 @@ impl VerifiableGroupInfo {
 
     /// Get (unverified) ciphersuite of the verifiable group info.
     ///
-    /// Note: This method should only be used when necessary to verify the group info signature.
+    /// Note: The ciphersuite is not authenticated before the group info is
+    /// verified, e.g. when joining the group with it.
     pub fn ciphersuite(&self) -> Ciphersuite {
         self.payload.group_context.ciphersuite()
     }
@@ -104,13 +105,27 @@ impl VerifiableGroupInfo {
         &self.payload.extensions
     }
 
+    /// Get (unverified) group context extensions of the verifiable group
+    /// info.
+    pub(crate) fn group_context_extensions(&self) -> &Extensions {
+        self.payload.group_context.extensions()
+    }
+
     /// Get (unverified) group ID of the verifiable group info.
     ///
-    /// Note: This method should only be used when necessary to verify the group
-    /// info signature.
-    pub(crate) fn group_id(&self) -> &GroupId {
+    /// Note: The group ID is not authenticated before the group info is verified,
+    /// e.g. when joining the group with it.
+    pub fn group_id(&self) -> &GroupId {
         self.payload.group_context.group_id()
     }
+
+    /// Get (unverified) epoch of the verifiable group info.
+    ///
+    /// Note: The epoch is not authenticated before the group info is verified,
+    /// e.g. when joining the group with it.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.payload.group_context.epoch()
+    }
 }
 
 #[cfg(test)]
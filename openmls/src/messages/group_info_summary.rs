@@ -0,0 +1,275 @@
+//! This module contains the [`GroupInfoSummary`].
+//!
+//! A [`GroupInfoSummary`] is a signed excerpt of the public state of a group
+//! for directory or search services that must not receive the full
+//! [`GroupInfo`](super::group_info::GroupInfo), which contains the whole
+//! group context and allows to join the group through an external commit.
+//! It only contains the ciphersuite, the epoch, the number of members, the
+//! required capabilities and the extensions of the group context that were
+//! selected by the exporter.
+//!
+//! A summary is exported by a member through
+//! [`MlsGroup::export_group_info_summary()`] or by an external sender of the
+//! group, e.g. the DS, through
+//! [`PublicGroup::export_group_info_summary()`]. When receiving a serialized
+//! summary, it can only be deserialized into a [`VerifiableGroupInfoSummary`],
+//! which has to be verified with the signature key of its
+//! [`GroupInfoSummarySigner`].
+//!
+//! [`MlsGroup::export_group_info_summary()`]: crate::group::MlsGroup::export_group_info_summary()
+//! [`PublicGroup::export_group_info_summary()`]: crate::group::PublicGroup::export_group_info_summary()
+
+use openmls_traits::types::Ciphersuite;
+use serde::{Deserialize as SerdeDeserialize, Serialize as SerdeSerialize};
+use tls_codec::{Serialize, TlsDeserialize, TlsSerialize, TlsSize};
+
+use crate::{
+    binary_tree::LeafNodeIndex,
+    ciphersuite::{
+        signable::{Signable, SignedStruct, Verifiable, VerifiedStruct},
+        Signature,
+    },
+    extensions::{Extensions, RequiredCapabilitiesExtension, SenderExtensionIndex},
+    group::{GroupEpoch, GroupId},
+};
+
+const SIGNATURE_GROUP_INFO_SUMMARY_LABEL: &str = "GroupInfoSummaryTBS";
+
+/// The signer of a [`GroupInfoSummary`].
+///
+/// ```c
+/// enum {
+///     reserved(0),
+///     member(1),
+///     external(2),
+///     (255)
+/// } GroupInfoSummarySignerType;
+///
+/// struct {
+///     GroupInfoSummarySignerType signer_type;
+///     select (GroupInfoSummarySigner.signer_type) {
+///         case member:
+///             uint32 leaf_index;
+///         case external:
+///             uint32 sender_index;
+///     }
+/// } GroupInfoSummarySigner;
+/// ```
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    SerdeSerialize,
+    SerdeDeserialize,
+    TlsSerialize,
+    TlsDeserialize,
+    TlsSize,
+)]
+#[repr(u8)]
+pub enum GroupInfoSummarySigner {
+    /// The summary is signed by the member at the given leaf index.
+    #[tls_codec(discriminant = 1)]
+    Member(LeafNodeIndex),
+    /// The summary is signed by the external sender with the given index in
+    /// the group's [`ExternalSendersExtension`](crate::extensions::ExternalSendersExtension).
+    External(SenderExtensionIndex),
+}
+
+/// GroupInfoSummary (To Be Signed)
+///
+/// ```c
+/// struct {
+///     opaque group_id<V>;
+///     CipherSuite cipher_suite;
+///     uint64 epoch;
+///     uint32 member_count;
+///     optional<RequiredCapabilities> required_capabilities;
+///     Extension extensions<V>;
+///     GroupInfoSummarySigner signer;
+/// } GroupInfoSummaryTBS;
+/// ```
+#[derive(
+    Debug, PartialEq, Clone, TlsDeserialize, TlsSerialize, TlsSize, SerdeSerialize, SerdeDeserialize,
+)]
+pub(crate) struct GroupInfoSummaryTBS {
+    group_id: GroupId,
+    ciphersuite: Ciphersuite,
+    epoch: GroupEpoch,
+    member_count: u32,
+    required_capabilities: Option<RequiredCapabilitiesExtension>,
+    extensions: Extensions,
+    signer: GroupInfoSummarySigner,
+}
+
+impl GroupInfoSummaryTBS {
+    /// Create a new to-be-signed group info summary.
+    pub(crate) fn new(
+        group_id: GroupId,
+        ciphersuite: Ciphersuite,
+        epoch: GroupEpoch,
+        member_count: u32,
+        required_capabilities: Option<RequiredCapabilitiesExtension>,
+        extensions: Extensions,
+        signer: GroupInfoSummarySigner,
+    ) -> Self {
+        Self {
+            group_id,
+            ciphersuite,
+            epoch,
+            member_count,
+            required_capabilities,
+            extensions,
+            signer,
+        }
+    }
+}
+
+/// A summary of the public state of a group.
+///
+/// ```c
+/// struct {
+///     GroupInfoSummaryTBS payload;
+///     /* SignWithLabel(., "GroupInfoSummaryTBS", GroupInfoSummaryTBS) */
+///     opaque signature<V>;
+/// } GroupInfoSummary;
+/// ```
+#[derive(Debug, PartialEq, Clone, TlsSerialize, TlsSize, SerdeSerialize, SerdeDeserialize)]
+pub struct GroupInfoSummary {
+    payload: GroupInfoSummaryTBS,
+    signature: Signature,
+}
+
+impl GroupInfoSummary {
+    /// Returns the group ID.
+    pub fn group_id(&self) -> &GroupId {
+        &self.payload.group_id
+    }
+
+    /// Returns the ciphersuite of the group.
+    pub fn ciphersuite(&self) -> Ciphersuite {
+        self.payload.ciphersuite
+    }
+
+    /// Returns the epoch the summary was exported in.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.payload.epoch
+    }
+
+    /// Returns the number of members of the group.
+    pub fn member_count(&self) -> u32 {
+        self.payload.member_count
+    }
+
+    /// Returns the required capabilities of the group, if any.
+    pub fn required_capabilities(&self) -> Option<&RequiredCapabilitiesExtension> {
+        self.payload.required_capabilities.as_ref()
+    }
+
+    /// Returns the group context extensions that were selected for the
+    /// summary.
+    pub fn extensions(&self) -> &Extensions {
+        &self.payload.extensions
+    }
+
+    /// Returns the signer of the summary.
+    pub fn signer(&self) -> GroupInfoSummarySigner {
+        self.payload.signer
+    }
+}
+
+/// A [`GroupInfoSummary`] of which the signature has not been verified.
+///
+/// It implements the [`Verifiable`] trait and can be turned into a
+/// [`GroupInfoSummary`] by calling `verify(...)` with the signature key of
+/// the [`GroupInfoSummarySigner`].
+#[derive(Debug, PartialEq, Clone, TlsDeserialize, TlsSize)]
+#[cfg_attr(any(test, feature = "test-utils"), derive(TlsSerialize))]
+pub struct VerifiableGroupInfoSummary {
+    payload: GroupInfoSummaryTBS,
+    signature: Signature,
+}
+
+impl VerifiableGroupInfoSummary {
+    /// Get the (unverified) group ID of the summary.
+    ///
+    /// Note: This method should only be used to look up the signature key
+    /// that is needed to verify the summary.
+    pub fn group_id(&self) -> &GroupId {
+        &self.payload.group_id
+    }
+
+    /// Get the (unverified) signer of the summary.
+    ///
+    /// Note: This method should only be used to look up the signature key
+    /// that is needed to verify the summary.
+    pub fn signer(&self) -> GroupInfoSummarySigner {
+        self.payload.signer
+    }
+}
+
+#[cfg(test)]
+impl VerifiableGroupInfoSummary {
+    /// Break the signature for testing purposes.
+    pub(crate) fn break_signature(&mut self) {
+        self.signature.modify(b"");
+    }
+}
+
+impl From<GroupInfoSummary> for VerifiableGroupInfoSummary {
+    fn from(summary: GroupInfoSummary) -> Self {
+        Self {
+            payload: summary.payload,
+            signature: summary.signature,
+        }
+    }
+}
+
+impl Signable for GroupInfoSummaryTBS {
+    type SignedOutput = GroupInfoSummary;
+
+    fn unsigned_payload(&self) -> Result<Vec<u8>, tls_codec::Error> {
+        self.tls_serialize_detached()
+    }
+
+    fn label(&self) -> &str {
+        SIGNATURE_GROUP_INFO_SUMMARY_LABEL
+    }
+}
+
+impl SignedStruct<GroupInfoSummaryTBS> for GroupInfoSummary {
+    fn from_payload(payload: GroupInfoSummaryTBS, signature: Signature) -> Self {
+        Self { payload, signature }
+    }
+}
+
+impl Verifiable for VerifiableGroupInfoSummary {
+    fn unsigned_payload(&self) -> Result<Vec<u8>, tls_codec::Error> {
+        self.payload.tls_serialize_detached()
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn label(&self) -> &str {
+        SIGNATURE_GROUP_INFO_SUMMARY_LABEL
+    }
+}
+
+impl VerifiedStruct<VerifiableGroupInfoSummary> for GroupInfoSummary {
+    type SealingType = private_mod::Seal;
+
+    fn from_verifiable(v: VerifiableGroupInfoSummary, _seal: Self::SealingType) -> Self {
+        Self {
+            payload: v.payload,
+            signature: v.signature,
+        }
+    }
+}
+
+mod private_mod {
+    #[derive(Default)]
+    pub struct Seal;
+}
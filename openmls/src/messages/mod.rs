@@ -15,7 +15,7 @@ use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTra
 #[cfg(test)]
 use crate::schedule::psk::{ExternalPsk, Psk};
 use crate::{
-    ciphersuite::{hash_ref::KeyPackageRef, *},
+    ciphersuite::{hash_ref::KeyPackageRef, signable::SignatureBatch, *},
     credentials::CredentialWithKey,
     error::LibraryError,
     framing::SenderContext,
@@ -33,8 +33,11 @@ use crate::{
 
 pub mod external_proposals;
 pub mod group_info;
+pub mod group_info_summary;
+pub mod proposal_retraction;
 pub mod proposals;
 pub mod proposals_in;
+pub mod welcome_decline;
 
 #[cfg(test)]
 mod tests;
@@ -92,6 +95,44 @@ impl Welcome {
         self.encrypted_group_info.as_slice()
     }
 
+    /// Splits the Welcome into Welcome messages with the encrypted group
+    /// secrets of at most `max_recipients` new members each, e.g. to only
+    /// send each new member the secrets it can decrypt when adding many
+    /// members at once.
+    ///
+    /// Every part carries the full encrypted group info and can be processed
+    /// by its recipients like the original Welcome. The parts are returned in
+    /// the order of the secrets. A `max_recipients` of `0` is treated as `1`.
+    pub fn split(self, max_recipients: usize) -> Vec<Welcome> {
+        let max_recipients = max_recipients.max(1);
+        if self.secrets.len() <= max_recipients {
+            return vec![self];
+        }
+        self.secrets
+            .chunks(max_recipients)
+            .map(|secrets| Welcome {
+                cipher_suite: self.cipher_suite,
+                secrets: secrets.to_vec(),
+                encrypted_group_info: self.encrypted_group_info.clone(),
+            })
+            .collect()
+    }
+
+    /// Returns a Welcome message that only contains the encrypted group
+    /// secrets of the new member with the given [`KeyPackageRef`], or `None`
+    /// if the member isn't a recipient of this Welcome.
+    pub fn for_new_member(&self, new_member: &KeyPackageRef) -> Option<Welcome> {
+        let secrets = self
+            .secrets
+            .iter()
+            .find(|secrets| &secrets.new_member == new_member)?;
+        Some(Welcome {
+            cipher_suite: self.cipher_suite,
+            secrets: vec![secrets.clone()],
+            encrypted_group_info: self.encrypted_group_info.clone(),
+        })
+    }
+
     /// Set the welcome's encrypted group info.
     #[cfg(test)]
     pub fn set_encrypted_group_info(&mut self, encrypted_group_info: Vec<u8>) {
@@ -176,6 +217,18 @@ pub(crate) struct CommitIn {
 }
 
 impl CommitIn {
+    pub(crate) fn proposals(&self) -> &[ProposalOrRefIn] {
+        &self.proposals
+    }
+
+    pub(crate) fn has_path(&self) -> bool {
+        self.path.is_some()
+    }
+
+    pub(crate) fn path(&self) -> Option<&UpdatePathIn> {
+        self.path.as_ref()
+    }
+
     pub(crate) fn unverified_credential(&self) -> Option<CredentialWithKey> {
         self.path.as_ref().map(|p| {
             let credential = p.leaf_node().credential().clone();
@@ -188,6 +241,9 @@ impl CommitIn {
     }
 
     /// Returns a [`Commit`] after successful validation.
+    ///
+    /// The signatures of all proposals by value and of the leaf node in the
+    /// update path are verified together in a single batch.
     pub(crate) fn validate(
         self,
         ciphersuite: Ciphersuite,
@@ -195,10 +251,11 @@ impl CommitIn {
         sender_context: SenderContext,
         protocol_version: ProtocolVersion,
     ) -> Result<Commit, ValidationError> {
+        let mut signatures = SignatureBatch::new();
         let proposals = self
             .proposals
             .into_iter()
-            .map(|p| p.validate(crypto, ciphersuite, protocol_version))
+            .map(|p| p.validate(ciphersuite, protocol_version, &mut signatures))
             .collect::<Result<Vec<_>, _>>()?;
 
         let path = if let Some(path) = self.path {
@@ -232,10 +289,11 @@ impl CommitIn {
                     TreePosition::new(group_id, new_leaf_index)
                 }
             };
-            Some(path.into_verified(ciphersuite, crypto, tree_position)?)
+            Some(path.into_verified(ciphersuite, tree_position, &mut signatures)?)
         } else {
             None
         };
+        signatures.verify(crypto)?;
         Ok(Commit { proposals, path })
     }
 }
@@ -302,7 +360,8 @@ impl PathSecret {
             .map_err(LibraryError::unexpected_crypto_error)?;
         let HpkeKeyPair { public, private } = backend
             .crypto()
-            .derive_hpke_keypair(ciphersuite.hpke_config(), node_secret.as_slice());
+            .derive_hpke_keypair(ciphersuite.hpke_config(), node_secret.as_slice())
+            .map_err(LibraryError::unexpected_crypto_error)?;
 
         Ok((HpkePublicKey::from(public), private).into())
     }
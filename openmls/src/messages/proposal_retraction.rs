@@ -0,0 +1,54 @@
+//! This module contains the [`ProposalRetraction`] marker.
+//!
+//! A member that sent a proposal by mistake can cancel it through
+//! [`MlsGroup::cancel_own_proposal()`], which removes the proposal from its
+//! own proposal store and returns a [`ProposalRetraction`]. The member sends
+//! the marker to the group as an application message. Other members pass it
+//! to [`MlsGroup::process_proposal_retraction()`] together with the
+//! authenticated sender of the application message to remove the proposal
+//! from their proposal stores as well.
+//!
+//! Retracting is best-effort: a member that commits before it processes the
+//! retraction still includes the proposal.
+//!
+//! [`MlsGroup::cancel_own_proposal()`]: crate::group::MlsGroup::cancel_own_proposal()
+//! [`MlsGroup::process_proposal_retraction()`]: crate::group::MlsGroup::process_proposal_retraction()
+
+use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
+
+use crate::{ciphersuite::hash_ref::ProposalRef, group::GroupEpoch};
+
+/// A marker that the sender of a proposal retracts it.
+///
+/// ```c
+/// struct {
+///     uint64 epoch;
+///     ProposalRef proposal_ref;
+/// } ProposalRetraction;
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, TlsSerialize, TlsDeserialize, TlsSize)]
+pub struct ProposalRetraction {
+    epoch: GroupEpoch,
+    proposal_ref: ProposalRef,
+}
+
+impl ProposalRetraction {
+    /// Create a new [`ProposalRetraction`] for the proposal with the given
+    /// reference that was sent in the `epoch`.
+    pub(crate) fn new(epoch: GroupEpoch, proposal_ref: ProposalRef) -> Self {
+        Self {
+            epoch,
+            proposal_ref,
+        }
+    }
+
+    /// Returns the epoch in which the proposal was sent.
+    pub fn epoch(&self) -> GroupEpoch {
+        self.epoch
+    }
+
+    /// Returns the [`ProposalRef`] of the retracted proposal.
+    pub fn proposal_ref(&self) -> &ProposalRef {
+        &self.proposal_ref
+    }
+}
@@ -323,6 +323,11 @@ pub struct PreSharedKeyProposal {
 }
 
 impl PreSharedKeyProposal {
+    /// Returns the [`PreSharedKeyId`] of the pre-shared key in this proposal.
+    pub fn psk_id(&self) -> &PreSharedKeyId {
+        &self.psk
+    }
+
     /// Returns the [`PreSharedKeyId`] and consume this proposal.
     pub(crate) fn into_psk_id(self) -> PreSharedKeyId {
         self.psk
@@ -361,6 +366,45 @@ pub struct ReInitProposal {
     pub(crate) extensions: Extensions,
 }
 
+impl ReInitProposal {
+    /// Create a new [`ReInitProposal`] for a new group with the given
+    /// [`GroupId`], [`ProtocolVersion`], [`Ciphersuite`] and group context
+    /// [`Extensions`].
+    pub fn new(
+        group_id: GroupId,
+        version: ProtocolVersion,
+        ciphersuite: Ciphersuite,
+        extensions: Extensions,
+    ) -> Self {
+        Self {
+            group_id,
+            version,
+            ciphersuite,
+            extensions,
+        }
+    }
+
+    /// Returns the group ID of the new group.
+    pub fn group_id(&self) -> &GroupId {
+        &self.group_id
+    }
+
+    /// Returns the protocol version of the new group.
+    pub fn version(&self) -> ProtocolVersion {
+        self.version
+    }
+
+    /// Returns the ciphersuite of the new group.
+    pub fn ciphersuite(&self) -> Ciphersuite {
+        self.ciphersuite
+    }
+
+    /// Returns the group context extensions of the new group.
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+}
+
 /// ExternalInit Proposal.
 ///
 /// An ExternalInit proposal is used by new members that want to join a group by using an external
@@ -426,10 +470,14 @@ pub struct GroupContextExtensionProposal {
 
 impl GroupContextExtensionProposal {
     /// Create a new [`GroupContextExtensionProposal`].
-    #[cfg(test)]
     pub(crate) fn new(extensions: Extensions) -> Self {
         Self { extensions }
     }
+
+    /// Returns the extensions contained in the proposal.
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
 }
 
 // Crate-only types
@@ -476,10 +524,20 @@ pub(crate) enum ProposalOrRef {
     Reference(ProposalRef),
 }
 
-#[derive(Error, Debug)]
-pub(crate) enum ProposalRefError {
+/// Proposal reference error
+#[derive(Error, Debug, PartialEq, Clone)]
+pub enum ProposalRefError {
+    /// The message doesn't contain a proposal.
     #[error("Expected `Proposal`, got `{wrong:?}`.")]
-    AuthenticatedContentHasWrongType { wrong: ContentType },
+    AuthenticatedContentHasWrongType {
+        /// The content type of the message.
+        wrong: ContentType,
+    },
+    /// The message is a `PrivateMessage`, whose proposal reference can only
+    /// be computed by the members of the group after decrypting it.
+    #[error("The proposal reference of a PrivateMessage can't be computed.")]
+    EncryptedMessage,
+    /// See [`LibraryError`] for more details.
     #[error(transparent)]
     Other(#[from] LibraryError),
 }
@@ -6,7 +6,7 @@
 //! [`ProposalType::is_supported()`] can be used.
 
 use crate::{
-    ciphersuite::{hash_ref::ProposalRef, signable::Verifiable},
+    ciphersuite::{hash_ref::ProposalRef, signable::SignatureBatch},
     credentials::CredentialWithKey,
     framing::SenderContext,
     group::errors::ValidationError,
@@ -99,15 +99,38 @@ impl ProposalIn {
         ciphersuite: Ciphersuite,
         sender_context: Option<SenderContext>,
         protocol_version: ProtocolVersion,
+    ) -> Result<Proposal, ValidationError> {
+        let mut signatures = SignatureBatch::new();
+        let proposal = self.validate_batched(
+            ciphersuite,
+            sender_context,
+            protocol_version,
+            &mut signatures,
+        )?;
+        signatures.verify(crypto)?;
+        Ok(proposal)
+    }
+
+    /// Same as [`ProposalIn::validate()`], except that the signature checks
+    /// are added to `signatures` instead of being performed right away.
+    ///
+    /// The returned [`Proposal`] MUST be discarded unless `signatures` is
+    /// verified successfully.
+    pub(crate) fn validate_batched(
+        self,
+        ciphersuite: Ciphersuite,
+        sender_context: Option<SenderContext>,
+        protocol_version: ProtocolVersion,
+        signatures: &mut SignatureBatch<ValidationError>,
     ) -> Result<Proposal, ValidationError> {
         Ok(match self {
             ProposalIn::Add(add) => {
-                Proposal::Add(add.validate(crypto, protocol_version, ciphersuite)?)
+                Proposal::Add(add.validate(protocol_version, ciphersuite, signatures)?)
             }
             ProposalIn::Update(update) => {
                 let sender_context =
                     sender_context.ok_or(ValidationError::CommitterIncludedOwnUpdate)?;
-                Proposal::Update(update.validate(crypto, ciphersuite, sender_context)?)
+                Proposal::Update(update.validate(ciphersuite, sender_context, signatures)?)
             }
             ProposalIn::Remove(remove) => Proposal::Remove(remove),
             ProposalIn::PreSharedKey(psk) => Proposal::PreSharedKey(psk),
@@ -143,14 +166,22 @@ impl AddProposalIn {
         self.key_package.unverified_credential()
     }
 
-    /// Returns a [`AddProposal`] after successful validation.
+    /// Returns the (unverified) key package of the new member.
+    pub(crate) fn key_package(&self) -> &KeyPackageIn {
+        &self.key_package
+    }
+
+    /// Returns a [`AddProposal`] after successful validation. The signature
+    /// checks are added to `signatures`.
     pub(crate) fn validate(
         self,
-        crypto: &impl OpenMlsCrypto,
         protocol_version: ProtocolVersion,
         ciphersuite: Ciphersuite,
+        signatures: &mut SignatureBatch<ValidationError>,
     ) -> Result<AddProposal, ValidationError> {
-        let key_package = self.key_package.validate(crypto, protocol_version)?;
+        let key_package = self
+            .key_package
+            .validate_batched(protocol_version, signatures)?;
         // Verify that the ciphersuite is valid
         if key_package.ciphersuite() != ciphersuite {
             return Err(ValidationError::InvalidAddProposalCiphersuite);
@@ -178,12 +209,18 @@ pub struct UpdateProposalIn {
 }
 
 impl UpdateProposalIn {
-    /// Returns a [`UpdateProposal`] after successful validation.
+    /// Returns the (unverified) leaf node of the proposal.
+    pub(crate) fn leaf_node(&self) -> &LeafNodeIn {
+        &self.leaf_node
+    }
+
+    /// Returns a [`UpdateProposal`] after successful validation. The signature
+    /// check is added to `signatures`.
     pub(crate) fn validate(
         self,
-        crypto: &impl OpenMlsCrypto,
         ciphersuite: Ciphersuite,
         sender_context: SenderContext,
+        signatures: &mut SignatureBatch<ValidationError>,
     ) -> Result<UpdateProposal, ValidationError> {
         let leaf_node = match self.leaf_node.into_verifiable_leaf_node() {
             VerifiableLeafNode::Update(mut leaf_node) => {
@@ -199,9 +236,7 @@ impl UpdateProposalIn {
                     .clone()
                     .into_signature_public_key_enriched(ciphersuite.signature_algorithm());
 
-                leaf_node
-                    .verify(crypto, pk)
-                    .map_err(|_| ValidationError::InvalidLeafNodeSignature)?
+                signatures.add(leaf_node, pk, ValidationError::InvalidLeafNodeSignature)?
             }
             _ => return Err(ValidationError::InvalidLeafNodeSourceType),
         };
@@ -226,16 +261,17 @@ pub(crate) enum ProposalOrRefIn {
 }
 
 impl ProposalOrRefIn {
-    /// Returns a [`ProposalOrRef`] after successful validation.
+    /// Returns a [`ProposalOrRef`] after successful validation. The signature
+    /// checks of proposals by value are added to `signatures`.
     pub(crate) fn validate(
         self,
-        crypto: &impl OpenMlsCrypto,
         ciphersuite: Ciphersuite,
         protocol_version: ProtocolVersion,
+        signatures: &mut SignatureBatch<ValidationError>,
     ) -> Result<ProposalOrRef, ValidationError> {
         Ok(match self {
             ProposalOrRefIn::Proposal(proposal_in) => ProposalOrRef::Proposal(
-                proposal_in.validate(crypto, ciphersuite, None, protocol_version)?,
+                proposal_in.validate_batched(ciphersuite, None, protocol_version, signatures)?,
             ),
             ProposalOrRefIn::Reference(reference) => ProposalOrRef::Reference(reference),
         })
@@ -104,7 +104,14 @@ fn test_welcome_context_mismatch(ciphersuite: Ciphersuite, backend: &impl OpenMl
     let psk_secret = {
         let resumption_psk_store = ResumptionPskStore::new(1024);
 
-        let psks = load_psks(backend.key_store(), &resumption_psk_store, &[]).unwrap();
+        let psks = load_psks(
+            backend.key_store(),
+            &resumption_psk_store,
+            None,
+            ciphersuite,
+            &[],
+        )
+        .unwrap();
 
         PskSecret::new(backend, ciphersuite, psks).unwrap()
     };
@@ -142,13 +149,6 @@ fn test_welcome_context_mismatch(ciphersuite: Ciphersuite, backend: &impl OpenMl
 
     welcome.encrypted_group_info = encrypted_verifiable_group_info.into();
 
-    // Create backup of encryption keypair, s.t. we can process the welcome a second time after failing.
-    let encryption_keypair = EncryptionKeyPair::read_from_key_store(
-        backend,
-        bob_kpb.key_package().leaf_node().encryption_key(),
-    )
-    .unwrap();
-
     // Bob tries to join the group
     let err = MlsGroup::new_from_welcome(
         backend,
@@ -165,21 +165,16 @@ fn test_welcome_context_mismatch(ciphersuite: Ciphersuite, backend: &impl OpenMl
 
     // === Process the original Welcome ===
 
-    // We need to store the key package and its encryption key again because it
-    // has been consumed already.
-    backend
-        .key_store()
-        .store(
-            bob_kp.hash_ref(backend.crypto()).unwrap().as_slice(),
-            bob_kp,
-        )
-        .unwrap();
-    backend
+    // The failed join was rolled back, so the private keys of the key
+    // package are still in the key store.
+    assert!(backend
         .key_store()
-        .store::<HpkePrivateKey>(bob_kp.hpke_init_key().as_slice(), bob_private_key)
-        .unwrap();
-
-    encryption_keypair.write_to_key_store(backend).unwrap();
+        .read::<HpkePrivateKey>(bob_kp.hpke_init_key().as_slice())
+        .is_some());
+    assert!(
+        EncryptionKeyPair::read_from_key_store(backend, bob_kp.leaf_node().encryption_key())
+            .is_some()
+    );
 
     let _group = MlsGroup::new_from_welcome(
         backend,
@@ -229,12 +224,15 @@ fn test_welcome_message(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoPr
     let welcome_nonce = AeadNonce::random(backend);
 
     // Generate receiver key pair.
-    let receiver_key_pair = backend.crypto().derive_hpke_keypair(
-        ciphersuite.hpke_config(),
-        Secret::random(ciphersuite, backend, None)
-            .expect("Not enough randomness.")
-            .as_slice(),
-    );
+    let receiver_key_pair = backend
+        .crypto()
+        .derive_hpke_keypair(
+            ciphersuite.hpke_config(),
+            Secret::random(ciphersuite, backend, None)
+                .expect("Not enough randomness.")
+                .as_slice(),
+        )
+        .expect("Error deriving an HPKE key pair.");
     let hpke_context = b"group info welcome test info";
     let group_secrets = b"these should be the group secrets";
     let new_member = KeyPackageRef::from_slice(&[0u8; 16]);
@@ -312,3 +310,75 @@ fn invalid_welcomes() {
     let msg = Welcome::tls_deserialize(&mut bytes);
     assert!(msg.is_err());
 }
+
+#[apply(ciphersuites_and_backends)]
+fn split_welcome(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let mls_group_config = MlsGroupConfigBuilder::new()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .use_ratchet_tree_extension(true)
+        .build();
+
+    let (alice_credential_with_key, alice_kpb, alice_signer, _alice_signature_key) =
+        crate::group::test_core_group::setup_client("Alice", ciphersuite, backend);
+    let new_members: Vec<_> = ["Bob", "Charlie", "Dave"]
+        .into_iter()
+        .map(|name| crate::group::test_core_group::setup_client(name, ciphersuite, backend).1)
+        .collect();
+    let key_packages: Vec<_> = new_members
+        .iter()
+        .map(|kpb| kpb.key_package().clone())
+        .collect();
+
+    // === Alice creates a group and adds everyone at once ===
+    let mut alice_group = MlsGroup::new_with_group_id(
+        backend,
+        &alice_signer,
+        &mls_group_config,
+        GroupId::random(backend),
+        alice_credential_with_key,
+    )
+    .expect("An unexpected error occurred.");
+    let (_commit, welcome, _group_info) = alice_group
+        .add_members(backend, &alice_signer, &key_packages)
+        .expect("Could not add members to group.");
+    alice_group
+        .merge_pending_commit(backend)
+        .expect("error merging pending commit");
+
+    // Batches keep the order of the secrets
+    let batches = welcome
+        .split_welcome(2)
+        .expect("Unexpected message type.")
+        .into_iter()
+        .map(|batch| batch.into_welcome().expect("Unexpected message type."))
+        .collect::<Vec<_>>();
+    let welcome = welcome.into_welcome().expect("Unexpected message type.");
+    assert_eq!(batches.len(), 2);
+    assert_eq!(batches[0].secrets(), &welcome.secrets()[..2]);
+    assert_eq!(batches[1].secrets(), &welcome.secrets()[2..]);
+    assert_eq!(welcome.clone().split(0).len(), 3);
+    assert_eq!(welcome.clone().split(3), vec![welcome.clone()]);
+
+    // Alice is not a recipient of the Welcome
+    let alice_ref = alice_kpb.key_package().hash_ref(backend.crypto()).unwrap();
+    assert!(welcome.for_new_member(&alice_ref).is_none());
+
+    // Every new member joins with only its own secrets
+    for kpb in &new_members {
+        let new_member = kpb.key_package().hash_ref(backend.crypto()).unwrap();
+        let own_welcome = welcome
+            .for_new_member(&new_member)
+            .expect("Missing secrets of a new member.");
+        assert_eq!(own_welcome.secrets().len(), 1);
+        assert_eq!(own_welcome.secrets()[0].new_member(), new_member);
+        assert!(
+            own_welcome.tls_serialize_detached().unwrap().len()
+                < welcome.tls_serialize_detached().unwrap().len()
+        );
+
+        let group = MlsGroup::new_from_welcome(backend, &mls_group_config, own_welcome, None)
+            .expect("Error creating group from a split Welcome.");
+        assert_eq!(group.epoch(), alice_group.epoch());
+        assert_eq!(group.members().count(), 4);
+    }
+}
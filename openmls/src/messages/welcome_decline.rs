@@ -0,0 +1,132 @@
+//! This module contains the [`WelcomeDecline`] notice.
+//!
+//! A prospective member that doesn't want to join a group it was invited to
+//! can produce a [`WelcomeDecline`] from the [`Welcome`](super::Welcome)
+//! through [`MlsGroup::decline_welcome()`]. The notice is signed with the
+//! signature key of the invited leaf and bound to the group and the
+//! [`KeyPackageRef`] of the key package that was used for the invitation.
+//! Existing members can verify it through
+//! [`MlsGroup::process_welcome_decline()`] and remove the unused leaf.
+//!
+//! [`MlsGroup::decline_welcome()`]: crate::group::MlsGroup::decline_welcome()
+//! [`MlsGroup::process_welcome_decline()`]: crate::group::MlsGroup::process_welcome_decline()
+
+use tls_codec::{Serialize, TlsDeserialize, TlsSerialize, TlsSize};
+
+use crate::{
+    ciphersuite::{
+        hash_ref::KeyPackageRef,
+        signable::{Signable, SignedStruct, Verifiable},
+        Signature,
+    },
+    group::GroupId,
+    treesync::node::encryption_keys::EncryptionKey,
+};
+
+const SIGNATURE_WELCOME_DECLINE_LABEL: &str = "WelcomeDeclineTBS";
+
+/// The to-be-signed content of a [`WelcomeDecline`].
+///
+/// ```c
+/// struct {
+///     opaque group_id<V>;
+///     KeyPackageRef key_package_ref;
+///     HPKEPublicKey encryption_key;
+/// } WelcomeDeclineTBS;
+/// ```
+#[derive(Debug, PartialEq, Clone, TlsSerialize, TlsDeserialize, TlsSize)]
+pub(crate) struct WelcomeDeclineTbs {
+    group_id: GroupId,
+    key_package_ref: KeyPackageRef,
+    encryption_key: EncryptionKey,
+}
+
+impl WelcomeDeclineTbs {
+    /// Create a new [`WelcomeDeclineTbs`] for the key package with the given
+    /// reference and leaf encryption key.
+    pub(crate) fn new(
+        group_id: GroupId,
+        key_package_ref: KeyPackageRef,
+        encryption_key: EncryptionKey,
+    ) -> Self {
+        Self {
+            group_id,
+            key_package_ref,
+            encryption_key,
+        }
+    }
+}
+
+/// A signed notice that an invited member declines a
+/// [`Welcome`](super::Welcome).
+///
+/// The signature has to be verified before the notice can be trusted, e.g.
+/// through [`MlsGroup::process_welcome_decline()`].
+///
+/// ```c
+/// struct {
+///     WelcomeDeclineTBS payload;
+///     opaque signature<V>;
+/// } WelcomeDecline;
+/// ```
+///
+/// [`MlsGroup::process_welcome_decline()`]: crate::group::MlsGroup::process_welcome_decline()
+#[derive(Debug, PartialEq, Clone, TlsSerialize, TlsDeserialize, TlsSize)]
+pub struct WelcomeDecline {
+    payload: WelcomeDeclineTbs,
+    signature: Signature,
+}
+
+impl WelcomeDecline {
+    /// Returns the [`GroupId`] of the group the invitation was for.
+    pub fn group_id(&self) -> &GroupId {
+        &self.payload.group_id
+    }
+
+    /// Returns the [`KeyPackageRef`] of the key package that was used for
+    /// the invitation.
+    ///
+    /// Note that existing members can't verify this reference, because they
+    /// only know the leaf node of the key package. Only the member that added
+    /// the key package can match it against the key package.
+    pub fn key_package_ref(&self) -> &KeyPackageRef {
+        &self.payload.key_package_ref
+    }
+
+    /// Returns the encryption key of the invited leaf.
+    pub fn encryption_key(&self) -> &EncryptionKey {
+        &self.payload.encryption_key
+    }
+}
+
+impl Signable for WelcomeDeclineTbs {
+    type SignedOutput = WelcomeDecline;
+
+    fn unsigned_payload(&self) -> Result<Vec<u8>, tls_codec::Error> {
+        self.tls_serialize_detached()
+    }
+
+    fn label(&self) -> &str {
+        SIGNATURE_WELCOME_DECLINE_LABEL
+    }
+}
+
+impl SignedStruct<WelcomeDeclineTbs> for WelcomeDecline {
+    fn from_payload(payload: WelcomeDeclineTbs, signature: Signature) -> Self {
+        Self { payload, signature }
+    }
+}
+
+impl Verifiable for WelcomeDecline {
+    fn unsigned_payload(&self) -> Result<Vec<u8>, tls_codec::Error> {
+        self.payload.tls_serialize_detached()
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn label(&self) -> &str {
+        SIGNATURE_WELCOME_DECLINE_LABEL
+    }
+}
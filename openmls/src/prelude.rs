@@ -7,10 +7,15 @@ pub use crate::group::{config::CryptoConfig, core_group::Member, ser::*, *};
 pub use crate::group::public_group::{errors::*, process::*, *};
 
 // Ciphersuite
-pub use crate::ciphersuite::{hash_ref::KeyPackageRef, signable::*, signature::*, *};
+pub use crate::ciphersuite::{
+    hash_ref::KeyPackageRef, policy::*, signable::*, signature::*, signature_cache::SignatureCache,
+    *,
+};
 
 // Messages
-pub use crate::messages::{external_proposals::*, proposals::*, proposals_in::*, *};
+pub use crate::messages::{
+    external_proposals::*, group_info_summary::*, proposals::*, proposals_in::*, *,
+};
 
 // Credentials
 pub use crate::credentials::{errors::*, *};
@@ -39,7 +44,7 @@ pub use crate::treesync::{
     node::leaf_node::{Capabilities, LeafNode},
     node::parent_node::ParentNode,
     node::Node,
-    RatchetTreeIn,
+    LeafPlacement, LeafPlacementStrategy, RatchetTreeFetcher, RatchetTreeIn,
 };
 
 // PSKs
@@ -4,8 +4,10 @@
 pub use crate::ciphersuite::{signable::Verifiable, *};
 
 // KATs
+#[cfg(any(feature = "test-vectors", test))]
 pub use crate::binary_tree::array_representation::kat_treemath;
 pub use crate::key_packages::KeyPackage;
+#[cfg(any(feature = "test-vectors", test))]
 pub use crate::schedule::kat_key_schedule::{self, KeyScheduleTestVector};
 // TODO: #624 - re-enable test vectors.
 // pub use crate::group::tests::{
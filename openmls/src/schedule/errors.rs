@@ -17,9 +17,11 @@ pub enum PskError {
     /// More than 2^16 PSKs were provided.
     #[error("More than 2^16 PSKs were provided.")]
     TooManyKeys,
-    /// The PSK could not be found in the key store.
-    #[error("The PSK could not be found in the key store.")]
-    KeyNotFound,
+    /// The PSKs with the given IDs could neither be found in the key store
+    /// nor be resolved through the
+    /// [`PskResolver`](crate::schedule::psk::PskResolver).
+    #[error("The PSKs {0:?} could not be found.")]
+    MissingPsks(Vec<PreSharedKeyId>),
     /// Failed to write PSK into keystore.
     #[error("Failed to write PSK into keystore.")]
     KeyStore,
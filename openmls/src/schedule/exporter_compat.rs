@@ -0,0 +1,148 @@
+//! # Exporter compatibility
+//!
+//! Some MLS implementations derive exported secrets with a construction that
+//! deviates from the MLS-Exporter of RFC 9420, usually because they
+//! implemented an earlier draft of the protocol:
+//!
+//! ```text
+//! MLS-Exporter(Label, Context, Length) =
+//!     ExpandWithLabel(DeriveSecret(exporter_secret, Label),
+//!                     "exported", Hash(Context), Length)
+//! ```
+//!
+//! To migrate a fleet of clients from such an implementation without
+//! changing the application keys derived with the exporter, an
+//! [`ExporterCompatibility`] can be set in the
+//! [`MlsGroupConfig`](crate::group::MlsGroupConfig). It maps exporter labels
+//! to the [`ExporterQuirk`]s of the other implementation. Labels that aren't
+//! listed are exported as specified in the RFC.
+//!
+//! Quirks should only be enabled for the labels that have to match keys of
+//! the other implementation, and removed once the migration is complete.
+
+use std::collections::BTreeMap;
+
+use openmls_traits::{
+    crypto::OpenMlsCrypto,
+    types::{Ciphersuite, CryptoError},
+    OpenMlsCryptoProvider,
+};
+use serde::{Deserialize, Serialize};
+
+use super::ExporterSecret;
+use crate::ciphersuite::Secret;
+
+/// The label prefix of `ExpandWithLabel` in early drafts of the protocol.
+const DRAFT_LABEL_PREFIX: &str = "mls10";
+
+/// A deviation from the MLS-Exporter construction of RFC 9420.
+///
+///  - `ExporterLabel`:
+/// The exported secret is expanded with the label `"exporter"` instead of
+/// `"exported"`.
+///  - `UnhashedContext`:
+/// The context is used as is instead of its hash.
+///  - `DraftLabelPrefix`:
+/// The labels of `DeriveSecret` and `ExpandWithLabel` are prefixed with
+/// `"mls10 "` instead of `"MLS 1.0 "`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ExporterQuirk {
+    /// Expand with the label `"exporter"`.
+    ExporterLabel,
+    /// Don't hash the context.
+    UnhashedContext,
+    /// Use the label prefix of early drafts.
+    DraftLabelPrefix,
+}
+
+/// The exporter labels for which secrets are exported with the
+/// [`ExporterQuirk`]s of another implementation. See the
+/// [module documentation](self).
+///
+/// By default, no label has quirks.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExporterCompatibility {
+    labels: BTreeMap<String, Vec<ExporterQuirk>>,
+}
+
+impl ExporterCompatibility {
+    /// Create a new compatibility layer without any quirks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Export secrets for the given `label` with the given `quirks`. This
+    /// replaces the quirks previously set for the label.
+    pub fn with_label(mut self, label: impl Into<String>, quirks: &[ExporterQuirk]) -> Self {
+        let mut quirks = quirks.to_vec();
+        quirks.sort();
+        quirks.dedup();
+        self.labels.insert(label.into(), quirks);
+        self
+    }
+
+    /// Returns the quirks of the given `label`. The slice is empty if the
+    /// secrets for the label are exported as specified in the RFC.
+    pub fn quirks(&self, label: &str) -> &[ExporterQuirk] {
+        self.labels
+            .get(label)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Returns `true` if no label has quirks.
+    pub fn is_empty(&self) -> bool {
+        self.labels.values().all(Vec::is_empty)
+    }
+}
+
+impl ExporterSecret {
+    /// Derive a secret from the exporter secret like
+    /// [`Self::derive_exported_secret()`], but with the given `quirks`.
+    pub(crate) fn derive_exported_secret_with_quirks(
+        &self,
+        ciphersuite: Ciphersuite,
+        backend: &impl OpenMlsCryptoProvider,
+        label: &str,
+        context: &[u8],
+        key_length: usize,
+        quirks: &[ExporterQuirk],
+    ) -> Result<Vec<u8>, CryptoError> {
+        if quirks.is_empty() {
+            return self.derive_exported_secret(ciphersuite, backend, label, context, key_length);
+        }
+
+        let context_hash;
+        let context = if quirks.contains(&ExporterQuirk::UnhashedContext) {
+            context
+        } else {
+            context_hash = backend
+                .crypto()
+                .hash(ciphersuite.hash_algorithm(), context)?;
+            &context_hash
+        };
+        let expand_label = if quirks.contains(&ExporterQuirk::ExporterLabel) {
+            "exporter"
+        } else {
+            "exported"
+        };
+        let expand = |secret: &Secret, label: &str, context: &[u8], length: usize| {
+            if quirks.contains(&ExporterQuirk::DraftLabelPrefix) {
+                secret.kdf_expand_full_label(
+                    backend,
+                    format!("{DRAFT_LABEL_PREFIX} {label}"),
+                    context,
+                    length,
+                )
+            } else {
+                secret.kdf_expand_label(backend, label, context, length)
+            }
+        };
+
+        // DeriveSecret(exporter_secret, Label)
+        let secret = expand(&self.secret, label, &[], ciphersuite.hash_length())?;
+        Ok(expand(&secret, expand_label, context, key_length)?
+            .as_slice()
+            .to_vec())
+    }
+}
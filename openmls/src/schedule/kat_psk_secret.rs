@@ -91,7 +91,14 @@ fn run_test_vector(test: TestElement, backend: &impl OpenMlsCryptoProvider) -> R
     let psk_secret = {
         let resumption_psk_store = ResumptionPskStore::new(1024);
 
-        let psks = load_psks(backend.key_store(), &resumption_psk_store, &psk_ids).unwrap();
+        let psks = load_psks(
+            backend.key_store(),
+            &resumption_psk_store,
+            None,
+            ciphersuite,
+            &psk_ids,
+        )
+        .unwrap();
 
         PskSecret::new(backend, ciphersuite, psks).unwrap()
     };
@@ -3,8 +3,7 @@
 use super::*;
 
 /// Combined message secrets that need to be stored for later decryption/verification
-#[derive(Serialize, Deserialize)]
-#[cfg_attr(test, derive(Clone))]
+#[derive(Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "crypto-debug", derive(Debug))]
 pub(crate) struct MessageSecrets {
     sender_data_secret: SenderDataSecret,
@@ -66,6 +65,11 @@ impl MessageSecrets {
         self.serialized_context.as_ref()
     }
 
+    /// Get a reference to the message secrets's secret tree.
+    pub(crate) fn secret_tree(&self) -> &SecretTree {
+        &self.secret_tree
+    }
+
     /// Get a mutable reference to the message secrets's secret tree.
     pub(crate) fn secret_tree_mut(&mut self) -> &mut SecretTree {
         &mut self.secret_tree
@@ -1,7 +1,8 @@
 //! # Key schedule
 //!
 //! This module contains the types and implementations for key schedule operations.
-//! It exposes the [`EpochAuthenticator`] & [`ResumptionPskSecret`].
+//! It exposes the [`EpochAuthenticator`], the [`VerificationCode`] derived from
+//! it & [`ResumptionPskSecret`].
 
 // Internal documentation
 //
@@ -141,6 +142,7 @@ pub mod errors;
 pub mod psk;
 
 // Crate
+pub(crate) mod exporter_compat;
 pub(crate) mod message_secrets;
 
 // Private
@@ -149,7 +151,7 @@ use message_secrets::MessageSecrets;
 use psk::PskSecret;
 
 // Tests
-#[cfg(any(feature = "test-utils", test))]
+#[cfg(any(feature = "test-vectors", test))]
 pub mod kat_key_schedule;
 #[cfg(test)]
 pub mod kat_psk_secret;
@@ -157,7 +159,10 @@ pub mod kat_psk_secret;
 mod unit_tests;
 
 // Public types
-pub use psk::{ExternalPsk, PreSharedKeyId, Psk};
+pub use exporter_compat::{ExporterCompatibility, ExporterQuirk};
+#[cfg(feature = "async")]
+pub use psk::AsyncPskResolver;
+pub use psk::{ExternalPsk, PreSharedKeyId, Psk, PskResolver};
 
 /// A group secret that can be used among members to prove that a member was
 /// part of a group in a given epoch.
@@ -185,8 +190,8 @@ impl ResumptionPskSecret {
 
 /// A secret that can be used among members to make sure everyone has the same
 /// group state.
-#[derive(Debug, Serialize, Deserialize)]
-#[cfg_attr(test, derive(Eq, PartialEq, Clone))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(Eq, PartialEq))]
 pub struct EpochAuthenticator {
     secret: Secret,
 }
@@ -207,6 +212,73 @@ impl EpochAuthenticator {
     pub fn as_slice(&self) -> &[u8] {
         self.secret.as_slice()
     }
+
+    /// Returns a short [`VerificationCode`] derived from the epoch
+    /// authenticator that members can compare out of band.
+    pub fn verification_code(&self) -> VerificationCode {
+        VerificationCode::new(self.as_slice())
+    }
+}
+
+/// The number of digit groups in a [`VerificationCode`].
+const VERIFICATION_CODE_GROUPS: usize = 6;
+
+/// The number of bytes of the epoch authenticator that are encoded in one
+/// digit group of a [`VerificationCode`].
+const VERIFICATION_CODE_GROUP_BYTES: usize = 5;
+
+/// A human-comparable code derived from an [`EpochAuthenticator`], in the
+/// style of safety numbers.
+///
+/// Members that see the same code are in the same epoch of the same group
+/// with the same group state, so comparing the codes out of band, e.g. by
+/// reading them out or scanning them, verifies the group.
+///
+/// The encoding is stable: the first 30 bytes of the epoch authenticator are
+/// split into six chunks of five bytes. Each chunk is read as a big-endian
+/// integer and reduced modulo 100000, resulting in six groups of five decimal
+/// digits. The [`Display`](std::fmt::Display) implementation writes the
+/// groups zero-padded and separated by spaces, e.g.
+/// `"04721 93315 00812 56730 21984 67402"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationCode {
+    groups: [u32; VERIFICATION_CODE_GROUPS],
+}
+
+impl VerificationCode {
+    /// Encode the first bytes of the `epoch_authenticator`. All ciphersuites
+    /// use hash functions with an output of at least 32 bytes.
+    fn new(epoch_authenticator: &[u8]) -> Self {
+        let mut groups = [0; VERIFICATION_CODE_GROUPS];
+        for (group, chunk) in groups
+            .iter_mut()
+            .zip(epoch_authenticator.chunks_exact(VERIFICATION_CODE_GROUP_BYTES))
+        {
+            let value = chunk
+                .iter()
+                .fold(0u64, |value, byte| (value << 8) | u64::from(*byte));
+            *group = (value % 100_000) as u32;
+        }
+        Self { groups }
+    }
+
+    /// Returns the digit groups of the code, each of them between 0 and
+    /// 99999.
+    pub fn groups(&self) -> &[u32] {
+        &self.groups
+    }
+}
+
+impl std::fmt::Display for VerificationCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, group) in self.groups.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{group:05}")?;
+        }
+        Ok(())
+    }
 }
 
 // Crate-only types
@@ -250,8 +322,8 @@ impl CommitSecret {
 }
 
 /// The `InitSecret` is used to connect the next epoch to the current one.
-#[derive(Debug, Serialize, Deserialize)]
-#[cfg_attr(test, derive(PartialEq, Clone))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
 pub(crate) struct InitSecret {
     secret: Secret,
 }
@@ -700,8 +772,8 @@ impl EncryptionSecret {
 }
 
 /// A secret that we can derive secrets from, that are used outside of OpenMLS.
-#[derive(Debug, Serialize, Deserialize)]
-#[cfg_attr(test, derive(PartialEq, Clone))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
 pub(crate) struct ExporterSecret {
     secret: Secret,
 }
@@ -745,8 +817,8 @@ impl ExporterSecret {
 }
 
 /// A secret used when joining a group with an external Commit.
-#[derive(Debug, Serialize, Deserialize)]
-#[cfg_attr(test, derive(PartialEq, Clone))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
 pub(crate) struct ExternalSecret {
     secret: Secret,
 }
@@ -766,8 +838,10 @@ impl ExternalSecret {
         &self,
         crypto: &impl OpenMlsCrypto,
         ciphersuite: Ciphersuite,
-    ) -> HpkeKeyPair {
-        crypto.derive_hpke_keypair(ciphersuite.hpke_config(), self.secret.as_slice())
+    ) -> Result<HpkeKeyPair, LibraryError> {
+        crypto
+            .derive_hpke_keypair(ciphersuite.hpke_config(), self.secret.as_slice())
+            .map_err(LibraryError::unexpected_crypto_error)
     }
 
     #[cfg(any(feature = "test-utils", test))]
@@ -777,8 +851,8 @@ impl ExternalSecret {
 }
 
 /// The confirmation key is used to calculate the `ConfirmationTag`.
-#[derive(Debug, Serialize, Deserialize)]
-#[cfg_attr(test, derive(PartialEq, Clone))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
 pub(crate) struct ConfirmationKey {
     secret: Secret,
 }
@@ -845,8 +919,8 @@ impl ConfirmationKey {
 }
 
 /// The membership key is used to calculate the `MembershipTag`.
-#[derive(Debug, Serialize, Deserialize)]
-#[cfg_attr(test, derive(PartialEq, Clone))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
 pub(crate) struct MembershipKey {
     secret: Secret,
 }
@@ -916,9 +990,9 @@ fn ciphertext_sample(ciphersuite: Ciphersuite, ciphertext: &[u8]) -> &[u8] {
 }
 
 /// A key that can be used to derive an `AeadKey` and an `AeadNonce`.
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 #[cfg_attr(test, derive(PartialEq))]
-#[cfg_attr(any(feature = "test-utils", test), derive(Debug, Clone))]
+#[cfg_attr(any(feature = "test-utils", test), derive(Debug))]
 pub(crate) struct SenderDataSecret {
     secret: Secret,
 }
@@ -1199,8 +1273,7 @@ impl EpochSecrets {
     }
 }
 
-#[derive(Serialize, Deserialize)]
-#[cfg_attr(test, derive(Clone))]
+#[derive(Clone, Serialize, Deserialize)]
 pub(crate) struct GroupEpochSecrets {
     init_secret: InitSecret,
     exporter_secret: ExporterSecret,
@@ -1,8 +1,11 @@
 //! # Preshared keys.
 
+use std::fmt::Debug;
+
 use openmls_traits::{
     key_store::{MlsEntity, MlsEntityId, OpenMlsKeyStore},
     random::OpenMlsRand,
+    storage::{StorageKey, StorageProvider},
     OpenMlsCryptoProvider,
 };
 use serde::{Deserialize, Serialize};
@@ -10,7 +13,7 @@ use tls_codec::{Serialize as TlsSerializeTrait, VLBytes};
 
 use super::*;
 use crate::{
-    group::{GroupEpoch, GroupId},
+    group::{GroupEpoch, GroupId, MlsGroup},
     schedule::psk::store::ResumptionPskStore,
 };
 
@@ -95,6 +98,15 @@ pub(crate) struct PskBundle {
     secret: Secret,
 }
 
+impl PskBundle {
+    /// Create a new [`PskBundle`] from the secret value of a PSK.
+    pub(crate) fn new(psk: &[u8], ciphersuite: Ciphersuite) -> Self {
+        Self {
+            secret: Secret::from_slice(psk, ProtocolVersion::default(), ciphersuite),
+        }
+    }
+}
+
 impl MlsEntity for PskBundle {
     const ID: MlsEntityId = MlsEntityId::PskBundle;
 }
@@ -278,6 +290,11 @@ impl PreSharedKeyId {
 
     /// Save this `PreSharedKeyId` in the keystore.
     ///
+    /// Resumption PSKs can be saved as well, e.g. with the secret returned by
+    /// [`MlsGroup::get_past_resumption_psk()`](crate::group::MlsGroup::get_past_resumption_psk),
+    /// to use them on a client that isn't a member of the group they stem
+    /// from. Their usage is not part of the key.
+    ///
     /// Note: The nonce is not saved as it must be unique for each time it's being applied.
     pub fn write_to_key_store<KeyStore: OpenMlsKeyStore>(
         &self,
@@ -286,12 +303,7 @@ impl PreSharedKeyId {
         psk: &[u8],
     ) -> Result<(), PskError> {
         let keystore_id = self.keystore_id()?;
-
-        let psk_bundle = {
-            let secret = Secret::from_slice(psk, ProtocolVersion::default(), ciphersuite);
-
-            PskBundle { secret }
-        };
+        let psk_bundle = PskBundle::new(psk, ciphersuite);
 
         backend
             .key_store()
@@ -299,9 +311,41 @@ impl PreSharedKeyId {
             .map_err(|_| PskError::KeyStore)
     }
 
+    /// Construct an external `PreSharedKeyId` for the `psk_id` with a random
+    /// nonce and save the `psk` for it in the keystore.
+    ///
+    /// The PSK can then be used in proposals of groups and when joining
+    /// groups with the given `ciphersuite`. Since the nonce is not part of the
+    /// key, storing the same `psk_id` again replaces the secret.
+    pub fn store_external<KeyStore: OpenMlsKeyStore>(
+        backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
+        ciphersuite: Ciphersuite,
+        psk_id: Vec<u8>,
+        psk: &[u8],
+    ) -> Result<Self, PskError> {
+        let psk_id = Self::new(
+            ciphersuite,
+            backend.rand(),
+            Psk::External(ExternalPsk::new(psk_id)),
+        )
+        .map_err(LibraryError::unexpected_crypto_error)?;
+        psk_id.write_to_key_store(backend, ciphersuite, psk)?;
+        Ok(psk_id)
+    }
+
+    /// Resumption PSKs are stored independent of their usage, so that the
+    /// secret of an epoch can be used for any purpose once it's stored.
     pub(crate) fn keystore_id(&self) -> Result<Vec<u8>, LibraryError> {
+        let psk = match &self.psk {
+            Psk::External(_) => self.psk.clone(),
+            Psk::Resumption(resumption) => Psk::Resumption(ResumptionPsk::new(
+                ResumptionPskUsage::Application,
+                resumption.psk_group_id.clone(),
+                resumption.psk_epoch,
+            )),
+        };
         let psk_id_with_empty_nonce = PreSharedKeyId {
-            psk: self.psk.clone(),
+            psk,
             psk_nonce: VLBytes::new(vec![]),
         };
 
@@ -317,13 +361,30 @@ impl PreSharedKeyId {
 
     // ----- Validation ----------------------------------------------------------------------------
 
-    pub(crate) fn validate_in_proposal(self, ciphersuite: Ciphersuite) -> Result<Self, PskError> {
+    /// Validates the PSK ID of a PreSharedKey proposal.
+    ///
+    /// Resumption PSKs with usage `reinit` or `branch` are only allowed in the
+    /// first commit of a group, i.e. if `first_commit` is `true`.
+    pub(crate) fn validate_in_proposal(
+        self,
+        ciphersuite: Ciphersuite,
+        first_commit: bool,
+    ) -> Result<Self, PskError> {
         // ValSem402
         match self.psk() {
             Psk::Resumption(resumption_psk) => {
-                if resumption_psk.usage != ResumptionPskUsage::Application {
+                let allowed = if first_commit {
+                    vec![
+                        ResumptionPskUsage::Application,
+                        ResumptionPskUsage::Reinit,
+                        ResumptionPskUsage::Branch,
+                    ]
+                } else {
+                    vec![ResumptionPskUsage::Application]
+                };
+                if !allowed.contains(&resumption_psk.usage) {
                     return Err(PskError::UsageMismatch {
-                        allowed: vec![ResumptionPskUsage::Application],
+                        allowed,
                         got: resumption_psk.usage,
                     });
                 }
@@ -468,37 +529,129 @@ impl From<Secret> for PskSecret {
     }
 }
 
+/// Resolves the secrets of PSKs on demand.
+///
+/// OpenMLS looks for the secret of a PSK in the key store (see
+/// [`PreSharedKeyId::write_to_key_store()`]) and, for resumption PSKs, in the
+/// state of the group they stem from. A [`PskResolver`] is queried for the
+/// PSKs that can't be found there, e.g. to fetch them from an application
+/// server. It can be set for a group with
+/// [`MlsGroup::set_psk_resolver()`] and for joining a group with
+/// [`MlsGroup::new_from_welcome_with_psk_resolver()`].
+///
+/// If a PSK can't be resolved either, the operation fails with
+/// [`PskError::MissingPsks`], which lists the IDs of all missing PSKs, such
+/// that the application can fetch them and try again.
+pub trait PskResolver: Send + Sync {
+    /// Returns the secret of the PSK with the given `psk_id`, or `None` if
+    /// the PSK is unknown. The nonce of the `psk_id` is not part of the PSK.
+    fn resolve(&self, psk_id: &PreSharedKeyId) -> Option<Vec<u8>>;
+}
+
+impl Debug for dyn PskResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("PskResolver")
+    }
+}
+
+/// Resolves the secrets of PSKs on demand asynchronously.
+///
+/// Like [`PskResolver`], but resolving returns a future. It is used by the
+/// async variants of the [`MlsGroup`] operations that take a PSK resolver.
+///
+/// Every [`PskResolver`] is an [`AsyncPskResolver`] as well.
+#[cfg(feature = "async")]
+pub trait AsyncPskResolver: Send + Sync {
+    /// Returns the secret of the PSK with the given `psk_id`, or `None` if
+    /// the PSK is unknown. The nonce of the `psk_id` is not part of the PSK.
+    fn resolve(
+        &self,
+        psk_id: &PreSharedKeyId,
+    ) -> impl std::future::Future<Output = Option<Vec<u8>>> + Send;
+}
+
+#[cfg(feature = "async")]
+impl<R: PskResolver> AsyncPskResolver for R {
+    fn resolve(
+        &self,
+        psk_id: &PreSharedKeyId,
+    ) -> impl std::future::Future<Output = Option<Vec<u8>>> + Send {
+        std::future::ready(PskResolver::resolve(self, psk_id))
+    }
+}
+
+/// Loads the secrets of the given PSKs.
+///
+/// External PSKs are read from the key store. Resumption PSKs are looked up
+///  1. in the key store, where they can be saved with
+///     [`PreSharedKeyId::write_to_key_store()`],
+///  2. in the stored state of the group they stem from, so that members of
+///     that group find them without further ado, e.g. when joining a branch
+///     of it, and
+///  3. in the `resumption_psk_store` of the current group.
+///
+/// PSKs that can't be found are resolved through the `psk_resolver`, if
+/// any. Returns [`PskError::MissingPsks`] with the IDs of all PSKs that
+/// can't be resolved either.
 pub(crate) fn load_psks<'p>(
     key_store: &impl OpenMlsKeyStore,
     resumption_psk_store: &ResumptionPskStore,
+    psk_resolver: Option<&dyn PskResolver>,
+    ciphersuite: Ciphersuite,
     psk_ids: &'p [PreSharedKeyId],
 ) -> Result<Vec<(&'p PreSharedKeyId, Secret)>, PskError> {
     let mut psk_bundles = Vec::new();
+    let mut missing_psks = Vec::new();
 
     for psk_id in psk_ids.iter() {
         log_crypto!(trace, "PSK store {:?}", resumption_psk_store);
 
-        match &psk_id.psk {
-            Psk::Resumption(resumption) => {
-                if let Some(psk_bundle) = resumption_psk_store.get(resumption.psk_epoch()) {
-                    psk_bundles.push((psk_id, psk_bundle.secret.clone()));
-                } else {
-                    return Err(PskError::KeyNotFound);
-                }
-            }
-            Psk::External(_) => {
-                if let Some(psk_bundle) = key_store.read::<PskBundle>(&psk_id.keystore_id()?) {
-                    psk_bundles.push((psk_id, psk_bundle.secret));
-                } else {
-                    return Err(PskError::KeyNotFound);
-                }
-            }
+        let stored_secret = key_store
+            .read::<PskBundle>(&psk_id.keystore_id()?)
+            .map(|psk_bundle| psk_bundle.secret);
+        let secret = match &psk_id.psk {
+            Psk::Resumption(resumption) => stored_secret
+                .or_else(|| load_stored_resumption_psk(key_store, resumption))
+                .or_else(|| {
+                    resumption_psk_store
+                        .get(resumption.psk_epoch())
+                        .map(|psk_bundle| psk_bundle.secret.clone())
+                }),
+            Psk::External(_) => stored_secret,
+        }
+        .or_else(|| {
+            psk_resolver
+                .and_then(|psk_resolver| psk_resolver.resolve(psk_id))
+                .map(|psk| PskBundle::new(&psk, ciphersuite).secret)
+        });
+
+        match secret {
+            Some(secret) => psk_bundles.push((psk_id, secret)),
+            None => missing_psks.push(psk_id.clone()),
         }
     }
 
+    if !missing_psks.is_empty() {
+        return Err(PskError::MissingPsks(missing_psks));
+    }
+
     Ok(psk_bundles)
 }
 
+/// Looks up a resumption PSK in the stored state of the group it stems from.
+fn load_stored_resumption_psk(
+    key_store: &impl OpenMlsKeyStore,
+    resumption: &ResumptionPsk,
+) -> Option<Secret> {
+    let group: MlsGroup = key_store.load(StorageKey::GroupState {
+        group_id: resumption.psk_group_id.as_slice(),
+    })?;
+
+    group
+        .get_past_resumption_psk(resumption.psk_epoch)
+        .map(|resumption_psk| resumption_psk.secret.clone())
+}
+
 /// This module contains a store that can hold a rollover list of resumption PSKs.
 pub mod store {
     use serde::{Deserialize, Serialize};
@@ -507,13 +660,14 @@ pub mod store {
 
     /// Resumption PSK store.
     ///
-    /// This is where the resumption PSKs are kept in a rollover list.
-    #[derive(Debug, Serialize, Deserialize)]
-    #[cfg_attr(test, derive(PartialEq, Clone))]
+    /// This is where the resumption PSKs are kept in a rollover list. Once the
+    /// store is full, adding a PSK evicts the one that was added first.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[cfg_attr(test, derive(PartialEq))]
     pub(crate) struct ResumptionPskStore {
         max_number_of_secrets: usize,
+        // Ordered from the oldest to the newest entry.
         resumption_psk: Vec<(GroupEpoch, ResumptionPskSecret)>,
-        cursor: usize,
     }
 
     impl ResumptionPskStore {
@@ -522,24 +676,22 @@ pub mod store {
             Self {
                 max_number_of_secrets,
                 resumption_psk: vec![],
-                cursor: 0,
             }
         }
 
-        /// Adds a new entry to the store.
+        /// Adds a new entry to the store, replacing an existing entry for the
+        /// same epoch.
         pub(crate) fn add(&mut self, epoch: GroupEpoch, resumption_psk: ResumptionPskSecret) {
-            if self.max_number_of_secrets == 0 {
-                return;
-            }
-            let item = (epoch, resumption_psk);
-            if self.resumption_psk.len() < self.max_number_of_secrets {
-                self.resumption_psk.push(item);
-                self.cursor += 1;
-            } else {
-                self.cursor += 1;
-                self.cursor %= self.resumption_psk.len();
-                self.resumption_psk[self.cursor] = item;
-            }
+            self.resumption_psk.retain(|(e, _s)| e != &epoch);
+            self.resumption_psk.push((epoch, resumption_psk));
+            self.evict();
+        }
+
+        /// Sets the maximum number of secrets in the store. If the store holds
+        /// more secrets, the oldest ones are removed.
+        pub(crate) fn set_max_number_of_secrets(&mut self, max_number_of_secrets: usize) {
+            self.max_number_of_secrets = max_number_of_secrets;
+            self.evict();
         }
 
         /// Searches an entry for a given epoch number and if found, returns the
@@ -550,5 +702,13 @@ pub mod store {
                 .find(|&(e, _s)| e == &epoch)
                 .map(|(_e, s)| s)
         }
+
+        fn evict(&mut self) {
+            let excess = self
+                .resumption_psk
+                .len()
+                .saturating_sub(self.max_number_of_secrets);
+            self.resumption_psk.drain(..excess);
+        }
     }
 }
@@ -3,10 +3,14 @@
 use openmls_rust_crypto::OpenMlsRustCrypto;
 use openmls_traits::{random::OpenMlsRand, OpenMlsCryptoProvider};
 
-use super::PskSecret;
+use super::{EpochAuthenticator, PskSecret, ResumptionPskSecret};
 use crate::{
     ciphersuite::Secret,
-    schedule::psk::{store::ResumptionPskStore, *},
+    group::{GroupEpoch, GroupId},
+    schedule::{
+        errors::PskError,
+        psk::{store::ResumptionPskStore, *},
+    },
     test_utils::*,
     versions::ProtocolVersion,
 };
@@ -46,8 +50,110 @@ fn test_psks(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
     let _psk_secret = {
         let resumption_psk_store = ResumptionPskStore::new(1024);
 
-        let psks = load_psks(backend.key_store(), &resumption_psk_store, &psk_ids).unwrap();
+        let psks = load_psks(
+            backend.key_store(),
+            &resumption_psk_store,
+            None,
+            ciphersuite,
+            &psk_ids,
+        )
+        .unwrap();
 
         PskSecret::new(backend, ciphersuite, psks).unwrap()
     };
 }
+
+#[test]
+fn resumption_psk_store_rollover() {
+    let resumption_psk = |byte: u8| ResumptionPskSecret {
+        secret: Secret::from_slice(
+            &[byte; 32],
+            ProtocolVersion::Mls10,
+            Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519,
+        ),
+    };
+    let epochs = |store: &ResumptionPskStore| {
+        (0..6)
+            .filter(|&epoch| store.get(GroupEpoch::from(epoch)).is_some())
+            .collect::<Vec<u64>>()
+    };
+
+    // The oldest secrets are evicted once the store is full.
+    let mut store = ResumptionPskStore::new(3);
+    for epoch in 0..5 {
+        store.add(GroupEpoch::from(epoch), resumption_psk(epoch as u8));
+    }
+    assert_eq!(epochs(&store), vec![2, 3, 4]);
+    assert_eq!(
+        store.get(GroupEpoch::from(3)).map(|psk| psk.as_slice()),
+        Some(&[3u8; 32][..])
+    );
+
+    // Adding a secret for an epoch again replaces it.
+    store.add(GroupEpoch::from(2), resumption_psk(0xff));
+    store.add(GroupEpoch::from(5), resumption_psk(5));
+    assert_eq!(epochs(&store), vec![2, 4, 5]);
+    assert_eq!(
+        store.get(GroupEpoch::from(2)).map(|psk| psk.as_slice()),
+        Some(&[0xffu8; 32][..])
+    );
+
+    // Shrinking the store drops the oldest secrets.
+    store.set_max_number_of_secrets(1);
+    assert_eq!(epochs(&store), vec![5]);
+
+    // A store without capacity keeps nothing.
+    let mut store = ResumptionPskStore::new(0);
+    store.add(GroupEpoch::from(0), resumption_psk(0));
+    assert!(epochs(&store).is_empty());
+}
+
+#[test]
+fn resumption_psk_usage_in_proposal() {
+    let ciphersuite = Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519;
+    let psk_id = |usage| {
+        PreSharedKeyId::resumption(
+            usage,
+            GroupId::from_slice(b"old group"),
+            GroupEpoch::from(3),
+            vec![0; ciphersuite.hash_length()],
+        )
+    };
+
+    // Application PSKs are always allowed.
+    assert!(psk_id(ResumptionPskUsage::Application)
+        .validate_in_proposal(ciphersuite, false)
+        .is_ok());
+
+    // ReInit and Branch PSKs only in the first commit of a group.
+    for usage in [ResumptionPskUsage::Reinit, ResumptionPskUsage::Branch] {
+        assert!(psk_id(usage)
+            .validate_in_proposal(ciphersuite, true)
+            .is_ok());
+        assert_eq!(
+            psk_id(usage).validate_in_proposal(ciphersuite, false),
+            Err(PskError::UsageMismatch {
+                allowed: vec![ResumptionPskUsage::Application],
+                got: usage,
+            })
+        );
+    }
+}
+
+#[test]
+fn verification_code_encoding() {
+    let epoch_authenticator = |bytes: &[u8]| EpochAuthenticator {
+        secret: Secret::from_slice(
+            bytes,
+            ProtocolVersion::Mls10,
+            Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519,
+        ),
+    };
+
+    let code = epoch_authenticator(&(0..32).collect::<Vec<u8>>()).verification_code();
+    assert_eq!(code.groups(), &[9060, 60585, 12110, 63635, 15160, 66685]);
+    assert_eq!(code.to_string(), "09060 60585 12110 63635 15160 66685");
+
+    let code = epoch_authenticator(&[0xff; 32]).verification_code();
+    assert_eq!(code.to_string(), "27775 27775 27775 27775 27775 27775");
+}
@@ -26,6 +26,7 @@ use crate::{
     treesync::node::encryption_keys::{EncryptionKeyPair, EncryptionPrivateKey},
 };
 
+pub mod storage_faults;
 pub mod test_framework;
 
 pub(crate) fn write(file_name: &str, obj: impl Serialize) {
@@ -203,12 +204,55 @@ pub(crate) fn generate_group_candidate(
 // This backend is currently used on all platforms
 pub use openmls_rust_crypto::OpenMlsRustCrypto;
 
+// The OpenSSL backend is tested in addition with the `openssl` feature
+#[cfg(feature = "openssl")]
+pub use openmls_openssl_crypto::OpenMlsOpenSsl;
+
+// The libcrux backend is tested in addition with the `libcrux` feature
+#[cfg(feature = "libcrux")]
+pub use openmls_libcrux_crypto::OpenMlsLibcrux;
+
 // === Backends ===
 
+#[cfg(not(any(feature = "openssl", feature = "libcrux")))]
+#[template]
+#[export]
+#[rstest(backend,
+    case::rust_crypto(&OpenMlsRustCrypto::default()),
+  )
+]
+#[allow(non_snake_case)]
+pub fn backends(backend: &impl OpenMlsCryptoProvider) {}
+
+#[cfg(all(feature = "openssl", not(feature = "libcrux")))]
+#[template]
+#[export]
+#[rstest(backend,
+    case::rust_crypto(&OpenMlsRustCrypto::default()),
+    case::openssl(&OpenMlsOpenSsl::default()),
+  )
+]
+#[allow(non_snake_case)]
+pub fn backends(backend: &impl OpenMlsCryptoProvider) {}
+
+#[cfg(all(feature = "libcrux", not(feature = "openssl")))]
+#[template]
+#[export]
+#[rstest(backend,
+    case::rust_crypto(&OpenMlsRustCrypto::default()),
+    case::libcrux(&OpenMlsLibcrux::default()),
+  )
+]
+#[allow(non_snake_case)]
+pub fn backends(backend: &impl OpenMlsCryptoProvider) {}
+
+#[cfg(all(feature = "openssl", feature = "libcrux"))]
 #[template]
 #[export]
 #[rstest(backend,
     case::rust_crypto(&OpenMlsRustCrypto::default()),
+    case::openssl(&OpenMlsOpenSsl::default()),
+    case::libcrux(&OpenMlsLibcrux::default()),
   )
 ]
 #[allow(non_snake_case)]
@@ -230,6 +274,12 @@ pub fn backends(backend: &impl OpenMlsCryptoProvider) {}
     ),
     case::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519(
         Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519
+    ),
+    case::MLS_256_DHKEMP521_AES256GCM_SHA512_P521(
+        Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521
+    ),
+    case::MLS_256_DHKEMP384_AES256GCM_SHA384_P384(
+        Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384
     )
 )]
 #[allow(non_snake_case)]
@@ -237,12 +287,72 @@ pub fn ciphersuites(ciphersuite: Ciphersuite) {}
 
 // === Ciphersuites & backends ===
 
+// The libcrux backend only supports the ciphersuites on X25519 and Ed25519
+#[cfg(not(any(feature = "openssl", feature = "libcrux")))]
+#[template]
+#[export]
+#[rstest(ciphersuite, backend,
+    case::rust_crypto_MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_128_DHKEMP256_AES128GCM_SHA256_P256(Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_256_DHKEMP521_AES256GCM_SHA512_P521(Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_256_DHKEMP384_AES256GCM_SHA384_P384(Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384, &OpenMlsRustCrypto::default()),
+  )
+]
+#[allow(non_snake_case)]
+pub fn ciphersuites_and_backends(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {}
+
+#[cfg(all(feature = "openssl", not(feature = "libcrux")))]
+#[template]
+#[export]
+#[rstest(ciphersuite, backend,
+    case::rust_crypto_MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_128_DHKEMP256_AES128GCM_SHA256_P256(Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_256_DHKEMP521_AES256GCM_SHA512_P521(Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_256_DHKEMP384_AES256GCM_SHA384_P384(Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384, &OpenMlsRustCrypto::default()),
+    case::openssl_MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519, &OpenMlsOpenSsl::default()),
+    case::openssl_MLS_128_DHKEMP256_AES128GCM_SHA256_P256(Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256, &OpenMlsOpenSsl::default()),
+    case::openssl_MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519, &OpenMlsOpenSsl::default()),
+    case::openssl_MLS_256_DHKEMP521_AES256GCM_SHA512_P521(Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521, &OpenMlsOpenSsl::default()),
+    case::openssl_MLS_256_DHKEMP384_AES256GCM_SHA384_P384(Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384, &OpenMlsOpenSsl::default()),
+  )
+]
+#[allow(non_snake_case)]
+pub fn ciphersuites_and_backends(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {}
+
+#[cfg(all(feature = "libcrux", not(feature = "openssl")))]
+#[template]
+#[export]
+#[rstest(ciphersuite, backend,
+    case::rust_crypto_MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_128_DHKEMP256_AES128GCM_SHA256_P256(Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_256_DHKEMP521_AES256GCM_SHA512_P521(Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_256_DHKEMP384_AES256GCM_SHA384_P384(Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384, &OpenMlsRustCrypto::default()),
+    case::libcrux_MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519, &OpenMlsLibcrux::default()),
+    case::libcrux_MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519, &OpenMlsLibcrux::default()),
+  )
+]
+#[allow(non_snake_case)]
+pub fn ciphersuites_and_backends(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {}
+
+#[cfg(all(feature = "openssl", feature = "libcrux"))]
 #[template]
 #[export]
 #[rstest(ciphersuite, backend,
     case::rust_crypto_MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519, &OpenMlsRustCrypto::default()),
     case::rust_crypto_MLS_128_DHKEMP256_AES128GCM_SHA256_P256(Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256, &OpenMlsRustCrypto::default()),
     case::rust_crypto_MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_256_DHKEMP521_AES256GCM_SHA512_P521(Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521, &OpenMlsRustCrypto::default()),
+    case::rust_crypto_MLS_256_DHKEMP384_AES256GCM_SHA384_P384(Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384, &OpenMlsRustCrypto::default()),
+    case::openssl_MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519, &OpenMlsOpenSsl::default()),
+    case::openssl_MLS_128_DHKEMP256_AES128GCM_SHA256_P256(Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256, &OpenMlsOpenSsl::default()),
+    case::openssl_MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519, &OpenMlsOpenSsl::default()),
+    case::openssl_MLS_256_DHKEMP521_AES256GCM_SHA512_P521(Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521, &OpenMlsOpenSsl::default()),
+    case::openssl_MLS_256_DHKEMP384_AES256GCM_SHA384_P384(Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384, &OpenMlsOpenSsl::default()),
+    case::libcrux_MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519, &OpenMlsLibcrux::default()),
+    case::libcrux_MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519(Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519, &OpenMlsLibcrux::default()),
   )
 ]
 #[allow(non_snake_case)]
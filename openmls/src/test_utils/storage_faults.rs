@@ -0,0 +1,363 @@
+//! Fault injection for the key store.
+//!
+//! The [`FaultyBackend`] puts a [`FaultyKeyStore`] in front of the key store
+//! of a backend. A [`StorageFault`] injected into it lets writes fail, crashes
+//! the process at a given point or persists cached writes in the wrong order.
+//!
+//! A crash is simulated by dropping all writes that weren't persisted yet,
+//! including a batch that is being applied, and failing all further
+//! operations until
+//! [`FaultyBackend::restart()`] is called. The in-memory group states of the
+//! crashed client must be dropped, and the groups loaded again from the key
+//! store with [`MlsGroup::load()`](crate::group::MlsGroup::load()).
+
+use std::{collections::HashMap, sync::Mutex};
+
+use openmls_traits::{
+    key_store::{BatchOperation, MlsEntity, MlsEntityId, OpenMlsKeyStore, WriteBatch},
+    OpenMlsCryptoProvider,
+};
+use thiserror::Error;
+
+/// A fault of the key store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageFault {
+    /// Writes fail as soon as the serialized values written since the fault
+    /// was injected exceed the given number of bytes. A failing write doesn't
+    /// change the stored value.
+    FailWriteAfterBytes(usize),
+    /// The process crashes right after the given number of deletions, e.g.
+    /// between deleting the keys of an epoch and writing the new group state.
+    /// A batch that reaches the number of deletions is not applied.
+    CrashAfterDeletes(usize),
+    /// Writes and deletions outside of batches are cached and only persisted
+    /// when the cache is flushed, i.e. through [`FaultyBackend::flush()`] or
+    /// before a batch is applied. The cache
+    /// keeps the last value per key and persists the keys in the reverse
+    /// order of their first write. A crash interrupts this after the given
+    /// number of keys.
+    ReorderFlushes {
+        /// The number of keys that are persisted when the process crashes.
+        persisted_on_crash: usize,
+    },
+}
+
+/// Error of the [`FaultyKeyStore`].
+#[derive(Error, Debug, PartialEq)]
+pub enum FaultyKeyStoreError<KeyStoreError> {
+    /// Writing failed because of a [`StorageFault::FailWriteAfterBytes`].
+    #[error("Writing failed.")]
+    WriteFailed,
+    /// The key store was used after a crash.
+    #[error("The process crashed.")]
+    Crashed,
+    /// Error of the underlying key store.
+    #[error(transparent)]
+    KeyStoreError(KeyStoreError),
+}
+
+/// A key store that injects a [`StorageFault`] into the operations on the
+/// underlying key store.
+pub struct FaultyKeyStore<'a, KeyStore: OpenMlsKeyStore> {
+    key_store: &'a KeyStore,
+    state: Mutex<FaultState<KeyStore>>,
+}
+
+struct FaultState<KeyStore: OpenMlsKeyStore> {
+    fault: Option<StorageFault>,
+    crashed: bool,
+    bytes_written: usize,
+    deletions: usize,
+    // Writes and deletions that weren't persisted yet, together with the
+    // order in which their keys were first written.
+    cache: HashMap<Vec<u8>, CachedWrite<KeyStore>>,
+    dirty_keys: Vec<Vec<u8>>,
+}
+
+impl<KeyStore: OpenMlsKeyStore> FaultState<KeyStore> {
+    fn caches_writes(&self) -> bool {
+        matches!(self.fault, Some(StorageFault::ReorderFlushes { .. }))
+    }
+
+    fn cache(&mut self, k: &[u8], write: CachedWrite<KeyStore>) {
+        if self.cache.insert(k.to_vec(), write).is_none() {
+            self.dirty_keys.push(k.to_vec());
+        }
+    }
+
+    /// Persists the first `count` cached keys in the reverse order of their
+    /// first write and drops the rest of the cache.
+    fn flush(
+        &mut self,
+        key_store: &KeyStore,
+        count: usize,
+    ) -> Result<(), FaultyKeyStoreError<KeyStore::Error>> {
+        let dirty_keys = std::mem::take(&mut self.dirty_keys);
+        let mut cache = std::mem::take(&mut self.cache);
+        for key in dirty_keys.into_iter().rev().take(count) {
+            match cache.remove(&key) {
+                Some(CachedWrite::Store { entity_id, value }) => key_store
+                    .store_encoded(entity_id, &key, &value)
+                    .map_err(FaultyKeyStoreError::KeyStoreError)?,
+                Some(CachedWrite::Delete { delete }) => {
+                    delete(key_store, &key).map_err(FaultyKeyStoreError::KeyStoreError)?
+                }
+                None => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Simulates a crash of the process.
+    fn crash(&mut self, key_store: &KeyStore) {
+        let persisted = match self.fault {
+            Some(StorageFault::ReorderFlushes { persisted_on_crash }) => persisted_on_crash,
+            _ => 0,
+        };
+        // A failing write is just another way for the process to die.
+        let _ = self.flush(key_store, persisted);
+        self.crashed = true;
+    }
+}
+
+/// A write or deletion in the cache of the [`FaultyKeyStore`]. Values are
+/// encoded by the underlying key store. The deletion function is instantiated
+/// for the type of the deleted entity.
+enum CachedWrite<KeyStore: OpenMlsKeyStore> {
+    Store {
+        entity_id: MlsEntityId,
+        value: Vec<u8>,
+    },
+    Delete {
+        delete: fn(&KeyStore, &[u8]) -> Result<(), KeyStore::Error>,
+    },
+}
+
+fn delete_entity<V: MlsEntity, KeyStore: OpenMlsKeyStore>(
+    key_store: &KeyStore,
+    key: &[u8],
+) -> Result<(), KeyStore::Error> {
+    key_store.delete::<V>(key)
+}
+
+impl<'a, KeyStore: OpenMlsKeyStore> FaultyKeyStore<'a, KeyStore> {
+    fn new(key_store: &'a KeyStore) -> Self {
+        Self {
+            key_store,
+            state: Mutex::new(FaultState {
+                fault: None,
+                crashed: false,
+                bytes_written: 0,
+                deletions: 0,
+                cache: HashMap::new(),
+                dirty_keys: vec![],
+            }),
+        }
+    }
+}
+
+impl<'a, KeyStore: OpenMlsKeyStore> OpenMlsKeyStore for FaultyKeyStore<'a, KeyStore> {
+    type Error = FaultyKeyStoreError<KeyStore::Error>;
+
+    fn store<V: MlsEntity>(&self, k: &[u8], v: &V) -> Result<(), Self::Error> {
+        let value = self.encode(v)?;
+        self.store_encoded(V::ID, k, &value)
+    }
+
+    fn read<V: MlsEntity>(&self, k: &[u8]) -> Option<V> {
+        let state = self.state.lock().unwrap();
+        if state.crashed {
+            return None;
+        }
+        match state.cache.get(k) {
+            Some(CachedWrite::Store { value, .. }) => self.key_store.decode(value),
+            Some(CachedWrite::Delete { .. }) => None,
+            None => self.key_store.read(k),
+        }
+    }
+
+    fn delete<V: MlsEntity>(&self, k: &[u8]) -> Result<(), Self::Error> {
+        let mut state = self.state.lock().unwrap();
+        if state.crashed {
+            return Err(FaultyKeyStoreError::Crashed);
+        }
+        if state.caches_writes() {
+            state.cache(
+                k,
+                CachedWrite::Delete {
+                    delete: delete_entity::<V, KeyStore>,
+                },
+            );
+        } else {
+            self.key_store
+                .delete::<V>(k)
+                .map_err(FaultyKeyStoreError::KeyStoreError)?;
+        }
+        state.deletions += 1;
+        if let Some(StorageFault::CrashAfterDeletes(deletions)) = state.fault {
+            if state.deletions >= deletions {
+                state.crash(self.key_store);
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_batch(&self, batch: WriteBatch) -> Result<(), Self::Error> {
+        let mut state = self.state.lock().unwrap();
+        if state.crashed {
+            return Err(FaultyKeyStoreError::Crashed);
+        }
+        // A batch is a barrier for the cache.
+        let cached = state.dirty_keys.len();
+        state.flush(self.key_store, cached)?;
+
+        // The batch is applied atomically, so it fails or is lost as a whole.
+        let mut bytes = 0;
+        let mut deletions = 0;
+        for operation in batch.operations() {
+            match operation {
+                BatchOperation::Store { value, .. } => bytes += value.len(),
+                BatchOperation::Delete { .. } => deletions += 1,
+            }
+        }
+        if let Some(StorageFault::FailWriteAfterBytes(budget)) = state.fault {
+            if state.bytes_written + bytes > budget {
+                return Err(FaultyKeyStoreError::WriteFailed);
+            }
+        }
+        state.bytes_written += bytes;
+        state.deletions += deletions;
+        if let Some(StorageFault::CrashAfterDeletes(deletions)) = state.fault {
+            if state.deletions >= deletions {
+                state.crash(self.key_store);
+                return Err(FaultyKeyStoreError::Crashed);
+            }
+        }
+        self.key_store
+            .apply_batch(batch)
+            .map_err(FaultyKeyStoreError::KeyStoreError)
+    }
+
+    fn encode<V: MlsEntity>(&self, v: &V) -> Result<Vec<u8>, Self::Error> {
+        self.key_store
+            .encode(v)
+            .map_err(FaultyKeyStoreError::KeyStoreError)
+    }
+
+    fn decode<V: MlsEntity>(&self, value: &[u8]) -> Option<V> {
+        self.key_store.decode(value)
+    }
+
+    fn store_encoded(
+        &self,
+        entity_id: MlsEntityId,
+        k: &[u8],
+        value: &[u8],
+    ) -> Result<(), Self::Error> {
+        // We unwrap here, because the lock is only held by the operations of
+        // this key store, which don't panic.
+        let mut state = self.state.lock().unwrap();
+        if state.crashed {
+            return Err(FaultyKeyStoreError::Crashed);
+        }
+        if let Some(StorageFault::FailWriteAfterBytes(budget)) = state.fault {
+            if state.bytes_written + value.len() > budget {
+                return Err(FaultyKeyStoreError::WriteFailed);
+            }
+        }
+        state.bytes_written += value.len();
+        if state.caches_writes() {
+            state.cache(
+                k,
+                CachedWrite::Store {
+                    entity_id,
+                    value: value.to_vec(),
+                },
+            );
+            return Ok(());
+        }
+        self.key_store
+            .store_encoded(entity_id, k, value)
+            .map_err(FaultyKeyStoreError::KeyStoreError)
+    }
+}
+
+/// A backend with a [`FaultyKeyStore`] in front of its key store.
+pub struct FaultyBackend<'a, Backend: OpenMlsCryptoProvider> {
+    backend: &'a Backend,
+    key_store: FaultyKeyStore<'a, Backend::KeyStoreProvider>,
+}
+
+impl<'a, Backend: OpenMlsCryptoProvider> FaultyBackend<'a, Backend> {
+    /// Create a new backend in front of the given `backend` without any
+    /// fault.
+    pub fn new(backend: &'a Backend) -> Self {
+        Self {
+            backend,
+            key_store: FaultyKeyStore::new(backend.key_store()),
+        }
+    }
+
+    /// Inject the given `fault` into all following operations. This replaces
+    /// a previously injected fault.
+    pub fn inject(&self, fault: StorageFault) {
+        let mut state = self.key_store.state.lock().unwrap();
+        state.fault = Some(fault);
+        state.bytes_written = 0;
+        state.deletions = 0;
+    }
+
+    /// Persist all cached writes, see [`StorageFault::ReorderFlushes`].
+    pub fn flush(
+        &self,
+    ) -> Result<(), FaultyKeyStoreError<<Backend::KeyStoreProvider as OpenMlsKeyStore>::Error>>
+    {
+        let mut state = self.key_store.state.lock().unwrap();
+        let cached = state.dirty_keys.len();
+        state.flush(self.backend.key_store(), cached)
+    }
+
+    /// Simulate a crash of the process.
+    pub fn crash(&self) {
+        self.key_store
+            .state
+            .lock()
+            .unwrap()
+            .crash(self.backend.key_store())
+    }
+
+    /// Returns `true` if the process crashed and wasn't restarted yet.
+    pub fn crashed(&self) -> bool {
+        self.key_store.state.lock().unwrap().crashed
+    }
+
+    /// Restart the process, e.g. after a crash or a failed write. If the
+    /// process didn't crash before, it loses the same writes as in a crash.
+    /// This removes the injected fault.
+    pub fn restart(&self) {
+        let mut state = self.key_store.state.lock().unwrap();
+        if !state.crashed {
+            state.crash(self.backend.key_store());
+        }
+        state.fault = None;
+        state.crashed = false;
+    }
+}
+
+impl<'a, Backend: OpenMlsCryptoProvider> OpenMlsCryptoProvider for FaultyBackend<'a, Backend> {
+    type CryptoProvider = Backend::CryptoProvider;
+    type RandProvider = Backend::RandProvider;
+    type KeyStoreProvider = FaultyKeyStore<'a, Backend::KeyStoreProvider>;
+
+    fn crypto(&self) -> &Self::CryptoProvider {
+        self.backend.crypto()
+    }
+
+    fn rand(&self) -> &Self::RandProvider {
+        self.backend.rand()
+    }
+
+    fn key_store(&self) -> &Self::KeyStoreProvider {
+        &self.key_store
+    }
+}
@@ -149,18 +149,24 @@ impl Client {
         } else {
             if message.content_type() == ContentType::Commit {
                 // Clear any potential pending commits.
-                group_state.clear_pending_commit();
+                group_state.clear_pending_commit(&self.crypto)?;
             }
-            // Process the message.
-            let processed_message = group_state.process_message(&self.crypto, message.clone())?;
+            // Process the message. Own proposals are already in the proposal
+            // store.
+            let processed_message = match group_state.process_message(&self.crypto, message.clone())
+            {
+                Ok(processed_message) => processed_message,
+                Err(ProcessMessageError::OwnProposal) => return Ok(()),
+                Err(e) => return Err(e.into()),
+            };
 
             match processed_message.into_content() {
                 ProcessedMessageContent::ApplicationMessage(_) => {}
                 ProcessedMessageContent::ProposalMessage(staged_proposal) => {
-                    group_state.store_pending_proposal(*staged_proposal);
+                    group_state.store_pending_proposal(&self.crypto, *staged_proposal)?;
                 }
                 ProcessedMessageContent::ExternalJoinProposalMessage(staged_proposal) => {
-                    group_state.store_pending_proposal(*staged_proposal);
+                    group_state.store_pending_proposal(&self.crypto, *staged_proposal)?;
                 }
                 ProcessedMessageContent::StagedCommitMessage(staged_commit) => {
                     group_state.merge_staged_commit(&self.crypto, *staged_commit)?;
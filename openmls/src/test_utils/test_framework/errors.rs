@@ -57,7 +57,7 @@ pub enum ClientError {
     TlsCodecError(#[from] tls_codec::Error),
     /// See [`ProcessMessageError`] for more details.
     #[error(transparent)]
-    ProcessMessageError(#[from] ProcessMessageError),
+    ProcessMessageError(#[from] ProcessMessageError<MemoryKeyStoreError>),
     /// See [`MlsGroupStateError`] for more details.
     #[error(transparent)]
     MlsGroupStateError(#[from] MlsGroupStateError),
@@ -69,10 +69,10 @@ pub enum ClientError {
     RemoveMembersError(#[from] RemoveMembersError<MemoryKeyStoreError>),
     /// See [`ProposeAddMemberError`] for more details.
     #[error(transparent)]
-    ProposeAddMemberError(#[from] ProposeAddMemberError),
+    ProposeAddMemberError(#[from] ProposeAddMemberError<MemoryKeyStoreError>),
     /// See [`ProposeRemoveMemberError`] for more details.
     #[error(transparent)]
-    ProposeRemoveMemberError(#[from] ProposeRemoveMemberError),
+    ProposeRemoveMemberError(#[from] ProposeRemoveMemberError<MemoryKeyStoreError>),
     /// See [`ExportSecretError`] for more details.
     #[error(transparent)]
     ExportSecretError(#[from] ExportSecretError),
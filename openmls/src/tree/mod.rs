@@ -8,5 +8,5 @@ pub mod sender_ratchet;
 // Crate
 pub(crate) mod secret_tree;
 
-#[cfg(any(feature = "test-utils", test))]
+#[cfg(any(feature = "test-vectors", test))]
 pub mod tests_and_kats;
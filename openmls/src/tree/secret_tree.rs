@@ -1,3 +1,5 @@
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
 use openmls_traits::types::{Ciphersuite, CryptoError};
 use thiserror::Error;
 use tls_codec::{Error as TlsCodecError, TlsSerialize, TlsSize};
@@ -30,6 +32,14 @@ pub enum SecretTreeError {
     /// The requested secret was deleted to preserve forward secrecy.
     #[error("The requested secret was deleted to preserve forward secrecy.")]
     SecretReuseError,
+    /// The key of a skipped message was evicted because of the limits on
+    /// skipped keys in the [`SenderRatchetConfiguration`]. The message of the
+    /// sender with the given generation can't be decrypted anymore.
+    #[error("The key of the skipped message with generation {generation} was evicted.")]
+    SkippedKeyEvicted {
+        /// The generation of the message that can't be decrypted anymore.
+        generation: u32,
+    },
     /// Cannot create decryption secrets from own sender ratchet or encryption secrets from the sender ratchets of other members.
     #[error("Cannot create decryption secrets from own sender ratchet or encryption secrets from the sender ratchets of other members.")]
     RatchetTypeError,
@@ -98,24 +108,99 @@ pub(crate) struct TreeContext {
     pub(crate) generation: u32,
 }
 
-#[derive(Debug, Serialize, Deserialize, TlsSerialize, TlsSize)]
-#[cfg_attr(any(feature = "test-utils", test), derive(PartialEq, Clone))]
+#[derive(Debug, Clone, Serialize, Deserialize, TlsSerialize, TlsSize)]
+#[cfg_attr(any(feature = "test-utils", test), derive(PartialEq))]
 pub(crate) struct SecretTreeNode {
     pub(crate) secret: Secret,
 }
 
+/// The handshake and the application ratchet of a sender, which can be
+/// stored without the rest of the [`SecretTree`]. See
+/// [`SecretTree::sender_ratchets()`].
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(any(feature = "crypto-debug", test), derive(Debug))]
+pub(crate) struct SenderRatchets {
+    handshake: SenderRatchet,
+    application: SenderRatchet,
+}
+
+/// Locks a mutex of a [`SecretTree`]. The secrets behind the locks are
+/// replaced as a whole and never left half-written, so a lock that was
+/// poisoned by a panic of another thread is recovered.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns the value of a mutex of a [`SecretTree`] without locking it. See
+/// [`lock()`].
+fn get_mut<T>(mutex: &mut Mutex<T>) -> &mut T {
+    mutex.get_mut().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The secret tree of an epoch.
+///
+/// The node secrets and each sender ratchet are behind their own lock, so
+/// that messages can be decrypted through a shared reference, see
+/// [`SecretTree::secret_for_decryption()`]. Messages of different senders
+/// only contend for the lock of the node secrets when the first message of
+/// a sender is decrypted, which derives its sender ratchets.
 #[derive(Serialize, Deserialize)]
-#[cfg_attr(any(feature = "test-utils", test), derive(PartialEq, Clone))]
 #[cfg_attr(any(feature = "crypto-debug", test), derive(Debug))]
 pub(crate) struct SecretTree {
     own_index: LeafNodeIndex,
-    leaf_nodes: Vec<Option<SecretTreeNode>>,
-    parent_nodes: Vec<Option<SecretTreeNode>>,
-    handshake_sender_ratchets: Vec<Option<SenderRatchet>>,
-    application_sender_ratchets: Vec<Option<SenderRatchet>>,
+    leaf_nodes: Mutex<Vec<Option<SecretTreeNode>>>,
+    parent_nodes: Mutex<Vec<Option<SecretTreeNode>>>,
+    handshake_sender_ratchets: Vec<Mutex<Option<SenderRatchet>>>,
+    application_sender_ratchets: Vec<Mutex<Option<SenderRatchet>>>,
     size: TreeSize,
 }
 
+#[cfg(any(feature = "test-utils", test))]
+impl PartialEq for SecretTree {
+    fn eq(&self, other: &Self) -> bool {
+        let ratchets_eq =
+            |ratchets: &[Mutex<Option<SenderRatchet>>],
+             other_ratchets: &[Mutex<Option<SenderRatchet>>]| {
+                ratchets.len() == other_ratchets.len()
+                    && ratchets
+                        .iter()
+                        .zip(other_ratchets)
+                        .all(|(ratchet, other_ratchet)| *lock(ratchet) == *lock(other_ratchet))
+            };
+        self.own_index == other.own_index
+            && *lock(&self.leaf_nodes) == *lock(&other.leaf_nodes)
+            && *lock(&self.parent_nodes) == *lock(&other.parent_nodes)
+            && ratchets_eq(
+                &self.handshake_sender_ratchets,
+                &other.handshake_sender_ratchets,
+            )
+            && ratchets_eq(
+                &self.application_sender_ratchets,
+                &other.application_sender_ratchets,
+            )
+            && self.size == other.size
+    }
+}
+
+impl Clone for SecretTree {
+    fn clone(&self) -> Self {
+        let clone_ratchets = |ratchets: &[Mutex<Option<SenderRatchet>>]| {
+            ratchets
+                .iter()
+                .map(|ratchet| Mutex::new(lock(ratchet).clone()))
+                .collect()
+        };
+        Self {
+            own_index: self.own_index,
+            leaf_nodes: Mutex::new(lock(&self.leaf_nodes).clone()),
+            parent_nodes: Mutex::new(lock(&self.parent_nodes).clone()),
+            handshake_sender_ratchets: clone_ratchets(&self.handshake_sender_ratchets),
+            application_sender_ratchets: clone_ratchets(&self.application_sender_ratchets),
+            size: self.size,
+        }
+    }
+}
+
 impl SecretTree {
     /// Creates a new SecretTree based on an `encryption_secret` and group size
     /// `size`. The inner nodes of the tree and the SenderRatchets only get
@@ -147,11 +232,11 @@ impl SecretTree {
             }
         }
 
-        let handshake_sender_ratchets = std::iter::repeat_with(|| Option::<SenderRatchet>::None)
+        let handshake_sender_ratchets = std::iter::repeat_with(|| Mutex::new(None))
             .take(size.leaf_count() as usize)
             .collect();
 
-        let application_sender_ratchets = std::iter::repeat_with(|| Option::<SenderRatchet>::None)
+        let application_sender_ratchets = std::iter::repeat_with(|| Mutex::new(None))
             .take(size.leaf_count() as usize)
             .collect();
 
@@ -163,8 +248,8 @@ impl SecretTree {
 
         SecretTree {
             own_index,
-            leaf_nodes,
-            parent_nodes,
+            leaf_nodes: Mutex::new(leaf_nodes),
+            parent_nodes: Mutex::new(parent_nodes),
             handshake_sender_ratchets,
             application_sender_ratchets,
             size,
@@ -174,9 +259,11 @@ impl SecretTree {
     /// Get current generation for a specific SenderRatchet
     #[cfg(test)]
     pub(crate) fn generation(&self, index: LeafNodeIndex, secret_type: SecretType) -> u32 {
-        match self
-            .ratchet_opt(index, secret_type)
-            .expect("Index out of bounds.")
+        match lock(
+            self.sender_ratchet(index, secret_type)
+                .expect("Index out of bounds."),
+        )
+        .as_ref()
         {
             Some(sender_ratchet) => sender_ratchet.generation(),
             None => 0,
@@ -186,7 +273,7 @@ impl SecretTree {
     /// Initializes a specific SenderRatchet pair for a given index by
     /// calculating and deleting the appropriate values in the SecretTree
     fn initialize_sender_ratchets(
-        &mut self,
+        &self,
         ciphersuite: Ciphersuite,
         backend: &impl OpenMlsCryptoProvider,
         index: LeafNodeIndex,
@@ -196,22 +283,22 @@ impl SecretTree {
             log::error!("Index is larger than the tree size.");
             return Err(SecretTreeError::IndexOutOfBounds);
         }
+        // The node secrets stay locked until the sender ratchets are set, so
+        // that concurrent callers don't derive them twice.
+        let mut leaf_nodes = lock(&self.leaf_nodes);
+        let mut parent_nodes = lock(&self.parent_nodes);
+
         // Check if SenderRatchets are already initialized
-        if self
-            .ratchet_opt(index, SecretType::HandshakeSecret)
-            .expect("Index out of bounds.")
-            .is_some()
-            && self
-                .ratchet_opt(index, SecretType::ApplicationSecret)
-                .expect("Index out of bounds.")
-                .is_some()
-        {
+        let handshake_sender_ratchet = self.sender_ratchet(index, SecretType::HandshakeSecret)?;
+        let application_sender_ratchet =
+            self.sender_ratchet(index, SecretType::ApplicationSecret)?;
+        if lock(handshake_sender_ratchet).is_some() && lock(application_sender_ratchet).is_some() {
             log::trace!("The sender ratchets are initialized already.");
             return Ok(());
         }
 
         // If we don't have a secret in the leaf node, we derive it
-        if self.leaf_nodes[index.usize()].is_none() {
+        if leaf_nodes[index.usize()].is_none() {
             // Collect empty nodes in the direct path until a non-empty node is
             // found
             let mut empty_nodes: Vec<ParentNodeIndex> = vec![];
@@ -219,23 +306,29 @@ impl SecretTree {
             log::trace!("Direct path for node {index:?}: {:?}", direct_path);
             for parent_node in direct_path {
                 empty_nodes.push(parent_node);
-                if self.parent_nodes[parent_node.usize()].is_some() {
+                if parent_nodes[parent_node.usize()].is_some() {
                     break;
                 }
             }
 
+            // The secrets of a sealed tree were deleted, see `Self::seal()`
+            if !matches!(empty_nodes.last(), Some(node) if parent_nodes[node.usize()].is_some()) {
+                log::error!("The secrets to derive the sender ratchets were deleted.");
+                return Err(SecretTreeError::SecretReuseError);
+            }
+
             // Invert direct path
             empty_nodes.reverse();
 
             // Derive the secrets down all the way to the leaf node
             for n in empty_nodes {
                 log::trace!("Derive down for parent node {n:?}.");
-                self.derive_down(ciphersuite, backend, n)?;
+                Self::derive_down(ciphersuite, backend, &mut leaf_nodes, &mut parent_nodes, n)?;
             }
         }
 
         // Calculate node secret and initialize SenderRatchets
-        let node_secret = match &self.leaf_nodes[index.usize()] {
+        let node_secret = match &leaf_nodes[index.usize()] {
             Some(node) => &node.secret,
             // We just derived all necessary nodes so this should not happen
             None => {
@@ -259,7 +352,9 @@ impl SecretTree {
             "application ratchet secret {application_ratchet_secret:x?}"
         );
 
-        let (handshake_sender_ratchet, application_sender_ratchet) = if index == self.own_index {
+        let (new_handshake_sender_ratchet, new_application_sender_ratchet) = if index
+            == self.own_index
+        {
             let handshake_sender_ratchet = SenderRatchet::EncryptionRatchet(
                 RatchetSecret::initial_ratchet_secret(handshake_ratchet_secret),
             );
@@ -277,19 +372,25 @@ impl SecretTree {
 
             (handshake_sender_ratchet, application_sender_ratchet)
         };
-        self.handshake_sender_ratchets[index.usize()] = Some(handshake_sender_ratchet);
-        self.application_sender_ratchets[index.usize()] = Some(application_sender_ratchet);
+        *lock(handshake_sender_ratchet) = Some(new_handshake_sender_ratchet);
+        *lock(application_sender_ratchet) = Some(new_application_sender_ratchet);
 
         // Delete leaf node
-        self.leaf_nodes[index.usize()] = None;
+        leaf_nodes[index.usize()] = None;
         Ok(())
     }
 
     /// Return RatchetSecrets for a given index and generation. This should be
     /// called when decrypting an PrivateMessage received from another member.
     /// Returns an error if index or generation are out of bound.
+    ///
+    /// Only the sender ratchet of the given index is locked while the secret
+    /// is derived, so messages of different senders can be decrypted
+    /// concurrently. If the [`SenderRatchetConfiguration`] limits the total
+    /// number of skipped keys, all sender ratchets are locked briefly
+    /// afterwards to evict keys.
     pub(crate) fn secret_for_decryption(
-        &mut self,
+        &self,
         ciphersuite: Ciphersuite,
         backend: &impl OpenMlsCryptoProvider,
         index: LeafNodeIndex,
@@ -309,22 +410,135 @@ impl SecretTree {
             log::error!("Sender index is not in the tree.");
             return Err(SecretTreeError::IndexOutOfBounds);
         }
-        if self.ratchet_opt(index, secret_type)?.is_none() {
+        let sender_ratchet = self.sender_ratchet(index, secret_type)?;
+        if lock(sender_ratchet).is_none() {
             log::trace!("   initialize sender ratchets");
             self.initialize_sender_ratchets(ciphersuite, backend, index)?;
         }
-        match self.ratchet_mut(index, secret_type) {
-            SenderRatchet::EncryptionRatchet(_) => {
+        let ratchet_key_material = match lock(sender_ratchet).as_mut() {
+            None => return Err(SecretTreeError::LibraryError),
+            Some(SenderRatchet::EncryptionRatchet(_)) => {
                 log::error!("This is the wrong ratchet type.");
-                Err(SecretTreeError::RatchetTypeError)
+                return Err(SecretTreeError::RatchetTypeError);
             }
-            SenderRatchet::DecryptionRatchet(dec_ratchet) => {
+            Some(SenderRatchet::DecryptionRatchet(dec_ratchet)) => {
                 log::trace!("   getting secret for decryption");
-                dec_ratchet.secret_for_decryption(ciphersuite, backend, generation, configuration)
+                dec_ratchet.secret_for_decryption(
+                    ciphersuite,
+                    backend,
+                    generation,
+                    configuration,
+                )?
             }
+        };
+        self.evict_skipped_keys(configuration);
+        Ok(ratchet_key_material)
+    }
+
+    /// Evicts the keys of skipped messages until all decryption ratchets
+    /// together keep at most the `maximum_total_skipped_keys` of the
+    /// [`SenderRatchetConfiguration`]. The oldest keys of the ratchet that
+    /// keeps the most keys are evicted first.
+    fn evict_skipped_keys(&self, configuration: &SenderRatchetConfiguration) {
+        let maximum_total_skipped_keys = match configuration.maximum_total_skipped_keys() {
+            Some(maximum_total_skipped_keys) => maximum_total_skipped_keys as usize,
+            None => return,
+        };
+        // The ratchets are always locked in the same order, so that
+        // concurrent evictions can't deadlock.
+        let mut sender_ratchets: Vec<MutexGuard<'_, Option<SenderRatchet>>> = self
+            .handshake_sender_ratchets
+            .iter()
+            .chain(self.application_sender_ratchets.iter())
+            .map(lock)
+            .collect();
+        let mut decryption_ratchets: Vec<&mut DecryptionRatchet> = sender_ratchets
+            .iter_mut()
+            .filter_map(|sender_ratchet| match &mut **sender_ratchet {
+                Some(SenderRatchet::DecryptionRatchet(dec_ratchet)) => Some(dec_ratchet),
+                _ => None,
+            })
+            .collect();
+        let mut total_skipped_keys: usize = decryption_ratchets
+            .iter()
+            .map(|dec_ratchet| dec_ratchet.skipped_keys())
+            .sum();
+        while total_skipped_keys > maximum_total_skipped_keys {
+            let evicted = decryption_ratchets
+                .iter_mut()
+                .max_by_key(|dec_ratchet| dec_ratchet.skipped_keys())
+                .map(|dec_ratchet| dec_ratchet.evict_oldest_skipped_key())
+                .unwrap_or(false);
+            if !evicted {
+                break;
+            }
+            total_skipped_keys -= 1;
         }
     }
 
+    /// Drops the keys of skipped messages that the `configuration` no longer
+    /// permits to keep from all decryption ratchets, e.g. after the
+    /// `out_of_order_tolerance` was lowered.
+    pub(crate) fn prune_skipped_keys(&mut self, configuration: &SenderRatchetConfiguration) {
+        self.handshake_sender_ratchets
+            .iter_mut()
+            .chain(self.application_sender_ratchets.iter_mut())
+            .for_each(|sender_ratchet| {
+                if let Some(SenderRatchet::DecryptionRatchet(dec_ratchet)) = get_mut(sender_ratchet)
+                {
+                    dec_ratchet.prune_past_secrets(configuration);
+                }
+            });
+        self.evict_skipped_keys(configuration);
+    }
+
+    /// Returns a copy of the sender ratchets of the given index, e.g. to
+    /// store them without the rest of the tree, or `None` if they weren't
+    /// initialized yet.
+    pub(crate) fn sender_ratchets(&self, index: LeafNodeIndex) -> Option<SenderRatchets> {
+        let handshake = self
+            .sender_ratchet(index, SecretType::HandshakeSecret)
+            .ok()
+            .and_then(|sender_ratchet| lock(sender_ratchet).clone())?;
+        let application = self
+            .sender_ratchet(index, SecretType::ApplicationSecret)
+            .ok()
+            .and_then(|sender_ratchet| lock(sender_ratchet).clone())?;
+        Some(SenderRatchets {
+            handshake,
+            application,
+        })
+    }
+
+    /// Restores the sender ratchets of the given index from a copy that was
+    /// stored without the rest of the tree. Each current ratchet is kept if
+    /// it has a later generation than its copy, so that a stale copy can't
+    /// roll it back. Indices outside the tree are ignored.
+    pub(crate) fn restore_sender_ratchets(
+        &mut self,
+        index: LeafNodeIndex,
+        sender_ratchets: SenderRatchets,
+    ) {
+        if index.u32() >= self.size.leaf_count() {
+            return;
+        }
+        for (secret_type, stored) in [
+            (SecretType::HandshakeSecret, sender_ratchets.handshake),
+            (SecretType::ApplicationSecret, sender_ratchets.application),
+        ] {
+            if let Ok(current) = self.sender_ratchet_mut(index, secret_type).map(get_mut) {
+                if current
+                    .as_ref()
+                    .map_or(true, |current| current.generation() <= stored.generation())
+                {
+                    *current = Some(stored);
+                }
+            }
+        }
+        // The leaf secret is deleted when the sender ratchets are initialized
+        get_mut(&mut self.leaf_nodes)[index.usize()] = None;
+    }
+
     /// Return the next RatchetSecrets that should be used for encryption and
     /// then increments the generation.
     pub(crate) fn secret_for_encryption(
@@ -334,57 +548,81 @@ impl SecretTree {
         index: LeafNodeIndex,
         secret_type: SecretType,
     ) -> Result<(u32, RatchetKeyMaterial), SecretTreeError> {
-        if self.ratchet_opt(index, secret_type)?.is_none() {
+        if get_mut(self.sender_ratchet_mut(index, secret_type)?).is_none() {
             self.initialize_sender_ratchets(ciphersuite, backend, index)
                 .expect("Index out of bounds");
         }
-        match self.ratchet_mut(index, secret_type) {
-            SenderRatchet::DecryptionRatchet(_) => {
+        match get_mut(self.sender_ratchet_mut(index, secret_type)?) {
+            None => Err(SecretTreeError::LibraryError),
+            Some(SenderRatchet::DecryptionRatchet(_)) => {
                 log::error!("Invalid ratchet type. Got decryption, expected encryption.");
                 Err(SecretTreeError::RatchetTypeError)
             }
-            SenderRatchet::EncryptionRatchet(enc_ratchet) => {
+            Some(SenderRatchet::EncryptionRatchet(enc_ratchet)) => {
                 enc_ratchet.ratchet_forward(backend, ciphersuite)
             }
         }
     }
 
-    /// Returns a mutable reference to a specific SenderRatchet. The
-    /// SenderRatchet needs to be initialized.
-    fn ratchet_mut(&mut self, index: LeafNodeIndex, secret_type: SecretType) -> &mut SenderRatchet {
-        let sender_ratchets = match secret_type {
-            SecretType::HandshakeSecret => &mut self.handshake_sender_ratchets,
-            SecretType::ApplicationSecret => &mut self.application_sender_ratchets,
-        };
-        sender_ratchets
-            .get_mut(index.usize())
-            .unwrap_or_else(|| panic!("SenderRatchets not initialized: {}", index.usize()))
-            .as_mut()
-            .expect("SecretTree not initialized")
+    /// Deletes the secrets of all nodes of the tree, so that no new sender
+    /// ratchets can be derived. The sender ratchets that were already
+    /// derived can still be used.
+    pub(crate) fn seal(&mut self) {
+        get_mut(&mut self.leaf_nodes)
+            .iter_mut()
+            .for_each(|node| *node = None);
+        get_mut(&mut self.parent_nodes)
+            .iter_mut()
+            .for_each(|node| *node = None);
+    }
+
+    /// Deletes the secrets of all nodes and all sender ratchets of the tree,
+    /// so that no secrets can be derived from it anymore.
+    pub(crate) fn shred(&mut self) {
+        self.seal();
+        self.handshake_sender_ratchets
+            .iter_mut()
+            .chain(self.application_sender_ratchets.iter_mut())
+            .for_each(|sender_ratchet| *get_mut(sender_ratchet) = None);
     }
 
-    /// Returns an optional reference to a specific SenderRatchet
-    fn ratchet_opt(
+    /// Returns the lock of a specific SenderRatchet.
+    fn sender_ratchet(
         &self,
         index: LeafNodeIndex,
         secret_type: SecretType,
-    ) -> Result<Option<&SenderRatchet>, SecretTreeError> {
+    ) -> Result<&Mutex<Option<SenderRatchet>>, SecretTreeError> {
         let sender_ratchets = match secret_type {
             SecretType::HandshakeSecret => &self.handshake_sender_ratchets,
             SecretType::ApplicationSecret => &self.application_sender_ratchets,
         };
-        match sender_ratchets.get(index.usize()) {
-            Some(sender_ratchet_option) => Ok(sender_ratchet_option.as_ref()),
-            None => Err(SecretTreeError::IndexOutOfBounds),
-        }
+        sender_ratchets
+            .get(index.usize())
+            .ok_or(SecretTreeError::IndexOutOfBounds)
+    }
+
+    /// Returns the lock of a specific SenderRatchet for exclusive access.
+    fn sender_ratchet_mut(
+        &mut self,
+        index: LeafNodeIndex,
+        secret_type: SecretType,
+    ) -> Result<&mut Mutex<Option<SenderRatchet>>, SecretTreeError> {
+        let sender_ratchets = match secret_type {
+            SecretType::HandshakeSecret => &mut self.handshake_sender_ratchets,
+            SecretType::ApplicationSecret => &mut self.application_sender_ratchets,
+        };
+        sender_ratchets
+            .get_mut(index.usize())
+            .ok_or(SecretTreeError::IndexOutOfBounds)
     }
 
     /// Derives the secrets for the child nodes in a SecretTree and blanks the
     /// parent node.
     fn derive_down(
-        &mut self,
         ciphersuite: Ciphersuite,
         backend: &impl OpenMlsCryptoProvider,
+        leaf_nodes: &mut [Option<SecretTreeNode>],
+        parent_nodes: &mut [Option<SecretTreeNode>],
         index_in_tree: ParentNodeIndex,
     ) -> Result<(), SecretTreeError> {
         log::debug!(
@@ -393,7 +631,7 @@ impl SecretTree {
             ciphersuite
         );
         let hash_len = ciphersuite.hash_length();
-        let node_secret = match &self.parent_nodes[index_in_tree.usize()] {
+        let node_secret = match &parent_nodes[index_in_tree.usize()] {
             Some(node) => &node.secret,
             // This function only gets called top to bottom, so this should not happen
             None => {
@@ -424,10 +662,10 @@ impl SecretTree {
         });
         match left_index {
             TreeNodeIndex::Leaf(leaf_index) => {
-                self.leaf_nodes[leaf_index.usize()] = value;
+                leaf_nodes[leaf_index.usize()] = value;
             }
             TreeNodeIndex::Parent(parent_index) => {
-                self.parent_nodes[parent_index.usize()] = value;
+                parent_nodes[parent_index.usize()] = value;
             }
         }
 
@@ -437,15 +675,15 @@ impl SecretTree {
         });
         match right_index {
             TreeNodeIndex::Leaf(leaf_index) => {
-                self.leaf_nodes[leaf_index.usize()] = value;
+                leaf_nodes[leaf_index.usize()] = value;
             }
             TreeNodeIndex::Parent(parent_index) => {
-                self.parent_nodes[parent_index.usize()] = value;
+                parent_nodes[parent_index.usize()] = value;
             }
         }
 
         // Delete parent node
-        self.parent_nodes[index_in_tree.usize()] = None;
+        parent_nodes[index_in_tree.usize()] = None;
         Ok(())
     }
 }
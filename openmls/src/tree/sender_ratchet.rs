@@ -28,10 +28,32 @@ pub(crate) type Generation = u32;
 ///  - maximum_forward_distance:
 /// This parameter defines how many incoming messages can be skipped. This is useful if the DS
 /// drops application messages. The default value is 1000.
+///  - maximum_skipped_keys:
+/// This parameter defines how many keys of skipped messages are kept per
+/// sender. The default value is `None`, i.e. the keys are only bounded by the
+/// `out_of_order_tolerance`.
+///  - maximum_total_skipped_keys:
+/// This parameter defines how many keys of skipped messages are kept for all
+/// senders of an epoch together. The default value is `None`, i.e. no limit.
+///
+/// **Eviction policy**
+///
+/// The key of a skipped message is kept until the message is decrypted or
+/// until its generation falls out of the `out_of_order_tolerance` window.
+/// If a sender has more than `maximum_skipped_keys` skipped keys, the keys of
+/// its oldest generations are evicted first. If all senders together have more
+/// than `maximum_total_skipped_keys` skipped keys, the keys of the oldest
+/// generations of the sender with the most skipped keys are evicted first.
+/// Decrypting a message whose key was evicted fails with
+/// [`SecretTreeError::SkippedKeyEvicted`].
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SenderRatchetConfiguration {
     out_of_order_tolerance: Generation,
     maximum_forward_distance: Generation,
+    #[serde(default)]
+    maximum_skipped_keys: Option<u32>,
+    #[serde(default)]
+    maximum_total_skipped_keys: Option<u32>,
 }
 
 impl SenderRatchetConfiguration {
@@ -40,8 +62,24 @@ impl SenderRatchetConfiguration {
         Self {
             out_of_order_tolerance,
             maximum_forward_distance,
+            maximum_skipped_keys: None,
+            maximum_total_skipped_keys: None,
         }
     }
+
+    /// Set the maximum number of skipped keys that are kept per sender.
+    pub fn with_maximum_skipped_keys(mut self, maximum_skipped_keys: u32) -> Self {
+        self.maximum_skipped_keys = Some(maximum_skipped_keys);
+        self
+    }
+
+    /// Set the maximum number of skipped keys that are kept for all senders
+    /// of an epoch together.
+    pub fn with_maximum_total_skipped_keys(mut self, maximum_total_skipped_keys: u32) -> Self {
+        self.maximum_total_skipped_keys = Some(maximum_total_skipped_keys);
+        self
+    }
+
     /// Get a reference to the sender ratchet configuration's out of order tolerance.
     pub fn out_of_order_tolerance(&self) -> Generation {
         self.out_of_order_tolerance
@@ -51,6 +89,17 @@ impl SenderRatchetConfiguration {
     pub fn maximum_forward_distance(&self) -> Generation {
         self.maximum_forward_distance
     }
+
+    /// Get the maximum number of skipped keys that are kept per sender.
+    pub fn maximum_skipped_keys(&self) -> Option<u32> {
+        self.maximum_skipped_keys
+    }
+
+    /// Get the maximum number of skipped keys that are kept for all senders
+    /// of an epoch together.
+    pub fn maximum_total_skipped_keys(&self) -> Option<u32> {
+        self.maximum_total_skipped_keys
+    }
 }
 
 impl Default for SenderRatchetConfiguration {
@@ -69,8 +118,8 @@ pub(crate) type RatchetKeyMaterial = (AeadKey, AeadNonce);
 /// `out_of_order_tolerance` and a `maximum_forward_distance` (see
 /// [`SenderRatchetConfiguration`]) while an Encryption Ratchet never keeps past
 /// secrets around.
-#[derive(Serialize, Deserialize)]
-#[cfg_attr(any(feature = "test-utils", test), derive(PartialEq, Clone))]
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(any(feature = "test-utils", test), derive(PartialEq))]
 #[cfg_attr(any(feature = "crypto-debug", test), derive(Debug))]
 pub(crate) enum SenderRatchet {
     EncryptionRatchet(RatchetSecret),
@@ -91,8 +140,8 @@ impl SenderRatchet {
 /// the ratchet chain, as well as its current [`Generation`]. It can be
 /// initialized with a given secret and then ratcheted forward, outputting
 /// [`RatchetKeyMaterial`] and increasing its [`Generation`] each time.
-#[derive(Debug, Serialize, Deserialize, Default)]
-#[cfg_attr(any(feature = "test-utils", test), derive(PartialEq, Clone))]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(any(feature = "test-utils", test), derive(PartialEq))]
 pub(crate) struct RatchetSecret {
     secret: Secret,
     generation: Generation,
@@ -166,12 +215,15 @@ impl RatchetSecret {
 /// [`RatchetKeyMaterial`] of epochs around until they are retrieved. This
 /// behaviour can be configured via the `out_of_order_tolerance` and
 /// `maximum_forward_distance` of the given [`SenderRatchetConfiguration`].
-#[derive(Serialize, Deserialize)]
-#[cfg_attr(any(feature = "test-utils", test), derive(PartialEq, Clone))]
+#[derive(Clone, Serialize, Deserialize)]
+#[cfg_attr(any(feature = "test-utils", test), derive(PartialEq))]
 #[cfg_attr(any(feature = "crypto-debug", test), derive(Debug))]
 pub struct DecryptionRatchet {
     past_secrets: VecDeque<Option<RatchetKeyMaterial>>,
     ratchet_head: RatchetSecret,
+    // Generations in the window of past secrets whose keys were evicted.
+    #[serde(default)]
+    evicted_generations: Vec<Generation>,
 }
 
 impl DecryptionRatchet {
@@ -180,14 +232,50 @@ impl DecryptionRatchet {
         Self {
             past_secrets: VecDeque::new(),
             ratchet_head: RatchetSecret::initial_ratchet_secret(secret),
+            evicted_generations: Vec::new(),
         }
     }
 
     /// Remove elements from the `past_secrets` queue until it is within the
     /// bounds determined by the [`SenderRatchetConfiguration`].
-    fn prune_past_secrets(&mut self, configuration: &SenderRatchetConfiguration) {
+    pub(crate) fn prune_past_secrets(&mut self, configuration: &SenderRatchetConfiguration) {
+        self.past_secrets
+            .truncate(configuration.out_of_order_tolerance() as usize);
+        if let Some(maximum_skipped_keys) = configuration.maximum_skipped_keys() {
+            let mut skipped_keys = self.skipped_keys();
+            while skipped_keys > maximum_skipped_keys as usize && self.evict_oldest_skipped_key() {
+                skipped_keys -= 1;
+            }
+        }
+        // Forget evicted generations that are out of the window anyway.
+        let generation = self.generation();
+        let window = self.past_secrets.len() as Generation;
+        self.evicted_generations
+            .retain(|evicted_generation| generation - evicted_generation <= window);
+    }
+
+    /// Returns the number of keys of skipped messages stored in this ratchet.
+    pub(crate) fn skipped_keys(&self) -> usize {
         self.past_secrets
-            .truncate(configuration.out_of_order_tolerance() as usize)
+            .iter()
+            .filter(|past_secret| past_secret.is_some())
+            .count()
+    }
+
+    /// Evicts the key of the oldest skipped message stored in this ratchet.
+    ///
+    /// Returns `false` if no key is stored.
+    pub(crate) fn evict_oldest_skipped_key(&mut self) -> bool {
+        match self.past_secrets.iter().rposition(Option::is_some) {
+            Some(index) => {
+                self.past_secrets[index] = None;
+                // The entry at index `i` belongs to generation `head - 1 - i`.
+                let generation = self.generation() - 1 - index as Generation;
+                self.evicted_generations.push(generation);
+                true
+            }
+            None => false,
+        }
     }
 
     /// Get the generation of the ratchet head.
@@ -265,9 +353,15 @@ impl DecryptionRatchet {
                 // with `None`, thus achieving FS for that secret as soon as the
                 // caller of this function drops it.
                 .take()
-                // If the requested generation was used to decrypt a message
-                // earlier, throw an error.
-                .ok_or(SecretTreeError::SecretReuseError)
+                // If the key of the requested generation was evicted or
+                // used to decrypt a message earlier, throw an error.
+                .ok_or_else(|| {
+                    if self.evicted_generations.contains(&generation) {
+                        SecretTreeError::SkippedKeyEvicted { generation }
+                    } else {
+                        SecretTreeError::SecretReuseError
+                    }
+                })
         }
     }
 }
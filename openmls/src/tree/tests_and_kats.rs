@@ -3,5 +3,5 @@
 #[cfg(test)]
 mod unit_tests;
 
-#[cfg(any(feature = "test-utils", test))]
+#[cfg(any(feature = "test-vectors", test))]
 pub mod kats;
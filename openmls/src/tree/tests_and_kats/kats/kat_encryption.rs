@@ -252,7 +252,7 @@ fn build_handshake_messages(
         group.ciphersuite(),
         backend,
         group.message_secrets_test_mut(),
-        0,
+        PaddingPolicy::None,
     )
     .expect("Could not create PrivateMessage");
     (
@@ -303,7 +303,7 @@ fn build_application_messages(
         group.ciphersuite(),
         backend,
         group.message_secrets_test_mut(),
-        0,
+        PaddingPolicy::None,
     ) {
         Ok(c) => c,
         Err(e) => panic!("Could not create PrivateMessage {e}"),
@@ -620,7 +620,7 @@ pub fn run_test_vector(
 
             // We have to take the fresh_secret_tree here because the secret_for_decryption
             // above ratcheted the tree forward.
-            let mut message_secrets = MessageSecrets::new(
+            let message_secrets = MessageSecrets::new(
                 sender_data_secret.clone(),
                 MembershipKey::random(ciphersuite, backend), // we don't care about this value
                 ConfirmationKey::random(ciphersuite, backend), // we don't care about this value
@@ -636,7 +636,7 @@ pub fn run_test_vector(
                 .to_verifiable_content(
                     ciphersuite,
                     backend,
-                    &mut message_secrets,
+                    &message_secrets,
                     leaf_index,
                     &SenderRatchetConfiguration::default(),
                     sender_data,
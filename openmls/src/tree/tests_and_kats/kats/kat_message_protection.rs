@@ -222,7 +222,11 @@ pub fn run_test_vector(
             private.append(&mut hex_to_bytes(&test.signature_pub));
             private
         }
-        Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256 => hex_to_bytes(&test.signature_priv),
+        Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256
+        | Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521
+        | Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384 => {
+            hex_to_bytes(&test.signature_priv)
+        }
         _ => unimplemented!(),
     };
     let random_own_signature_key =
@@ -260,7 +264,9 @@ pub fn run_test_vector(
                 private.append(&mut hex_to_bytes(&test.signature_pub));
                 private
             }
-            Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256 => {
+            Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256
+            | Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521
+            | Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384 => {
                 hex_to_bytes(&test.signature_priv)
             }
             _ => unimplemented!(),
@@ -433,6 +439,7 @@ pub fn run_test_vector(
                     &sender_ratchet_config,
                     &proposal_store,
                     &[],
+                    None,
                 )
                 .unwrap();
 
@@ -460,7 +467,7 @@ pub fn run_test_vector(
         )
         .unwrap();
         let my_proposal_priv = sender_group
-            .encrypt(proposal_authenticated_content, 0, backend)
+            .encrypt(proposal_authenticated_content, PaddingPolicy::None, backend)
             .unwrap();
         let my_proposal_priv_out =
             MlsMessageOut::from_private_message(my_proposal_priv, group.version());
@@ -611,7 +618,7 @@ pub fn run_test_vector(
             mac_value: vec![0; 32].into(), // Set a fake mac, we don't check it.
         }));
         let my_commit_pub = sender_group
-            .encrypt(commit_authenticated_content, 0, backend)
+            .encrypt(commit_authenticated_content, PaddingPolicy::None, backend)
             .unwrap();
         let my_commit_priv_out =
             MlsMessageOut::from_private_message(my_commit_pub, group.version());
@@ -681,6 +688,7 @@ pub fn run_test_vector(
                     &sender_ratchet_config,
                     &proposal_store,
                     &[],
+                    None,
                 )
                 .unwrap();
             match processed_message.into_content() {
@@ -701,7 +709,7 @@ pub fn run_test_vector(
         // Wrap `application` into a `PrivateMessage`.
         let mut sender_group = setup_group(backend, ciphersuite, &test, true);
         let private_message = sender_group
-            .create_application_message(&[], &application, 0, backend, &signer)
+            .create_application_message(&[], &application, PaddingPolicy::None, backend, &signer)
             .unwrap();
         let my_application_priv_out =
             MlsMessageOut::from_private_message(private_message, sender_group.version());
@@ -260,3 +260,41 @@ fn secret_tree(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
         application_secret_nonce.as_slice()
     );
 }
+
+// Test the eviction of skipped keys across all senders
+#[apply(ciphersuites_and_backends)]
+fn maximum_total_skipped_keys(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let configuration =
+        &SenderRatchetConfiguration::new(10, 1000).with_maximum_total_skipped_keys(4);
+    let encryption_secret = EncryptionSecret::random(ciphersuite, backend);
+    let mut secret_tree = SecretTree::new(
+        encryption_secret,
+        TreeSize::from_leaf_count(3u32),
+        LeafNodeIndex::new(2u32),
+    );
+    let secret_type = SecretType::ApplicationSecret;
+    let mut secret_for_decryption = |leaf_index: u32, generation: u32| {
+        secret_tree.secret_for_decryption(
+            ciphersuite,
+            backend,
+            LeafNodeIndex::new(leaf_index),
+            secret_type,
+            generation,
+            configuration,
+        )
+    };
+
+    // Leaf 0 skips three messages and leaf 1 skips two. Since only four keys
+    // are kept, the oldest key of leaf 0 is evicted.
+    assert!(secret_for_decryption(0, 3).is_ok());
+    assert!(secret_for_decryption(1, 2).is_ok());
+
+    assert_eq!(
+        secret_for_decryption(0, 0).expect_err("Expected error."),
+        SecretTreeError::SkippedKeyEvicted { generation: 0 }
+    );
+    assert!(secret_for_decryption(0, 1).is_ok());
+    assert!(secret_for_decryption(0, 2).is_ok());
+    assert!(secret_for_decryption(1, 0).is_ok());
+    assert!(secret_for_decryption(1, 1).is_ok());
+}
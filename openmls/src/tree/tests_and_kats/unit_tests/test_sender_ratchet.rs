@@ -128,6 +128,41 @@ fn test_forward_secrecy(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoPr
     }
 }
 
+// Test the eviction of skipped keys
+#[apply(ciphersuites_and_backends)]
+fn test_maximum_skipped_keys(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let configuration = &SenderRatchetConfiguration::new(10, 1000).with_maximum_skipped_keys(3);
+    let secret = Secret::random(ciphersuite, backend, ProtocolVersion::Mls10)
+        .expect("Not enough randomness.");
+    let mut ratchet = DecryptionRatchet::new(secret);
+
+    // Skip generations 0 to 4, only the keys of 2 to 4 are kept.
+    let _secret = ratchet
+        .secret_for_decryption(ciphersuite, backend, 5, configuration)
+        .expect("Expected decryption secret.");
+    assert_eq!(ratchet.skipped_keys(), 3);
+
+    // The keys of the oldest generations were evicted.
+    for generation in 0..2 {
+        let err = ratchet
+            .secret_for_decryption(ciphersuite, backend, generation, configuration)
+            .expect_err("Expected error.");
+        assert_eq!(err, SecretTreeError::SkippedKeyEvicted { generation });
+    }
+
+    // The other keys are still available, but only once.
+    for generation in 2..5 {
+        let _secret = ratchet
+            .secret_for_decryption(ciphersuite, backend, generation, configuration)
+            .expect("Expected decryption secret.");
+        let err = ratchet
+            .secret_for_decryption(ciphersuite, backend, generation, configuration)
+            .expect_err("Expected error.");
+        assert_eq!(err, SecretTreeError::SecretReuseError);
+    }
+    assert_eq!(ratchet.skipped_keys(), 0);
+}
+
 // Test if a sender ratchet overflow is caught
 #[test]
 fn sender_ratchet_generation_overflow() {
@@ -20,16 +20,18 @@
 use std::collections::HashSet;
 
 use log::debug;
-use openmls_traits::{signatures::Signer, types::Ciphersuite, OpenMlsCryptoProvider};
+use openmls_traits::{signatures::AsyncSigner, types::Ciphersuite, OpenMlsCryptoProvider};
 use serde::{Deserialize, Serialize};
 
 use super::{
     errors::*,
+    hashes::TreeHashCache,
     node::{
         encryption_keys::{EncryptionKey, EncryptionKeyPair, EncryptionPrivateKey},
         parent_node::{ParentNode, PathDerivationResult, PlainUpdatePathNode},
         Node, NodeReference,
     },
+    placement::LeafPlacement,
     treekem::UpdatePath,
     treesync_node::{TreeSyncLeafNode, TreeSyncParentNode},
     LeafNode, TreeSync, TreeSyncParentHashError,
@@ -37,7 +39,7 @@ use super::{
 use crate::{
     binary_tree::{
         array_representation::{
-            LeafNodeIndex, ParentNodeIndex, TreeNodeIndex, TreeSize, MIN_TREE_SIZE,
+            subtree, LeafNodeIndex, ParentNodeIndex, TreeNodeIndex, TreeSize, MIN_TREE_SIZE,
         },
         MlsBinaryTreeDiff, StagedMlsBinaryTreeDiff,
     },
@@ -57,20 +59,35 @@ pub(crate) type UpdatePathResult = (
 
 /// The [`StagedTreeSyncDiff`] can be created from a [`TreeSyncDiff`], examined
 /// and later merged into a [`TreeSync`] instance.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
 pub(crate) struct StagedTreeSyncDiff {
     diff: StagedMlsBinaryTreeDiff<TreeSyncLeafNode, TreeSyncParentNode>,
     new_tree_hash: Vec<u8>,
+    // The tree hashes of the subtrees changed by the diff. `None` for diffs
+    // that were staged before the tree hashes were cached.
+    #[serde(default)]
+    new_tree_hashes: Option<Vec<(TreeNodeIndex, Vec<u8>)>>,
 }
 
 impl StagedTreeSyncDiff {
+    #[allow(clippy::type_complexity)]
     pub(super) fn into_parts(
         self,
     ) -> (
         StagedMlsBinaryTreeDiff<TreeSyncLeafNode, TreeSyncParentNode>,
         Vec<u8>,
+        Option<Vec<(TreeNodeIndex, Vec<u8>)>>,
     ) {
-        (self.diff, self.new_tree_hash)
+        (self.diff, self.new_tree_hash, self.new_tree_hashes)
+    }
+
+    /// Returns an iterator over the leaf nodes that the diff adds or replaces.
+    /// Leaves that are blanked by the diff are skipped.
+    pub(crate) fn changed_leaf_nodes(&self) -> impl Iterator<Item = (LeafNodeIndex, &LeafNode)> {
+        self.diff
+            .changed_leaves()
+            .filter_map(|(index, leaf)| leaf.node().as_ref().map(|leaf_node| (index, leaf_node)))
     }
 }
 
@@ -82,12 +99,16 @@ impl StagedTreeSyncDiff {
 /// invariants. See [`TreeSync`] for the list of invariants.
 pub(crate) struct TreeSyncDiff<'a> {
     diff: MlsBinaryTreeDiff<'a, TreeSyncLeafNode, TreeSyncParentNode>,
+    tree_hashes: &'a TreeHashCache,
+    new_tree_hashes: Vec<(TreeNodeIndex, Vec<u8>)>,
 }
 
 impl<'a> From<&'a TreeSync> for TreeSyncDiff<'a> {
     fn from(tree_sync: &'a TreeSync) -> Self {
         TreeSyncDiff {
             diff: tree_sync.tree.empty_diff(),
+            tree_hashes: &tree_sync.tree_hashes,
+            new_tree_hashes: Vec::new(),
         }
     }
 }
@@ -211,6 +232,23 @@ impl<'a> TreeSyncDiff<'a> {
         LeafNodeIndex::new(leaf_count)
     }
 
+    /// Find and return the index of either the right-most blank leaf, or, if
+    /// there are no blank leaves, the leaf count.
+    ///
+    /// This keeps service members at the right edge of the tree, so that the
+    /// blank leaves on the left are left to other members.
+    pub(crate) fn free_service_leaf_index(&self) -> LeafNodeIndex {
+        let leaf_count = self.diff.leaves().count() as u32;
+
+        self.diff
+            .leaves()
+            .filter(|(_, leaf_id)| leaf_id.node().is_none())
+            .last()
+            .map(|(leaf_index, _)| leaf_index)
+            // Return the next free virtual blank leaf
+            .unwrap_or_else(|| LeafNodeIndex::new(leaf_count))
+    }
+
     /// Adds a new leaf to the tree either by filling a blank leaf or by
     /// extending the tree to the right to create a new leaf, inserting
     /// intermediate blanks as necessary. This also adds the leaf_index of the
@@ -224,6 +262,57 @@ impl<'a> TreeSyncDiff<'a> {
     ) -> Result<LeafNodeIndex, TreeSyncAddLeaf> {
         // Find a free leaf and fill it with the new key package.
         let leaf_index = self.free_leaf_index();
+        self.fill_leaf(leaf_node, leaf_index)
+    }
+
+    /// Adds the leaf of a service member to the tree like
+    /// [`Self::add_leaf()`], but fills the right-most blank leaf instead of
+    /// the left-most one. See [`Self::free_service_leaf_index()`].
+    ///
+    /// Returns the LeafNodeIndex of the new leaf.
+    pub(crate) fn add_service_leaf(
+        &mut self,
+        leaf_node: LeafNode,
+    ) -> Result<LeafNodeIndex, TreeSyncAddLeaf> {
+        let leaf_index = self.free_service_leaf_index();
+        self.fill_leaf(leaf_node, leaf_index)
+    }
+
+    /// Adds a new leaf to the tree like [`Self::add_leaf()`], but fills the
+    /// leaf chosen by the given [`LeafPlacement`]. If the placement chooses a
+    /// leaf that isn't free, the left-most blank leaf is filled instead.
+    ///
+    /// Returns the LeafNodeIndex of the new leaf.
+    pub(crate) fn add_leaf_with_placement(
+        &mut self,
+        leaf_node: LeafNode,
+        placement: &dyn LeafPlacement,
+    ) -> Result<LeafNodeIndex, TreeSyncAddLeaf> {
+        let leaves: Vec<Option<&LeafNode>> = self
+            .diff
+            .leaves()
+            .map(|(_, leaf_id)| leaf_id.node().as_ref())
+            .collect();
+        let leaf_index = placement.leaf_index(&leaves, &leaf_node);
+        let is_free = leaves
+            .get(leaf_index.usize())
+            .map_or(leaf_index.usize() == leaves.len(), |leaf| leaf.is_none());
+        let leaf_index = if is_free {
+            leaf_index
+        } else {
+            log::warn!("Leaf placement chose leaf {leaf_index:?}, which is not free.");
+            self.free_leaf_index()
+        };
+        self.fill_leaf(leaf_node, leaf_index)
+    }
+
+    /// Puts the new leaf at the given free `leaf_index` and adds it to the
+    /// `unmerged_leaves` of the parent nodes in its direct path.
+    fn fill_leaf(
+        &mut self,
+        leaf_node: LeafNode,
+        leaf_index: LeafNodeIndex,
+    ) -> Result<LeafNodeIndex, TreeSyncAddLeaf> {
         // If the free leaf index is within the tree, put the new leaf there,
         // otherwise extend the tree first.
         while leaf_index.u32() >= self.diff.size().leaf_count() {
@@ -280,17 +369,17 @@ impl<'a> TreeSyncDiff<'a> {
     }
 
     /// Given a new [`LeafNode`], use it to create a new path starting from
-    /// `leaf_index` and apply it to this diff. The given [`Signer`] reference
+    /// `leaf_index` and apply it to this diff. The given [`AsyncSigner`] reference
     /// is used to sign the target [`LeafNode`] after updating its parent hash.
     ///
     /// Returns the [`CommitSecret`] and the path resulting from the path
     /// derivation, as well as the newly derived [`EncryptionKeyPair`]s.
     ///
     /// Returns an error if the target leaf is not in the tree.
-    pub(crate) fn apply_own_update_path(
+    pub(crate) async fn apply_own_update_path(
         &mut self,
         backend: &impl OpenMlsCryptoProvider,
-        signer: &impl Signer,
+        signer: &impl AsyncSigner,
         ciphersuite: Ciphersuite,
         group_id: GroupId,
         leaf_index: LeafNodeIndex,
@@ -307,7 +396,8 @@ impl<'a> TreeSyncDiff<'a> {
 
         self.leaf_mut(leaf_index)
             .ok_or_else(|| LibraryError::custom("Didn't find own leaf in diff."))?
-            .update_parent_hash(&parent_hash, group_id, leaf_index, signer)?;
+            .update_parent_hash(&parent_hash, group_id, leaf_index, signer)
+            .await?;
 
         Ok((update_path_nodes, keypairs, commit_secret))
     }
@@ -677,12 +767,12 @@ impl<'a> TreeSyncDiff<'a> {
         Ok(StagedTreeSyncDiff {
             diff: self.diff.into(),
             new_tree_hash,
+            new_tree_hashes: Some(self.new_tree_hashes),
         })
     }
 
-    /// Helper function to compute and set the tree hash of the given node and
-    /// all nodes below it in the tree. The leaf nodes in `exclusion_list` are
-    /// not included in the tree hash.
+    /// Helper function to compute the tree hash of the given node. The leaf
+    /// nodes in `exclusion_list` are not included in the tree hash.
     pub(super) fn compute_tree_hash(
         &self,
         backend: &impl OpenMlsCryptoProvider,
@@ -690,7 +780,37 @@ impl<'a> TreeSyncDiff<'a> {
         node_index: TreeNodeIndex,
         exclusion_list: &HashSet<&LeafNodeIndex>,
     ) -> Result<Vec<u8>, LibraryError> {
-        match node_index {
+        self.compute_subtree_hash(
+            backend,
+            ciphersuite,
+            node_index,
+            exclusion_list,
+            &mut Vec::new(),
+        )
+    }
+
+    /// Compute the tree hash of the given node. Hashes of unchanged subtrees
+    /// are taken from the tree hash cache of the original tree if they don't
+    /// contain any of the leaf nodes in `exclusion_list`. The hashes that are
+    /// computed without exclusions are added to `new_tree_hashes`.
+    fn compute_subtree_hash(
+        &self,
+        backend: &impl OpenMlsCryptoProvider,
+        ciphersuite: Ciphersuite,
+        node_index: TreeNodeIndex,
+        exclusion_list: &HashSet<&LeafNodeIndex>,
+        new_tree_hashes: &mut Vec<(TreeNodeIndex, Vec<u8>)>,
+    ) -> Result<Vec<u8>, LibraryError> {
+        if self.diff.is_subtree_unchanged(node_index) {
+            let (leaves, _) = subtree(node_index);
+            if !exclusion_list.iter().any(|leaf| leaves.contains(*leaf)) {
+                if let Some(hash) = self.tree_hashes.get(node_index) {
+                    return Ok(hash.to_vec());
+                }
+            }
+        }
+
+        let hash = match node_index {
             TreeNodeIndex::Leaf(leaf_index) => {
                 let leaf = self.diff.leaf(leaf_index);
 
@@ -703,18 +823,33 @@ impl<'a> TreeSyncDiff<'a> {
             TreeNodeIndex::Parent(parent_index) => {
                 // Compute left hash.
                 let left_child = self.diff.left_child(parent_index);
-                let left_hash =
-                    self.compute_tree_hash(backend, ciphersuite, left_child, exclusion_list)?;
+                let left_hash = self.compute_subtree_hash(
+                    backend,
+                    ciphersuite,
+                    left_child,
+                    exclusion_list,
+                    new_tree_hashes,
+                )?;
                 // Compute right hash.
                 let right_child = self.diff.right_child(parent_index);
-                let right_hash =
-                    self.compute_tree_hash(backend, ciphersuite, right_child, exclusion_list)?;
+                let right_hash = self.compute_subtree_hash(
+                    backend,
+                    ciphersuite,
+                    right_child,
+                    exclusion_list,
+                    new_tree_hashes,
+                )?;
 
                 let node = self.diff.parent(parent_index);
 
                 node.compute_tree_hash(backend, ciphersuite, left_hash, right_hash, exclusion_list)
             }
+        }?;
+
+        if exclusion_list.is_empty() {
+            new_tree_hashes.push((node_index, hash.clone()));
         }
+        Ok(hash)
     }
 
     /// Return a reference to the leaf with the given index.
@@ -727,13 +862,24 @@ impl<'a> TreeSyncDiff<'a> {
         self.diff.leaf_mut(index).node_mut().as_mut()
     }
 
-    /// Compute and set the tree hash of all nodes in the tree.
+    /// Compute the tree hash of the root of the tree. Only the hashes of the
+    /// subtrees changed by the diff are computed. They are kept, so that they
+    /// can be added to the tree hash cache when the diff is merged.
     pub(crate) fn compute_tree_hashes(
         &mut self,
         backend: &impl OpenMlsCryptoProvider,
         ciphersuite: Ciphersuite,
     ) -> Result<Vec<u8>, LibraryError> {
-        self.compute_tree_hash(backend, ciphersuite, self.diff.root(), &HashSet::new())
+        let mut new_tree_hashes = Vec::new();
+        let tree_hash = self.compute_subtree_hash(
+            backend,
+            ciphersuite,
+            self.diff.root(),
+            &HashSet::new(),
+            &mut new_tree_hashes,
+        )?;
+        self.new_tree_hashes = new_tree_hashes;
+        Ok(tree_hash)
     }
 
     /// Returns the position of the subtree root shared by both given indices in
@@ -4,7 +4,8 @@ use openmls_traits::{crypto::OpenMlsCrypto, types::Ciphersuite, OpenMlsCryptoPro
 use tls_codec::{Serialize, TlsSerialize, TlsSize, VLByteSlice};
 
 use crate::{
-    binary_tree::array_representation::LeafNodeIndex, ciphersuite::HpkePublicKey,
+    binary_tree::array_representation::{LeafNodeIndex, TreeNodeIndex, TreeSize},
+    ciphersuite::HpkePublicKey,
     error::LibraryError,
 };
 
@@ -168,3 +169,58 @@ struct ParentNodeHashInput<'a> {
     left_hash: VLByteSlice<'a>,
     right_hash: VLByteSlice<'a>,
 }
+
+/// The tree hashes of the nodes of a [`TreeSync`](super::TreeSync), i.e. the
+/// hashes of the subtrees below the nodes.
+///
+/// The cache is filled with the hashes that are computed when a diff is
+/// staged, and updated with the hashes of the changed subtrees whenever a diff
+/// is merged. Diffs only recompute the hashes of the subtrees they change and
+/// take all other hashes from the cache. The cache is not persisted, it is
+/// filled again by the first diff that is merged after loading the tree.
+#[derive(Debug, Clone, Default)]
+pub(super) struct TreeHashCache {
+    leaves: Vec<Option<Vec<u8>>>,
+    parents: Vec<Option<Vec<u8>>>,
+}
+
+impl TreeHashCache {
+    /// Returns the cached tree hash of the node at `node_index`, if any.
+    pub(super) fn get(&self, node_index: TreeNodeIndex) -> Option<&[u8]> {
+        match node_index {
+            TreeNodeIndex::Leaf(leaf_index) => self.leaves.get(leaf_index.usize()),
+            TreeNodeIndex::Parent(parent_index) => self.parents.get(parent_index.usize()),
+        }
+        .and_then(|hash| hash.as_deref())
+    }
+
+    /// Resize the cache to the `tree_size` of a merged diff and replace the
+    /// hashes of the subtrees that the diff changed with `new_tree_hashes`.
+    pub(super) fn update(
+        &mut self,
+        tree_size: TreeSize,
+        new_tree_hashes: Vec<(TreeNodeIndex, Vec<u8>)>,
+    ) {
+        self.leaves.resize(tree_size.leaf_count() as usize, None);
+        self.parents.resize(tree_size.parent_count() as usize, None);
+        for (node_index, hash) in new_tree_hashes {
+            let entry = match node_index {
+                TreeNodeIndex::Leaf(leaf_index) => self.leaves.get_mut(leaf_index.usize()),
+                TreeNodeIndex::Parent(parent_index) => self.parents.get_mut(parent_index.usize()),
+            };
+            // Hashes of nodes outside of the tree are dropped.
+            if let Some(entry) = entry {
+                *entry = Some(hash);
+            }
+        }
+    }
+}
+
+// The cache is derived from the nodes of the tree and doesn't take part in
+// comparisons of trees.
+#[cfg(test)]
+impl PartialEq for TreeHashCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
@@ -39,6 +39,7 @@ use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
 
 use self::{
     diff::{StagedTreeSyncDiff, TreeSyncDiff},
+    hashes::TreeHashCache,
     node::{
         leaf_node::{
             Capabilities, LeafNodeSource, NewLeafNodeParams, TreeInfoTbs, TreePosition,
@@ -60,12 +61,12 @@ use crate::{
         array_representation::{is_node_in_tree, tree::TreeNode, LeafNodeIndex, TreeSize},
         MlsBinaryTree, MlsBinaryTreeError,
     },
-    ciphersuite::{signable::Verifiable, Secret},
+    ciphersuite::{signable::SignatureBatch, Secret},
     credentials::CredentialWithKey,
     error::LibraryError,
     extensions::Extensions,
     framing::SenderError,
-    group::{config::CryptoConfig, GroupId, Member},
+    group::{config::CryptoConfig, GroupEpoch, GroupId, Member},
     key_packages::Lifetime,
     messages::{PathSecret, PathSecretError},
     schedule::CommitSecret,
@@ -78,6 +79,7 @@ use errors::*;
 // Crate
 pub(crate) mod diff;
 pub(crate) mod node;
+pub(crate) mod placement;
 pub(crate) mod treekem;
 pub(crate) mod treesync_node;
 
@@ -91,9 +93,10 @@ pub use node::encryption_keys::EncryptionKey;
 
 // Public re-exports
 pub use node::{leaf_node::LeafNode, parent_node::ParentNode, Node};
+pub use placement::{LeafPlacement, LeafPlacementStrategy};
 
 // Tests
-#[cfg(any(feature = "test-utils", test))]
+#[cfg(any(feature = "test-vectors", test))]
 pub mod tests_and_kats;
 
 /// An exported ratchet tree as used in, e.g., [`GroupInfo`](crate::messages::group_info::GroupInfo).
@@ -160,7 +163,9 @@ impl RatchetTree {
             Some(Some(_)) => {
                 // The ratchet tree is not empty, i.e., has a last node, and the last node is not blank.
 
-                // Verify the nodes.
+                // Verify the nodes. The signatures of all leaf nodes are
+                // verified together in a single batch.
+                let mut signatures = SignatureBatch::new();
                 let mut verified_nodes = Vec::new();
                 for (index, node) in nodes.into_iter().enumerate() {
                     let verified_node = match (index % 2, node) {
@@ -177,21 +182,18 @@ impl RatchetTree {
                                 .into_signature_public_key_enriched(
                                     ciphersuite.signature_algorithm(),
                                 );
+                            let error = RatchetTreeError::InvalidNodeSignature;
                             Some(Node::LeafNode(match verifiable_leaf_node {
-                                VerifiableLeafNode::KeyPackage(leaf_node) => leaf_node
-                                    .verify(crypto, &signature_key)
-                                    .map_err(|_| RatchetTreeError::InvalidNodeSignature)?,
+                                VerifiableLeafNode::KeyPackage(leaf_node) => {
+                                    signatures.add(leaf_node, &signature_key, error)?
+                                }
                                 VerifiableLeafNode::Update(mut leaf_node) => {
                                     leaf_node.add_tree_position(tree_position);
-                                    leaf_node
-                                        .verify(crypto, &signature_key)
-                                        .map_err(|_| RatchetTreeError::InvalidNodeSignature)?
+                                    signatures.add(leaf_node, &signature_key, error)?
                                 }
                                 VerifiableLeafNode::Commit(mut leaf_node) => {
                                     leaf_node.add_tree_position(tree_position);
-                                    leaf_node
-                                        .verify(crypto, &signature_key)
-                                        .map_err(|_| RatchetTreeError::InvalidNodeSignature)?
+                                    signatures.add(leaf_node, &signature_key, error)?
                                 }
                             }))
                         }
@@ -208,6 +210,7 @@ impl RatchetTree {
                     };
                     verified_nodes.push(verified_node);
                 }
+                signatures.verify(crypto)?;
                 Ok(Self::trimmed(verified_nodes))
             }
         }
@@ -254,6 +257,71 @@ impl From<RatchetTree> for RatchetTreeIn {
     }
 }
 
+/// Fetches the ratchet tree of a group when it is needed for joining the
+/// group, e.g. from the delivery service.
+///
+/// The fetcher is only called if the group info used for joining doesn't
+/// contain a ratchet tree extension, so the tree doesn't have to be fetched
+/// in advance for every join attempt. It is implemented for closures with
+/// the signature of [`RatchetTreeFetcher::fetch_ratchet_tree()`].
+pub trait RatchetTreeFetcher {
+    /// Returns the ratchet tree of the group with the given `group_id` in the
+    /// given `epoch`, or `None` if it isn't available.
+    fn fetch_ratchet_tree(&self, group_id: &GroupId, epoch: GroupEpoch) -> Option<RatchetTreeIn>;
+}
+
+impl<F: Fn(&GroupId, GroupEpoch) -> Option<RatchetTreeIn>> RatchetTreeFetcher for F {
+    fn fetch_ratchet_tree(&self, group_id: &GroupId, epoch: GroupEpoch) -> Option<RatchetTreeIn> {
+        self(group_id, epoch)
+    }
+}
+
+/// The source of the ratchet tree when joining a group. The ratchet tree
+/// extension of the group info always takes precedence.
+pub(crate) enum RatchetTreeSource<'a> {
+    /// Only the ratchet tree extension is used.
+    Extension,
+    /// The given ratchet tree is used if there is no extension.
+    Provided(RatchetTreeIn),
+    /// The ratchet tree is fetched if there is no extension.
+    Fetched(Box<dyn RatchetTreeFetcher + 'a>),
+}
+
+impl RatchetTreeSource<'_> {
+    /// Returns the ratchet tree of the group with the given `group_id` in the
+    /// given `epoch` if there is no ratchet tree extension.
+    pub(crate) fn ratchet_tree(
+        self,
+        group_id: &GroupId,
+        epoch: GroupEpoch,
+    ) -> Option<RatchetTreeIn> {
+        match self {
+            Self::Extension => None,
+            Self::Provided(ratchet_tree) => Some(ratchet_tree),
+            Self::Fetched(fetcher) => fetcher.fetch_ratchet_tree(group_id, epoch),
+        }
+    }
+}
+
+impl fmt::Debug for RatchetTreeSource<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Extension => write!(f, "Extension"),
+            Self::Provided(ratchet_tree) => f.debug_tuple("Provided").field(ratchet_tree).finish(),
+            Self::Fetched(_) => write!(f, "Fetched"),
+        }
+    }
+}
+
+impl From<Option<RatchetTreeIn>> for RatchetTreeSource<'_> {
+    fn from(ratchet_tree: Option<RatchetTreeIn>) -> Self {
+        match ratchet_tree {
+            Some(ratchet_tree) => Self::Provided(ratchet_tree),
+            None => Self::Extension,
+        }
+    }
+}
+
 // The following `From` implementation breaks abstraction layers and MUST
 // NOT be made available outside of tests or "test-utils".
 #[cfg(any(feature = "test-utils", test))]
@@ -351,11 +419,13 @@ impl fmt::Display for RatchetTree {
 /// [`TreeSync`] instance guarantee a few invariants that are checked upon
 /// creating a new instance from an imported set of nodes, as well as when
 /// merging a diff.
-#[derive(Debug, Serialize, Deserialize)]
-#[cfg_attr(test, derive(PartialEq, Clone))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(PartialEq))]
 pub(crate) struct TreeSync {
     tree: MlsBinaryTree<TreeSyncLeafNode, TreeSyncParentNode>,
     tree_hash: Vec<u8>,
+    #[serde(skip)]
+    tree_hashes: TreeHashCache,
 }
 
 impl TreeSync {
@@ -396,6 +466,7 @@ impl TreeSync {
         let mut tree_sync = Self {
             tree,
             tree_hash: vec![],
+            tree_hashes: TreeHashCache::default(),
         };
         // Populate tree hash caches.
         tree_sync.populate_parent_hashes(backend, config.ciphersuite)?;
@@ -409,11 +480,19 @@ impl TreeSync {
     }
 
     /// Merge the given diff into this `TreeSync` instance, refreshing the
-    /// `tree_hash` value in the process.
+    /// `tree_hash` value and the cached tree hashes in the process.
     pub(crate) fn merge_diff(&mut self, tree_sync_diff: StagedTreeSyncDiff) {
-        let (diff, new_tree_hash) = tree_sync_diff.into_parts();
+        let (diff, new_tree_hash, new_tree_hashes) = tree_sync_diff.into_parts();
         self.tree_hash = new_tree_hash;
         self.tree.merge_diff(diff);
+        match new_tree_hashes {
+            Some(new_tree_hashes) => self
+                .tree_hashes
+                .update(self.tree.tree_size(), new_tree_hashes),
+            // The diff was staged without recording the hashes it changed, so
+            // none of the cached hashes can be trusted anymore.
+            None => self.tree_hashes = TreeHashCache::default(),
+        }
     }
 
     /// Create an empty diff based on this [`TreeSync`] instance all operations
@@ -431,30 +510,28 @@ impl TreeSync {
         ratchet_tree: RatchetTree,
     ) -> Result<Self, TreeSyncFromNodesError> {
         // TODO #800: Unmerged leaves should be checked
-        let mut ts_nodes: Vec<TreeNode<TreeSyncLeafNode, TreeSyncParentNode>> =
-            Vec::with_capacity(ratchet_tree.0.len());
-
-        // Set the leaf indices in all the leaves and convert the node types.
-        for (node_index, node_option) in ratchet_tree.0.into_iter().enumerate() {
-            let ts_node_option: TreeNode<TreeSyncLeafNode, TreeSyncParentNode> = match node_option {
-                Some(node) => {
-                    let node = node.clone();
-                    TreeSyncNode::from(node).into()
-                }
-                None => {
-                    if node_index % 2 == 0 {
-                        TreeNode::Leaf(TreeSyncLeafNode::blank())
-                    } else {
-                        TreeNode::Parent(TreeSyncParentNode::blank())
+        // Convert the node types. The nodes are converted while the tree is
+        // built, so that blank nodes don't have to be held in memory all at
+        // once if the tree is sparse.
+        let ts_nodes = ratchet_tree.0.into_iter().enumerate().map(
+            |(node_index, node_option)| -> TreeNode<TreeSyncLeafNode, TreeSyncParentNode> {
+                match node_option {
+                    Some(node) => TreeSyncNode::from(node).into(),
+                    None => {
+                        if node_index % 2 == 0 {
+                            TreeNode::Leaf(TreeSyncLeafNode::blank())
+                        } else {
+                            TreeNode::Parent(TreeSyncParentNode::blank())
+                        }
                     }
                 }
-            };
-            ts_nodes.push(ts_node_option);
-        }
+            },
+        );
         let tree = MlsBinaryTree::new(ts_nodes).map_err(|_| PublicTreeError::MalformedTree)?;
         let mut tree_sync = Self {
             tree,
             tree_hash: vec![],
+            tree_hashes: TreeHashCache::default(),
         };
         // Verify all parent hashes.
         tree_sync
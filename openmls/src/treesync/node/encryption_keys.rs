@@ -3,6 +3,7 @@ use std::fmt::Debug;
 use openmls_traits::{
     crypto::OpenMlsCrypto,
     key_store::{MlsEntity, MlsEntityId, OpenMlsKeyStore},
+    storage::{StorageKey, StorageProvider},
     types::{Ciphersuite, HpkeCiphertext, HpkeKeyPair},
     OpenMlsCryptoProvider,
 };
@@ -36,14 +37,12 @@ impl EncryptionKey {
         self.key.as_slice()
     }
 
-    /// Helper function to prefix the given (serialized) [`EncryptionKey`] with
-    /// the `ENCRYPTION_KEY_LABEL`.
-    ///
-    /// Returns the resulting bytes.
-    fn to_bytes_with_prefix(&self) -> Vec<u8> {
-        let mut key_store_index = ENCRYPTION_KEY_LABEL.to_vec();
-        key_store_index.extend_from_slice(self.as_slice());
-        key_store_index
+    /// Returns the [`StorageKey`] of the [`EncryptionKeyPair`] with this
+    /// [`EncryptionKey`].
+    pub(crate) fn storage_key(&self) -> StorageKey<'_> {
+        StorageKey::EncryptionKeyPair {
+            public_key: self.as_slice(),
+        }
     }
 
     /// Encrypt to this HPKE public key.
@@ -144,24 +143,22 @@ pub(crate) struct EncryptionKeyPair {
     private_key: EncryptionPrivateKey,
 }
 
-const ENCRYPTION_KEY_LABEL: &[u8; 19] = b"leaf_encryption_key";
-
 impl EncryptionKeyPair {
-    /// Write the [`EncryptionKeyPair`] to the key store of the `backend`. This
+    /// Write the [`EncryptionKeyPair`] to the storage of the `backend`. This
     /// function is meant to store standalone keypairs, not ones that are
     /// already in use with an MLS group.
     ///
-    /// Returns a key store error if access to the key store fails.
+    /// Returns a key store error if access to the storage fails.
     pub(crate) fn write_to_key_store<KeyStore: OpenMlsKeyStore>(
         &self,
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
     ) -> Result<(), KeyStore::Error> {
         backend
             .key_store()
-            .store(&self.public_key().to_bytes_with_prefix(), self)
+            .write(self.public_key().storage_key(), self)
     }
 
-    /// Read the [`EncryptionKeyPair`] from the key store of the `backend`. This
+    /// Read the [`EncryptionKeyPair`] from the storage of the `backend`. This
     /// function is meant to read standalone keypairs, not ones that are
     /// already in use with an MLS group.
     ///
@@ -170,23 +167,21 @@ impl EncryptionKeyPair {
         backend: &impl OpenMlsCryptoProvider,
         encryption_key: &EncryptionKey,
     ) -> Option<EncryptionKeyPair> {
-        backend
-            .key_store()
-            .read(&encryption_key.to_bytes_with_prefix())
+        backend.key_store().load(encryption_key.storage_key())
     }
 
-    /// Delete the [`EncryptionKeyPair`] from the key store of the `backend`.
+    /// Delete the [`EncryptionKeyPair`] from the storage of the `backend`.
     /// This function is meant to delete standalone keypairs, not ones that are
     /// already in use with an MLS group.
     ///
-    /// Returns a key store error if access to the key store fails.
+    /// Returns a key store error if access to the storage fails.
     pub(crate) fn delete_from_key_store<KeyStore: OpenMlsKeyStore>(
         &self,
         backend: &impl OpenMlsCryptoProvider<KeyStoreProvider = KeyStore>,
     ) -> Result<(), KeyStore::Error> {
         backend
             .key_store()
-            .delete::<Self>(&self.public_key().to_bytes_with_prefix())
+            .remove::<Self>(self.public_key().storage_key())
     }
 
     pub(crate) fn public_key(&self) -> &EncryptionKey {
@@ -206,6 +201,7 @@ impl EncryptionKeyPair {
         Ok(backend
             .crypto()
             .derive_hpke_keypair(config.ciphersuite.hpke_config(), ikm.as_slice())
+            .map_err(LibraryError::unexpected_crypto_error)?
             .into())
     }
 }
@@ -1,5 +1,9 @@
 //! This module contains the [`LeafNode`] struct and its implementation.
-use openmls_traits::{signatures::Signer, types::Ciphersuite, OpenMlsCryptoProvider};
+use openmls_traits::{
+    signatures::{AsyncSigner, Signer},
+    types::Ciphersuite,
+    OpenMlsCryptoProvider,
+};
 use serde::{Deserialize, Serialize};
 use tls_codec::{Serialize as TlsSerializeTrait, TlsDeserialize, TlsSerialize, TlsSize, VLBytes};
 
@@ -20,6 +24,7 @@ use crate::{
     extensions::{ExtensionType, Extensions},
     group::{config::CryptoConfig, GroupId},
     key_packages::{KeyPackage, Lifetime},
+    messages::proposals::ProposalType,
     treesync::errors::PublicTreeError,
     versions::ProtocolVersion,
 };
@@ -144,15 +149,50 @@ impl LeafNode {
             .map_err(|_| LibraryError::custom("Signing failed"))
     }
 
+    /// Create a new [`LeafNode`] for a key package without signing it. The
+    /// signature has to be added with [`LeafNode::set_signature()`] over the
+    /// bytes returned by [`LeafNode::key_package_sign_content()`].
+    pub(crate) fn new_unsigned(
+        encryption_key: EncryptionKey,
+        credential_with_key: CredentialWithKey,
+        lifetime: Lifetime,
+        capabilities: Capabilities,
+        extensions: Extensions,
+    ) -> Result<Self, LibraryError> {
+        let leaf_node_tbs = LeafNodeTbs::new(
+            encryption_key,
+            credential_with_key,
+            capabilities,
+            LeafNodeSource::KeyPackage(lifetime),
+            extensions,
+            TreeInfoTbs::KeyPackage,
+        )?;
+
+        Ok(Self::from_payload(leaf_node_tbs, Signature::from(vec![])))
+    }
+
+    /// Returns the bytes that have to be signed for this [`LeafNode`] when it
+    /// is used in a key package.
+    pub(crate) fn key_package_sign_content(&self) -> Result<Vec<u8>, LibraryError> {
+        LeafNodeTbs::from(self.clone(), TreeInfoTbs::KeyPackage)
+            .sign_content()
+            .map_err(|_| LibraryError::custom("Could not serialize the leaf node."))
+    }
+
+    /// Replace the signature of this [`LeafNode`].
+    pub(crate) fn set_signature(&mut self, signature: Signature) {
+        self.signature = signature;
+    }
+
     /// Update the parent hash of this [`LeafNode`].
     ///
     /// This re-signs the leaf node.
-    pub(in crate::treesync) fn update_parent_hash(
+    pub(in crate::treesync) async fn update_parent_hash(
         &mut self,
         parent_hash: &[u8],
         group_id: GroupId,
         leaf_index: LeafNodeIndex,
-        signer: &impl Signer,
+        signer: &impl AsyncSigner,
     ) -> Result<(), LibraryError> {
         self.payload.leaf_node_source = LeafNodeSource::Commit(parent_hash.into());
         let tbs = LeafNodeTbs::from(
@@ -163,7 +203,8 @@ impl LeafNode {
             }),
         );
         let leaf_node = tbs
-            .sign(signer)
+            .sign_async(signer)
+            .await
             .map_err(|_| LibraryError::custom("Signing failed"))?;
         self.payload = leaf_node.payload;
         self.signature = leaf_node.signature;
@@ -243,13 +284,13 @@ impl LeafNode {
     ///
     /// Optionally, a new leaf node can be provided to update more values such as
     /// the credential.
-    pub(crate) fn update_and_re_sign(
+    pub(crate) async fn update_and_re_sign(
         &mut self,
         new_encryption_key: impl Into<Option<EncryptionKey>>,
         leaf_node: impl Into<Option<LeafNode>>,
         group_id: GroupId,
         leaf_index: LeafNodeIndex,
-        signer: &impl Signer,
+        signer: &impl AsyncSigner,
     ) -> Result<(), PublicTreeError> {
         let tree_info = TreeInfoTbs::Update(TreePosition::new(group_id, leaf_index));
         // TODO: If we could take out the leaf_node without cloning, this would all be nicer.
@@ -273,24 +314,33 @@ impl LeafNode {
         }
 
         // Set the new signed leaf node with the new encryption key
-        let leaf_node = leaf_node_tbs.sign(signer)?;
+        let leaf_node = leaf_node_tbs.sign_async(signer).await?;
         self.payload = leaf_node.payload;
         self.signature = leaf_node.signature;
 
         Ok(())
     }
 
+    /// Replace the credential and the signature key in this leaf.
+    ///
+    /// This invalidates the signature, so the leaf node has to be signed
+    /// again with the new signature key, e.g. with [`Self::rekey()`].
+    pub(crate) fn set_credential_with_key(&mut self, credential_with_key: CredentialWithKey) {
+        self.payload.credential = credential_with_key.credential;
+        self.payload.signature_key = credential_with_key.signature_key;
+    }
+
     /// Replace the encryption key in this leaf with a random one.
     ///
     /// This signs the new leaf node as well.
-    pub(crate) fn rekey(
+    pub(crate) async fn rekey(
         &mut self,
         group_id: &GroupId,
         leaf_index: LeafNodeIndex,
         ciphersuite: Ciphersuite,
         protocol_version: ProtocolVersion,
         backend: &impl OpenMlsCryptoProvider,
-        signer: &impl Signer,
+        signer: &impl AsyncSigner,
     ) -> Result<EncryptionKeyPair, PublicTreeError> {
         if !self
             .payload
@@ -324,7 +374,8 @@ impl LeafNode {
             group_id.clone(),
             leaf_index,
             signer,
-        )?;
+        )
+        .await?;
 
         Ok(key_pair)
     }
@@ -377,6 +428,12 @@ impl LeafNode {
         &self.payload.extensions
     }
 
+    /// Returns `true` if the leaf node is marked as the leaf of a service
+    /// member with a [`ServiceMemberExtension`](crate::extensions::ServiceMemberExtension).
+    pub fn is_service_member(&self) -> bool {
+        self.payload.extensions.service_member().is_some()
+    }
+
     /// Returns `true` if the [`ExtensionType`] is supported by this leaf node.
     pub(crate) fn supports_extension(&self, extension_type: &ExtensionType) -> bool {
         self.payload
@@ -385,6 +442,12 @@ impl LeafNode {
             .contains(extension_type)
             || default_extensions().iter().any(|et| et == extension_type)
     }
+
+    /// Returns `true` if the [`ProposalType`] is supported by this leaf node.
+    pub(crate) fn supports_proposal(&self, proposal_type: &ProposalType) -> bool {
+        self.payload.capabilities.proposals.contains(proposal_type)
+            || default_proposals().iter().any(|pt| pt == proposal_type)
+    }
 }
 
 #[cfg(test)]
@@ -703,6 +766,40 @@ impl LeafNodeIn {
     pub fn credential(&self) -> &Credential {
         &self.payload.credential
     }
+
+    /// Returns the (unverified) extensions.
+    pub(crate) fn extensions(&self) -> &Extensions {
+        &self.payload.extensions
+    }
+
+    /// Returns the (unverified) `encryption_key`.
+    pub(crate) fn encryption_key(&self) -> &EncryptionKey {
+        &self.payload.encryption_key
+    }
+
+    /// Returns the (unverified) [`Capabilities`].
+    pub(crate) fn capabilities(&self) -> &Capabilities {
+        &self.payload.capabilities
+    }
+
+    /// Returns `true` if the [`ExtensionType`] is supported by this leaf node.
+    pub(crate) fn supports_extension(&self, extension_type: &ExtensionType) -> bool {
+        self.payload
+            .capabilities
+            .extensions
+            .contains(extension_type)
+            || default_extensions().iter().any(|et| et == extension_type)
+    }
+
+    /// Returns the (unverified) [`Lifetime`] if present.
+    /// `None` otherwise.
+    pub(crate) fn life_time(&self) -> Option<&Lifetime> {
+        if let LeafNodeSource::KeyPackage(life_time) = &self.payload.leaf_node_source {
+            Some(life_time)
+        } else {
+            None
+        }
+    }
 }
 
 impl From<LeafNode> for LeafNodeIn {
@@ -197,11 +197,19 @@ pub(super) fn default_versions() -> Vec<ProtocolVersion> {
     vec![ProtocolVersion::Mls10]
 }
 
+/// The ciphersuites that are advertised by default. The experimental
+/// post-quantum ciphersuites aren't included, since no crypto provider
+/// supports them yet. They have to be listed explicitly with
+/// [`Capabilities::new()`].
 pub(super) fn default_ciphersuites() -> Vec<Ciphersuite> {
     vec![
         Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519,
         Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
         Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519,
+        Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521,
+        Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384,
+        #[cfg(feature = "secp256k1")]
+        Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_SECP256K1,
     ]
 }
 
@@ -5,6 +5,7 @@ use openmls_traits::{
     types::{Ciphersuite, HpkeCiphertext},
     OpenMlsCryptoProvider,
 };
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use thiserror::*;
@@ -59,8 +60,11 @@ impl PlainUpdatePathNode {
         public_keys: &[EncryptionKey],
         group_context: &[u8],
     ) -> Result<UpdatePathNode, LibraryError> {
+        #[cfg(feature = "parallel")]
+        let public_keys = public_keys.par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let public_keys = public_keys.iter();
         public_keys
-            .par_iter()
             .map(|pk| {
                 self.path_secret
                     .encrypt(backend, ciphersuite, pk, group_context)
@@ -124,8 +128,11 @@ impl ParentNode {
         );
 
         // Iterate over the path secrets and derive a key pair
+        #[cfg(feature = "parallel")]
+        let path_secrets = path_secrets.into_par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let path_secrets = path_secrets.into_iter();
         let (path_with_keypairs, update_path_nodes): PathDerivationResults = path_secrets
-            .into_par_iter()
             .zip(path_indices)
             .map(|(path_secret, index)| {
                 // Derive a key pair from the path secret. This includes the
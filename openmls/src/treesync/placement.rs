@@ -0,0 +1,149 @@
+//! # Leaf placement
+//!
+//! When a member is added to a group, its leaf node is put at a free leaf of
+//! the tree. [RFC 9420] puts it at the left-most blank leaf, or extends the
+//! tree to the right if there are no blank leaves. The
+//! [`LeafPlacementStrategy`] of a group can change this, e.g. to put the
+//! devices of a user into adjacent leaves, so that they share most of their
+//! direct paths.
+//!
+//! Applications can also plug in their own [`LeafPlacement`] with
+//! [`MlsGroup::set_leaf_placement()`](crate::group::MlsGroup::set_leaf_placement()).
+//!
+//! The placement determines the tree of the new epoch, so **all members of
+//! a group (and a Delivery Service tracking it with a
+//! [`PublicGroup`](crate::group::PublicGroup)) must use the same placement**.
+//! Otherwise their trees diverge and they can't process the commit.
+//!
+//! The placement is only used for Add proposals. Members joining through an
+//! external commit are always put at the left-most blank leaf as required by
+//! the RFC, and service members are put at the right edge of the tree if the
+//! group requires the service member extension.
+//!
+//! [RFC 9420]: https://www.rfc-editor.org/rfc/rfc9420.html
+
+use std::{fmt::Debug, ops::Deref, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use super::LeafNode;
+use crate::binary_tree::LeafNodeIndex;
+
+/// A policy that decides where the leaf node of a new member is put in the
+/// tree.
+///
+/// See the [module documentation](self) for when it is called.
+pub trait LeafPlacement: Send + Sync {
+    /// Returns the index of the leaf that the new `leaf_node` is put at.
+    ///
+    /// `leaves` contains the leaves of the current tree, indexed by their
+    /// leaf index, where [`None`] is a blank leaf. The returned index must be
+    /// the index of a blank leaf, or `leaves.len()` to extend the tree. Any
+    /// other index is ignored and the leaf node is put at the left-most
+    /// blank leaf.
+    ///
+    /// The result must only depend on the arguments, so that all members of
+    /// the group place the leaf node at the same index.
+    fn leaf_index(&self, leaves: &[Option<&LeafNode>], leaf_node: &LeafNode) -> LeafNodeIndex;
+}
+
+impl Debug for dyn LeafPlacement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("LeafPlacement")
+    }
+}
+
+/// The built-in strategies to place the leaf nodes of new members.
+///
+///  - `LeftmostFree`:
+/// The leaf node is put at the left-most blank leaf, or the tree is
+/// extended if there are no blank leaves. This is the placement of
+/// [RFC 9420](https://www.rfc-editor.org/rfc/rfc9420.html#section-12.1.1)
+/// and the default.
+///  - `Locality`:
+/// If the tree contains leaf nodes with the same credential identity as the
+/// new leaf node, e.g. other devices of the same user, the leaf node is put
+/// at the blank leaf that is closest to them, i.e. whose lowest common
+/// ancestor with one of them is lowest in the tree. Ties are broken by
+/// taking the left-most blank leaf. The tree is never extended to get a
+/// closer leaf. Otherwise, the leaf node is placed like with `LeftmostFree`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LeafPlacementStrategy {
+    /// Place at the left-most blank leaf.
+    #[default]
+    LeftmostFree,
+    /// Place close to leaf nodes with the same credential identity.
+    Locality,
+}
+
+impl LeafPlacement for LeafPlacementStrategy {
+    fn leaf_index(&self, leaves: &[Option<&LeafNode>], leaf_node: &LeafNode) -> LeafNodeIndex {
+        let blank_leaves = leaves
+            .iter()
+            .enumerate()
+            .filter(|(_, leaf)| leaf.is_none())
+            .map(|(index, _)| index as u32);
+        let leftmost_free = || blank_leaves.clone().next().unwrap_or(leaves.len() as u32);
+
+        match self {
+            LeafPlacementStrategy::LeftmostFree => LeafNodeIndex::new(leftmost_free()),
+            LeafPlacementStrategy::Locality => {
+                let identity = leaf_node.credential().identity();
+                let related_leaves: Vec<u32> = leaves
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, leaf)| {
+                        leaf.map_or(false, |leaf| leaf.credential().identity() == identity)
+                    })
+                    .map(|(index, _)| index as u32)
+                    .collect();
+
+                // The level of the lowest common ancestor of two leaves,
+                // where the leaves themselves are at level 0.
+                let distance = |index: u32| {
+                    related_leaves
+                        .iter()
+                        .map(|related_leaf| u32::BITS - (index ^ related_leaf).leading_zeros())
+                        .min()
+                };
+
+                let mut closest: Option<(u32, u32)> = None;
+                for index in blank_leaves.clone() {
+                    let Some(distance) = distance(index) else {
+                        break;
+                    };
+                    if closest.map_or(true, |(_, closest_distance)| distance < closest_distance) {
+                        closest = Some((index, distance));
+                    }
+                }
+                LeafNodeIndex::new(closest.map_or_else(leftmost_free, |(index, _)| index))
+            }
+        }
+    }
+}
+
+/// A [`LeafPlacement`] set by the application. It is not part of the
+/// persisted group state.
+#[derive(Clone, Debug)]
+pub(crate) struct CustomLeafPlacement(Arc<dyn LeafPlacement>);
+
+impl CustomLeafPlacement {
+    pub(crate) fn new(placement: impl LeafPlacement + 'static) -> Self {
+        Self(Arc::new(placement))
+    }
+}
+
+impl Deref for CustomLeafPlacement {
+    type Target = dyn LeafPlacement;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for CustomLeafPlacement {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::as_ptr(&self.0).cast::<()>() == Arc::as_ptr(&other.0).cast::<()>()
+    }
+}
@@ -1,4 +1,4 @@
-#[cfg(any(feature = "test-utils", test))]
+#[cfg(any(feature = "test-vectors", test))]
 pub mod kats;
 #[cfg(test)]
 mod tests;
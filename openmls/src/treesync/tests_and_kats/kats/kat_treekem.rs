@@ -3,7 +3,11 @@ use std::collections::HashSet;
 use log::{debug, trace};
 use openmls_basic_credential::SignatureKeyPair;
 use openmls_rust_crypto::OpenMlsRustCrypto;
-use openmls_traits::{crypto::OpenMlsCrypto, types::Ciphersuite, OpenMlsCryptoProvider};
+use openmls_traits::{
+    crypto::OpenMlsCrypto,
+    types::{Ciphersuite, SignatureScheme},
+    OpenMlsCryptoProvider,
+};
 use serde::{Deserialize, Serialize};
 use tls_codec::{Deserialize as TlsDeserializeTrait, Serialize as TlsSerializeTrait};
 
@@ -111,7 +115,7 @@ pub fn run_test_vector(test: TreeKemTest, backend: &impl OpenMlsCryptoProvider)
                 .unwrap();
             let signature_key = own_leaf.signature_key();
             let mut private_key = leaf_private_test.signature_priv.clone();
-            if ciphersuite != Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256 {
+            if ciphersuite.signature_algorithm() == SignatureScheme::ED25519 {
                 private_key.append(&mut signature_key.as_slice().to_vec());
             }
             let signature_keypair = SignatureKeyPair::from_raw(
@@ -249,14 +253,14 @@ pub fn run_test_vector(test: TreeKemTest, backend: &impl OpenMlsCryptoProvider)
             };
 
             // TODO(#1279): Update own leaf.
-            let (vec_plain_update_path_nodes, _, commit_secret) = diff_after_kat
-                .apply_own_update_path(
+            let (vec_plain_update_path_nodes, _, commit_secret) =
+                crate::utils::poll_ready(diff_after_kat.apply_own_update_path(
                     backend,
                     &signer,
                     ciphersuite,
                     group_context.group_id().clone(),
                     LeafNodeIndex::new(path_test.sender),
-                )
+                ))
                 .unwrap();
 
             // TODO(#1279): Update GroupContext.
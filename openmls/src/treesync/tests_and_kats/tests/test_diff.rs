@@ -4,6 +4,7 @@ use rstest::*;
 use rstest_reuse::apply;
 
 use crate::{
+    binary_tree::LeafNodeIndex,
     credentials::{test_utils::new_credential, CredentialType},
     key_packages::KeyPackageBundle,
     treesync::{node::Node, RatchetTree, TreeSync},
@@ -66,3 +67,94 @@ fn test_free_leaf_computation(ciphersuite: Ciphersuite, backend: &impl OpenMlsCr
 
     assert_eq!(free_leaf_index.u32(), 2u32);
 }
+
+// Verifies that the tree hashes computed from the cached hashes of unchanged
+// subtrees match the ones computed from scratch
+#[apply(ciphersuites_and_backends)]
+fn test_incremental_tree_hash(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    let key_package_bundle = |identity: &[u8]| {
+        let (credential, signer) = new_credential(
+            backend,
+            identity,
+            CredentialType::Basic,
+            ciphersuite.signature_algorithm(),
+        );
+        KeyPackageBundle::new(backend, &signer, ciphersuite, credential)
+    };
+    let kpb_0 = key_package_bundle(b"leaf0");
+    let kpb_3 = key_package_bundle(b"leaf3");
+
+    let ratchet_tree = RatchetTree::trimmed(vec![
+        Some(Node::LeafNode(kpb_0.key_package().leaf_node().clone())), // Leaf 0
+        None,
+        None, // Leaf 1
+        None,
+        None, // Leaf 2
+        None,
+        Some(Node::LeafNode(kpb_3.key_package().leaf_node().clone())), // Leaf 3
+    ]);
+    let mut tree = TreeSync::from_ratchet_tree(backend, ciphersuite, ratchet_tree)
+        .expect("error generating tree");
+
+    let assert_tree_hash = |tree: &TreeSync| {
+        let fresh_tree =
+            TreeSync::from_ratchet_tree(backend, ciphersuite, tree.export_ratchet_tree())
+                .expect("error generating tree");
+        assert_eq!(tree.tree_hash(), fresh_tree.tree_hash());
+    };
+
+    // Fill the blank leaves and grow the tree.
+    let mut diff = tree.empty_diff();
+    for identity in [b"leaf1", b"leaf2", b"leaf4"] {
+        diff.add_leaf(
+            key_package_bundle(&identity[..])
+                .key_package()
+                .leaf_node()
+                .clone(),
+        )
+        .expect("error adding leaf");
+    }
+    let staged_diff = diff
+        .into_staged_diff(backend, ciphersuite)
+        .expect("error staging diff");
+    tree.merge_diff(staged_diff);
+    assert_eq!(tree.tree_size().leaf_count(), 8);
+    assert_tree_hash(&tree);
+
+    // Replace a leaf.
+    let mut diff = tree.empty_diff();
+    diff.update_leaf(
+        key_package_bundle(b"leaf2")
+            .key_package()
+            .leaf_node()
+            .clone(),
+        LeafNodeIndex::new(2),
+    );
+    let staged_diff = diff
+        .into_staged_diff(backend, ciphersuite)
+        .expect("error staging diff");
+    tree.merge_diff(staged_diff);
+    assert_tree_hash(&tree);
+
+    // Shrink the tree again.
+    let mut diff = tree.empty_diff();
+    diff.blank_leaf(LeafNodeIndex::new(4));
+    let staged_diff = diff
+        .into_staged_diff(backend, ciphersuite)
+        .expect("error staging diff");
+    tree.merge_diff(staged_diff);
+    assert_eq!(tree.tree_size().leaf_count(), 4);
+    assert_tree_hash(&tree);
+
+    // A deserialized tree starts without cached hashes.
+    let mut tree: TreeSync =
+        serde_json::from_slice(&serde_json::to_vec(&tree).expect("error serializing tree"))
+            .expect("error deserializing tree");
+    let mut diff = tree.empty_diff();
+    diff.blank_leaf(LeafNodeIndex::new(1));
+    let staged_diff = diff
+        .into_staged_diff(backend, ciphersuite)
+        .expect("error staging diff");
+    tree.merge_diff(staged_diff);
+    assert_tree_hash(&tree);
+}
@@ -7,10 +7,10 @@
 use std::collections::HashSet;
 
 use openmls_traits::{
-    crypto::OpenMlsCrypto,
     types::{Ciphersuite, HpkeCiphertext},
     OpenMlsCryptoProvider,
 };
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use tls_codec::{TlsDeserialize, TlsSerialize, TlsSize};
@@ -27,7 +27,11 @@ use super::{
 };
 use crate::{
     binary_tree::array_representation::LeafNodeIndex,
-    ciphersuite::{hpke, signable::Verifiable, HpkePublicKey},
+    ciphersuite::{
+        hpke,
+        signable::{SignatureBatch, SignatureError},
+        HpkePublicKey,
+    },
     error::LibraryError,
     messages::{proposals::AddProposal, EncryptedGroupSecrets, GroupSecrets, PathSecret},
     schedule::{psk::PreSharedKeyId, CommitSecret, JoinerSecret},
@@ -71,10 +75,12 @@ impl<'a> TreeSyncDiff<'a> {
         debug_assert_eq!(copath_resolutions.len(), path.len());
 
         // Encrypt the secrets
-        path.par_iter()
-            .zip(copath_resolutions.par_iter())
-            .map(|(node, resolution)| node.encrypt(backend, ciphersuite, resolution, group_context))
-            .collect::<Result<Vec<UpdatePathNode>, LibraryError>>()
+        #[cfg(feature = "parallel")]
+        let path = path.par_iter().zip(copath_resolutions.par_iter());
+        #[cfg(not(feature = "parallel"))]
+        let path = path.iter().zip(copath_resolutions.iter());
+        path.map(|(node, resolution)| node.encrypt(backend, ciphersuite, resolution, group_context))
+            .collect()
     }
 
     /// Decrypt an [`UpdatePath`] originating from the given
@@ -377,13 +383,22 @@ impl UpdatePathIn {
         &self.leaf_node
     }
 
-    /// Return a verified [`UpdatePath`].
-    pub(crate) fn into_verified(
+    /// Return the `nodes` of this [`UpdatePath`].
+    pub(crate) fn nodes(&self) -> &[UpdatePathNode] {
+        &self.nodes
+    }
+
+    /// Return a verified [`UpdatePath`]. The signature check of the leaf node
+    /// is added to `signatures`.
+    ///
+    /// The returned [`UpdatePath`] MUST be discarded unless `signatures` is
+    /// verified successfully.
+    pub(crate) fn into_verified<E: From<UpdatePathError>>(
         self,
         ciphersuite: Ciphersuite,
-        crypto: &impl OpenMlsCrypto,
         tree_position: TreePosition,
-    ) -> Result<UpdatePath, UpdatePathError> {
+        signatures: &mut SignatureBatch<E>,
+    ) -> Result<UpdatePath, E> {
         let leaf_node_in = self.leaf_node().clone();
         let verifiable_leaf_node = leaf_node_in.into_verifiable_leaf_node();
         match verifiable_leaf_node {
@@ -394,14 +409,18 @@ impl UpdatePathIn {
                     .into_signature_public_key_enriched(ciphersuite.signature_algorithm());
                 commit_leaf_node.add_tree_position(tree_position);
 
-                let leaf_node: LeafNode = commit_leaf_node.verify(crypto, pk)?;
+                let leaf_node: LeafNode = signatures.add(
+                    commit_leaf_node,
+                    pk,
+                    UpdatePathError::SignatureError(SignatureError::VerificationError).into(),
+                )?;
                 Ok(UpdatePath {
                     leaf_node,
                     nodes: self.nodes,
                 })
             }
             VerifiableLeafNode::Update(_) | VerifiableLeafNode::KeyPackage(_) => {
-                Err(UpdatePathError::InvalidType)
+                Err(UpdatePathError::InvalidType.into())
             }
         }
     }
@@ -55,8 +55,7 @@ impl From<TreeSyncNode> for TreeNode<TreeSyncLeafNode, TreeSyncParentNode> {
     }
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
-#[cfg_attr(test, derive(PartialEq))]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 /// This intermediate struct on top of `Option<Node>` allows us to cache tree
 /// hash values. Blank nodes are represented by [`TreeSyncNode`] instances where
 /// `node = None`.
@@ -107,8 +106,7 @@ impl From<TreeSyncLeafNode> for Option<Node> {
     }
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
-#[cfg_attr(test, derive(PartialEq))]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 /// This intermediate struct on top of `Option<Node>` allows us to cache tree
 /// hash values. Blank nodes are represented by [`TreeSyncNode`] instances where
 /// `node = None`.
@@ -1,3 +1,12 @@
+use std::{
+    future::Future,
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+};
+
+use crate::error::LibraryError;
+
 // === The folowing functions aren't necessarily cryptographically secure!
 
 #[cfg(any(feature = "test-utils", test))]
@@ -81,3 +90,30 @@ pub mod vector_converter {
         Ok(T::from_iter(container.into_iter()))
     }
 }
+
+/// Drive a future that is expected to be ready immediately to completion.
+///
+/// This is used by the synchronous API on top of the async signing code. The
+/// futures are ready immediately when a synchronous
+/// [`Signer`](openmls_traits::signatures::Signer) is used, because its
+/// [`AsyncSigner`](openmls_traits::signatures::AsyncSigner) implementation
+/// never suspends.
+///
+/// Returns a [`LibraryError`] if the future is not ready.
+pub(crate) fn poll_ready<T, E: From<LibraryError>>(
+    future: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    struct NoopWaker;
+
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    match future.as_mut().poll(&mut context) {
+        Poll::Ready(result) => result,
+        Poll::Pending => Err(LibraryError::custom("A future that must be ready suspended.").into()),
+    }
+}
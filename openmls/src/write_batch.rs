@@ -0,0 +1,130 @@
+//! Write batches of operations.
+//!
+//! Operations that must write several values together, e.g. merging a
+//! commit, run on a [`BatchProvider`]. It collects all writes and deletions of
+//! the operation in a [`WriteBatch`] that is owned by the operation, and
+//! applies them to the key store with
+//! [`OpenMlsKeyStore::apply_batch()`] once the operation succeeded.
+
+use std::sync::{Mutex, PoisonError};
+
+use openmls_traits::{
+    key_store::{MlsEntity, MlsEntityId, OpenMlsKeyStore, WriteBatch},
+    OpenMlsCryptoProvider,
+};
+
+/// A crypto provider that uses the crypto and randomness providers of the
+/// wrapped provider and a [`BatchKeyStore`] in front of its key store.
+pub(crate) struct BatchProvider<'a, Provider: OpenMlsCryptoProvider> {
+    backend: &'a Provider,
+    key_store: BatchKeyStore<'a, Provider::KeyStoreProvider>,
+}
+
+impl<'a, Provider: OpenMlsCryptoProvider> BatchProvider<'a, Provider> {
+    pub(crate) fn new(backend: &'a Provider) -> Self {
+        Self {
+            backend,
+            key_store: BatchKeyStore {
+                key_store: backend.key_store(),
+                batch: Mutex::new(WriteBatch::new()),
+            },
+        }
+    }
+
+    /// Applies all writes and deletions of the operation to the key store of
+    /// the wrapped provider.
+    ///
+    /// Returns an error if applying the batch fails. In that case nothing was
+    /// written.
+    pub(crate) fn apply(
+        self,
+    ) -> Result<(), <Provider::KeyStoreProvider as OpenMlsKeyStore>::Error> {
+        let batch = self
+            .key_store
+            .batch
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+        if batch.is_empty() {
+            return Ok(());
+        }
+        self.backend.key_store().apply_batch(batch)
+    }
+}
+
+impl<'a, Provider: OpenMlsCryptoProvider> OpenMlsCryptoProvider for BatchProvider<'a, Provider> {
+    type CryptoProvider = Provider::CryptoProvider;
+    type RandProvider = Provider::RandProvider;
+    type KeyStoreProvider = BatchKeyStore<'a, Provider::KeyStoreProvider>;
+
+    fn crypto(&self) -> &Self::CryptoProvider {
+        self.backend.crypto()
+    }
+
+    fn rand(&self) -> &Self::RandProvider {
+        self.backend.rand()
+    }
+
+    fn key_store(&self) -> &Self::KeyStoreProvider {
+        &self.key_store
+    }
+}
+
+/// A key store that records writes and deletions in a [`WriteBatch`] instead
+/// of applying them. Reads return the values as they are after the batch is
+/// applied.
+pub(crate) struct BatchKeyStore<'a, KeyStore: OpenMlsKeyStore> {
+    key_store: &'a KeyStore,
+    batch: Mutex<WriteBatch>,
+}
+
+impl<'a, KeyStore: OpenMlsKeyStore> std::fmt::Debug for BatchKeyStore<'a, KeyStore> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BatchKeyStore").finish_non_exhaustive()
+    }
+}
+
+impl<'a, KeyStore: OpenMlsKeyStore> OpenMlsKeyStore for BatchKeyStore<'a, KeyStore> {
+    type Error = KeyStore::Error;
+
+    fn store<V: MlsEntity>(&self, k: &[u8], v: &V) -> Result<(), Self::Error> {
+        let value = self.key_store.encode(v)?;
+        self.store_encoded(V::ID, k, &value)
+    }
+
+    fn read<V: MlsEntity>(&self, k: &[u8]) -> Option<V> {
+        let batch = self.batch.lock().unwrap_or_else(PoisonError::into_inner);
+        match batch.read::<V>(k) {
+            Some(value) => value.and_then(|value| self.key_store.decode(value)),
+            None => self.key_store.read(k),
+        }
+    }
+
+    fn delete<V: MlsEntity>(&self, k: &[u8]) -> Result<(), Self::Error> {
+        self.batch
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .delete::<V>(k);
+        Ok(())
+    }
+
+    fn encode<V: MlsEntity>(&self, v: &V) -> Result<Vec<u8>, Self::Error> {
+        self.key_store.encode(v)
+    }
+
+    fn decode<V: MlsEntity>(&self, value: &[u8]) -> Option<V> {
+        self.key_store.decode(value)
+    }
+
+    fn store_encoded(
+        &self,
+        entity_id: MlsEntityId,
+        k: &[u8],
+        value: &[u8],
+    ) -> Result<(), Self::Error> {
+        self.batch
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .store(entity_id, k, value.to_vec());
+        Ok(())
+    }
+}
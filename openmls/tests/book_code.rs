@@ -376,7 +376,9 @@ fn book_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvide
                 &alice_credential.credential
             );
             // Store proposal
-            alice_group.store_pending_proposal(*staged_proposal.clone());
+            alice_group
+                .store_pending_proposal(backend, *staged_proposal.clone())
+                .expect("Could not store proposal.");
         } else {
             unreachable!("Expected a Proposal.");
         }
@@ -386,7 +388,9 @@ fn book_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvide
             staged_proposal.sender(),
             Sender::Member(member) if *member == alice_group.own_leaf_index()
         ));
-        bob_group.store_pending_proposal(*staged_proposal);
+        bob_group
+            .store_pending_proposal(backend, *staged_proposal)
+            .expect("Could not store proposal.");
     } else {
         unreachable!("Expected a QueuedProposal.");
     }
@@ -801,7 +805,9 @@ fn book_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvide
             // Check that Charlie was removed
             assert_eq!(remove_proposal.removed(), charlie_group.own_leaf_index());
             // Store proposal
-            charlie_group.store_pending_proposal(*staged_proposal.clone());
+            charlie_group
+                .store_pending_proposal(backend, *staged_proposal.clone())
+                .expect("Could not store proposal.");
         } else {
             unreachable!("Expected a Proposal.");
         }
@@ -821,7 +827,7 @@ fn book_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvide
         .propose_add_member(backend, &alice_signature_keys, &bob_key_package)
         .expect("Could not create proposal to add Bob");
     alice_group
-        .remove_pending_proposal(proposal_ref)
+        .remove_pending_proposal(backend, proposal_ref)
         .expect("The proposal was not found");
     // ANCHOR_END: rollback_proposal_by_ref
 
@@ -863,7 +869,9 @@ fn book_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvide
             Sender::Member(member) if *member == alice_group.own_leaf_index()
         ));
         // Store proposal
-        charlie_group.store_pending_proposal(*staged_proposal);
+        charlie_group
+            .store_pending_proposal(backend, *staged_proposal)
+            .expect("Could not store proposal.");
     }
     // ANCHOR_END: inspect_add_proposal
     else {
@@ -1006,16 +1014,19 @@ fn book_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvide
         alice_processed_message.into_content()
     {
         // Store proposal
-        alice_group.store_pending_proposal(*staged_proposal);
+        alice_group
+            .store_pending_proposal(backend, *staged_proposal)
+            .expect("Could not store proposal.");
     } else {
         unreachable!("Expected a QueuedProposal.");
     }
 
-    // Should fail because you cannot remove yourself from a group
+    // Should fail because Bob is waiting to be removed from the group
+    assert!(bob_group.is_leaving());
     assert_eq!(
         bob_group.commit_to_pending_proposals(backend, &bob_signature_keys),
-        Err(CommitToPendingProposalsError::CreateCommitError(
-            CreateCommitError::CannotRemoveSelf
+        Err(CommitToPendingProposalsError::GroupStateError(
+            MlsGroupStateError::PendingLeave
         ))
     );
 
@@ -1129,7 +1140,9 @@ fn book_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvide
         .expect("Could not process message.");
     match alice_processed_message.into_content() {
         ProcessedMessageContent::ExternalJoinProposalMessage(proposal) => {
-            alice_group.store_pending_proposal(*proposal);
+            alice_group
+                .store_pending_proposal(backend, *proposal)
+                .expect("Could not store proposal.");
             let (_commit, welcome, _group_info) = alice_group
                 .commit_to_pending_proposals(backend, &alice_signature_keys)
                 .expect("Could not commit");
@@ -1189,7 +1202,9 @@ fn book_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvide
         .expect("Could not process message.");
     match alice_processed_message.into_content() {
         ProcessedMessageContent::ProposalMessage(proposal) => {
-            alice_group.store_pending_proposal(*proposal);
+            alice_group
+                .store_pending_proposal(backend, *proposal)
+                .expect("Could not store proposal.");
             assert_eq!(alice_group.members().count(), 2);
             alice_group
                 .commit_to_pending_proposals(backend, &alice_signature_keys)
@@ -1237,17 +1252,7 @@ fn book_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvide
         bob_group.export_secret(backend, "before load", &[], 32)
     );
 
-    // Check that the state flag gets reset when saving
-    assert_eq!(bob_group.state_changed(), InnerState::Changed);
-    //save(&mut bob_group);
-
-    bob_group
-        .save(backend)
-        .expect("Could not write group state to file");
-
-    // Check that the state flag gets reset when saving
-    assert_eq!(bob_group.state_changed(), InnerState::Persisted);
-
+    // The group state was written when Bob joined the group
     let bob_group = MlsGroup::load(&group_id, backend).expect("Could not load group from file");
 
     // Make sure the state is still the same
@@ -1,3 +1,5 @@
+#![cfg(feature = "external-commit")]
+
 use openmls::{
     credentials::test_utils::new_credential, messages::group_info::VerifiableGroupInfo, prelude::*,
     test_utils::*, *,
@@ -229,3 +231,155 @@ fn test_not_present_group_info(ciphersuite: Ciphersuite, backend: &impl OpenMlsC
 
     assert!(group_info.is_none());
 }
+
+#[apply(ciphersuites_and_backends)]
+fn test_ratchet_tree_fetcher(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    // Alice creates a new group ...
+    let (alice_group, _, alice_signer) = create_alice_group(ciphersuite, backend, false);
+
+    // ... and exports a group info without the ratchet tree.
+    let verifiable_group_info = || {
+        let group_info = alice_group
+            .export_group_info(backend, &alice_signer, false)
+            .unwrap();
+
+        let serialized_group_info = group_info.tls_serialize_detached().unwrap();
+
+        let mls_message_in =
+            MlsMessageIn::tls_deserialize(&mut serialized_group_info.as_slice()).unwrap();
+
+        mls_message_in.into_verifiable_group_info().unwrap()
+    };
+    let group_config = MlsGroupConfigBuilder::new()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+
+    let (bob_credential, bob_signature_keys) = new_credential(
+        backend,
+        b"Bob",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+
+    // The ratchet tree isn't available.
+    let got_error = MlsGroup::join_by_external_commit_with_ratchet_tree_fetcher(
+        backend,
+        &bob_signature_keys,
+        |_group_id: &GroupId, _epoch: GroupEpoch| -> Option<RatchetTreeIn> { None },
+        verifiable_group_info(),
+        &group_config,
+        b"",
+        bob_credential.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(got_error, ExternalCommitError::MissingRatchetTree);
+
+    // Bob fetches the ratchet tree of Alice's group.
+    let (mut bob_group, _, _) = MlsGroup::join_by_external_commit_with_ratchet_tree_fetcher(
+        backend,
+        &bob_signature_keys,
+        |group_id: &GroupId, epoch: GroupEpoch| {
+            assert_eq!(group_id, alice_group.group_id());
+            assert_eq!(epoch, alice_group.epoch());
+            Some(alice_group.export_ratchet_tree().into())
+        },
+        verifiable_group_info(),
+        &group_config,
+        b"",
+        bob_credential,
+    )
+    .unwrap();
+    bob_group.merge_pending_commit(backend).unwrap();
+}
+
+#[apply(ciphersuites_and_backends)]
+fn test_export_group_info(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoProvider) {
+    // Alice creates a new group ...
+    let (mut alice_group, _, alice_signer) = create_alice_group(ciphersuite, backend, false);
+    let group_config = MlsGroupConfigBuilder::new()
+        .crypto_config(CryptoConfig::with_default_version(ciphersuite))
+        .build();
+    let verifiable_group_info = |group: &MlsGroup, signer: &SignatureKeyPair| {
+        let group_info = group.export_group_info(backend, signer, true).unwrap();
+
+        let serialized_group_info = group_info.tls_serialize_detached().unwrap();
+
+        let mls_message_in =
+            MlsMessageIn::tls_deserialize(&mut serialized_group_info.as_slice()).unwrap();
+
+        mls_message_in.into_verifiable_group_info().unwrap()
+    };
+
+    // ... and Bob joins by an external commit.
+    let (bob_credential, bob_signature_keys) = new_credential(
+        backend,
+        b"Bob",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+    let (mut bob_group, msg, _group_info) = MlsGroup::join_by_external_commit(
+        backend,
+        &bob_signature_keys,
+        None,
+        verifiable_group_info(&alice_group, &alice_signer),
+        &group_config,
+        b"",
+        bob_credential,
+    )
+    .map(|(group, msg, group_info)| (group, MlsMessageIn::from(msg), group_info))
+    .unwrap();
+    bob_group.merge_pending_commit(backend).unwrap();
+
+    let msg = alice_group
+        .process_message(backend, msg)
+        .unwrap()
+        .into_content();
+    match msg {
+        ProcessedMessageContent::StagedCommitMessage(commit) => {
+            alice_group.merge_staged_commit(backend, *commit).unwrap();
+        }
+        _ => panic!("Unexpected message type"),
+    }
+
+    // Alice didn't create the last commit, but exports a group info for the
+    // new epoch that Charlie can join with.
+    let (charlie_credential, charlie_signature_keys) = new_credential(
+        backend,
+        b"Charlie",
+        CredentialType::Basic,
+        ciphersuite.signature_algorithm(),
+    );
+    let (mut charlie_group, ..) = MlsGroup::join_by_external_commit(
+        backend,
+        &charlie_signature_keys,
+        None,
+        verifiable_group_info(&alice_group, &alice_signer),
+        &group_config,
+        b"",
+        charlie_credential,
+    )
+    .unwrap();
+    charlie_group.merge_pending_commit(backend).unwrap();
+    assert_eq!(charlie_group.epoch(), GroupEpoch::from(2));
+
+    // After being removed, Bob can't export group infos anymore.
+    let (commit, ..) = alice_group
+        .remove_members(backend, &alice_signer, &[bob_group.own_leaf_index()])
+        .unwrap();
+    let msg = bob_group
+        .process_message(backend, MlsMessageIn::from(commit))
+        .unwrap()
+        .into_content();
+    match msg {
+        ProcessedMessageContent::StagedCommitMessage(commit) => {
+            bob_group.merge_staged_commit(backend, *commit).unwrap();
+        }
+        _ => panic!("Unexpected message type"),
+    }
+    assert_eq!(
+        bob_group
+            .export_group_info(backend, &bob_signature_keys, true)
+            .unwrap_err(),
+        ExportGroupInfoError::GroupStateError(MlsGroupStateError::UseAfterEviction)
+    );
+}
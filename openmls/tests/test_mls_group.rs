@@ -253,7 +253,9 @@ fn mls_group_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoPr
                     &alice_credential.credential
                 );
                 // Store proposal
-                alice_group.store_pending_proposal(*staged_proposal.clone());
+                alice_group
+                    .store_pending_proposal(backend, *staged_proposal.clone())
+                    .expect("Could not store proposal.");
             } else {
                 unreachable!("Expected a Proposal.");
             }
@@ -264,7 +266,9 @@ fn mls_group_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoPr
                 Sender::Member(member) if *member == alice_group.own_leaf_index()
             ));
 
-            bob_group.store_pending_proposal(*staged_proposal);
+            bob_group
+                .store_pending_proposal(backend, *staged_proposal)
+                .expect("Could not store proposal.");
         } else {
             unreachable!("Expected a QueuedProposal.");
         }
@@ -603,7 +607,9 @@ fn mls_group_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoPr
                 // Check that Charlie was removed
                 assert_eq!(remove_proposal.removed(), members[1].index);
                 // Store proposal
-                charlie_group.store_pending_proposal(*staged_proposal.clone());
+                charlie_group
+                    .store_pending_proposal(backend, *staged_proposal.clone())
+                    .expect("Could not store proposal.");
             } else {
                 unreachable!("Expected a Proposal.");
             }
@@ -652,7 +658,9 @@ fn mls_group_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoPr
                 Sender::Member(member) if *member == members[0].index
             ));
             // Store proposal
-            charlie_group.store_pending_proposal(*staged_proposal);
+            charlie_group
+                .store_pending_proposal(backend, *staged_proposal)
+                .expect("Could not store proposal.");
         } else {
             unreachable!("Expected a QueuedProposal.");
         }
@@ -777,16 +785,19 @@ fn mls_group_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoPr
             alice_processed_message.into_content()
         {
             // Store proposal
-            alice_group.store_pending_proposal(*staged_proposal);
+            alice_group
+                .store_pending_proposal(backend, *staged_proposal)
+                .expect("Could not store proposal.");
         } else {
             unreachable!("Expected a QueuedProposal.");
         }
 
-        // Should fail because you cannot remove yourself from a group
+        // Should fail because Bob is waiting to be removed from the group
+        assert!(bob_group.is_leaving());
         assert_eq!(
             bob_group.commit_to_pending_proposals(backend, &bob_signer),
-            Err(CommitToPendingProposalsError::CreateCommitError(
-                CreateCommitError::CannotRemoveSelf
+            Err(CommitToPendingProposalsError::GroupStateError(
+                MlsGroupStateError::PendingLeave
             ))
         );
 
@@ -853,6 +864,14 @@ fn mls_group_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoPr
         // Check that Bob's group is no longer active
         assert!(!bob_group.is_active());
 
+        // Check that Bob's departure was recorded
+        let departure = bob_group.departure().expect("Expected a departure.");
+        assert_eq!(departure.reason(), &DepartureReason::Left);
+        assert_eq!(
+            departure.epoch(),
+            GroupEpoch::from(alice_group.epoch().as_u64() - 1)
+        );
+
         // Make sure the group contains one member
         assert_eq!(alice_group.members().count(), 1);
 
@@ -876,13 +895,10 @@ fn mls_group_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoPr
             .add_members(backend, &alice_signer, &[bob_key_package])
             .expect("Could not add Bob");
 
-        // Test saving & loading the group state when there is a pending commit
-        alice_group
-            .save(backend)
-            .expect("Could not save group state.");
-
-        let _test_group =
+        // Test loading the group state when there is a pending commit
+        let test_group =
             MlsGroup::load(&group_id, backend).expect("Could not load the group state.");
+        assert!(test_group.pending_commit().is_some());
 
         // Merge Commit
         alice_group
@@ -902,16 +918,7 @@ fn mls_group_operations(ciphersuite: Ciphersuite, backend: &impl OpenMlsCryptoPr
             bob_group.export_secret(backend, "before load", &[], 32)
         );
 
-        // Check that the state flag gets reset when saving
-        assert_eq!(bob_group.state_changed(), InnerState::Changed);
-
-        bob_group
-            .save(backend)
-            .expect("Could not write group state to file");
-
-        // Check that the state flag gets reset when saving
-        assert_eq!(bob_group.state_changed(), InnerState::Persisted);
-
+        // The group state was written when Bob joined the group
         let bob_group = MlsGroup::load(&group_id, backend).expect("Could not load group from file");
 
         // Make sure the state is still the same
@@ -1083,3 +1090,47 @@ fn mls_group_ratchet_tree_extension(
         assert_eq!(error, WelcomeError::MissingRatchetTree);
     }
 }
+
+// Creating groups and key packages for a ciphersuite the provider doesn't
+// support must fail up front with a dedicated error.
+#[test]
+fn unsupported_ciphersuite() {
+    let backend = &openmls_rust_crypto::OpenMlsRustCrypto::default();
+    let ciphersuite = Ciphersuite::MLS_256_DHKEMX448_AES256GCM_SHA512_Ed448;
+    assert!(backend.crypto().supports(ciphersuite).is_err());
+    assert!(backend
+        .crypto()
+        .supports_signature_scheme(SignatureScheme::ED448)
+        .is_err());
+
+    // We can't generate Ed448 keys, so we use an Ed25519 signer here. The
+    // ciphersuite check has to happen before the signer is looked at.
+    let (alice_credential, alice_signer) = new_credential(
+        backend,
+        b"Alice",
+        CredentialType::Basic,
+        SignatureScheme::ED25519,
+    );
+
+    let error = MlsGroup::new(
+        backend,
+        &alice_signer,
+        &MlsGroupConfig::test_default(ciphersuite),
+        alice_credential.clone(),
+    )
+    .expect_err("Created a group with an unsupported ciphersuite.");
+    assert_eq!(error, NewGroupError::UnsupportedCiphersuite);
+
+    let error = KeyPackage::builder()
+        .build(
+            CryptoConfig {
+                ciphersuite,
+                version: ProtocolVersion::default(),
+            },
+            backend,
+            &alice_signer,
+            alice_credential,
+        )
+        .expect_err("Created a key package with an unsupported ciphersuite.");
+    assert_eq!(error, KeyPackageNewError::UnsupportedCiphersuite);
+}
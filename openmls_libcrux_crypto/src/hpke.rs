@@ -0,0 +1,163 @@
+//! # HPKE on libcrux
+//!
+//! [`HpkeLibcrux`] implements the primitives that `hpke-rs` needs with
+//! libcrux, so that HPKE only uses formally verified implementations.
+
+use hpke_rs_crypto::{
+    error::Error,
+    types::{AeadAlgorithm, KdfAlgorithm, KemAlgorithm},
+    HpkeCrypto,
+};
+use hpke_rs_rust_crypto::HpkeRustCrypto;
+use libcrux::hkdf;
+use openmls_traits::types::{AeadType, CryptoError, HashType, HpkeKemType};
+
+use crate::primitives::{self, RAW_KEY_LEN};
+
+/// The HPKE primitives of libcrux.
+#[derive(Debug)]
+pub(crate) struct HpkeLibcrux;
+
+fn kdf_hash(alg: KdfAlgorithm) -> HashType {
+    match alg {
+        KdfAlgorithm::HkdfSha256 => HashType::Sha2_256,
+        KdfAlgorithm::HkdfSha384 => HashType::Sha2_384,
+        KdfAlgorithm::HkdfSha512 => HashType::Sha2_512,
+    }
+}
+
+fn aead_type(alg: AeadAlgorithm) -> Result<AeadType, Error> {
+    match alg {
+        AeadAlgorithm::Aes128Gcm => Ok(AeadType::Aes128Gcm),
+        AeadAlgorithm::Aes256Gcm => Ok(AeadType::Aes256Gcm),
+        AeadAlgorithm::ChaCha20Poly1305 => Ok(AeadType::ChaCha20Poly1305),
+        AeadAlgorithm::HpkeExport => Err(Error::UnknownAeadAlgorithm),
+    }
+}
+
+fn library_error(e: CryptoError) -> Error {
+    Error::CryptoLibraryError(format!("libcrux error: {e}"))
+}
+
+impl HpkeCrypto for HpkeLibcrux {
+    type HpkePrng = <HpkeRustCrypto as HpkeCrypto>::HpkePrng;
+
+    fn name() -> String {
+        "libcrux".into()
+    }
+
+    fn kdf_extract(alg: KdfAlgorithm, salt: &[u8], ikm: &[u8]) -> Vec<u8> {
+        hkdf::extract(primitives::hkdf_algorithm(kdf_hash(alg)), salt, ikm)
+    }
+
+    fn kdf_expand(
+        alg: KdfAlgorithm,
+        prk: &[u8],
+        info: &[u8],
+        output_size: usize,
+    ) -> Result<Vec<u8>, Error> {
+        primitives::hkdf_expand(kdf_hash(alg), prk, info, output_size).map_err(|e| match e {
+            CryptoError::HkdfOutputLengthInvalid => Error::HpkeInvalidOutputLength,
+            e => library_error(e),
+        })
+    }
+
+    fn kem_derive(alg: KemAlgorithm, pk: &[u8], sk: &[u8]) -> Result<Vec<u8>, Error> {
+        Self::kem_validate_sk(alg, sk)?;
+        primitives::x25519(sk, pk).map_err(|_| Error::KemInvalidPublicKey)
+    }
+
+    fn kem_derive_base(alg: KemAlgorithm, sk: &[u8]) -> Result<Vec<u8>, Error> {
+        Self::kem_validate_sk(alg, sk)?;
+        primitives::x25519_public_key(sk).map_err(|_| Error::KemInvalidSecretKey)
+    }
+
+    fn kem_key_gen(alg: KemAlgorithm, prng: &mut Self::HpkePrng) -> Result<Vec<u8>, Error> {
+        Self::supports_kem(alg)?;
+        primitives::x25519_key_gen(prng)
+            .map(|(sk, _)| sk)
+            .map_err(library_error)
+    }
+
+    fn kem_validate_sk(alg: KemAlgorithm, sk: &[u8]) -> Result<Vec<u8>, Error> {
+        Self::supports_kem(alg)?;
+        if sk.len() != RAW_KEY_LEN {
+            return Err(Error::KemInvalidSecretKey);
+        }
+        Ok(sk.into())
+    }
+
+    fn aead_seal(
+        alg: AeadAlgorithm,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        msg: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        primitives::aead_seal(aead_type(alg)?, key, nonce, aad, msg).map_err(library_error)
+    }
+
+    fn aead_open(
+        alg: AeadAlgorithm,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        msg: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        primitives::aead_open(aead_type(alg)?, key, nonce, aad, msg)
+            .map_err(|_| Error::AeadOpenError)
+    }
+
+    fn prng() -> Self::HpkePrng {
+        HpkeRustCrypto::prng()
+    }
+
+    fn supports_kem(alg: KemAlgorithm) -> Result<(), Error> {
+        match alg {
+            KemAlgorithm::DhKem25519 => Ok(()),
+            _ => Err(Error::UnknownKemAlgorithm),
+        }
+    }
+
+    fn supports_kdf(_alg: KdfAlgorithm) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn supports_aead(alg: AeadAlgorithm) -> Result<(), Error> {
+        match alg {
+            AeadAlgorithm::HpkeExport => Ok(()),
+            _ if primitives::aead_available(aead_type(alg)?) => Ok(()),
+            _ => Err(Error::UnknownAeadAlgorithm),
+        }
+    }
+}
+
+/// Derive a key pair for DHKEM(X25519) from `ikm` as specified in Section
+/// 7.1.3 of RFC 9180. Returns `(private key, public key)`.
+///
+/// Returns an error for all other KEMs.
+pub(crate) fn derive_key_pair(
+    kem: HpkeKemType,
+    ikm: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+    if kem != HpkeKemType::DhKem25519 {
+        return Err(CryptoError::UnsupportedKem);
+    }
+    let suite_id = [b"KEM".as_slice(), &(kem as u16).to_be_bytes()].concat();
+
+    // LabeledExtract("", "dkp_prk", ikm)
+    let labeled_ikm = [b"HPKE-v1".as_slice(), suite_id.as_slice(), b"dkp_prk", ikm].concat();
+    let dkp_prk = HpkeLibcrux::kdf_extract(KdfAlgorithm::HkdfSha256, &[], &labeled_ikm);
+
+    // LabeledExpand(dkp_prk, "sk", "", Nsk)
+    let labeled_info = [
+        (RAW_KEY_LEN as u16).to_be_bytes().as_slice(),
+        b"HPKE-v1",
+        suite_id.as_slice(),
+        b"sk",
+    ]
+    .concat();
+    let sk = primitives::hkdf_expand(HashType::Sha2_256, &dkp_prk, &labeled_info, RAW_KEY_LEN)?;
+    let pk = primitives::x25519_public_key(&sk)?;
+    Ok((sk, pk))
+}
@@ -0,0 +1,37 @@
+//! # OpenMLS libcrux Crypto Provider
+//!
+//! This is an implementation of the [`OpenMlsCryptoProvider`] trait on top of
+//! [libcrux](https://github.com/cryspen/libcrux), whose primitives are
+//! formally verified.
+
+pub use openmls_memory_keystore::{MemoryKeyStore, MemoryKeyStoreError};
+use openmls_traits::OpenMlsCryptoProvider;
+
+mod hpke;
+mod primitives;
+mod provider;
+pub use provider::*;
+
+#[derive(Default, Debug)]
+pub struct OpenMlsLibcrux {
+    crypto: LibcruxCrypto,
+    key_store: MemoryKeyStore,
+}
+
+impl OpenMlsCryptoProvider for OpenMlsLibcrux {
+    type CryptoProvider = LibcruxCrypto;
+    type RandProvider = LibcruxCrypto;
+    type KeyStoreProvider = MemoryKeyStore;
+
+    fn crypto(&self) -> &Self::CryptoProvider {
+        &self.crypto
+    }
+
+    fn rand(&self) -> &Self::RandProvider {
+        &self.crypto
+    }
+
+    fn key_store(&self) -> &Self::KeyStoreProvider {
+        &self.key_store
+    }
+}
@@ -0,0 +1,161 @@
+//! # libcrux primitives
+//!
+//! Thin wrappers around `libcrux` that work on the byte encodings that
+//! OpenMLS uses, i.e. ciphertexts with the tag appended and Ed25519 private
+//! keys that may be followed by the public key.
+
+use libcrux::{
+    aead::{self, Iv, Key, Tag},
+    digest, ecdh, hkdf, signature,
+};
+use openmls_traits::types::{AeadType, CryptoError, HashType};
+use rand::{CryptoRng, RngCore};
+
+/// The length of the tags of all supported AEADs.
+const AEAD_TAG_LEN: usize = 16;
+
+/// The length of X25519 and Ed25519 private keys.
+pub(crate) const RAW_KEY_LEN: usize = 32;
+
+/// Maps all errors of libcrux to a [`CryptoError::CryptoLibraryError`].
+pub(crate) fn library_error<E>(_: E) -> CryptoError {
+    CryptoError::CryptoLibraryError
+}
+
+pub(crate) fn digest_algorithm(hash_type: HashType) -> digest::Algorithm {
+    match hash_type {
+        HashType::Sha2_256 => digest::Algorithm::Sha256,
+        HashType::Sha2_384 => digest::Algorithm::Sha384,
+        HashType::Sha2_512 => digest::Algorithm::Sha512,
+    }
+}
+
+pub(crate) fn hkdf_algorithm(hash_type: HashType) -> hkdf::Algorithm {
+    match hash_type {
+        HashType::Sha2_256 => hkdf::Algorithm::Sha256,
+        HashType::Sha2_384 => hkdf::Algorithm::Sha384,
+        HashType::Sha2_512 => hkdf::Algorithm::Sha512,
+    }
+}
+
+pub(crate) fn aead_algorithm(alg: AeadType) -> aead::Algorithm {
+    match alg {
+        AeadType::Aes128Gcm => aead::Algorithm::Aes128Gcm,
+        AeadType::Aes256Gcm => aead::Algorithm::Aes256Gcm,
+        AeadType::ChaCha20Poly1305 => aead::Algorithm::Chacha20Poly1305,
+    }
+}
+
+/// HKDF-Expand from RFC 5869.
+///
+/// Returns a [`CryptoError::HkdfOutputLengthInvalid`] if `okm_len` is larger
+/// than 255 times the length of the hash.
+pub(crate) fn hkdf_expand(
+    hash_type: HashType,
+    prk: &[u8],
+    info: &[u8],
+    okm_len: usize,
+) -> Result<Vec<u8>, CryptoError> {
+    if okm_len > 255 * hash_type.size() {
+        return Err(CryptoError::HkdfOutputLengthInvalid);
+    }
+    hkdf::expand(hkdf_algorithm(hash_type), prk, info, okm_len).map_err(library_error)
+}
+
+/// Encrypt `msg` and return the ciphertext with the tag appended.
+pub(crate) fn aead_seal(
+    alg: AeadType,
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    msg: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let key = Key::from_slice(aead_algorithm(alg), key).map_err(|_| CryptoError::InvalidLength)?;
+    let iv = Iv::new(nonce).map_err(|_| CryptoError::InvalidLength)?;
+    let (tag, mut ciphertext) =
+        aead::encrypt_detached(&key, msg, iv, aad).map_err(library_error)?;
+    ciphertext.extend_from_slice(tag.as_ref());
+    Ok(ciphertext)
+}
+
+/// Decrypt the ciphertext `ct_tag` with the tag appended.
+///
+/// Returns a [`CryptoError::AeadDecryptionError`] if the ciphertext is not
+/// authentic.
+pub(crate) fn aead_open(
+    alg: AeadType,
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    ct_tag: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let key = Key::from_slice(aead_algorithm(alg), key).map_err(|_| CryptoError::InvalidLength)?;
+    let iv = Iv::new(nonce).map_err(|_| CryptoError::InvalidLength)?;
+    if ct_tag.len() < AEAD_TAG_LEN {
+        return Err(CryptoError::AeadDecryptionError);
+    }
+    let (ciphertext, tag) = ct_tag.split_at(ct_tag.len() - AEAD_TAG_LEN);
+    let tag = Tag::from_slice(tag).map_err(|_| CryptoError::AeadDecryptionError)?;
+    aead::decrypt_detached(&key, ciphertext, iv, aad, &tag)
+        .map_err(|_| CryptoError::AeadDecryptionError)
+}
+
+/// Returns `true` if libcrux can compute `alg` on this platform.
+///
+/// The AES-GCM implementations of libcrux require hardware support for AES,
+/// so we probe them once with an all-zero key.
+pub(crate) fn aead_available(alg: AeadType) -> bool {
+    let key = vec![0u8; alg.key_size()];
+    let nonce = vec![0u8; alg.nonce_size()];
+    aead_seal(alg, &key, &nonce, &[], &[]).is_ok()
+}
+
+// === X25519 === //
+
+pub(crate) fn x25519_key_gen(
+    rng: &mut (impl CryptoRng + RngCore),
+) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+    ecdh::key_gen(ecdh::Algorithm::X25519, rng).map_err(library_error)
+}
+
+pub(crate) fn x25519_public_key(sk: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    ecdh::secret_to_public(ecdh::Algorithm::X25519, sk).map_err(library_error)
+}
+
+pub(crate) fn x25519(sk: &[u8], pk: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    ecdh::derive(ecdh::Algorithm::X25519, pk, sk).map_err(library_error)
+}
+
+// === Ed25519 === //
+
+/// Generate an Ed25519 key pair. The private key is the seed followed by the
+/// public key, as in the other providers.
+pub(crate) fn ed25519_key_gen(
+    rng: &mut (impl CryptoRng + RngCore),
+) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+    let (seed, pk) =
+        signature::key_gen(signature::Algorithm::Ed25519, rng).map_err(library_error)?;
+    Ok(([seed, pk.clone()].concat(), pk))
+}
+
+/// Sign `data` with an Ed25519 private key. Accepts both the seed and the
+/// seed followed by the public key.
+pub(crate) fn ed25519_sign(
+    key: &[u8],
+    data: &[u8],
+    rng: &mut (impl CryptoRng + RngCore),
+) -> Result<Vec<u8>, CryptoError> {
+    let seed = key
+        .get(..RAW_KEY_LEN)
+        .ok_or(CryptoError::CryptoLibraryError)?;
+    signature::sign(signature::Algorithm::Ed25519, data, seed, rng)
+        .map(|signature| signature.into_vec())
+        .map_err(library_error)
+}
+
+pub(crate) fn ed25519_verify(pk: &[u8], data: &[u8], signature: &[u8]) -> Result<(), CryptoError> {
+    let signature = signature::Ed25519Signature::from_slice(signature)
+        .map_err(|_| CryptoError::InvalidSignature)?;
+    signature::verify(data, &signature::Signature::Ed25519(signature), pk)
+        .map_err(|_| CryptoError::InvalidSignature)
+}
@@ -0,0 +1,329 @@
+use std::sync::RwLock;
+
+use hpke::Hpke;
+use hpke_rs_crypto::types as hpke_types;
+use libcrux::{digest, hkdf};
+use openmls_traits::{
+    crypto::OpenMlsCrypto,
+    random::OpenMlsRand,
+    types::{
+        self, AeadType, Ciphersuite, CryptoError, ExporterSecret, HashType, HpkeAeadType,
+        HpkeCiphertext, HpkeConfig, HpkeKdfType, HpkeKemType, HpkeKeyPair, SignatureScheme,
+    },
+};
+use rand::{RngCore, SeedableRng};
+use tls_codec::SecretVLBytes;
+
+use crate::{
+    hpke::{self as libcrux_hpke, HpkeLibcrux},
+    primitives,
+};
+
+/// The crypto primitives of libcrux.
+///
+/// The AES-GCM implementations of libcrux need hardware support for AES. The
+/// AES-GCM ciphersuites are only supported if it's available, which is
+/// checked when the provider is created.
+#[derive(Debug)]
+pub struct LibcruxCrypto {
+    aes_support: bool,
+    rng: RwLock<rand_chacha::ChaCha20Rng>,
+}
+
+impl Default for LibcruxCrypto {
+    fn default() -> Self {
+        Self {
+            aes_support: primitives::aead_available(AeadType::Aes128Gcm)
+                && primitives::aead_available(AeadType::Aes256Gcm),
+            rng: RwLock::new(rand_chacha::ChaCha20Rng::from_entropy()),
+        }
+    }
+}
+
+#[inline(always)]
+fn kem_mode(kem: HpkeKemType) -> Result<hpke_types::KemAlgorithm, CryptoError> {
+    match kem {
+        HpkeKemType::DhKem25519 => Ok(hpke_types::KemAlgorithm::DhKem25519),
+        _ => Err(CryptoError::UnsupportedKem),
+    }
+}
+
+#[inline(always)]
+fn kdf_mode(kdf: HpkeKdfType) -> hpke_types::KdfAlgorithm {
+    match kdf {
+        HpkeKdfType::HkdfSha256 => hpke_types::KdfAlgorithm::HkdfSha256,
+        HpkeKdfType::HkdfSha384 => hpke_types::KdfAlgorithm::HkdfSha384,
+        HpkeKdfType::HkdfSha512 => hpke_types::KdfAlgorithm::HkdfSha512,
+    }
+}
+
+#[inline(always)]
+fn aead_mode(aead: HpkeAeadType) -> hpke_types::AeadAlgorithm {
+    match aead {
+        HpkeAeadType::AesGcm128 => hpke_types::AeadAlgorithm::Aes128Gcm,
+        HpkeAeadType::AesGcm256 => hpke_types::AeadAlgorithm::Aes256Gcm,
+        HpkeAeadType::ChaCha20Poly1305 => hpke_types::AeadAlgorithm::ChaCha20Poly1305,
+        HpkeAeadType::Export => hpke_types::AeadAlgorithm::HpkeExport,
+    }
+}
+
+impl LibcruxCrypto {
+    fn check_aead(&self, alg: AeadType) -> Result<(), CryptoError> {
+        match alg {
+            AeadType::ChaCha20Poly1305 => Ok(()),
+            AeadType::Aes128Gcm | AeadType::Aes256Gcm if self.aes_support => Ok(()),
+            _ => Err(CryptoError::UnsupportedAeadAlgorithm),
+        }
+    }
+}
+
+impl OpenMlsCrypto for LibcruxCrypto {
+    fn supports(&self, ciphersuite: Ciphersuite) -> Result<(), CryptoError> {
+        match ciphersuite {
+            Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519 => Ok(()),
+            Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519 if self.aes_support => Ok(()),
+            _ => Err(CryptoError::UnsupportedCiphersuite),
+        }
+    }
+
+    fn supported_ciphersuites(&self) -> Vec<Ciphersuite> {
+        let mut ciphersuites =
+            vec![Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519];
+        if self.aes_support {
+            ciphersuites.push(Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519);
+        }
+        ciphersuites
+    }
+
+    fn supports_signature_scheme(&self, scheme: SignatureScheme) -> Result<(), CryptoError> {
+        match scheme {
+            SignatureScheme::ED25519 => Ok(()),
+            _ => Err(CryptoError::UnsupportedSignatureScheme),
+        }
+    }
+
+    fn supports_aead(&self, aead: AeadType) -> Result<(), CryptoError> {
+        self.check_aead(aead)
+    }
+
+    fn supports_hash(&self, _hash_type: HashType) -> Result<(), CryptoError> {
+        Ok(())
+    }
+
+    fn supports_hpke_kem(&self, kem: HpkeKemType) -> Result<(), CryptoError> {
+        kem_mode(kem).map(|_| ())
+    }
+
+    fn hkdf_extract(
+        &self,
+        hash_type: HashType,
+        salt: &[u8],
+        ikm: &[u8],
+    ) -> Result<SecretVLBytes, CryptoError> {
+        let prk = hkdf::extract(primitives::hkdf_algorithm(hash_type), salt, ikm);
+        Ok(prk.as_slice().into())
+    }
+
+    fn hkdf_expand(
+        &self,
+        hash_type: HashType,
+        prk: &[u8],
+        info: &[u8],
+        okm_len: usize,
+    ) -> Result<SecretVLBytes, CryptoError> {
+        primitives::hkdf_expand(hash_type, prk, info, okm_len).map(|okm| okm.as_slice().into())
+    }
+
+    fn hash(&self, hash_type: HashType, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        Ok(digest::hash(primitives::digest_algorithm(hash_type), data))
+    }
+
+    fn aead_encrypt(
+        &self,
+        alg: AeadType,
+        key: &[u8],
+        data: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        self.check_aead(alg)?;
+        primitives::aead_seal(alg, key, nonce, aad, data)
+            .map_err(|_| CryptoError::CryptoLibraryError)
+    }
+
+    fn aead_decrypt(
+        &self,
+        alg: AeadType,
+        key: &[u8],
+        ct_tag: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        self.check_aead(alg)?;
+        primitives::aead_open(alg, key, nonce, aad, ct_tag)
+    }
+
+    fn signature_key_gen(&self, alg: SignatureScheme) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        match alg {
+            SignatureScheme::ED25519 => {
+                let mut rng = self
+                    .rng
+                    .write()
+                    .map_err(|_| CryptoError::InsufficientRandomness)?;
+                primitives::ed25519_key_gen(&mut *rng)
+            }
+            _ => Err(CryptoError::UnsupportedSignatureScheme),
+        }
+    }
+
+    fn verify_signature(
+        &self,
+        alg: SignatureScheme,
+        data: &[u8],
+        pk: &[u8],
+        signature: &[u8],
+    ) -> Result<(), CryptoError> {
+        match alg {
+            SignatureScheme::ED25519 => primitives::ed25519_verify(pk, data, signature),
+            _ => Err(CryptoError::UnsupportedSignatureScheme),
+        }
+    }
+
+    fn sign(&self, alg: SignatureScheme, data: &[u8], key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        match alg {
+            SignatureScheme::ED25519 => {
+                // Ed25519 signatures are deterministic, the randomness is
+                // only required by the interface of libcrux.
+                let mut rng = self
+                    .rng
+                    .write()
+                    .map_err(|_| CryptoError::InsufficientRandomness)?;
+                primitives::ed25519_sign(key, data, &mut *rng)
+            }
+            _ => Err(CryptoError::UnsupportedSignatureScheme),
+        }
+    }
+
+    fn hpke_seal(
+        &self,
+        config: HpkeConfig,
+        pk_r: &[u8],
+        info: &[u8],
+        aad: &[u8],
+        ptxt: &[u8],
+    ) -> Result<types::HpkeCiphertext, CryptoError> {
+        let (kem_output, ciphertext) = hpke_from_config(config)?
+            .seal(&pk_r.into(), info, aad, ptxt, None, None, None)
+            .map_err(|_| CryptoError::HpkeEncryptionError)?;
+        Ok(HpkeCiphertext {
+            kem_output: kem_output.into(),
+            ciphertext: ciphertext.into(),
+        })
+    }
+
+    fn hpke_open(
+        &self,
+        config: HpkeConfig,
+        input: &types::HpkeCiphertext,
+        sk_r: &[u8],
+        info: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        hpke_from_config(config)?
+            .open(
+                input.kem_output.as_slice(),
+                &sk_r.into(),
+                info,
+                aad,
+                input.ciphertext.as_slice(),
+                None,
+                None,
+                None,
+            )
+            .map_err(|_| CryptoError::HpkeDecryptionError)
+    }
+
+    fn hpke_setup_sender_and_export(
+        &self,
+        config: HpkeConfig,
+        pk_r: &[u8],
+        info: &[u8],
+        exporter_context: &[u8],
+        exporter_length: usize,
+    ) -> Result<(Vec<u8>, ExporterSecret), CryptoError> {
+        let (kem_output, context) = hpke_from_config(config)?
+            .setup_sender(&pk_r.into(), info, None, None, None)
+            .map_err(|_| CryptoError::SenderSetupError)?;
+        let exported_secret = context
+            .export(exporter_context, exporter_length)
+            .map_err(|_| CryptoError::ExporterError)?;
+        Ok((kem_output, exported_secret.into()))
+    }
+
+    fn hpke_setup_receiver_and_export(
+        &self,
+        config: HpkeConfig,
+        enc: &[u8],
+        sk_r: &[u8],
+        info: &[u8],
+        exporter_context: &[u8],
+        exporter_length: usize,
+    ) -> Result<ExporterSecret, CryptoError> {
+        let context = hpke_from_config(config)?
+            .setup_receiver(enc, &sk_r.into(), info, None, None, None)
+            .map_err(|_| CryptoError::ReceiverSetupError)?;
+        let exported_secret = context
+            .export(exporter_context, exporter_length)
+            .map_err(|_| CryptoError::ExporterError)?;
+        Ok(exported_secret.into())
+    }
+
+    fn derive_hpke_keypair(
+        &self,
+        config: HpkeConfig,
+        ikm: &[u8],
+    ) -> Result<types::HpkeKeyPair, CryptoError> {
+        let (private, public) = libcrux_hpke::derive_key_pair(config.0, ikm)?;
+        Ok(HpkeKeyPair {
+            private: private.into(),
+            public,
+        })
+    }
+}
+
+fn hpke_from_config(config: HpkeConfig) -> Result<Hpke<HpkeLibcrux>, CryptoError> {
+    Ok(Hpke::<HpkeLibcrux>::new(
+        hpke::Mode::Base,
+        kem_mode(config.0)?,
+        kdf_mode(config.1),
+        aead_mode(config.2),
+    ))
+}
+
+impl OpenMlsRand for LibcruxCrypto {
+    type Error = RandError;
+
+    fn random_array<const N: usize>(&self) -> Result<[u8; N], Self::Error> {
+        let mut rng = self.rng.write().map_err(|_| Self::Error::LockPoisoned)?;
+        let mut out = [0u8; N];
+        rng.try_fill_bytes(&mut out)
+            .map_err(|_| Self::Error::NotEnoughRandomness)?;
+        Ok(out)
+    }
+
+    fn random_vec(&self, len: usize) -> Result<Vec<u8>, Self::Error> {
+        let mut rng = self.rng.write().map_err(|_| Self::Error::LockPoisoned)?;
+        let mut out = vec![0u8; len];
+        rng.try_fill_bytes(&mut out)
+            .map_err(|_| Self::Error::NotEnoughRandomness)?;
+        Ok(out)
+    }
+}
+
+#[derive(thiserror::Error, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RandError {
+    #[error("Rng lock is poisoned.")]
+    LockPoisoned,
+    #[error("Unable to collect enough randomness.")]
+    NotEnoughRandomness,
+}
@@ -0,0 +1,6 @@
+use openmls_libcrux_crypto::OpenMlsLibcrux;
+
+#[test]
+fn libcrux_conformance() {
+    openmls_provider_tests::run_all(&OpenMlsLibcrux::default());
+}
@@ -0,0 +1,227 @@
+//! # HPKE on OpenSSL
+//!
+//! [`HpkeOpenSsl`] implements the primitives that `hpke-rs` needs with
+//! OpenSSL, so that HPKE doesn't use any other cryptographic implementation.
+
+use hpke_rs_crypto::{
+    error::Error,
+    types::{AeadAlgorithm, KdfAlgorithm, KemAlgorithm},
+    HpkeCrypto,
+};
+use hpke_rs_rust_crypto::HpkeRustCrypto;
+use openmls_traits::types::{CryptoError, HpkeKemType};
+use openssl::{hash::MessageDigest, nid::Nid, pkey::Id, symm::Cipher};
+
+use crate::primitives::{self, RAW_KEY_LEN};
+
+/// The HPKE primitives of OpenSSL.
+#[derive(Debug)]
+pub(crate) struct HpkeOpenSsl;
+
+fn kdf_digest(alg: KdfAlgorithm) -> MessageDigest {
+    match alg {
+        KdfAlgorithm::HkdfSha256 => MessageDigest::sha256(),
+        KdfAlgorithm::HkdfSha384 => MessageDigest::sha384(),
+        KdfAlgorithm::HkdfSha512 => MessageDigest::sha512(),
+    }
+}
+
+/// Returns the curve of a DHKEM on a NIST curve or [`None`] for all other
+/// KEMs.
+fn kem_curve(alg: KemAlgorithm) -> Option<Nid> {
+    match alg {
+        KemAlgorithm::DhKemP256 => Some(Nid::X9_62_PRIME256V1),
+        KemAlgorithm::DhKemP384 => Some(Nid::SECP384R1),
+        KemAlgorithm::DhKemP521 => Some(Nid::SECP521R1),
+        _ => None,
+    }
+}
+
+fn aead_cipher(alg: AeadAlgorithm) -> Result<Cipher, Error> {
+    match alg {
+        AeadAlgorithm::Aes128Gcm => Ok(Cipher::aes_128_gcm()),
+        AeadAlgorithm::Aes256Gcm => Ok(Cipher::aes_256_gcm()),
+        AeadAlgorithm::ChaCha20Poly1305 => Ok(Cipher::chacha20_poly1305()),
+        AeadAlgorithm::HpkeExport => Err(Error::UnknownAeadAlgorithm),
+    }
+}
+
+fn library_error(e: CryptoError) -> Error {
+    Error::CryptoLibraryError(format!("OpenSSL error: {e}"))
+}
+
+impl HpkeCrypto for HpkeOpenSsl {
+    // Keys are generated by OpenSSL, so the PRNG of `hpke-rs` is only needed
+    // to satisfy the trait.
+    type HpkePrng = <HpkeRustCrypto as HpkeCrypto>::HpkePrng;
+
+    fn name() -> String {
+        "OpenSSL".into()
+    }
+
+    fn kdf_extract(alg: KdfAlgorithm, salt: &[u8], ikm: &[u8]) -> Vec<u8> {
+        // The trait doesn't allow returning an error. HMAC only fails if
+        // OpenSSL is broken.
+        primitives::hkdf_extract(kdf_digest(alg), salt, ikm).expect("HKDF-Extract failed")
+    }
+
+    fn kdf_expand(
+        alg: KdfAlgorithm,
+        prk: &[u8],
+        info: &[u8],
+        output_size: usize,
+    ) -> Result<Vec<u8>, Error> {
+        primitives::hkdf_expand(kdf_digest(alg), prk, info, output_size).map_err(|e| match e {
+            CryptoError::HkdfOutputLengthInvalid => Error::HpkeInvalidOutputLength,
+            e => library_error(e),
+        })
+    }
+
+    fn kem_derive(alg: KemAlgorithm, pk: &[u8], sk: &[u8]) -> Result<Vec<u8>, Error> {
+        Self::kem_validate_sk(alg, sk)?;
+        match alg {
+            KemAlgorithm::DhKem25519 => primitives::x25519(sk, pk),
+            _ => match kem_curve(alg) {
+                Some(curve) => primitives::ecdh(curve, sk, pk),
+                None => return Err(Error::UnknownKemAlgorithm),
+            },
+        }
+        .map_err(|_| Error::KemInvalidPublicKey)
+    }
+
+    fn kem_derive_base(alg: KemAlgorithm, sk: &[u8]) -> Result<Vec<u8>, Error> {
+        match alg {
+            KemAlgorithm::DhKem25519 => primitives::raw_public_key(Id::X25519, sk),
+            _ => match kem_curve(alg) {
+                Some(curve) => primitives::ec_private_key(curve, sk)
+                    .and_then(|key| primitives::ec_public_key_bytes(&key)),
+                None => return Err(Error::UnknownKemAlgorithm),
+            },
+        }
+        .map_err(|_| Error::KemInvalidSecretKey)
+    }
+
+    fn kem_key_gen(alg: KemAlgorithm, _prng: &mut Self::HpkePrng) -> Result<Vec<u8>, Error> {
+        match alg {
+            KemAlgorithm::DhKem25519 => primitives::raw_key_gen(Id::X25519),
+            _ => match kem_curve(alg) {
+                Some(curve) => primitives::ec_key_gen(curve),
+                None => return Err(Error::UnknownKemAlgorithm),
+            },
+        }
+        .map(|(sk, _)| sk)
+        .map_err(library_error)
+    }
+
+    fn kem_validate_sk(alg: KemAlgorithm, sk: &[u8]) -> Result<Vec<u8>, Error> {
+        match alg {
+            KemAlgorithm::DhKem25519 if sk.len() == RAW_KEY_LEN => Ok(sk.into()),
+            KemAlgorithm::DhKem25519 => Err(Error::KemInvalidSecretKey),
+            _ => match kem_curve(alg) {
+                Some(curve) => primitives::ec_private_key(curve, sk)
+                    .map(|_| sk.into())
+                    .map_err(|_| Error::KemInvalidSecretKey),
+                None => Err(Error::UnknownKemAlgorithm),
+            },
+        }
+    }
+
+    fn aead_seal(
+        alg: AeadAlgorithm,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        msg: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        primitives::aead_seal(aead_cipher(alg)?, key, nonce, aad, msg).map_err(library_error)
+    }
+
+    fn aead_open(
+        alg: AeadAlgorithm,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        msg: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        primitives::aead_open(aead_cipher(alg)?, key, nonce, aad, msg)
+            .map_err(|_| Error::AeadOpenError)
+    }
+
+    fn prng() -> Self::HpkePrng {
+        HpkeRustCrypto::prng()
+    }
+
+    fn supports_kem(alg: KemAlgorithm) -> Result<(), Error> {
+        match alg {
+            KemAlgorithm::DhKem25519
+            | KemAlgorithm::DhKemP256
+            | KemAlgorithm::DhKemP384
+            | KemAlgorithm::DhKemP521 => Ok(()),
+            _ => Err(Error::UnknownKemAlgorithm),
+        }
+    }
+
+    fn supports_kdf(_alg: KdfAlgorithm) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn supports_aead(_alg: AeadAlgorithm) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Derive a key pair for the DHKEM `kem` from `ikm` as specified in Section
+/// 7.1.3 of RFC 9180. Returns `(private key, public key)`.
+///
+/// Returns an error for unsupported KEMs or if no valid private key could be
+/// derived.
+pub(crate) fn derive_key_pair(
+    kem: HpkeKemType,
+    ikm: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+    let (alg, kdf, private_key_len, bitmask) = match kem {
+        HpkeKemType::DhKem25519 => (KemAlgorithm::DhKem25519, KdfAlgorithm::HkdfSha256, 32, 0),
+        HpkeKemType::DhKemP256 => (KemAlgorithm::DhKemP256, KdfAlgorithm::HkdfSha256, 32, 0xff),
+        HpkeKemType::DhKemP384 => (KemAlgorithm::DhKemP384, KdfAlgorithm::HkdfSha384, 48, 0xff),
+        HpkeKemType::DhKemP521 => (KemAlgorithm::DhKemP521, KdfAlgorithm::HkdfSha512, 66, 0x01),
+        _ => return Err(CryptoError::UnsupportedKem),
+    };
+    let md = kdf_digest(kdf);
+    let suite_id = [b"KEM".as_slice(), &(kem as u16).to_be_bytes()].concat();
+    let labeled_info = |label: &[u8], info: &[u8]| {
+        [
+            (private_key_len as u16).to_be_bytes().as_slice(),
+            b"HPKE-v1",
+            suite_id.as_slice(),
+            label,
+            info,
+        ]
+        .concat()
+    };
+
+    // LabeledExtract("", "dkp_prk", ikm)
+    let labeled_ikm = [b"HPKE-v1".as_slice(), suite_id.as_slice(), b"dkp_prk", ikm].concat();
+    let dkp_prk = primitives::hkdf_extract(md, &[], &labeled_ikm)?;
+
+    if matches!(alg, KemAlgorithm::DhKem25519) {
+        // LabeledExpand(dkp_prk, "sk", "", Nsk)
+        let sk = primitives::hkdf_expand(md, &dkp_prk, &labeled_info(b"sk", &[]), private_key_len)?;
+        let pk = primitives::raw_public_key(Id::X25519, &sk)?;
+        return Ok((sk, pk));
+    }
+
+    for counter in 0..=u8::MAX {
+        // LabeledExpand(dkp_prk, "candidate", I2OSP(counter, 1), Nsk)
+        let mut candidate = primitives::hkdf_expand(
+            md,
+            &dkp_prk,
+            &labeled_info(b"candidate", &[counter]),
+            private_key_len,
+        )?;
+        candidate[0] &= bitmask;
+        if let Ok(public) = HpkeOpenSsl::kem_derive_base(alg, &candidate) {
+            return Ok((candidate, public));
+        }
+    }
+    Err(CryptoError::CryptoLibraryError)
+}
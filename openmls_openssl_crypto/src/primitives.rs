@@ -0,0 +1,280 @@
+//! # OpenSSL primitives
+//!
+//! Thin wrappers around the `openssl` crate that work on the byte encodings
+//! of keys that OpenMLS uses, i.e. raw scalars and uncompressed SEC1 points
+//! for the NIST curves and raw keys for X25519 and Ed25519.
+
+use std::cmp::Ordering;
+
+use openmls_traits::types::CryptoError;
+use openssl::{
+    bn::{BigNum, BigNumContext},
+    derive::Deriver,
+    ec::{EcGroup, EcKey, EcKeyRef, EcPoint, PointConversionForm},
+    error::ErrorStack,
+    hash::{self, MessageDigest},
+    nid::Nid,
+    pkey::{HasPublic, Id, PKey, Private, Public},
+    sign::{Signer, Verifier},
+    symm::{self, Cipher},
+};
+
+/// The length of the tags of all supported AEADs.
+const AEAD_TAG_LEN: usize = 16;
+
+/// Maps all errors of OpenSSL to a [`CryptoError::CryptoLibraryError`].
+pub(crate) fn ossl(_: ErrorStack) -> CryptoError {
+    CryptoError::CryptoLibraryError
+}
+
+pub(crate) fn hash(md: MessageDigest, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    Ok(hash::hash(md, data).map_err(ossl)?.to_vec())
+}
+
+/// HKDF-Extract from RFC 5869.
+pub(crate) fn hkdf_extract(
+    md: MessageDigest,
+    salt: &[u8],
+    ikm: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    // An empty salt is the same as a salt of `HashLen` zeros. OpenSSL may
+    // refuse empty HMAC keys, so we use the latter.
+    let zeros;
+    let salt = if salt.is_empty() {
+        zeros = vec![0u8; md.size()];
+        &zeros
+    } else {
+        salt
+    };
+    let key = PKey::hmac(salt).map_err(ossl)?;
+    let mut signer = Signer::new(md, &key).map_err(ossl)?;
+    signer.update(ikm).map_err(ossl)?;
+    signer.sign_to_vec().map_err(ossl)
+}
+
+/// HKDF-Expand from RFC 5869.
+///
+/// Returns a [`CryptoError::HkdfOutputLengthInvalid`] if `okm_len` is larger
+/// than 255 times the length of the hash.
+pub(crate) fn hkdf_expand(
+    md: MessageDigest,
+    prk: &[u8],
+    info: &[u8],
+    okm_len: usize,
+) -> Result<Vec<u8>, CryptoError> {
+    if okm_len > 255 * md.size() {
+        return Err(CryptoError::HkdfOutputLengthInvalid);
+    }
+    let key = PKey::hmac(prk).map_err(ossl)?;
+    let mut okm = Vec::with_capacity(okm_len + md.size());
+    let mut block = Vec::new();
+    for counter in 1..=u8::MAX {
+        if okm.len() >= okm_len {
+            break;
+        }
+        let mut signer = Signer::new(md, &key).map_err(ossl)?;
+        signer.update(&block).map_err(ossl)?;
+        signer.update(info).map_err(ossl)?;
+        signer.update(&[counter]).map_err(ossl)?;
+        block = signer.sign_to_vec().map_err(ossl)?;
+        okm.extend_from_slice(&block);
+    }
+    okm.truncate(okm_len);
+    Ok(okm)
+}
+
+/// Encrypt `msg` and return the ciphertext with the tag appended.
+pub(crate) fn aead_seal(
+    cipher: Cipher,
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    msg: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    check_aead_parameters(cipher, key, nonce)?;
+    let mut tag = [0u8; AEAD_TAG_LEN];
+    let mut ciphertext =
+        symm::encrypt_aead(cipher, key, Some(nonce), aad, msg, &mut tag).map_err(ossl)?;
+    ciphertext.extend_from_slice(&tag);
+    Ok(ciphertext)
+}
+
+/// Decrypt the ciphertext `ct_tag` with the tag appended.
+///
+/// Returns a [`CryptoError::AeadDecryptionError`] if the ciphertext is not
+/// authentic.
+pub(crate) fn aead_open(
+    cipher: Cipher,
+    key: &[u8],
+    nonce: &[u8],
+    aad: &[u8],
+    ct_tag: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    check_aead_parameters(cipher, key, nonce)?;
+    if ct_tag.len() < AEAD_TAG_LEN {
+        return Err(CryptoError::AeadDecryptionError);
+    }
+    let (ciphertext, tag) = ct_tag.split_at(ct_tag.len() - AEAD_TAG_LEN);
+    symm::decrypt_aead(cipher, key, Some(nonce), aad, ciphertext, tag)
+        .map_err(|_| CryptoError::AeadDecryptionError)
+}
+
+/// OpenSSL silently adjusts the key and nonce lengths of some ciphers, so we
+/// check them up front.
+fn check_aead_parameters(cipher: Cipher, key: &[u8], nonce: &[u8]) -> Result<(), CryptoError> {
+    if key.len() != cipher.key_len() || Some(nonce.len()) != cipher.iv_len() {
+        return Err(CryptoError::InvalidLength);
+    }
+    Ok(())
+}
+
+// === NIST curves === //
+
+/// Returns the length of the private keys on a curve of the given `order`.
+fn scalar_len(order: &BigNum) -> usize {
+    (order.num_bits() as usize + 7) / 8
+}
+
+/// Parse the private key `sk` on the curve `nid`, i.e. a big-endian scalar
+/// that is smaller than the order of the curve.
+pub(crate) fn ec_private_key(nid: Nid, sk: &[u8]) -> Result<EcKey<Private>, CryptoError> {
+    let group = EcGroup::from_curve_name(nid).map_err(ossl)?;
+    let mut ctx = BigNumContext::new().map_err(ossl)?;
+    let mut order = BigNum::new().map_err(ossl)?;
+    group.order(&mut order, &mut ctx).map_err(ossl)?;
+    let d = BigNum::from_slice(sk).map_err(ossl)?;
+    if sk.len() != scalar_len(&order) || d.num_bits() == 0 || d.ucmp(&order) != Ordering::Less {
+        return Err(CryptoError::CryptoLibraryError);
+    }
+    let mut public = EcPoint::new(&group).map_err(ossl)?;
+    public.mul_generator(&group, &d, &ctx).map_err(ossl)?;
+    EcKey::from_private_components(&group, &d, &public).map_err(ossl)
+}
+
+/// Parse the SEC1 encoded public key `pk` on the curve `nid`.
+pub(crate) fn ec_public_key(nid: Nid, pk: &[u8]) -> Result<EcKey<Public>, CryptoError> {
+    let group = EcGroup::from_curve_name(nid).map_err(ossl)?;
+    let mut ctx = BigNumContext::new().map_err(ossl)?;
+    let point = EcPoint::from_bytes(&group, pk, &mut ctx).map_err(ossl)?;
+    let key = EcKey::from_public_key(&group, &point).map_err(ossl)?;
+    key.check_key().map_err(ossl)?;
+    Ok(key)
+}
+
+/// Returns the uncompressed SEC1 encoding of the public key of `key`.
+pub(crate) fn ec_public_key_bytes<T: HasPublic>(key: &EcKeyRef<T>) -> Result<Vec<u8>, CryptoError> {
+    let mut ctx = BigNumContext::new().map_err(ossl)?;
+    key.public_key()
+        .to_bytes(key.group(), PointConversionForm::UNCOMPRESSED, &mut ctx)
+        .map_err(ossl)
+}
+
+/// Generate a fresh key pair on the curve `nid` and return
+/// `(private key, public key)`.
+pub(crate) fn ec_key_gen(nid: Nid) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+    let group = EcGroup::from_curve_name(nid).map_err(ossl)?;
+    let mut ctx = BigNumContext::new().map_err(ossl)?;
+    let mut order = BigNum::new().map_err(ossl)?;
+    group.order(&mut order, &mut ctx).map_err(ossl)?;
+    let key = EcKey::generate(&group).map_err(ossl)?;
+    let sk = key
+        .private_key()
+        .to_vec_padded(scalar_len(&order) as i32)
+        .map_err(ossl)?;
+    Ok((sk, ec_public_key_bytes(&key)?))
+}
+
+/// ECDH on the curve `nid`. Returns the x-coordinate of the shared point.
+pub(crate) fn ecdh(nid: Nid, sk: &[u8], pk: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let sk = PKey::from_ec_key(ec_private_key(nid, sk)?).map_err(ossl)?;
+    let pk = PKey::from_ec_key(ec_public_key(nid, pk)?).map_err(ossl)?;
+    derive(&sk, &pk)
+}
+
+/// Sign `data` with ECDSA and return the DER encoded signature.
+pub(crate) fn ecdsa_sign(
+    nid: Nid,
+    md: MessageDigest,
+    sk: &[u8],
+    data: &[u8],
+) -> Result<Vec<u8>, CryptoError> {
+    let key = PKey::from_ec_key(ec_private_key(nid, sk)?).map_err(ossl)?;
+    let mut signer = Signer::new(md, &key).map_err(ossl)?;
+    signer.update(data).map_err(ossl)?;
+    signer.sign_to_vec().map_err(ossl)
+}
+
+/// Verify the DER encoded ECDSA `signature` on `data`.
+pub(crate) fn ecdsa_verify(
+    nid: Nid,
+    md: MessageDigest,
+    pk: &[u8],
+    data: &[u8],
+    signature: &[u8],
+) -> Result<(), CryptoError> {
+    let key = PKey::from_ec_key(ec_public_key(nid, pk)?).map_err(ossl)?;
+    let mut verifier = Verifier::new(md, &key).map_err(ossl)?;
+    verifier.update(data).map_err(ossl)?;
+    match verifier.verify(signature) {
+        Ok(true) => Ok(()),
+        _ => Err(CryptoError::InvalidSignature),
+    }
+}
+
+// === X25519 and Ed25519 === //
+
+/// The length of X25519 and Ed25519 private keys.
+pub(crate) const RAW_KEY_LEN: usize = 32;
+
+/// Generate a fresh key pair of type `id` and return
+/// `(private key, public key)`.
+pub(crate) fn raw_key_gen(id: Id) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+    let key = if id == Id::X25519 {
+        PKey::generate_x25519()
+    } else if id == Id::ED25519 {
+        PKey::generate_ed25519()
+    } else {
+        return Err(CryptoError::CryptoLibraryError);
+    }
+    .map_err(ossl)?;
+    Ok((
+        key.raw_private_key().map_err(ossl)?,
+        key.raw_public_key().map_err(ossl)?,
+    ))
+}
+
+/// Returns the public key of the private key `sk` of type `id`.
+pub(crate) fn raw_public_key(id: Id, sk: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    PKey::private_key_from_raw_bytes(sk, id)
+        .and_then(|key| key.raw_public_key())
+        .map_err(ossl)
+}
+
+pub(crate) fn x25519(sk: &[u8], pk: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let sk = PKey::private_key_from_raw_bytes(sk, Id::X25519).map_err(ossl)?;
+    let pk = PKey::public_key_from_raw_bytes(pk, Id::X25519).map_err(ossl)?;
+    derive(&sk, &pk)
+}
+
+/// Sign `data` with the Ed25519 private key `sk`.
+pub(crate) fn ed25519_sign(sk: &[u8], data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let key = PKey::private_key_from_raw_bytes(sk, Id::ED25519).map_err(ossl)?;
+    let mut signer = Signer::new_without_digest(&key).map_err(ossl)?;
+    signer.sign_oneshot_to_vec(data).map_err(ossl)
+}
+
+/// Verify the Ed25519 `signature` on `data`.
+pub(crate) fn ed25519_verify(pk: &[u8], data: &[u8], signature: &[u8]) -> Result<(), CryptoError> {
+    let key = PKey::public_key_from_raw_bytes(pk, Id::ED25519).map_err(ossl)?;
+    let mut verifier = Verifier::new_without_digest(&key).map_err(ossl)?;
+    match verifier.verify_oneshot(signature, data) {
+        Ok(true) => Ok(()),
+        _ => Err(CryptoError::InvalidSignature),
+    }
+}
+
+fn derive(sk: &PKey<Private>, pk: &PKey<Public>) -> Result<Vec<u8>, CryptoError> {
+    let mut deriver = Deriver::new(sk).map_err(ossl)?;
+    deriver.set_peer(pk).map_err(ossl)?;
+    deriver.derive_to_vec().map_err(ossl)
+}
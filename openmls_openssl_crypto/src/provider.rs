@@ -0,0 +1,337 @@
+use hpke::Hpke;
+use hpke_rs_crypto::types as hpke_types;
+use openmls_traits::{
+    crypto::OpenMlsCrypto,
+    random::OpenMlsRand,
+    types::{
+        self, AeadType, Ciphersuite, CryptoError, ExporterSecret, HashType, HpkeAeadType,
+        HpkeCiphertext, HpkeConfig, HpkeKdfType, HpkeKemType, HpkeKeyPair, SignatureScheme,
+    },
+};
+use openssl::{hash::MessageDigest, nid::Nid, pkey::Id, symm::Cipher};
+use tls_codec::SecretVLBytes;
+
+use crate::{
+    hpke::{self as ossl_hpke, HpkeOpenSsl},
+    primitives::{self, RAW_KEY_LEN},
+};
+
+/// The crypto primitives of OpenSSL. Randomness is drawn from the CSPRNG of
+/// OpenSSL.
+#[derive(Debug, Default)]
+pub struct OpenSslCrypto;
+
+#[inline(always)]
+fn kem_mode(kem: HpkeKemType) -> Result<hpke_types::KemAlgorithm, CryptoError> {
+    match kem {
+        HpkeKemType::DhKemP256 => Ok(hpke_types::KemAlgorithm::DhKemP256),
+        HpkeKemType::DhKemP384 => Ok(hpke_types::KemAlgorithm::DhKemP384),
+        HpkeKemType::DhKemP521 => Ok(hpke_types::KemAlgorithm::DhKemP521),
+        HpkeKemType::DhKem25519 => Ok(hpke_types::KemAlgorithm::DhKem25519),
+        _ => Err(CryptoError::UnsupportedKem),
+    }
+}
+
+#[inline(always)]
+fn kdf_mode(kdf: HpkeKdfType) -> hpke_types::KdfAlgorithm {
+    match kdf {
+        HpkeKdfType::HkdfSha256 => hpke_types::KdfAlgorithm::HkdfSha256,
+        HpkeKdfType::HkdfSha384 => hpke_types::KdfAlgorithm::HkdfSha384,
+        HpkeKdfType::HkdfSha512 => hpke_types::KdfAlgorithm::HkdfSha512,
+    }
+}
+
+#[inline(always)]
+fn aead_mode(aead: HpkeAeadType) -> hpke_types::AeadAlgorithm {
+    match aead {
+        HpkeAeadType::AesGcm128 => hpke_types::AeadAlgorithm::Aes128Gcm,
+        HpkeAeadType::AesGcm256 => hpke_types::AeadAlgorithm::Aes256Gcm,
+        HpkeAeadType::ChaCha20Poly1305 => hpke_types::AeadAlgorithm::ChaCha20Poly1305,
+        HpkeAeadType::Export => hpke_types::AeadAlgorithm::HpkeExport,
+    }
+}
+
+fn digest(hash_type: HashType) -> MessageDigest {
+    match hash_type {
+        HashType::Sha2_256 => MessageDigest::sha256(),
+        HashType::Sha2_384 => MessageDigest::sha384(),
+        HashType::Sha2_512 => MessageDigest::sha512(),
+    }
+}
+
+fn cipher(alg: AeadType) -> Cipher {
+    match alg {
+        AeadType::Aes128Gcm => Cipher::aes_128_gcm(),
+        AeadType::Aes256Gcm => Cipher::aes_256_gcm(),
+        AeadType::ChaCha20Poly1305 => Cipher::chacha20_poly1305(),
+    }
+}
+
+/// Returns the curve and hash of an ECDSA signature scheme or [`None`] for all
+/// other signature schemes.
+fn ecdsa_parameters(alg: SignatureScheme) -> Option<(Nid, MessageDigest)> {
+    match alg {
+        SignatureScheme::ECDSA_SECP256R1_SHA256 => {
+            Some((Nid::X9_62_PRIME256V1, MessageDigest::sha256()))
+        }
+        SignatureScheme::ECDSA_SECP384R1_SHA384 => Some((Nid::SECP384R1, MessageDigest::sha384())),
+        SignatureScheme::ECDSA_SECP521R1_SHA512 => Some((Nid::SECP521R1, MessageDigest::sha512())),
+        _ => None,
+    }
+}
+
+impl OpenMlsCrypto for OpenSslCrypto {
+    fn supports(&self, ciphersuite: Ciphersuite) -> Result<(), CryptoError> {
+        match ciphersuite {
+            Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519
+            | Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519
+            | Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256
+            | Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521
+            | Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384 => Ok(()),
+            _ => Err(CryptoError::UnsupportedCiphersuite),
+        }
+    }
+
+    fn supported_ciphersuites(&self) -> Vec<Ciphersuite> {
+        vec![
+            Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519,
+            Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519,
+            Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
+            Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521,
+            Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384,
+        ]
+    }
+
+    fn supports_signature_scheme(&self, scheme: SignatureScheme) -> Result<(), CryptoError> {
+        match scheme {
+            SignatureScheme::ED25519
+            | SignatureScheme::ECDSA_SECP256R1_SHA256
+            | SignatureScheme::ECDSA_SECP384R1_SHA384
+            | SignatureScheme::ECDSA_SECP521R1_SHA512 => Ok(()),
+            _ => Err(CryptoError::UnsupportedSignatureScheme),
+        }
+    }
+
+    fn supports_aead(&self, _aead: AeadType) -> Result<(), CryptoError> {
+        Ok(())
+    }
+
+    fn supports_hash(&self, _hash_type: HashType) -> Result<(), CryptoError> {
+        Ok(())
+    }
+
+    fn supports_hpke_kem(&self, kem: HpkeKemType) -> Result<(), CryptoError> {
+        kem_mode(kem).map(|_| ())
+    }
+
+    fn hkdf_extract(
+        &self,
+        hash_type: HashType,
+        salt: &[u8],
+        ikm: &[u8],
+    ) -> Result<SecretVLBytes, CryptoError> {
+        primitives::hkdf_extract(digest(hash_type), salt, ikm).map(|okm| okm.as_slice().into())
+    }
+
+    fn hkdf_expand(
+        &self,
+        hash_type: HashType,
+        prk: &[u8],
+        info: &[u8],
+        okm_len: usize,
+    ) -> Result<SecretVLBytes, CryptoError> {
+        primitives::hkdf_expand(digest(hash_type), prk, info, okm_len)
+            .map(|okm| okm.as_slice().into())
+    }
+
+    fn hash(&self, hash_type: HashType, data: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        primitives::hash(digest(hash_type), data)
+    }
+
+    fn aead_encrypt(
+        &self,
+        alg: AeadType,
+        key: &[u8],
+        data: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        primitives::aead_seal(cipher(alg), key, nonce, aad, data)
+            .map_err(|_| CryptoError::CryptoLibraryError)
+    }
+
+    fn aead_decrypt(
+        &self,
+        alg: AeadType,
+        key: &[u8],
+        ct_tag: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        primitives::aead_open(cipher(alg), key, nonce, aad, ct_tag)
+    }
+
+    fn signature_key_gen(&self, alg: SignatureScheme) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+        if let Some((curve, _)) = ecdsa_parameters(alg) {
+            return primitives::ec_key_gen(curve);
+        }
+        match alg {
+            SignatureScheme::ED25519 => {
+                // The private key is the seed followed by the public key, as
+                // in the other providers.
+                let (sk, pk) = primitives::raw_key_gen(Id::ED25519)?;
+                Ok(([sk, pk.clone()].concat(), pk))
+            }
+            _ => Err(CryptoError::UnsupportedSignatureScheme),
+        }
+    }
+
+    fn verify_signature(
+        &self,
+        alg: SignatureScheme,
+        data: &[u8],
+        pk: &[u8],
+        signature: &[u8],
+    ) -> Result<(), CryptoError> {
+        if let Some((curve, md)) = ecdsa_parameters(alg) {
+            return primitives::ecdsa_verify(curve, md, pk, data, signature);
+        }
+        match alg {
+            SignatureScheme::ED25519 => primitives::ed25519_verify(pk, data, signature),
+            _ => Err(CryptoError::UnsupportedSignatureScheme),
+        }
+    }
+
+    fn sign(&self, alg: SignatureScheme, data: &[u8], key: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        if let Some((curve, md)) = ecdsa_parameters(alg) {
+            return primitives::ecdsa_sign(curve, md, key, data);
+        }
+        match alg {
+            SignatureScheme::ED25519 => {
+                // Accept both the seed and the seed followed by the public key.
+                let seed = key
+                    .get(..RAW_KEY_LEN)
+                    .ok_or(CryptoError::CryptoLibraryError)?;
+                primitives::ed25519_sign(seed, data)
+            }
+            _ => Err(CryptoError::UnsupportedSignatureScheme),
+        }
+    }
+
+    fn hpke_seal(
+        &self,
+        config: HpkeConfig,
+        pk_r: &[u8],
+        info: &[u8],
+        aad: &[u8],
+        ptxt: &[u8],
+    ) -> Result<types::HpkeCiphertext, CryptoError> {
+        let (kem_output, ciphertext) = hpke_from_config(config)?
+            .seal(&pk_r.into(), info, aad, ptxt, None, None, None)
+            .map_err(|_| CryptoError::HpkeEncryptionError)?;
+        Ok(HpkeCiphertext {
+            kem_output: kem_output.into(),
+            ciphertext: ciphertext.into(),
+        })
+    }
+
+    fn hpke_open(
+        &self,
+        config: HpkeConfig,
+        input: &types::HpkeCiphertext,
+        sk_r: &[u8],
+        info: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, CryptoError> {
+        hpke_from_config(config)?
+            .open(
+                input.kem_output.as_slice(),
+                &sk_r.into(),
+                info,
+                aad,
+                input.ciphertext.as_slice(),
+                None,
+                None,
+                None,
+            )
+            .map_err(|_| CryptoError::HpkeDecryptionError)
+    }
+
+    fn hpke_setup_sender_and_export(
+        &self,
+        config: HpkeConfig,
+        pk_r: &[u8],
+        info: &[u8],
+        exporter_context: &[u8],
+        exporter_length: usize,
+    ) -> Result<(Vec<u8>, ExporterSecret), CryptoError> {
+        let (kem_output, context) = hpke_from_config(config)?
+            .setup_sender(&pk_r.into(), info, None, None, None)
+            .map_err(|_| CryptoError::SenderSetupError)?;
+        let exported_secret = context
+            .export(exporter_context, exporter_length)
+            .map_err(|_| CryptoError::ExporterError)?;
+        Ok((kem_output, exported_secret.into()))
+    }
+
+    fn hpke_setup_receiver_and_export(
+        &self,
+        config: HpkeConfig,
+        enc: &[u8],
+        sk_r: &[u8],
+        info: &[u8],
+        exporter_context: &[u8],
+        exporter_length: usize,
+    ) -> Result<ExporterSecret, CryptoError> {
+        let context = hpke_from_config(config)?
+            .setup_receiver(enc, &sk_r.into(), info, None, None, None)
+            .map_err(|_| CryptoError::ReceiverSetupError)?;
+        let exported_secret = context
+            .export(exporter_context, exporter_length)
+            .map_err(|_| CryptoError::ExporterError)?;
+        Ok(exported_secret.into())
+    }
+
+    fn derive_hpke_keypair(
+        &self,
+        config: HpkeConfig,
+        ikm: &[u8],
+    ) -> Result<types::HpkeKeyPair, CryptoError> {
+        let (private, public) = ossl_hpke::derive_key_pair(config.0, ikm)?;
+        Ok(HpkeKeyPair {
+            private: private.into(),
+            public,
+        })
+    }
+}
+
+fn hpke_from_config(config: HpkeConfig) -> Result<Hpke<HpkeOpenSsl>, CryptoError> {
+    Ok(Hpke::<HpkeOpenSsl>::new(
+        hpke::Mode::Base,
+        kem_mode(config.0)?,
+        kdf_mode(config.1),
+        aead_mode(config.2),
+    ))
+}
+
+impl OpenMlsRand for OpenSslCrypto {
+    type Error = RandError;
+
+    fn random_array<const N: usize>(&self) -> Result<[u8; N], Self::Error> {
+        let mut out = [0u8; N];
+        openssl::rand::rand_bytes(&mut out).map_err(|_| Self::Error::NotEnoughRandomness)?;
+        Ok(out)
+    }
+
+    fn random_vec(&self, len: usize) -> Result<Vec<u8>, Self::Error> {
+        let mut out = vec![0u8; len];
+        openssl::rand::rand_bytes(&mut out).map_err(|_| Self::Error::NotEnoughRandomness)?;
+        Ok(out)
+    }
+}
+
+#[derive(thiserror::Error, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RandError {
+    #[error("Unable to collect enough randomness.")]
+    NotEnoughRandomness,
+}
@@ -0,0 +1,6 @@
+use openmls_openssl_crypto::OpenMlsOpenSsl;
+
+#[test]
+fn openssl_conformance() {
+    openmls_provider_tests::run_all(&OpenMlsOpenSsl::default());
+}
@@ -0,0 +1,181 @@
+//! # DHKEM on P-384 and P-521
+//!
+//! `hpke-rs-rust-crypto` only implements the DHKEMs on X25519 and P-256.
+//! [`HpkeNistCrypto`] extends it with DHKEM(P-384, HKDF-SHA384) and
+//! DHKEM(P-521, HKDF-SHA512) from RFC 9180, so that the ciphersuites of
+//! RFC 9420 on these curves can be used.
+
+use hpke_rs_crypto::{
+    error::Error,
+    types::{AeadAlgorithm, KdfAlgorithm, KemAlgorithm},
+    HpkeCrypto,
+};
+use hpke_rs_rust_crypto::HpkeRustCrypto;
+use openmls_traits::types::{CryptoError, HpkeKemType};
+use p384::elliptic_curve::sec1::ToEncodedPoint;
+
+/// [`HpkeRustCrypto`] with support for DHKEM(P-384) and DHKEM(P-521). All
+/// other algorithms are delegated to [`HpkeRustCrypto`].
+#[derive(Debug)]
+pub(crate) struct HpkeNistCrypto;
+
+impl HpkeCrypto for HpkeNistCrypto {
+    type HpkePrng = <HpkeRustCrypto as HpkeCrypto>::HpkePrng;
+
+    fn name() -> String {
+        "RustCrypto with DHKEM(P-384) and DHKEM(P-521)".into()
+    }
+
+    fn kdf_extract(alg: KdfAlgorithm, salt: &[u8], ikm: &[u8]) -> Vec<u8> {
+        HpkeRustCrypto::kdf_extract(alg, salt, ikm)
+    }
+
+    fn kdf_expand(
+        alg: KdfAlgorithm,
+        prk: &[u8],
+        info: &[u8],
+        output_size: usize,
+    ) -> Result<Vec<u8>, Error> {
+        HpkeRustCrypto::kdf_expand(alg, prk, info, output_size)
+    }
+
+    fn kem_derive(alg: KemAlgorithm, pk: &[u8], sk: &[u8]) -> Result<Vec<u8>, Error> {
+        match alg {
+            KemAlgorithm::DhKemP384 => {
+                let sk = p384::SecretKey::from_slice(sk).map_err(|_| Error::KemInvalidSecretKey)?;
+                let pk =
+                    p384::PublicKey::from_sec1_bytes(pk).map_err(|_| Error::KemInvalidPublicKey)?;
+                let shared_secret =
+                    p384::ecdh::diffie_hellman(sk.to_nonzero_scalar(), pk.as_affine());
+                Ok(shared_secret.raw_secret_bytes().as_slice().into())
+            }
+            KemAlgorithm::DhKemP521 => {
+                let sk = p521::SecretKey::from_slice(sk).map_err(|_| Error::KemInvalidSecretKey)?;
+                let pk =
+                    p521::PublicKey::from_sec1_bytes(pk).map_err(|_| Error::KemInvalidPublicKey)?;
+                let shared_secret =
+                    p521::ecdh::diffie_hellman(sk.to_nonzero_scalar(), pk.as_affine());
+                Ok(shared_secret.raw_secret_bytes().as_slice().into())
+            }
+            _ => HpkeRustCrypto::kem_derive(alg, pk, sk),
+        }
+    }
+
+    fn kem_derive_base(alg: KemAlgorithm, sk: &[u8]) -> Result<Vec<u8>, Error> {
+        match alg {
+            KemAlgorithm::DhKemP384 => {
+                let sk = p384::SecretKey::from_slice(sk).map_err(|_| Error::KemInvalidSecretKey)?;
+                Ok(sk.public_key().to_encoded_point(false).as_bytes().into())
+            }
+            KemAlgorithm::DhKemP521 => {
+                let sk = p521::SecretKey::from_slice(sk).map_err(|_| Error::KemInvalidSecretKey)?;
+                Ok(sk.public_key().to_encoded_point(false).as_bytes().into())
+            }
+            _ => HpkeRustCrypto::kem_derive_base(alg, sk),
+        }
+    }
+
+    fn kem_key_gen(alg: KemAlgorithm, prng: &mut Self::HpkePrng) -> Result<Vec<u8>, Error> {
+        match alg {
+            KemAlgorithm::DhKemP384 => {
+                Ok(p384::SecretKey::random(prng).to_bytes().as_slice().into())
+            }
+            KemAlgorithm::DhKemP521 => {
+                Ok(p521::SecretKey::random(prng).to_bytes().as_slice().into())
+            }
+            _ => HpkeRustCrypto::kem_key_gen(alg, prng),
+        }
+    }
+
+    fn kem_validate_sk(alg: KemAlgorithm, sk: &[u8]) -> Result<Vec<u8>, Error> {
+        match alg {
+            KemAlgorithm::DhKemP384 => p384::SecretKey::from_slice(sk)
+                .map(|_| sk.into())
+                .map_err(|_| Error::KemInvalidSecretKey),
+            KemAlgorithm::DhKemP521 => p521::SecretKey::from_slice(sk)
+                .map(|_| sk.into())
+                .map_err(|_| Error::KemInvalidSecretKey),
+            _ => HpkeRustCrypto::kem_validate_sk(alg, sk),
+        }
+    }
+
+    fn aead_seal(
+        alg: AeadAlgorithm,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        msg: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        HpkeRustCrypto::aead_seal(alg, key, nonce, aad, msg)
+    }
+
+    fn aead_open(
+        alg: AeadAlgorithm,
+        key: &[u8],
+        nonce: &[u8],
+        aad: &[u8],
+        msg: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        HpkeRustCrypto::aead_open(alg, key, nonce, aad, msg)
+    }
+
+    fn prng() -> Self::HpkePrng {
+        HpkeRustCrypto::prng()
+    }
+
+    fn supports_kem(alg: KemAlgorithm) -> Result<(), Error> {
+        match alg {
+            KemAlgorithm::DhKemP384 | KemAlgorithm::DhKemP521 => Ok(()),
+            _ => HpkeRustCrypto::supports_kem(alg),
+        }
+    }
+
+    fn supports_kdf(alg: KdfAlgorithm) -> Result<(), Error> {
+        HpkeRustCrypto::supports_kdf(alg)
+    }
+
+    fn supports_aead(alg: AeadAlgorithm) -> Result<(), Error> {
+        HpkeRustCrypto::supports_aead(alg)
+    }
+}
+
+/// Derive a key pair for DHKEM(P-384) or DHKEM(P-521) from `ikm` as specified
+/// in Section 7.1.3 of RFC 9180. Returns `(private key, public key)`.
+///
+/// Returns an error for all other KEMs or if no valid private key could be
+/// derived.
+pub(crate) fn derive_key_pair(
+    kem: HpkeKemType,
+    ikm: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>), CryptoError> {
+    let (alg, kdf, private_key_len, bitmask) = match kem {
+        HpkeKemType::DhKemP384 => (KemAlgorithm::DhKemP384, KdfAlgorithm::HkdfSha384, 48, 0xff),
+        HpkeKemType::DhKemP521 => (KemAlgorithm::DhKemP521, KdfAlgorithm::HkdfSha512, 66, 0x01),
+        _ => return Err(CryptoError::UnsupportedKem),
+    };
+    let suite_id = [b"KEM".as_slice(), &(kem as u16).to_be_bytes()].concat();
+
+    // LabeledExtract("", "dkp_prk", ikm)
+    let labeled_ikm = [b"HPKE-v1".as_slice(), suite_id.as_slice(), b"dkp_prk", ikm].concat();
+    let dkp_prk = HpkeNistCrypto::kdf_extract(kdf, &[], &labeled_ikm);
+
+    for counter in 0..=u8::MAX {
+        // LabeledExpand(dkp_prk, "candidate", I2OSP(counter, 1), Nsk)
+        let labeled_info = [
+            (private_key_len as u16).to_be_bytes().as_slice(),
+            b"HPKE-v1",
+            suite_id.as_slice(),
+            b"candidate",
+            &[counter],
+        ]
+        .concat();
+        let mut candidate =
+            HpkeNistCrypto::kdf_expand(kdf, &dkp_prk, &labeled_info, private_key_len)
+                .map_err(|_| CryptoError::CryptoLibraryError)?;
+        candidate[0] &= bitmask;
+        if let Ok(public) = HpkeNistCrypto::kem_derive_base(alg, &candidate) {
+            return Ok((candidate, public));
+        }
+    }
+    Err(CryptoError::CryptoLibraryError)
+}
@@ -6,6 +6,7 @@
 pub use openmls_memory_keystore::{MemoryKeyStore, MemoryKeyStoreError};
 use openmls_traits::OpenMlsCryptoProvider;
 
+mod ec_kem;
 mod provider;
 pub use provider::*;
 
@@ -11,7 +11,6 @@ use ed25519_dalek::Signer as DalekSigner;
 use hkdf::Hkdf;
 use hpke::Hpke;
 use hpke_rs_crypto::types as hpke_types;
-use hpke_rs_rust_crypto::HpkeRustCrypto;
 use openmls_traits::{
     crypto::OpenMlsCrypto,
     random::OpenMlsRand,
@@ -31,6 +30,8 @@ use rand::{RngCore, SeedableRng};
 use sha2::{Digest, Sha256, Sha384, Sha512};
 use tls_codec::SecretVLBytes;
 
+use crate::ec_kem::{self, HpkeNistCrypto};
+
 #[derive(Debug)]
 pub struct RustCrypto {
     rng: RwLock<rand_chacha::ChaCha20Rng>,
@@ -44,14 +45,17 @@ impl Default for RustCrypto {
     }
 }
 
+/// Returns the `hpke-rs` KEM of the [`HpkeKemType`] or an error if `hpke-rs`
+/// doesn't implement it, e.g. for the experimental X-Wing KEM.
 #[inline(always)]
-fn kem_mode(kem: HpkeKemType) -> hpke_types::KemAlgorithm {
+fn kem_mode(kem: HpkeKemType) -> Result<hpke_types::KemAlgorithm, CryptoError> {
     match kem {
-        HpkeKemType::DhKemP256 => hpke_types::KemAlgorithm::DhKemP256,
-        HpkeKemType::DhKemP384 => hpke_types::KemAlgorithm::DhKemP384,
-        HpkeKemType::DhKemP521 => hpke_types::KemAlgorithm::DhKemP521,
-        HpkeKemType::DhKem25519 => hpke_types::KemAlgorithm::DhKem25519,
-        HpkeKemType::DhKem448 => hpke_types::KemAlgorithm::DhKem448,
+        HpkeKemType::DhKemP256 => Ok(hpke_types::KemAlgorithm::DhKemP256),
+        HpkeKemType::DhKemP384 => Ok(hpke_types::KemAlgorithm::DhKemP384),
+        HpkeKemType::DhKemP521 => Ok(hpke_types::KemAlgorithm::DhKemP521),
+        HpkeKemType::DhKem25519 => Ok(hpke_types::KemAlgorithm::DhKem25519),
+        HpkeKemType::DhKem448 => Ok(hpke_types::KemAlgorithm::DhKem448),
+        HpkeKemType::XWingKemDraft06 => Err(CryptoError::UnsupportedKem),
     }
 }
 
@@ -79,7 +83,11 @@ impl OpenMlsCrypto for RustCrypto {
         match ciphersuite {
             Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519
             | Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519
-            | Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256 => Ok(()),
+            | Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256
+            | Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521
+            | Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384 => Ok(()),
+            #[cfg(feature = "secp256k1")]
+            Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_SECP256K1 => Ok(()),
             _ => Err(CryptoError::UnsupportedCiphersuite),
         }
     }
@@ -89,9 +97,43 @@ impl OpenMlsCrypto for RustCrypto {
             Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_Ed25519,
             Ciphersuite::MLS_128_DHKEMX25519_CHACHA20POLY1305_SHA256_Ed25519,
             Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_P256,
+            Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521,
+            Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384,
+            #[cfg(feature = "secp256k1")]
+            Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_SECP256K1,
         ]
     }
 
+    fn supports_signature_scheme(&self, scheme: SignatureScheme) -> Result<(), CryptoError> {
+        match scheme {
+            SignatureScheme::ED25519
+            | SignatureScheme::ECDSA_SECP256R1_SHA256
+            | SignatureScheme::ECDSA_SECP384R1_SHA384
+            | SignatureScheme::ECDSA_SECP521R1_SHA512 => Ok(()),
+            #[cfg(feature = "secp256k1")]
+            SignatureScheme::ECDSA_SECP256K1_SHA256 => Ok(()),
+            _ => Err(CryptoError::UnsupportedSignatureScheme),
+        }
+    }
+
+    fn supports_aead(&self, _aead: AeadType) -> Result<(), CryptoError> {
+        Ok(())
+    }
+
+    fn supports_hash(&self, _hash_type: HashType) -> Result<(), CryptoError> {
+        Ok(())
+    }
+
+    fn supports_hpke_kem(&self, kem: HpkeKemType) -> Result<(), CryptoError> {
+        match kem {
+            HpkeKemType::DhKem25519
+            | HpkeKemType::DhKemP256
+            | HpkeKemType::DhKemP384
+            | HpkeKemType::DhKemP521 => Ok(()),
+            _ => Err(CryptoError::UnsupportedKem),
+        }
+    }
+
     fn hkdf_extract(
         &self,
         hash_type: openmls_traits::types::HashType,
@@ -234,6 +276,34 @@ impl OpenMlsCrypto for RustCrypto {
                 let pk = k.verifying_key().to_encoded_point(false).as_bytes().into();
                 Ok((k.to_bytes().as_slice().into(), pk))
             }
+            SignatureScheme::ECDSA_SECP384R1_SHA384 => {
+                let mut rng = self
+                    .rng
+                    .write()
+                    .map_err(|_| CryptoError::InsufficientRandomness)?;
+                let k = p384::ecdsa::SigningKey::random(&mut *rng);
+                let pk = k.verifying_key().to_encoded_point(false).as_bytes().into();
+                Ok((k.to_bytes().as_slice().into(), pk))
+            }
+            SignatureScheme::ECDSA_SECP521R1_SHA512 => {
+                let mut rng = self
+                    .rng
+                    .write()
+                    .map_err(|_| CryptoError::InsufficientRandomness)?;
+                let k = p521::ecdsa::SigningKey::random(&mut *rng);
+                let pk = k.verifying_key().to_encoded_point(false).as_bytes().into();
+                Ok((k.to_bytes().as_slice().into(), pk))
+            }
+            #[cfg(feature = "secp256k1")]
+            SignatureScheme::ECDSA_SECP256K1_SHA256 => {
+                let mut rng = self
+                    .rng
+                    .write()
+                    .map_err(|_| CryptoError::InsufficientRandomness)?;
+                let k = k256::ecdsa::SigningKey::random(&mut *rng);
+                let pk = k.verifying_key().to_encoded_point(false).as_bytes().into();
+                Ok((k.to_bytes().as_slice().into(), pk))
+            }
             SignatureScheme::ED25519 => {
                 // XXX: We can't use our RNG here
                 let k = ed25519_dalek::Keypair::generate(&mut rand_07::rngs::OsRng).to_bytes();
@@ -265,6 +335,31 @@ impl OpenMlsCrypto for RustCrypto {
                 )
                 .map_err(|_| CryptoError::InvalidSignature)
             }
+            SignatureScheme::ECDSA_SECP384R1_SHA384 => {
+                let k = p384::ecdsa::VerifyingKey::from_sec1_bytes(pk)
+                    .map_err(|_| CryptoError::CryptoLibraryError)?;
+                let signature = p384::ecdsa::Signature::from_der(signature)
+                    .map_err(|_| CryptoError::InvalidSignature)?;
+                k.verify(data, &signature)
+                    .map_err(|_| CryptoError::InvalidSignature)
+            }
+            SignatureScheme::ECDSA_SECP521R1_SHA512 => {
+                let k = p521::ecdsa::VerifyingKey::from_sec1_bytes(pk)
+                    .map_err(|_| CryptoError::CryptoLibraryError)?;
+                let signature = p521::ecdsa::Signature::from_der(signature)
+                    .map_err(|_| CryptoError::InvalidSignature)?;
+                k.verify(data, &signature)
+                    .map_err(|_| CryptoError::InvalidSignature)
+            }
+            #[cfg(feature = "secp256k1")]
+            SignatureScheme::ECDSA_SECP256K1_SHA256 => {
+                let k = k256::ecdsa::VerifyingKey::from_sec1_bytes(pk)
+                    .map_err(|_| CryptoError::CryptoLibraryError)?;
+                let signature = k256::ecdsa::Signature::from_der(signature)
+                    .map_err(|_| CryptoError::InvalidSignature)?;
+                k.verify(data, &signature)
+                    .map_err(|_| CryptoError::InvalidSignature)
+            }
             SignatureScheme::ED25519 => {
                 let k = ed25519_dalek::PublicKey::from_bytes(pk)
                     .map_err(|_| CryptoError::CryptoLibraryError)?;
@@ -293,6 +388,25 @@ impl OpenMlsCrypto for RustCrypto {
                 let signature: Signature = k.sign(data);
                 Ok(signature.to_der().to_bytes().into())
             }
+            SignatureScheme::ECDSA_SECP384R1_SHA384 => {
+                let k = p384::ecdsa::SigningKey::from_slice(key)
+                    .map_err(|_| CryptoError::CryptoLibraryError)?;
+                let signature: p384::ecdsa::Signature = k.sign(data);
+                Ok(signature.to_der().to_bytes().into())
+            }
+            SignatureScheme::ECDSA_SECP521R1_SHA512 => {
+                let k = p521::ecdsa::SigningKey::from_slice(key)
+                    .map_err(|_| CryptoError::CryptoLibraryError)?;
+                let signature: p521::ecdsa::Signature = k.sign(data);
+                Ok(signature.to_der().to_bytes().into())
+            }
+            #[cfg(feature = "secp256k1")]
+            SignatureScheme::ECDSA_SECP256K1_SHA256 => {
+                let k = k256::ecdsa::SigningKey::from_slice(key)
+                    .map_err(|_| CryptoError::CryptoLibraryError)?;
+                let signature: k256::ecdsa::Signature = k.sign(data);
+                Ok(signature.to_der().to_bytes().into())
+            }
             SignatureScheme::ED25519 => {
                 let k = ed25519_dalek::Keypair::from_bytes(key)
                     .map_err(|_| CryptoError::CryptoLibraryError)?;
@@ -310,14 +424,14 @@ impl OpenMlsCrypto for RustCrypto {
         info: &[u8],
         aad: &[u8],
         ptxt: &[u8],
-    ) -> types::HpkeCiphertext {
-        let (kem_output, ciphertext) = hpke_from_config(config)
+    ) -> Result<types::HpkeCiphertext, CryptoError> {
+        let (kem_output, ciphertext) = hpke_from_config(config)?
             .seal(&pk_r.into(), info, aad, ptxt, None, None, None)
-            .unwrap();
-        HpkeCiphertext {
+            .map_err(|_| CryptoError::HpkeEncryptionError)?;
+        Ok(HpkeCiphertext {
             kem_output: kem_output.into(),
             ciphertext: ciphertext.into(),
-        }
+        })
     }
 
     fn hpke_open(
@@ -328,7 +442,7 @@ impl OpenMlsCrypto for RustCrypto {
         info: &[u8],
         aad: &[u8],
     ) -> Result<Vec<u8>, CryptoError> {
-        hpke_from_config(config)
+        hpke_from_config(config)?
             .open(
                 input.kem_output.as_slice(),
                 &sk_r.into(),
@@ -350,7 +464,7 @@ impl OpenMlsCrypto for RustCrypto {
         exporter_context: &[u8],
         exporter_length: usize,
     ) -> Result<(Vec<u8>, ExporterSecret), CryptoError> {
-        let (kem_output, context) = hpke_from_config(config)
+        let (kem_output, context) = hpke_from_config(config)?
             .setup_sender(&pk_r.into(), info, None, None, None)
             .map_err(|_| CryptoError::SenderSetupError)?;
         let exported_secret = context
@@ -368,7 +482,7 @@ impl OpenMlsCrypto for RustCrypto {
         exporter_context: &[u8],
         exporter_length: usize,
     ) -> Result<ExporterSecret, CryptoError> {
-        let context = hpke_from_config(config)
+        let context = hpke_from_config(config)?
             .setup_receiver(enc, &sk_r.into(), info, None, None, None)
             .map_err(|_| CryptoError::ReceiverSetupError)?;
         let exported_secret = context
@@ -377,25 +491,36 @@ impl OpenMlsCrypto for RustCrypto {
         Ok(exported_secret.into())
     }
 
-    fn derive_hpke_keypair(&self, config: HpkeConfig, ikm: &[u8]) -> types::HpkeKeyPair {
-        let kp = hpke_from_config(config)
+    fn derive_hpke_keypair(
+        &self,
+        config: HpkeConfig,
+        ikm: &[u8],
+    ) -> Result<types::HpkeKeyPair, CryptoError> {
+        if matches!(config.0, HpkeKemType::DhKemP384 | HpkeKemType::DhKemP521) {
+            let (private, public) = ec_kem::derive_key_pair(config.0, ikm)?;
+            return Ok(HpkeKeyPair {
+                private: private.into(),
+                public,
+            });
+        }
+        let kp = hpke_from_config(config)?
             .derive_key_pair(ikm)
-            .unwrap()
+            .map_err(|_| CryptoError::CryptoLibraryError)?
             .into_keys();
-        HpkeKeyPair {
+        Ok(HpkeKeyPair {
             private: kp.0.as_slice().into(),
             public: kp.1.as_slice().into(),
-        }
+        })
     }
 }
 
-fn hpke_from_config(config: HpkeConfig) -> Hpke<HpkeRustCrypto> {
-    Hpke::<HpkeRustCrypto>::new(
+fn hpke_from_config(config: HpkeConfig) -> Result<Hpke<HpkeNistCrypto>, CryptoError> {
+    Ok(Hpke::<HpkeNistCrypto>::new(
         hpke::Mode::Base,
-        kem_mode(config.0),
+        kem_mode(config.0)?,
         kdf_mode(config.1),
         aead_mode(config.2),
-    )
+    ))
 }
 
 impl OpenMlsRand for RustCrypto {
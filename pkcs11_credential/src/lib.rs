@@ -0,0 +1,14 @@
+//! # PKCS#11 Credential
+//!
+//! Signature keys for credentials that live on a hardware security module or
+//! any other token that is accessible through PKCS#11.
+//!
+//! [`Pkcs11Token`] implements the [`SignatureKeyProvider`] trait, such that
+//! [`HandleSigner`] can sign with keys on the token without the private key
+//! ever leaving it.
+
+#[doc(no_inline)]
+pub use openmls_traits::signatures::{HandleSigner, SignatureKeyProvider};
+
+mod pkcs11;
+pub use pkcs11::*;
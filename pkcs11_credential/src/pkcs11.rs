@@ -0,0 +1,295 @@
+//! # PKCS#11 Token
+//!
+//! A [`SignatureKeyProvider`] for PKCS#11 tokens on top of `cryptoki`.
+
+use std::{path::Path, sync::Mutex};
+
+use cryptoki::{
+    context::{CInitializeArgs, Pkcs11},
+    mechanism::Mechanism,
+    object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle},
+    session::{Session, UserType},
+    types::AuthPin,
+};
+use openmls_traits::{signatures::SignatureKeyProvider, types::SignatureScheme};
+
+/// Errors of the [`Pkcs11Token`].
+#[derive(thiserror::Error, Debug)]
+pub enum Pkcs11Error {
+    /// See [`cryptoki::error::Error`] for more details.
+    #[error(transparent)]
+    Cryptoki(#[from] cryptoki::error::Error),
+    /// No token with the requested label is present.
+    #[error("No token with the requested label is present.")]
+    TokenNotFound,
+    /// The signature scheme is not supported.
+    #[error("The signature scheme is not supported.")]
+    UnsupportedSignatureScheme,
+    /// The token returned a malformed public key.
+    #[error("The token returned a malformed public key.")]
+    InvalidPublicKey,
+    /// The token returned a malformed signature.
+    #[error("The token returned a malformed signature.")]
+    InvalidSignature,
+    /// The session lock is poisoned.
+    #[error("The session lock is poisoned.")]
+    LockPoisoned,
+}
+
+/// A logged-in session with a PKCS#11 token.
+///
+/// Key pairs are stored on the token with the key ID as `CKA_ID`. Private keys
+/// are generated as sensitive and non-extractable.
+#[derive(Debug)]
+pub struct Pkcs11Token {
+    session: Mutex<Session>,
+}
+
+/// The PKCS#11 parameters of a [`SignatureScheme`].
+struct SchemeParameters {
+    /// The DER encoded OID of the curve.
+    ec_params: &'static [u8],
+    key_type: KeyType,
+    key_gen: Mechanism<'static>,
+    sign: Mechanism<'static>,
+    /// The length of a scalar for ECDSA or [`None`] for EdDSA.
+    scalar_len: Option<usize>,
+}
+
+impl SchemeParameters {
+    fn new(signature_scheme: SignatureScheme) -> Result<Self, Pkcs11Error> {
+        // The OIDs from RFC 5480 and RFC 8410.
+        let (ec_params, sign, scalar_len): (&'static [u8], _, _) = match signature_scheme {
+            SignatureScheme::ECDSA_SECP256R1_SHA256 => (
+                &[0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07],
+                Mechanism::EcdsaSha256,
+                32,
+            ),
+            SignatureScheme::ECDSA_SECP384R1_SHA384 => (
+                &[0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x22],
+                Mechanism::EcdsaSha384,
+                48,
+            ),
+            SignatureScheme::ECDSA_SECP521R1_SHA512 => (
+                &[0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x23],
+                Mechanism::EcdsaSha512,
+                66,
+            ),
+            SignatureScheme::ED25519 => {
+                return Ok(Self {
+                    ec_params: &[0x06, 0x03, 0x2b, 0x65, 0x70],
+                    key_type: KeyType::EC_EDWARDS,
+                    key_gen: Mechanism::EccEdwardsKeyPairGen,
+                    sign: Mechanism::Eddsa,
+                    scalar_len: None,
+                })
+            }
+            _ => return Err(Pkcs11Error::UnsupportedSignatureScheme),
+        };
+        Ok(Self {
+            ec_params,
+            key_type: KeyType::EC,
+            key_gen: Mechanism::EccKeyPairGen,
+            sign,
+            scalar_len: Some(scalar_len),
+        })
+    }
+
+    /// The length of a public key in the encoding of OpenMLS.
+    fn public_key_len(&self) -> usize {
+        match self.scalar_len {
+            Some(scalar_len) => 1 + 2 * scalar_len,
+            None => 32,
+        }
+    }
+}
+
+impl Pkcs11Token {
+    /// Create a token from an open and logged-in `session`.
+    pub fn new(session: Session) -> Self {
+        Self {
+            session: Mutex::new(session),
+        }
+    }
+
+    /// Load the PKCS#11 `module`, open a session with the token with the
+    /// `label` and log in as user with the `pin`.
+    pub fn open(module: impl AsRef<Path>, label: &str, pin: &str) -> Result<Self, Pkcs11Error> {
+        let pkcs11 = Pkcs11::new(module)?;
+        pkcs11.initialize(CInitializeArgs::OsThreads)?;
+        let mut slot = None;
+        for candidate in pkcs11.get_slots_with_token()? {
+            if pkcs11.get_token_info(candidate)?.label() == label {
+                slot = Some(candidate);
+                break;
+            }
+        }
+        let slot = slot.ok_or(Pkcs11Error::TokenNotFound)?;
+        let session = pkcs11.open_rw_session(slot)?;
+        session.login(UserType::User, Some(&AuthPin::new(pin.into())))?;
+        Ok(Self::new(session))
+    }
+
+    /// Find the single object of the `class` with the `key_id` and key type.
+    fn find_object(
+        session: &Session,
+        class: ObjectClass,
+        key_id: &[u8],
+        parameters: &SchemeParameters,
+    ) -> Result<Option<ObjectHandle>, Pkcs11Error> {
+        let template = [
+            Attribute::Class(class),
+            Attribute::KeyType(parameters.key_type),
+            Attribute::EcParams(parameters.ec_params.to_vec()),
+            Attribute::Id(key_id.to_vec()),
+        ];
+        Ok(session.find_objects(&template)?.into_iter().next())
+    }
+
+    /// Read the public key of the key pair with the `public_key` handle.
+    fn public_key(
+        session: &Session,
+        public_key: ObjectHandle,
+        parameters: &SchemeParameters,
+    ) -> Result<Vec<u8>, Pkcs11Error> {
+        let attributes = session.get_attributes(public_key, &[AttributeType::EcPoint])?;
+        let point = attributes
+            .into_iter()
+            .find_map(|attribute| match attribute {
+                Attribute::EcPoint(point) => Some(point),
+                _ => None,
+            })
+            .ok_or(Pkcs11Error::InvalidPublicKey)?;
+        // `CKA_EC_POINT` is a DER encoded octet string, but some tokens
+        // return the raw point instead.
+        let public = match unwrap_octet_string(&point) {
+            Some(public) => public.to_vec(),
+            None => point,
+        };
+        if public.len() != parameters.public_key_len() {
+            return Err(Pkcs11Error::InvalidPublicKey);
+        }
+        Ok(public)
+    }
+}
+
+impl SignatureKeyProvider for Pkcs11Token {
+    type KeyHandle = ObjectHandle;
+    type Error = Pkcs11Error;
+
+    fn generate_key(
+        &self,
+        key_id: &[u8],
+        signature_scheme: SignatureScheme,
+    ) -> Result<(Self::KeyHandle, Vec<u8>), Self::Error> {
+        let parameters = SchemeParameters::new(signature_scheme)?;
+        let public_template = [
+            Attribute::Token(true),
+            Attribute::Private(false),
+            Attribute::Verify(true),
+            Attribute::EcParams(parameters.ec_params.to_vec()),
+            Attribute::Id(key_id.to_vec()),
+        ];
+        let private_template = [
+            Attribute::Token(true),
+            Attribute::Private(true),
+            Attribute::Sensitive(true),
+            Attribute::Extractable(false),
+            Attribute::Sign(true),
+            Attribute::Id(key_id.to_vec()),
+        ];
+        let session = self.session.lock().map_err(|_| Pkcs11Error::LockPoisoned)?;
+        let (public_key, private_key) =
+            session.generate_key_pair(&parameters.key_gen, &public_template, &private_template)?;
+        let public = Self::public_key(&session, public_key, &parameters)?;
+        Ok((private_key, public))
+    }
+
+    fn find_key(
+        &self,
+        key_id: &[u8],
+        signature_scheme: SignatureScheme,
+    ) -> Result<Option<(Self::KeyHandle, Vec<u8>)>, Self::Error> {
+        let parameters = SchemeParameters::new(signature_scheme)?;
+        let session = self.session.lock().map_err(|_| Pkcs11Error::LockPoisoned)?;
+        let private_key =
+            match Self::find_object(&session, ObjectClass::PRIVATE_KEY, key_id, &parameters)? {
+                Some(private_key) => private_key,
+                None => return Ok(None),
+            };
+        let public_key =
+            match Self::find_object(&session, ObjectClass::PUBLIC_KEY, key_id, &parameters)? {
+                Some(public_key) => public_key,
+                None => return Ok(None),
+            };
+        let public = Self::public_key(&session, public_key, &parameters)?;
+        Ok(Some((private_key, public)))
+    }
+
+    fn sign(
+        &self,
+        key: &Self::KeyHandle,
+        signature_scheme: SignatureScheme,
+        payload: &[u8],
+    ) -> Result<Vec<u8>, Self::Error> {
+        let parameters = SchemeParameters::new(signature_scheme)?;
+        let session = self.session.lock().map_err(|_| Pkcs11Error::LockPoisoned)?;
+        let signature = session.sign(&parameters.sign, *key, payload)?;
+        match parameters.scalar_len {
+            // PKCS#11 returns ECDSA signatures as `r || s`.
+            Some(scalar_len) if signature.len() == 2 * scalar_len => {
+                let (r, s) = signature.split_at(scalar_len);
+                let mut content = der_integer(r);
+                content.extend(der_integer(s));
+                Ok(der_tlv(0x30, &content))
+            }
+            Some(_) => Err(Pkcs11Error::InvalidSignature),
+            None => Ok(signature),
+        }
+    }
+}
+
+/// Returns the content of the DER encoded octet string `bytes` or [`None`] if
+/// `bytes` isn't exactly one octet string.
+fn unwrap_octet_string(bytes: &[u8]) -> Option<&[u8]> {
+    let (&tag, rest) = bytes.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    let (len, content) = match first {
+        0..=0x7f => (first as usize, rest),
+        0x81 => {
+            let (&len, rest) = rest.split_first()?;
+            (len as usize, rest)
+        }
+        _ => return None,
+    };
+    (tag == 0x04 && content.len() == len).then_some(content)
+}
+
+/// DER encode the unsigned big-endian integer `bytes`.
+fn der_integer(bytes: &[u8]) -> Vec<u8> {
+    let start = bytes
+        .iter()
+        .position(|&b| b != 0)
+        .unwrap_or(bytes.len().saturating_sub(1));
+    let bytes = &bytes[start..];
+    let mut content = Vec::with_capacity(bytes.len() + 1);
+    // A leading zero keeps the integer positive.
+    if bytes.first().map_or(true, |&b| b & 0x80 != 0) {
+        content.push(0);
+    }
+    content.extend_from_slice(bytes);
+    der_tlv(0x02, &content)
+}
+
+/// DER encode the `content` with the `tag`.
+///
+/// The content of all encodings in this module is shorter than 256 bytes.
+fn der_tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    if content.len() >= 0x80 {
+        out.push(0x81);
+    }
+    out.push(content.len() as u8);
+    out.extend_from_slice(content);
+    out
+}
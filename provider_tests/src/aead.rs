@@ -0,0 +1,162 @@
+//! # AEAD Tests
+//!
+//! Known answer tests from the GCM specification and round trips for all
+//! supported [`AeadType`]s.
+
+use openmls_traits::{crypto::OpenMlsCrypto, types::AeadType};
+
+use crate::hex;
+
+/// An AEAD known answer test.
+struct AeadVector {
+    alg: AeadType,
+    key: &'static str,
+    nonce: &'static str,
+    aad: &'static str,
+    plaintext: &'static str,
+    ct_tag: &'static str,
+}
+
+/// Test cases 1, 2, 13 and 14 from "The Galois/Counter Mode of Operation
+/// (GCM)" by McGrew and Viega.
+const AEAD_VECTORS: &[AeadVector] = &[
+    AeadVector {
+        alg: AeadType::Aes128Gcm,
+        key: "00000000000000000000000000000000",
+        nonce: "000000000000000000000000",
+        aad: "",
+        plaintext: "",
+        ct_tag: "58e2fccefa7e3061367f1d57a4e7455a",
+    },
+    AeadVector {
+        alg: AeadType::Aes128Gcm,
+        key: "00000000000000000000000000000000",
+        nonce: "000000000000000000000000",
+        aad: "",
+        plaintext: "00000000000000000000000000000000",
+        ct_tag: "0388dace60b6a392f328c2b971b2fe78ab6e47d42cec13bdf53a67b21257bddf",
+    },
+    AeadVector {
+        alg: AeadType::Aes256Gcm,
+        key: "0000000000000000000000000000000000000000000000000000000000000000",
+        nonce: "000000000000000000000000",
+        aad: "",
+        plaintext: "",
+        ct_tag: "530f8afbc74536b9a963b4f1c4cb738b",
+    },
+    AeadVector {
+        alg: AeadType::Aes256Gcm,
+        key: "0000000000000000000000000000000000000000000000000000000000000000",
+        nonce: "000000000000000000000000",
+        aad: "",
+        plaintext: "00000000000000000000000000000000",
+        ct_tag: "cea7403d4d606b6e074ec5d3baf39d18d0d1c8a799996bf0265b98b5d48ab919",
+    },
+];
+
+/// Run all AEAD tests.
+pub fn run_all(crypto: &impl OpenMlsCrypto) {
+    known_answer_tests(crypto);
+    round_trips(crypto);
+}
+
+/// Check encryption and decryption against the known answer tests of all
+/// supported [`AeadType`]s.
+pub fn known_answer_tests(crypto: &impl OpenMlsCrypto) {
+    for vector in AEAD_VECTORS {
+        if crypto.supports_aead(vector.alg).is_err() {
+            continue;
+        }
+        let key = hex(vector.key);
+        let nonce = hex(vector.nonce);
+        let aad = hex(vector.aad);
+        let plaintext = hex(vector.plaintext);
+        let ct_tag = hex(vector.ct_tag);
+
+        let encrypted = crypto
+            .aead_encrypt(vector.alg, &key, &plaintext, &nonce, &aad)
+            .unwrap_or_else(|e| panic!("{:?} encryption failed: {e:?}", vector.alg));
+        assert_eq!(
+            encrypted, ct_tag,
+            "{:?} encryption doesn't match the test vector.",
+            vector.alg
+        );
+
+        let decrypted = crypto
+            .aead_decrypt(vector.alg, &key, &ct_tag, &nonce, &aad)
+            .unwrap_or_else(|e| panic!("{:?} decryption failed: {e:?}", vector.alg));
+        assert_eq!(
+            decrypted, plaintext,
+            "{:?} decryption doesn't match the test vector.",
+            vector.alg
+        );
+    }
+}
+
+/// Encrypt and decrypt with all supported [`AeadType`]s and check that
+/// modified inputs are rejected.
+pub fn round_trips(crypto: &impl OpenMlsCrypto) {
+    for alg in [
+        AeadType::Aes128Gcm,
+        AeadType::Aes256Gcm,
+        AeadType::ChaCha20Poly1305,
+    ] {
+        if crypto.supports_aead(alg).is_err() {
+            continue;
+        }
+        let key: Vec<u8> = (0..alg.key_size() as u8).collect();
+        let nonce: Vec<u8> = (0..alg.nonce_size() as u8).rev().collect();
+        let aad = b"OpenMLS provider tests";
+        let plaintext = b"Hello, MLS group!";
+
+        let ct_tag = crypto
+            .aead_encrypt(alg, &key, plaintext, &nonce, aad)
+            .unwrap_or_else(|e| panic!("{alg:?} encryption failed: {e:?}"));
+        assert_eq!(
+            ct_tag.len(),
+            plaintext.len() + alg.tag_size(),
+            "{alg:?} ciphertext has the wrong length."
+        );
+        let decrypted = crypto
+            .aead_decrypt(alg, &key, &ct_tag, &nonce, aad)
+            .unwrap_or_else(|e| panic!("{alg:?} decryption failed: {e:?}"));
+        assert_eq!(decrypted, plaintext, "{alg:?} round trip failed.");
+
+        // Modified ciphertext
+        let mut modified = ct_tag.clone();
+        modified[0] ^= 0xff;
+        assert!(
+            crypto
+                .aead_decrypt(alg, &key, &modified, &nonce, aad)
+                .is_err(),
+            "{alg:?} decrypted a modified ciphertext."
+        );
+
+        // Modified tag
+        let mut modified = ct_tag.clone();
+        let last = modified.len() - 1;
+        modified[last] ^= 0xff;
+        assert!(
+            crypto
+                .aead_decrypt(alg, &key, &modified, &nonce, aad)
+                .is_err(),
+            "{alg:?} decrypted a ciphertext with a modified tag."
+        );
+
+        // Wrong additional data
+        assert!(
+            crypto
+                .aead_decrypt(alg, &key, &ct_tag, &nonce, b"other aad")
+                .is_err(),
+            "{alg:?} decrypted with the wrong additional data."
+        );
+
+        // Truncated input
+        assert!(
+            crypto
+                .aead_decrypt(alg, &key, &ct_tag[..alg.tag_size() - 1], &nonce, aad)
+                .is_err(),
+            "{alg:?} decrypted an input shorter than the tag."
+        );
+    }
+}
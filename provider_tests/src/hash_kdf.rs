@@ -0,0 +1,147 @@
+//! # Hash and KDF Tests
+//!
+//! Known answer tests for the supported [`HashType`]s and HKDF.
+
+use openmls_traits::{crypto::OpenMlsCrypto, types::HashType};
+
+use crate::hex;
+
+/// Hashes of `"abc"` from FIPS 180-2.
+const HASH_VECTORS: &[(HashType, &str)] = &[
+    (
+        HashType::Sha2_256,
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+    ),
+    (
+        HashType::Sha2_384,
+        "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed\
+         8086072ba1e7cc2358baeca134c825a7",
+    ),
+    (
+        HashType::Sha2_512,
+        "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+         2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+    ),
+];
+
+/// An HKDF known answer test.
+struct HkdfVector {
+    hash_type: HashType,
+    ikm: &'static str,
+    salt: &'static str,
+    info: &'static str,
+    prk: &'static str,
+    okm: &'static str,
+}
+
+/// Test case 1 from RFC 5869.
+const HKDF_VECTORS: &[HkdfVector] = &[HkdfVector {
+    hash_type: HashType::Sha2_256,
+    ikm: "0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b",
+    salt: "000102030405060708090a0b0c",
+    info: "f0f1f2f3f4f5f6f7f8f9",
+    prk: "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5",
+    okm: "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf\
+          34007208d5b887185865",
+}];
+
+/// Run all hash and KDF tests.
+pub fn run_all(crypto: &impl OpenMlsCrypto) {
+    hash_known_answer_tests(crypto);
+    hkdf_known_answer_tests(crypto);
+    hkdf_output_length(crypto);
+}
+
+/// Check the supported [`HashType`]s against the known answer tests.
+pub fn hash_known_answer_tests(crypto: &impl OpenMlsCrypto) {
+    for (hash_type, digest) in HASH_VECTORS {
+        if crypto.supports_hash(*hash_type).is_err() {
+            continue;
+        }
+        let hash = crypto
+            .hash(*hash_type, b"abc")
+            .unwrap_or_else(|e| panic!("{hash_type:?} failed: {e:?}"));
+        assert_eq!(
+            hash.len(),
+            hash_type.size(),
+            "{hash_type:?} has the wrong length."
+        );
+        assert_eq!(
+            hash,
+            hex(digest),
+            "{hash_type:?} doesn't match the test vector."
+        );
+    }
+}
+
+/// Check HKDF extract and expand against the known answer tests.
+pub fn hkdf_known_answer_tests(crypto: &impl OpenMlsCrypto) {
+    for vector in HKDF_VECTORS {
+        if crypto.supports_hash(vector.hash_type).is_err() {
+            continue;
+        }
+        let okm = hex(vector.okm);
+        let prk = crypto
+            .hkdf_extract(vector.hash_type, &hex(vector.salt), &hex(vector.ikm))
+            .unwrap_or_else(|e| panic!("HKDF extract failed: {e:?}"));
+        assert_eq!(
+            prk.as_slice(),
+            hex(vector.prk),
+            "HKDF extract with {:?} doesn't match the test vector.",
+            vector.hash_type
+        );
+        let expanded = crypto
+            .hkdf_expand(
+                vector.hash_type,
+                prk.as_slice(),
+                &hex(vector.info),
+                okm.len(),
+            )
+            .unwrap_or_else(|e| panic!("HKDF expand failed: {e:?}"));
+        assert_eq!(
+            expanded.as_slice(),
+            okm,
+            "HKDF expand with {:?} doesn't match the test vector.",
+            vector.hash_type
+        );
+    }
+}
+
+/// Check that HKDF expand returns outputs of the requested length and
+/// rejects lengths larger than `255 * hash length`.
+pub fn hkdf_output_length(crypto: &impl OpenMlsCrypto) {
+    for hash_type in [HashType::Sha2_256, HashType::Sha2_384, HashType::Sha2_512] {
+        if crypto.supports_hash(hash_type).is_err() {
+            continue;
+        }
+        let prk = crypto
+            .hkdf_extract(hash_type, b"salt", b"input key material")
+            .unwrap_or_else(|e| panic!("HKDF extract failed: {e:?}"));
+        assert_eq!(
+            prk.as_slice().len(),
+            hash_type.size(),
+            "HKDF extract with {hash_type:?} has the wrong length."
+        );
+        for okm_len in [1, hash_type.size(), 255 * hash_type.size()] {
+            let okm = crypto
+                .hkdf_expand(hash_type, prk.as_slice(), b"info", okm_len)
+                .unwrap_or_else(|e| panic!("HKDF expand failed: {e:?}"));
+            assert_eq!(
+                okm.as_slice().len(),
+                okm_len,
+                "HKDF expand with {hash_type:?} has the wrong length."
+            );
+        }
+        assert!(
+            crypto
+                .hkdf_expand(
+                    hash_type,
+                    prk.as_slice(),
+                    b"info",
+                    255 * hash_type.size() + 1
+                )
+                .is_err(),
+            "HKDF expand with {hash_type:?} accepted a too long output length."
+        );
+    }
+}
@@ -0,0 +1,213 @@
+//! # HPKE Tests
+//!
+//! Round trips and consistency checks for the HPKE configurations of all
+//! supported ciphersuites.
+
+use openmls_traits::{crypto::OpenMlsCrypto, types::HpkeAeadType};
+
+use crate::supported_ciphersuites;
+
+/// Run all HPKE tests.
+pub fn run_all(crypto: &impl OpenMlsCrypto) {
+    key_derivation(crypto);
+    seal_open(crypto);
+    export(crypto);
+}
+
+/// Check that HPKE key pairs are derived deterministically from the input
+/// key material.
+pub fn key_derivation(crypto: &impl OpenMlsCrypto) {
+    for ciphersuite in supported_ciphersuites(crypto) {
+        let ikm = vec![0x42; ciphersuite.hash_length()];
+        let key_pair = crypto
+            .derive_hpke_keypair(ciphersuite.hpke_config(), &ikm)
+            .unwrap_or_else(|e| panic!("HPKE key derivation for {ciphersuite:?} failed: {e:?}"));
+        let same_key_pair = crypto
+            .derive_hpke_keypair(ciphersuite.hpke_config(), &ikm)
+            .unwrap_or_else(|e| panic!("HPKE key derivation for {ciphersuite:?} failed: {e:?}"));
+        assert_eq!(
+            key_pair.public, same_key_pair.public,
+            "HPKE key derivation for {ciphersuite:?} is not deterministic."
+        );
+        assert_eq!(
+            &*key_pair.private, &*same_key_pair.private,
+            "HPKE key derivation for {ciphersuite:?} is not deterministic."
+        );
+
+        let other_key_pair = crypto
+            .derive_hpke_keypair(ciphersuite.hpke_config(), &[0x23; 32])
+            .unwrap_or_else(|e| panic!("HPKE key derivation for {ciphersuite:?} failed: {e:?}"));
+        assert_ne!(
+            key_pair.public, other_key_pair.public,
+            "HPKE key derivation for {ciphersuite:?} ignores the input key material."
+        );
+    }
+}
+
+/// Seal and open with the HPKE configurations of all supported ciphersuites
+/// and check that modified inputs are rejected.
+pub fn seal_open(crypto: &impl OpenMlsCrypto) {
+    for ciphersuite in supported_ciphersuites(crypto) {
+        if ciphersuite.hpke_aead_algorithm() == HpkeAeadType::Export {
+            continue;
+        }
+        let key_pair = crypto
+            .derive_hpke_keypair(ciphersuite.hpke_config(), &[0x01; 32])
+            .unwrap_or_else(|e| panic!("HPKE key derivation for {ciphersuite:?} failed: {e:?}"));
+        let other_key_pair = crypto
+            .derive_hpke_keypair(ciphersuite.hpke_config(), &[0x02; 32])
+            .unwrap_or_else(|e| panic!("HPKE key derivation for {ciphersuite:?} failed: {e:?}"));
+        let info = b"OpenMLS provider tests";
+        let aad = b"additional data";
+        let plaintext = b"Hello, MLS group!";
+
+        let ciphertext = crypto
+            .hpke_seal(
+                ciphersuite.hpke_config(),
+                &key_pair.public,
+                info,
+                aad,
+                plaintext,
+            )
+            .unwrap_or_else(|e| panic!("HPKE seal for {ciphersuite:?} failed: {e:?}"));
+        let decrypted = crypto
+            .hpke_open(
+                ciphersuite.hpke_config(),
+                &ciphertext,
+                &key_pair.private,
+                info,
+                aad,
+            )
+            .unwrap_or_else(|e| panic!("HPKE open for {ciphersuite:?} failed: {e:?}"));
+        assert_eq!(
+            decrypted, plaintext,
+            "HPKE round trip for {ciphersuite:?} failed."
+        );
+
+        // Encryption is randomized.
+        let second_ciphertext = crypto
+            .hpke_seal(
+                ciphersuite.hpke_config(),
+                &key_pair.public,
+                info,
+                aad,
+                plaintext,
+            )
+            .unwrap_or_else(|e| panic!("HPKE seal for {ciphersuite:?} failed: {e:?}"));
+        assert_ne!(
+            ciphertext.kem_output, second_ciphertext.kem_output,
+            "HPKE seal for {ciphersuite:?} is not randomized."
+        );
+
+        assert!(
+            crypto
+                .hpke_open(
+                    ciphersuite.hpke_config(),
+                    &ciphertext,
+                    &other_key_pair.private,
+                    info,
+                    aad,
+                )
+                .is_err(),
+            "HPKE open for {ciphersuite:?} succeeded with the wrong private key."
+        );
+        assert!(
+            crypto
+                .hpke_open(
+                    ciphersuite.hpke_config(),
+                    &ciphertext,
+                    &key_pair.private,
+                    b"other info",
+                    aad,
+                )
+                .is_err(),
+            "HPKE open for {ciphersuite:?} succeeded with the wrong info."
+        );
+        assert!(
+            crypto
+                .hpke_open(
+                    ciphersuite.hpke_config(),
+                    &ciphertext,
+                    &key_pair.private,
+                    info,
+                    b"other aad",
+                )
+                .is_err(),
+            "HPKE open for {ciphersuite:?} succeeded with the wrong additional data."
+        );
+
+        let mut modified = ciphertext.clone();
+        let mut modified_bytes = modified.ciphertext.as_slice().to_vec();
+        modified_bytes[0] ^= 0xff;
+        modified.ciphertext = modified_bytes.into();
+        assert!(
+            crypto
+                .hpke_open(
+                    ciphersuite.hpke_config(),
+                    &modified,
+                    &key_pair.private,
+                    info,
+                    aad,
+                )
+                .is_err(),
+            "HPKE open for {ciphersuite:?} succeeded with a modified ciphertext."
+        );
+    }
+}
+
+/// Check that sender and receiver export the same secret.
+pub fn export(crypto: &impl OpenMlsCrypto) {
+    for ciphersuite in supported_ciphersuites(crypto) {
+        let key_pair = crypto
+            .derive_hpke_keypair(ciphersuite.hpke_config(), &[0x03; 32])
+            .unwrap_or_else(|e| panic!("HPKE key derivation for {ciphersuite:?} failed: {e:?}"));
+        let info = b"OpenMLS provider tests";
+        let exporter_context = b"exporter context";
+        let exporter_length = ciphersuite.hash_length();
+
+        let (enc, sender_secret) = crypto
+            .hpke_setup_sender_and_export(
+                ciphersuite.hpke_config(),
+                &key_pair.public,
+                info,
+                exporter_context,
+                exporter_length,
+            )
+            .unwrap_or_else(|e| panic!("HPKE sender export for {ciphersuite:?} failed: {e:?}"));
+        assert_eq!(
+            sender_secret.len(),
+            exporter_length,
+            "HPKE export for {ciphersuite:?} has the wrong length."
+        );
+
+        let receiver_secret = crypto
+            .hpke_setup_receiver_and_export(
+                ciphersuite.hpke_config(),
+                &enc,
+                &key_pair.private,
+                info,
+                exporter_context,
+                exporter_length,
+            )
+            .unwrap_or_else(|e| panic!("HPKE receiver export for {ciphersuite:?} failed: {e:?}"));
+        assert_eq!(
+            &*sender_secret, &*receiver_secret,
+            "HPKE sender and receiver for {ciphersuite:?} exported different secrets."
+        );
+
+        let other_secret = crypto
+            .hpke_setup_receiver_and_export(
+                ciphersuite.hpke_config(),
+                &enc,
+                &key_pair.private,
+                info,
+                b"other exporter context",
+                exporter_length,
+            )
+            .unwrap_or_else(|e| panic!("HPKE receiver export for {ciphersuite:?} failed: {e:?}"));
+        assert_ne!(
+            &*sender_secret, &*other_secret,
+            "HPKE export for {ciphersuite:?} ignores the exporter context."
+        );
+    }
+}
@@ -0,0 +1,154 @@
+//! # Key Store Tests
+//!
+//! Checks the store, read and delete semantics OpenMLS relies on.
+
+use openmls_traits::key_store::{MlsEntity, MlsEntityId, OpenMlsKeyStore, WriteBatch};
+use serde::{Deserialize, Serialize};
+
+/// A value that is stored by the tests.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct TestEntity {
+    label: String,
+    bytes: Vec<u8>,
+}
+
+impl MlsEntity for TestEntity {
+    const ID: MlsEntityId = MlsEntityId::GroupState;
+}
+
+impl TestEntity {
+    fn new(label: &str) -> Self {
+        Self {
+            label: label.to_owned(),
+            bytes: label.bytes().rev().collect(),
+        }
+    }
+}
+
+/// Run all key store tests.
+pub fn run_all(key_store: &impl OpenMlsKeyStore) {
+    store_read_delete(key_store);
+    overwrite(key_store);
+    independent_keys(key_store);
+    batches(key_store);
+}
+
+/// Check that stored values can be read and are gone after deleting them.
+pub fn store_read_delete(key_store: &impl OpenMlsKeyStore) {
+    let key = b"openmls provider tests: store read delete";
+    let value = TestEntity::new("value");
+
+    assert_eq!(
+        key_store.read::<TestEntity>(key),
+        None,
+        "Reading a value that was never stored returned a value."
+    );
+    key_store
+        .store(key, &value)
+        .unwrap_or_else(|e| panic!("Storing a value failed: {e:?}"));
+    assert_eq!(
+        key_store.read::<TestEntity>(key),
+        Some(value),
+        "Reading a stored value failed."
+    );
+    key_store
+        .delete::<TestEntity>(key)
+        .unwrap_or_else(|e| panic!("Deleting a value failed: {e:?}"));
+    assert_eq!(
+        key_store.read::<TestEntity>(key),
+        None,
+        "Reading a deleted value returned a value."
+    );
+}
+
+/// Check that storing a value for an existing key replaces the old value.
+pub fn overwrite(key_store: &impl OpenMlsKeyStore) {
+    let key = b"openmls provider tests: overwrite";
+    let old_value = TestEntity::new("old value");
+    let new_value = TestEntity::new("new value");
+
+    key_store
+        .store(key, &old_value)
+        .unwrap_or_else(|e| panic!("Storing a value failed: {e:?}"));
+    key_store
+        .store(key, &new_value)
+        .unwrap_or_else(|e| panic!("Overwriting a value failed: {e:?}"));
+    assert_eq!(
+        key_store.read::<TestEntity>(key),
+        Some(new_value),
+        "Reading an overwritten value didn't return the new value."
+    );
+    key_store
+        .delete::<TestEntity>(key)
+        .unwrap_or_else(|e| panic!("Deleting a value failed: {e:?}"));
+}
+
+/// Check that values stored for different keys don't affect each other.
+pub fn independent_keys(key_store: &impl OpenMlsKeyStore) {
+    let first_key = b"openmls provider tests: first key";
+    let second_key = b"openmls provider tests: second key";
+    let first_value = TestEntity::new("first value");
+    let second_value = TestEntity::new("second value");
+
+    key_store
+        .store(first_key, &first_value)
+        .unwrap_or_else(|e| panic!("Storing a value failed: {e:?}"));
+    key_store
+        .store(second_key, &second_value)
+        .unwrap_or_else(|e| panic!("Storing a value failed: {e:?}"));
+    key_store
+        .delete::<TestEntity>(first_key)
+        .unwrap_or_else(|e| panic!("Deleting a value failed: {e:?}"));
+    assert_eq!(
+        key_store.read::<TestEntity>(first_key),
+        None,
+        "Reading a deleted value returned a value."
+    );
+    assert_eq!(
+        key_store.read::<TestEntity>(second_key),
+        Some(second_value),
+        "Deleting a value affected the value of another key."
+    );
+    key_store
+        .delete::<TestEntity>(second_key)
+        .unwrap_or_else(|e| panic!("Deleting a value failed: {e:?}"));
+}
+
+/// Check that values in a batch, which are encoded by the key store itself,
+/// can be read once the batch is applied.
+pub fn batches(key_store: &impl OpenMlsKeyStore) {
+    let key = b"openmls provider tests: batches";
+    let deleted_key = b"openmls provider tests: batches deleted";
+    let value = TestEntity::new("batched value");
+
+    let encoded_value = key_store
+        .encode(&value)
+        .unwrap_or_else(|e| panic!("Encoding a value failed: {e:?}"));
+    assert_eq!(
+        key_store.decode::<TestEntity>(&encoded_value),
+        Some(value.clone()),
+        "Decoding an encoded value failed."
+    );
+    key_store
+        .store(deleted_key, &value)
+        .unwrap_or_else(|e| panic!("Storing a value failed: {e:?}"));
+    let mut batch = WriteBatch::new();
+    batch.store(TestEntity::ID, key, encoded_value);
+    batch.delete::<TestEntity>(deleted_key);
+    key_store
+        .apply_batch(batch)
+        .unwrap_or_else(|e| panic!("Applying a batch failed: {e:?}"));
+    assert_eq!(
+        key_store.read::<TestEntity>(key),
+        Some(value),
+        "Reading a value written in a batch failed."
+    );
+    assert_eq!(
+        key_store.read::<TestEntity>(deleted_key),
+        None,
+        "Reading a value deleted in a batch returned a value."
+    );
+    key_store
+        .delete::<TestEntity>(key)
+        .unwrap_or_else(|e| panic!("Deleting a value failed: {e:?}"));
+}
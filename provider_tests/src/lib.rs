@@ -0,0 +1,66 @@
+//! # OpenMLS Provider Conformance Tests
+//!
+//! A test battery for implementations of the [`OpenMlsCryptoProvider`] trait.
+//! Third-party providers can run it in their CI to check that they behave the
+//! way OpenMLS expects.
+//!
+//! ```ignore
+//! #[test]
+//! fn conformance() {
+//!     openmls_provider_tests::run_all(&MyProvider::default());
+//! }
+//! ```
+//!
+//! The tests are grouped into modules that can also be run individually.
+//! Crypto tests only cover the algorithms that the provider reports as
+//! supported, e.g. through [`OpenMlsCrypto::supported_ciphersuites()`].
+//!
+//! All test functions panic with a descriptive message if the provider does
+//! not conform.
+
+use openmls_traits::{crypto::OpenMlsCrypto, types::Ciphersuite, OpenMlsCryptoProvider};
+
+pub mod aead;
+pub mod hash_kdf;
+pub mod hpke;
+pub mod key_store;
+pub mod rand;
+pub mod signatures;
+
+/// Run all conformance tests on the `provider`.
+pub fn run_all(provider: &impl OpenMlsCryptoProvider) {
+    aead::run_all(provider.crypto());
+    hash_kdf::run_all(provider.crypto());
+    hpke::run_all(provider.crypto());
+    signatures::run_all(provider.crypto());
+    key_store::run_all(provider.key_store());
+    rand::run_all(provider.rand());
+}
+
+/// Returns the ciphersuites supported by `crypto`.
+///
+/// Panics if `crypto` doesn't support any ciphersuite or if the list is
+/// inconsistent with [`OpenMlsCrypto::supports()`].
+pub(crate) fn supported_ciphersuites(crypto: &impl OpenMlsCrypto) -> Vec<Ciphersuite> {
+    let ciphersuites = crypto.supported_ciphersuites();
+    assert!(
+        !ciphersuites.is_empty(),
+        "The provider doesn't support any ciphersuite."
+    );
+    for ciphersuite in &ciphersuites {
+        assert!(
+            crypto.supports(*ciphersuite).is_ok(),
+            "{ciphersuite:?} is listed as supported but `supports` fails."
+        );
+    }
+    ciphersuites
+}
+
+/// Decode a hex string from a test vector.
+pub(crate) fn hex(s: &str) -> Vec<u8> {
+    assert!(s.len() % 2 == 0, "Invalid hex string in test vector.");
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).expect("Invalid hex string in test vector."))
+        .collect()
+}
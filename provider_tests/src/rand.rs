@@ -0,0 +1,48 @@
+//! # Randomness Tests
+//!
+//! Basic sanity checks for the [`OpenMlsRand`] source. These tests can't
+//! judge the quality of the randomness. They only catch obviously broken
+//! sources, e.g. ones that return constant or truncated output.
+
+use openmls_traits::random::OpenMlsRand;
+
+/// Run all randomness tests.
+pub fn run_all(rand: &impl OpenMlsRand) {
+    output_length(rand);
+    distinct_outputs(rand);
+}
+
+/// Check that the randomness source returns outputs of the requested length.
+pub fn output_length(rand: &impl OpenMlsRand) {
+    for len in [0, 1, 32, 1000] {
+        let bytes = rand
+            .random_vec(len)
+            .unwrap_or_else(|e| panic!("Getting {len} random bytes failed: {e:?}"));
+        assert_eq!(bytes.len(), len, "Random vector has the wrong length.");
+    }
+    rand.random_array::<32>()
+        .unwrap_or_else(|e| panic!("Getting a random array failed: {e:?}"));
+}
+
+/// Check that consecutive outputs differ and aren't constant.
+pub fn distinct_outputs(rand: &impl OpenMlsRand) {
+    let first = rand
+        .random_array::<32>()
+        .unwrap_or_else(|e| panic!("Getting a random array failed: {e:?}"));
+    let second = rand
+        .random_array::<32>()
+        .unwrap_or_else(|e| panic!("Getting a random array failed: {e:?}"));
+    assert_ne!(first, second, "Random arrays repeat.");
+
+    let first = rand
+        .random_vec(32)
+        .unwrap_or_else(|e| panic!("Getting random bytes failed: {e:?}"));
+    let second = rand
+        .random_vec(32)
+        .unwrap_or_else(|e| panic!("Getting random bytes failed: {e:?}"));
+    assert_ne!(first, second, "Random vectors repeat.");
+    assert!(
+        first.iter().any(|byte| *byte != first[0]),
+        "Random vector is constant."
+    );
+}
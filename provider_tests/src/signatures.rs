@@ -0,0 +1,172 @@
+//! # Signature Tests
+//!
+//! Known answer tests and round trips for all supported
+//! [`SignatureScheme`]s.
+
+use openmls_traits::{
+    crypto::OpenMlsCrypto,
+    types::{CryptoError, SignatureScheme},
+};
+
+use crate::hex;
+
+/// Test 1 from RFC 8032, Section 7.1: `(public key, message, signature)`.
+const ED25519_VECTOR: (&str, &str, &str) = (
+    "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+    "",
+    "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555\
+     fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+);
+
+/// The signature schemes that are covered by the tests.
+const SIGNATURE_SCHEMES: &[SignatureScheme] = &[
+    SignatureScheme::ECDSA_SECP256R1_SHA256,
+    SignatureScheme::ECDSA_SECP384R1_SHA384,
+    SignatureScheme::ECDSA_SECP521R1_SHA512,
+    SignatureScheme::ED25519,
+    SignatureScheme::ED448,
+    #[cfg(feature = "secp256k1")]
+    SignatureScheme::ECDSA_SECP256K1_SHA256,
+];
+
+/// Run all signature tests.
+pub fn run_all(crypto: &impl OpenMlsCrypto) {
+    known_answer_tests(crypto);
+    ciphersuite_schemes_supported(crypto);
+    round_trips(crypto);
+    batch_verification(crypto);
+}
+
+/// Check signature verification against the known answer tests.
+pub fn known_answer_tests(crypto: &impl OpenMlsCrypto) {
+    if crypto
+        .supports_signature_scheme(SignatureScheme::ED25519)
+        .is_ok()
+    {
+        let (pk, message, signature) = ED25519_VECTOR;
+        let (pk, message, mut signature) = (hex(pk), hex(message), hex(signature));
+        crypto
+            .verify_signature(SignatureScheme::ED25519, &message, &pk, &signature)
+            .expect("ED25519 signature from RFC 8032 didn't verify.");
+        signature[0] ^= 0xff;
+        assert!(
+            crypto
+                .verify_signature(SignatureScheme::ED25519, &message, &pk, &signature)
+                .is_err(),
+            "Modified ED25519 signature verified."
+        );
+    }
+}
+
+/// Check that the signature schemes of all supported ciphersuites are
+/// supported.
+pub fn ciphersuite_schemes_supported(crypto: &impl OpenMlsCrypto) {
+    for ciphersuite in crate::supported_ciphersuites(crypto) {
+        assert!(
+            crypto
+                .supports_signature_scheme(ciphersuite.signature_algorithm())
+                .is_ok(),
+            "The signature scheme of {ciphersuite:?} is not supported."
+        );
+    }
+}
+
+/// Generate keys, sign and verify with all supported [`SignatureScheme`]s and
+/// check that invalid signatures are rejected.
+pub fn round_trips(crypto: &impl OpenMlsCrypto) {
+    for scheme in supported_schemes(crypto) {
+        let message = b"OpenMLS provider tests";
+        let (sk, pk) = key_gen(crypto, scheme);
+        let (_other_sk, other_pk) = key_gen(crypto, scheme);
+        assert_ne!(pk, other_pk, "{scheme:?} generated the same key twice.");
+
+        let signature = crypto
+            .sign(scheme, message, &sk)
+            .unwrap_or_else(|e| panic!("{scheme:?} signing failed: {e:?}"));
+        crypto
+            .verify_signature(scheme, message, &pk, &signature)
+            .unwrap_or_else(|e| panic!("{scheme:?} signature didn't verify: {e:?}"));
+
+        assert!(
+            crypto
+                .verify_signature(scheme, b"other message", &pk, &signature)
+                .is_err(),
+            "{scheme:?} signature verified for the wrong message."
+        );
+        assert!(
+            crypto
+                .verify_signature(scheme, message, &other_pk, &signature)
+                .is_err(),
+            "{scheme:?} signature verified with the wrong public key."
+        );
+        let mut modified = signature.clone();
+        let last = modified.len() - 1;
+        modified[last] ^= 0x01;
+        assert!(
+            crypto
+                .verify_signature(scheme, message, &pk, &modified)
+                .is_err(),
+            "Modified {scheme:?} signature verified."
+        );
+        assert!(
+            crypto
+                .verify_signature(scheme, message, &pk, &signature[..signature.len() - 1])
+                .is_err(),
+            "Truncated {scheme:?} signature verified."
+        );
+    }
+}
+
+/// Check [`OpenMlsCrypto::verify_batch()`] for all supported
+/// [`SignatureScheme`]s.
+pub fn batch_verification(crypto: &impl OpenMlsCrypto) {
+    for scheme in supported_schemes(crypto) {
+        let messages: Vec<Vec<u8>> = (0..3u8).map(|i| vec![i; 32]).collect();
+        let keys: Vec<(Vec<u8>, Vec<u8>)> = (0..3).map(|_| key_gen(crypto, scheme)).collect();
+        let mut signatures: Vec<Vec<u8>> = messages
+            .iter()
+            .zip(&keys)
+            .map(|(message, (sk, _))| {
+                crypto
+                    .sign(scheme, message, sk)
+                    .unwrap_or_else(|e| panic!("{scheme:?} signing failed: {e:?}"))
+            })
+            .collect();
+
+        let messages: Vec<&[u8]> = messages.iter().map(|m| m.as_slice()).collect();
+        let public_keys: Vec<&[u8]> = keys.iter().map(|(_, pk)| pk.as_slice()).collect();
+
+        let signature_refs: Vec<&[u8]> = signatures.iter().map(|s| s.as_slice()).collect();
+        crypto
+            .verify_batch(scheme, &messages, &public_keys, &signature_refs)
+            .unwrap_or_else(|e| panic!("Valid {scheme:?} batch didn't verify: {e:?}"));
+        assert_eq!(
+            crypto.verify_batch(scheme, &messages[..2], &public_keys, &signature_refs),
+            Err(CryptoError::InvalidLength),
+            "{scheme:?} batch with mismatched lengths was not rejected."
+        );
+
+        signatures.swap(0, 1);
+        let signature_refs: Vec<&[u8]> = signatures.iter().map(|s| s.as_slice()).collect();
+        assert!(
+            crypto
+                .verify_batch(scheme, &messages, &public_keys, &signature_refs)
+                .is_err(),
+            "{scheme:?} batch with invalid signatures verified."
+        );
+    }
+}
+
+/// Returns the [`SignatureScheme`]s supported by `crypto`.
+fn supported_schemes(crypto: &impl OpenMlsCrypto) -> impl Iterator<Item = SignatureScheme> + '_ {
+    SIGNATURE_SCHEMES
+        .iter()
+        .copied()
+        .filter(|scheme| crypto.supports_signature_scheme(*scheme).is_ok())
+}
+
+fn key_gen(crypto: &impl OpenMlsCrypto, scheme: SignatureScheme) -> (Vec<u8>, Vec<u8>) {
+    crypto
+        .signature_key_gen(scheme)
+        .unwrap_or_else(|e| panic!("{scheme:?} key generation failed: {e:?}"))
+}
@@ -0,0 +1,6 @@
+use openmls_rust_crypto::OpenMlsRustCrypto;
+
+#[test]
+fn rust_crypto_conformance() {
+    openmls_provider_tests::run_all(&OpenMlsRustCrypto::default());
+}
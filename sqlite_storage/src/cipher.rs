@@ -0,0 +1,44 @@
+//! # Encryption at Rest
+//!
+//! The [`SqliteKeyStore`](crate::SqliteKeyStore) encrypts every value with a
+//! [`ValueCipher`] before it is written to the database and decrypts it after
+//! reading. The cipher is provided by the application, e.g. with a key that
+//! is kept in the key chain of the platform.
+
+/// Encrypts and decrypts the values of a
+/// [`SqliteKeyStore`](crate::SqliteKeyStore).
+///
+/// The key of the value is passed as associated data, so that an
+/// authenticated cipher can bind the value to its key. Otherwise values
+/// could be swapped between keys in the database.
+pub trait ValueCipher: Send + Sync {
+    /// Encrypts the serialized `value` that is stored for the `key`.
+    ///
+    /// Returns an error if encrypting fails.
+    fn encrypt(&self, key: &[u8], value: &[u8]) -> Result<Vec<u8>, ValueCipherError>;
+
+    /// Decrypts the `ciphertext` that is stored for the `key`.
+    ///
+    /// Returns an error if decrypting fails, e.g. if the ciphertext was
+    /// modified.
+    fn decrypt(&self, key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, ValueCipherError>;
+}
+
+/// A [`ValueCipher`] that stores values without encryption.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PlaintextValues;
+
+impl ValueCipher for PlaintextValues {
+    fn encrypt(&self, _key: &[u8], value: &[u8]) -> Result<Vec<u8>, ValueCipherError> {
+        Ok(value.to_vec())
+    }
+
+    fn decrypt(&self, _key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, ValueCipherError> {
+        Ok(ciphertext.to_vec())
+    }
+}
+
+/// Error of a [`ValueCipher`].
+#[derive(thiserror::Error, Debug, PartialEq, Eq, Clone, Copy)]
+#[error("Encrypting or decrypting a value failed.")]
+pub struct ValueCipherError;
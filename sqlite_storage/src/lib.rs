@@ -0,0 +1,289 @@
+//! # OpenMLS SQLite Storage
+//!
+//! This is an implementation of the [`OpenMlsKeyStore`] trait on top of
+//! SQLite. Since every [`OpenMlsKeyStore`] is a
+//! [`StorageProvider`](openmls_traits::storage::StorageProvider), the
+//! [`SqliteKeyStore`] also persists the state of groups.
+//!
+//! Each kind of entity (see [`MlsEntityId`]) is stored in its own table.
+//! Batches of writes (see [`OpenMlsKeyStore::apply_batch()`]) are applied in
+//! one SQLite transaction, so that e.g. merging a commit either writes all of
+//! its changes or none.
+//!
+//! Values can be encrypted at rest with a [`ValueCipher`].
+
+use std::{path::Path, sync::Mutex};
+
+use openmls_traits::key_store::{
+    BatchOperation, MlsEntity, MlsEntityId, OpenMlsKeyStore, WriteBatch,
+};
+use rusqlite::{params, Connection, OptionalExtension};
+
+mod cipher;
+pub use cipher::*;
+
+#[cfg(feature = "rust-crypto")]
+mod provider;
+#[cfg(feature = "rust-crypto")]
+pub use provider::*;
+
+/// The version of the database schema. It is stored in the `user_version` of
+/// the database.
+const SCHEMA_VERSION: i32 = 4;
+
+/// The tables of the entities, one per [`MlsEntityId`].
+const TABLES: [&str; 10] = [
+    "signature_key_pairs",
+    "hpke_private_keys",
+    "key_packages",
+    "psk_bundles",
+    "encryption_key_pairs",
+    "group_states",
+    "queued_proposals",
+    "client_states",
+    "receiver_states",
+    "sender_ratchets",
+];
+
+/// Returns the table of the entities with the given ID.
+fn table(entity_id: MlsEntityId) -> &'static str {
+    match entity_id {
+        MlsEntityId::SignatureKeyPair => TABLES[0],
+        MlsEntityId::HpkePrivateKey => TABLES[1],
+        MlsEntityId::KeyPackage => TABLES[2],
+        MlsEntityId::PskBundle => TABLES[3],
+        MlsEntityId::EncryptionKeyPair => TABLES[4],
+        MlsEntityId::GroupState => TABLES[5],
+        MlsEntityId::QueuedProposals => TABLES[6],
+        MlsEntityId::ClientState => TABLES[7],
+        MlsEntityId::ReceiverState => TABLES[8],
+        MlsEntityId::SenderRatchets => TABLES[9],
+    }
+}
+
+/// A key store that stores its values in a SQLite database.
+///
+/// Values are serialized as JSON and encrypted with the [`ValueCipher`]
+/// before they are written to the database.
+pub struct SqliteKeyStore<Cipher: ValueCipher = PlaintextValues> {
+    connection: Mutex<Connection>,
+    cipher: Cipher,
+}
+
+impl SqliteKeyStore {
+    /// Opens the database at `path` and creates the tables if necessary.
+    /// Values are stored without encryption.
+    ///
+    /// Returns an error if the database can't be opened or was created by a
+    /// newer version of this crate.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SqliteKeyStoreError> {
+        Self::from_connection(Connection::open(path)?, PlaintextValues)
+    }
+
+    /// Opens a new database in memory, e.g. for tests.
+    ///
+    /// Returns an error if the database can't be opened.
+    pub fn open_in_memory() -> Result<Self, SqliteKeyStoreError> {
+        Self::from_connection(Connection::open_in_memory()?, PlaintextValues)
+    }
+}
+
+impl<Cipher: ValueCipher> SqliteKeyStore<Cipher> {
+    /// Opens the database at `path` and creates the tables if necessary.
+    /// Values are encrypted with the `cipher`.
+    ///
+    /// Returns an error if the database can't be opened or was created by a
+    /// newer version of this crate.
+    pub fn open_with_cipher(
+        path: impl AsRef<Path>,
+        cipher: Cipher,
+    ) -> Result<Self, SqliteKeyStoreError> {
+        Self::from_connection(Connection::open(path)?, cipher)
+    }
+
+    /// Uses an existing `connection`, e.g. one that was opened with custom
+    /// flags, and creates the tables if necessary. Values are encrypted with
+    /// the `cipher`.
+    ///
+    /// Returns an error if the tables can't be created or the database was
+    /// created by a newer version of this crate.
+    pub fn from_connection(
+        connection: Connection,
+        cipher: Cipher,
+    ) -> Result<Self, SqliteKeyStoreError> {
+        let version: i32 = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        if version > SCHEMA_VERSION {
+            return Err(SqliteKeyStoreError::UnsupportedSchemaVersion);
+        }
+        for table in TABLES {
+            connection.execute_batch(&format!(
+                "CREATE TABLE IF NOT EXISTS {table} (key BLOB PRIMARY KEY, value BLOB NOT NULL);"
+            ))?;
+        }
+        connection.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+            cipher,
+        })
+    }
+
+    /// Runs `f` with the connection to the database.
+    fn with_connection<T>(
+        &self,
+        f: impl FnOnce(&Connection) -> Result<T, SqliteKeyStoreError>,
+    ) -> Result<T, SqliteKeyStoreError> {
+        let connection = self
+            .connection
+            .lock()
+            .map_err(|_| SqliteKeyStoreError::LockPoisoned)?;
+        f(&connection)
+    }
+}
+
+impl<Cipher: ValueCipher> std::fmt::Debug for SqliteKeyStore<Cipher> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SqliteKeyStore").finish_non_exhaustive()
+    }
+}
+
+impl<Cipher: ValueCipher> OpenMlsKeyStore for SqliteKeyStore<Cipher> {
+    /// The error type returned by the [`OpenMlsKeyStore`].
+    type Error = SqliteKeyStoreError;
+
+    /// Store a value `v` that implements the [`MlsEntity`] trait for
+    /// serialization for ID `k`.
+    ///
+    /// Returns an error if storing fails.
+    fn store<V: MlsEntity>(&self, k: &[u8], v: &V) -> Result<(), Self::Error> {
+        let value = self.encode(v)?;
+        self.store_encoded(V::ID, k, &value)
+    }
+
+    /// Read and return a value stored for ID `k` that implements the
+    /// [`MlsEntity`] trait for deserialization.
+    ///
+    /// Returns [`None`] if no value is stored for `k` or reading fails.
+    fn read<V: MlsEntity>(&self, k: &[u8]) -> Option<V> {
+        let value: Vec<u8> = self
+            .with_connection(|connection| {
+                Ok(connection
+                    .query_row(
+                        &format!("SELECT value FROM {} WHERE key = ?1", table(V::ID)),
+                        [k],
+                        |row| row.get(0),
+                    )
+                    .optional()?)
+            })
+            .ok()??;
+        let value = self.cipher.decrypt(k, &value).ok()?;
+        self.decode(&value)
+    }
+
+    /// Delete a value stored for ID `k`.
+    ///
+    /// Returns an error if deleting fails.
+    fn delete<V: MlsEntity>(&self, k: &[u8]) -> Result<(), Self::Error> {
+        self.with_connection(|connection| {
+            connection.execute(&format!("DELETE FROM {} WHERE key = ?1", table(V::ID)), [k])?;
+            Ok(())
+        })
+    }
+
+    /// Encode the value `v` as JSON. The value is encrypted when it is
+    /// stored.
+    ///
+    /// Returns an error if serializing fails.
+    fn encode<V: MlsEntity>(&self, v: &V) -> Result<Vec<u8>, Self::Error> {
+        serde_json::to_vec(v).map_err(|_| SqliteKeyStoreError::SerializationError)
+    }
+
+    /// Decode a value that was encoded with [`Self::encode()`].
+    ///
+    /// Returns [`None`] if deserializing fails.
+    fn decode<V: MlsEntity>(&self, value: &[u8]) -> Option<V> {
+        serde_json::from_slice(value).ok()
+    }
+
+    /// Encrypt and store a `value` that was encoded with [`Self::encode()`]
+    /// for ID `k` in the table of the `entity_id`.
+    ///
+    /// Returns an error if encrypting or storing fails.
+    fn store_encoded(
+        &self,
+        entity_id: MlsEntityId,
+        k: &[u8],
+        value: &[u8],
+    ) -> Result<(), Self::Error> {
+        let value = self.cipher.encrypt(k, value)?;
+        self.with_connection(|connection| {
+            connection.execute(
+                &format!(
+                    "INSERT OR REPLACE INTO {} (key, value) VALUES (?1, ?2)",
+                    table(entity_id)
+                ),
+                params![k, value],
+            )?;
+            Ok(())
+        })
+    }
+
+    /// Apply all writes and deletions of the `batch` in one SQLite
+    /// transaction.
+    ///
+    /// Returns an error if a value can't be encrypted, or the transaction
+    /// fails. In that case none of the operations is applied.
+    fn apply_batch(&self, batch: WriteBatch) -> Result<(), Self::Error> {
+        self.with_connection(|connection| {
+            // The transaction is rolled back when it is dropped without being
+            // committed. It can't be nested into another one, since the
+            // connection is locked.
+            let transaction = connection.unchecked_transaction()?;
+            for operation in batch.into_operations() {
+                match operation {
+                    BatchOperation::Store {
+                        entity_id,
+                        key,
+                        value,
+                    } => {
+                        let value = self.cipher.encrypt(&key, &value)?;
+                        transaction.execute(
+                            &format!(
+                                "INSERT OR REPLACE INTO {} (key, value) VALUES (?1, ?2)",
+                                table(entity_id)
+                            ),
+                            params![key, value],
+                        )?;
+                    }
+                    BatchOperation::Delete { entity_id, key } => {
+                        transaction.execute(
+                            &format!("DELETE FROM {} WHERE key = ?1", table(entity_id)),
+                            [key],
+                        )?;
+                    }
+                }
+            }
+            Ok(transaction.commit()?)
+        })
+    }
+}
+
+/// Errors thrown by the [`SqliteKeyStore`].
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum SqliteKeyStoreError {
+    /// Error accessing the database.
+    #[error(transparent)]
+    SqliteError(#[from] rusqlite::Error),
+    /// Error serializing a value.
+    #[error("Error serializing value.")]
+    SerializationError,
+    /// Error encrypting a value.
+    #[error(transparent)]
+    CipherError(#[from] ValueCipherError),
+    /// The database was created by a newer version of this crate.
+    #[error("The database was created by a newer version of this crate.")]
+    UnsupportedSchemaVersion,
+    /// The lock on the connection is poisoned.
+    #[error("The lock on the connection is poisoned.")]
+    LockPoisoned,
+}
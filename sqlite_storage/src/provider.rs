@@ -0,0 +1,45 @@
+//! # SQLite Crypto Provider
+//!
+//! A provider that combines the RustCrypto primitives of
+//! `openmls_rust_crypto` with a [`SqliteKeyStore`].
+
+use openmls_rust_crypto::RustCrypto;
+use openmls_traits::OpenMlsCryptoProvider;
+
+use crate::{PlaintextValues, SqliteKeyStore, ValueCipher};
+
+/// An implementation of the [`OpenMlsCryptoProvider`] trait that stores keys
+/// and group states in a [`SqliteKeyStore`].
+#[derive(Debug)]
+pub struct OpenMlsSqliteRustCrypto<Cipher: ValueCipher = PlaintextValues> {
+    crypto: RustCrypto,
+    key_store: SqliteKeyStore<Cipher>,
+}
+
+impl<Cipher: ValueCipher> OpenMlsSqliteRustCrypto<Cipher> {
+    /// Creates a provider with the given `key_store`.
+    pub fn new(key_store: SqliteKeyStore<Cipher>) -> Self {
+        Self {
+            crypto: RustCrypto::default(),
+            key_store,
+        }
+    }
+}
+
+impl<Cipher: ValueCipher> OpenMlsCryptoProvider for OpenMlsSqliteRustCrypto<Cipher> {
+    type CryptoProvider = RustCrypto;
+    type RandProvider = RustCrypto;
+    type KeyStoreProvider = SqliteKeyStore<Cipher>;
+
+    fn crypto(&self) -> &Self::CryptoProvider {
+        &self.crypto
+    }
+
+    fn rand(&self) -> &Self::RandProvider {
+        &self.crypto
+    }
+
+    fn key_store(&self) -> &Self::KeyStoreProvider {
+        &self.key_store
+    }
+}
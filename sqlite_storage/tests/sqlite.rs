@@ -0,0 +1,122 @@
+use openmls_sqlite_storage::{PlaintextValues, SqliteKeyStore, ValueCipher, ValueCipherError};
+use openmls_traits::key_store::{MlsEntity, MlsEntityId, OpenMlsKeyStore, WriteBatch};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct TestEntity(Vec<u8>);
+
+impl MlsEntity for TestEntity {
+    const ID: MlsEntityId = MlsEntityId::GroupState;
+}
+
+/// A cipher that XORs the value with the key, which is enough to check that
+/// values are stored encrypted.
+struct XorCipher;
+
+impl ValueCipher for XorCipher {
+    fn encrypt(&self, key: &[u8], value: &[u8]) -> Result<Vec<u8>, ValueCipherError> {
+        Ok(value
+            .iter()
+            .zip(key.iter().cycle())
+            .map(|(v, k)| v ^ k ^ 0xff)
+            .collect())
+    }
+
+    fn decrypt(&self, key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, ValueCipherError> {
+        self.encrypt(key, ciphertext)
+    }
+}
+
+/// A cipher that fails to encrypt values for keys that start with `failing`.
+struct FailingCipher;
+
+impl ValueCipher for FailingCipher {
+    fn encrypt(&self, key: &[u8], value: &[u8]) -> Result<Vec<u8>, ValueCipherError> {
+        if key.starts_with(b"failing") {
+            return Err(ValueCipherError);
+        }
+        Ok(value.to_vec())
+    }
+
+    fn decrypt(&self, _key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, ValueCipherError> {
+        Ok(ciphertext.to_vec())
+    }
+}
+
+/// Returns a path for a database file that doesn't exist yet.
+fn database_path(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "openmls_sqlite_storage_{name}_{}.db",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn key_store_conformance() {
+    let key_store = SqliteKeyStore::open_in_memory().expect("Error opening the database.");
+    openmls_provider_tests::key_store::run_all(&key_store);
+}
+
+#[test]
+fn write_batches() {
+    let key_store = SqliteKeyStore::open_in_memory().expect("Error opening the database.");
+    let value = TestEntity(vec![1, 2, 3]);
+
+    // Building a batch doesn't write anything
+    let encoded_value = key_store.encode(&value).unwrap();
+    let mut batch = WriteBatch::new();
+    batch.store(TestEntity::ID, b"key", encoded_value.clone());
+    batch.store(TestEntity::ID, b"other key", encoded_value.clone());
+    batch.delete::<TestEntity>(b"other key");
+    assert_eq!(key_store.read::<TestEntity>(b"key"), None);
+    assert_eq!(
+        batch.read::<TestEntity>(b"key"),
+        Some(Some(encoded_value.as_slice()))
+    );
+    assert_eq!(
+        key_store.decode::<TestEntity>(&encoded_value),
+        Some(value.clone())
+    );
+    assert_eq!(batch.read::<TestEntity>(b"other key"), Some(None));
+
+    // Applied writes and deletions are persisted
+    key_store.apply_batch(batch).unwrap();
+    assert_eq!(key_store.read::<TestEntity>(b"key"), Some(value.clone()));
+    assert_eq!(key_store.read::<TestEntity>(b"other key"), None);
+
+    // A failing batch doesn't apply any of its operations
+    let key_store = SqliteKeyStore::open_with_cipher(database_path("write_batches"), FailingCipher)
+        .expect("Error opening the database.");
+    let encoded_value = key_store.encode(&value).unwrap();
+    let mut batch = WriteBatch::new();
+    batch.store(TestEntity::ID, b"key", encoded_value.clone());
+    batch.store(TestEntity::ID, b"failing key", encoded_value);
+    assert!(key_store.apply_batch(batch).is_err());
+    assert_eq!(key_store.read::<TestEntity>(b"key"), None);
+}
+
+#[test]
+fn encryption_at_rest() {
+    let path = database_path("encryption_at_rest");
+    let value = TestEntity(b"secret".to_vec());
+
+    let key_store =
+        SqliteKeyStore::open_with_cipher(&path, XorCipher).expect("Error opening the database.");
+    key_store.store(b"key", &value).unwrap();
+    drop(key_store);
+
+    // The value can only be read with the cipher
+    let key_store = SqliteKeyStore::open_with_cipher(&path, PlaintextValues)
+        .expect("Error opening the database.");
+    assert_eq!(key_store.read::<TestEntity>(b"key"), None);
+    drop(key_store);
+
+    let key_store =
+        SqliteKeyStore::open_with_cipher(&path, XorCipher).expect("Error opening the database.");
+    assert_eq!(key_store.read::<TestEntity>(b"key"), Some(value));
+    drop(key_store);
+
+    std::fs::remove_file(path).unwrap();
+}
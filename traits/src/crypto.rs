@@ -6,7 +6,7 @@ use tls_codec::SecretVLBytes;
 
 use crate::types::{
     AeadType, Ciphersuite, CryptoError, ExporterSecret, HashType, HpkeCiphertext, HpkeConfig,
-    HpkeKeyPair, KemOutput, SignatureScheme,
+    HpkeKemType, HpkeKeyPair, KemOutput, SignatureScheme,
 };
 
 pub trait OpenMlsCrypto {
@@ -18,6 +18,65 @@ pub trait OpenMlsCrypto {
     /// Returns the list of supported [`Ciphersuite`]s.
     fn supported_ciphersuites(&self) -> Vec<Ciphersuite>;
 
+    /// Check whether the [`SignatureScheme`] is supported by the backend or not.
+    ///
+    /// Returns a [`CryptoError::UnsupportedSignatureScheme`] if the signature
+    /// scheme is not supported.
+    ///
+    /// The default implementation considers a signature scheme supported if it
+    /// is used by any of the [`supported_ciphersuites`](Self::supported_ciphersuites).
+    fn supports_signature_scheme(&self, scheme: SignatureScheme) -> Result<(), CryptoError> {
+        self.supported_ciphersuites()
+            .iter()
+            .any(|cs| cs.signature_algorithm() == scheme)
+            .then_some(())
+            .ok_or(CryptoError::UnsupportedSignatureScheme)
+    }
+
+    /// Check whether the [`AeadType`] is supported by the backend or not.
+    ///
+    /// Returns a [`CryptoError::UnsupportedAeadAlgorithm`] if the AEAD is not
+    /// supported.
+    ///
+    /// The default implementation considers an AEAD supported if it is used by
+    /// any of the [`supported_ciphersuites`](Self::supported_ciphersuites).
+    fn supports_aead(&self, aead: AeadType) -> Result<(), CryptoError> {
+        self.supported_ciphersuites()
+            .iter()
+            .any(|cs| cs.aead_algorithm() == aead)
+            .then_some(())
+            .ok_or(CryptoError::UnsupportedAeadAlgorithm)
+    }
+
+    /// Check whether the [`HashType`] is supported by the backend or not.
+    ///
+    /// Returns a [`CryptoError::UnsupportedHashAlgorithm`] if the hash is not
+    /// supported.
+    ///
+    /// The default implementation considers a hash supported if it is used by
+    /// any of the [`supported_ciphersuites`](Self::supported_ciphersuites).
+    fn supports_hash(&self, hash_type: HashType) -> Result<(), CryptoError> {
+        self.supported_ciphersuites()
+            .iter()
+            .any(|cs| cs.hash_algorithm() == hash_type)
+            .then_some(())
+            .ok_or(CryptoError::UnsupportedHashAlgorithm)
+    }
+
+    /// Check whether the [`HpkeKemType`] is supported by the backend or not.
+    ///
+    /// Returns a [`CryptoError::UnsupportedKem`] if the KEM is not supported.
+    ///
+    /// The default implementation considers a KEM supported if it is used by
+    /// any of the [`supported_ciphersuites`](Self::supported_ciphersuites).
+    fn supports_hpke_kem(&self, kem: HpkeKemType) -> Result<(), CryptoError> {
+        self.supported_ciphersuites()
+            .iter()
+            .any(|cs| cs.hpke_kem_algorithm() == kem)
+            .then_some(())
+            .ok_or(CryptoError::UnsupportedKem)
+    }
+
     /// HKDF extract.
     ///
     /// Returns an error if the [`HashType`] is not supported.
@@ -89,6 +148,33 @@ pub trait OpenMlsCrypto {
         signature: &[u8],
     ) -> Result<(), CryptoError>;
 
+    /// Verify a batch of signatures that all use the same [`SignatureScheme`].
+    ///
+    /// The `i`-th signature is verified over the `i`-th message with the
+    /// `i`-th public key. Returns `Ok(())` only if all signatures are valid,
+    /// [`CryptoError::InvalidLength`] if the slices don't have the same
+    /// length and an error otherwise.
+    ///
+    /// Providers that support batch verification for `alg` should override
+    /// this function. The default implementation verifies each signature with
+    /// [`OpenMlsCrypto::verify_signature`].
+    fn verify_batch(
+        &self,
+        alg: SignatureScheme,
+        messages: &[&[u8]],
+        public_keys: &[&[u8]],
+        signatures: &[&[u8]],
+    ) -> Result<(), CryptoError> {
+        if messages.len() != public_keys.len() || messages.len() != signatures.len() {
+            return Err(CryptoError::InvalidLength);
+        }
+        messages
+            .iter()
+            .zip(public_keys)
+            .zip(signatures)
+            .try_for_each(|((data, pk), signature)| self.verify_signature(alg, data, pk, signature))
+    }
+
     /// Sign with the given parameters.
     ///
     /// Returns an error if the [`SignatureScheme`] is not supported or an error
@@ -98,6 +184,9 @@ pub trait OpenMlsCrypto {
     // === HPKE === //
 
     /// HPKE single-shot encryption of `ptxt` to `pk_r`, using `info` and `aad`.
+    ///
+    /// Returns an error if the KEM of the `config` is not supported or
+    /// encryption fails.
     fn hpke_seal(
         &self,
         config: HpkeConfig,
@@ -105,7 +194,7 @@ pub trait OpenMlsCrypto {
         info: &[u8],
         aad: &[u8],
         ptxt: &[u8],
-    ) -> HpkeCiphertext;
+    ) -> Result<HpkeCiphertext, CryptoError>;
 
     /// HPKE single-shot decryption of `input` with `sk_r`, using `info` and
     /// `aad`.
@@ -144,5 +233,11 @@ pub trait OpenMlsCrypto {
     ) -> Result<ExporterSecret, CryptoError>;
 
     /// Derive a new HPKE keypair from a given input key material.
-    fn derive_hpke_keypair(&self, config: HpkeConfig, ikm: &[u8]) -> HpkeKeyPair;
+    ///
+    /// Returns an error if the KEM of the `config` is not supported.
+    fn derive_hpke_keypair(
+        &self,
+        config: HpkeConfig,
+        ikm: &[u8],
+    ) -> Result<HpkeKeyPair, CryptoError>;
 }
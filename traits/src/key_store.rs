@@ -1,6 +1,7 @@
 //! # OpenMLS Key Store Trait
 
 /// Sealed list of struct openmls manages (create/read/delete) through [OpenMlsKeyStore]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MlsEntityId {
     SignatureKeyPair,
     HpkePrivateKey,
@@ -8,6 +9,10 @@ pub enum MlsEntityId {
     PskBundle,
     EncryptionKeyPair,
     GroupState,
+    QueuedProposals,
+    ClientState,
+    ReceiverState,
+    SenderRatchets,
 }
 
 /// To implement by any struct owned by openmls aiming to be persisted in [OpenMlsKeyStore]
@@ -50,4 +55,352 @@ pub trait OpenMlsKeyStore: Send + Sync {
     ///
     /// Returns an error if storing fails.
     fn delete<V: MlsEntity>(&self, k: &[u8]) -> Result<(), Self::Error>;
+
+    /// Encode the value `v` the way [`store()`](Self::store) writes it.
+    ///
+    /// Values are encoded with this function when they are added to a
+    /// [`WriteBatch`], so that the batch holds them in the encoding of the
+    /// key store it is applied to.
+    ///
+    /// Returns an error if encoding fails.
+    fn encode<V: MlsEntity>(&self, v: &V) -> Result<Vec<u8>, Self::Error>
+    where
+        Self: Sized;
+
+    /// Decode a value that was encoded with [`encode()`](Self::encode).
+    ///
+    /// Returns [`None`] if decoding fails.
+    fn decode<V: MlsEntity>(&self, value: &[u8]) -> Option<V>
+    where
+        Self: Sized;
+
+    /// Store a `value` that was encoded with [`encode()`](Self::encode) for
+    /// ID `k`, like [`store()`](Self::store) stores an entity of the kind
+    /// `entity_id`.
+    ///
+    /// Returns an error if storing fails.
+    fn store_encoded(
+        &self,
+        entity_id: MlsEntityId,
+        k: &[u8],
+        value: &[u8],
+    ) -> Result<(), Self::Error>;
+
+    /// Apply all writes and deletions of the `batch` in their order.
+    ///
+    /// The operations must be applied together or not at all, e.g. if the
+    /// process crashes in between. OpenMLS uses a batch when merging a commit,
+    /// which replaces the encryption key pairs of a group together with the
+    /// group state.
+    ///
+    /// The default implementation applies the operations one by one through
+    /// [`store_encoded()`](Self::store_encoded) and
+    /// [`delete()`](Self::delete), i.e. it is not atomic.
+    ///
+    /// Returns an error if applying the batch fails.
+    fn apply_batch(&self, batch: WriteBatch) -> Result<(), Self::Error>
+    where
+        Self: Sized,
+    {
+        for operation in batch.into_operations() {
+            operation.apply(self)?;
+        }
+        Ok(())
+    }
 }
+
+/// The async Key Store trait
+///
+/// Like [`OpenMlsKeyStore`], but all operations return futures, e.g. for key
+/// stores that are backed by a remote service.
+#[cfg(feature = "async")]
+pub trait OpenMlsAsyncKeyStore: Send + Sync {
+    /// The error type returned by the [`OpenMlsAsyncKeyStore`].
+    type Error: std::error::Error + std::fmt::Debug + PartialEq;
+
+    /// Store a value `v` that implements the [`MlsEntity`] trait for
+    /// serialization for ID `k`.
+    ///
+    /// The value is `Sync`, so that the returned future can hold a reference
+    /// to it and still be `Send`.
+    ///
+    /// Returns an error if storing fails.
+    fn store<V: MlsEntity + Sync>(
+        &self,
+        k: &[u8],
+        v: &V,
+    ) -> impl std::future::Future<Output = Result<(), Self::Error>> + Send
+    where
+        Self: Sized;
+
+    /// Read and return a value stored for ID `k` that implements the
+    /// [`MlsEntity`] trait for deserialization.
+    ///
+    /// Returns [`None`] if no value is stored for `k` or reading fails.
+    fn read<V: MlsEntity + Send>(
+        &self,
+        k: &[u8],
+    ) -> impl std::future::Future<Output = Option<V>> + Send
+    where
+        Self: Sized;
+
+    /// Delete a value stored for ID `k`.
+    ///
+    /// Returns an error if storing fails.
+    fn delete<V: MlsEntity>(
+        &self,
+        k: &[u8],
+    ) -> impl std::future::Future<Output = Result<(), Self::Error>> + Send
+    where
+        Self: Sized;
+
+    /// Encode the value `v` the way [`store()`](Self::store) writes it. See
+    /// [`OpenMlsKeyStore::encode()`].
+    ///
+    /// Returns an error if encoding fails.
+    fn encode<V: MlsEntity>(&self, v: &V) -> Result<Vec<u8>, Self::Error>
+    where
+        Self: Sized;
+
+    /// Decode a value that was encoded with [`encode()`](Self::encode).
+    ///
+    /// Returns [`None`] if decoding fails.
+    fn decode<V: MlsEntity>(&self, value: &[u8]) -> Option<V>
+    where
+        Self: Sized;
+
+    /// Store a `value` that was encoded with [`encode()`](Self::encode) for
+    /// ID `k`. See [`OpenMlsKeyStore::store_encoded()`].
+    ///
+    /// Returns an error if storing fails.
+    fn store_encoded(
+        &self,
+        entity_id: MlsEntityId,
+        k: &[u8],
+        value: &[u8],
+    ) -> impl std::future::Future<Output = Result<(), Self::Error>> + Send;
+
+    /// Apply all writes and deletions of the `batch` in their order. See
+    /// [`OpenMlsKeyStore::apply_batch()`].
+    ///
+    /// The default implementation applies the operations one by one, i.e. it
+    /// is not atomic.
+    fn apply_batch(
+        &self,
+        batch: WriteBatch,
+    ) -> impl std::future::Future<Output = Result<(), Self::Error>> + Send
+    where
+        Self: Sized,
+    {
+        async move {
+            for operation in batch.into_operations() {
+                operation.apply_async(self).await?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// A batch of writes and deletions that a key store applies together, see
+/// [`OpenMlsKeyStore::apply_batch()`].
+///
+/// The batch is owned by whoever builds it. Building it doesn't touch the key
+/// store, so that the batches of concurrent operations don't interfere with
+/// each other. The values in the batch are encoded with
+/// [`OpenMlsKeyStore::encode()`] of the key store that the batch is applied
+/// to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WriteBatch {
+    operations: Vec<BatchOperation>,
+}
+
+impl WriteBatch {
+    /// Create an empty batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a write of an entity of the kind `entity_id` for ID `k` to the
+    /// batch. The `value` has to be encoded with
+    /// [`OpenMlsKeyStore::encode()`] of the key store that the batch is
+    /// applied to.
+    pub fn store(&mut self, entity_id: MlsEntityId, k: &[u8], value: Vec<u8>) {
+        self.operations.push(BatchOperation::Store {
+            entity_id,
+            key: k.to_vec(),
+            value,
+        });
+    }
+
+    /// Add a deletion of the value for ID `k` to the batch.
+    pub fn delete<V: MlsEntity>(&mut self, k: &[u8]) {
+        self.operations.push(BatchOperation::Delete {
+            entity_id: V::ID,
+            key: k.to_vec(),
+        });
+    }
+
+    /// Read the encoded value of an entity `V` for ID `k` as it is after the
+    /// batch is applied.
+    ///
+    /// Returns [`None`] if the batch doesn't write or delete `k`, and
+    /// `Some(None)` if it deletes `k`.
+    pub fn read<V: MlsEntity>(&self, k: &[u8]) -> Option<Option<&[u8]>> {
+        self.operations
+            .iter()
+            .rev()
+            .find(|operation| operation.entity_id() == V::ID && operation.key() == k)
+            .map(|operation| match operation {
+                BatchOperation::Store { value, .. } => Some(value.as_slice()),
+                BatchOperation::Delete { .. } => None,
+            })
+    }
+
+    /// Returns `true` if the batch contains no operations.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// The operations of the batch in the order they were added.
+    pub fn operations(&self) -> &[BatchOperation] {
+        &self.operations
+    }
+
+    /// Consume the batch and return its operations in the order they were
+    /// added.
+    pub fn into_operations(self) -> Vec<BatchOperation> {
+        self.operations
+    }
+}
+
+/// A write or deletion in a [`WriteBatch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchOperation {
+    /// Store a value for ID `key`. The value is encoded with
+    /// [`OpenMlsKeyStore::encode()`] of the key store the batch is applied
+    /// to.
+    Store {
+        entity_id: MlsEntityId,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    /// Delete the value stored for ID `key`.
+    Delete {
+        entity_id: MlsEntityId,
+        key: Vec<u8>,
+    },
+}
+
+impl BatchOperation {
+    /// The kind of entity that is written or deleted.
+    pub fn entity_id(&self) -> MlsEntityId {
+        match self {
+            BatchOperation::Store { entity_id, .. } | BatchOperation::Delete { entity_id, .. } => {
+                *entity_id
+            }
+        }
+    }
+
+    /// The ID that is written or deleted.
+    pub fn key(&self) -> &[u8] {
+        match self {
+            BatchOperation::Store { key, .. } | BatchOperation::Delete { key, .. } => key,
+        }
+    }
+
+    /// Apply the operation on its own to the given `key_store`.
+    ///
+    /// Returns an error if storing or deleting fails.
+    pub fn apply<KeyStore: OpenMlsKeyStore>(
+        self,
+        key_store: &KeyStore,
+    ) -> Result<(), KeyStore::Error> {
+        match self {
+            BatchOperation::Store {
+                entity_id,
+                key,
+                value,
+            } => key_store.store_encoded(entity_id, &key, &value),
+            BatchOperation::Delete { entity_id, key } => {
+                delete_batch_entity(key_store, entity_id, &key)
+            }
+        }
+    }
+
+    /// Apply the operation on its own to the given async `key_store`.
+    ///
+    /// Returns an error if storing or deleting fails.
+    #[cfg(feature = "async")]
+    pub async fn apply_async<KeyStore: OpenMlsAsyncKeyStore>(
+        self,
+        key_store: &KeyStore,
+    ) -> Result<(), KeyStore::Error> {
+        match self {
+            BatchOperation::Store {
+                entity_id,
+                key,
+                value,
+            } => key_store.store_encoded(entity_id, &key, &value).await,
+            BatchOperation::Delete { entity_id, key } => {
+                delete_batch_entity_async(key_store, entity_id, &key).await
+            }
+        }
+    }
+}
+
+/// The entity with the ID whose discriminant is `ID`, which is used to delete
+/// values of that kind of entity in a [`WriteBatch`] through
+/// [`OpenMlsKeyStore::delete()`].
+#[derive(serde::Serialize, serde::Deserialize)]
+struct BatchEntity<const ID: u8>;
+
+macro_rules! batch_entities {
+    ($($id:ident),*) => {
+        $(
+            impl MlsEntity for BatchEntity<{ MlsEntityId::$id as u8 }> {
+                const ID: MlsEntityId = MlsEntityId::$id;
+            }
+        )*
+
+        fn delete_batch_entity<KeyStore: OpenMlsKeyStore>(
+            key_store: &KeyStore,
+            entity_id: MlsEntityId,
+            key: &[u8],
+        ) -> Result<(), KeyStore::Error> {
+            match entity_id {
+                $(
+                    MlsEntityId::$id => key_store
+                        .delete::<BatchEntity<{ MlsEntityId::$id as u8 }>>(key),
+                )*
+            }
+        }
+
+        #[cfg(feature = "async")]
+        async fn delete_batch_entity_async<KeyStore: OpenMlsAsyncKeyStore>(
+            key_store: &KeyStore,
+            entity_id: MlsEntityId,
+            key: &[u8],
+        ) -> Result<(), KeyStore::Error> {
+            match entity_id {
+                $(
+                    MlsEntityId::$id => key_store
+                        .delete::<BatchEntity<{ MlsEntityId::$id as u8 }>>(key)
+                        .await,
+                )*
+            }
+        }
+    };
+}
+
+batch_entities!(
+    SignatureKeyPair,
+    HpkePrivateKey,
+    KeyPackage,
+    PskBundle,
+    EncryptionKeyPair,
+    GroupState,
+    QueuedProposals,
+    ClientState,
+    ReceiverState,
+    SenderRatchets
+);
@@ -1,3 +1,5 @@
+use std::{fmt::Debug, sync::Arc};
+
 use crate::types::{Error, SignatureScheme};
 
 /// Sign the provided payload and return a signature.
@@ -10,3 +12,176 @@ pub trait Signer {
     /// The [`SignatureScheme`] of this signer.
     fn signature_scheme(&self) -> SignatureScheme;
 }
+
+/// Sign the provided payload asynchronously and return a signature.
+///
+/// Like [`Signer`], but signing returns a future, e.g. for signers that
+/// delegate signing to a remote signing service or a secure enclave such that
+/// the private key never enters the process.
+///
+/// Every [`Signer`] is an [`AsyncSigner`] as well.
+pub trait AsyncSigner {
+    /// Sign the provided payload.
+    ///
+    /// Returns a signature on success or an Error.
+    fn sign(
+        &self,
+        payload: &[u8],
+    ) -> impl std::future::Future<Output = Result<Vec<u8>, Error>> + Send;
+
+    /// The [`SignatureScheme`] of this signer.
+    fn signature_scheme(&self) -> SignatureScheme;
+}
+
+impl<S: Signer> AsyncSigner for S {
+    fn sign(
+        &self,
+        payload: &[u8],
+    ) -> impl std::future::Future<Output = Result<Vec<u8>, Error>> + Send {
+        std::future::ready(Signer::sign(self, payload))
+    }
+
+    fn signature_scheme(&self) -> SignatureScheme {
+        Signer::signature_scheme(self)
+    }
+}
+
+/// A provider of signature keys whose private keys never leave the provider
+/// and are only referenced by opaque handles, e.g. keys in the iOS Secure
+/// Enclave, the Android Keystore or an HSM.
+///
+/// Keys are identified by a key ID that the application chooses, e.g. the
+/// alias of a key in the Android Keystore or the application tag of a key in
+/// the iOS keychain. [`HandleSigner`] implements [`Signer`] on top of a
+/// provider.
+pub trait SignatureKeyProvider {
+    /// An opaque reference to a private key of the provider.
+    type KeyHandle: Clone + Debug;
+
+    /// The error returned by the provider.
+    type Error: std::error::Error;
+
+    /// Generate a key pair for the `signature_scheme` with the `key_id`.
+    ///
+    /// Returns the handle of the private key and the public key in the
+    /// encoding of OpenMLS, i.e. an uncompressed SEC1 point for ECDSA and the
+    /// raw public key for EdDSA.
+    fn generate_key(
+        &self,
+        key_id: &[u8],
+        signature_scheme: SignatureScheme,
+    ) -> Result<(Self::KeyHandle, Vec<u8>), Self::Error>;
+
+    /// Find the key pair with the `key_id` for the `signature_scheme`.
+    ///
+    /// Returns the handle of the private key and the public key as in
+    /// [`SignatureKeyProvider::generate_key()`], or [`None`] if there is no
+    /// such key.
+    fn find_key(
+        &self,
+        key_id: &[u8],
+        signature_scheme: SignatureScheme,
+    ) -> Result<Option<(Self::KeyHandle, Vec<u8>)>, Self::Error>;
+
+    /// Sign the `payload` with the private key referenced by `key`.
+    ///
+    /// Returns the signature in the encoding of OpenMLS, i.e. DER for ECDSA.
+    fn sign(
+        &self,
+        key: &Self::KeyHandle,
+        signature_scheme: SignatureScheme,
+        payload: &[u8],
+    ) -> Result<Vec<u8>, Self::Error>;
+}
+
+/// A [`Signer`] whose private key lives in a [`SignatureKeyProvider`].
+///
+/// The signer only holds the handle of the private key and the public key, so
+/// it can be used for MLS credentials without exporting the private key.
+pub struct HandleSigner<P: SignatureKeyProvider> {
+    provider: Arc<P>,
+    key: P::KeyHandle,
+    key_id: Vec<u8>,
+    public: Vec<u8>,
+    signature_scheme: SignatureScheme,
+}
+
+impl<P: SignatureKeyProvider> Debug for HandleSigner<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HandleSigner")
+            .field("key", &self.key)
+            .field("key_id", &self.key_id)
+            .field("public", &self.public)
+            .field("signature_scheme", &self.signature_scheme)
+            .finish()
+    }
+}
+
+impl<P: SignatureKeyProvider> HandleSigner<P> {
+    /// Generate a fresh key pair with the `key_id` in the `provider`.
+    pub fn generate(
+        provider: Arc<P>,
+        key_id: &[u8],
+        signature_scheme: SignatureScheme,
+    ) -> Result<Self, P::Error> {
+        let (key, public) = provider.generate_key(key_id, signature_scheme)?;
+        Ok(Self {
+            provider,
+            key,
+            key_id: key_id.to_vec(),
+            public,
+            signature_scheme,
+        })
+    }
+
+    /// Load the key pair with the `key_id` from the `provider`.
+    ///
+    /// Returns [`None`] if the provider doesn't hold such a key pair.
+    pub fn load(
+        provider: Arc<P>,
+        key_id: &[u8],
+        signature_scheme: SignatureScheme,
+    ) -> Result<Option<Self>, P::Error> {
+        Ok(provider
+            .find_key(key_id, signature_scheme)?
+            .map(|(key, public)| Self {
+                provider,
+                key,
+                key_id: key_id.to_vec(),
+                public,
+                signature_scheme,
+            }))
+    }
+
+    /// Get the key ID of this key in the provider.
+    pub fn key_id(&self) -> &[u8] {
+        &self.key_id
+    }
+
+    /// Get the handle of the private key.
+    pub fn key_handle(&self) -> &P::KeyHandle {
+        &self.key
+    }
+
+    /// Get the public key as byte slice.
+    pub fn public(&self) -> &[u8] {
+        &self.public
+    }
+
+    /// Get the public key as byte vector.
+    pub fn to_public_vec(&self) -> Vec<u8> {
+        self.public.clone()
+    }
+}
+
+impl<P: SignatureKeyProvider> Signer for HandleSigner<P> {
+    fn sign(&self, payload: &[u8]) -> Result<Vec<u8>, Error> {
+        self.provider
+            .sign(&self.key, self.signature_scheme, payload)
+            .map_err(|_| Error::SigningError)
+    }
+
+    fn signature_scheme(&self) -> SignatureScheme {
+        self.signature_scheme
+    }
+}
@@ -0,0 +1,179 @@
+//! # OpenMLS Storage Provider Trait
+//!
+//! OpenMLS writes its state through a [`StorageProvider`] after every state
+//! mutation. Values are addressed with typed [`StorageKey`]s, one kind of key
+//! per entity, so that a provider can e.g. put each entity into its own table.
+//!
+//! Every [`OpenMlsKeyStore`] is a [`StorageProvider`]. It stores the values
+//! under the bytes returned by [`StorageKey::to_bytes()`].
+
+use crate::key_store::{MlsEntity, MlsEntityId, OpenMlsKeyStore};
+
+/// The label that prefixes the keys of [`StorageKey::EncryptionKeyPair`]s.
+const ENCRYPTION_KEY_PAIR_LABEL: &[u8] = b"leaf_encryption_key";
+
+/// The label that prefixes the keys of [`StorageKey::QueuedProposals`].
+const QUEUED_PROPOSALS_LABEL: &[u8] = b"queued_proposals";
+
+/// The label that prefixes the keys of [`StorageKey::ClientState`].
+const CLIENT_STATE_LABEL: &[u8] = b"client_state";
+
+/// The label that prefixes the keys of [`StorageKey::ReceiverState`]s.
+const RECEIVER_STATE_LABEL: &[u8] = b"receiver_state";
+
+/// The label that prefixes the keys of [`StorageKey::SenderRatchets`].
+const SENDER_RATCHETS_LABEL: &[u8] = b"sender_ratchets";
+
+/// A typed key for a value that OpenMLS persists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StorageKey<'a> {
+    /// The state of the group with the given group ID, including the secrets
+    /// of its current epoch. Both are written together, so that the stored
+    /// group state is always consistent with its secrets.
+    GroupState {
+        /// The serialized group ID.
+        group_id: &'a [u8],
+    },
+    /// The encryption key pairs that the own leaf of a group uses in an epoch.
+    EpochKeyPairs {
+        /// The serialized group ID.
+        group_id: &'a [u8],
+        /// The epoch.
+        epoch: u64,
+        /// The index of the own leaf.
+        leaf_index: u32,
+    },
+    /// A standalone encryption key pair, e.g. of a key package or a pending
+    /// commit, with the given public key.
+    EncryptionKeyPair {
+        /// The serialized public key.
+        public_key: &'a [u8],
+    },
+    /// The proposals that a group queued in an epoch for the next commit.
+    QueuedProposals {
+        /// The serialized group ID.
+        group_id: &'a [u8],
+        /// The epoch.
+        epoch: u64,
+    },
+    /// The group IDs and key packages of the client with the given signature
+    /// key.
+    ClientState {
+        /// The serialized signature public key of the client.
+        signature_key: &'a [u8],
+    },
+    /// The state that a group changes when it processes application messages
+    /// without writing the group state, e.g. the sequence number that is
+    /// expected next. It is newer than the group state and is deleted when
+    /// the group state is written again.
+    ReceiverState {
+        /// The serialized group ID.
+        group_id: &'a [u8],
+    },
+    /// The handshake and application ratchets of a sender of a group in an
+    /// epoch that were written without the group state. See
+    /// [`StorageKey::ReceiverState`].
+    SenderRatchets {
+        /// The serialized group ID.
+        group_id: &'a [u8],
+        /// The epoch.
+        epoch: u64,
+        /// The index of the leaf of the sender.
+        leaf_index: u32,
+    },
+}
+
+impl<'a> StorageKey<'a> {
+    /// Returns the [`MlsEntityId`] of the values stored under this key.
+    pub fn entity_id(&self) -> MlsEntityId {
+        match self {
+            StorageKey::GroupState { .. } => MlsEntityId::GroupState,
+            StorageKey::EpochKeyPairs { .. } | StorageKey::EncryptionKeyPair { .. } => {
+                MlsEntityId::EncryptionKeyPair
+            }
+            StorageKey::QueuedProposals { .. } => MlsEntityId::QueuedProposals,
+            StorageKey::ClientState { .. } => MlsEntityId::ClientState,
+            StorageKey::ReceiverState { .. } => MlsEntityId::ReceiverState,
+            StorageKey::SenderRatchets { .. } => MlsEntityId::SenderRatchets,
+        }
+    }
+
+    /// Returns the bytes of this key.
+    ///
+    /// Keys of different kinds never collide as long as group IDs are unique
+    /// and don't start with the label of another kind of key.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            StorageKey::GroupState { group_id } => group_id.to_vec(),
+            StorageKey::EpochKeyPairs {
+                group_id,
+                epoch,
+                leaf_index,
+            } => [*group_id, &leaf_index.to_be_bytes(), &epoch.to_be_bytes()].concat(),
+            StorageKey::EncryptionKeyPair { public_key } => {
+                [ENCRYPTION_KEY_PAIR_LABEL, *public_key].concat()
+            }
+            StorageKey::QueuedProposals { group_id, epoch } => {
+                [QUEUED_PROPOSALS_LABEL, *group_id, &epoch.to_be_bytes()].concat()
+            }
+            StorageKey::ClientState { signature_key } => {
+                [CLIENT_STATE_LABEL, *signature_key].concat()
+            }
+            StorageKey::ReceiverState { group_id } => [RECEIVER_STATE_LABEL, *group_id].concat(),
+            StorageKey::SenderRatchets {
+                group_id,
+                epoch,
+                leaf_index,
+            } => [
+                SENDER_RATCHETS_LABEL,
+                *group_id,
+                &epoch.to_be_bytes(),
+                &leaf_index.to_be_bytes(),
+            ]
+            .concat(),
+        }
+    }
+}
+
+/// The Storage Provider trait
+pub trait StorageProvider: Send + Sync {
+    /// The error type returned by the [`StorageProvider`].
+    type Error: std::error::Error + std::fmt::Debug + PartialEq;
+
+    /// Write the value `v` for the key `k`, replacing any previous value.
+    ///
+    /// Returns an error if writing fails.
+    fn write<V: MlsEntity>(&self, k: StorageKey<'_>, v: &V) -> Result<(), Self::Error>
+    where
+        Self: Sized;
+
+    /// Load the value stored for the key `k`.
+    ///
+    /// Returns [`None`] if no value is stored for `k` or reading fails.
+    fn load<V: MlsEntity>(&self, k: StorageKey<'_>) -> Option<V>
+    where
+        Self: Sized;
+
+    /// Remove the value stored for the key `k`.
+    ///
+    /// Returns an error if removing fails.
+    fn remove<V: MlsEntity>(&self, k: StorageKey<'_>) -> Result<(), Self::Error>
+    where
+        Self: Sized;
+}
+
+impl<KeyStore: OpenMlsKeyStore> StorageProvider for KeyStore {
+    type Error = KeyStore::Error;
+
+    fn write<V: MlsEntity>(&self, k: StorageKey<'_>, v: &V) -> Result<(), Self::Error> {
+        self.store(&k.to_bytes(), v)
+    }
+
+    fn load<V: MlsEntity>(&self, k: StorageKey<'_>) -> Option<V> {
+        self.read(&k.to_bytes())
+    }
+
+    fn remove<V: MlsEntity>(&self, k: StorageKey<'_>) -> Result<(), Self::Error> {
+        self.delete::<V>(&k.to_bytes())
+    }
+}
@@ -7,6 +7,7 @@ pub mod crypto;
 pub mod key_store;
 pub mod random;
 pub mod signatures;
+pub mod storage;
 pub mod types;
 
 /// The OpenMLS Crypto Provider Trait
@@ -27,3 +28,27 @@ pub trait OpenMlsCryptoProvider: Send + Sync {
     /// Get the key store provider.
     fn key_store(&self) -> &Self::KeyStoreProvider;
 }
+
+/// The OpenMLS Async Crypto Provider Trait
+///
+/// Like [`OpenMlsCryptoProvider`], but with an
+/// [`OpenMlsAsyncKeyStore`](key_store::OpenMlsAsyncKeyStore). It must be
+/// passed in to the async variants of the public OpenMLS API.
+///
+/// The crypto and randomness providers are synchronous, because they only
+/// operate on key material that OpenMLS holds in memory.
+#[cfg(feature = "async")]
+pub trait OpenMlsAsyncCryptoProvider: Send + Sync {
+    type CryptoProvider: crypto::OpenMlsCrypto;
+    type RandProvider: random::OpenMlsRand;
+    type KeyStoreProvider: key_store::OpenMlsAsyncKeyStore;
+
+    /// Get the crypto provider.
+    fn crypto(&self) -> &Self::CryptoProvider;
+
+    /// Get the randomness provider.
+    fn rand(&self) -> &Self::RandProvider;
+
+    /// Get the key store provider.
+    fn key_store(&self) -> &Self::KeyStoreProvider;
+}
@@ -100,6 +100,82 @@ pub enum SignatureScheme {
     ED25519 = 0x0807,
     /// ED448
     ED448 = 0x0808,
+    /// ML-DSA-44 (experimental)
+    #[cfg(feature = "pq-signatures")]
+    ML_DSA_44 = 0x0904,
+    /// ML-DSA-65 (experimental)
+    #[cfg(feature = "pq-signatures")]
+    ML_DSA_65 = 0x0905,
+    /// ML-DSA-87 (experimental)
+    #[cfg(feature = "pq-signatures")]
+    ML_DSA_87 = 0x0906,
+    /// ECDSA_SECP256K1_SHA256
+    ///
+    /// There is no code point for secp256k1 in the TLS registry. This uses a
+    /// value from the private use range.
+    #[cfg(feature = "secp256k1")]
+    ECDSA_SECP256K1_SHA256 = 0xFE03,
+}
+
+impl SignatureScheme {
+    /// Returns `true` if this is a post-quantum signature scheme.
+    pub const fn is_post_quantum(&self) -> bool {
+        match self {
+            SignatureScheme::ECDSA_SECP256R1_SHA256
+            | SignatureScheme::ECDSA_SECP384R1_SHA384
+            | SignatureScheme::ECDSA_SECP521R1_SHA512
+            | SignatureScheme::ED25519
+            | SignatureScheme::ED448 => false,
+            #[cfg(feature = "secp256k1")]
+            SignatureScheme::ECDSA_SECP256K1_SHA256 => false,
+            #[cfg(feature = "pq-signatures")]
+            SignatureScheme::ML_DSA_44
+            | SignatureScheme::ML_DSA_65
+            | SignatureScheme::ML_DSA_87 => true,
+        }
+    }
+
+    /// Returns the size of a public key of this [`SignatureScheme`] in bytes,
+    /// or `None` if the size depends on the encoding (ECDSA).
+    pub const fn public_key_size(&self) -> Option<usize> {
+        match self {
+            SignatureScheme::ECDSA_SECP256R1_SHA256
+            | SignatureScheme::ECDSA_SECP384R1_SHA384
+            | SignatureScheme::ECDSA_SECP521R1_SHA512 => None,
+            #[cfg(feature = "secp256k1")]
+            SignatureScheme::ECDSA_SECP256K1_SHA256 => None,
+            SignatureScheme::ED25519 => Some(32),
+            SignatureScheme::ED448 => Some(57),
+            #[cfg(feature = "pq-signatures")]
+            SignatureScheme::ML_DSA_44 => Some(1312),
+            #[cfg(feature = "pq-signatures")]
+            SignatureScheme::ML_DSA_65 => Some(1952),
+            #[cfg(feature = "pq-signatures")]
+            SignatureScheme::ML_DSA_87 => Some(2592),
+        }
+    }
+
+    /// Returns the maximum size of a signature of this [`SignatureScheme`] in
+    /// bytes.
+    ///
+    /// For ECDSA this is the size of a DER encoded signature.
+    pub const fn max_signature_size(&self) -> usize {
+        match self {
+            SignatureScheme::ECDSA_SECP256R1_SHA256 => 72,
+            SignatureScheme::ECDSA_SECP384R1_SHA384 => 104,
+            SignatureScheme::ECDSA_SECP521R1_SHA512 => 139,
+            #[cfg(feature = "secp256k1")]
+            SignatureScheme::ECDSA_SECP256K1_SHA256 => 72,
+            SignatureScheme::ED25519 => 64,
+            SignatureScheme::ED448 => 114,
+            #[cfg(feature = "pq-signatures")]
+            SignatureScheme::ML_DSA_44 => 2420,
+            #[cfg(feature = "pq-signatures")]
+            SignatureScheme::ML_DSA_65 => 3309,
+            #[cfg(feature = "pq-signatures")]
+            SignatureScheme::ML_DSA_87 => 4627,
+        }
+    }
 }
 
 impl TryFrom<u16> for SignatureScheme {
@@ -112,6 +188,14 @@ impl TryFrom<u16> for SignatureScheme {
             0x0603 => Ok(SignatureScheme::ECDSA_SECP521R1_SHA512),
             0x0807 => Ok(SignatureScheme::ED25519),
             0x0808 => Ok(SignatureScheme::ED448),
+            #[cfg(feature = "pq-signatures")]
+            0x0904 => Ok(SignatureScheme::ML_DSA_44),
+            #[cfg(feature = "pq-signatures")]
+            0x0905 => Ok(SignatureScheme::ML_DSA_65),
+            #[cfg(feature = "pq-signatures")]
+            0x0906 => Ok(SignatureScheme::ML_DSA_87),
+            #[cfg(feature = "secp256k1")]
+            0xFE03 => Ok(SignatureScheme::ECDSA_SECP256K1_SHA256),
             _ => Err(format!("Unsupported SignatureScheme: {value}")),
         }
     }
@@ -130,6 +214,7 @@ pub enum Error {
 pub enum CryptoError {
     CryptoLibraryError,
     AeadDecryptionError,
+    HpkeEncryptionError,
     HpkeDecryptionError,
     UnsupportedSignatureScheme,
     KdfLabelTooLarge,
@@ -139,6 +224,7 @@ pub enum CryptoError {
     InvalidSignature,
     UnsupportedAeadAlgorithm,
     UnsupportedKdf,
+    UnsupportedKem,
     InvalidLength,
     UnsupportedHashAlgorithm,
     SignatureEncodingError,
@@ -182,6 +268,51 @@ pub enum HpkeKemType {
 
     /// DH KEM on x448
     DhKem448 = 0x0021,
+
+    /// X-Wing hybrid KEM, i.e. x25519 combined with ML-KEM-768
+    /// (draft-connolly-cfrg-xwing-kem-06, experimental). None of the crypto
+    /// providers in this repository implement it.
+    XWingKemDraft06 = 0x647a,
+}
+
+impl HpkeKemType {
+    /// Returns `true` if this is a hybrid KEM that combines a classical with a
+    /// post-quantum KEM.
+    pub const fn is_hybrid(&self) -> bool {
+        match self {
+            HpkeKemType::DhKemP256
+            | HpkeKemType::DhKemP384
+            | HpkeKemType::DhKemP521
+            | HpkeKemType::DhKem25519
+            | HpkeKemType::DhKem448 => false,
+            HpkeKemType::XWingKemDraft06 => true,
+        }
+    }
+
+    /// Returns the size of an encapsulated key (`enc`) of this
+    /// [`HpkeKemType`] in bytes.
+    pub const fn enc_size(&self) -> usize {
+        match self {
+            HpkeKemType::DhKemP256 => 65,
+            HpkeKemType::DhKemP384 => 97,
+            HpkeKemType::DhKemP521 => 133,
+            HpkeKemType::DhKem25519 => 32,
+            HpkeKemType::DhKem448 => 56,
+            HpkeKemType::XWingKemDraft06 => 1120,
+        }
+    }
+
+    /// Returns the size of a public key of this [`HpkeKemType`] in bytes.
+    pub const fn public_key_size(&self) -> usize {
+        match self {
+            HpkeKemType::DhKemP256 => 65,
+            HpkeKemType::DhKemP384 => 97,
+            HpkeKemType::DhKemP521 => 133,
+            HpkeKemType::DhKem25519 => 32,
+            HpkeKemType::DhKem448 => 56,
+            HpkeKemType::XWingKemDraft06 => 1216,
+        }
+    }
 }
 
 /// KDF Types for HPKE
@@ -348,6 +479,29 @@ pub enum Ciphersuite {
 
     /// DH KEM P384 | AES-GCM 256 | SHA2-384 | EcDSA P384
     MLS_256_DHKEMP384_AES256GCM_SHA384_P384 = 0x0007,
+
+    /// DH KEM x25519 | AES-GCM 128 | SHA2-256 | ML-DSA-44
+    ///
+    /// Experimental ciphersuite for prototyping post-quantum authenticated
+    /// groups. It uses a value from the private use range.
+    #[cfg(feature = "pq-signatures")]
+    MLS_128_DHKEMX25519_AES128GCM_SHA256_MLDSA44 = 0xF0A1,
+
+    /// X-Wing | AES-GCM 128 | SHA2-256 | Ed25519
+    ///
+    /// Code point for prototyping post-quantum confidentiality with the
+    /// hybrid X-Wing KEM in a custom crypto provider. None of the crypto
+    /// providers in this repository support it. It uses a value from the
+    /// private use range.
+    #[cfg(feature = "pq-kem")]
+    MLS_128_XWING_AES128GCM_SHA256_Ed25519 = 0xF0A2,
+
+    /// DH KEM P256 | AES-GCM 128 | SHA2-256 | EcDSA secp256k1
+    ///
+    /// Ciphersuite for groups whose members sign with secp256k1 keys. It uses
+    /// a value from the private use range.
+    #[cfg(feature = "secp256k1")]
+    MLS_128_DHKEMP256_AES128GCM_SHA256_SECP256K1 = 0xF0A3,
 }
 
 impl core::fmt::Display for Ciphersuite {
@@ -383,6 +537,12 @@ impl TryFrom<u16> for Ciphersuite {
             0x0005 => Ok(Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521),
             0x0006 => Ok(Ciphersuite::MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_Ed448),
             0x0007 => Ok(Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384),
+            #[cfg(feature = "pq-signatures")]
+            0xF0A1 => Ok(Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_MLDSA44),
+            #[cfg(feature = "pq-kem")]
+            0xF0A2 => Ok(Ciphersuite::MLS_128_XWING_AES128GCM_SHA256_Ed25519),
+            #[cfg(feature = "secp256k1")]
+            0xF0A3 => Ok(Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_SECP256K1),
             _ => Err(Self::Error::DecodingError(format!(
                 "{v} is not a valid ciphersuite value"
             ))),
@@ -446,6 +606,12 @@ impl Ciphersuite {
             Ciphersuite::MLS_256_DHKEMX448_AES256GCM_SHA512_Ed448
             | Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521
             | Ciphersuite::MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_Ed448 => HashType::Sha2_512,
+            #[cfg(feature = "pq-signatures")]
+            Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_MLDSA44 => HashType::Sha2_256,
+            #[cfg(feature = "pq-kem")]
+            Ciphersuite::MLS_128_XWING_AES128GCM_SHA256_Ed25519 => HashType::Sha2_256,
+            #[cfg(feature = "secp256k1")]
+            Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_SECP256K1 => HashType::Sha2_256,
         }
     }
 
@@ -470,6 +636,14 @@ impl Ciphersuite {
             Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384 => {
                 SignatureScheme::ECDSA_SECP384R1_SHA384
             }
+            #[cfg(feature = "pq-signatures")]
+            Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_MLDSA44 => SignatureScheme::ML_DSA_44,
+            #[cfg(feature = "pq-kem")]
+            Ciphersuite::MLS_128_XWING_AES128GCM_SHA256_Ed25519 => SignatureScheme::ED25519,
+            #[cfg(feature = "secp256k1")]
+            Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_SECP256K1 => {
+                SignatureScheme::ECDSA_SECP256K1_SHA256
+            }
         }
     }
 
@@ -486,6 +660,12 @@ impl Ciphersuite {
             Ciphersuite::MLS_256_DHKEMX448_AES256GCM_SHA512_Ed448
             | Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521
             | Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384 => AeadType::Aes256Gcm,
+            #[cfg(feature = "pq-signatures")]
+            Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_MLDSA44 => AeadType::Aes128Gcm,
+            #[cfg(feature = "pq-kem")]
+            Ciphersuite::MLS_128_XWING_AES128GCM_SHA256_Ed25519 => AeadType::Aes128Gcm,
+            #[cfg(feature = "secp256k1")]
+            Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_SECP256K1 => AeadType::Aes128Gcm,
         }
     }
 
@@ -504,6 +684,12 @@ impl Ciphersuite {
             | Ciphersuite::MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_Ed448 => {
                 HpkeKdfType::HkdfSha512
             }
+            #[cfg(feature = "pq-signatures")]
+            Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_MLDSA44 => HpkeKdfType::HkdfSha256,
+            #[cfg(feature = "pq-kem")]
+            Ciphersuite::MLS_128_XWING_AES128GCM_SHA256_Ed25519 => HpkeKdfType::HkdfSha256,
+            #[cfg(feature = "secp256k1")]
+            Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_SECP256K1 => HpkeKdfType::HkdfSha256,
         }
     }
 
@@ -520,6 +706,12 @@ impl Ciphersuite {
             | Ciphersuite::MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_Ed448 => HpkeKemType::DhKem448,
             Ciphersuite::MLS_256_DHKEMP384_AES256GCM_SHA384_P384 => HpkeKemType::DhKemP384,
             Ciphersuite::MLS_256_DHKEMP521_AES256GCM_SHA512_P521 => HpkeKemType::DhKemP521,
+            #[cfg(feature = "pq-signatures")]
+            Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_MLDSA44 => HpkeKemType::DhKem25519,
+            #[cfg(feature = "pq-kem")]
+            Ciphersuite::MLS_128_XWING_AES128GCM_SHA256_Ed25519 => HpkeKemType::XWingKemDraft06,
+            #[cfg(feature = "secp256k1")]
+            Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_SECP256K1 => HpkeKemType::DhKemP256,
         }
     }
 
@@ -538,6 +730,12 @@ impl Ciphersuite {
             Ciphersuite::MLS_256_DHKEMX448_CHACHA20POLY1305_SHA512_Ed448 => {
                 HpkeAeadType::ChaCha20Poly1305
             }
+            #[cfg(feature = "pq-signatures")]
+            Ciphersuite::MLS_128_DHKEMX25519_AES128GCM_SHA256_MLDSA44 => HpkeAeadType::AesGcm128,
+            #[cfg(feature = "pq-kem")]
+            Ciphersuite::MLS_128_XWING_AES128GCM_SHA256_Ed25519 => HpkeAeadType::AesGcm128,
+            #[cfg(feature = "secp256k1")]
+            Ciphersuite::MLS_128_DHKEMP256_AES128GCM_SHA256_SECP256K1 => HpkeAeadType::AesGcm128,
         }
     }
 